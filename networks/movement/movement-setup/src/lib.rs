@@ -0,0 +1,82 @@
+use std::str::FromStr;
+
+/// A step of the setup of a Movement node and its bridge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetupStep {
+	/// The DA light node and the settlement.
+	DaLightNode,
+	/// The node execution and DA db config.
+	Node,
+	/// The bridge config and contracts.
+	Bridge,
+}
+
+impl SetupStep {
+	pub const ALL: [SetupStep; 3] = [SetupStep::DaLightNode, SetupStep::Node, SetupStep::Bridge];
+
+	/// The steps that must be run before this one.
+	pub fn dependencies(&self) -> &'static [SetupStep] {
+		match self {
+			SetupStep::DaLightNode => &[],
+			SetupStep::Node => &[SetupStep::DaLightNode],
+			SetupStep::Bridge => &[SetupStep::Node],
+		}
+	}
+}
+
+impl FromStr for SetupStep {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.trim() {
+			"da_light_node" => Ok(SetupStep::DaLightNode),
+			"node" => Ok(SetupStep::Node),
+			"bridge" => Ok(SetupStep::Bridge),
+			_ => Err(anyhow::anyhow!(
+				"Unknown setup step: {s}, expected da_light_node, node or bridge"
+			)),
+		}
+	}
+}
+
+/// Parse a comma separated list of steps.
+pub fn parse_steps(steps: &str) -> Result<Vec<SetupStep>, anyhow::Error> {
+	steps
+		.split(',')
+		.filter(|step| !step.trim().is_empty())
+		.map(SetupStep::from_str)
+		.collect()
+}
+
+/// The steps to run, with their dependencies, each one after the steps it depends on.
+pub fn plan(steps: &[SetupStep]) -> Vec<SetupStep> {
+	fn visit(step: SetupStep, plan: &mut Vec<SetupStep>) {
+		if plan.contains(&step) {
+			return;
+		}
+		for dependency in step.dependencies() {
+			visit(*dependency, plan);
+		}
+		plan.push(step);
+	}
+
+	let mut plan = Vec::new();
+	for step in steps {
+		visit(*step, &mut plan);
+	}
+	plan
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_plan() {
+		assert_eq!(plan(&[SetupStep::Bridge]), SetupStep::ALL);
+		assert_eq!(plan(&[SetupStep::Bridge, SetupStep::DaLightNode]), SetupStep::ALL);
+		assert_eq!(plan(&[SetupStep::Node]), [SetupStep::DaLightNode, SetupStep::Node]);
+		assert_eq!(parse_steps("node, bridge").unwrap(), [SetupStep::Node, SetupStep::Bridge]);
+		assert!(parse_steps("relayer").is_err());
+	}
+}
@@ -0,0 +1,168 @@
+use bridge_config::Config as BridgeConfig;
+use godfig::{backend::config_file::ConfigFile, Godfig};
+use movement_config::Config;
+use movement_full_node_setup::local::Local;
+use movement_setup::{parse_steps, plan, SetupStep};
+use std::time::Duration;
+use tracing::info;
+
+// Interval of the connection attempts to the Movement faucet.
+const FAUCET_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[tokio::main]
+async fn main() -> Result<(), anyhow::Error> {
+	use tracing_subscriber::EnvFilter;
+
+	tracing_subscriber::fmt()
+		.with_env_filter(
+			EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+		)
+		.init();
+
+	// The steps to run, all of them by default, with their dependencies.
+	let steps = match std::env::var("MOVEMENT_SETUP_STEPS") {
+		Ok(steps) => parse_steps(&steps)?,
+		Err(_) => SetupStep::ALL.to_vec(),
+	};
+	let steps = plan(&steps);
+	info!("Starting Movement setup, steps: {steps:?}");
+
+	// get the node config file
+	let dot_movement = dot_movement::DotMovement::try_from_env()?;
+	let config_file = dot_movement.try_get_or_create_config_file().await?;
+	let godfig: Godfig<Config, ConfigFile> = Godfig::new(ConfigFile::new(config_file), vec![]);
+
+	// get the bridge config file
+	let mut bridge_dot_movement = dot_movement.clone();
+	bridge_dot_movement.set_path(bridge_config::get_config_path(&dot_movement));
+	let bridge_config_file = bridge_dot_movement.try_get_or_create_config_file().await?;
+	let bridge_godfig: Godfig<BridgeConfig, ConfigFile> =
+		Godfig::new(ConfigFile::new(bridge_config_file), vec![]);
+
+	// Run all the steps in one transaction on the node config: it's only written if they
+	// all succeed, and each step reads the config updated by the steps it depends on.
+	let settlement_task = godfig
+		.try_transaction_with_result(|config| {
+			let steps = steps.clone();
+			let bridge_godfig = &bridge_godfig;
+			async move {
+				let mut config = config.unwrap_or_default();
+				let local = Local::default();
+				let mut settlement_task = None;
+				for step in steps {
+					info!("Running setup step {step:?}");
+					match step {
+						SetupStep::DaLightNode => {
+							let (new_config, join_handle) =
+								local.run_da_light_node_setup(dot_movement.clone(), config).await?;
+							config = new_config;
+							settlement_task = Some(join_handle);
+						}
+						SetupStep::Node => {
+							config = local
+								.setup_maptos_execution_config(dot_movement.clone(), config)
+								.await?;
+							config = local.setup_da_db_config(dot_movement.clone(), config).await?;
+						}
+						SetupStep::Bridge => setup_bridge(bridge_godfig, &config).await?,
+					}
+				}
+				Ok((Some(config), settlement_task))
+			}
+		})
+		.await?;
+
+	info!("Node config setup done.");
+
+	let shutdown = shutdown_signal();
+	tokio::pin!(shutdown);
+
+	if steps.contains(&SetupStep::Bridge) {
+		tokio::select! {
+			res = deploy_movement_bridge(&bridge_godfig) => res?,
+			_ = &mut shutdown => return Ok(()),
+		}
+		info!("Bridge setup done.");
+	}
+
+	// Keep the settlement task alive, it runs the local Ethereum node.
+	if let Some(settlement_task) = settlement_task {
+		tokio::select! {
+			res = settlement_task => {
+				info!("Settlement task finished.");
+				res??;
+			}
+			_ = &mut shutdown => info!("Cancellation received, killing settlement task."),
+		}
+	}
+
+	Ok(())
+}
+
+// Compose the bridge config from the node config and deploy the Ethereum contracts.
+// The Movement modules can only be deployed once the node runs, after the node config
+// is written, see `deploy_movement_bridge`.
+async fn setup_bridge(
+	bridge_godfig: &Godfig<BridgeConfig, ConfigFile>,
+	config: &Config,
+) -> Result<(), anyhow::Error> {
+	bridge_godfig
+		.try_transaction(|bridge_config| async move {
+			let mut bridge_config = bridge_config.unwrap_or_default();
+			bridge_setup::apply_maptos_config(
+				&mut bridge_config,
+				&config.execution_config.maptos_config,
+			);
+			bridge_setup::apply_settlement_config(&mut bridge_config, &config.mcr)?;
+
+			// Use custom as movement node in init.
+			bridge_config.movement.mvt_init_network = "custom".to_string();
+
+			bridge_setup::deploy::setup_local_ethereum(&mut bridge_config).await?;
+			Ok(Some(bridge_config))
+		})
+		.await?;
+	Ok(())
+}
+
+// Deploy the bridge modules on the Movement node, once its faucet is reachable.
+async fn deploy_movement_bridge(
+	bridge_godfig: &Godfig<BridgeConfig, ConfigFile>,
+) -> Result<(), anyhow::Error> {
+	bridge_godfig
+		.try_transaction(|bridge_config| async move {
+			let mut bridge_config =
+				bridge_config.ok_or_else(|| anyhow::anyhow!("Bridge config not set up"))?;
+			let faucet = (
+				bridge_config.movement.mvt_faucet_connection_hostname.clone(),
+				bridge_config.movement.mvt_faucet_connection_port,
+			);
+			info!("Waiting for the Movement faucet {}:{}", faucet.0, faucet.1);
+			while tokio::net::TcpStream::connect(faucet.clone()).await.is_err() {
+				tokio::time::sleep(FAUCET_POLL_INTERVAL).await;
+			}
+
+			bridge_setup::deploy::init_movement_node(&mut bridge_config.movement)?;
+			bridge_setup::deploy::deploy_local_movement_node(&mut bridge_config.movement)?;
+			Ok(Some(bridge_config))
+		})
+		.await?;
+	Ok(())
+}
+
+async fn shutdown_signal() {
+	use tokio::signal::unix::{signal, SignalKind};
+
+	match signal(SignalKind::terminate()) {
+		Ok(mut sigterm) => {
+			tokio::select! {
+				_ = sigterm.recv() => info!("Receive SIGTERM"),
+				_ = tokio::signal::ctrl_c() => info!("Receive Ctrl-C"),
+			}
+		}
+		Err(err) => {
+			tracing::warn!("Failed to listen to SIGTERM, only Ctrl-C stops the setup: {err}");
+			let _ = tokio::signal::ctrl_c().await;
+		}
+	}
+}
@@ -15,7 +15,9 @@ impl Local {
 		Self { mcr_settlement_strategy: Default::default() }
 	}
 
-	async fn run_da_light_node_setup(
+	/// Set up the DA light node and the settlement, returning the join handle of the
+	/// settlement task.
+	pub async fn run_da_light_node_setup(
 		&self,
 		dot_movement: DotMovement,
 		mut config: movement_config::Config,
@@ -43,7 +45,8 @@ impl Local {
 		Ok((config, join_handle))
 	}
 
-	async fn setup_maptos_execution_config(
+	/// Set up the execution db path and the default signer address whitelist.
+	pub async fn setup_maptos_execution_config(
 		&self,
 		dot_movement: DotMovement,
 		mut config: movement_config::Config,
@@ -78,7 +81,8 @@ impl Local {
 		Ok(config)
 	}
 
-	async fn setup_da_db_config(
+	/// Set up the DA db path in the `.movement` directory.
+	pub async fn setup_da_db_config(
 		&self,
 		dot_movement: DotMovement,
 		mut config: movement_config::Config,
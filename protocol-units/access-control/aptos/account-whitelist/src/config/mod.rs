@@ -1,12 +1,14 @@
 use crate::file::{Whitelist, WhitelistOperations};
 use aptos_types::account_address::AccountAddress;
 use godfig::env_default;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
 env_default!(default_aptos_account_whitelist, "APTOS_ACCOUNT_WHITELIST", String);
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Config {
 	/// The whitelist (path) for the mempool
 	#[serde(default = "default_aptos_account_whitelist")]
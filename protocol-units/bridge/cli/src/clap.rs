@@ -1,4 +1,6 @@
 pub mod eth_to_movement;
+pub mod sweep;
+pub mod vectors;
 use clap::{Parser, Subcommand};
 
 #[derive(Parser)]
@@ -14,4 +16,9 @@ pub enum Commands {
 	/// Ethereum to Movement Labs bridge commands
 	#[command(subcommand)]
 	BridgeEthToMovETH(eth_to_movement::Commands),
+	/// Sweep of the relayer fees to cold storage, with the admin key
+	#[command(subcommand)]
+	Sweep(sweep::Commands),
+	/// Test vectors of the encodings expected by the relayer, for the contract teams
+	Vectors(vectors::VectorsArgs),
 }
@@ -0,0 +1,45 @@
+use alloy::primitives::Address;
+use alloy::signers::local::PrivateKeySigner;
+use bridge_service::chains::movement::utils::MovementAddress;
+use clap::Subcommand;
+use url::Url;
+
+#[derive(Subcommand)]
+pub enum Commands {
+	/// Sign the cold storage policy of the relayer config with the admin key
+	SignPolicy {
+		/// Private key of the admin
+		#[arg(long)]
+		admin_key: PrivateKeySigner,
+
+		/// Ethereum cold storage address, not swept if missing
+		#[arg(long)]
+		eth_cold_address: Option<Address>,
+
+		/// Movement cold storage address, not swept if missing
+		#[arg(long)]
+		movement_cold_address: Option<MovementAddress>,
+
+		/// MOVE tokens left on the Ethereum relayer account, in the smallest unit
+		#[arg(long, default_value_t = 0)]
+		eth_threshold: u64,
+
+		/// Octas left on the Movement relayer account
+		#[arg(long, default_value_t = 0)]
+		movement_threshold: u64,
+	},
+	/// Request the relayer to sweep its accounts to cold storage now
+	Run {
+		/// Private key of the admin
+		#[arg(long)]
+		admin_key: PrivateKeySigner,
+
+		/// Bearer token of the operator on the admin API
+		#[arg(long)]
+		admin_token: String,
+
+		/// URL of the REST API of the relayer
+		#[arg(long, default_value = "http://localhost:30883")]
+		url: Url,
+	},
+}
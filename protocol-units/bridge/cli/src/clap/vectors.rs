@@ -0,0 +1,54 @@
+use alloy::primitives::Address;
+use bridge_service::chains::movement::utils::MovementAddress;
+use clap::Args;
+use std::path::PathBuf;
+
+#[derive(Args, Clone, Debug)]
+pub struct VectorsArgs {
+	/// Seed the preimages are derived from
+	#[arg(long, default_value = "movement-bridge")]
+	pub seed: String,
+
+	/// Number of vectors
+	#[arg(long, default_value_t = 8)]
+	pub count: u32,
+
+	/// Ethereum account of the transfers
+	#[arg(long, default_value = "0x1111111111111111111111111111111111111111")]
+	pub eth_address: Address,
+
+	/// Movement account of the transfers
+	#[arg(
+		long,
+		default_value = "0x2222222222222222222222222222222222222222222222222222222222222222"
+	)]
+	pub movement_address: MovementAddress,
+
+	/// Amount of the transfers
+	#[arg(long, default_value_t = 100)]
+	pub amount: u64,
+
+	/// Time lock duration of the initiator contract, in seconds
+	#[arg(long, default_value_t = 3600)]
+	pub time_lock_duration: u64,
+
+	/// Timestamp of the block of the initiations
+	#[arg(long, default_value_t = 1_700_000_000)]
+	pub timestamp: u64,
+
+	/// Nonce of the initiator contract at the first initiation
+	#[arg(long, default_value_t = 0)]
+	pub first_nonce: u64,
+
+	/// Format of the preimages on Movement: raw, utf8 or bcs_string
+	#[arg(long, default_value = "utf8")]
+	pub pre_image_format: String,
+
+	/// Digest of the hash locks: keccak256 or sha2_256
+	#[arg(long, default_value = "keccak256")]
+	pub hash_lock_algorithm: String,
+
+	/// File the vectors are written to, instead of the standard output
+	#[arg(long)]
+	pub output: Option<PathBuf>,
+}
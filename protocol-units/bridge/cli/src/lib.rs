@@ -1,4 +1,6 @@
 pub mod clap;
 pub mod eth_to_moveth;
 pub mod state;
+pub mod sweep;
 pub mod types;
+pub mod vectors;
@@ -1,10 +1,16 @@
 use bridge_cli::clap::{CliOptions, Commands};
+use bridge_util::chains::bridge_contracts::BridgeContractError;
+use bridge_util::reason::WithReasonCode;
 use clap::Parser;
 use eyre::Result;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-	inner_main().await.map_err(|e| eyre::eyre!(e))
+	// The contract errors are prefixed with their reason code, for the scripts.
+	inner_main().await.map_err(|e| match e.downcast_ref::<BridgeContractError>() {
+		Some(err) => eyre::eyre!("[{}] {e}", err.reason_code()),
+		None => eyre::eyre!(e),
+	})
 }
 
 async fn inner_main() -> anyhow::Result<()> {
@@ -16,6 +22,12 @@ async fn inner_main() -> anyhow::Result<()> {
 		Commands::BridgeEthToMovETH(command) => {
 			bridge_cli::eth_to_moveth::execute(command).await?;
 		}
+		Commands::Sweep(command) => {
+			bridge_cli::sweep::execute(command).await?;
+		}
+		Commands::Vectors(args) => {
+			bridge_cli::vectors::execute(args)?;
+		}
 	}
 
 	Ok(())
@@ -0,0 +1,53 @@
+use crate::clap::sweep::Commands;
+use anyhow::Result;
+use bridge_service::rest::LATEST_API_VERSION;
+use bridge_service::sweep::{request_message, sign, SweepPolicy, SweepRequest};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub async fn execute(command: &Commands) -> Result<()> {
+	match command {
+		Commands::SignPolicy {
+			admin_key,
+			eth_cold_address,
+			movement_cold_address,
+			eth_threshold,
+			movement_threshold,
+		} => {
+			let policy = SweepPolicy {
+				eth_cold_address: *eth_cold_address,
+				movement_cold_address: movement_cold_address.as_ref().map(|address| address.0),
+				eth_threshold: *eth_threshold,
+				movement_threshold: *movement_threshold,
+			};
+			println!("admin_address: {}", admin_key.address());
+			println!("policy_signature: {}", sign(admin_key, &policy.message())?);
+		}
+		Commands::Run { admin_key, admin_token, url } => {
+			let requested_at = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+			let request = SweepRequest {
+				requested_at,
+				signature: sign(admin_key, &request_message(requested_at))?,
+			};
+			let url = url.join(&format!("{LATEST_API_VERSION}/admin/sweeps"))?;
+			let response = reqwest::Client::new()
+				.post(url)
+				.bearer_auth(admin_token)
+				.json(&request)
+				.send()
+				.await?;
+			let status = response.status();
+			if !status.is_success() {
+				// The v2 API returns the errors as JSON with their reason code.
+				let error: serde_json::Value = response.json().await?;
+				anyhow::bail!(
+					"Sweep refused ({status}) [{}]: {}",
+					error["code"].as_str().unwrap_or_default(),
+					error["error"].as_str().unwrap_or_default()
+				);
+			}
+			let records: serde_json::Value = response.json().await?;
+			println!("{}", serde_json::to_string_pretty(&records)?);
+		}
+	}
+	Ok(())
+}
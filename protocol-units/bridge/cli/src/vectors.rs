@@ -0,0 +1,44 @@
+use crate::clap::vectors::VectorsArgs;
+use anyhow::Result;
+use bridge_service::pre_image::PreImageFormat;
+use bridge_service::vectors::{generate_vectors, VectorParams};
+use bridge_util::types::HashLockAlgorithm;
+use std::str::FromStr;
+
+/// Write the test vectors of the parameters as JSON.
+pub fn execute(args: &VectorsArgs) -> Result<()> {
+	let params = VectorParams {
+		seed: args.seed.clone(),
+		count: args.count,
+		eth_address: args.eth_address,
+		movement_address: args.movement_address.0,
+		amount: args.amount,
+		time_lock_duration: args.time_lock_duration,
+		timestamp: args.timestamp,
+		first_nonce: args.first_nonce,
+		pre_image_format: PreImageFormat::from_str(&args.pre_image_format)
+			.map_err(|err| anyhow::anyhow!(err))?,
+		hash_lock_algorithm: HashLockAlgorithm::from_str(&args.hash_lock_algorithm)
+			.map_err(|err| anyhow::anyhow!(err))?,
+	};
+	let vectors = serde_json::json!({
+		"params": {
+			"seed": params.seed,
+			"eth_address": params.eth_address,
+			"movement_address": params.movement_address.to_hex_literal(),
+			"amount": params.amount,
+			"time_lock_duration": params.time_lock_duration,
+			"timestamp": params.timestamp,
+			"first_nonce": params.first_nonce,
+			"pre_image_format": args.pre_image_format,
+			"hash_lock_algorithm": params.hash_lock_algorithm,
+		},
+		"vectors": generate_vectors(&params)?,
+	});
+	let json = serde_json::to_string_pretty(&vectors)?;
+	match &args.output {
+		Some(path) => std::fs::write(path, json)?,
+		None => println!("{json}"),
+	}
+	Ok(())
+}
@@ -0,0 +1,8 @@
+//! Prints the JSON schema of the bridge config file.
+use bridge_config::Config;
+
+fn main() -> Result<(), anyhow::Error> {
+	let schema = godfig::schema::json_schema::<Config>();
+	println!("{}", serde_json::to_string_pretty(&schema)?);
+	Ok(())
+}
@@ -0,0 +1,21 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Authentication of the operators on the admin API and on the gRPC operator service.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AdminConfig {
+	/// File of the operator tokens, one `<operator> <token>` pair per line. The operator of an
+	/// admin request is the one of its bearer token. Empty to reject all the admin changes.
+	#[serde(default = "default_admin_tokens_file")]
+	pub tokens_file: String,
+}
+
+env_default!(default_admin_tokens_file, "BRIDGE_ADMIN_TOKENS_FILE", String, String::new());
+
+impl Default for AdminConfig {
+	fn default() -> Self {
+		AdminConfig { tokens_file: default_admin_tokens_file() }
+	}
+}
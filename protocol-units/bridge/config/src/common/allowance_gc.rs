@@ -0,0 +1,45 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Revocation of the ERC-20 allowances granted by the relayer wallet to spenders that are no
+/// longer the bridge contracts, e.g. after a token pair removal or a contract migration.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AllowanceGcConfig {
+	#[serde(default = "default_enabled")]
+	pub enabled: bool,
+	/// Period of the collection, in seconds.
+	#[serde(default = "default_interval_secs")]
+	pub interval_secs: u64,
+	/// First block scanned for the approvals of the wallet.
+	#[serde(default = "default_from_block")]
+	pub from_block: u64,
+	/// Tokens checked beyond the MOVE token of the Ethereum config.
+	#[serde(default)]
+	pub tokens: Vec<String>,
+	/// Spenders kept beyond the bridge contracts of the Ethereum config.
+	#[serde(default)]
+	pub allowed_spenders: Vec<String>,
+	/// Only report the orphaned allowances, without revoking them.
+	#[serde(default = "default_dry_run")]
+	pub dry_run: bool,
+}
+
+env_default!(default_enabled, "BRIDGE_ALLOWANCE_GC_ENABLED", bool, false);
+env_default!(default_interval_secs, "BRIDGE_ALLOWANCE_GC_INTERVAL_SECS", u64, 24 * 60 * 60);
+env_default!(default_from_block, "BRIDGE_ALLOWANCE_GC_FROM_BLOCK", u64, 0);
+env_default!(default_dry_run, "BRIDGE_ALLOWANCE_GC_DRY_RUN", bool, false);
+
+impl Default for AllowanceGcConfig {
+	fn default() -> Self {
+		AllowanceGcConfig {
+			enabled: default_enabled(),
+			interval_secs: default_interval_secs(),
+			from_block: default_from_block(),
+			tokens: Vec::new(),
+			allowed_spenders: Vec::new(),
+			dry_run: default_dry_run(),
+		}
+	}
+}
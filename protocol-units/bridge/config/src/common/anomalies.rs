@@ -0,0 +1,43 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Rules of the advisory alerts on the patterns of the transfers. A rule is disabled while its
+/// threshold is the maximum.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AnomaliesConfig {
+	/// Length of the window the transfers are analyzed in, in seconds.
+	#[serde(default = "default_window_secs")]
+	pub window_secs: u64,
+	/// Amount initiated by an address in the window.
+	#[serde(default = "default_max_volume_per_address")]
+	pub max_volume_per_address: u64,
+	/// Refunds on both chains in the window.
+	#[serde(default = "default_max_refunds")]
+	pub max_refunds: u64,
+	/// Initiations of the same amount in the window.
+	#[serde(default = "default_max_repeated_amounts")]
+	pub max_repeated_amounts: u64,
+}
+
+env_default!(default_window_secs, "BRIDGE_ANOMALIES_WINDOW_SECS", u64, 3600);
+env_default!(
+	default_max_volume_per_address,
+	"BRIDGE_ANOMALIES_MAX_VOLUME_PER_ADDRESS",
+	u64,
+	u64::MAX
+);
+env_default!(default_max_refunds, "BRIDGE_ANOMALIES_MAX_REFUNDS", u64, u64::MAX);
+env_default!(default_max_repeated_amounts, "BRIDGE_ANOMALIES_MAX_REPEATED_AMOUNTS", u64, u64::MAX);
+
+impl Default for AnomaliesConfig {
+	fn default() -> Self {
+		AnomaliesConfig {
+			window_secs: default_window_secs(),
+			max_volume_per_address: default_max_volume_per_address(),
+			max_refunds: default_max_refunds(),
+			max_repeated_amounts: default_max_repeated_amounts(),
+		}
+	}
+}
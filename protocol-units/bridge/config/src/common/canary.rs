@@ -0,0 +1,77 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_CANARY_INTERVAL_SECS: u64 = 60 * 60;
+const DEFAULT_CANARY_TIMEOUT_SECS: u64 = 10 * 60;
+const DEFAULT_CANARY_AMOUNT: u64 = 1;
+
+/// Scheduled end to end transfers proving the whole bridge path is healthy.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CanaryConfig {
+	#[serde(default = "default_canary_enabled")]
+	pub enabled: bool,
+	#[serde(default = "default_canary_interval_secs")]
+	pub interval_secs: u64,
+	/// Time allowed for a canary transfer to complete on both chains.
+	#[serde(default = "default_canary_timeout_secs")]
+	pub timeout_secs: u64,
+	#[serde(default = "default_canary_amount")]
+	pub amount: u64,
+	/// Hex encoded Eth private key of the canary account, distinct from the relayer signer.
+	#[serde(default = "default_canary_eth_private_key")]
+	pub eth_private_key: String,
+	/// Hex encoded Movement private key of the canary account, distinct from the relayer signer.
+	#[serde(default = "default_canary_movement_private_key")]
+	pub movement_private_key: String,
+	/// Url receiving a JSON POST when a canary transfer fails.
+	#[serde(default)]
+	pub alert_webhook_url: Option<String>,
+}
+
+env_default!(default_canary_enabled, "BRIDGE_CANARY_ENABLED", bool, false);
+
+env_default!(
+	default_canary_interval_secs,
+	"BRIDGE_CANARY_INTERVAL_SECS",
+	u64,
+	DEFAULT_CANARY_INTERVAL_SECS
+);
+
+env_default!(
+	default_canary_timeout_secs,
+	"BRIDGE_CANARY_TIMEOUT_SECS",
+	u64,
+	DEFAULT_CANARY_TIMEOUT_SECS
+);
+
+env_default!(default_canary_amount, "BRIDGE_CANARY_AMOUNT", u64, DEFAULT_CANARY_AMOUNT);
+
+env_default!(
+	default_canary_eth_private_key,
+	"BRIDGE_CANARY_ETH_PRIVATE_KEY",
+	String,
+	String::new()
+);
+
+env_default!(
+	default_canary_movement_private_key,
+	"BRIDGE_CANARY_MOVEMENT_PRIVATE_KEY",
+	String,
+	String::new()
+);
+
+impl Default for CanaryConfig {
+	fn default() -> Self {
+		CanaryConfig {
+			enabled: default_canary_enabled(),
+			interval_secs: default_canary_interval_secs(),
+			timeout_secs: default_canary_timeout_secs(),
+			amount: default_canary_amount(),
+			eth_private_key: default_canary_eth_private_key(),
+			movement_private_key: default_canary_movement_private_key(),
+			alert_webhook_url: None,
+		}
+	}
+}
@@ -0,0 +1,44 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Thresholds of the anomalies pausing the locks of the relayer. An anomaly is ignored while
+/// its threshold is the maximum.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CircuitBreakerConfig {
+	/// Length of the window the initiations and RPC divergences are counted in, in seconds.
+	#[serde(default = "default_window_secs")]
+	pub window_secs: u64,
+	/// Initiations on both chains in the window.
+	#[serde(default = "default_max_initiations")]
+	pub max_initiations: u64,
+	/// Amount locked on a chain beyond the amount initiated on the other since the start.
+	#[serde(default = "default_max_locked_excess")]
+	pub max_locked_excess: u64,
+	/// Transfers of an event not found by the RPC of their chain in the window. The transfers
+	/// are only read on chain in stateless verification.
+	#[serde(default = "default_max_rpc_divergences")]
+	pub max_rpc_divergences: u64,
+}
+
+env_default!(default_window_secs, "BRIDGE_CIRCUIT_BREAKER_WINDOW_SECS", u64, 300);
+env_default!(default_max_initiations, "BRIDGE_CIRCUIT_BREAKER_MAX_INITIATIONS", u64, u64::MAX);
+env_default!(default_max_locked_excess, "BRIDGE_CIRCUIT_BREAKER_MAX_LOCKED_EXCESS", u64, u64::MAX);
+env_default!(
+	default_max_rpc_divergences,
+	"BRIDGE_CIRCUIT_BREAKER_MAX_RPC_DIVERGENCES",
+	u64,
+	u64::MAX
+);
+
+impl Default for CircuitBreakerConfig {
+	fn default() -> Self {
+		CircuitBreakerConfig {
+			window_secs: default_window_secs(),
+			max_initiations: default_max_initiations(),
+			max_locked_excess: default_max_locked_excess(),
+			max_rpc_divergences: default_max_rpc_divergences(),
+		}
+	}
+}
@@ -0,0 +1,48 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_MAX_INVALID_CLAIMS_PER_CLIENT: u32 = 5;
+const DEFAULT_MAX_CLAIMS_PER_MINUTE: u32 = 60;
+
+/// Claims of the end users holding the preimages of their transfers: the secret is posted on
+/// the REST API and the relayer completes the transfer on their behalf.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ClaimsConfig {
+	#[serde(default = "default_claims_enabled")]
+	pub enabled: bool,
+	/// Wrong secrets accepted per minute from a client, its wrong secrets are refused after.
+	/// The right secret of a transfer is always accepted.
+	#[serde(default = "default_max_invalid_claims_per_client")]
+	pub max_invalid_per_client: u32,
+	/// Claims accepted per minute from a client.
+	#[serde(default = "default_max_claims_per_minute")]
+	pub max_per_minute: u32,
+}
+
+env_default!(default_claims_enabled, "BRIDGE_CLAIMS_ENABLED", bool, false);
+
+env_default!(
+	default_max_invalid_claims_per_client,
+	"BRIDGE_CLAIMS_MAX_INVALID_PER_CLIENT",
+	u32,
+	DEFAULT_MAX_INVALID_CLAIMS_PER_CLIENT
+);
+
+env_default!(
+	default_max_claims_per_minute,
+	"BRIDGE_CLAIMS_MAX_PER_MINUTE",
+	u32,
+	DEFAULT_MAX_CLAIMS_PER_MINUTE
+);
+
+impl Default for ClaimsConfig {
+	fn default() -> Self {
+		ClaimsConfig {
+			enabled: default_claims_enabled(),
+			max_invalid_per_client: default_max_invalid_claims_per_client(),
+			max_per_minute: default_max_claims_per_minute(),
+		}
+	}
+}
@@ -0,0 +1,38 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Coordination of several relayer instances running against the same chains. The instances
+/// elect a leader through a lease in the indexer database, only the leader runs the relayer
+/// loop and submits transactions, the others stand by and take over when its lease expires.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CoordinationConfig {
+	#[serde(default = "default_coordination_enabled")]
+	pub enabled: bool,
+	/// Name of this instance in the lease, unique among the instances.
+	#[serde(default = "default_coordination_instance_id")]
+	pub instance_id: String,
+	/// Validity of the lease, in seconds. The leader renews it every third of it.
+	#[serde(default = "default_coordination_lease_secs")]
+	pub lease_secs: u64,
+}
+
+env_default!(default_coordination_enabled, "BRIDGE_COORDINATION_ENABLED", bool, false);
+env_default!(
+	default_coordination_instance_id,
+	"BRIDGE_COORDINATION_INSTANCE_ID",
+	String,
+	String::new()
+);
+env_default!(default_coordination_lease_secs, "BRIDGE_COORDINATION_LEASE_SECS", u64, 30);
+
+impl Default for CoordinationConfig {
+	fn default() -> Self {
+		CoordinationConfig {
+			enabled: default_coordination_enabled(),
+			instance_id: default_coordination_instance_id(),
+			lease_secs: default_coordination_lease_secs(),
+		}
+	}
+}
@@ -0,0 +1,24 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Failover drills started on the admin API, for the test deployments only.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct DrillsConfig {
+	/// Accept the drills, the faults they inject fail the transfers while they last.
+	#[serde(default = "default_enabled")]
+	pub enabled: bool,
+	/// Longest fault a drill can inject, in seconds.
+	#[serde(default = "default_max_duration_secs")]
+	pub max_duration_secs: u64,
+}
+
+env_default!(default_enabled, "BRIDGE_DRILLS_ENABLED", bool, false);
+env_default!(default_max_duration_secs, "BRIDGE_DRILLS_MAX_DURATION_SECS", u64, 300);
+
+impl Default for DrillsConfig {
+	fn default() -> Self {
+		DrillsConfig { enabled: default_enabled(), max_duration_secs: default_max_duration_secs() }
+	}
+}
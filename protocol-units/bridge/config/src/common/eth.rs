@@ -2,6 +2,7 @@ use crate::common::DEFAULT_REST_CONNECTION_TIMEOUT;
 use alloy::signers::local::PrivateKeySigner;
 use godfig::env_default;
 use godfig::env_short_default;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::env;
 
@@ -15,7 +16,8 @@ const DEFAULT_ETH_WETH_CONTRACT: &str = "0xe3e3";
 const DEFAULT_ETH_MOVETOKEN_CONTRACT: &str = "0xe3e2";
 const DEFAULT_ASSET: &str = "MOVE";
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct EthConfig {
 	// Connection config.
 	#[serde(default = "default_eth_rpc_connection_protocol")]
@@ -53,6 +55,17 @@ pub struct EthConfig {
 	pub gas_limit: u64,
 	#[serde(default = "default_transaction_send_retries")]
 	pub transaction_send_retries: u32,
+	/// Attach the access list computed by the node to the transactions sent by the relayer.
+	#[serde(default = "default_use_access_lists")]
+	pub use_access_lists: bool,
+	/// Blocks mined on top of the block of an initiated event before the relayer handles it,
+	/// so a reorg can't drop the initiating transaction of a locked transfer.
+	#[serde(default = "default_confirmations")]
+	pub confirmations: u64,
+	/// Scan the blocks over the WS connection instead of polling the RPC, without falling
+	/// back to the RPC when it fails.
+	#[serde(default = "default_monitoring_over_ws")]
+	pub monitoring_over_ws: bool,
 
 	#[serde(default = "default_asset")]
 	pub asset: String,
@@ -103,6 +116,12 @@ env_short_default!(default_gas_limit, u64, 10_000_000_000_000_000 as u64);
 
 env_short_default!(default_transaction_send_retries, u32, 10 as u32);
 
+env_default!(default_use_access_lists, "ETH_USE_ACCESS_LISTS", bool, false);
+
+env_default!(default_confirmations, "ETH_CONFIRMATIONS", u64, 0);
+
+env_default!(default_monitoring_over_ws, "ETH_MONITORING_OVER_WS", bool, false);
+
 env_default!(
 	default_eth_rpc_connection_protocol,
 	"ETH_RPC_CONNECTION_PROTOCOL",
@@ -163,6 +182,11 @@ impl EthConfig {
 		)
 	}
 
+	/// Whether a WS endpoint is configured, the RPC endpoint may be a WS one too.
+	pub fn eth_ws_configured(&self) -> bool {
+		matches!(self.eth_ws_connection_protocol.as_str(), "ws" | "wss")
+	}
+
 	pub fn eth_ws_connection_url(&self) -> String {
 		format!(
 			"{}://{}:{}",
@@ -195,6 +219,9 @@ impl Default for EthConfig {
 			signer_private_key: default_signer_private_key(),
 			gas_limit: default_gas_limit(),
 			transaction_send_retries: default_transaction_send_retries(),
+			use_access_lists: default_use_access_lists(),
+			confirmations: default_confirmations(),
+			monitoring_over_ws: default_monitoring_over_ws(),
 
 			asset: default_asset(),
 
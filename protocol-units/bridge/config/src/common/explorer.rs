@@ -0,0 +1,19 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Links to the block explorers and tagging of the relayer transactions.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ExplorerConfig {
+	/// Url of an Ethereum transaction in the explorer, `{hash}` is replaced by its hash,
+	/// e.g. `https://etherscan.io/tx/{hash}`.
+	#[serde(default)]
+	pub eth_tx_url: Option<String>,
+	/// Url of a Movement transaction in the explorer, `{hash}` is replaced by its hash.
+	#[serde(default)]
+	pub movement_tx_url: Option<String>,
+	/// Tag of the relayer appended, with the transfer id, to the calldata of its Ethereum
+	/// transactions. The Movement transactions have no memo field to tag.
+	#[serde(default)]
+	pub eth_calldata_tag: Option<String>,
+}
@@ -0,0 +1,45 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Accounting of the fees paid by the relayer on each transfer, converted to the bridged token
+/// so the operators can compare them with the relayer fees.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FeeTrackingConfig {
+	/// Price in wei of the smallest unit of the bridged token. The price of the refund config
+	/// is used if 0.
+	#[serde(default = "default_eth_wei_per_token_unit")]
+	pub eth_wei_per_token_unit: u64,
+	/// URL of a JSON price feed of the token, read instead of the fixed price. Empty to only
+	/// use the fixed price.
+	#[serde(default = "default_price_url")]
+	pub price_url: String,
+	/// JSON pointer of the price in wei per token unit in the response of the feed.
+	#[serde(default = "default_price_pointer")]
+	pub price_pointer: String,
+	/// Period of the reads of the price feed, in seconds.
+	#[serde(default = "default_price_refresh_secs")]
+	pub price_refresh_secs: u64,
+}
+
+env_default!(default_eth_wei_per_token_unit, "BRIDGE_FEE_TRACKING_ETH_WEI_PER_TOKEN_UNIT", u64, 0);
+env_default!(default_price_url, "BRIDGE_FEE_TRACKING_PRICE_URL", String, String::new());
+env_default!(
+	default_price_pointer,
+	"BRIDGE_FEE_TRACKING_PRICE_POINTER",
+	String,
+	"/price".to_string()
+);
+env_default!(default_price_refresh_secs, "BRIDGE_FEE_TRACKING_PRICE_REFRESH_SECS", u64, 300);
+
+impl Default for FeeTrackingConfig {
+	fn default() -> Self {
+		FeeTrackingConfig {
+			eth_wei_per_token_unit: default_eth_wei_per_token_unit(),
+			price_url: default_price_url(),
+			price_pointer: default_price_pointer(),
+			price_refresh_secs: default_price_refresh_secs(),
+		}
+	}
+}
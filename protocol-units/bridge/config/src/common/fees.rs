@@ -0,0 +1,53 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_FEE_DISTRIBUTION_INTERVAL_SECS: u64 = 24 * 60 * 60;
+
+/// Relayer fee deducted from the bridged amounts and distribution of the accrued fees.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FeesConfig {
+	#[serde(default = "default_fee_distribution_interval_secs")]
+	pub distribution_interval_secs: u64,
+	/// Recipients of the accrued fees. No distribution is done if empty.
+	#[serde(default)]
+	pub recipients: Vec<FeeRecipientConfig>,
+	/// Flat part of the relayer fee, in the smallest unit of the token.
+	#[serde(default = "default_relayer_fee_flat")]
+	pub relayer_fee_flat: u64,
+	/// Part of the relayer fee proportional to the bridged amount, in basis points.
+	#[serde(default = "default_relayer_fee_bps")]
+	pub relayer_fee_bps: u16,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FeeRecipientConfig {
+	/// Name recorded in the ledger, like treasury, operator or insurance.
+	pub name: String,
+	/// Hex encoded Eth address receiving the share.
+	pub eth_address: String,
+	/// Share of the accrued fees in basis points. All shares must sum to 10000.
+	pub share_bps: u16,
+}
+
+env_default!(
+	default_fee_distribution_interval_secs,
+	"BRIDGE_FEE_DISTRIBUTION_INTERVAL_SECS",
+	u64,
+	DEFAULT_FEE_DISTRIBUTION_INTERVAL_SECS
+);
+env_default!(default_relayer_fee_flat, "BRIDGE_RELAYER_FEE_FLAT", u64, 0);
+env_default!(default_relayer_fee_bps, "BRIDGE_RELAYER_FEE_BPS", u16, 0);
+
+impl Default for FeesConfig {
+	fn default() -> Self {
+		FeesConfig {
+			distribution_interval_secs: default_fee_distribution_interval_secs(),
+			recipients: Vec::new(),
+			relayer_fee_flat: default_relayer_fee_flat(),
+			relayer_fee_bps: default_relayer_fee_bps(),
+		}
+	}
+}
@@ -0,0 +1,44 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Startup mode of the relayer: follow the head of the chains, or process a fixed range of
+/// blocks and versions without submitting any transaction, for the investigation of past
+/// transfers.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ForensicsConfig {
+	/// Process only the ranges below, with the submissions disabled.
+	#[serde(default = "default_forensics_mode")]
+	pub enabled: bool,
+	/// First Ethereum block processed.
+	#[serde(default = "default_forensics_eth_from_block")]
+	pub eth_from_block: u64,
+	/// Last Ethereum block processed, included.
+	#[serde(default = "default_forensics_eth_to_block")]
+	pub eth_to_block: u64,
+	/// First Movement ledger version processed.
+	#[serde(default = "default_forensics_mvt_from_version")]
+	pub mvt_from_version: u64,
+	/// Last Movement ledger version processed, included.
+	#[serde(default = "default_forensics_mvt_to_version")]
+	pub mvt_to_version: u64,
+}
+
+env_default!(default_forensics_mode, "BRIDGE_FORENSICS_MODE", bool, false);
+env_default!(default_forensics_eth_from_block, "BRIDGE_FORENSICS_ETH_FROM_BLOCK", u64, 0);
+env_default!(default_forensics_eth_to_block, "BRIDGE_FORENSICS_ETH_TO_BLOCK", u64, u64::MAX);
+env_default!(default_forensics_mvt_from_version, "BRIDGE_FORENSICS_MVT_FROM_VERSION", u64, 0);
+env_default!(default_forensics_mvt_to_version, "BRIDGE_FORENSICS_MVT_TO_VERSION", u64, u64::MAX);
+
+impl Default for ForensicsConfig {
+	fn default() -> Self {
+		ForensicsConfig {
+			enabled: default_forensics_mode(),
+			eth_from_block: default_forensics_eth_from_block(),
+			eth_to_block: default_forensics_eth_to_block(),
+			mvt_from_version: default_forensics_mvt_from_version(),
+			mvt_to_version: default_forensics_mvt_to_version(),
+		}
+	}
+}
@@ -0,0 +1,91 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_FEE_HISTORY_BLOCKS: u64 = 10;
+const DEFAULT_PRIORITY_FEE_PERCENTILE: u64 = 50;
+const DEFAULT_BASE_FEE_MULTIPLIER_PERCENT: u64 = 200;
+const DEFAULT_STUCK_AFTER_SECS: u64 = 120;
+const DEFAULT_BUMP_PERCENT: u64 = 15;
+const DEFAULT_MAX_BUMPS: u32 = 3;
+
+/// Fees of the Ethereum transactions sent by the relayer.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GasConfig {
+	/// Send EIP-1559 transactions with fees computed from the fee history of the last blocks,
+	/// instead of legacy transactions at the node gas price.
+	#[serde(default = "default_gas_eip1559")]
+	pub eip1559: bool,
+	/// Number of the last blocks of the fee history.
+	#[serde(default = "default_gas_fee_history_blocks")]
+	pub fee_history_blocks: u64,
+	/// Percentile of the priority fees paid in the fee history blocks used as priority fee.
+	#[serde(default = "default_gas_priority_fee_percentile")]
+	pub priority_fee_percentile: u64,
+	/// Max fee per gas as a percentage of the next block base fee, plus the priority fee.
+	#[serde(default = "default_gas_base_fee_multiplier_percent")]
+	pub base_fee_multiplier_percent: u64,
+	/// Cap of the max fee per gas, in wei.
+	#[serde(default)]
+	pub max_fee_per_gas_cap: Option<u64>,
+	/// A transaction not mined after this delay is replaced, with the same nonce and
+	/// bumped fees. 0 to never replace the transactions.
+	#[serde(default = "default_gas_stuck_after_secs")]
+	pub stuck_after_secs: u64,
+	/// Fees increase of a replacement transaction, the nodes require at least 10%.
+	#[serde(default = "default_gas_bump_percent")]
+	pub bump_percent: u64,
+	/// Number of replacements before the transaction is given up.
+	#[serde(default = "default_gas_max_bumps")]
+	pub max_bumps: u32,
+}
+
+env_default!(default_gas_eip1559, "ETH_GAS_EIP1559", bool, false);
+
+env_default!(
+	default_gas_fee_history_blocks,
+	"ETH_GAS_FEE_HISTORY_BLOCKS",
+	u64,
+	DEFAULT_FEE_HISTORY_BLOCKS
+);
+
+env_default!(
+	default_gas_priority_fee_percentile,
+	"ETH_GAS_PRIORITY_FEE_PERCENTILE",
+	u64,
+	DEFAULT_PRIORITY_FEE_PERCENTILE
+);
+
+env_default!(
+	default_gas_base_fee_multiplier_percent,
+	"ETH_GAS_BASE_FEE_MULTIPLIER_PERCENT",
+	u64,
+	DEFAULT_BASE_FEE_MULTIPLIER_PERCENT
+);
+
+env_default!(
+	default_gas_stuck_after_secs,
+	"ETH_GAS_STUCK_AFTER_SECS",
+	u64,
+	DEFAULT_STUCK_AFTER_SECS
+);
+
+env_default!(default_gas_bump_percent, "ETH_GAS_BUMP_PERCENT", u64, DEFAULT_BUMP_PERCENT);
+
+env_default!(default_gas_max_bumps, "ETH_GAS_MAX_BUMPS", u32, DEFAULT_MAX_BUMPS);
+
+impl Default for GasConfig {
+	fn default() -> Self {
+		GasConfig {
+			eip1559: default_gas_eip1559(),
+			fee_history_blocks: default_gas_fee_history_blocks(),
+			priority_fee_percentile: default_gas_priority_fee_percentile(),
+			base_fee_multiplier_percent: default_gas_base_fee_multiplier_percent(),
+			max_fee_per_gas_cap: None,
+			stuck_after_secs: default_gas_stuck_after_secs(),
+			bump_percent: default_gas_bump_percent(),
+			max_bumps: default_gas_max_bumps(),
+		}
+	}
+}
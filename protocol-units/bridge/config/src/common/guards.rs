@@ -0,0 +1,81 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_MIN_TRANSFER_AMOUNT: u64 = 1;
+const DEFAULT_MAX_TRANSFER_AMOUNT: u64 = u64::MAX;
+const DEFAULT_AUTO_APPROVAL_LIMIT: u64 = u64::MAX;
+
+/// Limits checked on a prospective transfer before it is initiated.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GuardsConfig {
+	#[serde(default = "default_min_transfer_amount")]
+	pub min_transfer_amount: u64,
+	#[serde(default = "default_max_transfer_amount")]
+	pub max_transfer_amount: u64,
+	/// Transfers above this amount wait for an operator approval before being locked.
+	#[serde(default = "default_auto_approval_limit")]
+	pub auto_approval_limit: u64,
+	/// Hex encoded recipient addresses allowed to receive a transfer.
+	/// All recipients are allowed if empty.
+	#[serde(default)]
+	pub recipient_allowlist: Vec<String>,
+	/// Transfers initiated on Ethereum are relayed to Movement.
+	#[serde(default = "default_eth_to_movement_enabled")]
+	pub eth_to_movement_enabled: bool,
+	/// Transfers initiated on Movement are relayed to Ethereum.
+	#[serde(default = "default_movement_to_eth_enabled")]
+	pub movement_to_eth_enabled: bool,
+	/// Maximum value of a transfer in gwei, priced with the token price of the fee tracking.
+	/// Not checked if 0.
+	#[serde(default = "default_max_transfer_value_gwei")]
+	pub max_transfer_value_gwei: u64,
+	/// Period of the reads of the relayer balances checked by the liquidity guard, in seconds.
+	#[serde(default = "default_liquidity_refresh_secs")]
+	pub liquidity_refresh_secs: u64,
+}
+
+env_default!(
+	default_min_transfer_amount,
+	"BRIDGE_MIN_TRANSFER_AMOUNT",
+	u64,
+	DEFAULT_MIN_TRANSFER_AMOUNT
+);
+
+env_default!(
+	default_max_transfer_amount,
+	"BRIDGE_MAX_TRANSFER_AMOUNT",
+	u64,
+	DEFAULT_MAX_TRANSFER_AMOUNT
+);
+
+env_default!(
+	default_auto_approval_limit,
+	"BRIDGE_AUTO_APPROVAL_LIMIT",
+	u64,
+	DEFAULT_AUTO_APPROVAL_LIMIT
+);
+
+env_default!(default_eth_to_movement_enabled, "BRIDGE_ETH_TO_MOVEMENT_ENABLED", bool, true);
+
+env_default!(default_movement_to_eth_enabled, "BRIDGE_MOVEMENT_TO_ETH_ENABLED", bool, true);
+
+env_default!(default_max_transfer_value_gwei, "BRIDGE_MAX_TRANSFER_VALUE_GWEI", u64, 0);
+
+env_default!(default_liquidity_refresh_secs, "BRIDGE_LIQUIDITY_REFRESH_SECS", u64, 60);
+
+impl Default for GuardsConfig {
+	fn default() -> Self {
+		GuardsConfig {
+			min_transfer_amount: default_min_transfer_amount(),
+			max_transfer_amount: default_max_transfer_amount(),
+			auto_approval_limit: default_auto_approval_limit(),
+			recipient_allowlist: Vec::new(),
+			eth_to_movement_enabled: default_eth_to_movement_enabled(),
+			movement_to_eth_enabled: default_movement_to_eth_enabled(),
+			max_transfer_value_gwei: default_max_transfer_value_gwei(),
+			liquidity_refresh_secs: default_liquidity_refresh_secs(),
+		}
+	}
+}
@@ -0,0 +1,37 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Periodic reconciliation of the amounts initiated on Ethereum with the amounts locked on
+/// Movement.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct InvariantsConfig {
+	#[serde(default = "default_enabled")]
+	pub enabled: bool,
+	#[serde(default = "default_check_interval_secs")]
+	pub check_interval_secs: u64,
+	/// Transfers changed more recently are left out of the totals, their lock may be on its
+	/// way.
+	#[serde(default = "default_settle_secs")]
+	pub settle_secs: u64,
+	/// Difference between the totals above which an alert is raised, in token units.
+	#[serde(default = "default_max_locked_divergence")]
+	pub max_locked_divergence: u64,
+}
+
+env_default!(default_enabled, "BRIDGE_INVARIANTS_ENABLED", bool, false);
+env_default!(default_check_interval_secs, "BRIDGE_INVARIANTS_CHECK_INTERVAL_SECS", u64, 300);
+env_default!(default_settle_secs, "BRIDGE_INVARIANTS_SETTLE_SECS", u64, 300);
+env_default!(default_max_locked_divergence, "BRIDGE_INVARIANTS_MAX_LOCKED_DIVERGENCE", u64, 0);
+
+impl Default for InvariantsConfig {
+	fn default() -> Self {
+		InvariantsConfig {
+			enabled: default_enabled(),
+			check_interval_secs: default_check_interval_secs(),
+			settle_secs: default_settle_secs(),
+			max_locked_divergence: default_max_locked_divergence(),
+		}
+	}
+}
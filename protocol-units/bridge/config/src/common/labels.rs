@@ -0,0 +1,47 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_ENS_REGISTRY: &str = "0x00000000000C2E074eC69A0dFb2997BA6C7d2e1e";
+const DEFAULT_LABEL_CACHE_SECS: u64 = 10 * 60;
+
+/// Human labels of the addresses shown in the logs and the REST responses.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct LabelsConfig {
+	/// JSON file mapping hex encoded addresses to their label. Its labels take
+	/// precedence over the resolved names.
+	#[serde(default)]
+	pub label_file: Option<String>,
+	/// Resolve the Ethereum addresses to their ENS primary name.
+	#[serde(default = "default_ens_enabled")]
+	pub ens_enabled: bool,
+	/// ENS registry of the Ethereum network.
+	#[serde(default = "default_ens_registry")]
+	pub ens_registry: String,
+	/// Address of the Aptos Names Service on Movement, the Movement addresses
+	/// are not resolved without it.
+	#[serde(default)]
+	pub movement_ans_address: Option<String>,
+	/// Time a resolved label, or its absence, is kept before being resolved again.
+	#[serde(default = "default_label_cache_secs")]
+	pub cache_secs: u64,
+}
+
+env_default!(default_ens_enabled, "BRIDGE_ENS_LABELS_ENABLED", bool, false);
+
+env_default!(default_ens_registry, "BRIDGE_ENS_REGISTRY", String, DEFAULT_ENS_REGISTRY.to_string());
+
+env_default!(default_label_cache_secs, "BRIDGE_LABEL_CACHE_SECS", u64, DEFAULT_LABEL_CACHE_SECS);
+
+impl Default for LabelsConfig {
+	fn default() -> Self {
+		LabelsConfig {
+			label_file: None,
+			ens_enabled: default_ens_enabled(),
+			ens_registry: default_ens_registry(),
+			movement_ans_address: None,
+			cache_secs: default_label_cache_secs(),
+		}
+	}
+}
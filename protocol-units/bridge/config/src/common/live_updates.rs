@@ -0,0 +1,34 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_LIVE_UPDATES_MAX_CONNECTIONS: u64 = 256;
+
+/// Websocket streams of the changes of state of the transfers, for the bridge frontends.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct LiveUpdatesConfig {
+	#[serde(default = "default_live_updates_enabled")]
+	pub enabled: bool,
+	/// Number of websocket connections open at the same time, the others are refused.
+	#[serde(default = "default_live_updates_max_connections")]
+	pub max_connections: u64,
+}
+
+env_default!(default_live_updates_enabled, "BRIDGE_LIVE_UPDATES", bool, true);
+
+env_default!(
+	default_live_updates_max_connections,
+	"BRIDGE_LIVE_UPDATES_MAX_CONNECTIONS",
+	u64,
+	DEFAULT_LIVE_UPDATES_MAX_CONNECTIONS
+);
+
+impl Default for LiveUpdatesConfig {
+	fn default() -> Self {
+		LiveUpdatesConfig {
+			enabled: default_live_updates_enabled(),
+			max_connections: default_live_updates_max_connections(),
+		}
+	}
+}
@@ -0,0 +1,40 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_METRICS_LISTENER_HOSTNAME: &str = "0.0.0.0";
+const DEFAULT_METRICS_PORT: u16 = 9464;
+
+/// Listener of the Prometheus metrics, served apart from the REST API so the scrapers don't
+/// need access to it. The REST API keeps serving them on `/metrics`.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct MetricsConfig {
+	#[serde(default = "default_metrics_enabled")]
+	pub enabled: bool,
+	#[serde(default = "default_metrics_listener_hostname")]
+	pub listener_hostname: String,
+	#[serde(default = "default_metrics_port")]
+	pub port: u16,
+}
+
+env_default!(default_metrics_enabled, "BRIDGE_METRICS_ENABLED", bool, false);
+
+env_default!(
+	default_metrics_listener_hostname,
+	"BRIDGE_METRICS_LISTENER_HOSTNAME",
+	String,
+	DEFAULT_METRICS_LISTENER_HOSTNAME.to_string()
+);
+
+env_default!(default_metrics_port, "BRIDGE_METRICS_PORT", u16, DEFAULT_METRICS_PORT);
+
+impl Default for MetricsConfig {
+	fn default() -> Self {
+		MetricsConfig {
+			enabled: default_metrics_enabled(),
+			listener_hostname: default_metrics_listener_hostname(),
+			port: default_metrics_port(),
+		}
+	}
+}
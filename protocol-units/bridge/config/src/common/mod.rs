@@ -1,5 +1,43 @@
+pub mod admin;
+pub mod allowance_gc;
+pub mod anomalies;
+pub mod canary;
+pub mod circuit_breaker;
+pub mod claims;
+pub mod coordination;
+pub mod drills;
 pub mod eth;
+pub mod explorer;
+pub mod fee_tracking;
+pub mod fees;
+pub mod forensics;
+pub mod gas;
+pub mod guards;
+pub mod invariants;
+pub mod labels;
+pub mod live_updates;
+pub mod metrics;
 pub mod movement;
+pub mod notifications;
+pub mod pre_image;
+pub mod rate_limit;
+pub mod redundancy;
+pub mod refund;
+pub mod relayer;
+pub mod reports;
+pub mod retry;
+pub mod rpc_cache;
+pub mod runbook;
+pub mod secrets;
+pub mod signer;
+pub mod split;
+pub mod startup;
+pub mod sweep;
+pub mod telemetry;
 pub mod testing;
+pub mod timelock;
+pub mod tokens;
+pub mod transfer_store;
+pub mod webhooks;
 
 const DEFAULT_REST_CONNECTION_TIMEOUT: u64 = 5;
@@ -1,6 +1,7 @@
 use crate::common::DEFAULT_REST_CONNECTION_TIMEOUT;
 use aptos_crypto::{ed25519::Ed25519PrivateKey, Uniform, ValidCryptoMaterialStringExt};
 use godfig::env_default;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 const DEFAULT_MOVEMENT_NATIVE_ADDRESS: &str = "0xface";
@@ -13,9 +14,11 @@ const DEFAULT_GRPC_LISTENER_HOSTNAME: &str = "0.0.0.0";
 const DEFAULT_GRPC_LISTENER_PORT: u16 = 50051;
 const DEFAULT_REST_LISTENER_PORT: u16 = 30883;
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct MovementConfig {
 	#[serde(default = "default_movement_signer_key")]
+	#[schemars(with = "String")]
 	pub movement_signer_key: Ed25519PrivateKey,
 	#[serde(default = "default_movement_native_address")]
 	pub movement_native_address: String,
@@ -36,6 +39,9 @@ pub struct MovementConfig {
 
 	#[serde(default = "default_mvt_init_network")]
 	pub mvt_init_network: String,
+	// Expected chain id, not verified if 0.
+	#[serde(default)]
+	pub mvt_chain_id: u8,
 
 	/// Endpoint for the REST service
 	#[serde(default = "default_rest_listener_hostname")]
@@ -52,6 +58,19 @@ pub struct MovementConfig {
 	pub grpc_port: u16,
 	#[serde(default = "rest_connection_timeout_secs")]
 	pub rest_connection_timeout_secs: u64,
+
+	/// Read the sequence number of the signer and the coin store of the recipients while the
+	/// Ethereum initiations wait for their confirmations.
+	#[serde(default = "default_mvt_prefetch_resources")]
+	pub mvt_prefetch_resources: bool,
+	/// Register the coin store of the recipients without one before locking their transfers,
+	/// paid by the relayer.
+	#[serde(default = "default_mvt_auto_register_recipients")]
+	pub mvt_auto_register_recipients: bool,
+	/// Directory of the compiled batch scripts of the bridge modules. Without it, the batches
+	/// are submitted one transaction per transfer.
+	#[serde(default)]
+	pub mvt_batch_scripts_path: Option<String>,
 }
 
 env_default!(
@@ -145,6 +164,10 @@ env_default!(
 
 env_default!(default_mvt_init_network, "MVT_FAUCET_INIT_NETWORK", String, "local".to_string());
 
+env_default!(default_mvt_prefetch_resources, "MVT_PREFETCH_RESOURCES", bool, true);
+
+env_default!(default_mvt_auto_register_recipients, "MVT_AUTO_REGISTER_RECIPIENTS", bool, false);
+
 impl MovementConfig {
 	pub fn mvt_rpc_connection_url(&self) -> String {
 		format!(
@@ -179,12 +202,16 @@ impl MovementConfig {
 			mvt_faucet_connection_hostname: default_mvt_rpc_connection_hostname(),
 			mvt_faucet_connection_port: 30732,
 			mvt_init_network: default_mvt_init_network(),
+			mvt_chain_id: 0,
 			rest_listener_hostname: default_rest_listener_hostname(),
 			rest_port: default_rest_listener_port(),
 			grpc_protocol: default_grpc_connection_protocol(),
 			grpc_listener_hostname: default_grpc_listener_hostname(),
 			grpc_port: default_grpc_listener_port(),
 			rest_connection_timeout_secs: rest_connection_timeout_secs(),
+			mvt_prefetch_resources: default_mvt_prefetch_resources(),
+			mvt_auto_register_recipients: default_mvt_auto_register_recipients(),
+			mvt_batch_scripts_path: None,
 		}
 	}
 }
@@ -201,12 +228,16 @@ impl Default for MovementConfig {
 			mvt_faucet_connection_hostname: default_mvt_rpc_connection_hostname(),
 			mvt_faucet_connection_port: default_mvt_faucet_connection_port(),
 			mvt_init_network: default_mvt_init_network(),
+			mvt_chain_id: 0,
 			rest_listener_hostname: default_rest_listener_hostname(),
 			rest_port: default_rest_listener_port(),
 			grpc_protocol: default_grpc_connection_protocol(),
 			grpc_listener_hostname: default_grpc_listener_hostname(),
 			grpc_port: default_grpc_listener_port(),
 			rest_connection_timeout_secs: rest_connection_timeout_secs(),
+			mvt_prefetch_resources: default_mvt_prefetch_resources(),
+			mvt_auto_register_recipients: default_mvt_auto_register_recipients(),
+			mvt_batch_scripts_path: None,
 		}
 	}
 }
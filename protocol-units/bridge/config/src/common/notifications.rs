@@ -0,0 +1,52 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_MAX_NOTIFICATIONS_PER_HOUR: u32 = 10;
+const DEFAULT_MAX_REGISTRATIONS: u64 = 10_000;
+
+/// Notifications of the end users about the stages of their transfers.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct NotificationsConfig {
+	/// Accept the registrations of the end users on the REST API.
+	#[serde(default = "default_notifications_enabled")]
+	pub enabled: bool,
+	/// Url of the gateway receiving a JSON POST for each email to send.
+	/// Email registrations are refused without it.
+	#[serde(default)]
+	pub email_gateway_url: Option<String>,
+	/// Maximum number of notifications sent to a webhook or an email per hour.
+	#[serde(default = "default_max_notifications_per_hour")]
+	pub max_per_hour: u32,
+	/// Maximum number of registrations of the transfers not finished.
+	#[serde(default = "default_max_registrations")]
+	pub max_registrations: u64,
+}
+
+env_default!(default_notifications_enabled, "BRIDGE_USER_NOTIFICATIONS_ENABLED", bool, false);
+
+env_default!(
+	default_max_notifications_per_hour,
+	"BRIDGE_USER_NOTIFICATIONS_MAX_PER_HOUR",
+	u32,
+	DEFAULT_MAX_NOTIFICATIONS_PER_HOUR
+);
+
+env_default!(
+	default_max_registrations,
+	"BRIDGE_USER_NOTIFICATIONS_MAX_REGISTRATIONS",
+	u64,
+	DEFAULT_MAX_REGISTRATIONS
+);
+
+impl Default for NotificationsConfig {
+	fn default() -> Self {
+		NotificationsConfig {
+			enabled: default_notifications_enabled(),
+			email_gateway_url: None,
+			max_per_hour: default_max_notifications_per_hour(),
+			max_registrations: default_max_registrations(),
+		}
+	}
+}
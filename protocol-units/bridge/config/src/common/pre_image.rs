@@ -0,0 +1,76 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_PRE_IMAGE_FORMAT: &str = "utf8";
+const DEFAULT_HASH_LOCK_ALGORITHM: &str = "keccak256";
+
+/// Format of the preimages of the hash locks of the token pairs.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PreImageConfig {
+	/// Format of the token pairs without an override: `raw`, `utf8` or `bcs_string`.
+	#[serde(default = "default_pre_image_format")]
+	pub format: String,
+	/// Formats of the token pairs, by asset of the pair.
+	#[serde(default)]
+	pub overrides: Vec<PreImageFormatConfig>,
+	/// Digest of the hash locks of the Ethereum contracts: `keccak256` or `sha2_256`.
+	#[serde(default = "default_eth_hash_lock_algorithm")]
+	pub eth_hash_lock_algorithm: String,
+	/// Digest of the hash locks of the Move modules: `keccak256` or `sha2_256`.
+	#[serde(default = "default_movement_hash_lock_algorithm")]
+	pub movement_hash_lock_algorithm: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PreImageFormatConfig {
+	/// Asset of the token pair, as the Ethereum `asset` (ex: `WETH`).
+	pub asset: String,
+	/// Either `raw`, `utf8` or `bcs_string`.
+	pub format: String,
+}
+
+env_default!(
+	default_pre_image_format,
+	"BRIDGE_PRE_IMAGE_FORMAT",
+	String,
+	DEFAULT_PRE_IMAGE_FORMAT.to_string()
+);
+
+env_default!(
+	default_eth_hash_lock_algorithm,
+	"BRIDGE_ETH_HASH_LOCK_ALGORITHM",
+	String,
+	DEFAULT_HASH_LOCK_ALGORITHM.to_string()
+);
+
+env_default!(
+	default_movement_hash_lock_algorithm,
+	"BRIDGE_MVT_HASH_LOCK_ALGORITHM",
+	String,
+	DEFAULT_HASH_LOCK_ALGORITHM.to_string()
+);
+
+impl PreImageConfig {
+	/// Format of the token pair of the asset.
+	pub fn format_of(&self, asset: &str) -> &str {
+		self.overrides
+			.iter()
+			.find(|asset_format| asset_format.asset == asset)
+			.map(|asset_format| asset_format.format.as_str())
+			.unwrap_or(&self.format)
+	}
+}
+
+impl Default for PreImageConfig {
+	fn default() -> Self {
+		PreImageConfig {
+			format: default_pre_image_format(),
+			overrides: Vec::new(),
+			eth_hash_lock_algorithm: default_eth_hash_lock_algorithm(),
+			movement_hash_lock_algorithm: default_movement_hash_lock_algorithm(),
+		}
+	}
+}
@@ -0,0 +1,43 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Caps on the amounts initiated in a sliding window, limiting what an exploit can drain.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimitConfig {
+	/// Length of the window, in seconds.
+	#[serde(default = "default_window_secs")]
+	pub window_secs: u64,
+	/// Amount a single initiator address can transfer in the window.
+	#[serde(default = "default_max_amount_per_initiator")]
+	pub max_amount_per_initiator: u64,
+	/// Amount all the initiators can transfer in the window.
+	#[serde(default = "default_max_amount_global")]
+	pub max_amount_global: u64,
+	/// Transfers over a cap are locked once the window has room for them.
+	/// They are refunded if disabled.
+	#[serde(default = "default_defer_over_cap")]
+	pub defer_over_cap: bool,
+}
+
+env_default!(default_window_secs, "BRIDGE_RATE_LIMIT_WINDOW_SECS", u64, 3600);
+env_default!(
+	default_max_amount_per_initiator,
+	"BRIDGE_RATE_LIMIT_MAX_AMOUNT_PER_INITIATOR",
+	u64,
+	u64::MAX
+);
+env_default!(default_max_amount_global, "BRIDGE_RATE_LIMIT_MAX_AMOUNT_GLOBAL", u64, u64::MAX);
+env_default!(default_defer_over_cap, "BRIDGE_RATE_LIMIT_DEFER_OVER_CAP", bool, true);
+
+impl Default for RateLimitConfig {
+	fn default() -> Self {
+		RateLimitConfig {
+			window_secs: default_window_secs(),
+			max_amount_per_initiator: default_max_amount_per_initiator(),
+			max_amount_global: default_max_amount_global(),
+			defer_over_cap: default_defer_over_cap(),
+		}
+	}
+}
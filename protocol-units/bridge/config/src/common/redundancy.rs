@@ -0,0 +1,43 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Broadcast of the critical Ethereum transactions through a second RPC provider. The
+/// completions of the transfers close to the expiry of their time lock are signed once and sent
+/// to both providers, with the same nonce, so an outage or a slow mempool of one provider
+/// doesn't let the time lock expire.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RedundancyConfig {
+	/// RPC url of the second provider, independent of the one of the Ethereum config. Empty to
+	/// send every transaction through the first provider only.
+	#[serde(default = "default_secondary_rpc_url")]
+	pub secondary_rpc_url: String,
+	/// A completion is critical when the time lock of its transfer expires within this delay,
+	/// in seconds.
+	#[serde(default = "default_within_secs")]
+	pub within_secs: u64,
+}
+
+env_default!(
+	default_secondary_rpc_url,
+	"BRIDGE_REDUNDANCY_SECONDARY_RPC_URL",
+	String,
+	String::new()
+);
+env_default!(default_within_secs, "BRIDGE_REDUNDANCY_WITHIN_SECS", u64, 30 * 60);
+
+impl RedundancyConfig {
+	pub fn is_enabled(&self) -> bool {
+		!self.secondary_rpc_url.is_empty()
+	}
+}
+
+impl Default for RedundancyConfig {
+	fn default() -> Self {
+		RedundancyConfig {
+			secondary_rpc_url: default_secondary_rpc_url(),
+			within_secs: default_within_secs(),
+		}
+	}
+}
@@ -0,0 +1,37 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_MOVEMENT_REFUND_GAS_UNITS: u64 = 2_000;
+
+/// Estimation of the cost of the refunds suggested to the initiators.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RefundConfig {
+	/// Price in wei of the smallest unit of the bridged token, to compare the Ethereum
+	/// refund fees with the refunded amounts. The fees are not compared if 0.
+	#[serde(default = "default_eth_wei_per_token_unit")]
+	pub eth_wei_per_token_unit: u64,
+	/// Gas units of a refund on Movement. The refund can't be simulated without
+	/// the public key of the initiator.
+	#[serde(default = "default_movement_refund_gas_units")]
+	pub movement_refund_gas_units: u64,
+}
+
+env_default!(default_eth_wei_per_token_unit, "BRIDGE_REFUND_ETH_WEI_PER_TOKEN_UNIT", u64, 0);
+
+env_default!(
+	default_movement_refund_gas_units,
+	"BRIDGE_REFUND_MOVEMENT_GAS_UNITS",
+	u64,
+	DEFAULT_MOVEMENT_REFUND_GAS_UNITS
+);
+
+impl Default for RefundConfig {
+	fn default() -> Self {
+		RefundConfig {
+			eth_wei_per_token_unit: default_eth_wei_per_token_unit(),
+			movement_refund_gas_units: default_movement_refund_gas_units(),
+		}
+	}
+}
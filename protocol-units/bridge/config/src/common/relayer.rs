@@ -0,0 +1,29 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Operating mode of the relayer loop.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RelayerConfig {
+	/// Derive the state of each transfer from on-chain reads when an event is received
+	/// instead of relying on the state kept in memory.
+	#[serde(default = "default_stateless_verification")]
+	pub stateless_verification: bool,
+	/// Maximum number of transfers locked and not yet completed. Transfers initiated
+	/// above the cap are kept in a backlog and locked once in-flight transfers complete.
+	#[serde(default = "default_max_in_flight_transfers")]
+	pub max_in_flight_transfers: u64,
+}
+
+env_default!(default_stateless_verification, "BRIDGE_STATELESS_VERIFICATION", bool, false);
+env_default!(default_max_in_flight_transfers, "BRIDGE_MAX_IN_FLIGHT_TRANSFERS", u64, u64::MAX);
+
+impl Default for RelayerConfig {
+	fn default() -> Self {
+		RelayerConfig {
+			stateless_verification: default_stateless_verification(),
+			max_in_flight_transfers: default_max_in_flight_transfers(),
+		}
+	}
+}
@@ -0,0 +1,61 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_REPORTS_PERIOD_SECS: u64 = 24 * 60 * 60;
+const DEFAULT_REPORTS_MAX_PERIODS: u32 = 366;
+const DEFAULT_REPORTS_GAS_RECORD_INTERVAL_SECS: u64 = 60;
+
+/// Revenue reports of the liquidity operators, computed from the fee ledger of the indexer
+/// db. The Ethereum gas costs are converted to the bridged token with
+/// `refund.eth_wei_per_token_unit`.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ReportsConfig {
+	/// Liquidity provided to the bridge, in the smallest unit of the token. The utilization
+	/// and the APY are not reported if 0.
+	#[serde(default = "default_reports_liquidity")]
+	pub liquidity: u64,
+	/// Length of the periods of a report when the request doesn't set it.
+	#[serde(default = "default_reports_period_secs")]
+	pub period_secs: u64,
+	#[serde(default = "default_reports_max_periods")]
+	pub max_periods: u32,
+	/// Interval of the records of the gas spent by the relayer in the ledger.
+	#[serde(default = "default_reports_gas_record_interval_secs")]
+	pub gas_record_interval_secs: u64,
+}
+
+env_default!(default_reports_liquidity, "BRIDGE_REPORTS_LIQUIDITY", u64, 0);
+
+env_default!(
+	default_reports_period_secs,
+	"BRIDGE_REPORTS_PERIOD_SECS",
+	u64,
+	DEFAULT_REPORTS_PERIOD_SECS
+);
+
+env_default!(
+	default_reports_max_periods,
+	"BRIDGE_REPORTS_MAX_PERIODS",
+	u32,
+	DEFAULT_REPORTS_MAX_PERIODS
+);
+
+env_default!(
+	default_reports_gas_record_interval_secs,
+	"BRIDGE_REPORTS_GAS_RECORD_INTERVAL_SECS",
+	u64,
+	DEFAULT_REPORTS_GAS_RECORD_INTERVAL_SECS
+);
+
+impl Default for ReportsConfig {
+	fn default() -> Self {
+		ReportsConfig {
+			liquidity: default_reports_liquidity(),
+			period_secs: default_reports_period_secs(),
+			max_periods: default_reports_max_periods(),
+			gas_record_interval_secs: default_reports_gas_record_interval_secs(),
+		}
+	}
+}
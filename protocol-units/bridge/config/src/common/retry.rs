@@ -0,0 +1,62 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_CALL_MAX_RETRIES: u32 = 3;
+const DEFAULT_CALL_BACKOFF_MS: u64 = 500;
+const DEFAULT_CALL_MAX_BACKOFF_MS: u64 = 10_000;
+
+/// Overrides of the relayer retry classification table.
+/// Each entry replaces the built-in rule for the given error kind.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RetryConfig {
+	#[serde(default)]
+	pub overrides: Vec<RetryRuleConfig>,
+	/// Retries of a bridge contract call failing with a retryable error,
+	/// before the error is returned to the caller.
+	#[serde(default = "default_call_max_retries")]
+	pub call_max_retries: u32,
+	/// Delay before the first retry of a call, doubled on each retry.
+	#[serde(default = "default_call_backoff_ms")]
+	pub call_backoff_ms: u64,
+	#[serde(default = "default_call_max_backoff_ms")]
+	pub call_max_backoff_ms: u64,
+}
+
+env_default!(default_call_max_retries, "BRIDGE_CALL_MAX_RETRIES", u32, DEFAULT_CALL_MAX_RETRIES);
+
+env_default!(default_call_backoff_ms, "BRIDGE_CALL_BACKOFF_MS", u64, DEFAULT_CALL_BACKOFF_MS);
+
+env_default!(
+	default_call_max_backoff_ms,
+	"BRIDGE_CALL_MAX_BACKOFF_MS",
+	u64,
+	DEFAULT_CALL_MAX_BACKOFF_MS
+);
+
+impl Default for RetryConfig {
+	fn default() -> Self {
+		RetryConfig {
+			overrides: Vec::new(),
+			call_max_retries: default_call_max_retries(),
+			call_backoff_ms: default_call_backoff_ms(),
+			call_max_backoff_ms: default_call_max_backoff_ms(),
+		}
+	}
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RetryRuleConfig {
+	/// Error kind as exported by the admin API (ex: `OnChainError`).
+	pub error_kind: String,
+	/// Either `retry` or `abort`.
+	pub decision: String,
+	/// Number of retries before the transfer is aborted. Ignored for `abort`.
+	#[serde(default)]
+	pub max_retries: usize,
+	/// Delay applied before re-executing the action.
+	#[serde(default)]
+	pub backoff_secs: u64,
+}
@@ -0,0 +1,46 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_RPC_CACHE_TTL_SECS: u64 = 60;
+const DEFAULT_RPC_CACHE_GAS_PRICE_TTL_SECS: u64 = 5;
+
+/// Cache of the idempotent RPC reads.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RpcCacheConfig {
+	#[serde(default = "default_rpc_cache_enabled")]
+	pub enabled: bool,
+	/// Time to live of the chain id, token decimals and contract code reads.
+	#[serde(default = "default_rpc_cache_ttl_secs")]
+	pub ttl_secs: u64,
+	/// Time to live of the gas price reads.
+	#[serde(default = "default_rpc_cache_gas_price_ttl_secs")]
+	pub gas_price_ttl_secs: u64,
+}
+
+env_default!(default_rpc_cache_enabled, "BRIDGE_RPC_CACHE_ENABLED", bool, true);
+
+env_default!(
+	default_rpc_cache_ttl_secs,
+	"BRIDGE_RPC_CACHE_TTL_SECS",
+	u64,
+	DEFAULT_RPC_CACHE_TTL_SECS
+);
+
+env_default!(
+	default_rpc_cache_gas_price_ttl_secs,
+	"BRIDGE_RPC_CACHE_GAS_PRICE_TTL_SECS",
+	u64,
+	DEFAULT_RPC_CACHE_GAS_PRICE_TTL_SECS
+);
+
+impl Default for RpcCacheConfig {
+	fn default() -> Self {
+		RpcCacheConfig {
+			enabled: default_rpc_cache_enabled(),
+			ttl_secs: default_rpc_cache_ttl_secs(),
+			gas_price_ttl_secs: default_rpc_cache_gas_price_ttl_secs(),
+		}
+	}
+}
@@ -0,0 +1,59 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_STUCK_TRANSFER_SLA_SECS: u64 = 60 * 60;
+const DEFAULT_HOOK_TIMEOUT_SECS: u64 = 30;
+
+/// Action run when an alert of the given kind is raised: either a local command
+/// or an HTTP POST, both receiving the alert as JSON.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RunbookHookConfig {
+	/// One of liquidity_low, transfer_stuck, reconciliation_mismatch or canary_failed.
+	pub alert: String,
+	/// Program and arguments of the command, reading the alert on its stdin.
+	#[serde(default)]
+	pub command: Vec<String>,
+	/// Url receiving the alert as a JSON POST.
+	#[serde(default)]
+	pub url: Option<String>,
+}
+
+/// Automated remediation hooks run on the operator alerts.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RunbookConfig {
+	#[serde(default)]
+	pub hooks: Vec<RunbookHookConfig>,
+	/// Time without change of state after which a transfer is reported stuck.
+	#[serde(default = "default_stuck_transfer_sla_secs")]
+	pub stuck_transfer_sla_secs: u64,
+	/// Time after which a hook command or request is aborted.
+	#[serde(default = "default_hook_timeout_secs")]
+	pub hook_timeout_secs: u64,
+}
+
+env_default!(
+	default_stuck_transfer_sla_secs,
+	"BRIDGE_STUCK_TRANSFER_SLA_SECS",
+	u64,
+	DEFAULT_STUCK_TRANSFER_SLA_SECS
+);
+
+env_default!(
+	default_hook_timeout_secs,
+	"BRIDGE_RUNBOOK_HOOK_TIMEOUT_SECS",
+	u64,
+	DEFAULT_HOOK_TIMEOUT_SECS
+);
+
+impl Default for RunbookConfig {
+	fn default() -> Self {
+		RunbookConfig {
+			hooks: Vec::new(),
+			stuck_transfer_sla_secs: default_stuck_transfer_sla_secs(),
+			hook_timeout_secs: default_hook_timeout_secs(),
+		}
+	}
+}
@@ -0,0 +1,29 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Storage of the preimages of the hash locks generated by the relayer.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SecretsConfig {
+	/// File the encrypted preimages are stored in. Empty for `secrets.store`
+	/// in the bridge config folder.
+	#[serde(default = "default_secrets_store_file")]
+	pub store_file: String,
+	/// File holding the hex encoded 32 bytes key of the preimages. Empty for the encryption
+	/// key of the indexer db, without any key the preimages are only kept in memory.
+	#[serde(default = "default_secrets_key_file")]
+	pub key_file: String,
+}
+
+env_default!(default_secrets_store_file, "BRIDGE_SECRETS_STORE_FILE", String, String::new());
+env_default!(default_secrets_key_file, "BRIDGE_SECRETS_KEY_FILE", String, String::new());
+
+impl Default for SecretsConfig {
+	fn default() -> Self {
+		SecretsConfig {
+			store_file: default_secrets_store_file(),
+			key_file: default_secrets_key_file(),
+		}
+	}
+}
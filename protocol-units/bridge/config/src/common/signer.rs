@@ -0,0 +1,58 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Signer of the Ethereum transactions of the relayer, and policy of the separate signing
+/// process holding its key.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SignerConfig {
+	/// Signer of the relayer: `local` signs with `eth.signer_private_key`, `aws_kms` with the
+	/// key of `aws_kms_key_id` and `remote` with the signing service at `url`. The private key
+	/// can be left unset with the last two, so it's never on the relayer host.
+	#[serde(default = "default_signer_backend")]
+	pub backend: String,
+	/// Id or ARN of the secp256k1 key of AWS KMS signing with the `aws_kms` backend. The AWS
+	/// credentials and region are read from the environment.
+	#[serde(default = "default_signer_aws_kms_key_id")]
+	pub aws_kms_key_id: String,
+	/// URL of the signing service of the `remote` backend, `unix://` for a Unix socket or
+	/// `http(s)://`.
+	#[serde(default = "default_signer_url")]
+	pub url: String,
+	/// Unix socket of the signer. The `local` backend signs with the signer listening on it if
+	/// set, like the `remote` backend.
+	#[serde(default = "default_signer_socket_path")]
+	pub socket_path: String,
+	/// Largest amount the signer accepts to lock, initiate, transfer or approve in one
+	/// transaction. Must be set, the signer doesn't start with 0.
+	#[serde(default = "default_signer_max_amount")]
+	pub max_amount: u64,
+	/// Contracts the signer accepts to send transactions to, among the bridge and token
+	/// contracts of the config. Defaults to all of them if empty.
+	#[serde(default)]
+	pub allowed_contracts: Vec<String>,
+}
+
+env_default!(default_signer_backend, "BRIDGE_SIGNER_BACKEND", String, "local".to_string());
+
+env_default!(default_signer_aws_kms_key_id, "BRIDGE_SIGNER_AWS_KMS_KEY_ID", String, String::new());
+
+env_default!(default_signer_url, "BRIDGE_SIGNER_URL", String, String::new());
+
+env_default!(default_signer_socket_path, "BRIDGE_SIGNER_SOCKET_PATH", String, String::new());
+
+env_default!(default_signer_max_amount, "BRIDGE_SIGNER_MAX_AMOUNT", u64, 0);
+
+impl Default for SignerConfig {
+	fn default() -> Self {
+		SignerConfig {
+			backend: default_signer_backend(),
+			aws_kms_key_id: default_signer_aws_kms_key_id(),
+			url: default_signer_url(),
+			socket_path: default_signer_socket_path(),
+			max_amount: default_signer_max_amount(),
+			allowed_contracts: Vec::new(),
+		}
+	}
+}
@@ -0,0 +1,37 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_SPLIT_THRESHOLD: u64 = u64::MAX;
+const DEFAULT_SPLIT_CHILD_COUNT: u64 = 4;
+
+/// Split of the large transfers into smaller child HTLCs on the counterparty chain.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SplitConfig {
+	/// Transfers above this amount are locked as several child HTLCs.
+	#[serde(default = "default_split_threshold")]
+	pub threshold: u64,
+	/// The number of child HTLCs a split transfer is locked with.
+	#[serde(default = "default_split_child_count")]
+	pub child_count: u64,
+	/// Lock all the children at once instead of one after the other.
+	#[serde(default = "default_split_parallel")]
+	pub parallel: bool,
+}
+
+env_default!(default_split_threshold, "BRIDGE_SPLIT_THRESHOLD", u64, DEFAULT_SPLIT_THRESHOLD);
+
+env_default!(default_split_child_count, "BRIDGE_SPLIT_CHILD_COUNT", u64, DEFAULT_SPLIT_CHILD_COUNT);
+
+env_default!(default_split_parallel, "BRIDGE_SPLIT_PARALLEL", bool, false);
+
+impl Default for SplitConfig {
+	fn default() -> Self {
+		SplitConfig {
+			threshold: default_split_threshold(),
+			child_count: default_split_child_count(),
+			parallel: default_split_parallel(),
+		}
+	}
+}
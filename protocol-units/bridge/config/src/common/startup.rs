@@ -0,0 +1,42 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_CHECK_TIMEOUT_SECS: u64 = 10;
+
+/// Checks of the endpoints of the config when the relayer starts.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct StartupConfig {
+	/// Timeout of the check of each endpoint: DNS resolution, TLS certificate and chain id.
+	#[serde(default = "default_startup_check_timeout_secs")]
+	pub check_timeout_secs: u64,
+	/// Stop the relayer if an endpoint check fails, else only log the report.
+	#[serde(default = "default_startup_fail_on_error")]
+	pub fail_on_error: bool,
+	/// Refuse the unsafe fallbacks of the config, and stop the relayer on the warnings of its
+	/// startup. Intended for the mainnet deployments.
+	#[serde(default = "default_startup_strict_mode")]
+	pub strict_mode: bool,
+}
+
+env_default!(
+	default_startup_check_timeout_secs,
+	"STARTUP_CHECK_TIMEOUT_SECS",
+	u64,
+	DEFAULT_CHECK_TIMEOUT_SECS
+);
+
+env_default!(default_startup_fail_on_error, "STARTUP_FAIL_ON_ERROR", bool, true);
+
+env_default!(default_startup_strict_mode, "BRIDGE_STRICT_MODE", bool, false);
+
+impl Default for StartupConfig {
+	fn default() -> Self {
+		StartupConfig {
+			check_timeout_secs: default_startup_check_timeout_secs(),
+			fail_on_error: default_startup_fail_on_error(),
+			strict_mode: default_startup_strict_mode(),
+		}
+	}
+}
@@ -0,0 +1,59 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Sweep of the fees accumulated on the hot relayer accounts to cold storage. The balance of a
+/// relayer account above its threshold is transferred to the cold storage address of its
+/// chain. The cold storage addresses and the thresholds must be signed by the admin key, and
+/// the manual sweeps are requested with a signature of the admin key.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SweepConfig {
+	/// Sweep on a schedule, the manual sweeps are accepted even if disabled.
+	#[serde(default = "default_enabled")]
+	pub enabled: bool,
+	/// Period of the scheduled sweeps, in seconds.
+	#[serde(default = "default_interval_secs")]
+	pub interval_secs: u64,
+	/// Ethereum address of the admin key. No sweep is done if empty.
+	#[serde(default = "default_admin_address")]
+	pub admin_address: String,
+	/// Signature by the admin key of the cold storage addresses and the thresholds, as
+	/// printed by `bridge-cli sweep sign-policy`.
+	#[serde(default = "default_policy_signature")]
+	pub policy_signature: String,
+	/// Cold storage address of the MOVE tokens of the Ethereum relayer account. The Ethereum
+	/// account isn't swept if empty.
+	#[serde(default)]
+	pub eth_cold_address: String,
+	/// Cold storage address of the coins of the Movement relayer account. The Movement account
+	/// isn't swept if empty.
+	#[serde(default)]
+	pub movement_cold_address: String,
+	/// MOVE tokens kept on the Ethereum relayer account, in the smallest unit.
+	#[serde(default)]
+	pub eth_threshold: u64,
+	/// Coins kept on the Movement relayer account for the gas, in octas.
+	#[serde(default)]
+	pub movement_threshold: u64,
+}
+
+env_default!(default_enabled, "BRIDGE_SWEEP_ENABLED", bool, false);
+env_default!(default_interval_secs, "BRIDGE_SWEEP_INTERVAL_SECS", u64, 24 * 60 * 60);
+env_default!(default_admin_address, "BRIDGE_SWEEP_ADMIN_ADDRESS", String, String::new());
+env_default!(default_policy_signature, "BRIDGE_SWEEP_POLICY_SIGNATURE", String, String::new());
+
+impl Default for SweepConfig {
+	fn default() -> Self {
+		SweepConfig {
+			enabled: default_enabled(),
+			interval_secs: default_interval_secs(),
+			admin_address: default_admin_address(),
+			policy_signature: default_policy_signature(),
+			eth_cold_address: String::new(),
+			movement_cold_address: String::new(),
+			eth_threshold: 0,
+			movement_threshold: 0,
+		}
+	}
+}
@@ -0,0 +1,47 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_OTLP_ENDPOINT: &str = "http://localhost:4317";
+const DEFAULT_OTLP_SERVICE_NAME: &str = "movement-bridge-relayer";
+
+/// Export of the tracing spans of the relayer to an OpenTelemetry collector, over OTLP gRPC.
+/// The spans of a transfer carry its id, so its whole processing can be followed in the
+/// collector.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TelemetryConfig {
+	#[serde(default = "default_otlp_enabled")]
+	pub otlp_enabled: bool,
+	#[serde(default = "default_otlp_endpoint")]
+	pub otlp_endpoint: String,
+	/// Name of the relayer in the collector.
+	#[serde(default = "default_otlp_service_name")]
+	pub service_name: String,
+}
+
+env_default!(default_otlp_enabled, "BRIDGE_OTLP_ENABLED", bool, false);
+
+env_default!(
+	default_otlp_endpoint,
+	"BRIDGE_OTLP_ENDPOINT",
+	String,
+	DEFAULT_OTLP_ENDPOINT.to_string()
+);
+
+env_default!(
+	default_otlp_service_name,
+	"BRIDGE_OTLP_SERVICE_NAME",
+	String,
+	DEFAULT_OTLP_SERVICE_NAME.to_string()
+);
+
+impl Default for TelemetryConfig {
+	fn default() -> Self {
+		TelemetryConfig {
+			otlp_enabled: default_otlp_enabled(),
+			otlp_endpoint: default_otlp_endpoint(),
+			service_name: default_otlp_service_name(),
+		}
+	}
+}
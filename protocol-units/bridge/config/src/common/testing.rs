@@ -1,8 +1,10 @@
 use alloy::signers::local::PrivateKeySigner;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct TestingConfig {
 	#[serde(default = "Vec::new")]
 	pub eth_well_known_account_private_keys: Vec<String>,
@@ -0,0 +1,115 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_CHECK_INTERVAL_SECS: u64 = 30;
+const DEFAULT_GRACE_SECS: u64 = 60;
+const DEFAULT_MAX_ATTEMPTS: u32 = 5;
+const DEFAULT_MIN_LOCK_MARGIN_SECS: u64 = 60 * 60;
+const DEFAULT_TIME_LOCK_UNIT: &str = "timestamp";
+const DEFAULT_ETH_BLOCK_TIME_MS: u64 = 12_000;
+const DEFAULT_MVT_BLOCK_TIME_MS: u64 = 500;
+
+/// Refund of the initiators and abort of the locks once their time lock is expired.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TimelockConfig {
+	/// Submit the refunds and the aborts of the expired transfers.
+	#[serde(default = "default_timelock_watcher_enabled")]
+	pub enabled: bool,
+	/// Interval between two checks of the time locks.
+	#[serde(default = "default_timelock_check_interval_secs")]
+	pub check_interval_secs: u64,
+	/// Delay after the expiry before submitting, the chain clock may be late on the relayer.
+	#[serde(default = "default_timelock_grace_secs")]
+	pub grace_secs: u64,
+	/// Number of submissions of a refund or an abort before giving up on it.
+	#[serde(default = "default_timelock_max_attempts")]
+	pub max_attempts: u32,
+	/// Time left before the expiry of the initiator time lock under which a transfer isn't
+	/// locked on the counterparty chain anymore, its initiator is refunded instead. The
+	/// recipient must have the time to complete the lock before the initiator can be refunded.
+	#[serde(default = "default_timelock_min_lock_margin_secs")]
+	pub min_lock_margin_secs: u64,
+	/// Unit of the time locks of the Ethereum contracts, `timestamp` or `block_height`.
+	#[serde(default = "default_eth_time_lock_unit")]
+	pub eth_unit: String,
+	/// Unit of the time locks of the Move modules, `timestamp` or `block_height`.
+	#[serde(default = "default_mvt_time_lock_unit")]
+	pub mvt_unit: String,
+	/// Average time between two Ethereum blocks, to convert the heights to durations.
+	#[serde(default = "default_eth_block_time_ms")]
+	pub eth_block_time_ms: u64,
+	/// Average time between two Movement blocks, to convert the heights to durations.
+	#[serde(default = "default_mvt_block_time_ms")]
+	pub mvt_block_time_ms: u64,
+}
+
+env_default!(default_timelock_watcher_enabled, "BRIDGE_TIMELOCK_WATCHER_ENABLED", bool, true);
+
+env_default!(
+	default_timelock_check_interval_secs,
+	"BRIDGE_TIMELOCK_CHECK_INTERVAL_SECS",
+	u64,
+	DEFAULT_CHECK_INTERVAL_SECS
+);
+
+env_default!(default_timelock_grace_secs, "BRIDGE_TIMELOCK_GRACE_SECS", u64, DEFAULT_GRACE_SECS);
+
+env_default!(
+	default_timelock_max_attempts,
+	"BRIDGE_TIMELOCK_MAX_ATTEMPTS",
+	u32,
+	DEFAULT_MAX_ATTEMPTS
+);
+
+env_default!(
+	default_timelock_min_lock_margin_secs,
+	"BRIDGE_TIMELOCK_MIN_LOCK_MARGIN_SECS",
+	u64,
+	DEFAULT_MIN_LOCK_MARGIN_SECS
+);
+
+env_default!(
+	default_eth_time_lock_unit,
+	"BRIDGE_ETH_TIMELOCK_UNIT",
+	String,
+	DEFAULT_TIME_LOCK_UNIT.to_string()
+);
+
+env_default!(
+	default_mvt_time_lock_unit,
+	"BRIDGE_MVT_TIMELOCK_UNIT",
+	String,
+	DEFAULT_TIME_LOCK_UNIT.to_string()
+);
+
+env_default!(
+	default_eth_block_time_ms,
+	"BRIDGE_ETH_BLOCK_TIME_MS",
+	u64,
+	DEFAULT_ETH_BLOCK_TIME_MS
+);
+
+env_default!(
+	default_mvt_block_time_ms,
+	"BRIDGE_MVT_BLOCK_TIME_MS",
+	u64,
+	DEFAULT_MVT_BLOCK_TIME_MS
+);
+
+impl Default for TimelockConfig {
+	fn default() -> Self {
+		TimelockConfig {
+			enabled: default_timelock_watcher_enabled(),
+			check_interval_secs: default_timelock_check_interval_secs(),
+			grace_secs: default_timelock_grace_secs(),
+			max_attempts: default_timelock_max_attempts(),
+			min_lock_margin_secs: default_timelock_min_lock_margin_secs(),
+			eth_unit: default_eth_time_lock_unit(),
+			mvt_unit: default_mvt_time_lock_unit(),
+			eth_block_time_ms: default_eth_block_time_ms(),
+			mvt_block_time_ms: default_mvt_block_time_ms(),
+		}
+	}
+}
@@ -0,0 +1,37 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// ERC-20 tokens bridged from Ethereum, each with its own initiator contract.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TokensConfig {
+	#[serde(default)]
+	pub erc20: Vec<Erc20TokenConfig>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Erc20TokenConfig {
+	/// Symbol of the token (ex: `USDC`).
+	pub symbol: String,
+	/// Address of the token contract.
+	pub token_contract: String,
+	/// Address of the initiator contract bridging the token.
+	pub initiator_contract: String,
+	/// Decimals of the token, read from the token contract if not set.
+	#[serde(default)]
+	pub decimals: Option<u8>,
+}
+
+impl Default for TokensConfig {
+	fn default() -> Self {
+		TokensConfig { erc20: Vec::new() }
+	}
+}
+
+impl TokensConfig {
+	/// Config of the ERC-20 token with the symbol.
+	pub fn erc20_of(&self, symbol: &str) -> Option<&Erc20TokenConfig> {
+		self.erc20.iter().find(|token| token.symbol == symbol)
+	}
+}
@@ -0,0 +1,42 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_TRANSFER_STORE_BACKEND: &str = "memory";
+const DEFAULT_TRANSFER_STORE_POOL_SIZE: u32 = 8;
+
+/// Store of the last state of the transfers, reloaded when the relayer restarts. `memory`
+/// keeps them in the relayer, `postgres` in the indexer database so they survive restarts
+/// and are shared by the relayer instances.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TransferStoreConfig {
+	#[serde(default = "default_transfer_store_backend")]
+	pub backend: String,
+	/// Maximum connections to the database of the postgres backend.
+	#[serde(default = "default_transfer_store_pool_size")]
+	pub pool_size: u32,
+}
+
+env_default!(
+	default_transfer_store_backend,
+	"BRIDGE_TRANSFER_STORE_BACKEND",
+	String,
+	DEFAULT_TRANSFER_STORE_BACKEND.to_string()
+);
+
+env_default!(
+	default_transfer_store_pool_size,
+	"BRIDGE_TRANSFER_STORE_POOL_SIZE",
+	u32,
+	DEFAULT_TRANSFER_STORE_POOL_SIZE
+);
+
+impl Default for TransferStoreConfig {
+	fn default() -> Self {
+		TransferStoreConfig {
+			backend: default_transfer_store_backend(),
+			pool_size: default_transfer_store_pool_size(),
+		}
+	}
+}
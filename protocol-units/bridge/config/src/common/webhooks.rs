@@ -0,0 +1,30 @@
+use godfig::env_default;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_WEBHOOK_HISTORY_SIZE: u64 = 10_000;
+
+/// Notifications of the changes of state of the transfers.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct WebhooksConfig {
+	/// Url receiving a JSON POST on each change of state of a transfer.
+	#[serde(default)]
+	pub transfer_state_url: Option<String>,
+	/// Number of the last notifications kept for replay.
+	#[serde(default = "default_webhook_history_size")]
+	pub history_size: u64,
+}
+
+env_default!(
+	default_webhook_history_size,
+	"BRIDGE_WEBHOOK_HISTORY_SIZE",
+	u64,
+	DEFAULT_WEBHOOK_HISTORY_SIZE
+);
+
+impl Default for WebhooksConfig {
+	fn default() -> Self {
+		WebhooksConfig { transfer_state_url: None, history_size: default_webhook_history_size() }
+	}
+}
@@ -1,30 +1,217 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 pub mod common;
 
 pub const BRIDGE_CONF_FOLDER: &str = "bridge";
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Config {
 	/// The ETH connection configuration.
 	/// This is mandatory for all possible operations.
 	#[serde(default)]
 	pub eth: common::eth::EthConfig,
 
+	/// ERC-20 tokens bridged besides the asset of the token pair.
+	#[serde(default)]
+	pub tokens: common::tokens::TokensConfig,
+
 	#[serde(default)]
 	pub movement: common::movement::MovementConfig,
 
 	/// Optional testing config
 	#[serde(default)]
 	pub testing: common::testing::TestingConfig,
+
+	/// Overrides of the retry classification of relayer errors.
+	#[serde(default)]
+	pub retry: common::retry::RetryConfig,
+
+	/// Limits checked on prospective transfers.
+	#[serde(default)]
+	pub guards: common::guards::GuardsConfig,
+
+	/// Split of the accrued fees among recipients.
+	#[serde(default)]
+	pub fees: common::fees::FeesConfig,
+
+	/// Operating mode of the relayer loop.
+	#[serde(default)]
+	pub relayer: common::relayer::RelayerConfig,
+
+	/// Scheduled canary transfers.
+	#[serde(default)]
+	pub canary: common::canary::CanaryConfig,
+
+	/// Split of the large transfers.
+	#[serde(default)]
+	pub split: common::split::SplitConfig,
+
+	/// Cache of the idempotent RPC reads.
+	#[serde(default)]
+	pub rpc_cache: common::rpc_cache::RpcCacheConfig,
+
+	/// Separate signing process.
+	#[serde(default)]
+	pub signer: common::signer::SignerConfig,
+
+	/// Notifications of the transfer state changes.
+	#[serde(default)]
+	pub webhooks: common::webhooks::WebhooksConfig,
+
+	/// Hooks run on the operator alerts.
+	#[serde(default)]
+	pub runbook: common::runbook::RunbookConfig,
+
+	/// Labels of the addresses.
+	#[serde(default)]
+	pub labels: common::labels::LabelsConfig,
+
+	/// Cost estimation of the refunds.
+	#[serde(default)]
+	pub refund: common::refund::RefundConfig,
+
+	/// Format of the preimages of the hash locks.
+	#[serde(default)]
+	pub pre_image: common::pre_image::PreImageConfig,
+
+	/// Block explorer links and tagging of the transactions.
+	#[serde(default)]
+	pub explorer: common::explorer::ExplorerConfig,
+
+	/// Fees of the Ethereum transactions.
+	#[serde(default)]
+	pub gas: common::gas::GasConfig,
+
+	/// Checks of the endpoints when the relayer starts.
+	#[serde(default)]
+	pub startup: common::startup::StartupConfig,
+
+	/// Notifications of the end users about their transfers.
+	#[serde(default)]
+	pub notifications: common::notifications::NotificationsConfig,
+
+	/// Refunds and aborts of the expired transfers.
+	#[serde(default)]
+	pub timelock: common::timelock::TimelockConfig,
+
+	/// Processing of a fixed range of blocks, without submissions.
+	#[serde(default)]
+	pub forensics: common::forensics::ForensicsConfig,
+
+	/// Storage of the preimages generated by the relayer.
+	#[serde(default)]
+	pub secrets: common::secrets::SecretsConfig,
+
+	/// Websocket streams of the transfer updates.
+	#[serde(default)]
+	pub live_updates: common::live_updates::LiveUpdatesConfig,
+
+	/// Completions of the transfers with the secrets posted by the end users.
+	#[serde(default)]
+	pub claims: common::claims::ClaimsConfig,
+
+	/// Listener of the Prometheus metrics.
+	#[serde(default)]
+	pub metrics: common::metrics::MetricsConfig,
+
+	/// Store of the last state of the transfers.
+	#[serde(default)]
+	pub transfer_store: common::transfer_store::TransferStoreConfig,
+
+	/// Election of the leader among the relayer instances.
+	#[serde(default)]
+	pub coordination: common::coordination::CoordinationConfig,
+
+	/// Authentication of the operators on the admin API.
+	#[serde(default)]
+	pub admin: common::admin::AdminConfig,
+
+	/// Export of the tracing spans over OTLP.
+	#[serde(default)]
+	pub telemetry: common::telemetry::TelemetryConfig,
+
+	/// Revenue reports of the liquidity operators.
+	#[serde(default)]
+	pub reports: common::reports::ReportsConfig,
+
+	/// Caps on the amounts initiated by address and overall.
+	#[serde(default)]
+	pub rate_limit: common::rate_limit::RateLimitConfig,
+
+	/// Anomalies pausing the locks of the relayer.
+	#[serde(default)]
+	pub circuit_breaker: common::circuit_breaker::CircuitBreakerConfig,
+
+	/// Reconciliation of the amounts locked on both chains.
+	#[serde(default)]
+	pub invariants: common::invariants::InvariantsConfig,
+
+	/// Advisory alerts on the patterns of the transfers.
+	#[serde(default)]
+	pub anomalies: common::anomalies::AnomaliesConfig,
+	/// Failover drills of the test deployments.
+	#[serde(default)]
+	pub drills: common::drills::DrillsConfig,
+	/// Revocation of the orphaned ERC-20 allowances of the relayer wallet.
+	#[serde(default)]
+	pub allowance_gc: common::allowance_gc::AllowanceGcConfig,
+	/// Broadcast of the critical completions through a second Ethereum RPC provider.
+	#[serde(default)]
+	pub redundancy: common::redundancy::RedundancyConfig,
+	/// Accounting of the fees paid by the relayer on each transfer.
+	#[serde(default)]
+	pub fee_tracking: common::fee_tracking::FeeTrackingConfig,
+	/// Sweep of the accumulated fees to cold storage.
+	#[serde(default)]
+	pub sweep: common::sweep::SweepConfig,
 }
 
 impl Default for Config {
 	fn default() -> Self {
 		Config {
 			eth: common::eth::EthConfig::default(),
+			tokens: common::tokens::TokensConfig::default(),
 			movement: common::movement::MovementConfig::default(),
 			testing: common::testing::TestingConfig::default(),
+			retry: common::retry::RetryConfig::default(),
+			guards: common::guards::GuardsConfig::default(),
+			fees: common::fees::FeesConfig::default(),
+			relayer: common::relayer::RelayerConfig::default(),
+			canary: common::canary::CanaryConfig::default(),
+			split: common::split::SplitConfig::default(),
+			rpc_cache: common::rpc_cache::RpcCacheConfig::default(),
+			signer: common::signer::SignerConfig::default(),
+			webhooks: common::webhooks::WebhooksConfig::default(),
+			runbook: common::runbook::RunbookConfig::default(),
+			labels: common::labels::LabelsConfig::default(),
+			refund: common::refund::RefundConfig::default(),
+			pre_image: common::pre_image::PreImageConfig::default(),
+			explorer: common::explorer::ExplorerConfig::default(),
+			gas: common::gas::GasConfig::default(),
+			startup: common::startup::StartupConfig::default(),
+			notifications: common::notifications::NotificationsConfig::default(),
+			timelock: common::timelock::TimelockConfig::default(),
+			forensics: common::forensics::ForensicsConfig::default(),
+			secrets: common::secrets::SecretsConfig::default(),
+			live_updates: common::live_updates::LiveUpdatesConfig::default(),
+			claims: common::claims::ClaimsConfig::default(),
+			metrics: common::metrics::MetricsConfig::default(),
+			transfer_store: common::transfer_store::TransferStoreConfig::default(),
+			coordination: common::coordination::CoordinationConfig::default(),
+			admin: common::admin::AdminConfig::default(),
+			telemetry: common::telemetry::TelemetryConfig::default(),
+			reports: common::reports::ReportsConfig::default(),
+			rate_limit: common::rate_limit::RateLimitConfig::default(),
+			circuit_breaker: common::circuit_breaker::CircuitBreakerConfig::default(),
+			invariants: common::invariants::InvariantsConfig::default(),
+			anomalies: common::anomalies::AnomaliesConfig::default(),
+			drills: common::drills::DrillsConfig::default(),
+			allowance_gc: common::allowance_gc::AllowanceGcConfig::default(),
+			redundancy: common::redundancy::RedundancyConfig::default(),
+			fee_tracking: common::fee_tracking::FeeTrackingConfig::default(),
+			sweep: common::sweep::SweepConfig::default(),
 		}
 	}
 }
@@ -39,8 +226,46 @@ impl Config {
 	pub fn suzuka() -> Self {
 		Config {
 			eth: common::eth::EthConfig::default(),
+			tokens: common::tokens::TokensConfig::default(),
 			movement: common::movement::MovementConfig::for_test(),
 			testing: common::testing::TestingConfig::default(),
+			retry: common::retry::RetryConfig::default(),
+			guards: common::guards::GuardsConfig::default(),
+			fees: common::fees::FeesConfig::default(),
+			relayer: common::relayer::RelayerConfig::default(),
+			canary: common::canary::CanaryConfig::default(),
+			split: common::split::SplitConfig::default(),
+			rpc_cache: common::rpc_cache::RpcCacheConfig::default(),
+			signer: common::signer::SignerConfig::default(),
+			webhooks: common::webhooks::WebhooksConfig::default(),
+			runbook: common::runbook::RunbookConfig::default(),
+			labels: common::labels::LabelsConfig::default(),
+			refund: common::refund::RefundConfig::default(),
+			pre_image: common::pre_image::PreImageConfig::default(),
+			explorer: common::explorer::ExplorerConfig::default(),
+			gas: common::gas::GasConfig::default(),
+			startup: common::startup::StartupConfig::default(),
+			notifications: common::notifications::NotificationsConfig::default(),
+			timelock: common::timelock::TimelockConfig::default(),
+			forensics: common::forensics::ForensicsConfig::default(),
+			secrets: common::secrets::SecretsConfig::default(),
+			live_updates: common::live_updates::LiveUpdatesConfig::default(),
+			claims: common::claims::ClaimsConfig::default(),
+			metrics: common::metrics::MetricsConfig::default(),
+			transfer_store: common::transfer_store::TransferStoreConfig::default(),
+			coordination: common::coordination::CoordinationConfig::default(),
+			admin: common::admin::AdminConfig::default(),
+			telemetry: common::telemetry::TelemetryConfig::default(),
+			reports: common::reports::ReportsConfig::default(),
+			rate_limit: common::rate_limit::RateLimitConfig::default(),
+			circuit_breaker: common::circuit_breaker::CircuitBreakerConfig::default(),
+			invariants: common::invariants::InvariantsConfig::default(),
+			anomalies: common::anomalies::AnomaliesConfig::default(),
+			drills: common::drills::DrillsConfig::default(),
+			allowance_gc: common::allowance_gc::AllowanceGcConfig::default(),
+			redundancy: common::redundancy::RedundancyConfig::default(),
+			fee_tracking: common::fee_tracking::FeeTrackingConfig::default(),
+			sweep: common::sweep::SweepConfig::default(),
 		}
 	}
 }
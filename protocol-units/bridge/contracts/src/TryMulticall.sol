@@ -0,0 +1,20 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.22;
+
+/// @notice Batch of calls to the contract in one transaction where a reverted call doesn't
+/// revert the others, so the relayer can tell which transfers of a batch went through.
+abstract contract TryMulticall {
+    event BatchCallFailed(uint256 index, bytes reason);
+
+    function tryMulticall(bytes[] calldata data) external returns (bool[] memory successes) {
+        successes = new bool[](data.length);
+        for (uint256 i = 0; i < data.length; i++) {
+            // Delegated to the contract itself, the sender of the calls stays the caller.
+            (bool success, bytes memory reason) = address(this).delegatecall(data[i]);
+            successes[i] = success;
+            if (!success) {
+                emit BatchCallFailed(i, reason);
+            }
+        }
+    }
+}
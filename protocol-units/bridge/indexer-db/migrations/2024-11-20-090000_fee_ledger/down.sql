@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE fee_distributions;
+DROP TABLE fee_accruals;
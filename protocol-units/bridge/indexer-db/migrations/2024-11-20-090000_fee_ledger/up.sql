@@ -0,0 +1,15 @@
+CREATE TABLE fee_accruals (
+    id SERIAL PRIMARY KEY,
+    bridge_transfer_id VARCHAR(64) NOT NULL,
+    amount NUMERIC NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE fee_distributions (
+    id SERIAL PRIMARY KEY,
+    recipient_name VARCHAR(64) NOT NULL,
+    recipient VARCHAR(64) NOT NULL,    -- Eth address of the recipient
+    amount NUMERIC NOT NULL,
+    tx_hash VARCHAR(64) NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,14 @@
+-- Chain submissions are recorded before being sent, so a restart can tell
+-- the ones never sent from the ones sent but not confirmed.
+CREATE TABLE submission_outbox (
+    id SERIAL PRIMARY KEY,
+    chain VARCHAR(8) NOT NULL,
+    bridge_transfer_id VARCHAR(64) NOT NULL,
+    action_kind VARCHAR(32) NOT NULL,
+    status VARCHAR(16) NOT NULL,      -- planned, sent, confirmed or failed
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX submission_outbox_pending ON submission_outbox (status)
+    WHERE status IN ('planned', 'sent');
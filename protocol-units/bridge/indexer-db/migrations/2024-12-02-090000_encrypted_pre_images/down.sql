@@ -0,0 +1,3 @@
+-- Fails while encrypted preimages are stored.
+ALTER TABLE counter_party_completed_events ALTER COLUMN pre_image TYPE VARCHAR(64);
+ALTER TABLE wait_and_complete_initiators ALTER COLUMN pre_image TYPE VARCHAR(64);
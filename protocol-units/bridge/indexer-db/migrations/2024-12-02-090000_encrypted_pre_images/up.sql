@@ -0,0 +1,3 @@
+-- Encrypted preimages are longer than their hex encoding.
+ALTER TABLE wait_and_complete_initiators ALTER COLUMN pre_image TYPE TEXT;
+ALTER TABLE counter_party_completed_events ALTER COLUMN pre_image TYPE TEXT;
@@ -0,0 +1,17 @@
+-- Last state of each transfer as seen by the relayer, so another instance can take over
+-- the transfers in flight.
+CREATE TABLE transfer_states (
+    bridge_transfer_id VARCHAR(64) PRIMARY KEY,
+    init_chain VARCHAR(8) NOT NULL,
+    state VARCHAR(32),                -- missing until the first change of state
+    sequence BIGINT NOT NULL,         -- of the last change of state
+    initiator VARCHAR NOT NULL,
+    recipient VARCHAR NOT NULL,
+    hash_lock VARCHAR(64) NOT NULL,
+    time_lock BIGINT NOT NULL,
+    amount NUMERIC NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX transfer_states_in_flight ON transfer_states (state)
+    WHERE state IS DISTINCT FROM 'Done';
@@ -0,0 +1,8 @@
+CREATE TABLE gas_costs (
+    id SERIAL PRIMARY KEY,
+    chain VARCHAR(16) NOT NULL,        -- ethereum or movement
+    amount NUMERIC NOT NULL,           -- In wei on Ethereum, in octas on Movement
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX gas_costs_created_at ON gas_costs (created_at);
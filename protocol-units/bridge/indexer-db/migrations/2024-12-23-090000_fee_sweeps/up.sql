@@ -0,0 +1,8 @@
+CREATE TABLE fee_sweeps (
+    id SERIAL PRIMARY KEY,
+    chain VARCHAR(16) NOT NULL,        -- ethereum or movement
+    destination VARCHAR(66) NOT NULL,  -- Cold storage address
+    amount NUMERIC NOT NULL,           -- MOVE token units on Ethereum, octas on Movement
+    tx_hash VARCHAR(66) NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
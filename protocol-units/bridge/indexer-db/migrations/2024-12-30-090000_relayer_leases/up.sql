@@ -0,0 +1,6 @@
+-- Leases of the relayer instances, the holder of the leader lease submits the transactions.
+CREATE TABLE relayer_leases (
+    name VARCHAR(32) PRIMARY KEY,
+    holder VARCHAR(64) NOT NULL,    -- instance id
+    expires_at BIGINT NOT NULL      -- unix timestamp, in seconds
+);
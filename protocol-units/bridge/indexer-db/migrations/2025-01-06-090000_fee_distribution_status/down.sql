@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX fee_accruals_bridge_transfer_id;
+DELETE FROM fee_distributions WHERE tx_hash IS NULL;
+ALTER TABLE fee_distributions DROP COLUMN status;
+ALTER TABLE fee_distributions ALTER COLUMN tx_hash SET NOT NULL;
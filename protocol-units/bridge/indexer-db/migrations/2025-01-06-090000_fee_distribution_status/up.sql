@@ -0,0 +1,10 @@
+-- A distribution is recorded as pending before its transaction is sent, and its transaction
+-- hash is set once sent. A pending distribution left by a crash is counted as distributed,
+-- so it's never sent twice, and is reconciled by hand.
+ALTER TABLE fee_distributions ALTER COLUMN tx_hash DROP NOT NULL;
+ALTER TABLE fee_distributions ADD COLUMN status VARCHAR(16) NOT NULL DEFAULT 'sent';
+
+-- The fee of a transfer is accrued once, even when its events are replayed.
+DELETE FROM fee_accruals a USING fee_accruals b
+    WHERE a.id > b.id AND a.bridge_transfer_id = b.bridge_transfer_id;
+CREATE UNIQUE INDEX fee_accruals_bridge_transfer_id ON fee_accruals (bridge_transfer_id);
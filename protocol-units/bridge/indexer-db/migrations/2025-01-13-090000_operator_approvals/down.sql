@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE approval_audit_entries;
+DROP TABLE pending_approvals;
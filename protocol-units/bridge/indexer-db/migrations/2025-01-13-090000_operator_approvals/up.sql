@@ -0,0 +1,17 @@
+-- Transfers held until an operator approves or denies them, restored on restart.
+CREATE TABLE pending_approvals (
+    bridge_transfer_id VARCHAR(64) PRIMARY KEY,
+    init_chain VARCHAR(16) NOT NULL,
+    amount NUMERIC NOT NULL,
+    queued_at BIGINT NOT NULL       -- unix timestamp, in seconds
+);
+
+-- Audit trail of the approvals, never truncated.
+CREATE TABLE approval_audit_entries (
+    id SERIAL PRIMARY KEY,
+    bridge_transfer_id VARCHAR(64) NOT NULL,
+    action VARCHAR(16) NOT NULL,
+    operator VARCHAR(64),           -- none for the relayer
+    amount NUMERIC NOT NULL,
+    at BIGINT NOT NULL              -- unix timestamp, in seconds
+);
@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE submission_outbox DROP COLUMN tx_hash;
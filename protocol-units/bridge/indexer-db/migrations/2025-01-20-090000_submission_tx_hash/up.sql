@@ -0,0 +1,2 @@
+-- Hash of the transaction of a submission, set once the chain client has sent it.
+ALTER TABLE submission_outbox ADD COLUMN tx_hash VARCHAR(66);
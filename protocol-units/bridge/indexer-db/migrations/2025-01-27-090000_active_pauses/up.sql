@@ -0,0 +1,8 @@
+-- Pauses of the bridge set by the operators, restored on restart.
+CREATE TABLE active_pauses (
+    token VARCHAR(32) NOT NULL,     -- empty for all the tokens
+    direction VARCHAR(16) NOT NULL, -- empty for all the directions
+    operator VARCHAR(64) NOT NULL,
+    paused_at BIGINT NOT NULL,      -- unix timestamp, in seconds
+    PRIMARY KEY (token, direction)
+);
@@ -0,0 +1,3 @@
+-- Secret revealed on the counterparty chain, so a restarted relayer completes the initiator
+-- side of the transfers.
+ALTER TABLE transfer_states ADD COLUMN pre_image VARCHAR(64);
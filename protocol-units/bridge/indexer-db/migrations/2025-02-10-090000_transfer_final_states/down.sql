@@ -0,0 +1,7 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX transfer_states_in_flight;
+CREATE INDEX transfer_states_in_flight ON transfer_states (state)
+    WHERE state IS DISTINCT FROM 'Done';
+
+UPDATE transfer_states SET state = 'Done' WHERE state IN ('Completed', 'Refunded', 'Aborted');
+UPDATE transfer_states SET state = 'Aborted' WHERE state = 'LockAborted';
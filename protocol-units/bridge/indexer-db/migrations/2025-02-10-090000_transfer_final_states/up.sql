@@ -0,0 +1,10 @@
+-- The transfers end completed, refunded or aborted instead of done, and the aborted locks
+-- waiting for the refund of their initiator are renamed. The transfers done with their secret
+-- stored were completed, the others are kept as aborted.
+UPDATE transfer_states SET state = 'LockAborted' WHERE state = 'Aborted';
+UPDATE transfer_states SET state = 'Completed' WHERE state = 'Done' AND pre_image IS NOT NULL;
+UPDATE transfer_states SET state = 'Aborted' WHERE state = 'Done';
+
+DROP INDEX transfer_states_in_flight;
+CREATE INDEX transfer_states_in_flight ON transfer_states (state)
+    WHERE state IS NULL OR state NOT IN ('Completed', 'Refunded', 'Aborted');
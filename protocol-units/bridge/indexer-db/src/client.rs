@@ -1,14 +1,25 @@
+use crate::encryption::{is_encrypted, FieldCipher};
 use crate::migrations::run_migrations;
 use crate::models::*;
 use crate::schema::*;
+use bigdecimal::BigDecimal;
 use bridge_util::chains::bridge_contracts::BridgeContractEvent;
-use bridge_util::types::BridgeTransferId;
-use bridge_util::TransferActionType;
-use diesel::pg::PgConnection;
+use bridge_util::types::{Amount, BridgeTransferId};
+use bridge_util::{TransferAction, TransferActionType};
+use diesel::connection::{AnsiTransactionManager, TransactionManager};
+use diesel::dsl::{not, sum};
+use diesel::pg::{Pg, PgConnection};
 use diesel::prelude::*;
+use diesel::sql_types::Bool;
+use std::collections::HashSet;
+
+// Fields encrypted when the client has a key.
+const WAIT_AND_COMPLETE_PRE_IMAGE: &str = "wait_and_complete_initiators.pre_image";
+const COUNTER_PARTY_COMPLETED_PRE_IMAGE: &str = "counter_party_completed_events.pre_image";
 
 pub struct Client {
 	conn: PgConnection,
+	cipher: Option<FieldCipher>,
 }
 
 pub struct BridgeEventPackage {
@@ -23,15 +34,76 @@ pub struct BridgeEventPackage {
 impl Client {
 	/// Creates a new client with the given connection.
 	pub fn new(conn: PgConnection) -> Self {
-		Self { conn }
+		Self { conn, cipher: None }
+	}
+
+	/// Encrypts the sensitive fields written with the cipher.
+	pub fn with_cipher(mut self, cipher: FieldCipher) -> Self {
+		self.cipher = Some(cipher);
+		self
 	}
 
-	/// Gets the client from an environment variable containing the postgresql url.
+	/// Gets the client from an environment variable containing the postgresql url,
+	/// with the encryption key of the sensitive fields if configured.
 	pub fn from_env() -> Result<Self, anyhow::Error> {
 		let url = std::env::var("BRIDGE_INDEXER_DATABASE_URL")?;
 		let conn = PgConnection::establish(&url)
 			.map_err(|e| anyhow::anyhow!("Failed to connect to postgresql instance: {}", e))?;
-		Ok(Self::new(conn))
+		let client = Self::new(conn);
+		Ok(match FieldCipher::from_env()? {
+			Some(cipher) => client.with_cipher(cipher),
+			None => client,
+		})
+	}
+
+	/// Whether the sensitive fields are encrypted before being written.
+	pub fn encrypts_sensitive_fields(&self) -> bool {
+		self.cipher.is_some()
+	}
+
+	// Encodes a sensitive field of the record, encrypted when the client has a key.
+	fn encode_sensitive(&self, record: &str, field: &str, value: &[u8]) -> String {
+		match &self.cipher {
+			Some(cipher) => cipher.encrypt(record, field, value),
+			None => hex::encode(value),
+		}
+	}
+
+	// Decodes a sensitive field of the record, stored encrypted or in clear.
+	fn decode_sensitive(
+		&self,
+		record: &str,
+		field: &str,
+		stored: &str,
+	) -> Result<Vec<u8>, anyhow::Error> {
+		if !is_encrypted(stored) {
+			return Ok(hex::decode(stored)?);
+		}
+		self.cipher
+			.as_ref()
+			.ok_or_else(|| anyhow::anyhow!("No key to decrypt the field {field} of {record}"))?
+			.decrypt(record, field, stored)
+	}
+
+	/// Gets the preimage of a counterparty completed event.
+	pub fn counter_party_pre_image(
+		&self,
+		event: &CounterPartyCompletedEvent,
+	) -> Result<Vec<u8>, anyhow::Error> {
+		self.decode_sensitive(
+			&event.bridge_transfer_id,
+			COUNTER_PARTY_COMPLETED_PRE_IMAGE,
+			&event.pre_image,
+		)
+	}
+
+	/// Gets the preimage of a wait and complete initiator action.
+	pub fn wait_and_complete_pre_image(
+		&self,
+		action: &WaitAndCompleteInitiator,
+	) -> Result<Vec<u8>, anyhow::Error> {
+		// The action doesn't reference its transfer, the field is bound to the table only.
+		self.decode_sensitive("", WAIT_AND_COMPLETE_PRE_IMAGE, &action.pre_image)
 	}
 
 	/// Run migrations on the database.
@@ -40,6 +112,26 @@ impl Client {
 		Ok(())
 	}
 
+	// Run the writes in a single transaction, so they are committed,
+	// and flushed to disk, once.
+	fn in_transaction<T>(
+		&mut self,
+		writes: impl FnOnce(&mut Self) -> Result<T, diesel::result::Error>,
+	) -> Result<T, diesel::result::Error> {
+		AnsiTransactionManager::begin_transaction(&mut self.conn)?;
+		match writes(self) {
+			Ok(result) => {
+				AnsiTransactionManager::commit_transaction(&mut self.conn)?;
+				Ok(result)
+			}
+			Err(err) => {
+				// The error of the writes is the one worth returning.
+				let _ = AnsiTransactionManager::rollback_transaction(&mut self.conn);
+				Err(err)
+			}
+		}
+	}
+
 	/// Inserts a new transfer action into the database.
 	pub fn insert_transfer_action(
 		&mut self,
@@ -65,10 +157,12 @@ impl Client {
 					.execute(&mut self.conn)?;
 			}
 			TransferActionType::WaitAndCompleteInitiator(wait_time_secs, hash_lock_pre_image) => {
+				let pre_image =
+					self.encode_sensitive("", WAIT_AND_COMPLETE_PRE_IMAGE, &hash_lock_pre_image.0);
 				diesel::insert_into(wait_and_complete_initiators::table)
 					.values(NewWaitAndCompleteInitiator {
 						wait_time_secs: wait_time_secs as i64,
-						pre_image: hex::encode(hash_lock_pre_image.0.to_vec()),
+						pre_image,
 						created_at: chrono::Utc::now().naive_utc(),
 					})
 					.execute(&mut self.conn)?;
@@ -76,6 +170,9 @@ impl Client {
 			TransferActionType::RefundInitiator => {
 				// do nothing
 			}
+			TransferActionType::AbortCounterparty => {
+				// do nothing
+			}
 			TransferActionType::TransferDone => {
 				// do nothing
 			}
@@ -145,10 +242,16 @@ impl Client {
 					.execute(&mut self.conn)?;
 			}
 			BridgeContractEvent::CounterPartyCompleted(bridge_transfer_id, hash_lock_pre_image) => {
+				let bridge_transfer_id = hex::encode(bridge_transfer_id.0.to_vec());
+				let pre_image = self.encode_sensitive(
+					&bridge_transfer_id,
+					COUNTER_PARTY_COMPLETED_PRE_IMAGE,
+					&hash_lock_pre_image.0,
+				);
 				diesel::insert_into(counter_party_completed_events::table)
 					.values(NewCounterPartyCompletedEvent {
-						bridge_transfer_id: hex::encode(bridge_transfer_id.0.to_vec()),
-						pre_image: hex::encode(hash_lock_pre_image.0.to_vec()),
+						bridge_transfer_id,
+						pre_image,
 						created_at: chrono::Utc::now().naive_utc(),
 					})
 					.execute(&mut self.conn)?;
@@ -174,6 +277,22 @@ impl Client {
 		Ok(())
 	}
 
+	/// Inserts the bridge contract events in a single transaction,
+	/// none is inserted if one fails.
+	pub fn insert_bridge_contract_events<A>(
+		&mut self,
+		contract_events: Vec<BridgeContractEvent<A>>,
+	) -> Result<(), diesel::result::Error>
+	where
+		A: Into<Vec<u8>>,
+	{
+		self.in_transaction(|client| {
+			contract_events
+				.into_iter()
+				.try_for_each(|contract_event| client.insert_bridge_contract_event(contract_event))
+		})
+	}
+
 	/// Finds all events with a bridge transfer id.
 	pub fn find_all_events_for_bridge_transfer_id(
 		&mut self,
@@ -216,6 +335,427 @@ impl Client {
 			refunded_events,
 		})
 	}
+
+	/// Searches the initiated transfers matching the filter, newest first.
+	pub fn search_transfers(
+		&mut self,
+		filter: &TransferFilter,
+		limit: i64,
+	) -> Result<Vec<IndexedTransfer>, diesel::result::Error> {
+		let mut query = initiated_events::table.into_boxed();
+		if let Some(initiator) = &filter.initiator {
+			query = query.filter(initiated_events::initiator.eq(initiator.clone()));
+		}
+		if let Some(recipient) = &filter.recipient {
+			query = query.filter(initiated_events::recipient.eq(recipient.clone()));
+		}
+		if let Some(from) = filter.from {
+			query = query.filter(initiated_events::created_at.ge(from));
+		}
+		if let Some(to) = filter.to {
+			query = query.filter(initiated_events::created_at.lt(to));
+		}
+		if let Some(before_id) = filter.before_id {
+			query = query.filter(initiated_events::id.lt(before_id));
+		}
+		if let Some(state) = filter.state {
+			// A transfer is in a state if it has its event and none of the later ones.
+			if let Some(has_event) = has_state_event(state) {
+				query = query.filter(has_event);
+			}
+			for later in IndexedTransferState::ALL.into_iter().filter(|later| *later > state) {
+				if let Some(has_event) = has_state_event(later) {
+					query = query.filter(not(has_event));
+				}
+			}
+		}
+		let initiated = query
+			.order(initiated_events::id.desc())
+			.limit(limit)
+			.load::<InitiatedEvent>(&mut self.conn)?;
+
+		let ids: Vec<String> =
+			initiated.iter().map(|event| event.bridge_transfer_id.clone()).collect();
+		let locked: HashSet<String> = locked_events::table
+			.filter(locked_events::bridge_transfer_id.eq_any(&ids))
+			.select(locked_events::bridge_transfer_id)
+			.load(&mut self.conn)?
+			.into_iter()
+			.collect();
+		let secret_received: HashSet<String> = counter_party_completed_events::table
+			.filter(counter_party_completed_events::bridge_transfer_id.eq_any(&ids))
+			.select(counter_party_completed_events::bridge_transfer_id)
+			.load(&mut self.conn)?
+			.into_iter()
+			.collect();
+		let completed: HashSet<String> = initiator_completed_events::table
+			.filter(initiator_completed_events::bridge_transfer_id.eq_any(&ids))
+			.select(initiator_completed_events::bridge_transfer_id)
+			.load(&mut self.conn)?
+			.into_iter()
+			.collect();
+		let cancelled: HashSet<String> = cancelled_events::table
+			.filter(cancelled_events::bridge_transfer_id.eq_any(&ids))
+			.select(cancelled_events::bridge_transfer_id)
+			.load(&mut self.conn)?
+			.into_iter()
+			.collect();
+		let refunded: HashSet<String> = refunded_events::table
+			.filter(refunded_events::bridge_transfer_id.eq_any(&ids))
+			.select(refunded_events::bridge_transfer_id)
+			.load(&mut self.conn)?
+			.into_iter()
+			.collect();
+
+		Ok(initiated
+			.into_iter()
+			.map(|initiated| {
+				let id = &initiated.bridge_transfer_id;
+				let state = if refunded.contains(id) {
+					IndexedTransferState::Refunded
+				} else if cancelled.contains(id) {
+					IndexedTransferState::Cancelled
+				} else if completed.contains(id) {
+					IndexedTransferState::Completed
+				} else if secret_received.contains(id) {
+					IndexedTransferState::SecretReceived
+				} else if locked.contains(id) {
+					IndexedTransferState::Locked
+				} else {
+					IndexedTransferState::Initiated
+				};
+				IndexedTransfer { initiated, state }
+			})
+			.collect())
+	}
+
+	/// Records the fee accrued on a bridge transfer, once per transfer.
+	pub fn insert_fee_accrual(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+		amount: Amount,
+	) -> Result<(), diesel::result::Error> {
+		diesel::insert_into(fee_accruals::table)
+			.values(NewFeeAccrual {
+				bridge_transfer_id: hex::encode(bridge_transfer_id.0.to_vec()),
+				amount: amount.0.into(),
+				created_at: chrono::Utc::now().naive_utc(),
+			})
+			.on_conflict(fee_accruals::bridge_transfer_id)
+			.do_nothing()
+			.execute(&mut self.conn)?;
+		Ok(())
+	}
+
+	/// Records a fee distribution as pending before its transaction is sent,
+	/// and returns its id.
+	pub fn insert_pending_fee_distribution(
+		&mut self,
+		recipient_name: &str,
+		recipient: Vec<u8>,
+		amount: Amount,
+	) -> Result<i32, diesel::result::Error> {
+		diesel::insert_into(fee_distributions::table)
+			.values(NewFeeDistribution {
+				recipient_name: recipient_name.to_string(),
+				recipient: hex::encode(recipient),
+				amount: amount.0.into(),
+				tx_hash: None,
+				created_at: chrono::Utc::now().naive_utc(),
+				status: DistributionStatus::Pending.as_str().to_string(),
+			})
+			.returning(fee_distributions::id)
+			.get_result(&mut self.conn)
+	}
+
+	/// Records the transaction of a pending fee distribution.
+	pub fn mark_fee_distribution_sent(
+		&mut self,
+		id: i32,
+		tx_hash: Vec<u8>,
+	) -> Result<(), diesel::result::Error> {
+		diesel::update(fee_distributions::table.find(id))
+			.set((
+				fee_distributions::tx_hash.eq(hex::encode(tx_hash)),
+				fee_distributions::status.eq(DistributionStatus::Sent.as_str()),
+			))
+			.execute(&mut self.conn)?;
+		Ok(())
+	}
+
+	/// Gets the fee distributions still pending, left by a failed or interrupted send.
+	pub fn get_pending_fee_distributions(
+		&mut self,
+	) -> Result<Vec<FeeDistribution>, diesel::result::Error> {
+		fee_distributions::table
+			.filter(fee_distributions::status.eq(DistributionStatus::Pending.as_str()))
+			.order(fee_distributions::created_at.asc())
+			.load::<FeeDistribution>(&mut self.conn)
+	}
+
+	/// Gets the accrued fees not distributed yet. The pending distributions are counted as
+	/// distributed, their transaction may be on chain.
+	pub fn undistributed_fees(&mut self) -> Result<BigDecimal, diesel::result::Error> {
+		let accrued: Option<BigDecimal> =
+			fee_accruals::table.select(sum(fee_accruals::amount)).first(&mut self.conn)?;
+		let distributed: Option<BigDecimal> = fee_distributions::table
+			.select(sum(fee_distributions::amount))
+			.first(&mut self.conn)?;
+		Ok(accrued.unwrap_or_default() - distributed.unwrap_or_default())
+	}
+
+	/// Records a chain submission in the outbox before it is sent.
+	/// Actions that don't submit a transaction are not recorded.
+	pub fn insert_planned_submission(
+		&mut self,
+		action: &TransferAction,
+	) -> Result<(), diesel::result::Error> {
+		if !is_submission(&action.kind) {
+			return Ok(());
+		}
+		let now = chrono::Utc::now().naive_utc();
+		diesel::insert_into(submission_outbox::table)
+			.values(NewSubmission {
+				chain: action.chain.to_string(),
+				bridge_transfer_id: hex::encode(action.transfer_id.0.to_vec()),
+				action_kind: action.kind.to_string(),
+				status: SubmissionStatus::Planned.as_str().to_string(),
+				created_at: now,
+				updated_at: now,
+			})
+			.execute(&mut self.conn)?;
+		Ok(())
+	}
+
+	/// Records the submission of the action in the outbox and indexes the action,
+	/// in a single transaction committed before the submission is sent.
+	pub fn record_planned_submission(
+		&mut self,
+		action: &TransferAction,
+	) -> Result<(), diesel::result::Error> {
+		self.in_transaction(|client| {
+			client.insert_planned_submission(action)?;
+			client.insert_transfer_action(action.kind.clone())
+		})
+	}
+
+	/// Updates the status of the unconfirmed outbox entries of the actions
+	/// in a single transaction, in order.
+	pub fn update_submission_statuses(
+		&mut self,
+		updates: &[(TransferAction, SubmissionStatus)],
+	) -> Result<(), diesel::result::Error> {
+		self.in_transaction(|client| {
+			updates
+				.iter()
+				.try_for_each(|(action, status)| client.update_submission_status(action, *status))
+		})
+	}
+
+	/// Updates the status of the unconfirmed outbox entries of the action.
+	pub fn update_submission_status(
+		&mut self,
+		action: &TransferAction,
+		status: SubmissionStatus,
+	) -> Result<(), diesel::result::Error> {
+		if !is_submission(&action.kind) {
+			return Ok(());
+		}
+		diesel::update(
+			submission_outbox::table
+				.filter(submission_outbox::chain.eq(action.chain.to_string()))
+				.filter(
+					submission_outbox::bridge_transfer_id
+						.eq(hex::encode(action.transfer_id.0.to_vec())),
+				)
+				.filter(submission_outbox::action_kind.eq(action.kind.to_string()))
+				.filter(submission_outbox::status.ne(SubmissionStatus::Confirmed.as_str())),
+		)
+		.set((
+			submission_outbox::status.eq(status.as_str()),
+			submission_outbox::updated_at.eq(chrono::Utc::now().naive_utc()),
+		))
+		.execute(&mut self.conn)?;
+		Ok(())
+	}
+
+	/// Marks the planned outbox entry of the action as sent once the chain client has sent its
+	/// transaction, and records the hash of the transaction. The status of an entry already
+	/// confirmed is kept.
+	pub fn mark_submission_sent(
+		&mut self,
+		action: &TransferAction,
+		tx_hash: &str,
+	) -> Result<(), diesel::result::Error> {
+		if !is_submission(&action.kind) {
+			return Ok(());
+		}
+		let chain = action.chain.to_string();
+		let bridge_transfer_id = hex::encode(action.transfer_id.0.to_vec());
+		let action_kind = action.kind.to_string();
+		let entries = || {
+			submission_outbox::table
+				.filter(submission_outbox::chain.eq(chain.clone()))
+				.filter(submission_outbox::bridge_transfer_id.eq(bridge_transfer_id.clone()))
+				.filter(submission_outbox::action_kind.eq(action_kind.clone()))
+		};
+		self.in_transaction(|client| {
+			diesel::update(
+				entries().filter(submission_outbox::status.eq(SubmissionStatus::Planned.as_str())),
+			)
+			.set((
+				submission_outbox::status.eq(SubmissionStatus::Sent.as_str()),
+				submission_outbox::updated_at.eq(chrono::Utc::now().naive_utc()),
+			))
+			.execute(&mut client.conn)?;
+			diesel::update(
+				entries()
+					.filter(submission_outbox::tx_hash.is_null())
+					.filter(submission_outbox::status.ne(SubmissionStatus::Failed.as_str())),
+			)
+			.set(submission_outbox::tx_hash.eq(tx_hash))
+			.execute(&mut client.conn)?;
+			Ok(())
+		})
+	}
+
+	/// Gets the submissions planned or sent but not confirmed, oldest first.
+	pub fn get_unconfirmed_submissions(
+		&mut self,
+	) -> Result<Vec<Submission>, diesel::result::Error> {
+		submission_outbox::table
+			.filter(
+				submission_outbox::status
+					.eq_any([SubmissionStatus::Planned.as_str(), SubmissionStatus::Sent.as_str()]),
+			)
+			.order(submission_outbox::created_at.asc())
+			.load::<Submission>(&mut self.conn)
+	}
+
+	/// Gets all the submissions of a bridge transfer, oldest first.
+	pub fn get_submissions_for_bridge_transfer_id(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> Result<Vec<Submission>, diesel::result::Error> {
+		submission_outbox::table
+			.filter(
+				submission_outbox::bridge_transfer_id
+					.eq(hex::encode(bridge_transfer_id.0.to_vec())),
+			)
+			.order(submission_outbox::created_at.asc())
+			.load::<Submission>(&mut self.conn)
+	}
+
+	/// Records the fees of the transactions sent by the relayer on a chain, in its native unit.
+	pub fn insert_gas_cost(
+		&mut self,
+		chain: &str,
+		amount: u128,
+	) -> Result<(), diesel::result::Error> {
+		diesel::insert_into(gas_costs::table)
+			.values(NewGasCost {
+				chain: chain.to_string(),
+				amount: BigDecimal::from(amount),
+				created_at: chrono::Utc::now().naive_utc(),
+			})
+			.execute(&mut self.conn)?;
+		Ok(())
+	}
+
+	/// Gets the fee accruals, gas costs and locked amounts recorded in the time range.
+	pub fn get_ledger_entries(
+		&mut self,
+		from: chrono::NaiveDateTime,
+		to: chrono::NaiveDateTime,
+	) -> Result<LedgerEntries, diesel::result::Error> {
+		let fee_accruals = fee_accruals::table
+			.filter(fee_accruals::created_at.ge(from).and(fee_accruals::created_at.lt(to)))
+			.select((fee_accruals::created_at, fee_accruals::amount))
+			.load(&mut self.conn)?;
+		let gas_costs = gas_costs::table
+			.filter(gas_costs::created_at.ge(from).and(gas_costs::created_at.lt(to)))
+			.select((gas_costs::created_at, gas_costs::chain, gas_costs::amount))
+			.load(&mut self.conn)?;
+		let locked = locked_events::table
+			.filter(locked_events::created_at.ge(from).and(locked_events::created_at.lt(to)))
+			.select((locked_events::created_at, locked_events::amount))
+			.load(&mut self.conn)?;
+		Ok(LedgerEntries { fee_accruals, gas_costs, locked })
+	}
+
+	/// Records a sweep of the fees of a relayer account to its cold storage address.
+	pub fn insert_fee_sweep(
+		&mut self,
+		chain: &str,
+		destination: &str,
+		amount: u128,
+		tx_hash: &str,
+	) -> Result<(), diesel::result::Error> {
+		diesel::insert_into(fee_sweeps::table)
+			.values(NewFeeSweep {
+				chain: chain.to_string(),
+				destination: destination.to_string(),
+				amount: BigDecimal::from(amount),
+				tx_hash: tx_hash.to_string(),
+				created_at: chrono::Utc::now().naive_utc(),
+			})
+			.execute(&mut self.conn)?;
+		Ok(())
+	}
+
+	/// Gets all the fee sweeps, the most recent first.
+	pub fn get_fee_sweeps(&mut self) -> Result<Vec<FeeSweep>, diesel::result::Error> {
+		fee_sweeps::table
+			.order(fee_sweeps::created_at.desc())
+			.load::<FeeSweep>(&mut self.conn)
+	}
+
+	/// Gets all the fee distributions.
+	pub fn get_fee_distributions(&mut self) -> Result<Vec<FeeDistribution>, diesel::result::Error> {
+		fee_distributions::table
+			.order(fee_distributions::created_at.desc())
+			.load::<FeeDistribution>(&mut self.conn)
+	}
+}
+
+/// Filter matching the initiated events of the transfers that have the event of the state.
+/// All the transfers have been initiated, so there is no filter for that state.
+fn has_state_event(
+	state: IndexedTransferState,
+) -> Option<Box<dyn BoxableExpression<initiated_events::table, Pg, SqlType = Bool>>> {
+	let id = initiated_events::bridge_transfer_id;
+	match state {
+		IndexedTransferState::Initiated => None,
+		IndexedTransferState::Locked => Some(Box::new(
+			id.eq_any(locked_events::table.select(locked_events::bridge_transfer_id)),
+		)),
+		IndexedTransferState::SecretReceived => Some(Box::new(
+			id.eq_any(
+				counter_party_completed_events::table
+					.select(counter_party_completed_events::bridge_transfer_id),
+			),
+		)),
+		IndexedTransferState::Completed => Some(Box::new(
+			id.eq_any(
+				initiator_completed_events::table
+					.select(initiator_completed_events::bridge_transfer_id),
+			),
+		)),
+		IndexedTransferState::Cancelled => Some(Box::new(
+			id.eq_any(cancelled_events::table.select(cancelled_events::bridge_transfer_id)),
+		)),
+		IndexedTransferState::Refunded => Some(Box::new(
+			id.eq_any(refunded_events::table.select(refunded_events::bridge_transfer_id)),
+		)),
+	}
+}
+
+fn is_submission(kind: &TransferActionType) -> bool {
+	matches!(
+		kind,
+		TransferActionType::LockBridgeTransfer { .. }
+			| TransferActionType::WaitAndCompleteInitiator(..)
+	)
 }
 
 /*#[cfg(test)]
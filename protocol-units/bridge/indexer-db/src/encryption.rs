@@ -0,0 +1,122 @@
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+
+/// Environment variable with the hex encoded 32 bytes key of the sensitive fields.
+pub const ENCRYPTION_KEY_ENV: &str = "BRIDGE_INDEXER_ENCRYPTION_KEY";
+/// Environment variable with the path of a file holding the hex encoded key,
+/// e.g. a secret mounted by the orchestrator.
+pub const ENCRYPTION_KEY_FILE_ENV: &str = "BRIDGE_INDEXER_ENCRYPTION_KEY_FILE";
+
+// Prefix of the encrypted fields, the fields stored before encryption are plain hex.
+const ENCRYPTED_PREFIX: &str = "enc1:";
+const NONCE_LEN: usize = 24;
+
+/// Encrypts the sensitive fields of the records, like the preimages of the hash locks,
+/// so a copy of the database can't be used to complete the transfers in flight.
+/// Each field is bound to its record, an encrypted field copied to another record
+/// doesn't decrypt.
+#[derive(Clone)]
+pub struct FieldCipher {
+	cipher: XChaCha20Poly1305,
+}
+
+impl std::fmt::Debug for FieldCipher {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str("FieldCipher(..)")
+	}
+}
+
+impl FieldCipher {
+	pub fn new(key: [u8; 32]) -> Self {
+		Self { cipher: XChaCha20Poly1305::new(&key.into()) }
+	}
+
+	/// Creates the cipher from a hex encoded 32 bytes key.
+	pub fn from_hex(key: &str) -> Result<Self, anyhow::Error> {
+		let key: [u8; 32] = hex::decode(key.trim().trim_start_matches("0x"))?.try_into().map_err(
+			|key: Vec<u8>| anyhow::anyhow!("Encryption key must be 32 bytes, got {}", key.len()),
+		)?;
+		Ok(Self::new(key))
+	}
+
+	/// Gets the cipher from the key file, or the key, in the environment.
+	/// None when no key is configured.
+	pub fn from_env() -> Result<Option<Self>, anyhow::Error> {
+		if let Ok(path) = std::env::var(ENCRYPTION_KEY_FILE_ENV) {
+			let key = std::fs::read_to_string(&path)
+				.map_err(|e| anyhow::anyhow!("Failed to read encryption key {path}: {e}"))?;
+			return Self::from_hex(&key).map(Some);
+		}
+		match std::env::var(ENCRYPTION_KEY_ENV) {
+			Ok(key) => Self::from_hex(&key).map(Some),
+			Err(_) => Ok(None),
+		}
+	}
+
+	/// Encrypts the field of the record with a random nonce.
+	pub fn encrypt(&self, record: &str, field: &str, plaintext: &[u8]) -> String {
+		let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+		let aad = associated_data(record, field);
+		let ciphertext = self
+			.cipher
+			.encrypt(&nonce, Payload { msg: plaintext, aad: &aad })
+			.expect("Encryption of a field can't fail");
+		format!("{ENCRYPTED_PREFIX}{}{}", hex::encode(nonce), hex::encode(ciphertext))
+	}
+
+	/// Decrypts the field of the record encrypted with `encrypt`.
+	pub fn decrypt(
+		&self,
+		record: &str,
+		field: &str,
+		stored: &str,
+	) -> Result<Vec<u8>, anyhow::Error> {
+		let sealed = hex::decode(
+			stored
+				.strip_prefix(ENCRYPTED_PREFIX)
+				.ok_or_else(|| anyhow::anyhow!("Field {field} of {record} is not encrypted"))?,
+		)?;
+		if sealed.len() < NONCE_LEN {
+			anyhow::bail!("Encrypted field {field} of {record} is truncated");
+		}
+		let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+		let aad = associated_data(record, field);
+		self.cipher
+			.decrypt(XNonce::from_slice(nonce), Payload { msg: ciphertext, aad: &aad })
+			.map_err(|_| anyhow::anyhow!("Failed to decrypt field {field} of {record}"))
+	}
+}
+
+/// Whether the stored field has been encrypted.
+pub fn is_encrypted(stored: &str) -> bool {
+	stored.starts_with(ENCRYPTED_PREFIX)
+}
+
+fn associated_data(record: &str, field: &str) -> Vec<u8> {
+	format!("{field}:{record}").into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_field_encryption() -> Result<(), anyhow::Error> {
+		let cipher = FieldCipher::from_hex(&hex::encode([7u8; 32]))?;
+		let pre_image = [1u8; 32];
+
+		let stored = cipher.encrypt("transfer", "pre_image", &pre_image);
+		assert!(is_encrypted(&stored));
+		assert!(!stored.contains(&hex::encode(pre_image)));
+		assert_ne!(stored, cipher.encrypt("transfer", "pre_image", &pre_image));
+		assert_eq!(cipher.decrypt("transfer", "pre_image", &stored)?, pre_image);
+
+		// Bound to the record and the field, and to the key.
+		assert!(cipher.decrypt("other transfer", "pre_image", &stored).is_err());
+		assert!(cipher.decrypt("transfer", "hash_lock", &stored).is_err());
+		assert!(FieldCipher::new([8; 32]).decrypt("transfer", "pre_image", &stored).is_err());
+		assert!(cipher.decrypt("transfer", "pre_image", &hex::encode(pre_image)).is_err());
+		assert!(FieldCipher::from_hex("0x1234").is_err());
+		Ok(())
+	}
+}
@@ -1,4 +1,6 @@
 pub mod client;
+pub mod encryption;
 pub mod migrations;
 pub mod models;
 pub mod schema;
+pub mod transfer_store;
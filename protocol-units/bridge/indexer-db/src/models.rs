@@ -1,6 +1,7 @@
 use crate::schema::*;
 use bigdecimal::BigDecimal;
 use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
 
 // LockBridgeTransfer mapping
 #[derive(Debug, Insertable, Default)]
@@ -72,6 +73,50 @@ pub struct InitiatedEvent {
 	pub created_at: chrono::NaiveDateTime,
 }
 
+/// State of a transfer derived from the events indexed for it.
+/// The variants are ordered by progress, a transfer is in the last state it has an event for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexedTransferState {
+	Initiated,
+	Locked,
+	SecretReceived,
+	Completed,
+	Cancelled,
+	Refunded,
+}
+
+impl IndexedTransferState {
+	pub const ALL: [IndexedTransferState; 6] = [
+		IndexedTransferState::Initiated,
+		IndexedTransferState::Locked,
+		IndexedTransferState::SecretReceived,
+		IndexedTransferState::Completed,
+		IndexedTransferState::Cancelled,
+		IndexedTransferState::Refunded,
+	];
+}
+
+/// Criteria of a transfer search. Addresses are hex encoded without the 0x prefix.
+#[derive(Debug, Clone, Default)]
+pub struct TransferFilter {
+	pub initiator: Option<String>,
+	pub recipient: Option<String>,
+	pub state: Option<IndexedTransferState>,
+	/// Inclusive lower bound of the initiation time.
+	pub from: Option<chrono::NaiveDateTime>,
+	/// Exclusive upper bound of the initiation time.
+	pub to: Option<chrono::NaiveDateTime>,
+	/// Only return the transfers indexed before this initiated event id.
+	pub before_id: Option<i32>,
+}
+
+#[derive(Debug)]
+pub struct IndexedTransfer {
+	pub initiated: InitiatedEvent,
+	pub state: IndexedTransferState,
+}
+
 // LockedEvent mapping
 #[derive(Debug, Insertable, Default)]
 #[diesel(table_name = locked_events)]
@@ -163,3 +208,216 @@ pub struct RefundedEvent {
 	pub bridge_transfer_id: String,
 	pub created_at: chrono::NaiveDateTime,
 }
+
+// FeeAccrual mapping
+#[derive(Debug, Insertable, Default)]
+#[diesel(table_name = fee_accruals)]
+pub struct NewFeeAccrual {
+	pub bridge_transfer_id: String,
+	pub amount: BigDecimal,
+	pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Queryable, Insertable)]
+#[diesel(table_name = fee_accruals)]
+pub struct FeeAccrual {
+	pub id: i32,
+	pub bridge_transfer_id: String,
+	pub amount: BigDecimal,
+	pub created_at: chrono::NaiveDateTime,
+}
+
+// FeeDistribution mapping
+#[derive(Debug, Insertable, Default)]
+#[diesel(table_name = fee_distributions)]
+pub struct NewFeeDistribution {
+	pub recipient_name: String,
+	pub recipient: String,
+	pub amount: BigDecimal,
+	pub tx_hash: Option<String>,
+	pub created_at: chrono::NaiveDateTime,
+	pub status: String,
+}
+
+#[derive(Debug, Queryable, Insertable)]
+#[diesel(table_name = fee_distributions)]
+pub struct FeeDistribution {
+	pub id: i32,
+	pub recipient_name: String,
+	pub recipient: String,
+	pub amount: BigDecimal,
+	/// Missing while the distribution is pending.
+	pub tx_hash: Option<String>,
+	pub created_at: chrono::NaiveDateTime,
+	pub status: String,
+}
+
+/// Progress of a fee distribution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DistributionStatus {
+	/// Recorded before its transaction is sent, the transaction may or may not be on chain.
+	Pending,
+	/// The transaction has been executed on chain.
+	Sent,
+}
+
+impl DistributionStatus {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			DistributionStatus::Pending => "pending",
+			DistributionStatus::Sent => "sent",
+		}
+	}
+}
+
+// FeeSweep mapping
+#[derive(Debug, Insertable, Default)]
+#[diesel(table_name = fee_sweeps)]
+pub struct NewFeeSweep {
+	pub chain: String,
+	pub destination: String,
+	pub amount: BigDecimal,
+	pub tx_hash: String,
+	pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Queryable, Insertable)]
+#[diesel(table_name = fee_sweeps)]
+pub struct FeeSweep {
+	pub id: i32,
+	pub chain: String,
+	pub destination: String,
+	pub amount: BigDecimal,
+	pub tx_hash: String,
+	pub created_at: chrono::NaiveDateTime,
+}
+
+// GasCost mapping
+#[derive(Debug, Insertable, Default)]
+#[diesel(table_name = gas_costs)]
+pub struct NewGasCost {
+	pub chain: String,
+	pub amount: BigDecimal,
+	pub created_at: chrono::NaiveDateTime,
+}
+
+/// Entries of the ledger in a time range, for the revenue reports.
+#[derive(Debug, Clone, Default)]
+pub struct LedgerEntries {
+	/// Fees accrued on the transfers.
+	pub fee_accruals: Vec<(chrono::NaiveDateTime, BigDecimal)>,
+	/// Fees of the transactions of the relayer, by chain.
+	pub gas_costs: Vec<(chrono::NaiveDateTime, String, BigDecimal)>,
+	/// Amounts locked on the counterparty chains.
+	pub locked: Vec<(chrono::NaiveDateTime, BigDecimal)>,
+}
+
+/// Progress of a chain submission recorded in the outbox.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubmissionStatus {
+	/// Recorded but not handed to the chain client yet.
+	Planned,
+	/// Handed to the chain client, the transaction may or may not be on chain.
+	Sent,
+	/// The transaction has been executed on chain.
+	Confirmed,
+	/// The chain client returned an error.
+	Failed,
+}
+
+impl SubmissionStatus {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			SubmissionStatus::Planned => "planned",
+			SubmissionStatus::Sent => "sent",
+			SubmissionStatus::Confirmed => "confirmed",
+			SubmissionStatus::Failed => "failed",
+		}
+	}
+}
+
+// SubmissionOutbox mapping
+#[derive(Debug, Insertable)]
+#[diesel(table_name = submission_outbox)]
+pub struct NewSubmission {
+	pub chain: String,
+	pub bridge_transfer_id: String,
+	pub action_kind: String,
+	pub status: String,
+	pub created_at: chrono::NaiveDateTime,
+	pub updated_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Queryable)]
+#[diesel(table_name = submission_outbox)]
+pub struct Submission {
+	pub id: i32,
+	pub chain: String,
+	pub bridge_transfer_id: String,
+	pub action_kind: String,
+	pub status: String,
+	pub created_at: chrono::NaiveDateTime,
+	pub updated_at: chrono::NaiveDateTime,
+	/// Missing until the transaction is sent.
+	pub tx_hash: Option<String>,
+}
+
+/// Last state of a transfer recorded by the relayer, the bytes hex encoded.
+#[derive(Debug, Clone, PartialEq, Queryable, Insertable, AsChangeset)]
+#[diesel(table_name = transfer_states, treat_none_as_null = true)]
+pub struct TransferStateRow {
+	pub bridge_transfer_id: String,
+	pub init_chain: String,
+	pub state: Option<String>,
+	pub sequence: i64,
+	pub initiator: String,
+	pub recipient: String,
+	pub hash_lock: String,
+	pub time_lock: i64,
+	pub amount: BigDecimal,
+	pub updated_at: chrono::NaiveDateTime,
+	/// Missing until the secret is revealed on the counterparty chain.
+	pub pre_image: Option<String>,
+}
+
+/// Pause set by an operator, the token and the direction empty when it applies to all of them.
+#[derive(Debug, Clone, PartialEq, Queryable, Insertable)]
+#[diesel(table_name = active_pauses)]
+pub struct ActivePauseRow {
+	pub token: String,
+	pub direction: String,
+	pub operator: String,
+	pub paused_at: i64,
+}
+
+/// Transfer held until an operator approves or denies it, the id hex encoded.
+#[derive(Debug, Clone, PartialEq, Queryable, Insertable, AsChangeset)]
+#[diesel(table_name = pending_approvals)]
+pub struct PendingApprovalRow {
+	pub bridge_transfer_id: String,
+	pub init_chain: String,
+	pub amount: BigDecimal,
+	pub queued_at: i64,
+}
+
+// ApprovalAuditEntry mapping
+#[derive(Debug, Insertable)]
+#[diesel(table_name = approval_audit_entries)]
+pub struct NewApprovalAuditEntry {
+	pub bridge_transfer_id: String,
+	pub action: String,
+	pub operator: Option<String>,
+	pub amount: BigDecimal,
+	pub at: i64,
+}
+
+#[derive(Debug, Queryable)]
+#[diesel(table_name = approval_audit_entries)]
+pub struct ApprovalAuditEntry {
+	pub id: i32,
+	pub bridge_transfer_id: String,
+	pub action: String,
+	pub operator: Option<String>,
+	pub amount: BigDecimal,
+	pub at: i64,
+}
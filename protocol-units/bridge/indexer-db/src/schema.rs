@@ -80,3 +80,110 @@ table! {
 		created_at -> Timestamp,
 	}
 }
+
+table! {
+	active_pauses (token, direction) {
+		token -> Text,
+		direction -> Text,
+		operator -> Text,
+		paused_at -> BigInt,
+	}
+}
+
+table! {
+	approval_audit_entries (id) {
+		id -> Int4,
+		bridge_transfer_id -> Text,
+		action -> Text,
+		operator -> Nullable<Text>,
+		amount -> Numeric,
+		at -> BigInt,
+	}
+}
+
+table! {
+	fee_accruals (id) {
+		id -> Int4,
+		bridge_transfer_id -> Text,
+		amount -> Numeric,
+		created_at -> Timestamp,
+	}
+}
+
+table! {
+	fee_distributions (id) {
+		id -> Int4,
+		recipient_name -> Text,
+		recipient -> Text,
+		amount -> Numeric,
+		tx_hash -> Nullable<Text>,
+		created_at -> Timestamp,
+		status -> Text,
+	}
+}
+
+table! {
+	fee_sweeps (id) {
+		id -> Int4,
+		chain -> Text,
+		destination -> Text,
+		amount -> Numeric,
+		tx_hash -> Text,
+		created_at -> Timestamp,
+	}
+}
+
+table! {
+	gas_costs (id) {
+		id -> Int4,
+		chain -> Text,
+		amount -> Numeric,
+		created_at -> Timestamp,
+	}
+}
+
+table! {
+	pending_approvals (bridge_transfer_id) {
+		bridge_transfer_id -> Text,
+		init_chain -> Text,
+		amount -> Numeric,
+		queued_at -> BigInt,
+	}
+}
+
+table! {
+	relayer_leases (name) {
+		name -> Text,
+		holder -> Text,
+		expires_at -> BigInt,
+	}
+}
+
+table! {
+	submission_outbox (id) {
+		id -> Int4,
+		chain -> Text,
+		bridge_transfer_id -> Text,
+		action_kind -> Text,
+		status -> Text,
+		created_at -> Timestamp,
+		updated_at -> Timestamp,
+		tx_hash -> Nullable<Text>,
+	}
+}
+
+table! {
+	transfer_states (bridge_transfer_id) {
+		bridge_transfer_id -> Text,
+		init_chain -> Text,
+		state -> Nullable<Text>,
+		sequence -> BigInt,
+		initiator -> Text,
+		recipient -> Text,
+		hash_lock -> Text,
+		time_lock -> BigInt,
+		amount -> Numeric,
+		updated_at -> Timestamp,
+		pre_image -> Nullable<Text>,
+	}
+}
@@ -0,0 +1,226 @@
+//! Postgres store of the last state of each transfer, shared by the relayer instances. The
+//! connections are pooled, and each change of state is written in a transaction locking the
+//! row of its transfer. The instances also elect their leader with a lease in it, and keep the
+//! transfers pending an operator approval with their audit trail, and the pauses of the bridge.
+use crate::migrations::run_migrations;
+use crate::models::{
+	ActivePauseRow, ApprovalAuditEntry, NewApprovalAuditEntry, PendingApprovalRow, TransferStateRow,
+};
+use crate::schema::{
+	active_pauses, approval_audit_entries, pending_approvals, relayer_leases, transfer_states,
+};
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool, PooledConnection};
+
+/// Whether a change of state was written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transition {
+	Applied,
+	/// The store already has the change, or a later one written by another instance.
+	Stale,
+}
+
+/// Clones share the same connection pool.
+#[derive(Clone)]
+pub struct PgTransferStore {
+	pool: Pool<ConnectionManager<PgConnection>>,
+}
+
+impl PgTransferStore {
+	/// Connects a pool of at most `pool_size` connections to the database, and runs the
+	/// migrations.
+	pub fn connect(url: &str, pool_size: u32) -> Result<Self, anyhow::Error> {
+		let pool = Pool::builder()
+			.max_size(pool_size.max(1))
+			.build(ConnectionManager::<PgConnection>::new(url))
+			.map_err(|e| anyhow::anyhow!("Failed to connect to postgresql instance: {}", e))?;
+		let store = PgTransferStore { pool };
+		run_migrations(&mut store.conn()?)?;
+		Ok(store)
+	}
+
+	/// Connects to the database of the indexer.
+	pub fn from_env(pool_size: u32) -> Result<Self, anyhow::Error> {
+		let url = std::env::var("BRIDGE_INDEXER_DATABASE_URL")?;
+		Self::connect(&url, pool_size)
+	}
+
+	fn conn(&self) -> Result<PooledConnection<ConnectionManager<PgConnection>>, anyhow::Error> {
+		self.pool
+			.get()
+			.map_err(|e| anyhow::anyhow!("No connection to the transfer store: {}", e))
+	}
+
+	/// Writes the state of the transfer unless the store has a change of the same or a later
+	/// sequence, in a transaction holding the row of the transfer.
+	pub fn transition(&self, row: &TransferStateRow) -> Result<Transition, anyhow::Error> {
+		let mut conn = self.conn()?;
+		conn.transaction::<_, diesel::result::Error, _>(|conn| {
+			let stored = transfer_states::table
+				.find(&row.bridge_transfer_id)
+				.select(transfer_states::sequence)
+				.for_update()
+				.first::<i64>(conn)
+				.optional()?;
+			match stored {
+				Some(sequence) if sequence >= row.sequence => Ok(Transition::Stale),
+				Some(_) => {
+					diesel::update(transfer_states::table.find(&row.bridge_transfer_id))
+						.set(row)
+						.execute(conn)?;
+					Ok(Transition::Applied)
+				}
+				// A concurrent first write of the transfer fails on the primary key, and the
+				// transaction is rolled back.
+				None => {
+					diesel::insert_into(transfer_states::table).values(row).execute(conn)?;
+					Ok(Transition::Applied)
+				}
+			}
+		})
+		.map_err(|e| anyhow::anyhow!(e))
+	}
+
+	/// Gets the state of a transfer, the id hex encoded.
+	pub fn get(&self, bridge_transfer_id: &str) -> Result<Option<TransferStateRow>, anyhow::Error> {
+		Ok(transfer_states::table
+			.find(bridge_transfer_id)
+			.first::<TransferStateRow>(&mut self.conn()?)
+			.optional()?)
+	}
+
+	/// Gets the transfers not over, the oldest update first.
+	pub fn in_flight(&self) -> Result<Vec<TransferStateRow>, anyhow::Error> {
+		Ok(transfer_states::table
+			.filter(transfer_states::state.is_distinct_from("Completed"))
+			.filter(transfer_states::state.is_distinct_from("Refunded"))
+			.filter(transfer_states::state.is_distinct_from("Aborted"))
+			.order(transfer_states::updated_at.asc())
+			.load::<TransferStateRow>(&mut self.conn()?)?)
+	}
+
+	/// Takes or renews the lease for the holder until `expires_at`, unless another holder has
+	/// it until after `now`, in a transaction holding the row of the lease. Returns whether the
+	/// holder has the lease. The timestamps are unix timestamps in seconds.
+	pub fn acquire_lease(
+		&self,
+		name: &str,
+		holder: &str,
+		now: u64,
+		expires_at: u64,
+	) -> Result<bool, anyhow::Error> {
+		let mut conn = self.conn()?;
+		conn.transaction::<_, diesel::result::Error, _>(|conn| {
+			let current = relayer_leases::table
+				.find(name)
+				.select((relayer_leases::holder, relayer_leases::expires_at))
+				.for_update()
+				.first::<(String, i64)>(conn)
+				.optional()?;
+			match current {
+				Some((current_holder, current_expires_at))
+					if current_holder != holder && current_expires_at > now as i64 =>
+				{
+					Ok(false)
+				}
+				Some(_) => {
+					diesel::update(relayer_leases::table.find(name))
+						.set((
+							relayer_leases::holder.eq(holder),
+							relayer_leases::expires_at.eq(expires_at as i64),
+						))
+						.execute(conn)?;
+					Ok(true)
+				}
+				// A concurrent first lease fails on the primary key, and the transaction is
+				// rolled back.
+				None => {
+					diesel::insert_into(relayer_leases::table)
+						.values((
+							relayer_leases::name.eq(name),
+							relayer_leases::holder.eq(holder),
+							relayer_leases::expires_at.eq(expires_at as i64),
+						))
+						.execute(conn)?;
+					Ok(true)
+				}
+			}
+		})
+		.map_err(|e| anyhow::anyhow!(e))
+	}
+
+	/// Writes a transfer pending approval, replacing the one of the same transfer.
+	pub fn save_pending_approval(&self, row: &PendingApprovalRow) -> Result<(), anyhow::Error> {
+		diesel::insert_into(pending_approvals::table)
+			.values(row)
+			.on_conflict(pending_approvals::bridge_transfer_id)
+			.do_update()
+			.set(row)
+			.execute(&mut self.conn()?)?;
+		Ok(())
+	}
+
+	/// Removes a transfer pending approval once resolved, the id hex encoded.
+	pub fn remove_pending_approval(&self, bridge_transfer_id: &str) -> Result<(), anyhow::Error> {
+		diesel::delete(pending_approvals::table.find(bridge_transfer_id))
+			.execute(&mut self.conn()?)?;
+		Ok(())
+	}
+
+	/// Gets the transfers pending approval, the oldest first.
+	pub fn pending_approvals(&self) -> Result<Vec<PendingApprovalRow>, anyhow::Error> {
+		Ok(pending_approvals::table
+			.order(pending_approvals::queued_at.asc())
+			.load::<PendingApprovalRow>(&mut self.conn()?)?)
+	}
+
+	/// Appends an entry to the audit trail of the approvals.
+	pub fn insert_approval_audit_entry(
+		&self,
+		entry: &NewApprovalAuditEntry,
+	) -> Result<(), anyhow::Error> {
+		diesel::insert_into(approval_audit_entries::table)
+			.values(entry)
+			.execute(&mut self.conn()?)?;
+		Ok(())
+	}
+
+	/// Gets the latest `limit` entries of the audit trail of the approvals, the oldest first.
+	pub fn latest_approval_audit_entries(
+		&self,
+		limit: i64,
+	) -> Result<Vec<ApprovalAuditEntry>, anyhow::Error> {
+		let mut entries = approval_audit_entries::table
+			.order(approval_audit_entries::id.desc())
+			.limit(limit)
+			.load::<ApprovalAuditEntry>(&mut self.conn()?)?;
+		entries.reverse();
+		Ok(entries)
+	}
+
+	/// Writes a pause, replacing the one of the same token and direction.
+	pub fn save_pause(&self, row: &ActivePauseRow) -> Result<(), anyhow::Error> {
+		diesel::insert_into(active_pauses::table)
+			.values(row)
+			.on_conflict((active_pauses::token, active_pauses::direction))
+			.do_update()
+			.set((
+				active_pauses::operator.eq(&row.operator),
+				active_pauses::paused_at.eq(row.paused_at),
+			))
+			.execute(&mut self.conn()?)?;
+		Ok(())
+	}
+
+	/// Removes the pause of the token and direction once resumed.
+	pub fn remove_pause(&self, token: &str, direction: &str) -> Result<(), anyhow::Error> {
+		diesel::delete(active_pauses::table.find((token, direction))).execute(&mut self.conn()?)?;
+		Ok(())
+	}
+
+	/// Gets the active pauses.
+	pub fn active_pauses(&self) -> Result<Vec<ActivePauseRow>, anyhow::Error> {
+		Ok(active_pauses::table.load::<ActivePauseRow>(&mut self.conn()?)?)
+	}
+}
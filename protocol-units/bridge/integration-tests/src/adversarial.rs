@@ -0,0 +1,92 @@
+//! Adversarial scenarios played against the bridge contracts and the relayer state machine.
+//! Each helper performs the attack and returns the result for the test to assert the error.
+use alloy::primitives::keccak256;
+use bridge_service::chains::bridge_contracts::{BridgeContract, BridgeContractResult};
+use bridge_service::types::{
+	Amount, BridgeAddress, BridgeTransferDetails, BridgeTransferId, ChainId, HashLock,
+	HashLockPreImage, LockDetails,
+};
+use bridge_util::{
+	BridgeContractEvent, InvalidEventError, TransferEvent, TransferState, TransferStateType,
+};
+
+/// A random pre-image that does not hash to the hash lock.
+pub fn wrong_preimage(hash_lock: HashLock) -> HashLockPreImage {
+	loop {
+		let pre_image = HashLockPreImage::random();
+		if *keccak256(pre_image.0) != hash_lock.0 {
+			return pre_image;
+		}
+	}
+}
+
+/// Tries to complete a locked counterparty transfer with a pre-image not matching its hash lock.
+pub async fn complete_with_wrong_preimage<A, C: BridgeContract<A>>(
+	client: &mut C,
+	bridge_transfer_id: BridgeTransferId,
+	hash_lock: HashLock,
+) -> BridgeContractResult<()> {
+	client
+		.counterparty_complete_bridge_transfer(bridge_transfer_id, wrong_preimage(hash_lock))
+		.await
+}
+
+/// Refunds an initiated transfer then tries to complete it with its valid pre-image.
+/// The refund must succeed, the returned inner result is the completion attempt.
+pub async fn complete_after_refund<A, C: BridgeContract<A>>(
+	client: &mut C,
+	bridge_transfer_id: BridgeTransferId,
+	pre_image: HashLockPreImage,
+) -> BridgeContractResult<BridgeContractResult<()>> {
+	client.refund_bridge_transfer(bridge_transfer_id).await?;
+	Ok(client.initiator_complete_bridge_transfer(bridge_transfer_id, pre_image).await)
+}
+
+/// Locks a counterparty transfer with a random hash lock instead of the initiated one.
+/// Returns the hash lock the transfer has been locked with.
+pub async fn lock_with_mismatched_hash_lock<A, C: BridgeContract<A>>(
+	client: &mut C,
+	bridge_transfer_id: BridgeTransferId,
+	hash_lock: HashLock,
+	initiator: BridgeAddress<Vec<u8>>,
+	recipient: BridgeAddress<A>,
+	amount: Amount,
+) -> BridgeContractResult<HashLock> {
+	let mismatched_hash_lock = loop {
+		let candidate = HashLock::random();
+		if candidate != hash_lock {
+			break candidate;
+		}
+	};
+	client
+		.lock_bridge_transfer(
+			bridge_transfer_id,
+			mismatched_hash_lock,
+			initiator,
+			recipient,
+			amount,
+		)
+		.await?;
+	Ok(mismatched_hash_lock)
+}
+
+/// Validates a lock event the way the relayer does, against the state of the initiated transfer.
+pub fn relayer_validate_lock<I, L>(
+	init_chain: ChainId,
+	initiated: BridgeTransferDetails<I>,
+	lock: LockDetails<L>,
+) -> Result<(), InvalidEventError>
+where
+	I: Into<Vec<u8>> + Clone,
+	L: std::fmt::Debug,
+{
+	let state = TransferState::from_initiator_details(
+		init_chain,
+		initiated,
+		TransferStateType::Initialized,
+	);
+	state.validate_event(&TransferEvent {
+		chain: init_chain.other(),
+		contract_event: BridgeContractEvent::Locked(lock),
+	})
+}
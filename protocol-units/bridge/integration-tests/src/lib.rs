@@ -7,7 +7,7 @@ use alloy::{
 use alloy_network::EthereumWallet;
 use aptos_sdk::{
 	rest_client::{aptos_api_types::Transaction as AptosTransaction, Client, FaucetClient},
-	types::{account_address::AccountAddress, LocalAccount},
+	types::LocalAccount,
 };
 use bridge_config::Config;
 use bridge_service::chains::ethereum::types::MockMOVEToken;
@@ -31,10 +31,11 @@ use bridge_service::{
 			utils::{self as movement_utils, MovementAddress, MovementHash},
 		},
 	},
-	types::{BridgeTransferId, HashLockPreImage},
+	types::{BridgeTransferId, HashLockPreImage, Preimage},
 };
+use bridge_test_fixtures::{amounts, eth as eth_fixtures, movement as movement_fixtures, secrets};
 use godfig::{backend::config_file::ConfigFile, Godfig};
-use rand::{distributions::Alphanumeric, thread_rng, Rng, SeedableRng};
+use rand::{distributions::Alphanumeric, thread_rng, Rng};
 use std::{
 	convert::TryInto,
 	str::FromStr,
@@ -42,6 +43,10 @@ use std::{
 };
 use url::Url;
 
+pub mod adversarial;
+pub mod network;
+pub mod node;
+pub mod relayer;
 pub mod utils;
 
 #[derive(Clone)]
@@ -76,14 +81,13 @@ impl Default for EthToMovementCallArgs {
 		bridge_transfer_id.extend_from_slice(random_suffix.as_bytes());
 
 		Self {
-			// Dummy valid EIP-55 address used in framework modules
-			// initiator: b"32Be343B94f860124dC4fEe278FDCBD38C102D88".to_vec(),
-			// Actual Eth address
-			initiator: b"0x3c44cdddb6a900fa2b585dd299e03d12fa4293bc".to_vec(),
-			// All lowercase version:
-			//initiator: b"0x32be343b94f860124dc4fee278fdcbd38c102d88".to_vec(),
+			// Actual Eth address, all lowercase
+			initiator: eth_fixtures::ANVIL_ADDRESSES[eth_fixtures::INITIATOR_INDEX]
+				.to_string()
+				.to_lowercase()
+				.into_bytes(),
 			// Dummy recipient address
-			recipient: MovementAddress(AccountAddress::new(*b"0x00000000000000000000000000face")),
+			recipient: MovementAddress(movement_fixtures::RECIPIENT),
 			// Convert to [u8; 32] with explicit type annotation
 			bridge_transfer_id: MovementHash(
 				bridge_transfer_id
@@ -91,9 +95,9 @@ impl Default for EthToMovementCallArgs {
 					.try_into()
 					.expect("Expected bridge_transfer_id to be 32 bytes"),
 			),
-			hash_lock: MovementHash(*keccak256(b"secret")),
-			time_lock: 3600,
-			amount: 100,
+			hash_lock: MovementHash(*keccak256(secrets::SECRET)),
+			time_lock: amounts::TIME_LOCK,
+			amount: amounts::TRANSFER_AMOUNT,
 		}
 	}
 }
@@ -112,7 +116,7 @@ impl Default for MovementToEthCallArgs {
 		let pre_image: [u8; 32] = thread_rng().gen();
 
 		Self {
-			initiator: MovementAddress(AccountAddress::new(*b"0x000000000000000000000000A55018")),
+			initiator: MovementAddress(movement_fixtures::INITIATOR),
 			recipient: b"32Be343B94f860124dC4fEe278FDCBD38C102D88".to_vec(),
 			bridge_transfer_id: EthHash(
 				bridge_transfer_id
@@ -120,9 +124,10 @@ impl Default for MovementToEthCallArgs {
 					.try_into()
 					.expect("Expected bridge_transfer_id to be 32 bytes"),
 			),
-			hash_lock: EthHash(*keccak256(&pre_image)), // Hash the secret for the hash lock
-			time_lock: 3600,
-			amount: 100,
+			// The hash lock of the Ethereum contracts, over the raw bytes of the secret.
+			hash_lock: EthHash(Preimage(pre_image.to_vec()).hash_for_eth().0),
+			time_lock: amounts::TIME_LOCK,
+			amount: amounts::TRANSFER_AMOUNT,
 			pre_image, // Store the generated secret in the struct
 		}
 	}
@@ -239,6 +244,29 @@ impl HarnessEthClient {
 			.await?;
 		}
 
+		self.initiate_funded_bridge_transfer(
+			config,
+			initiator_privatekey,
+			recipient,
+			hash_lock,
+			amount,
+		)
+		.await
+	}
+
+	/// Initiate a transfer from an initiator already holding the MOVE tokens,
+	/// e.g. an operator funded account on a testnet.
+	pub async fn initiate_funded_bridge_transfer(
+		&self,
+		config: &Config,
+		initiator_privatekey: PrivateKeySigner,
+		recipient: MovementAddress,
+		hash_lock: HashLock,
+		amount: Amount,
+	) -> Result<(), anyhow::Error> {
+		let initiator_address = initiator_privatekey.address();
+		let move_value = U256::from(amount.0);
+
 		let initiator_rpc_provider = ProviderBuilder::new()
 			.with_recommended_fillers()
 			.wallet(EthereumWallet::from(initiator_privatekey))
@@ -300,17 +328,17 @@ pub struct HarnessMvtClient {
 	pub rest_client: Client,
 	/// The Aptos Faucet Client
 	pub faucet_client: Arc<RwLock<FaucetClient>>,
+	/// The local Movement node, when the harness started it
+	pub node: Option<node::MovementNodeSupervisor>,
 }
 
 impl HarnessMvtClient {
 	pub fn gen_aptos_account_bytes() -> Vec<u8> {
-		let mut rng = ::rand::rngs::StdRng::from_seed([3u8; 32]);
-		let movement_recipient = LocalAccount::generate(&mut rng);
+		let movement_recipient = movement_fixtures::test_account();
 		movement_recipient.public_key().to_bytes().to_vec()
 	}
 	pub fn gen_aptos_account() -> LocalAccount {
-		let mut rng = ::rand::rngs::StdRng::from_seed([3u8; 32]);
-		LocalAccount::generate(&mut rng)
+		movement_fixtures::test_account()
 	}
 
 	pub async fn build(config: &Config) -> Self {
@@ -329,7 +357,26 @@ impl HarnessMvtClient {
 			node_connection_url.clone(),
 		)));
 
-		HarnessMvtClient { movement_client, rest_client, faucet_client }
+		HarnessMvtClient { movement_client, rest_client, faucet_client, node: None }
+	}
+
+	/// Starts a local Movement node, waits for it to be ready and builds the client on it.
+	pub async fn build_with_node(
+		config: &Config,
+		supervisor_config: node::SupervisorConfig,
+	) -> Result<Self, anyhow::Error> {
+		let node = node::MovementNodeSupervisor::start(&config.movement, supervisor_config).await?;
+		let mut harness = HarnessMvtClient::build(config).await;
+		harness.node = Some(node);
+		Ok(harness)
+	}
+
+	/// Checks that the Movement node answers, and is still running if the harness started it.
+	pub async fn is_healthy(&self) -> bool {
+		match &self.node {
+			Some(node) => node.is_healthy().await,
+			None => self.rest_client.get_ledger_information().await.is_ok(),
+		}
 	}
 
 	pub async fn fund_account(&self) -> LocalAccount {
@@ -337,7 +384,7 @@ impl HarnessMvtClient {
 		self.faucet_client
 			.write()
 			.unwrap()
-			.fund(account.address(), 100_000_000)
+			.fund(account.address(), amounts::FAUCET_AMOUNT)
 			.await
 			.expect("Failed to fund account");
 		account
@@ -431,6 +478,15 @@ impl TestHarness {
 		Ok(bridge_config)
 	}
 
+	/// Read the bridge config and update it for the network of the profile.
+	pub async fn read_network_config(
+		profile: &network::NetworkProfile,
+	) -> Result<Config, anyhow::Error> {
+		let mut config = TestHarness::read_bridge_config().await?;
+		profile.apply(&mut config)?;
+		Ok(config)
+	}
+
 	pub async fn new_with_eth_and_movement(
 	) -> Result<(HarnessEthClient, HarnessMvtClient, Config), anyhow::Error> {
 		let config = TestHarness::read_bridge_config().await?;
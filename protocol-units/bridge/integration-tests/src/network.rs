@@ -0,0 +1,105 @@
+use aptos_sdk::crypto::{ed25519::Ed25519PrivateKey, ValidCryptoMaterialStringExt};
+use bridge_config::Config;
+use bridge_test_fixtures::amounts;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Env var selecting the network when there is no `--network` argument.
+pub const NETWORK_ENV: &str = "BRIDGE_TEST_NETWORK";
+/// Env var of the operator funded Ethereum key, holding ETH and MOVE on the testnet.
+pub const TESTNET_ETH_PRIVATE_KEY_ENV: &str = "BRIDGE_TESTNET_ETH_PRIVATE_KEY";
+/// Env var of the operator funded Movement key.
+pub const TESTNET_MOVEMENT_PRIVATE_KEY_ENV: &str = "BRIDGE_TESTNET_MOVEMENT_PRIVATE_KEY";
+/// Env var overriding the amount of the testnet transfers.
+pub const TESTNET_AMOUNT_ENV: &str = "BRIDGE_TESTNET_AMOUNT";
+
+/// Network the scenarios of the harness run against.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TestNetwork {
+	/// The local chains started by the process-compose setup.
+	#[default]
+	Local,
+	/// The Sepolia and Movement testnets, with the deployed contracts of the config.
+	Testnet,
+}
+
+impl FromStr for TestNetwork {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"local" => Ok(TestNetwork::Local),
+			"testnet" => Ok(TestNetwork::Testnet),
+			_ => Err(anyhow::anyhow!("Unknown network: {s}, expected local or testnet")),
+		}
+	}
+}
+
+impl TestNetwork {
+	/// The network of the `--network` argument, else of the `BRIDGE_TEST_NETWORK` env var,
+	/// local if none is set.
+	pub fn from_args_or_env(args: impl IntoIterator<Item = String>) -> Result<Self, anyhow::Error> {
+		let mut args = args.into_iter();
+		while let Some(arg) = args.next() {
+			if let Some(network) = arg.strip_prefix("--network=") {
+				return network.parse();
+			}
+			if arg == "--network" {
+				let network =
+					args.next().ok_or_else(|| anyhow::anyhow!("Missing --network value"))?;
+				return network.parse();
+			}
+		}
+		match std::env::var(NETWORK_ENV) {
+			Ok(network) => network.parse(),
+			Err(_) => Ok(TestNetwork::Local),
+		}
+	}
+}
+
+/// Parameters of the scenarios on a network.
+#[derive(Debug, Clone)]
+pub struct NetworkProfile {
+	pub network: TestNetwork,
+	/// Amount of the transfers, small on the testnet so the funded keys last.
+	pub amount: u64,
+	/// Timeout of each wait for an event or a transfer state.
+	pub timeout: Duration,
+}
+
+impl NetworkProfile {
+	pub fn new(network: TestNetwork) -> Result<Self, anyhow::Error> {
+		match network {
+			TestNetwork::Local => Ok(NetworkProfile {
+				network,
+				amount: amounts::TRANSFER_AMOUNT,
+				timeout: Duration::from_secs(30),
+			}),
+			TestNetwork::Testnet => {
+				let amount = match std::env::var(TESTNET_AMOUNT_ENV) {
+					Ok(amount) => amount.parse()?,
+					Err(_) => 1,
+				};
+				// The testnet blocks are slower and the relayer may be busy.
+				Ok(NetworkProfile { network, amount, timeout: Duration::from_secs(600) })
+			}
+		}
+	}
+
+	/// Update the bridge config read by the harness for the network. On the testnet the
+	/// clients of the harness sign with the operator funded keys. The local network keeps
+	/// the keys of the setup.
+	pub fn apply(&self, config: &mut Config) -> Result<(), anyhow::Error> {
+		if self.network == TestNetwork::Testnet {
+			config.eth.signer_private_key = required_env(TESTNET_ETH_PRIVATE_KEY_ENV)?;
+			config.movement.movement_signer_key = Ed25519PrivateKey::from_encoded_string(
+				&required_env(TESTNET_MOVEMENT_PRIVATE_KEY_ENV)?,
+			)?;
+		}
+		Ok(())
+	}
+}
+
+fn required_env(name: &str) -> Result<String, anyhow::Error> {
+	std::env::var(name).map_err(|_| anyhow::anyhow!("{name} must be set on the testnet"))
+}
@@ -0,0 +1,155 @@
+use bridge_config::common::movement::MovementConfig;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::{Child, Command};
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+/// How the Movement node is started and probed.
+#[derive(Debug, Clone)]
+pub struct SupervisorConfig {
+	/// How long to wait for the REST API and faucet to answer, per start attempt.
+	pub ready_timeout: Duration,
+	/// The delay between two readiness probes.
+	pub probe_interval: Duration,
+	/// How many times the node is restarted when it exits before being ready.
+	pub max_restarts: u32,
+}
+
+impl Default for SupervisorConfig {
+	fn default() -> Self {
+		Self {
+			ready_timeout: Duration::from_secs(120),
+			probe_interval: Duration::from_millis(500),
+			max_restarts: 2,
+		}
+	}
+}
+
+/// Runs a local Movement node for the tests and knows when it is ready.
+/// The node is considered ready once both its REST API and its faucet answer,
+/// so tests don't have to sleep after the start. The node is killed on drop.
+pub struct MovementNodeSupervisor {
+	child: Mutex<Child>,
+	rest_url: String,
+	faucet_url: String,
+	http: reqwest::Client,
+}
+
+impl MovementNodeSupervisor {
+	/// Starts the node and waits for it to be ready, restarting it if it crashes during the start.
+	pub async fn start(
+		config: &MovementConfig,
+		supervisor_config: SupervisorConfig,
+	) -> Result<Self, anyhow::Error> {
+		let rest_url = config.mvt_rpc_connection_url();
+		let faucet_url = config.mvt_faucet_connection_url();
+		let http = reqwest::Client::builder().timeout(Duration::from_secs(2)).build()?;
+
+		let mut attempt = 0;
+		loop {
+			let mut child = Self::spawn_node()?;
+			match Self::wait_ready(&mut child, &http, &rest_url, &faucet_url, &supervisor_config)
+				.await
+			{
+				Ok(()) => {
+					tracing::info!("Movement node is ready at {rest_url}");
+					return Ok(Self { child: Mutex::new(child), rest_url, faucet_url, http });
+				}
+				Err(StartError::Exited(status)) if attempt < supervisor_config.max_restarts => {
+					attempt += 1;
+					tracing::warn!(
+						"Movement node exited during start with {status}, restarting ({attempt}/{})",
+						supervisor_config.max_restarts
+					);
+				}
+				Err(StartError::Exited(status)) => {
+					anyhow::bail!("Movement node exited during start with {status}, giving up")
+				}
+				Err(StartError::Timeout) => {
+					let _ = child.kill().await;
+					anyhow::bail!(
+						"Movement node was not ready after {:?}",
+						supervisor_config.ready_timeout
+					)
+				}
+				Err(StartError::Io(e)) => {
+					let _ = child.kill().await;
+					return Err(e.into());
+				}
+			}
+		}
+	}
+
+	fn spawn_node() -> Result<Child, anyhow::Error> {
+		let mut child = Command::new("movement")
+			.args(["node", "run-local-testnet", "--force-restart", "--assume-yes"])
+			.stdout(Stdio::piped())
+			.stderr(Stdio::piped())
+			.kill_on_drop(true)
+			.spawn()?;
+
+		if let Some(stdout) = child.stdout.take() {
+			tokio::spawn(forward_output(stdout, "STDOUT"));
+		}
+		if let Some(stderr) = child.stderr.take() {
+			tokio::spawn(forward_output(stderr, "STDERR"));
+		}
+		Ok(child)
+	}
+
+	async fn wait_ready(
+		child: &mut Child,
+		http: &reqwest::Client,
+		rest_url: &str,
+		faucet_url: &str,
+		supervisor_config: &SupervisorConfig,
+	) -> Result<(), StartError> {
+		let deadline = Instant::now() + supervisor_config.ready_timeout;
+		loop {
+			if let Some(status) = child.try_wait().map_err(StartError::Io)? {
+				return Err(StartError::Exited(status));
+			}
+			if probe(http, rest_url, faucet_url).await {
+				return Ok(());
+			}
+			if Instant::now() >= deadline {
+				return Err(StartError::Timeout);
+			}
+			tokio::time::sleep(supervisor_config.probe_interval).await;
+		}
+	}
+
+	/// Checks that the node is still running and that its REST API and faucet answer.
+	pub async fn is_healthy(&self) -> bool {
+		let running = matches!(self.child.lock().await.try_wait(), Ok(None));
+		running && probe(&self.http, &self.rest_url, &self.faucet_url).await
+	}
+
+	/// Kills the node.
+	pub async fn stop(&self) -> Result<(), anyhow::Error> {
+		self.child.lock().await.kill().await?;
+		Ok(())
+	}
+}
+
+enum StartError {
+	Exited(std::process::ExitStatus),
+	Timeout,
+	Io(std::io::Error),
+}
+
+async fn probe(http: &reqwest::Client, rest_url: &str, faucet_url: &str) -> bool {
+	let ok = |url: String| async move {
+		http.get(url).send().await.map(|r| r.status().is_success()).unwrap_or(false)
+	};
+	ok(format!("{rest_url}/v1")).await && ok(faucet_url.to_string()).await
+}
+
+async fn forward_output<R: AsyncRead + Unpin>(reader: R, stream: &'static str) {
+	let mut lines = BufReader::new(reader).lines();
+	while let Ok(Some(line)) = lines.next_line().await {
+		tracing::debug!("movement node {stream}: {line}");
+	}
+}
@@ -0,0 +1,222 @@
+use bridge_config::Config;
+use bridge_service::approvals::ApprovalQueue;
+use bridge_service::chains::{
+	ethereum::{client::EthClient, event_monitoring::EthMonitoring},
+	movement::{client_framework::MovementClientFramework, event_monitoring::MovementMonitoring},
+};
+use bridge_service::circuit_breaker::CircuitBreaker;
+use bridge_service::event_bus::EventBus;
+use bridge_service::fees::RelayerFeeSchedule;
+use bridge_service::guards::EnabledDirections;
+use bridge_service::intake::IntakeLimit;
+use bridge_service::leader::{LeaderElection, LeaseStore, MemoryLeaseStore};
+use bridge_service::pause::PauseSwitches;
+use bridge_service::rate_limit::RateLimiter;
+use bridge_service::retry::RetryTable;
+use bridge_service::split::{SplitPolicy, SplitTransfers};
+use bridge_service::submissions::SentTransactions;
+use bridge_service::timelock::{LockMargin, TimeLockPolicy};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+
+/// A relayer loop running in the test process against the harness chains.
+/// All relayers built from the same config share the same signers,
+/// so concurrent relayers compete on the same transfers and nonces.
+pub struct HarnessRelayer {
+	pub id: usize,
+	/// Queue of the transfers waiting for an operator decision on this relayer.
+	pub approval_queue: ApprovalQueue,
+	/// Bus the relayer loop publishes its contract events, actions and state changes on.
+	pub event_bus: EventBus,
+	/// Election of the leader in coordination mode, the relayer loop runs only when elected.
+	pub election: Option<LeaderElection>,
+	health_tx: mpsc::Sender<oneshot::Sender<String>>,
+	join_handle: JoinHandle<Result<(), anyhow::Error>>,
+}
+
+impl HarnessRelayer {
+	pub async fn spawn(id: usize, config: &Config) -> Result<Self, anyhow::Error> {
+		Self::spawn_with_election(id, config, None).await
+	}
+
+	/// Spawn the relayer in coordination mode: it stands by until elected, and stops once it
+	/// loses the lease. The health checks are answered with `STANDBY` meanwhile.
+	pub async fn spawn_with_election(
+		id: usize,
+		config: &Config,
+		election: Option<LeaderElection>,
+	) -> Result<Self, anyhow::Error> {
+		let retry_table = RetryTable::try_from(&config.retry)?;
+		let stateless_verification = config.relayer.stateless_verification;
+		let enabled_directions = EnabledDirections::from(&config.guards);
+		let (approval_queue, approval_decision_rx) =
+			ApprovalQueue::new(config.guards.auto_approval_limit);
+
+		let (eth_health_tx, eth_health_rx) = mpsc::channel(10);
+		let mut eth_stream = EthMonitoring::build(&config.eth, eth_health_rx).await?;
+		let dropped_initiation_rx = eth_stream
+			.take_dropped_initiations()
+			.ok_or_else(|| anyhow::anyhow!("Dropped initiations already taken"))?;
+		let (sent_transactions, sent_transaction_rx) = SentTransactions::channel();
+		let mut eth_client = EthClient::new(&config.eth).await?;
+		eth_client.set_sent_transactions(sent_transactions.clone());
+		let (mvt_health_tx, mvt_health_rx) = mpsc::channel(10);
+		let mvt_stream = MovementMonitoring::build(&config.movement, mvt_health_rx).await?;
+		let mut mvt_client = MovementClientFramework::new(&config.movement).await?;
+		mvt_client.set_sent_transactions(sent_transactions);
+
+		let (health_tx, mut health_rx) = mpsc::channel(10);
+		let approval_queue_clone = approval_queue.clone();
+		let split_transfers = SplitTransfers::new(SplitPolicy::from(&config.split));
+		let pause_switches = PauseSwitches::new(config.eth.asset.clone());
+		let intake_limit = IntakeLimit::from(&config.relayer);
+		let rate_limiter = RateLimiter::from(&config.rate_limit);
+		let fee_schedule = RelayerFeeSchedule::from(&config.fees);
+		let circuit_breaker = CircuitBreaker::new(&config.circuit_breaker, pause_switches.clone());
+		// The heights of the chains aren't read, only the timestamp time locks are checked.
+		let (lock_margin, _) =
+			LockMargin::new(&config.timelock, TimeLockPolicy::from_config(&config.timelock)?);
+		let event_bus = EventBus::default();
+		let event_bus_clone = event_bus.clone();
+		let election_clone = election.clone();
+		let join_handle = tokio::spawn(async move {
+			if let Some(election) = &election_clone {
+				let acquire = election.acquire();
+				tokio::pin!(acquire);
+				loop {
+					tokio::select! {
+						_ = &mut acquire => break,
+						Some(oneshot_tx) = health_rx.recv() => {
+							let _ = oneshot_tx.send("STANDBY".to_string());
+						}
+					}
+				}
+			}
+			let shutdown = async move {
+				match election_clone {
+					Some(election) => election.hold(std::future::pending()).await,
+					None => std::future::pending().await,
+				}
+			};
+			bridge_service::run_bridge(
+				eth_client,
+				eth_stream,
+				mvt_client,
+				mvt_stream,
+				health_rx,
+				None,
+				eth_health_tx,
+				mvt_health_tx,
+				retry_table,
+				stateless_verification,
+				approval_queue_clone,
+				approval_decision_rx,
+				dropped_initiation_rx,
+				sent_transaction_rx,
+				enabled_directions,
+				event_bus_clone,
+				split_transfers,
+				pause_switches,
+				intake_limit,
+				rate_limiter,
+				fee_schedule,
+				circuit_breaker,
+				lock_margin,
+				None,
+				shutdown,
+			)
+			.await
+		});
+		tracing::info!("Harness relayer {id} started");
+
+		Ok(HarnessRelayer { id, approval_queue, event_bus, election, health_tx, join_handle })
+	}
+
+	/// Return the health status reported by the relayer loop.
+	pub async fn health(&self) -> Result<String, anyhow::Error> {
+		let (tx, rx) = oneshot::channel();
+		self.health_tx.send(tx).await?;
+		Ok(rx.await?)
+	}
+
+	pub fn is_running(&self) -> bool {
+		!self.join_handle.is_finished()
+	}
+
+	pub fn is_leader(&self) -> bool {
+		self.election.as_ref().is_some_and(|election| election.is_leader())
+	}
+
+	pub fn abort(self) {
+		tracing::info!("Harness relayer {} stopped", self.id);
+		self.join_handle.abort();
+	}
+}
+
+/// Several relayers running concurrently on the same chains,
+/// used to test duplicate completion handling and nonce contention.
+pub struct MultiRelayerHarness {
+	pub relayers: Vec<HarnessRelayer>,
+}
+
+impl MultiRelayerHarness {
+	pub async fn spawn(config: &Config, count: usize) -> Result<Self, anyhow::Error> {
+		let mut relayers = Vec::with_capacity(count);
+		for id in 0..count {
+			relayers.push(HarnessRelayer::spawn(id, config).await?);
+		}
+		Ok(MultiRelayerHarness { relayers })
+	}
+
+	/// Spawn relayers electing their leader with a lease of `lease` in a store shared by them.
+	pub async fn spawn_coordinated(
+		config: &Config,
+		count: usize,
+		lease: Duration,
+	) -> Result<Self, anyhow::Error> {
+		let store: Arc<dyn LeaseStore> = Arc::new(MemoryLeaseStore::default());
+		let mut relayers = Vec::with_capacity(count);
+		for id in 0..count {
+			let election = LeaderElection::new(store.clone(), format!("relayer-{id}"), lease);
+			relayers.push(HarnessRelayer::spawn_with_election(id, config, Some(election)).await?);
+		}
+		Ok(MultiRelayerHarness { relayers })
+	}
+
+	/// Index of the relayers holding the lease.
+	pub fn leaders(&self) -> Vec<usize> {
+		self.relayers
+			.iter()
+			.filter(|relayer| relayer.is_leader())
+			.map(|relayer| relayer.id)
+			.collect()
+	}
+
+	/// Stop the relayer, as if its process was killed.
+	pub fn kill(&mut self, id: usize) {
+		if let Some(index) = self.relayers.iter().position(|relayer| relayer.id == id) {
+			self.relayers.remove(index).abort();
+		}
+	}
+
+	pub fn running_count(&self) -> usize {
+		self.relayers.iter().filter(|relayer| relayer.is_running()).count()
+	}
+
+	/// Return the health status of each relayer.
+	pub async fn health(&self) -> Vec<Result<String, anyhow::Error>> {
+		let mut statuses = Vec::with_capacity(self.relayers.len());
+		for relayer in &self.relayers {
+			statuses.push(relayer.health().await);
+		}
+		statuses
+	}
+
+	pub fn shutdown(self) {
+		for relayer in self.relayers {
+			relayer.abort();
+		}
+	}
+}
@@ -145,7 +145,7 @@ pub async fn initiate_bridge_transfer_helper(
 	}
 
 	// Mint MovETH to the initiator's address
-	let mint_amount = 200 * 100_000_000; // Assuming 8 decimals for MovETH
+	let mint_amount = 200 * bridge_test_fixtures::amounts::ONE_MOVE;
 
 	let mint_args = vec![
 		movement_utils::serialize_address_initiator(&movement_client.signer().address())?, // Mint to initiator's address
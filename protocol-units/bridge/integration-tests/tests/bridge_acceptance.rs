@@ -0,0 +1,295 @@
+//! Release acceptance scenarios, run against the local chains or the public testnets:
+//! `cargo test -p bridge-integration-tests --test bridge_acceptance -- --network testnet`
+//! On the testnet, a relayer must run against the deployed contracts of the bridge config,
+//! and the operator funded keys are read from the env, see `bridge_integration_tests::network`.
+use alloy::primitives::keccak256;
+use bridge_config::Config;
+use bridge_integration_tests::network::{NetworkProfile, TestNetwork};
+use bridge_integration_tests::{HarnessEthClient, HarnessMvtClient, TestHarness};
+use bridge_service::chains::{
+	bridge_contracts::{BridgeContract, BridgeContractEvent, BridgeContractResult},
+	ethereum::{event_monitoring::EthMonitoring, types::EthAddress},
+	movement::{event_monitoring::MovementMonitoring, utils as movement_utils},
+};
+use bridge_service::types::{Amount, BridgeTransferId, HashLock, HashLockPreImage};
+use futures::{Stream, StreamExt};
+use std::fmt::Debug;
+use tracing_subscriber::EnvFilter;
+
+#[derive(Debug, Clone, Copy)]
+enum Direction {
+	EthToMovement,
+	MovementToEth,
+}
+
+// A transfer initiated by the run, until it's completed on both chains.
+#[derive(Debug)]
+struct RunTransfer {
+	direction: Direction,
+	hash_lock: HashLock,
+	pre_image: HashLockPreImage,
+	bridge_transfer_id: Option<BridgeTransferId>,
+	locked: bool,
+	completed: bool,
+}
+
+struct AcceptanceRun {
+	profile: NetworkProfile,
+	config: Config,
+	eth_harness: HarnessEthClient,
+	mvt_harness: HarnessMvtClient,
+	transfers: Vec<RunTransfer>,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), anyhow::Error> {
+	tracing_subscriber::fmt()
+		.with_env_filter(
+			EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+		)
+		.init();
+
+	let network = TestNetwork::from_args_or_env(std::env::args().skip(1))?;
+	let profile = NetworkProfile::new(network)?;
+	tracing::info!("Run the acceptance scenarios with {profile:?}");
+	let config = TestHarness::read_network_config(&profile).await?;
+	let mut run = AcceptanceRun {
+		eth_harness: HarnessEthClient::build(&config).await,
+		mvt_harness: HarnessMvtClient::build(&config).await,
+		profile,
+		config,
+		transfers: Vec::new(),
+	};
+
+	let result = run.scenarios().await;
+	run.cleanup().await;
+	result?;
+	tracing::info!("All acceptance scenarios passed");
+	Ok(())
+}
+
+fn initiated_with<A>(event: &BridgeContractEvent<A>, hash_lock: HashLock) -> bool {
+	matches!(event, BridgeContractEvent::Initiated(details) if details.hash_lock == hash_lock)
+}
+
+fn locked<A>(event: &BridgeContractEvent<A>, bridge_transfer_id: BridgeTransferId) -> bool {
+	matches!(
+		event,
+		BridgeContractEvent::Locked(details) if details.bridge_transfer_id == bridge_transfer_id
+	)
+}
+
+impl AcceptanceRun {
+	async fn scenarios(&mut self) -> Result<(), anyhow::Error> {
+		if self.profile.network == TestNetwork::Local {
+			// The testnet accounts are funded by the operator.
+			let signer_address = self.mvt_harness.movement_client.signer().address();
+			let faucet_client = self.mvt_harness.faucet_client.write().unwrap();
+			faucet_client
+				.fund(signer_address, bridge_test_fixtures::amounts::LARGE_FAUCET_AMOUNT)
+				.await?;
+		}
+		self.eth_to_movement().await?;
+		self.movement_to_eth().await?;
+		Ok(())
+	}
+
+	fn new_transfer(&mut self, direction: Direction) -> usize {
+		let pre_image = HashLockPreImage::random();
+		self.transfers.push(RunTransfer {
+			direction,
+			hash_lock: HashLock(From::from(keccak256(pre_image))),
+			pre_image,
+			bridge_transfer_id: None,
+			locked: false,
+			completed: false,
+		});
+		self.transfers.len() - 1
+	}
+
+	// The user initiates on Ethereum, the relayer locks on Movement, the recipient completes
+	// on Movement and the relayer completes on Ethereum.
+	async fn eth_to_movement(&mut self) -> Result<(), anyhow::Error> {
+		tracing::info!("Scenario: Ethereum to Movement transfer");
+		let index = self.new_transfer(Direction::EthToMovement);
+		let (hash_lock, pre_image) =
+			(self.transfers[index].hash_lock, self.transfers[index].pre_image);
+		let mut eth_monitoring = self.eth_monitoring().await?;
+		let mut mvt_monitoring = self.mvt_monitoring().await?;
+
+		let recipient =
+			movement_utils::MovementAddress(self.mvt_harness.movement_client.signer().address());
+		let amount = Amount(self.profile.amount);
+		match self.profile.network {
+			// The local initiator gets its tokens from the mock token.
+			TestNetwork::Local => {
+				self.eth_harness
+					.initiate_eth_bridge_transfer(
+						&self.config,
+						HarnessEthClient::get_initiator_private_key(&self.config),
+						recipient,
+						hash_lock,
+						amount,
+					)
+					.await?;
+			}
+			TestNetwork::Testnet => {
+				self.eth_harness
+					.initiate_funded_bridge_transfer(
+						&self.config,
+						self.eth_harness.signer_private_key.clone(),
+						recipient,
+						hash_lock,
+						amount,
+					)
+					.await?;
+			}
+		}
+		let event = self
+			.wait_event(&mut eth_monitoring, |event| initiated_with(event, hash_lock))
+			.await?;
+		let bridge_transfer_id = event.bridge_transfer_id();
+		self.transfers[index].bridge_transfer_id = Some(bridge_transfer_id);
+
+		self.wait_event(&mut mvt_monitoring, |event| locked(event, bridge_transfer_id))
+			.await?;
+		self.transfers[index].locked = true;
+
+		self.mvt_harness
+			.movement_client
+			.counterparty_complete_bridge_transfer(bridge_transfer_id, pre_image)
+			.await?;
+		self.wait_event(&mut eth_monitoring, |event| {
+			*event == BridgeContractEvent::InitiatorCompleted(bridge_transfer_id)
+		})
+		.await?;
+		self.transfers[index].completed = true;
+		tracing::info!("Ethereum to Movement transfer {bridge_transfer_id} completed");
+		Ok(())
+	}
+
+	// The user initiates on Movement, the relayer locks on Ethereum, the recipient completes
+	// on Ethereum and the relayer completes on Movement.
+	async fn movement_to_eth(&mut self) -> Result<(), anyhow::Error> {
+		tracing::info!("Scenario: Movement to Ethereum transfer");
+		let index = self.new_transfer(Direction::MovementToEth);
+		let (hash_lock, pre_image) =
+			(self.transfers[index].hash_lock, self.transfers[index].pre_image);
+		let mut eth_monitoring = self.eth_monitoring().await?;
+		let mut mvt_monitoring = self.mvt_monitoring().await?;
+
+		let initiator = movement_utils::create_local_account(
+			self.config.movement.movement_signer_key.clone(),
+			&self.mvt_harness.rest_client,
+		)
+		.await?;
+		let recipient = EthAddress(self.eth_harness.signer_address());
+		self.mvt_harness
+			.initiate_bridge_transfer(&initiator, recipient, hash_lock, self.profile.amount)
+			.await?;
+		let event = self
+			.wait_event(&mut mvt_monitoring, |event| initiated_with(event, hash_lock))
+			.await?;
+		let bridge_transfer_id = event.bridge_transfer_id();
+		self.transfers[index].bridge_transfer_id = Some(bridge_transfer_id);
+
+		self.wait_event(&mut eth_monitoring, |event| locked(event, bridge_transfer_id))
+			.await?;
+		self.transfers[index].locked = true;
+
+		self.eth_harness
+			.eth_client
+			.counterparty_complete_bridge_transfer(bridge_transfer_id, pre_image)
+			.await?;
+		self.wait_event(&mut mvt_monitoring, |event| {
+			*event == BridgeContractEvent::InitiatorCompleted(bridge_transfer_id)
+		})
+		.await?;
+		self.transfers[index].completed = true;
+		tracing::info!("Movement to Ethereum transfer {bridge_transfer_id} completed");
+		Ok(())
+	}
+
+	async fn eth_monitoring(&self) -> Result<EthMonitoring, anyhow::Error> {
+		let (_, eth_health_rx) = tokio::sync::mpsc::channel(10);
+		EthMonitoring::build(&self.config.eth, eth_health_rx).await
+	}
+
+	async fn mvt_monitoring(&self) -> Result<MovementMonitoring, anyhow::Error> {
+		let (_, mvt_health_rx) = tokio::sync::mpsc::channel(10);
+		MovementMonitoring::build(&self.config.movement, mvt_health_rx).await
+	}
+
+	// Wait for the first event matching, within the timeout of the network.
+	async fn wait_event<A: Debug, S>(
+		&self,
+		stream: &mut S,
+		matches: impl Fn(&BridgeContractEvent<A>) -> bool,
+	) -> Result<BridgeContractEvent<A>, anyhow::Error>
+	where
+		S: Stream<Item = BridgeContractResult<BridgeContractEvent<A>>> + Unpin,
+	{
+		let wait = async {
+			while let Some(event) = stream.next().await {
+				match event {
+					Ok(event) if matches(&event) => return Ok(event),
+					Ok(_) => (),
+					Err(err) => tracing::warn!("Event stream error: {err}"),
+				}
+			}
+			Err(anyhow::anyhow!("Event stream closed"))
+		};
+		tokio::time::timeout(self.profile.timeout, wait)
+			.await
+			.map_err(|_| anyhow::anyhow!("No matching event after {:?}", self.profile.timeout))?
+	}
+
+	// Complete the transfers left locked by a failed scenario, so the funds reach their
+	// recipient, and report the ones the relayer will refund once their time lock expires.
+	async fn cleanup(&mut self) {
+		for transfer in self.transfers.iter().filter(|transfer| !transfer.completed) {
+			let Some(bridge_transfer_id) = transfer.bridge_transfer_id else {
+				tracing::warn!(
+					"Transfer {:?} with hash lock {:?} not seen initiated",
+					transfer.direction,
+					transfer.hash_lock
+				);
+				continue;
+			};
+			if !transfer.locked {
+				tracing::warn!(
+					"Transfer {:?} {bridge_transfer_id} not locked, refunded after its time lock",
+					transfer.direction
+				);
+				continue;
+			}
+			let completed = match transfer.direction {
+				Direction::EthToMovement => {
+					self.mvt_harness
+						.movement_client
+						.counterparty_complete_bridge_transfer(
+							bridge_transfer_id,
+							transfer.pre_image,
+						)
+						.await
+				}
+				Direction::MovementToEth => {
+					self.eth_harness
+						.eth_client
+						.counterparty_complete_bridge_transfer(
+							bridge_transfer_id,
+							transfer.pre_image,
+						)
+						.await
+				}
+			};
+			match completed {
+				Ok(()) => tracing::info!("Cleanup completed transfer {bridge_transfer_id}"),
+				Err(err) => tracing::warn!(
+					"Cleanup failed to complete transfer {bridge_transfer_id}, preimage {:?}: {err}",
+					transfer.pre_image
+				),
+			}
+		}
+	}
+}
@@ -0,0 +1,227 @@
+use alloy::primitives::keccak256;
+use anyhow::Result;
+use aptos_sdk::types::account_address::AccountAddress;
+use bridge_integration_tests::adversarial;
+use bridge_integration_tests::{HarnessEthClient, HarnessMvtClient, TestHarness};
+use bridge_service::chains::bridge_contracts::{
+	BridgeContract, BridgeContractError, BridgeContractEvent,
+};
+use bridge_service::chains::ethereum::{event_monitoring::EthMonitoring, types::EthAddress};
+use bridge_service::chains::movement::utils::MovementAddress;
+use bridge_service::types::{
+	Amount, BridgeAddress, BridgeTransferDetails, BridgeTransferId, ChainId, HashLock,
+	HashLockPreImage, LockDetails,
+};
+use bridge_util::InvalidEventError;
+use futures::StreamExt;
+
+#[tokio::test]
+async fn test_eth_complete_with_wrong_preimage() -> Result<(), anyhow::Error> {
+	let _ = tracing_subscriber::fmt().with_max_level(tracing::Level::INFO).try_init();
+	let (mut eth_client_harness, config) =
+		TestHarness::new_only_eth().await.expect("Bridge config file not set");
+
+	let hash_lock_pre_image = HashLockPreImage::random();
+	let hash_lock = HashLock(From::from(keccak256(hash_lock_pre_image)));
+	let transfer_id = BridgeTransferId::gen_unique_hash(&mut rand::rngs::OsRng);
+	eth_client_harness
+		.eth_client
+		.lock_bridge_transfer(
+			transfer_id,
+			hash_lock,
+			BridgeAddress(vec![3; 32]),
+			BridgeAddress(EthAddress(HarnessEthClient::get_recipeint_address(&config))),
+			Amount(1),
+		)
+		.await?;
+
+	let res = adversarial::complete_with_wrong_preimage(
+		&mut eth_client_harness.eth_client,
+		transfer_id,
+		hash_lock,
+	)
+	.await;
+	assert!(
+		matches!(res, Err(BridgeContractError::OnChainError(_))),
+		"Completion with a wrong preimage must revert, got: {res:?}"
+	);
+
+	// The transfer can still be completed with the right preimage.
+	BridgeContract::counterparty_complete_bridge_transfer(
+		&mut eth_client_harness.eth_client,
+		transfer_id,
+		hash_lock_pre_image,
+	)
+	.await
+	.expect("Failed to complete bridge transfer");
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_movement_complete_with_wrong_preimage() -> Result<(), anyhow::Error> {
+	let _ = tracing_subscriber::fmt().with_max_level(tracing::Level::INFO).try_init();
+	let (mut mvt_client_harness, _config) =
+		TestHarness::new_with_movement().await.expect("Bridge config file not set");
+
+	let hash_lock_pre_image = HashLockPreImage::random();
+	let hash_lock = HashLock(From::from(keccak256(hash_lock_pre_image)));
+	let transfer_id = BridgeTransferId::gen_unique_hash(&mut rand::rngs::OsRng);
+	let recipient = bridge_test_fixtures::movement::RECIPIENT;
+	{
+		let faucet_client = mvt_client_harness.faucet_client.write().unwrap();
+		faucet_client
+			.fund(
+				mvt_client_harness.movement_client.signer().address(),
+				bridge_test_fixtures::amounts::FAUCET_AMOUNT,
+			)
+			.await?;
+		faucet_client
+			.fund(recipient, bridge_test_fixtures::amounts::FAUCET_AMOUNT)
+			.await?;
+	}
+
+	mvt_client_harness
+		.movement_client
+		.lock_bridge_transfer(
+			transfer_id,
+			hash_lock,
+			BridgeAddress(b"32Be343B94f860124dC4fEe278FDCBD38C102D88".to_vec()),
+			BridgeAddress(MovementAddress(recipient)),
+			Amount(1),
+		)
+		.await?;
+
+	let res = adversarial::complete_with_wrong_preimage(
+		&mut mvt_client_harness.movement_client,
+		transfer_id,
+		hash_lock,
+	)
+	.await;
+	assert!(
+		matches!(res, Err(BridgeContractError::CompleteTransferError)),
+		"Completion with a wrong preimage must fail, got: {res:?}"
+	);
+
+	let details = BridgeContract::get_bridge_transfer_details_counterparty(
+		&mut mvt_client_harness.movement_client,
+		transfer_id,
+	)
+	.await?
+	.expect("Expected to find bridge transfer details, but got None");
+	assert_eq!(details.state, 1, "Bridge transfer should still be pending.");
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_eth_complete_after_refund() -> Result<(), anyhow::Error> {
+	let _ = tracing_subscriber::fmt().with_max_level(tracing::Level::INFO).try_init();
+	let (mut eth_client_harness, config) =
+		TestHarness::new_only_eth().await.expect("Bridge config file not set");
+	let (_eth_health_tx, eth_health_rx) = tokio::sync::mpsc::channel(10);
+	let mut eth_monitoring = EthMonitoring::build(&config.eth, eth_health_rx).await.unwrap();
+
+	let recipient = HarnessMvtClient::gen_aptos_account();
+	let hash_lock_pre_image = HashLockPreImage::random();
+	let hash_lock = HashLock(From::from(keccak256(hash_lock_pre_image)));
+	eth_client_harness
+		.initiate_eth_bridge_transfer(
+			&config,
+			HarnessEthClient::get_initiator_private_key(&config),
+			MovementAddress(recipient.address()),
+			hash_lock,
+			Amount(1),
+		)
+		.await?;
+
+	let event_option =
+		tokio::time::timeout(std::time::Duration::from_secs(30), eth_monitoring.next())
+			.await
+			.expect("Timeout while waiting for the ETH Initiated event");
+	let bridge_transfer_id = match event_option {
+		Some(Ok(BridgeContractEvent::Initiated(detail))) => detail.bridge_transfer_id,
+		_ => panic!("Not an Initiated event: {:?}", event_option),
+	};
+
+	// Wait end of timelock
+	tokio::time::sleep(tokio::time::Duration::from_secs(20)).await;
+
+	let res = adversarial::complete_after_refund(
+		&mut eth_client_harness.eth_client,
+		bridge_transfer_id,
+		hash_lock_pre_image,
+	)
+	.await
+	.expect("Failed to refund bridge transfer");
+	assert!(
+		matches!(res, Err(BridgeContractError::OnChainError(_))),
+		"Completion of a refunded transfer must revert, got: {res:?}"
+	);
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_relayer_rejects_mismatched_lock() -> Result<(), anyhow::Error> {
+	let _ = tracing_subscriber::fmt().with_max_level(tracing::Level::INFO).try_init();
+	let (mut eth_client_harness, config) =
+		TestHarness::new_only_eth().await.expect("Bridge config file not set");
+
+	// A Movement to Eth transfer, initiated with this hash lock.
+	let hash_lock = HashLock(From::from(keccak256(HashLockPreImage::random())));
+	let transfer_id = BridgeTransferId::gen_unique_hash(&mut rand::rngs::OsRng);
+	let initiator = MovementAddress(AccountAddress::random());
+	let recipient = HarnessEthClient::get_recipeint_address(&config);
+
+	let mismatched_hash_lock = adversarial::lock_with_mismatched_hash_lock(
+		&mut eth_client_harness.eth_client,
+		transfer_id,
+		hash_lock,
+		BridgeAddress((&initiator).into()),
+		BridgeAddress(EthAddress(recipient)),
+		Amount(1),
+	)
+	.await?;
+
+	let locked = BridgeContract::get_bridge_transfer_details_counterparty(
+		&mut eth_client_harness.eth_client,
+		transfer_id,
+	)
+	.await?
+	.expect("Expected to find bridge transfer details, but got None");
+	assert_eq!(locked.hash_lock, mismatched_hash_lock);
+
+	let initiated = BridgeTransferDetails {
+		bridge_transfer_id: transfer_id,
+		initiator: BridgeAddress(initiator),
+		recipient: BridgeAddress(recipient.to_vec()),
+		hash_lock,
+		time_lock: locked.time_lock,
+		amount: Amount(1),
+		state: 1,
+	};
+	let lock = LockDetails {
+		bridge_transfer_id: locked.bridge_transfer_id,
+		initiator: locked.initiator.clone(),
+		recipient: locked.recipient.clone(),
+		hash_lock: locked.hash_lock,
+		time_lock: locked.time_lock,
+		amount: locked.amount,
+	};
+	let res = adversarial::relayer_validate_lock(ChainId::TWO, initiated.clone(), lock.clone());
+	assert!(
+		matches!(res, Err(InvalidEventError::HashLockMismatch)),
+		"The relayer must reject the mismatched lock, got: {res:?}"
+	);
+
+	// The same lock with the initiated hash lock is accepted.
+	let res = adversarial::relayer_validate_lock(
+		ChainId::TWO,
+		initiated,
+		LockDetails { hash_lock, ..lock },
+	);
+	assert!(res.is_ok(), "The relayer must accept the matching lock, got: {res:?}");
+
+	Ok(())
+}
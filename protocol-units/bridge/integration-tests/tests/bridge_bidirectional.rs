@@ -0,0 +1,211 @@
+use alloy::primitives::keccak256;
+use anyhow::Result;
+use bridge_indexer_db::models::SubmissionStatus;
+use bridge_integration_tests::relayer::HarnessRelayer;
+use bridge_integration_tests::{HarnessEthClient, TestHarness};
+use bridge_service::chains::{
+	bridge_contracts::BridgeContract,
+	ethereum::types::EthAddress,
+	movement::{client_framework::MovementClientFramework, utils::MovementAddress},
+};
+use bridge_service::event_bus::{BusEvent, Topic};
+use bridge_service::types::{Amount, ChainId, HashLock, HashLockPreImage};
+use bridge_util::states::TransferStateType;
+use bridge_util::types::BridgeTransferId;
+use bridge_util::TransferActionType;
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing_subscriber::EnvFilter;
+
+/// What the relayer published for one transfer.
+#[derive(Debug, Default)]
+struct TransferTrace {
+	/// Chain of the Initiated event of the transfer.
+	initiator_chain: Option<ChainId>,
+	states: Vec<(u64, Option<TransferStateType>, TransferStateType)>,
+	/// Chain of each action confirmed on chain, i.e. each transaction the relayer paid gas for.
+	confirmed_actions: Vec<(ChainId, &'static str)>,
+}
+
+impl TransferTrace {
+	fn state(&self) -> Option<TransferStateType> {
+		self.states.last().map(|(_, _, state)| *state)
+	}
+}
+
+// Follow the bus until all the transfers initiated on the given chains are in the state.
+async fn wait_for_state(
+	events: &mut bridge_service::event_bus::Subscription,
+	traces: &mut HashMap<BridgeTransferId, TransferTrace>,
+	chains: &[ChainId],
+	state: TransferStateType,
+) -> Result<(), anyhow::Error> {
+	loop {
+		let reached = chains.iter().all(|chain| {
+			traces
+				.values()
+				.any(|trace| trace.initiator_chain == Some(*chain) && trace.state() == Some(state))
+		});
+		if reached {
+			return Ok(());
+		}
+		let event = tokio::time::timeout(Duration::from_secs(60), events.recv())
+			.await?
+			.ok_or_else(|| anyhow::anyhow!("Relayer event bus closed"))?;
+		match event {
+			BusEvent::StateChange(change) => {
+				let trace = traces.entry(change.transfer_id).or_default();
+				if change.previous.is_none() {
+					trace.initiator_chain = change.cause.map(|cause| cause.chain);
+				}
+				trace.states.push((change.sequence, change.previous, change.new));
+			}
+			BusEvent::Action(action, SubmissionStatus::Confirmed) => {
+				let kind = match action.kind {
+					TransferActionType::LockBridgeTransfer { .. } => "lock",
+					TransferActionType::WaitAndCompleteInitiator(..) => "complete",
+					TransferActionType::RefundInitiator => "refund",
+					_ => continue,
+				};
+				traces
+					.entry(action.transfer_id)
+					.or_default()
+					.confirmed_actions
+					.push((action.chain, kind));
+			}
+			_ => (),
+		}
+	}
+}
+
+#[tokio::test]
+async fn test_bridge_transfer_both_directions_concurrently() -> Result<(), anyhow::Error> {
+	tracing_subscriber::fmt()
+		.with_env_filter(
+			EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+		)
+		.init();
+
+	MovementClientFramework::bridge_setup_scripts().await?;
+
+	let (mut eth_client_harness, mut mvt_client_harness, config) =
+		TestHarness::new_with_eth_and_movement().await?;
+	let relayer = HarnessRelayer::spawn(0, &config).await?;
+	let mut events = relayer.event_bus.subscribe(&[Topic::StateChanges, Topic::Actions]);
+
+	let movement_client_signer_address = mvt_client_harness.movement_client.signer().address();
+	{
+		let faucet_client = mvt_client_harness.faucet_client.write().unwrap();
+		faucet_client
+			.fund(
+				movement_client_signer_address,
+				bridge_test_fixtures::amounts::LARGE_FAUCET_AMOUNT,
+			)
+			.await?;
+	}
+	let mvt_recipient_privkey = mvt_client_harness.fund_account().await;
+	let mvt_recipient = MovementAddress(mvt_recipient_privkey.address());
+	let mvt_initiator = mvt_client_harness.fund_account().await;
+	let eth_recipient = EthAddress(HarnessEthClient::get_initiator(&config));
+
+	// Initiate one transfer in each direction at the same time.
+	let eth_pre_image = HashLockPreImage::random();
+	let eth_hash_lock = HashLock(From::from(keccak256(eth_pre_image)));
+	let mvt_pre_image = HashLockPreImage::random();
+	let mvt_hash_lock = HashLock(From::from(keccak256(mvt_pre_image)));
+	let (eth_initiated, mvt_initiated) = tokio::join!(
+		eth_client_harness.initiate_eth_bridge_transfer(
+			&config,
+			HarnessEthClient::get_initiator_private_key(&config),
+			mvt_recipient,
+			eth_hash_lock,
+			Amount(1),
+		),
+		mvt_client_harness.initiate_bridge_transfer(
+			&mvt_initiator,
+			eth_recipient,
+			mvt_hash_lock,
+			1
+		),
+	);
+	eth_initiated?;
+	mvt_initiated?;
+
+	// The relayer locks both transfers on their counterparty chain.
+	let mut traces = HashMap::new();
+	wait_for_state(
+		&mut events,
+		&mut traces,
+		&[ChainId::ONE, ChainId::TWO],
+		TransferStateType::Locked,
+	)
+	.await?;
+	let transfer_id = |chain| {
+		traces
+			.iter()
+			.find(|(_, trace)| trace.initiator_chain == Some(chain))
+			.map(|(transfer_id, _)| *transfer_id)
+			.expect("Transfer initiated on the chain")
+	};
+	let eth_transfer_id = transfer_id(ChainId::ONE);
+	let mvt_transfer_id = transfer_id(ChainId::TWO);
+	assert_ne!(eth_transfer_id, mvt_transfer_id);
+
+	// Both recipients complete their side at the same time.
+	let (mvt_completed, eth_completed) = tokio::join!(
+		mvt_client_harness.counterparty_complete_bridge_transfer(
+			mvt_recipient_privkey,
+			eth_transfer_id,
+			eth_pre_image,
+		),
+		eth_client_harness
+			.eth_client
+			.counterparty_complete_bridge_transfer(mvt_transfer_id, mvt_pre_image),
+	);
+	mvt_completed?;
+	eth_completed?;
+
+	wait_for_state(
+		&mut events,
+		&mut traces,
+		&[ChainId::ONE, ChainId::TWO],
+		TransferStateType::Completed,
+	)
+	.await?;
+
+	// Only the two transfers went through the relayer.
+	assert_eq!(traces.len(), 2, "Unexpected transfers {:?}", traces.keys().collect::<Vec<_>>());
+	for (transfer_id, initiator_chain) in
+		[(eth_transfer_id, ChainId::ONE), (mvt_transfer_id, ChainId::TWO)]
+	{
+		let trace = &traces[&transfer_id];
+
+		// The sequence numbers of each transfer start at 1 and follow each other,
+		// whatever the changes of the other direction in between.
+		let mut previous_state = None;
+		for (index, (sequence, previous, _)) in trace.states.iter().enumerate() {
+			assert_eq!(*sequence, index as u64 + 1, "Transfer {transfer_id:?}: {trace:?}");
+			assert_eq!(*previous, previous_state, "Transfer {transfer_id:?}: {trace:?}");
+			previous_state = Some(trace.states[index].2);
+		}
+		assert!(
+			trace.states.iter().all(|(_, _, state)| !matches!(
+				state,
+				TransferStateType::Refund | TransferStateType::PendingApproval
+			)),
+			"Transfer {transfer_id:?}: {trace:?}"
+		);
+
+		// One lock on the counterparty chain and one completion on the initiator chain
+		// were paid for the transfer, none on the other direction's chains.
+		assert_eq!(
+			trace.confirmed_actions,
+			vec![(initiator_chain.other(), "lock"), (initiator_chain, "complete")],
+			"Transfer {transfer_id:?}"
+		);
+	}
+
+	assert_eq!(relayer.health().await?, "OK");
+	relayer.abort();
+	Ok(())
+}
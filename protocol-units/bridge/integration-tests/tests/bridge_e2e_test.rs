@@ -92,7 +92,9 @@ async fn test_bridge_transfer_eth_movement_happy_path() -> Result<(), anyhow::Er
 
 	{
 		let faucet_client = mvt_client_harness.faucet_client.write().unwrap();
-		faucet_client.fund(movement_client_signer_address, 100_000_000).await?;
+		faucet_client
+			.fund(movement_client_signer_address, bridge_test_fixtures::amounts::FAUCET_AMOUNT)
+			.await?;
 	}
 
 	let recipient_privkey = mvt_client_harness.fund_account().await;
@@ -0,0 +1,263 @@
+use alloy::eips::BlockId;
+use alloy::primitives::{keccak256, Address, FixedBytes, U256};
+use alloy::providers::{Provider, ProviderBuilder};
+use alloy::signers::local::PrivateKeySigner;
+use alloy_network::EthereumWallet;
+use anyhow::Result;
+use bridge_config::Config;
+use bridge_integration_tests::relayer::MultiRelayerHarness;
+use bridge_integration_tests::HarnessEthClient;
+use bridge_integration_tests::TestHarness;
+use bridge_service::chains::bridge_contracts::BridgeContractEvent;
+use bridge_service::chains::ethereum::event_monitoring::EthMonitoring;
+use bridge_service::chains::ethereum::types::AtomicBridgeInitiatorMOVE;
+use bridge_service::chains::ethereum::utils::{send_transaction, send_transaction_rules};
+use bridge_service::chains::movement::{
+	event_monitoring::MovementMonitoring, utils::MovementAddress,
+};
+use bridge_service::types::{Amount, BridgeTransferId, HashLock, HashLockPreImage};
+use futures::StreamExt;
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing_subscriber::EnvFilter;
+
+// Time to wait for the lock of a transfer, and for a duplicate lock once it's locked.
+const LOCK_TIMEOUT: Duration = Duration::from_secs(30);
+const DUPLICATE_LOCK_WINDOW: Duration = Duration::from_secs(5);
+// Same for the completion of a transfer on Eth.
+const COMPLETE_TIMEOUT: Duration = Duration::from_secs(30);
+const DUPLICATE_COMPLETE_WINDOW: Duration = Duration::from_secs(5);
+
+fn init_tracing() {
+	let _ = tracing_subscriber::fmt()
+		.with_env_filter(
+			EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+		)
+		.try_init();
+}
+
+// Initiate a transfer to the recipient on Eth, all the relayers see the Initiated event.
+async fn initiate_transfer(
+	config: &Config,
+	recipient: MovementAddress,
+) -> Result<HashLock, anyhow::Error> {
+	let (hash_lock, _) = initiate_transfer_with_secret(config, recipient).await?;
+	Ok(hash_lock)
+}
+
+// Initiate a transfer like `initiate_transfer`, returning the secret to complete it.
+async fn initiate_transfer_with_secret(
+	config: &Config,
+	recipient: MovementAddress,
+) -> Result<(HashLock, HashLockPreImage), anyhow::Error> {
+	let initiator_privkey = HarnessEthClient::get_initiator_private_key(config);
+	let initiator_address = initiator_privkey.address();
+	let rpc_provider = ProviderBuilder::new()
+		.with_recommended_fillers()
+		.wallet(EthereumWallet::from(initiator_privkey))
+		.on_builtin(&config.eth.eth_rpc_connection_url())
+		.await?;
+	let contract =
+		AtomicBridgeInitiatorMOVE::new(config.eth.eth_initiator_contract.parse()?, &rpc_provider);
+	let hash_lock_pre_image = HashLockPreImage::random();
+	let hash_lock = HashLock(From::from(keccak256(hash_lock_pre_image)));
+	let amount = Amount(1);
+	let recipient_bytes: [u8; 32] = Into::<Vec<u8>>::into(recipient)
+		.try_into()
+		.expect("Recipient address must be 32 bytes");
+	let call = contract
+		.initiateBridgeTransfer(
+			U256::from(amount.0),
+			FixedBytes(recipient_bytes),
+			FixedBytes(hash_lock.0),
+		)
+		.from(initiator_address);
+	send_transaction(
+		call,
+		initiator_address,
+		&send_transaction_rules(),
+		config.eth.transaction_send_retries,
+		config.eth.gas_limit as u128,
+	)
+	.await?;
+	Ok((hash_lock, hash_lock_pre_image))
+}
+
+// Count the Locked events of the transfer on Movement, until the window for a duplicate lock
+// after the first one has passed, or until no lock came in time.
+async fn count_locks(config: &Config, hash_lock: HashLock) -> Result<usize, anyhow::Error> {
+	let (_, mvt_health_rx) = tokio::sync::mpsc::channel(10);
+	let mut mvt_monitoring = MovementMonitoring::build(&config.movement, mvt_health_rx).await?;
+	let mut locked_count = 0;
+	let mut deadline = Instant::now() + LOCK_TIMEOUT;
+	while let Ok(event) = tokio::time::timeout_at(deadline, mvt_monitoring.next()).await {
+		match event {
+			Some(Ok(BridgeContractEvent::Locked(details))) if details.hash_lock == hash_lock => {
+				locked_count += 1;
+				deadline = deadline.min(Instant::now() + DUPLICATE_LOCK_WINDOW);
+			}
+			Some(_) => (),
+			None => break,
+		}
+	}
+	Ok(locked_count)
+}
+
+// Wait for the first Locked event of the transfer on Movement, and return its id.
+async fn wait_for_lock(
+	config: &Config,
+	hash_lock: HashLock,
+) -> Result<BridgeTransferId, anyhow::Error> {
+	let (_, mvt_health_rx) = tokio::sync::mpsc::channel(10);
+	let mut mvt_monitoring = MovementMonitoring::build(&config.movement, mvt_health_rx).await?;
+	let deadline = Instant::now() + LOCK_TIMEOUT;
+	loop {
+		match tokio::time::timeout_at(deadline, mvt_monitoring.next()).await? {
+			Some(Ok(BridgeContractEvent::Locked(details))) if details.hash_lock == hash_lock => {
+				return Ok(details.bridge_transfer_id)
+			}
+			Some(_) => (),
+			None => anyhow::bail!("Movement event stream closed before the lock"),
+		}
+	}
+}
+
+// Count the InitiatorCompleted events of the transfer on Eth, the same way as `count_locks`.
+async fn count_completions(
+	config: &Config,
+	bridge_transfer_id: BridgeTransferId,
+) -> Result<usize, anyhow::Error> {
+	let (_, eth_health_rx) = tokio::sync::mpsc::channel(10);
+	let mut eth_monitoring = EthMonitoring::build(&config.eth, eth_health_rx).await?;
+	let mut completed_count = 0;
+	let mut deadline = Instant::now() + COMPLETE_TIMEOUT;
+	while let Ok(event) = tokio::time::timeout_at(deadline, eth_monitoring.next()).await {
+		match event {
+			Some(Ok(BridgeContractEvent::InitiatorCompleted(id))) if id == bridge_transfer_id => {
+				completed_count += 1;
+				deadline = deadline.min(Instant::now() + DUPLICATE_COMPLETE_WINDOW);
+			}
+			Some(_) => (),
+			None => break,
+		}
+	}
+	Ok(completed_count)
+}
+
+// Number of transactions of the account still waiting in the mempool.
+async fn pending_transaction_count(
+	config: &Config,
+	address: Address,
+) -> Result<u64, anyhow::Error> {
+	let rpc_provider =
+		ProviderBuilder::new().on_builtin(&config.eth.eth_rpc_connection_url()).await?;
+	let pending = rpc_provider.get_transaction_count(address).block_id(BlockId::pending()).await?;
+	let mined = rpc_provider.get_transaction_count(address).block_id(BlockId::latest()).await?;
+	Ok(pending - mined)
+}
+
+#[tokio::test]
+async fn test_two_relayers_lock_transfer_once() -> Result<(), anyhow::Error> {
+	init_tracing();
+
+	let (_eth_client_harness, mvt_client_harness, config) =
+		TestHarness::new_with_eth_and_movement().await?;
+	let relayers = MultiRelayerHarness::spawn(&config, 2).await?;
+
+	let recipient_privkey = mvt_client_harness.fund_account().await;
+	let hash_lock =
+		initiate_transfer(&config, MovementAddress(recipient_privkey.address())).await?;
+
+	// Only one Locked event must be emitted for the transfer.
+	let locked_count = count_locks(&config, hash_lock).await?;
+	assert_eq!(locked_count, 1, "Transfer locked {locked_count} times");
+
+	// The losing relayer must survive the duplicate lock failure.
+	assert_eq!(relayers.running_count(), 2);
+	for status in relayers.health().await {
+		assert_eq!(status?, "OK");
+	}
+
+	relayers.shutdown();
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_two_relayers_complete_transfer_once() -> Result<(), anyhow::Error> {
+	init_tracing();
+
+	let (_eth_client_harness, mut mvt_client_harness, config) =
+		TestHarness::new_with_eth_and_movement().await?;
+	let relayers = MultiRelayerHarness::spawn(&config, 2).await?;
+	// The relayers share the Eth signer, so their completions compete on the same nonces.
+	let relayer_address = config.eth.signer_private_key.parse::<PrivateKeySigner>()?.address();
+
+	let recipient_privkey = mvt_client_harness.fund_account().await;
+	let recipient = MovementAddress(recipient_privkey.address());
+	let (hash_lock, hash_lock_pre_image) =
+		initiate_transfer_with_secret(&config, recipient.clone()).await?;
+	let bridge_transfer_id = wait_for_lock(&config, hash_lock).await?;
+
+	// Both relayers see the completion on Movement and race on completing the transfer on Eth.
+	mvt_client_harness
+		.counterparty_complete_bridge_transfer(
+			recipient_privkey,
+			bridge_transfer_id,
+			hash_lock_pre_image,
+		)
+		.await?;
+	let completed_count = count_completions(&config, bridge_transfer_id).await?;
+	assert_eq!(completed_count, 1, "Transfer completed {completed_count} times");
+
+	// The losing relayer must survive the failed completion without leaving its nonce stuck.
+	assert_eq!(relayers.running_count(), 2);
+	for status in relayers.health().await {
+		assert_eq!(status?, "OK");
+	}
+	let pending_count = pending_transaction_count(&config, relayer_address).await?;
+	assert_eq!(pending_count, 0, "{pending_count} transactions of the relayers stuck");
+
+	// The relayers still send transactions after the race.
+	let hash_lock = initiate_transfer(&config, recipient).await?;
+	let locked_count = count_locks(&config, hash_lock).await?;
+	assert_eq!(locked_count, 1, "Transfer locked {locked_count} times after the race");
+
+	relayers.shutdown();
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_coordinated_relayers_fail_over() -> Result<(), anyhow::Error> {
+	init_tracing();
+
+	let (_eth_client_harness, mvt_client_harness, config) =
+		TestHarness::new_with_eth_and_movement().await?;
+	let lease = Duration::from_secs(3);
+	let mut relayers = MultiRelayerHarness::spawn_coordinated(&config, 2, lease).await?;
+
+	// Exactly one relayer is elected, the other stands by.
+	tokio::time::sleep(Duration::from_secs(1)).await;
+	let leaders = relayers.leaders();
+	assert_eq!(leaders.len(), 1, "Leaders elected: {leaders:?}");
+	let leader = leaders[0];
+	let standby = 1 - leader;
+	assert_eq!(relayers.relayers[leader].health().await?, "OK");
+	assert_eq!(relayers.relayers[standby].health().await?, "STANDBY");
+
+	let recipient_privkey = mvt_client_harness.fund_account().await;
+	let recipient = MovementAddress(recipient_privkey.address());
+	let hash_lock = initiate_transfer(&config, recipient.clone()).await?;
+	let locked_count = count_locks(&config, hash_lock).await?;
+	assert_eq!(locked_count, 1, "Transfer locked {locked_count} times");
+
+	// Once the lease of the killed leader expires, the relayer standing by takes over.
+	relayers.kill(leader);
+	tokio::time::sleep(lease * 2).await;
+	assert_eq!(relayers.leaders(), vec![standby]);
+	let hash_lock = initiate_transfer(&config, recipient).await?;
+	let locked_count = count_locks(&config, hash_lock).await?;
+	assert_eq!(locked_count, 1, "Transfer locked {locked_count} times after the fail over");
+
+	relayers.shutdown();
+	Ok(())
+}
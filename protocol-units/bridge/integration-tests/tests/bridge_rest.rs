@@ -1,5 +1,8 @@
 use bridge_config::Config;
+use bridge_service::catchup::{CatchUpProgress, ETH_CHAIN, MOVEMENT_CHAIN};
+use bridge_service::guards::TransferGuards;
 use bridge_service::rest::BridgeRest;
+use poem::http::StatusCode;
 use poem::test::TestClient;
 use std::sync::Arc;
 use tracing_subscriber::EnvFilter;
@@ -50,3 +53,105 @@ async fn test_rest_service_health_endpoint() -> Result<(), anyhow::Error> {
 
 	Ok(())
 }
+
+#[tokio::test]
+async fn test_rest_service_precheck_endpoint() -> Result<(), anyhow::Error> {
+	let mock_config = Config::default();
+	let (health_tx, _health_rx) = tokio::sync::mpsc::channel(10);
+	let rest_service = BridgeRest::new(&mock_config.movement, health_tx)?
+		.with_guards(TransferGuards::from(&mock_config));
+	let client = TestClient::new(rest_service.create_routes());
+
+	// Valid transfer to a 32 bytes Movement recipient.
+	let response = client
+		.post("/precheck")
+		.body_json(&serde_json::json!({
+			"direction": "eth_to_movement",
+			"token": mock_config.eth.asset,
+			"amount": 100,
+			"recipient": format!("0x{}", "01".repeat(32)),
+		}))
+		.send()
+		.await;
+	response.assert_status_is_ok();
+	let json = response.json().await;
+	json.value().object().get("pass").assert_bool(true);
+
+	// Unknown token and Eth address used as Movement recipient.
+	let response = client
+		.post("/precheck")
+		.body_json(&serde_json::json!({
+			"direction": "eth_to_movement",
+			"token": "NOTATOKEN",
+			"amount": 100,
+			"recipient": format!("0x{}", "01".repeat(20)),
+		}))
+		.send()
+		.await;
+	response.assert_status_is_ok();
+	let json = response.json().await;
+	json.value().object().get("pass").assert_bool(false);
+	json.value().object().get("failures").array().assert_len(2);
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_rest_service_api_versions() -> Result<(), anyhow::Error> {
+	let mock_config = Config::default();
+	let (health_tx, _health_rx) = tokio::sync::mpsc::channel(10);
+	let rest_service = BridgeRest::new(&mock_config.movement, health_tx)?;
+	let client = TestClient::new(rest_service.create_routes());
+
+	// The latest version returns the errors as JSON without deprecation header.
+	let response = client.get("/v2/transfers").send().await;
+	response.assert_status(StatusCode::SERVICE_UNAVAILABLE);
+	response.assert_header_is_not_exist("deprecation");
+	let json = response.json().await;
+	json.value()
+		.object()
+		.get("error")
+		.assert_string("Transfer search is not enabled");
+
+	// The v1 and unversioned paths keep the plain text errors and link to the latest version.
+	for path in ["/v1/transfers", "/transfers"] {
+		let response = client.get(path).send().await;
+		response.assert_status(StatusCode::SERVICE_UNAVAILABLE);
+		response.assert_header("deprecation", "true");
+		response.assert_header("link", "</v2/transfers>; rel=\"successor-version\"");
+		response.assert_text("Transfer search is not enabled").await;
+	}
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_rest_service_readiness() -> Result<(), anyhow::Error> {
+	let mock_config = Config::default();
+	let (health_tx, _health_rx) = tokio::sync::mpsc::channel(10);
+	let catch_up = CatchUpProgress::default();
+	catch_up.register(ETH_CHAIN);
+	catch_up.register(MOVEMENT_CHAIN);
+	let rest_service =
+		BridgeRest::new(&mock_config.movement, health_tx)?.with_catch_up(catch_up.clone());
+	let client = TestClient::new(rest_service.create_routes());
+
+	// Not ready while a monitoring is replaying the history.
+	catch_up.record_scan(ETH_CHAIN, 100, Some(100), 0);
+	catch_up.mark_caught_up(ETH_CHAIN);
+	catch_up.record_scan(MOVEMENT_CHAIN, 30, None, 30);
+	let response = client.get("/readyz").send().await;
+	response.assert_status(StatusCode::SERVICE_UNAVAILABLE);
+	let json = response.json().await;
+	json.value().object().get("ready").assert_bool(false);
+	let movement = json.value().object().get("chains").object().get("movement").object();
+	movement.get("events_found").assert_i64(30);
+	movement.get("caught_up").assert_bool(false);
+
+	catch_up.mark_caught_up(MOVEMENT_CHAIN);
+	let response = client.get("/readyz").send().await;
+	response.assert_status_is_ok();
+	response.json().await.value().object().get("ready").assert_bool(true);
+
+	Ok(())
+}
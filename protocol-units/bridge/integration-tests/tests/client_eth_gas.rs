@@ -0,0 +1,66 @@
+use alloy::primitives::keccak256;
+use bridge_config::Config;
+use bridge_integration_tests::HarnessEthClient;
+use bridge_integration_tests::TestHarness;
+use bridge_service::chains::bridge_contracts::BridgeContract;
+use bridge_service::chains::ethereum::{client::EthClient, types::EthAddress};
+use bridge_service::rpc_metrics::{GasUsage, RpcMetrics};
+use bridge_service::types::{Amount, BridgeAddress, BridgeTransferId, HashLock, HashLockPreImage};
+use std::collections::BTreeMap;
+
+const ROUNDS: usize = 5;
+
+// Lock and complete transfers on the counterparty contract, returning the gas used per operation.
+async fn measure_gas(
+	config: &Config,
+	use_access_lists: bool,
+) -> Result<BTreeMap<String, GasUsage>, anyhow::Error> {
+	let mut eth_config = config.eth.clone();
+	eth_config.use_access_lists = use_access_lists;
+	let mut eth_client = EthClient::new(&eth_config).await?;
+	let rpc_metrics = RpcMetrics::default();
+	eth_client.set_rpc_metrics(rpc_metrics.clone());
+
+	for _ in 0..ROUNDS {
+		let hash_lock_pre_image = HashLockPreImage::random();
+		let hash_lock = HashLock(From::from(keccak256(hash_lock_pre_image)));
+		let transfer_id = BridgeTransferId::gen_unique_hash(&mut rand::rngs::OsRng);
+		eth_client
+			.lock_bridge_transfer(
+				transfer_id,
+				hash_lock,
+				BridgeAddress(vec![3; 32]),
+				BridgeAddress(EthAddress(HarnessEthClient::get_recipeint_address(config))),
+				Amount(1),
+			)
+			.await?;
+		eth_client
+			.counterparty_complete_bridge_transfer(transfer_id, hash_lock_pre_image)
+			.await?;
+	}
+	Ok(rpc_metrics.gas_usage())
+}
+
+/// Gas per operation with and without access lists. Run with `--nocapture` to see the report.
+#[tokio::test]
+async fn test_eth_client_gas_per_operation() -> Result<(), anyhow::Error> {
+	let _ = tracing_subscriber::fmt().with_max_level(tracing::Level::INFO).try_init();
+	let (_eth_client_harness, config) = TestHarness::new_only_eth().await?;
+
+	let baseline = measure_gas(&config, false).await?;
+	let with_access_lists = measure_gas(&config, true).await?;
+
+	println!("{:<24}{:>16}{:>20}", "operation", "avg gas", "avg gas access list");
+	for (operation, usage) in &baseline {
+		let optimized = with_access_lists.get(operation).copied().unwrap_or_default();
+		println!("{operation:<24}{:>16}{:>20}", usage.average(), optimized.average());
+		assert_eq!(usage.transactions, ROUNDS as u64);
+		assert_eq!(optimized.transactions, ROUNDS as u64);
+	}
+	assert_eq!(
+		baseline.keys().collect::<Vec<_>>(),
+		vec!["complete_counterparty", "lock"],
+		"Unexpected operations {baseline:?}"
+	);
+	Ok(())
+}
@@ -1,7 +1,6 @@
 use alloy::primitives::keccak256;
 use anyhow::Result;
 use aptos_sdk::coin_client::CoinClient;
-use aptos_sdk::types::account_address::AccountAddress;
 use bridge_integration_tests::utils as test_utils;
 use bridge_integration_tests::{EthToMovementCallArgs, MovementToEthCallArgs, TestHarness};
 use bridge_service::chains::bridge_contracts::BridgeContractEvent;
@@ -29,18 +28,22 @@ async fn test_movement_client_counterparty_complete_transfer() -> Result<(), any
 	let amount = Amount(1);
 	let transfer_id = BridgeTransferId::gen_unique_hash(&mut rand::rngs::OsRng);
 	let initiator = b"32Be343B94f860124dC4fEe278FDCBD38C102D88".to_vec();
-	let recipient = AccountAddress::new(*b"0x00000000000000000000000000face");
+	let recipient = bridge_test_fixtures::movement::RECIPIENT;
 
 	let coin_client = CoinClient::new(&mvt_client_harness.rest_client);
 	let movement_client_signer = mvt_client_harness.movement_client.signer();
 	{
 		let faucet_client = mvt_client_harness.faucet_client.write().unwrap();
-		faucet_client.fund(movement_client_signer.address(), 100_000_000).await?;
-		faucet_client.fund(recipient, 100_000_000).await?;
+		faucet_client
+			.fund(movement_client_signer.address(), bridge_test_fixtures::amounts::FAUCET_AMOUNT)
+			.await?;
+		faucet_client
+			.fund(recipient, bridge_test_fixtures::amounts::FAUCET_AMOUNT)
+			.await?;
 	}
 	let balance = coin_client.get_account_balance(&movement_client_signer.address()).await?;
 	assert!(
-		balance >= 100_000_000,
+		balance >= bridge_test_fixtures::amounts::FAUCET_AMOUNT,
 		"Expected Movement Client to have at least 100_000_000, but found {}",
 		balance
 	);
@@ -68,9 +71,7 @@ async fn test_movement_client_counterparty_complete_transfer() -> Result<(), any
 	assert_eq!(details.state, 1, "Bridge transfer should be pending.");
 	info!("Bridge transfer details: {:?}", details);
 
-	let secret = b"secret";
-	let mut padded_secret = [0u8; 32];
-	padded_secret[..secret.len()].copy_from_slice(secret);
+	let padded_secret = bridge_test_fixtures::secrets::padded_secret();
 
 	BridgeContract::counterparty_complete_bridge_transfer(
 		&mut mvt_client_harness.movement_client,
@@ -106,8 +107,11 @@ async fn test_movement_client_initiate_transfer() -> Result<(), anyhow::Error> {
 	let args = MovementToEthCallArgs::default();
 
 	let test_result = async {
-		test_utils::fund_and_check_balance_framework(&mut mvt_client_harness, 100_000_000_000)
-			.await?;
+		test_utils::fund_and_check_balance_framework(
+			&mut mvt_client_harness,
+			bridge_test_fixtures::amounts::LARGE_FAUCET_AMOUNT,
+		)
+		.await?;
 
 		{
 			let res = BridgeContract::initiate_bridge_transfer(
@@ -181,12 +185,14 @@ async fn test_movement_client_abort_transfer() -> Result<(), anyhow::Error> {
 
 	{
 		let faucet_client = mvt_client_harness.faucet_client.write().unwrap();
-		faucet_client.fund(movement_client_signer.address(), 100_000_000).await?;
+		faucet_client
+			.fund(movement_client_signer.address(), bridge_test_fixtures::amounts::FAUCET_AMOUNT)
+			.await?;
 	}
 
 	let balance = coin_client.get_account_balance(&movement_client_signer.address()).await?;
 	assert!(
-		balance >= 100_000_000,
+		balance >= bridge_test_fixtures::amounts::FAUCET_AMOUNT,
 		"Expected Movement Client to have at least 100_000_000, but found {}",
 		balance
 	);
@@ -215,9 +221,7 @@ async fn test_movement_client_abort_transfer() -> Result<(), anyhow::Error> {
 
 	sleep(Duration::from_secs(20)).await;
 
-	let secret = b"secret";
-	let mut padded_secret = [0u8; 32];
-	padded_secret[..secret.len()].copy_from_slice(secret);
+	let padded_secret = bridge_test_fixtures::secrets::padded_secret();
 
 	BridgeContract::abort_bridge_transfer(
 		&mut mvt_client_harness.movement_client,
@@ -251,7 +255,11 @@ async fn test_movement_client_initiator_complete_transfer() -> Result<(), anyhow
 	let (mut mvt_client_harness, config) =
 		TestHarness::new_with_movement().await.expect("Bridge config file not set");
 	let args = MovementToEthCallArgs::default();
-	test_utils::fund_and_check_balance_framework(&mut mvt_client_harness, 100_000_000_000).await?;
+	test_utils::fund_and_check_balance_framework(
+		&mut mvt_client_harness,
+		bridge_test_fixtures::amounts::LARGE_FAUCET_AMOUNT,
+	)
+	.await?;
 	{
 		let res = BridgeContract::initiate_bridge_transfer(
 			&mut mvt_client_harness.movement_client,
@@ -325,7 +333,11 @@ async fn test_movement_client_refund_transfer() -> Result<(), anyhow::Error> {
 	let (mut mvt_client_harness, config) =
 		TestHarness::new_with_movement().await.expect("Bridge config file not set");
 	let args = MovementToEthCallArgs::default();
-	test_utils::fund_and_check_balance_framework(&mut mvt_client_harness, 100_000_000_000).await?;
+	test_utils::fund_and_check_balance_framework(
+		&mut mvt_client_harness,
+		bridge_test_fixtures::amounts::LARGE_FAUCET_AMOUNT,
+	)
+	.await?;
 
 	{
 		let res = BridgeContract::initiate_bridge_transfer(
@@ -0,0 +1,21 @@
+script {
+    use std::vector;
+    use aptos_framework::atomic_bridge_initiator;
+
+    fun complete_bridge_transfers(
+        caller: &signer,
+        bridge_transfer_ids: vector<vector<u8>>,
+        pre_images: vector<vector<u8>>
+    ) {
+        let i = 0;
+        let len = vector::length(&bridge_transfer_ids);
+        while (i < len) {
+            atomic_bridge_initiator::complete_bridge_transfer(
+                caller,
+                *vector::borrow(&bridge_transfer_ids, i),
+                *vector::borrow(&pre_images, i)
+            );
+            i = i + 1;
+        };
+    }
+}
@@ -0,0 +1,27 @@
+script {
+    use std::vector;
+    use aptos_framework::atomic_bridge_counterparty;
+
+    fun lock_bridge_transfers(
+        caller: &signer,
+        initiators: vector<vector<u8>>,
+        bridge_transfer_ids: vector<vector<u8>>,
+        hash_locks: vector<vector<u8>>,
+        recipients: vector<address>,
+        amounts: vector<u64>
+    ) {
+        let i = 0;
+        let len = vector::length(&bridge_transfer_ids);
+        while (i < len) {
+            atomic_bridge_counterparty::lock_bridge_transfer_assets(
+                caller,
+                *vector::borrow(&initiators, i),
+                *vector::borrow(&bridge_transfer_ids, i),
+                *vector::borrow(&hash_locks, i),
+                *vector::borrow(&recipients, i),
+                *vector::borrow(&amounts, i)
+            );
+            i = i + 1;
+        };
+    }
+}
@@ -0,0 +1,264 @@
+//! Benchmarks of the relayer hot paths: the decoding of the contract events of both chains,
+//! the transitions of the transfer state machine and the indexer store.
+//!
+//! Save a baseline on the base branch, then compare the change against it:
+//! ```text
+//! cargo bench -p bridge-service --bench hot_paths -- --save-baseline main
+//! cargo bench -p bridge-service --bench hot_paths -- --baseline main
+//! ```
+//! The run fails when a benchmark is slower than the baseline by more than the threshold
+//! of its group. The store benchmarks run only when `BRIDGE_INDEXER_DATABASE_URL` is set.
+use alloy::primitives::{Bytes, LogData, B256};
+use bridge_indexer_db::client::Client as IndexerClient;
+use bridge_indexer_db::encryption::FieldCipher;
+use bridge_service::chains::ethereum::event_decoding::{
+	decode_counterparty_log, decode_initiator_log,
+};
+use bridge_service::chains::movement::event_monitoring::BridgeInitEventData;
+use bridge_service::chains::movement::utils::MovementAddress;
+use bridge_util::types::{
+	Amount, BridgeAddress, BridgeTransferDetails, BridgeTransferId, HashLock, HashLockPreImage,
+	LockDetails, TimeLock,
+};
+use bridge_util::{BridgeContractEvent, ChainId, InitiatedTransfer, TransferEvent, TransferState};
+use criterion::{black_box, BatchSize, Criterion};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+const BRIDGE_LOGS: &str = include_str!("../abis/fixtures/bridge_logs.json");
+
+// Data of a Movement initiated event, as returned by the node event API.
+const MOVEMENT_INITIATED_EVENT: &str = r#"{
+	"amount": "100",
+	"bridge_transfer_id": "0xeaefd189df98d57b8f4619584cff1fd67f2787c664ac8e9761ecfd7a6ae1fa2b",
+	"hash_lock": "0xfb54fb738082d0214980feb4055e779d7d4722cb0809d5fbe79df8117801c3bb",
+	"initiator": "0xf90391c81027f03cdea491ed8b36ffaced26b6df208a9b569e5baf2590eb9b16",
+	"recipient": "0x1ad4b06b0b4b5c5a2e3c5d7e9b2f1a3c4d5e6f70",
+	"time_lock": "1",
+	"state": 1
+}"#;
+
+// Changes of the mean time against the baseline below the noise threshold are ignored.
+const NOISE_THRESHOLD: f64 = 0.02;
+
+// Maximum slowdown of the mean time of the benchmarks of each group against the baseline.
+// The store benchmarks go through a database, they are noisier.
+const REGRESSION_THRESHOLDS: &[(&str, f64)] =
+	&[("event_decoding", 0.10), ("state_machine", 0.10), ("store", 0.25)];
+
+fn fixture_log(event: &str) -> LogData {
+	let fixtures: Vec<serde_json::Value> = serde_json::from_str(BRIDGE_LOGS).unwrap();
+	let fixture = fixtures.iter().find(|fixture| fixture["event"] == event).unwrap();
+	let topics = fixture["topics"]
+		.as_array()
+		.unwrap()
+		.iter()
+		.map(|topic| B256::from_str(topic.as_str().unwrap()).unwrap())
+		.collect();
+	let data = Bytes::from_str(fixture["data"].as_str().unwrap()).unwrap();
+	LogData::new(topics, data).unwrap()
+}
+
+fn bench_event_decoding(c: &mut Criterion) {
+	let mut group = c.benchmark_group("event_decoding");
+	let initiated = fixture_log("BridgeTransferInitiated");
+	group.bench_function("eth_initiated_log", |b| {
+		b.iter(|| decode_initiator_log(black_box(&initiated)).unwrap())
+	});
+	let locked = fixture_log("BridgeTransferLocked");
+	group.bench_function("eth_locked_log", |b| {
+		b.iter(|| decode_counterparty_log(black_box(&locked)).unwrap())
+	});
+	// The Movement monitoring decodes the JSON data of the events returned by the node.
+	group.bench_function("movement_initiated_event", |b| {
+		b.iter(|| {
+			let data: BridgeInitEventData =
+				serde_json::from_str(black_box(MOVEMENT_INITIATED_EVENT)).unwrap();
+			BridgeTransferDetails::<MovementAddress>::try_from(data).unwrap()
+		})
+	});
+	group.finish();
+}
+
+fn transfer_details(transfer_id: BridgeTransferId) -> BridgeTransferDetails<Vec<u8>> {
+	BridgeTransferDetails {
+		bridge_transfer_id: transfer_id,
+		initiator: BridgeAddress(vec![1; 20]),
+		recipient: BridgeAddress(vec![2; 32]),
+		hash_lock: HashLock([3; 32]),
+		time_lock: TimeLock(600),
+		amount: Amount(100),
+		state: 0,
+	}
+}
+
+// Validate and apply the event on the state, as the relayer does for each contract event.
+fn transition(
+	state: TransferState,
+	chain: ChainId,
+	contract_event: BridgeContractEvent<Vec<u8>>,
+) -> TransferState {
+	let event = TransferEvent { chain, contract_event };
+	state.validate_event(&event).unwrap();
+	state.apply_event(event.contract_event).unwrap().0
+}
+
+fn bench_state_machine(c: &mut Criterion) {
+	let mut group = c.benchmark_group("state_machine");
+	let transfer_id = BridgeTransferId([4; 32]);
+	let details = transfer_details(transfer_id);
+	let lock_details = LockDetails {
+		bridge_transfer_id: transfer_id,
+		initiator: BridgeAddress(vec![1; 20]),
+		recipient: BridgeAddress(vec![2; 32]),
+		hash_lock: details.hash_lock,
+		time_lock: details.time_lock,
+		amount: details.amount,
+	};
+	// Initiated on chain one, locked and completed on chain two, completed on chain one.
+	group.bench_function("complete_transfer", |b| {
+		b.iter_batched(
+			|| (details.clone(), lock_details.clone()),
+			|(details, lock_details)| {
+				let (transfer, _) = InitiatedTransfer::new(ChainId::ONE, transfer_id, details);
+				let state = transition(
+					transfer.into(),
+					ChainId::TWO,
+					BridgeContractEvent::Locked(lock_details),
+				);
+				let state = transition(
+					state,
+					ChainId::TWO,
+					BridgeContractEvent::CounterPartyCompleted(
+						transfer_id,
+						HashLockPreImage([5; 32]),
+					),
+				);
+				transition(
+					state,
+					ChainId::ONE,
+					BridgeContractEvent::InitiatorCompleted(transfer_id),
+				)
+			},
+			BatchSize::SmallInput,
+		)
+	});
+	group.finish();
+}
+
+fn bench_store(c: &mut Criterion) {
+	let mut client = match IndexerClient::from_env() {
+		Ok(client) => client,
+		Err(err) => {
+			eprintln!("Skip the store benchmarks, no indexer db: {err}");
+			return;
+		}
+	};
+	client.run_migrations().unwrap();
+	let mut encrypting_client =
+		IndexerClient::from_env().unwrap().with_cipher(FieldCipher::new([6; 32]));
+
+	let mut group = c.benchmark_group("store");
+	// Each write indexes a new transfer.
+	let mut next_id = rand::random::<u64>();
+	let mut new_transfer_id = move || {
+		next_id = next_id.wrapping_add(1);
+		let mut id = [0; 32];
+		id[..8].copy_from_slice(&next_id.to_be_bytes());
+		BridgeTransferId(id)
+	};
+	for (name, client) in [("plain", &mut client), ("encrypted", &mut encrypting_client)] {
+		group.bench_function(format!("write_events_{name}"), |b| {
+			b.iter(|| {
+				let transfer_id = new_transfer_id();
+				client
+					.insert_bridge_contract_event(BridgeContractEvent::Initiated(transfer_details(
+						transfer_id,
+					)))
+					.unwrap();
+				client
+					.insert_bridge_contract_event(
+						BridgeContractEvent::<Vec<u8>>::CounterPartyCompleted(
+							transfer_id,
+							HashLockPreImage([5; 32]),
+						),
+					)
+					.unwrap();
+			})
+		});
+		group.bench_function(format!("read_events_{name}"), |b| {
+			let transfer_id = new_transfer_id();
+			client
+				.insert_bridge_contract_event(
+					BridgeContractEvent::<Vec<u8>>::CounterPartyCompleted(
+						transfer_id,
+						HashLockPreImage([5; 32]),
+					),
+				)
+				.unwrap();
+			b.iter(|| {
+				let events = client.find_all_events_for_bridge_transfer_id(transfer_id).unwrap();
+				client
+					.counter_party_pre_image(&events.counter_party_completed_events[0])
+					.unwrap()
+			})
+		});
+	}
+	group.finish();
+}
+
+// Directory of the criterion reports.
+fn criterion_home() -> PathBuf {
+	match (std::env::var("CRITERION_HOME"), std::env::var("CARGO_TARGET_DIR")) {
+		(Ok(home), _) => PathBuf::from(home),
+		(_, Ok(target)) => PathBuf::from(target).join("criterion"),
+		_ => PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../../target/criterion"),
+	}
+}
+
+// Return the benchmarks slower than the baseline by more than the threshold of their group,
+// with their change of mean time.
+fn regressions() -> Vec<(String, f64)> {
+	let mut regressions = Vec::new();
+	for (group, threshold) in REGRESSION_THRESHOLDS {
+		let Ok(benches) = std::fs::read_dir(criterion_home().join(group)) else {
+			continue;
+		};
+		for bench in benches.flatten() {
+			// The change is only there when the benchmark has been compared to a baseline.
+			let Ok(estimates) = std::fs::read_to_string(bench.path().join("change/estimates.json"))
+			else {
+				continue;
+			};
+			let estimates: serde_json::Value = serde_json::from_str(&estimates).unwrap();
+			let Some(change) = estimates["mean"]["point_estimate"].as_f64() else {
+				continue;
+			};
+			if change > *threshold {
+				regressions
+					.push((format!("{group}/{}", bench.file_name().to_string_lossy()), change));
+			}
+		}
+	}
+	regressions
+}
+
+fn main() {
+	let mut criterion = Criterion::default().noise_threshold(NOISE_THRESHOLD).configure_from_args();
+	bench_event_decoding(&mut criterion);
+	bench_state_machine(&mut criterion);
+	bench_store(&mut criterion);
+	criterion.final_summary();
+
+	// Nothing is measured when the benchmarks are only run as tests.
+	if !std::env::args().any(|arg| arg == "--bench") {
+		return;
+	}
+	let regressions = regressions();
+	for (bench, change) in &regressions {
+		eprintln!("Regression of {bench}: {:+.1}% of mean time", change * 100.0);
+	}
+	if !regressions.is_empty() {
+		std::process::exit(1);
+	}
+}
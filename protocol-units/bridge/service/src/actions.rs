@@ -1,21 +1,27 @@
 use crate::chains::movement::utils as movement_utils;
+use crate::telemetry::transfer_span;
 use bridge_util::chains::bridge_contracts::BridgeContract;
 use bridge_util::chains::bridge_contracts::BridgeContractError;
-use bridge_util::types::BridgeAddress;
+use bridge_util::chains::bridge_contracts::{BatchResults, BridgeContractResult};
+use bridge_util::types::{BridgeAddress, LockTransfer};
 use bridge_util::ActionExecError;
 use bridge_util::TransferAction;
 use bridge_util::TransferActionType;
 use std::future::Future;
 use std::pin::Pin;
+use tracing::Instrument;
 
+/// Build the execution of the action, returning the action once executed on chain.
 pub fn process_action<A>(
 	action: TransferAction,
 	mut client: impl BridgeContract<A> + 'static,
-) -> Option<Pin<Box<dyn Future<Output = Result<(), ActionExecError>> + Send>>>
+) -> Option<Pin<Box<dyn Future<Output = Result<TransferAction, ActionExecError>> + Send>>>
 where
 	A: Clone + Send + TryFrom<Vec<u8>>,
 {
-	tracing::info!("Action: creating execution for action:{action}");
+	// The execution and the calls of the contracts run in the span of the transfer.
+	let span = transfer_span(action.transfer_id);
+	span.in_scope(|| tracing::info!("Action: creating execution for action:{action}"));
 	match action.kind.clone() {
 		TransferActionType::LockBridgeTransfer {
 			bridge_transfer_id,
@@ -45,9 +51,10 @@ where
 						amount,
 					)
 					.await
-					.map_err(|err| ActionExecError(action, err))
+					.map_err(|err| ActionExecError(action.clone(), err))?;
+				Ok(action)
 			};
-			Some(Box::pin(future))
+			Some(Box::pin(future.instrument(span)))
 		}
 		TransferActionType::WaitAndCompleteInitiator(wait_time_sec, secret) => {
 			let future = async move {
@@ -57,12 +64,152 @@ where
 				client
 					.initiator_complete_bridge_transfer(action.transfer_id, secret)
 					.await
-					.map_err(|err| ActionExecError(action, err))
+					.map_err(|err| ActionExecError(action.clone(), err))?;
+				Ok(action)
 			};
-			Some(Box::pin(future))
+			Some(Box::pin(future.instrument(span)))
+		}
+		TransferActionType::AbortCounterparty => {
+			let future = async move {
+				client
+					.abort_bridge_transfer(action.transfer_id)
+					.await
+					.map_err(|err| ActionExecError(action.clone(), err))?;
+				Ok(action)
+			};
+			Some(Box::pin(future.instrument(span)))
 		}
 		TransferActionType::RefundInitiator => None,
 		TransferActionType::TransferDone => None,
 		TransferActionType::NoAction => None,
 	}
 }
+
+// Most actions sent in a single batch transaction.
+const MAX_BATCH_SIZE: usize = 16;
+
+/// Split the actions released together into batches of the locks, and of the completions not
+/// waiting, of each chain, and the actions executed one by one.
+pub fn batch_actions(
+	actions: Vec<TransferAction>,
+) -> (Vec<Vec<TransferAction>>, Vec<TransferAction>) {
+	let mut batches: Vec<Vec<TransferAction>> = Vec::new();
+	let mut single = Vec::new();
+	for action in actions {
+		if !matches!(
+			action.kind,
+			TransferActionType::LockBridgeTransfer { .. }
+				| TransferActionType::WaitAndCompleteInitiator(0, _)
+		) {
+			single.push(action);
+			continue;
+		}
+		let batch = batches.iter_mut().find(|batch| {
+			batch.len() < MAX_BATCH_SIZE
+				&& batch[0].chain == action.chain
+				&& std::mem::discriminant(&batch[0].kind) == std::mem::discriminant(&action.kind)
+		});
+		match batch {
+			Some(batch) => batch.push(action),
+			None => batches.push(vec![action]),
+		}
+	}
+	// An action alone in its batch is executed alone.
+	let (batches, alone): (Vec<_>, Vec<_>) = batches.into_iter().partition(|batch| batch.len() > 1);
+	single.extend(alone.into_iter().flatten());
+	(batches, single)
+}
+
+/// Build the execution of a batch of actions returned by `batch_actions`, returning the result
+/// of each action once the batch is executed on chain.
+pub fn process_batch<A>(
+	actions: Vec<TransferAction>,
+	mut client: impl BridgeContract<A> + 'static,
+) -> Pin<Box<dyn Future<Output = Vec<Result<TransferAction, ActionExecError>>> + Send>>
+where
+	A: Clone + Send + TryFrom<Vec<u8>>,
+{
+	tracing::info!("Action: creating execution for a batch of {} actions", actions.len());
+	Box::pin(async move {
+		let mut results = Vec::with_capacity(actions.len());
+		let mut lock_actions = Vec::new();
+		let mut locks = Vec::new();
+		let mut complete_actions = Vec::new();
+		let mut completions = Vec::new();
+		for action in actions {
+			match action.kind.clone() {
+				TransferActionType::LockBridgeTransfer {
+					bridge_transfer_id,
+					hash_lock,
+					initiator,
+					recipient,
+					amount,
+				} => {
+					if recipient.0.len() == 32 {
+						if let Err(err) = movement_utils::fund_recipient(&recipient).await {
+							results.push(Err(ActionExecError(action, err)));
+							continue;
+						}
+					}
+					let Ok(recipient) = recipient.0.try_into() else {
+						let err = BridgeContractError::BadAddressEncoding(
+							"lock bridge transfer fail to convert recipient address to vec<u8>"
+								.to_string(),
+						);
+						results.push(Err(ActionExecError(action, err)));
+						continue;
+					};
+					locks.push(LockTransfer {
+						bridge_transfer_id,
+						hash_lock,
+						initiator,
+						recipient: BridgeAddress(recipient),
+						amount,
+					});
+					lock_actions.push(action);
+				}
+				TransferActionType::WaitAndCompleteInitiator(_, secret) => {
+					completions.push((action.transfer_id, secret));
+					complete_actions.push(action);
+				}
+				_ => {
+					if let Some(future) = process_action(action, client.clone()) {
+						results.push(future.await);
+					}
+				}
+			}
+		}
+		if !locks.is_empty() {
+			let batch_result = client.lock_bridge_transfers_batch(locks).await;
+			results.extend(action_results(lock_actions, batch_result));
+		}
+		if !completions.is_empty() {
+			let batch_result = client.complete_bridge_transfers_batch(completions).await;
+			results.extend(action_results(complete_actions, batch_result));
+		}
+		results
+	})
+}
+
+// Pair the actions of a batch with their result, all failed with the batch if it failed.
+fn action_results(
+	actions: Vec<TransferAction>,
+	batch_result: BridgeContractResult<BatchResults>,
+) -> Vec<Result<TransferAction, ActionExecError>> {
+	let results = match batch_result {
+		Ok(results) => results,
+		Err(err) => actions.iter().map(|_| Err(err.clone())).collect(),
+	};
+	// An action without result is failed, so it's retried rather than forgotten.
+	let results = results
+		.into_iter()
+		.chain(std::iter::repeat_with(|| Err(BridgeContractError::InvalidResponseLength)));
+	actions
+		.into_iter()
+		.zip(results)
+		.map(|(action, result)| match result {
+			Ok(()) => Ok(action),
+			Err(err) => Err(ActionExecError(action, err)),
+		})
+		.collect()
+}
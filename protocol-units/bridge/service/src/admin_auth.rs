@@ -0,0 +1,101 @@
+//! Authentication of the operators on the admin API. Each operator has a bearer token, the
+//! operator of a request is the one of its token, never a name in the payload, so the audit
+//! trail records who actually made a change.
+use bridge_config::common::admin::AdminConfig;
+use bridge_util::reason::{ReasonCode, WithReasonCode};
+use std::sync::Arc;
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AdminAuthError {
+	#[error("Missing bearer token")]
+	MissingToken,
+	#[error("Unknown operator token")]
+	UnknownToken,
+}
+
+impl WithReasonCode for AdminAuthError {
+	fn reason_code(&self) -> ReasonCode {
+		ReasonCode::Unauthorized
+	}
+}
+
+/// Tokens of the operators. Without any token all the requests are rejected.
+/// Clones share the same tokens.
+#[derive(Debug, Clone, Default)]
+pub struct AdminAuth {
+	// (operator, token)
+	operators: Arc<Vec<(String, String)>>,
+}
+
+impl AdminAuth {
+	pub fn new(operators: Vec<(String, String)>) -> Self {
+		AdminAuth { operators: Arc::new(operators) }
+	}
+
+	/// Read the tokens file of the config, one `<operator> <token>` pair per line.
+	pub fn from_config(config: &AdminConfig) -> Result<Self, anyhow::Error> {
+		if config.tokens_file.is_empty() {
+			tracing::warn!("No admin tokens, the admin changes are rejected");
+			return Ok(AdminAuth::default());
+		}
+		let content = std::fs::read_to_string(&config.tokens_file).map_err(|e| {
+			anyhow::anyhow!("Failed to read admin tokens {}: {e}", config.tokens_file)
+		})?;
+		let mut operators = Vec::new();
+		for line in content.lines().map(str::trim).filter(|line| !line.is_empty()) {
+			match line.split_whitespace().collect::<Vec<_>>()[..] {
+				[operator, token] => operators.push((operator.to_string(), token.to_string())),
+				_ => anyhow::bail!("Invalid line in admin tokens {}", config.tokens_file),
+			}
+		}
+		Ok(AdminAuth::new(operators))
+	}
+
+	/// The operator of the `Authorization` header value.
+	pub fn authenticate(&self, authorization: Option<&str>) -> Result<String, AdminAuthError> {
+		let token = authorization
+			.and_then(|value| value.strip_prefix("Bearer "))
+			.map(str::trim)
+			.filter(|token| !token.is_empty())
+			.ok_or(AdminAuthError::MissingToken)?;
+		// All the tokens are compared, in constant time, so the timing doesn't leak them.
+		let mut found = None;
+		for (operator, expected) in self.operators.iter() {
+			if constant_time_eq(token.as_bytes(), expected.as_bytes()) {
+				found = Some(operator.clone());
+			}
+		}
+		found.ok_or(AdminAuthError::UnknownToken)
+	}
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+	if a.len() != b.len() {
+		return false;
+	}
+	a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_authenticate_operator() {
+		let auth = AdminAuth::new(vec![
+			("alice".to_string(), "alice-token".to_string()),
+			("bob".to_string(), "bob-token".to_string()),
+		]);
+		assert_eq!(auth.authenticate(Some("Bearer bob-token")), Ok("bob".to_string()));
+		assert_eq!(
+			auth.authenticate(Some("Bearer carol-token")),
+			Err(AdminAuthError::UnknownToken)
+		);
+		assert_eq!(auth.authenticate(Some("bob-token")), Err(AdminAuthError::MissingToken));
+		assert_eq!(auth.authenticate(None), Err(AdminAuthError::MissingToken));
+		// Without any token, all the requests are rejected.
+		let none = AdminAuth::default();
+		assert_eq!(none.authenticate(Some("Bearer bob-token")), Err(AdminAuthError::UnknownToken));
+	}
+}
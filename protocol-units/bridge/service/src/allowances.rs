@@ -0,0 +1,273 @@
+//! Garbage collection of the ERC-20 allowances granted by the relayer wallet. The spenders the
+//! wallet approved are found in the Approval events of the tokens. An allowance left to a
+//! spender that is no longer a bridge contract, after a token pair removal or a contract
+//! migration, is revoked so the spender can't pull the funds of the hot wallet.
+use crate::chains::ethereum::client::EthClient;
+use alloy::primitives::Address;
+use bridge_config::common::allowance_gc::AllowanceGcConfig;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashSet};
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// Blocks scanned per query of the Approval events.
+const SCANNED_BLOCKS: u64 = 10_000;
+
+/// An allowance of the wallet to a spender that isn't a bridge contract.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OrphanedAllowance {
+	pub token: String,
+	pub spender: String,
+	/// Allowance found, in the smallest unit of the token.
+	pub allowance: String,
+	/// Hash of the revocation, missing in dry run or if it failed.
+	pub revocation_tx: Option<String>,
+}
+
+/// Result of the last collection.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AllowanceGcStatus {
+	/// Unix timestamp of the last collection, missing until the first one.
+	pub collected_at: Option<u64>,
+	/// Last block scanned for the approvals of the wallet.
+	pub scanned_to_block: Option<u64>,
+	pub orphaned: Vec<OrphanedAllowance>,
+	/// Allowances revoked since the start.
+	pub revoked: u64,
+}
+
+/// Results of the collections. Clones share the same results.
+#[derive(Debug, Clone, Default)]
+pub struct AllowanceGcMetrics {
+	status: Arc<Mutex<AllowanceGcStatus>>,
+}
+
+impl AllowanceGcMetrics {
+	fn lock(&self) -> std::sync::MutexGuard<'_, AllowanceGcStatus> {
+		self.status.lock().expect("Allowance collection metrics lock poisoned")
+	}
+
+	fn record(&self, orphaned: Vec<OrphanedAllowance>, scanned_to_block: u64, now_secs: u64) {
+		let mut status = self.lock();
+		status.revoked +=
+			orphaned.iter().filter(|allowance| allowance.revocation_tx.is_some()).count() as u64;
+		status.orphaned = orphaned;
+		status.scanned_to_block = Some(scanned_to_block);
+		status.collected_at = Some(now_secs);
+	}
+
+	pub fn status(&self) -> AllowanceGcStatus {
+		self.lock().clone()
+	}
+
+	pub fn export_prometheus(&self) -> String {
+		let mut out = String::new();
+		let status = self.status();
+		if status.collected_at.is_none() {
+			return out;
+		}
+		let unrevoked = status
+			.orphaned
+			.iter()
+			.filter(|allowance| allowance.revocation_tx.is_none())
+			.count();
+		let _ = writeln!(out, "# TYPE bridge_orphaned_allowances gauge");
+		let _ = writeln!(out, "bridge_orphaned_allowances {unrevoked}");
+		let _ = writeln!(out, "# TYPE bridge_revoked_allowances_total counter");
+		let _ = writeln!(out, "bridge_revoked_allowances_total {}", status.revoked);
+		out
+	}
+}
+
+/// Finds the allowances of the wallet to the spenders other than the bridge contracts and
+/// revokes them.
+pub struct AllowanceCollector {
+	config: AllowanceGcConfig,
+	eth_client: EthClient,
+	tokens: Vec<Address>,
+	allowed_spenders: HashSet<Address>,
+	// Spenders approved by the wallet, by token, in the blocks scanned so far.
+	approved: BTreeSet<(Address, Address)>,
+	next_block: u64,
+	metrics: AllowanceGcMetrics,
+}
+
+impl AllowanceCollector {
+	/// The collector of the MOVE token and the tokens of the config, keeping the allowances to
+	/// the bridge contracts and to the spenders of the config.
+	pub fn new(
+		config: &AllowanceGcConfig,
+		eth_client: EthClient,
+		metrics: AllowanceGcMetrics,
+	) -> Result<Self, anyhow::Error> {
+		let mut tokens = vec![eth_client.config.movetoken_contract];
+		for token in parse_addresses(&config.tokens)? {
+			if !tokens.contains(&token) {
+				tokens.push(token);
+			}
+		}
+		let allowed_spenders =
+			[eth_client.config.initiator_contract, eth_client.config.counterparty_contract]
+				.into_iter()
+				.chain(parse_addresses(&config.allowed_spenders)?)
+				.collect();
+		Ok(AllowanceCollector {
+			config: config.clone(),
+			eth_client,
+			tokens,
+			allowed_spenders,
+			approved: BTreeSet::new(),
+			next_block: config.from_block,
+			metrics,
+		})
+	}
+
+	pub async fn run(mut self) {
+		let mut interval =
+			tokio::time::interval(Duration::from_secs(self.config.interval_secs.max(1)));
+		loop {
+			interval.tick().await;
+			if let Err(err) = self.collect().await {
+				tracing::warn!("Collection of the orphaned allowances failed: {err}");
+			}
+		}
+	}
+
+	async fn collect(&mut self) -> Result<(), anyhow::Error> {
+		let head = self.eth_client.get_block_number().await?;
+		for (from_block, to_block) in scan_ranges(self.next_block, head, SCANNED_BLOCKS) {
+			for token in &self.tokens {
+				for spender in
+					self.eth_client.approved_spenders(*token, from_block, to_block).await?
+				{
+					self.approved.insert((*token, spender));
+				}
+			}
+			self.next_block = to_block + 1;
+		}
+
+		let mut orphaned = Vec::new();
+		for (token, spender) in orphaned_candidates(&self.approved, &self.allowed_spenders) {
+			let allowance = self.eth_client.allowance(token, spender).await?;
+			if allowance.is_zero() {
+				// Checked again if the wallet approves the spender again.
+				self.approved.remove(&(token, spender));
+				continue;
+			}
+			tracing::warn!(
+				target: "bridge_alert",
+				"Orphaned allowance of {allowance} to {spender} on token {token}"
+			);
+			let revocation_tx = if self.config.dry_run {
+				None
+			} else {
+				match self.eth_client.revoke_allowance(token, spender).await {
+					Ok(receipt) => {
+						tracing::info!("Allowance to {spender} on token {token} revoked");
+						self.approved.remove(&(token, spender));
+						Some(receipt.transaction_hash.to_string())
+					}
+					Err(err) => {
+						tracing::warn!("Failed to revoke the allowance to {spender}: {err}");
+						None
+					}
+				}
+			};
+			orphaned.push(OrphanedAllowance {
+				token: token.to_string(),
+				spender: spender.to_string(),
+				allowance: allowance.to_string(),
+				revocation_tx,
+			});
+		}
+		self.metrics.record(orphaned, self.next_block.saturating_sub(1), now_secs());
+		Ok(())
+	}
+}
+
+fn parse_addresses(addresses: &[String]) -> Result<Vec<Address>, anyhow::Error> {
+	addresses
+		.iter()
+		.map(|address| {
+			address
+				.parse()
+				.map_err(|err| anyhow::anyhow!("Invalid address {address}: {err}"))
+		})
+		.collect()
+}
+
+// The ranges of at most `max_blocks` blocks from `from_block` to `to_block` included.
+fn scan_ranges(from_block: u64, to_block: u64, max_blocks: u64) -> Vec<(u64, u64)> {
+	let mut ranges = Vec::new();
+	let mut start = from_block;
+	while start <= to_block {
+		let end = to_block.min(start.saturating_add(max_blocks - 1));
+		ranges.push((start, end));
+		start = end + 1;
+	}
+	ranges
+}
+
+// The approved spenders, by token, that aren't allowed.
+fn orphaned_candidates(
+	approved: &BTreeSet<(Address, Address)>,
+	allowed_spenders: &HashSet<Address>,
+) -> Vec<(Address, Address)> {
+	approved
+		.iter()
+		.filter(|(_, spender)| !allowed_spenders.contains(spender))
+		.copied()
+		.collect()
+}
+
+fn now_secs() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|d| d.as_secs())
+		.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_orphaned_allowances() {
+		assert_eq!(
+			scan_ranges(0, 24_999, 10_000),
+			[(0, 9_999), (10_000, 19_999), (20_000, 24_999)]
+		);
+		assert_eq!(scan_ranges(7, 7, 10_000), [(7, 7)]);
+		// No new block since the last scan.
+		assert!(scan_ranges(8, 7, 10_000).is_empty());
+
+		let (token, bridge, migrated) =
+			(Address::repeat_byte(1), Address::repeat_byte(2), Address::repeat_byte(3));
+		let approved = BTreeSet::from([(token, bridge), (token, migrated)]);
+		let allowed = HashSet::from([bridge]);
+		assert_eq!(orphaned_candidates(&approved, &allowed), [(token, migrated)]);
+		assert!(parse_addresses(&["0x01".to_string()]).is_err());
+
+		let metrics = AllowanceGcMetrics::default();
+		assert_eq!(metrics.export_prometheus(), "");
+		let orphaned = OrphanedAllowance {
+			token: token.to_string(),
+			spender: migrated.to_string(),
+			allowance: "10".to_string(),
+			revocation_tx: None,
+		};
+		metrics.clone().record(vec![orphaned.clone()], 100, 1000);
+		metrics.record(
+			vec![OrphanedAllowance { revocation_tx: Some("0x1".to_string()), ..orphaned }],
+			200,
+			1010,
+		);
+		let status = metrics.status();
+		assert_eq!(status.scanned_to_block, Some(200));
+		assert_eq!(status.revoked, 1);
+		let export = metrics.export_prometheus();
+		assert!(export.contains("bridge_orphaned_allowances 0\n"));
+		assert!(export.contains("bridge_revoked_allowances_total 1\n"));
+	}
+}
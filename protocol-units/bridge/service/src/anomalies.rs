@@ -0,0 +1,355 @@
+//! Advisory alerts on unusual patterns of the transfers: a spike of the volume initiated by an
+//! address, many refunds, the same amount initiated again and again. The rules analyze the
+//! contract events of the bus, the stream stored by the indexer, and only alert: the transfers
+//! are processed as usual. Other rules can be plugged in the detector.
+use crate::event_bus::{BusEvent, Subscription};
+use bridge_config::common::anomalies::AnomaliesConfig;
+use bridge_util::chains::bridge_contracts::BridgeContractEvent;
+use bridge_util::events::TransferEvent;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt::Write;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Advisories kept for the admin API, the oldest are dropped beyond.
+const MAX_ADVISORIES: usize = 1000;
+
+/// A rule detecting a pattern in the contract events.
+pub trait DetectionRule: Send {
+	/// Name of the rule in the advisories and the metrics.
+	fn name(&self) -> &'static str;
+
+	/// Analyze the event received at the time, returning the reason of an advisory if it
+	/// completes the pattern.
+	fn observe(&mut self, event: &TransferEvent<Vec<u8>>, now_secs: u64) -> Option<String>;
+}
+
+/// An advisory alert raised by a rule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Advisory {
+	pub rule: &'static str,
+	pub reason: String,
+	pub raised_at_secs: u64,
+}
+
+#[derive(Debug, Default)]
+struct RaisedAdvisories {
+	recent: VecDeque<Advisory>,
+	// Advisories raised since the start, by rule.
+	totals: BTreeMap<&'static str, u64>,
+}
+
+/// The advisories raised by the detector. Clones share the same advisories.
+#[derive(Debug, Clone, Default)]
+pub struct AnomalyAdvisories {
+	advisories: Arc<Mutex<RaisedAdvisories>>,
+}
+
+impl AnomalyAdvisories {
+	fn lock(&self) -> std::sync::MutexGuard<'_, RaisedAdvisories> {
+		self.advisories.lock().expect("Anomaly advisories lock poisoned")
+	}
+
+	fn raise(&self, advisory: Advisory) {
+		tracing::warn!(
+			target: "bridge_alert",
+			"Anomaly {}: {} (advisory)",
+			advisory.rule,
+			advisory.reason
+		);
+		let mut advisories = self.lock();
+		*advisories.totals.entry(advisory.rule).or_default() += 1;
+		if advisories.recent.len() >= MAX_ADVISORIES {
+			advisories.recent.pop_front();
+		}
+		advisories.recent.push_back(advisory);
+	}
+
+	/// The advisories still kept, the most recent first.
+	pub fn recent(&self) -> Vec<Advisory> {
+		self.lock().recent.iter().rev().cloned().collect()
+	}
+
+	pub fn export_prometheus(&self) -> String {
+		let mut out = String::new();
+		let advisories = self.lock();
+		let _ = writeln!(out, "# TYPE bridge_anomaly_advisories_total counter");
+		for (rule, total) in &advisories.totals {
+			let _ = writeln!(out, "bridge_anomaly_advisories_total{{rule=\"{rule}\"}} {total}");
+		}
+		out
+	}
+}
+
+/// Runs the detection rules on the contract events.
+pub struct AnomalyDetector {
+	rules: Vec<Box<dyn DetectionRule>>,
+	advisories: AnomalyAdvisories,
+}
+
+impl AnomalyDetector {
+	/// The detector with the built-in rules enabled in the config.
+	pub fn from_config(config: &AnomaliesConfig, advisories: AnomalyAdvisories) -> Self {
+		let mut rules: Vec<Box<dyn DetectionRule>> = Vec::new();
+		if config.max_volume_per_address < u64::MAX {
+			rules.push(Box::new(VolumeSpike::new(
+				config.window_secs,
+				config.max_volume_per_address,
+			)));
+		}
+		if config.max_refunds < u64::MAX {
+			rules.push(Box::new(RefundBurst::new(config.window_secs, config.max_refunds)));
+		}
+		if config.max_repeated_amounts < u64::MAX {
+			rules.push(Box::new(RepeatedAmounts::new(
+				config.window_secs,
+				config.max_repeated_amounts,
+			)));
+		}
+		AnomalyDetector { rules, advisories }
+	}
+
+	/// Add a rule to the built-in ones.
+	pub fn with_rule(mut self, rule: Box<dyn DetectionRule>) -> Self {
+		self.rules.push(rule);
+		self
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.rules.is_empty()
+	}
+
+	/// Analyze the contract events of the subscription until the bus is dropped.
+	pub async fn run(mut self, mut events: Subscription) {
+		while let Some(event) = events.recv().await {
+			self.observe(&event, now_secs());
+		}
+	}
+
+	fn observe(&mut self, event: &BusEvent, now_secs: u64) {
+		let BusEvent::Contract(event) = event else {
+			return;
+		};
+		for rule in &mut self.rules {
+			if let Some(reason) = rule.observe(event, now_secs) {
+				self.advisories.raise(Advisory {
+					rule: rule.name(),
+					reason,
+					raised_at_secs: now_secs,
+				});
+			}
+		}
+	}
+}
+
+// Values observed in a window with their time and key, oldest first. A key is alerted at most
+// once per window.
+struct Window<K> {
+	window_secs: u64,
+	values: VecDeque<(u64, K, u64)>,
+	alerted: HashMap<K, u64>,
+}
+
+impl<K: Clone + Eq + Hash> Window<K> {
+	fn new(window_secs: u64) -> Self {
+		Window { window_secs, values: VecDeque::new(), alerted: HashMap::new() }
+	}
+
+	// Add the value of the key, returning the count and the sum of the values of the key in
+	// the window.
+	fn add(&mut self, key: K, value: u64, now_secs: u64) -> (u64, u128) {
+		let window_start = now_secs.saturating_sub(self.window_secs);
+		while self.values.front().is_some_and(|(at, ..)| *at <= window_start) {
+			self.values.pop_front();
+		}
+		self.alerted.retain(|_, at| *at > window_start);
+		self.values.push_back((now_secs, key.clone(), value));
+		self.values
+			.iter()
+			.filter(|(_, k, _)| *k == key)
+			.fold((0, 0), |(count, sum), (_, _, value)| (count + 1, sum + u128::from(*value)))
+	}
+
+	// Whether the key wasn't alerted yet in the window, it's then alerted.
+	fn alert(&mut self, key: K, now_secs: u64) -> bool {
+		self.alerted.insert(key, now_secs).is_none()
+	}
+}
+
+/// Amount initiated by an address above a maximum in the window.
+pub struct VolumeSpike {
+	max_amount: u64,
+	window: Window<Vec<u8>>,
+}
+
+impl VolumeSpike {
+	pub fn new(window_secs: u64, max_amount: u64) -> Self {
+		VolumeSpike { max_amount, window: Window::new(window_secs) }
+	}
+}
+
+impl DetectionRule for VolumeSpike {
+	fn name(&self) -> &'static str {
+		"volume_spike"
+	}
+
+	fn observe(&mut self, event: &TransferEvent<Vec<u8>>, now_secs: u64) -> Option<String> {
+		let BridgeContractEvent::Initiated(details) = &event.contract_event else {
+			return None;
+		};
+		let initiator = details.initiator.0.clone();
+		let (_, volume) = self.window.add(initiator.clone(), details.amount.0, now_secs);
+		let above = volume > u128::from(self.max_amount);
+		if !above || !self.window.alert(initiator.clone(), now_secs) {
+			return None;
+		}
+		Some(format!(
+			"0x{} initiated {volume} in {}s, above {}",
+			hex::encode(&initiator),
+			self.window.window_secs,
+			self.max_amount
+		))
+	}
+}
+
+/// Refunds above a maximum in the window.
+pub struct RefundBurst {
+	max_refunds: u64,
+	window: Window<()>,
+}
+
+impl RefundBurst {
+	pub fn new(window_secs: u64, max_refunds: u64) -> Self {
+		RefundBurst { max_refunds, window: Window::new(window_secs) }
+	}
+}
+
+impl DetectionRule for RefundBurst {
+	fn name(&self) -> &'static str {
+		"refund_burst"
+	}
+
+	fn observe(&mut self, event: &TransferEvent<Vec<u8>>, now_secs: u64) -> Option<String> {
+		let BridgeContractEvent::Refunded(_) = &event.contract_event else {
+			return None;
+		};
+		let (refunds, _) = self.window.add((), 0, now_secs);
+		if refunds <= self.max_refunds || !self.window.alert((), now_secs) {
+			return None;
+		}
+		Some(format!(
+			"{refunds} refunds in {}s, above {}",
+			self.window.window_secs, self.max_refunds
+		))
+	}
+}
+
+/// Initiations of the same amount above a maximum in the window.
+pub struct RepeatedAmounts {
+	max_repeats: u64,
+	window: Window<u64>,
+}
+
+impl RepeatedAmounts {
+	pub fn new(window_secs: u64, max_repeats: u64) -> Self {
+		RepeatedAmounts { max_repeats, window: Window::new(window_secs) }
+	}
+}
+
+impl DetectionRule for RepeatedAmounts {
+	fn name(&self) -> &'static str {
+		"repeated_amounts"
+	}
+
+	fn observe(&mut self, event: &TransferEvent<Vec<u8>>, now_secs: u64) -> Option<String> {
+		let BridgeContractEvent::Initiated(details) = &event.contract_event else {
+			return None;
+		};
+		let amount = details.amount.0;
+		let (repeats, _) = self.window.add(amount, amount, now_secs);
+		if repeats <= self.max_repeats || !self.window.alert(amount, now_secs) {
+			return None;
+		}
+		Some(format!(
+			"{repeats} initiations of {amount} in {}s, above {}",
+			self.window.window_secs, self.max_repeats
+		))
+	}
+}
+
+fn now_secs() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|d| d.as_secs())
+		.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bridge_util::types::{
+		Amount, BridgeAddress, BridgeTransferDetails, BridgeTransferId, ChainId, HashLock, TimeLock,
+	};
+
+	fn initiated(initiator: u8, amount: u64) -> BusEvent {
+		BusEvent::Contract(TransferEvent {
+			chain: ChainId::ONE,
+			contract_event: BridgeContractEvent::Initiated(BridgeTransferDetails {
+				bridge_transfer_id: BridgeTransferId([initiator; 32]),
+				initiator: BridgeAddress(vec![initiator; 20]),
+				recipient: BridgeAddress(vec![2; 32]),
+				hash_lock: HashLock([3; 32]),
+				time_lock: TimeLock(100),
+				amount: Amount(amount),
+				state: 0,
+			}),
+		})
+	}
+
+	fn refunded() -> BusEvent {
+		BusEvent::Contract(TransferEvent {
+			chain: ChainId::ONE,
+			contract_event: BridgeContractEvent::Refunded(BridgeTransferId([1; 32])),
+		})
+	}
+
+	#[test]
+	fn test_anomaly_detector() {
+		let config = AnomaliesConfig {
+			window_secs: 100,
+			max_volume_per_address: 50,
+			max_refunds: 1,
+			max_repeated_amounts: 2,
+		};
+		let advisories = AnomalyAdvisories::default();
+		let mut detector = AnomalyDetector::from_config(&config, advisories.clone());
+		detector.observe(&initiated(1, 30), 1000);
+		detector.observe(&initiated(2, 30), 1000);
+		assert!(advisories.recent().is_empty());
+		// The volume of the first address is above the maximum, it's alerted once per window.
+		detector.observe(&initiated(1, 30), 1010);
+		detector.observe(&initiated(1, 5), 1020);
+		let recent = advisories.recent();
+		assert_eq!(recent.len(), 2);
+		assert_eq!(recent[1].rule, "volume_spike");
+		// The third initiation of 30.
+		assert_eq!(recent[0].rule, "repeated_amounts");
+		assert_eq!(recent[0].raised_at_secs, 1010);
+
+		detector.observe(&refunded(), 1020);
+		detector.observe(&refunded(), 1030);
+		assert_eq!(advisories.recent()[0].rule, "refund_burst");
+		// The window moved on, the address can be alerted again.
+		detector.observe(&initiated(1, 60), 1200);
+		assert_eq!(advisories.recent()[0].rule, "volume_spike");
+
+		let export = advisories.export_prometheus();
+		assert!(export.contains("bridge_anomaly_advisories_total{rule=\"volume_spike\"} 2\n"));
+		assert!(export.contains("bridge_anomaly_advisories_total{rule=\"refund_burst\"} 1\n"));
+
+		// The rules are disabled by default.
+		assert!(AnomalyDetector::from_config(&AnomaliesConfig::default(), advisories).is_empty());
+	}
+}
@@ -0,0 +1,413 @@
+use bigdecimal::{BigDecimal, ToPrimitive};
+use bridge_indexer_db::models::{NewApprovalAuditEntry, PendingApprovalRow};
+use bridge_indexer_db::transfer_store::PgTransferStore;
+use bridge_util::types::{Amount, BridgeTransferId, ChainId};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc;
+
+const MAX_AUDIT_ENTRIES: usize = 1000;
+
+/// A transfer held until an operator approves or denies it.
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingApproval {
+	/// Hex encoded transfer id.
+	pub transfer_id: String,
+	pub init_chain: String,
+	pub amount: u64,
+	pub queued_at_secs: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalDecision {
+	/// Resume the transfer and lock it on the counterparty chain.
+	Approve,
+	/// Abort the transfer and refund the initiator.
+	Deny,
+}
+
+/// Decision posted by an operator on the admin API. The operator is the authenticated one.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OperatorDecision {
+	/// Hex encoded transfer id.
+	pub transfer_id: String,
+	pub decision: ApprovalDecision,
+}
+
+/// Decision sent to the relayer loop.
+#[derive(Debug, Clone)]
+pub struct ApprovalRequest {
+	pub transfer_id: BridgeTransferId,
+	pub decision: ApprovalDecision,
+	pub operator: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+	Queued,
+	Approved,
+	Denied,
+	Dropped,
+}
+
+impl AuditAction {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			AuditAction::Queued => "queued",
+			AuditAction::Approved => "approved",
+			AuditAction::Denied => "denied",
+			AuditAction::Dropped => "dropped",
+		}
+	}
+}
+
+impl std::str::FromStr for AuditAction {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"queued" => Ok(AuditAction::Queued),
+			"approved" => Ok(AuditAction::Approved),
+			"denied" => Ok(AuditAction::Denied),
+			"dropped" => Ok(AuditAction::Dropped),
+			action => anyhow::bail!("Unknown audit action {action}"),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEntry {
+	pub transfer_id: String,
+	pub action: AuditAction,
+	pub operator: Option<String>,
+	pub amount: u64,
+	pub at_secs: u64,
+}
+
+/// Store of the queue and of its audit trail, so they survive a restart. The audit trail is
+/// never truncated in the store, only the latest entries are kept in memory.
+pub trait ApprovalStore: Send + Sync {
+	fn save_pending(&self, pending: &PendingApproval) -> Result<(), anyhow::Error>;
+
+	fn remove_pending(&self, transfer_id: &str) -> Result<(), anyhow::Error>;
+
+	fn pending(&self) -> Result<Vec<PendingApproval>, anyhow::Error>;
+
+	fn append_audit(&self, entry: &AuditEntry) -> Result<(), anyhow::Error>;
+
+	/// The latest `limit` entries, the oldest first.
+	fn latest_audit(&self, limit: usize) -> Result<Vec<AuditEntry>, anyhow::Error>;
+}
+
+impl ApprovalStore for PgTransferStore {
+	fn save_pending(&self, pending: &PendingApproval) -> Result<(), anyhow::Error> {
+		self.save_pending_approval(&PendingApprovalRow {
+			bridge_transfer_id: pending.transfer_id.clone(),
+			init_chain: pending.init_chain.clone(),
+			amount: BigDecimal::from(pending.amount),
+			queued_at: i64::try_from(pending.queued_at_secs)?,
+		})
+	}
+
+	fn remove_pending(&self, transfer_id: &str) -> Result<(), anyhow::Error> {
+		self.remove_pending_approval(transfer_id)
+	}
+
+	fn pending(&self) -> Result<Vec<PendingApproval>, anyhow::Error> {
+		self.pending_approvals()?
+			.into_iter()
+			.map(|row| {
+				Ok(PendingApproval {
+					transfer_id: row.bridge_transfer_id,
+					init_chain: row.init_chain,
+					amount: to_u64(&row.amount)?,
+					queued_at_secs: u64::try_from(row.queued_at)?,
+				})
+			})
+			.collect()
+	}
+
+	fn append_audit(&self, entry: &AuditEntry) -> Result<(), anyhow::Error> {
+		self.insert_approval_audit_entry(&NewApprovalAuditEntry {
+			bridge_transfer_id: entry.transfer_id.clone(),
+			action: entry.action.as_str().to_string(),
+			operator: entry.operator.clone(),
+			amount: BigDecimal::from(entry.amount),
+			at: i64::try_from(entry.at_secs)?,
+		})
+	}
+
+	fn latest_audit(&self, limit: usize) -> Result<Vec<AuditEntry>, anyhow::Error> {
+		self.latest_approval_audit_entries(i64::try_from(limit)?)?
+			.into_iter()
+			.map(|row| {
+				Ok(AuditEntry {
+					transfer_id: row.bridge_transfer_id,
+					action: row.action.parse()?,
+					operator: row.operator,
+					amount: to_u64(&row.amount)?,
+					at_secs: u64::try_from(row.at)?,
+				})
+			})
+			.collect()
+	}
+}
+
+fn to_u64(amount: &BigDecimal) -> Result<u64, anyhow::Error> {
+	amount.to_u64().ok_or_else(|| anyhow::anyhow!("Invalid amount {amount}"))
+}
+
+#[derive(Debug, Default)]
+struct QueueState {
+	pending: BTreeMap<String, PendingApproval>,
+	audit_log: VecDeque<AuditEntry>,
+}
+
+/// Queue of the transfers above the auto approval limit.
+/// The relayer loop fills the queue, operators resolve it through the admin API.
+/// Clones share the same queue.
+#[derive(Clone)]
+pub struct ApprovalQueue {
+	auto_approval_limit: u64,
+	state: Arc<Mutex<QueueState>>,
+	decision_tx: mpsc::Sender<ApprovalRequest>,
+	store: Option<Arc<dyn ApprovalStore>>,
+}
+
+impl ApprovalQueue {
+	/// Create the queue and the receiver of the operator decisions for the relayer loop.
+	pub fn new(auto_approval_limit: u64) -> (Self, mpsc::Receiver<ApprovalRequest>) {
+		let (decision_tx, decision_rx) = mpsc::channel(100);
+		let queue = ApprovalQueue {
+			auto_approval_limit,
+			state: Arc::new(Mutex::new(QueueState::default())),
+			decision_tx,
+			store: None,
+		};
+		(queue, decision_rx)
+	}
+
+	/// Keep the queue and its audit trail in the store, and restore the transfers pending
+	/// approval and the latest audit entries from it.
+	pub fn with_store(mut self, store: Arc<dyn ApprovalStore>) -> Result<Self, anyhow::Error> {
+		{
+			let mut state = self.lock();
+			for pending in store.pending()? {
+				state.pending.insert(pending.transfer_id.clone(), pending);
+			}
+			state.audit_log = store.latest_audit(MAX_AUDIT_ENTRIES)?.into();
+		}
+		self.store = Some(store);
+		Ok(self)
+	}
+
+	pub fn requires_approval(&self, amount: Amount) -> bool {
+		amount.0 > self.auto_approval_limit
+	}
+
+	pub fn queue(&self, transfer_id: BridgeTransferId, init_chain: ChainId, amount: Amount) {
+		let id = hex::encode(transfer_id.0);
+		let pending = PendingApproval {
+			transfer_id: id.clone(),
+			init_chain: init_chain.to_string(),
+			amount: amount.0,
+			queued_at_secs: now_secs(),
+		};
+		self.persist(|store| store.save_pending(&pending));
+		let entry = {
+			let mut state = self.lock();
+			state.pending.insert(id.clone(), pending);
+			audit(&mut state, id, AuditAction::Queued, None, amount.0)
+		};
+		self.persist(|store| store.append_audit(&entry));
+	}
+
+	/// Remove the transfer from the queue once the relayer applied the operator decision.
+	pub fn resolve(&self, request: &ApprovalRequest) -> Option<PendingApproval> {
+		let id = hex::encode(request.transfer_id.0);
+		let action = match request.decision {
+			ApprovalDecision::Approve => AuditAction::Approved,
+			ApprovalDecision::Deny => AuditAction::Denied,
+		};
+		let (pending, entry) = {
+			let mut state = self.lock();
+			let pending = state.pending.remove(&id)?;
+			let operator = Some(request.operator.clone());
+			let entry = audit(&mut state, id.clone(), action, operator, pending.amount);
+			(pending, entry)
+		};
+		self.persist(|store| store.remove_pending(&id));
+		self.persist(|store| store.append_audit(&entry));
+		Some(pending)
+	}
+
+	/// Remove a transfer closed on chain before any decision.
+	pub fn drop_pending(&self, transfer_id: BridgeTransferId) {
+		let id = hex::encode(transfer_id.0);
+		let entry = {
+			let mut state = self.lock();
+			let Some(pending) = state.pending.remove(&id) else {
+				return;
+			};
+			audit(&mut state, id.clone(), AuditAction::Dropped, None, pending.amount)
+		};
+		self.persist(|store| store.remove_pending(&id));
+		self.persist(|store| store.append_audit(&entry));
+	}
+
+	pub fn pending(&self) -> Vec<PendingApproval> {
+		self.lock().pending.values().cloned().collect()
+	}
+
+	pub fn audit_log(&self) -> Vec<AuditEntry> {
+		self.lock().audit_log.iter().cloned().collect()
+	}
+
+	/// Send the decision of the authenticated operator to the relayer loop.
+	pub async fn submit(
+		&self,
+		decision: OperatorDecision,
+		operator: String,
+	) -> Result<(), anyhow::Error> {
+		let id = decision.transfer_id.strip_prefix("0x").unwrap_or(&decision.transfer_id);
+		let transfer_id = BridgeTransferId::parse(id)?;
+		if !self.lock().pending.contains_key(&hex::encode(transfer_id.0)) {
+			anyhow::bail!("Transfer {id} is not pending approval");
+		}
+		self.decision_tx
+			.send(ApprovalRequest { transfer_id, decision: decision.decision, operator })
+			.await
+			.map_err(|_| anyhow::anyhow!("Relayer loop is not running"))
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<'_, QueueState> {
+		self.state.lock().expect("Approval queue lock poisoned")
+	}
+
+	// The queue in memory stays the reference when the store fails, the failure is alerted.
+	fn persist(&self, write: impl FnOnce(&dyn ApprovalStore) -> Result<(), anyhow::Error>) {
+		if let Some(store) = &self.store {
+			if let Err(err) = write(store.as_ref()) {
+				tracing::error!(target: "bridge_alert", "Failed to store the approval queue: {err}");
+			}
+		}
+	}
+}
+
+fn audit(
+	state: &mut QueueState,
+	transfer_id: String,
+	action: AuditAction,
+	operator: Option<String>,
+	amount: u64,
+) -> AuditEntry {
+	tracing::info!(
+		target: "bridge_audit",
+		"Transfer {transfer_id} amount {amount} {action:?} by {}",
+		operator.as_deref().unwrap_or("relayer")
+	);
+	let entry = AuditEntry { transfer_id, action, operator, amount, at_secs: now_secs() };
+	state.audit_log.push_back(entry.clone());
+	while state.audit_log.len() > MAX_AUDIT_ENTRIES {
+		state.audit_log.pop_front();
+	}
+	entry
+}
+
+fn now_secs() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn test_approval_flow() {
+		let (queue, mut decision_rx) = ApprovalQueue::new(100);
+		assert!(!queue.requires_approval(Amount(100)));
+		assert!(queue.requires_approval(Amount(101)));
+
+		let transfer_id = BridgeTransferId([1; 32]);
+		queue.queue(transfer_id, ChainId::ONE, Amount(500));
+		assert_eq!(queue.pending().len(), 1);
+
+		// Unknown transfers are rejected.
+		let unknown = OperatorDecision {
+			transfer_id: hex::encode([2; 32]),
+			decision: ApprovalDecision::Approve,
+		};
+		assert!(queue.submit(unknown, "alice".to_string()).await.is_err());
+
+		let decision = OperatorDecision {
+			transfer_id: format!("0x{}", hex::encode([1; 32])),
+			decision: ApprovalDecision::Deny,
+		};
+		queue.submit(decision, "alice".to_string()).await.unwrap();
+		let request = decision_rx.recv().await.unwrap();
+		assert_eq!(request.transfer_id, transfer_id);
+		assert_eq!(request.operator, "alice");
+		assert!(queue.resolve(&request).is_some());
+		assert!(queue.pending().is_empty());
+
+		let actions: Vec<_> = queue.audit_log().iter().map(|entry| entry.action).collect();
+		assert_eq!(actions, vec![AuditAction::Queued, AuditAction::Denied]);
+	}
+
+	#[derive(Default)]
+	struct MemoryApprovalStore {
+		pending: Mutex<BTreeMap<String, PendingApproval>>,
+		audit_log: Mutex<Vec<AuditEntry>>,
+	}
+
+	impl ApprovalStore for MemoryApprovalStore {
+		fn save_pending(&self, pending: &PendingApproval) -> Result<(), anyhow::Error> {
+			self.pending
+				.lock()
+				.unwrap()
+				.insert(pending.transfer_id.clone(), pending.clone());
+			Ok(())
+		}
+
+		fn remove_pending(&self, transfer_id: &str) -> Result<(), anyhow::Error> {
+			self.pending.lock().unwrap().remove(transfer_id);
+			Ok(())
+		}
+
+		fn pending(&self) -> Result<Vec<PendingApproval>, anyhow::Error> {
+			Ok(self.pending.lock().unwrap().values().cloned().collect())
+		}
+
+		fn append_audit(&self, entry: &AuditEntry) -> Result<(), anyhow::Error> {
+			self.audit_log.lock().unwrap().push(entry.clone());
+			Ok(())
+		}
+
+		fn latest_audit(&self, limit: usize) -> Result<Vec<AuditEntry>, anyhow::Error> {
+			let audit_log = self.audit_log.lock().unwrap();
+			Ok(audit_log[audit_log.len().saturating_sub(limit)..].to_vec())
+		}
+	}
+
+	#[test]
+	fn test_queue_restored_from_store() {
+		let store = Arc::new(MemoryApprovalStore::default());
+		let (queue, _decision_rx) = ApprovalQueue::new(100);
+		let queue = queue.with_store(store.clone()).unwrap();
+		queue.queue(BridgeTransferId([1; 32]), ChainId::ONE, Amount(500));
+		queue.queue(BridgeTransferId([2; 32]), ChainId::TWO, Amount(600));
+		queue.drop_pending(BridgeTransferId([2; 32]));
+
+		// A restarted relayer finds the pending transfer and the audit trail.
+		let (restarted, _decision_rx) = ApprovalQueue::new(100);
+		let restarted = restarted.with_store(store).unwrap();
+		let pending: Vec<_> = restarted.pending().into_iter().map(|p| p.transfer_id).collect();
+		assert_eq!(pending, vec![hex::encode([1; 32])]);
+		let actions: Vec<_> = restarted.audit_log().iter().map(|entry| entry.action).collect();
+		assert_eq!(actions, vec![AuditAction::Queued, AuditAction::Queued, AuditAction::Dropped]);
+	}
+}
@@ -0,0 +1,32 @@
+use alloy::signers::local::PrivateKeySigner;
+use anyhow::Result;
+use bridge_config::Config;
+use bridge_service::signer::{SigningPolicy, SigningService};
+use godfig::{backend::config_file::ConfigFile, Godfig};
+
+/// Signing process holding the Ethereum key of the relayer.
+/// The relayer connects to it when `signer.socket_path` is set in its config.
+#[tokio::main]
+async fn main() -> Result<()> {
+	use tracing_subscriber::EnvFilter;
+
+	tracing_subscriber::fmt()
+		.with_env_filter(
+			EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+		)
+		.init();
+
+	let mut dot_movement = dot_movement::DotMovement::try_from_env()?;
+	let pathbuff = bridge_config::get_config_path(&dot_movement);
+	dot_movement.set_path(pathbuff);
+	let config_file = dot_movement.try_get_or_create_config_file().await?;
+	let godfig: Godfig<Config, ConfigFile> = Godfig::new(ConfigFile::new(config_file), vec![]);
+	let bridge_config: Config = godfig.try_wait_for_ready().await?;
+
+	if bridge_config.signer.socket_path.is_empty() {
+		anyhow::bail!("No signer socket configured, set signer.socket_path");
+	}
+	let key = bridge_config.eth.signer_private_key.parse::<PrivateKeySigner>()?;
+	let policy = SigningPolicy::try_from(&bridge_config)?;
+	SigningService::new(key, policy).serve(&bridge_config.signer.socket_path).await
+}
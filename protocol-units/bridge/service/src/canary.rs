@@ -0,0 +1,396 @@
+use crate::chains::ethereum::{client::EthClient, types::EthAddress};
+use crate::chains::movement::{client_framework::MovementClientFramework, utils::MovementAddress};
+use crate::event_bus::{BusEvent, Subscription};
+use crate::guards::EnabledDirections;
+use crate::retry::call_retry_policy;
+use crate::runbook::{Alert, AlertKind, RunbookHooks};
+use crate::secrets::SecretManager;
+use aptos_sdk::crypto::{ed25519::Ed25519PrivateKey, ValidCryptoMaterialStringExt};
+use bridge_config::common::canary::CanaryConfig;
+use bridge_config::Config;
+use bridge_util::chains::bridge_contracts::{
+	BridgeContract, BridgeContractEvent, RetryPolicy, RetryingBridgeContract,
+};
+use bridge_util::types::{Amount, BridgeAddress, BridgeTransferId, HashLock, TransferDirection};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc;
+
+/// Progress of a canary transfer, as seen by the relayer loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CanaryStage {
+	Initiated(BridgeTransferId),
+	Locked,
+	InitiatorCompleted,
+	Aborted,
+}
+
+#[derive(Default)]
+struct TrackerState {
+	by_hash_lock: HashMap<HashLock, mpsc::UnboundedSender<CanaryStage>>,
+	by_id: HashMap<BridgeTransferId, HashLock>,
+}
+
+/// Forwards the relayer events of the canary transfers to the canary.
+/// Canary transfers are recognized by their hash lock, which only the canary knows.
+#[derive(Clone, Default)]
+pub struct CanaryTracker {
+	state: Arc<Mutex<TrackerState>>,
+}
+
+impl CanaryTracker {
+	fn lock(&self) -> std::sync::MutexGuard<'_, TrackerState> {
+		self.state.lock().expect("Canary tracker lock poisoned")
+	}
+
+	fn watch(&self, hash_lock: HashLock) -> mpsc::UnboundedReceiver<CanaryStage> {
+		let (tx, rx) = mpsc::unbounded_channel();
+		self.lock().by_hash_lock.insert(hash_lock, tx);
+		rx
+	}
+
+	fn forget(&self, hash_lock: HashLock) {
+		let mut state = self.lock();
+		state.by_hash_lock.remove(&hash_lock);
+		state.by_id.retain(|_, lock| *lock != hash_lock);
+	}
+
+	/// Follow the contract events published on the bus until it is dropped.
+	pub async fn run(self, mut events: Subscription) {
+		while let Some(event) = events.recv().await {
+			if let BusEvent::Contract(event) = event {
+				self.observe(&event.contract_event);
+			}
+		}
+	}
+
+	fn observe<A>(&self, event: &BridgeContractEvent<A>) {
+		let mut state = self.lock();
+		if state.by_hash_lock.is_empty() {
+			return;
+		}
+		let (transfer_id, stage) = match event {
+			BridgeContractEvent::Initiated(details) => {
+				if !state.by_hash_lock.contains_key(&details.hash_lock) {
+					return;
+				}
+				state.by_id.insert(details.bridge_transfer_id, details.hash_lock);
+				(details.bridge_transfer_id, CanaryStage::Initiated(details.bridge_transfer_id))
+			}
+			BridgeContractEvent::Locked(details) => {
+				(details.bridge_transfer_id, CanaryStage::Locked)
+			}
+			BridgeContractEvent::InitiatorCompleted(id) => (*id, CanaryStage::InitiatorCompleted),
+			BridgeContractEvent::Cancelled(id) | BridgeContractEvent::Refunded(id) => {
+				(*id, CanaryStage::Aborted)
+			}
+			BridgeContractEvent::CounterPartyCompleted(_, _) => return,
+		};
+		let sender = state
+			.by_id
+			.get(&transfer_id)
+			.and_then(|hash_lock| state.by_hash_lock.get(hash_lock));
+		if let Some(sender) = sender {
+			let _ = sender.send(stage);
+		}
+	}
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CanaryStats {
+	pub runs: u64,
+	pub failures: u64,
+	pub last_latency_ms: Option<u64>,
+	/// Unix timestamp of the last successful canary transfer.
+	pub last_success: Option<u64>,
+	pub last_error: Option<String>,
+}
+
+/// Results of the canary transfers per direction.
+#[derive(Clone, Default)]
+pub struct CanaryMetrics {
+	stats: Arc<Mutex<BTreeMap<TransferDirection, CanaryStats>>>,
+}
+
+impl CanaryMetrics {
+	fn record(&self, direction: TransferDirection, result: &Result<Duration, anyhow::Error>) {
+		let mut stats = self.stats.lock().expect("Canary metrics lock poisoned");
+		let stats = stats.entry(direction).or_default();
+		stats.runs += 1;
+		match result {
+			Ok(latency) => {
+				stats.last_latency_ms = Some(latency.as_millis() as u64);
+				stats.last_success =
+					SystemTime::now().duration_since(UNIX_EPOCH).map(|now| now.as_secs()).ok();
+				stats.last_error = None;
+			}
+			Err(err) => {
+				stats.failures += 1;
+				stats.last_error = Some(err.to_string());
+			}
+		}
+	}
+
+	pub fn snapshot(&self) -> BTreeMap<TransferDirection, CanaryStats> {
+		self.stats.lock().expect("Canary metrics lock poisoned").clone()
+	}
+
+	pub fn export_prometheus(&self) -> String {
+		let mut out = String::new();
+		let stats = self.snapshot();
+		let _ = writeln!(out, "# TYPE bridge_canary_runs counter");
+		for (direction, stat) in &stats {
+			let _ = writeln!(out, "bridge_canary_runs{{direction=\"{direction}\"}} {}", stat.runs);
+		}
+		let _ = writeln!(out, "# TYPE bridge_canary_failures counter");
+		for (direction, stat) in &stats {
+			let _ = writeln!(
+				out,
+				"bridge_canary_failures{{direction=\"{direction}\"}} {}",
+				stat.failures
+			);
+		}
+		let _ = writeln!(out, "# TYPE bridge_canary_latency_ms gauge");
+		for (direction, stat) in &stats {
+			if let Some(latency) = stat.last_latency_ms {
+				let _ = writeln!(
+					out,
+					"bridge_canary_latency_ms{{direction=\"{direction}\"}} {latency}"
+				);
+			}
+		}
+		out
+	}
+}
+
+/// Sends a tiny transfer in each enabled direction on a schedule with dedicated keys
+/// and follows it through the relayer until it completes on both chains.
+/// A transfer that fails or doesn't complete in time raises an alert.
+pub struct Canary {
+	eth_client: EthClient,
+	movement_client: MovementClientFramework,
+	enabled_directions: EnabledDirections,
+	tracker: CanaryTracker,
+	metrics: CanaryMetrics,
+	interval: Duration,
+	timeout: Duration,
+	amount: Amount,
+	alert_webhook_url: Option<String>,
+	runbook_hooks: RunbookHooks,
+	call_retry: RetryPolicy,
+	secrets: SecretManager,
+}
+
+impl Canary {
+	pub async fn build(
+		config: &Config,
+		enabled_directions: EnabledDirections,
+		tracker: CanaryTracker,
+		metrics: CanaryMetrics,
+		runbook_hooks: RunbookHooks,
+		secrets: SecretManager,
+	) -> Result<Self, anyhow::Error> {
+		let canary: &CanaryConfig = &config.canary;
+		if canary.eth_private_key.is_empty() || canary.movement_private_key.is_empty() {
+			anyhow::bail!("The canary needs both an Eth and a Movement private key");
+		}
+
+		let mut eth_config = config.eth.clone();
+		eth_config.signer_private_key = canary.eth_private_key.clone();
+		let eth_client = EthClient::new(&eth_config).await?;
+
+		let mut movement_config = config.movement.clone();
+		movement_config.movement_signer_key =
+			Ed25519PrivateKey::from_encoded_string(&canary.movement_private_key)?;
+		let movement_client = MovementClientFramework::new(&movement_config).await?;
+
+		Ok(Canary {
+			eth_client,
+			movement_client,
+			enabled_directions,
+			tracker,
+			metrics,
+			interval: Duration::from_secs(canary.interval_secs),
+			timeout: Duration::from_secs(canary.timeout_secs),
+			amount: Amount(canary.amount),
+			alert_webhook_url: canary.alert_webhook_url.clone(),
+			runbook_hooks,
+			call_retry: call_retry_policy(&config.retry),
+			secrets,
+		})
+	}
+
+	pub async fn run(self) {
+		let mut interval = tokio::time::interval(self.interval);
+		loop {
+			interval.tick().await;
+			for direction in [TransferDirection::EthToMovement, TransferDirection::MovementToEth] {
+				if !self.enabled_directions.is_enabled(direction) {
+					continue;
+				}
+				let result = self.run_once(direction).await;
+				self.metrics.record(direction, &result);
+				match result {
+					Ok(latency) => {
+						tracing::info!("Canary {direction} transfer completed in {latency:?}")
+					}
+					Err(err) => self.alert(direction, &err).await,
+				}
+			}
+		}
+	}
+
+	/// Runs one canary transfer and returns its end to end latency.
+	async fn run_once(&self, direction: TransferDirection) -> Result<Duration, anyhow::Error> {
+		let hash_lock = self.secrets.generate()?;
+		let mut stages = self.tracker.watch(hash_lock);
+		let start = Instant::now();
+
+		let result =
+			tokio::time::timeout(self.timeout, self.transfer(direction, hash_lock, &mut stages))
+				.await;
+		self.tracker.forget(hash_lock);
+
+		match result {
+			Ok(Ok(())) => Ok(start.elapsed()),
+			Ok(Err(err)) => Err(err),
+			Err(_) => Err(anyhow::anyhow!("Transfer not completed after {:?}", self.timeout)),
+		}
+	}
+
+	async fn transfer(
+		&self,
+		direction: TransferDirection,
+		hash_lock: HashLock,
+		stages: &mut mpsc::UnboundedReceiver<CanaryStage>,
+	) -> Result<(), anyhow::Error> {
+		let eth_address = self.eth_client.signer_address();
+		let movement_address = self.movement_client.signer().address();
+		// A transient RPC failure of a canary call is not a failure of the bridge.
+		let mut eth_client = RetryingBridgeContract::new(self.eth_client.clone(), self.call_retry);
+		let mut movement_client =
+			RetryingBridgeContract::new(self.movement_client.clone(), self.call_retry);
+
+		let initiated = match direction {
+			TransferDirection::EthToMovement => {
+				eth_client
+					.initiate_bridge_transfer(
+						BridgeAddress(EthAddress(eth_address)),
+						BridgeAddress(movement_address.to_vec()),
+						hash_lock,
+						self.amount,
+					)
+					.await
+			}
+			TransferDirection::MovementToEth => {
+				movement_client
+					.initiate_bridge_transfer(
+						BridgeAddress(MovementAddress(movement_address)),
+						BridgeAddress(eth_address.to_vec()),
+						hash_lock,
+						self.amount,
+					)
+					.await
+			}
+		};
+		if let Err(err) = initiated {
+			self.secrets.discard(hash_lock);
+			return Err(err.into());
+		}
+
+		let transfer_id = loop {
+			if let CanaryStage::Initiated(transfer_id) = next_stage(stages).await? {
+				break transfer_id;
+			}
+		};
+		while next_stage(stages).await? != CanaryStage::Locked {}
+
+		// The canary is the recipient and reveals the secret on the counterparty chain,
+		// released once the lock is seen.
+		let pre_image = self.secrets.release(transfer_id).await;
+		match direction {
+			TransferDirection::EthToMovement => {
+				movement_client
+					.counterparty_complete_bridge_transfer(transfer_id, pre_image)
+					.await?
+			}
+			TransferDirection::MovementToEth => {
+				eth_client.counterparty_complete_bridge_transfer(transfer_id, pre_image).await?
+			}
+		}
+
+		while next_stage(stages).await? != CanaryStage::InitiatorCompleted {}
+		Ok(())
+	}
+
+	async fn alert(&self, direction: TransferDirection, err: &anyhow::Error) {
+		tracing::error!(target: "bridge_alert", "Canary {direction} transfer failed: {err}");
+		self.runbook_hooks.raise(Alert {
+			kind: AlertKind::CanaryFailed,
+			summary: format!("Canary {direction} transfer failed"),
+			context: serde_json::json!({ "direction": direction, "error": err.to_string() }),
+		});
+		let Some(url) = &self.alert_webhook_url else {
+			return;
+		};
+		let body = serde_json::json!({
+			"source": "bridge_canary",
+			"direction": direction,
+			"error": err.to_string(),
+		});
+		let request = reqwest::Client::new()
+			.post(url)
+			.header("Content-Type", "application/json")
+			.body(body.to_string());
+		if let Err(err) = request.send().await {
+			tracing::warn!("Failed to send the canary alert to {url}: {err}");
+		}
+	}
+}
+
+async fn next_stage(
+	stages: &mut mpsc::UnboundedReceiver<CanaryStage>,
+) -> Result<CanaryStage, anyhow::Error> {
+	match stages.recv().await {
+		Some(CanaryStage::Aborted) => Err(anyhow::anyhow!("Transfer was refunded or cancelled")),
+		Some(stage) => Ok(stage),
+		None => Err(anyhow::anyhow!("Canary tracker closed")),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bridge_util::types::{BridgeTransferDetails, TimeLock};
+
+	#[tokio::test]
+	async fn test_tracker_follows_canary_transfer() {
+		let tracker = CanaryTracker::default();
+		let hash_lock = HashLock([1; 32]);
+		let transfer_id = BridgeTransferId([2; 32]);
+		let mut stages = tracker.watch(hash_lock);
+
+		let initiated = |hash_lock, bridge_transfer_id| {
+			BridgeContractEvent::Initiated(BridgeTransferDetails {
+				bridge_transfer_id,
+				initiator: BridgeAddress(vec![0u8; 20]),
+				recipient: BridgeAddress(vec![0u8; 32]),
+				hash_lock,
+				time_lock: TimeLock(0),
+				amount: Amount(1),
+				state: 0,
+			})
+		};
+		// Transfers of other users are ignored.
+		tracker.observe(&initiated(HashLock([3; 32]), BridgeTransferId([4; 32])));
+		tracker.observe(&initiated(hash_lock, transfer_id));
+		tracker.observe(&BridgeContractEvent::<Vec<u8>>::InitiatorCompleted(transfer_id));
+
+		assert_eq!(stages.recv().await, Some(CanaryStage::Initiated(transfer_id)));
+		assert_eq!(stages.recv().await, Some(CanaryStage::InitiatorCompleted));
+		assert!(stages.try_recv().is_err());
+	}
+}
@@ -0,0 +1,245 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Label of the Ethereum monitoring, scanning blocks.
+pub const ETH_CHAIN: &str = "ethereum";
+/// Label of the Movement monitoring, scanning event sequence numbers.
+pub const MOVEMENT_CHAIN: &str = "movement";
+
+// Period of the progress logs while catching up.
+const PROGRESS_LOG_PERIOD: Duration = Duration::from_secs(10);
+
+#[derive(Debug)]
+struct ChainCatchUp {
+	started: Instant,
+	// Position of the first scan, the progress rate is measured from it.
+	start_position: Option<u64>,
+	position: u64,
+	head: Option<u64>,
+	events_found: u64,
+	caught_up: bool,
+	last_log: Instant,
+	last_scan: Option<Instant>,
+}
+
+impl ChainCatchUp {
+	fn new() -> Self {
+		let now = Instant::now();
+		ChainCatchUp {
+			started: now,
+			start_position: None,
+			position: 0,
+			head: None,
+			events_found: 0,
+			caught_up: false,
+			last_log: now,
+			last_scan: None,
+		}
+	}
+
+	// Estimated time to reach the head at the scan rate since the start,
+	// unknown without head or before the scan has progressed.
+	fn eta_secs(&self) -> Option<u64> {
+		if self.caught_up {
+			return Some(0);
+		}
+		let head = self.head?;
+		let scanned = self.position.saturating_sub(self.start_position?);
+		if scanned == 0 {
+			return None;
+		}
+		let remaining = head.saturating_sub(self.position);
+		let rate = scanned as f64 / self.started.elapsed().as_secs_f64();
+		Some((remaining as f64 / rate).ceil() as u64)
+	}
+}
+
+/// Catch-up progress of one chain monitoring.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChainProgress {
+	/// Last block, or event sequence number, scanned.
+	pub position: u64,
+	/// Head of the chain when known.
+	pub head: Option<u64>,
+	pub events_found: u64,
+	pub caught_up: bool,
+	pub eta_secs: Option<u64>,
+}
+
+/// Readiness of the relayer with the catch-up progress of each chain.
+#[derive(Debug, Clone, Serialize)]
+pub struct Readiness {
+	pub ready: bool,
+	pub chains: BTreeMap<String, ChainProgress>,
+}
+
+/// Progress of the chain monitorings replaying the history at start.
+/// The relayer is ready once all the registered monitorings have caught up.
+/// Clones share the same progress.
+#[derive(Debug, Clone, Default)]
+pub struct CatchUpProgress {
+	chains: Arc<Mutex<BTreeMap<&'static str, ChainCatchUp>>>,
+}
+
+impl CatchUpProgress {
+	fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<&'static str, ChainCatchUp>> {
+		self.chains.lock().expect("Catch-up progress lock poisoned")
+	}
+
+	/// Register a chain monitoring starting its catch-up.
+	pub fn register(&self, chain: &'static str) {
+		self.lock().insert(chain, ChainCatchUp::new());
+	}
+
+	/// Record a scan of the monitoring up to the position, with the events it found.
+	pub fn record_scan(&self, chain: &'static str, position: u64, head: Option<u64>, events: u64) {
+		let mut chains = self.lock();
+		let progress = chains.entry(chain).or_insert_with(ChainCatchUp::new);
+		progress.start_position.get_or_insert(position);
+		progress.position = progress.position.max(position);
+		progress.head = head.or(progress.head);
+		progress.events_found += events;
+		progress.last_scan = Some(Instant::now());
+		if !progress.caught_up && progress.last_log.elapsed() >= PROGRESS_LOG_PERIOD {
+			progress.last_log = Instant::now();
+			tracing::info!(
+				"Catching up {chain}: scanned to {}{}, {} events found, eta {}",
+				progress.position,
+				progress.head.map(|head| format!(" of {head}")).unwrap_or_default(),
+				progress.events_found,
+				progress
+					.eta_secs()
+					.map(|eta| format!("{eta}s"))
+					.unwrap_or("unknown".to_string())
+			);
+		}
+	}
+
+	/// Mark the monitoring caught up with the head of the chain. A monitoring stays
+	/// caught up once it has been, so the readiness doesn't flap.
+	pub fn mark_caught_up(&self, chain: &'static str) {
+		let mut chains = self.lock();
+		let progress = chains.entry(chain).or_insert_with(ChainCatchUp::new);
+		if !progress.caught_up {
+			progress.caught_up = true;
+			tracing::info!(
+				"Caught up {chain} at {} in {}s, {} events found",
+				progress.position,
+				progress.started.elapsed().as_secs(),
+				progress.events_found
+			);
+		}
+	}
+
+	/// Seconds since the last scan of each monitoring, the delay of the events found on its
+	/// chain. Missing until the first scan.
+	pub fn scan_lag_secs(&self) -> BTreeMap<&'static str, u64> {
+		self.lock()
+			.iter()
+			.filter_map(|(chain, progress)| {
+				progress.last_scan.map(|last_scan| (*chain, last_scan.elapsed().as_secs()))
+			})
+			.collect()
+	}
+
+	pub fn is_ready(&self) -> bool {
+		self.lock().values().all(|progress| progress.caught_up)
+	}
+
+	pub fn readiness(&self) -> Readiness {
+		let chains = self.lock();
+		Readiness {
+			ready: chains.values().all(|progress| progress.caught_up),
+			chains: chains
+				.iter()
+				.map(|(chain, progress)| {
+					(
+						chain.to_string(),
+						ChainProgress {
+							position: progress.position,
+							head: progress.head,
+							events_found: progress.events_found,
+							caught_up: progress.caught_up,
+							eta_secs: progress.eta_secs(),
+						},
+					)
+				})
+				.collect(),
+		}
+	}
+
+	pub fn export_prometheus(&self) -> String {
+		let mut out = String::new();
+		let readiness = self.readiness();
+		let _ = writeln!(out, "# TYPE bridge_catchup_position gauge");
+		for (chain, progress) in &readiness.chains {
+			let _ =
+				writeln!(out, "bridge_catchup_position{{chain=\"{chain}\"}} {}", progress.position);
+		}
+		let _ = writeln!(out, "# TYPE bridge_catchup_events_found gauge");
+		for (chain, progress) in &readiness.chains {
+			let _ = writeln!(
+				out,
+				"bridge_catchup_events_found{{chain=\"{chain}\"}} {}",
+				progress.events_found
+			);
+		}
+		let _ = writeln!(out, "# TYPE bridge_catchup_eta_secs gauge");
+		for (chain, progress) in &readiness.chains {
+			if let Some(eta_secs) = progress.eta_secs {
+				let _ = writeln!(out, "bridge_catchup_eta_secs{{chain=\"{chain}\"}} {eta_secs}");
+			}
+		}
+		let _ = writeln!(out, "# TYPE bridge_catchup_caught_up gauge");
+		for (chain, progress) in &readiness.chains {
+			let _ = writeln!(
+				out,
+				"bridge_catchup_caught_up{{chain=\"{chain}\"}} {}",
+				u8::from(progress.caught_up)
+			);
+		}
+		out
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_catch_up_progress() {
+		let progress = CatchUpProgress::default();
+		assert!(progress.is_ready());
+
+		progress.register(ETH_CHAIN);
+		progress.register(MOVEMENT_CHAIN);
+		progress.record_scan(MOVEMENT_CHAIN, 10, None, 10);
+		progress.record_scan(MOVEMENT_CHAIN, 20, None, 10);
+		assert!(!progress.is_ready());
+		let readiness = progress.readiness();
+		assert_eq!(
+			readiness.chains[MOVEMENT_CHAIN],
+			ChainProgress {
+				position: 20,
+				head: None,
+				events_found: 20,
+				caught_up: false,
+				eta_secs: None,
+			}
+		);
+
+		progress.record_scan(ETH_CHAIN, 100, Some(100), 0);
+		progress.mark_caught_up(ETH_CHAIN);
+		assert!(!progress.is_ready());
+		progress.clone().mark_caught_up(MOVEMENT_CHAIN);
+		assert!(progress.is_ready());
+		assert_eq!(progress.readiness().chains[ETH_CHAIN].eta_secs, Some(0));
+		let export = progress.export_prometheus();
+		assert!(export.contains("bridge_catchup_position{chain=\"ethereum\"} 100\n"));
+		assert!(export.contains("bridge_catchup_events_found{chain=\"movement\"} 20\n"));
+		assert!(export.contains("bridge_catchup_caught_up{chain=\"movement\"} 1\n"));
+	}
+}
@@ -0,0 +1,96 @@
+use bridge_util::chains::bridge_contracts::{BridgeContractError, BridgeContractResult};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const DEFAULT_FAILURE_THRESHOLD: u32 = 3;
+const DEFAULT_COOLDOWN: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Default)]
+struct ConnectionState {
+	// The connection must be re-established before the next call.
+	stale: bool,
+	// Incremented on each reconnection so the client clones know their connection is outdated.
+	generation: u64,
+	consecutive_failures: u32,
+	open_until: Option<Instant>,
+}
+
+/// Track the health of a chain client connection.
+/// The connection is marked stale when a call fails and re-established lazily on the next call.
+/// After `failure_threshold` consecutive reconnection failures the breaker opens
+/// and calls fail fast until the cooldown expires.
+/// The state is shared between all clones of the client.
+#[derive(Debug, Clone)]
+pub struct ConnectionBreaker {
+	name: &'static str,
+	failure_threshold: u32,
+	cooldown: Duration,
+	state: Arc<Mutex<ConnectionState>>,
+}
+
+impl ConnectionBreaker {
+	pub fn new(name: &'static str) -> Self {
+		ConnectionBreaker {
+			name,
+			failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+			cooldown: DEFAULT_COOLDOWN,
+			state: Arc::new(Mutex::new(ConnectionState::default())),
+		}
+	}
+
+	/// Mark the connection as to be re-established before the next call.
+	pub fn mark_stale(&self) {
+		let mut state = self.state.lock().expect("Connection state lock poisoned");
+		state.stale = true;
+	}
+
+	/// Return true if the connection of the given generation must be re-established.
+	/// Return an error if the breaker is open.
+	pub fn need_reconnect(&self, generation: u64) -> BridgeContractResult<bool> {
+		let mut state = self.state.lock().expect("Connection state lock poisoned");
+		if let Some(open_until) = state.open_until {
+			if Instant::now() < open_until {
+				return Err(BridgeContractError::OnChainError(format!(
+					"{} connection circuit breaker open after {} failures",
+					self.name, state.consecutive_failures
+				)));
+			}
+			// Cooldown expired, half open: allow one reconnection try.
+			state.open_until = None;
+		}
+		Ok(state.stale || state.generation != generation)
+	}
+
+	/// Whether the calls fail fast until the cooldown expires.
+	pub fn is_open(&self) -> bool {
+		let state = self.state.lock().expect("Connection state lock poisoned");
+		state.open_until.is_some_and(|open_until| Instant::now() < open_until)
+	}
+
+	/// Record a successful reconnection and return the generation of the new connection.
+	pub fn record_success(&self) -> u64 {
+		let mut state = self.state.lock().expect("Connection state lock poisoned");
+		if state.stale {
+			state.generation += 1;
+			state.stale = false;
+		}
+		state.consecutive_failures = 0;
+		state.open_until = None;
+		state.generation
+	}
+
+	pub fn record_failure(&self) {
+		let mut state = self.state.lock().expect("Connection state lock poisoned");
+		state.stale = true;
+		state.consecutive_failures += 1;
+		if state.consecutive_failures >= self.failure_threshold {
+			tracing::warn!(
+				"{} connection circuit breaker open for {:?} after {} failures",
+				self.name,
+				self.cooldown,
+				state.consecutive_failures
+			);
+			state.open_until = Some(Instant::now() + self.cooldown);
+		}
+	}
+}
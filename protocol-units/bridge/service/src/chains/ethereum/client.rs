@@ -1,25 +1,53 @@
+use super::gas::GasStrategy;
+use super::redundancy::RedundantBroadcast;
 use super::types::{
 	AlloyProvider, AssetKind, AtomicBridgeCounterpartyMOVE, AtomicBridgeInitiatorMOVE,
-	CounterpartyContract, EthAddress, InitiatorContract,
+	CounterpartyContract, Erc20Token, EthAddress, IMulticall, InitiatorContract, MockMOVEToken,
+	IERC20,
 };
-use super::utils::{calculate_storage_slot, send_transaction, send_transaction_rules};
+use super::utils::{
+	calculate_storage_slot, is_transport_error, send_transaction_reporting, send_transaction_rules,
+	send_transaction_with_strategy, with_access_list, with_calldata_tag,
+};
+use crate::chains::connection::ConnectionBreaker;
+use crate::drill::{DrillFault, FaultInjector};
+use crate::explorer::{calldata_tag, TxExplorer};
+use crate::fee_tracker::FeeTracker;
+use crate::key_audit::{AuditedEthSigner, KeyAudit, ETHEREUM};
+use crate::metrics::BridgeMetrics;
+use crate::pre_image::PreImageFormat;
+use crate::rpc_cache::{RpcCache, RpcRead};
+use crate::rpc_metrics::RpcMetrics;
+use crate::signer::{EthSigner, SignerBackend};
+use crate::submissions::SentTransactions;
 use alloy::{
-	network::EthereumWallet,
-	primitives::{Address, FixedBytes, U256},
+	contract::{CallBuilder, CallDecoder},
+	eips::{BlockId, BlockNumberOrTag},
+	network::{Ethereum, EthereumWallet},
+	primitives::{Address, Bytes, FixedBytes, LogData, U256},
 	providers::{Provider, ProviderBuilder},
 	rlp::{RlpDecodable, RlpEncodable},
+	rpc::{client::RpcClient, types::TransactionReceipt},
 	signers::local::PrivateKeySigner,
+	sol_types::{decode_revert_reason, SolCall, SolEvent},
+	transports::{BoxTransport, Transport},
 };
 use alloy_primitives::Uint;
 use alloy_rlp::Decodable;
 use bridge_config::common::eth::EthConfig;
+use bridge_config::common::tokens::Erc20TokenConfig;
 use bridge_grpc::bridge_server::BridgeServer;
-use bridge_util::chains::bridge_contracts::{BridgeContractError, BridgeContractResult};
+use bridge_util::chains::bridge_contracts::{
+	BatchResults, BridgeContractError, BridgeContractResult,
+};
 use bridge_util::types::{
 	Amount, BridgeAddress, BridgeTransferDetails, BridgeTransferDetailsCounterparty,
-	BridgeTransferId, HashLock, HashLockPreImage, TimeLock,
+	BridgeTransferId, ChainId, HashLock, HashLockPreImage, LockTransfer, TimeLock,
 };
-use std::{fmt::Debug, net::SocketAddr};
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{fmt::Debug, future::IntoFuture, net::SocketAddr};
 use tonic::transport::Server;
 use tracing::info;
 use url::Url;
@@ -28,30 +56,61 @@ use url::Url;
 #[derive(Clone, Debug)]
 pub struct Config {
 	pub rpc_url: Url,
-	pub signer_private_key: PrivateKeySigner,
+	pub ws_url: Url,
+	/// Expected chain id, not verified if 0.
+	pub chain_id: u64,
+	/// Signs the transactions sent by the client.
+	pub wallet: EthereumWallet,
+	pub signer_address: Address,
 	pub initiator_contract: Address,
 	pub counterparty_contract: Address,
 	pub movetoken_contract: Address,
 	pub gas_limit: u128,
 	pub transaction_send_retries: u32,
+	/// Attach the access list computed by the node to the transactions.
+	pub use_access_lists: bool,
 	pub asset: AssetKind,
+	/// Records the signatures of the wallet.
+	pub key_audit: KeyAudit,
 }
 impl TryFrom<&EthConfig> for Config {
 	type Error = anyhow::Error;
 
 	fn try_from(conf: &EthConfig) -> Result<Self, Self::Error> {
 		let signer_private_key = conf.signer_private_key.parse::<PrivateKeySigner>()?;
+		Config::with_signer(conf, EthSigner::Local(signer_private_key))
+	}
+}
+
+impl Config {
+	/// The config of a client signing with the signer, whatever the private key of the
+	/// Ethereum config.
+	pub fn with_signer(conf: &EthConfig, signer: EthSigner) -> Result<Self, anyhow::Error> {
 		let rpc_url = conf.eth_rpc_connection_url().parse()?;
+		let ws_url = conf.eth_ws_connection_url().parse()?;
+		let initiator_contract = conf.eth_initiator_contract.parse()?;
+		let counterparty_contract = conf.eth_counterparty_contract.parse()?;
+		let key_audit = KeyAudit::default();
 
 		Ok(Config {
 			rpc_url,
-			signer_private_key,
-			initiator_contract: conf.eth_initiator_contract.parse()?,
-			counterparty_contract: conf.eth_counterparty_contract.parse()?,
+			ws_url,
+			chain_id: conf.eth_chain_id,
+			signer_address: signer.address(),
+			wallet: EthereumWallet::from(AuditedEthSigner::new(
+				signer,
+				key_audit.clone(),
+				initiator_contract,
+				counterparty_contract,
+			)),
+			initiator_contract,
+			counterparty_contract,
 			movetoken_contract: conf.eth_move_token_contract.parse()?,
 			gas_limit: conf.gas_limit.into(),
 			transaction_send_retries: conf.transaction_send_retries,
+			use_access_lists: conf.use_access_lists,
 			asset: conf.asset.clone().into(),
+			key_audit,
 		})
 	}
 }
@@ -76,6 +135,100 @@ struct EthBridgeTransferDetailsCounterparty {
 	pub state: u8,
 }
 
+/// Assigns the nonces of the transactions of a signer sent concurrently by the clones of a
+/// client. The nonce filler of the provider reads the nonce of each transaction from the node,
+/// so concurrent transactions get the same one. The nonces sent and not yet mined are tracked
+/// locally, and the next nonce is read again from the pending transaction count of the node
+/// when a gap appears, i.e. a nonce is released without its transaction being mined.
+#[derive(Clone, Debug, Default)]
+pub struct NonceManager {
+	state: Arc<Mutex<NonceState>>,
+}
+
+#[derive(Debug, Default)]
+struct NonceState {
+	// None when the next nonce must be read from the node.
+	next: Option<u64>,
+	// Nonces of the transactions sent and not yet mined.
+	in_flight: BTreeSet<u64>,
+}
+
+impl NonceState {
+	// The next nonce not in flight, from the node pending transaction count if it was read.
+	fn assign(&mut self, pending_count: Option<u64>) -> u64 {
+		if let Some(pending_count) = pending_count {
+			// The transactions of the lower nonces are in the node pool or mined.
+			self.in_flight.retain(|nonce| *nonce >= pending_count);
+			self.next = Some(pending_count);
+		}
+		let mut nonce = self.next.unwrap_or_default();
+		while self.in_flight.contains(&nonce) {
+			nonce += 1;
+		}
+		self.in_flight.insert(nonce);
+		self.next = Some(nonce + 1);
+		nonce
+	}
+}
+
+impl NonceManager {
+	fn lock(&self) -> std::sync::MutexGuard<'_, NonceState> {
+		self.state.lock().expect("Nonce manager lock poisoned")
+	}
+
+	/// Lease the next nonce of the signer. The nonce is released when the lease is dropped,
+	/// unless it's confirmed once its transaction is mined.
+	pub async fn lease<T: Transport + Clone, P: Provider<T, Ethereum>>(
+		&self,
+		provider: &P,
+		signer_address: Address,
+	) -> Result<NonceLease, anyhow::Error> {
+		let pending_count = match self.lock().next {
+			Some(_) => None,
+			None => Some(
+				provider
+					.get_transaction_count(signer_address)
+					.block_id(BlockId::pending())
+					.await?,
+			),
+		};
+		// Concurrent leases may have read the same count, the nonces in flight are skipped.
+		let nonce = self.lock().assign(pending_count);
+		Ok(NonceLease { manager: self.clone(), nonce, confirmed: false })
+	}
+}
+
+/// A nonce assigned by the `NonceManager` to a transaction.
+#[derive(Debug)]
+pub struct NonceLease {
+	manager: NonceManager,
+	nonce: u64,
+	confirmed: bool,
+}
+
+impl NonceLease {
+	pub fn nonce(&self) -> u64 {
+		self.nonce
+	}
+
+	/// The transaction of the nonce is mined, successfully or not.
+	pub fn confirm(mut self) {
+		self.manager.lock().in_flight.remove(&self.nonce);
+		self.confirmed = true;
+	}
+}
+
+impl Drop for NonceLease {
+	fn drop(&mut self) {
+		if !self.confirmed {
+			// The nonce may not have been used: it's a gap to fill.
+			let mut state = self.manager.lock();
+			state.in_flight.remove(&self.nonce);
+			state.next = None;
+		}
+	}
+}
+
 #[derive(Clone)]
 pub struct EthClient {
 	pub rpc_provider: AlloyProvider,
@@ -83,32 +236,338 @@ pub struct EthClient {
 	counterparty_contract: CounterpartyContract,
 	pub config: Config,
 	signer_address: Address,
+	connection: ConnectionBreaker,
+	connection_generation: u64,
+	faults: FaultInjector,
+	rpc_metrics: RpcMetrics,
+	metrics: BridgeMetrics,
+	rpc_cache: RpcCache,
+	// Transport used instead of connecting to the RPC url.
+	rpc_transport: Option<BoxTransport>,
+	pre_image_format: PreImageFormat,
+	// Tag appended with the transfer id to the calldata of the transactions.
+	calldata_tag: Option<String>,
+	tx_explorer: TxExplorer,
+	gas_strategy: GasStrategy,
+	// Shared by the clones of the client.
+	nonce_manager: NonceManager,
+	// Second provider of the completions close to the expiry of their time lock.
+	redundancy: Option<RedundantBroadcast>,
+	fee_tracker: FeeTracker,
+	sent_transactions: SentTransactions,
 }
 
 impl EthClient {
+	/// Create a client signing with the private key of the Ethereum config.
 	pub async fn new(config: &EthConfig) -> Result<Self, anyhow::Error> {
-		let config: Config = config.try_into()?;
-		let signer_address = config.signer_private_key.address();
-		let rpc_provider = ProviderBuilder::new()
-			.with_recommended_fillers()
-			.wallet(EthereumWallet::from(config.signer_private_key.clone()))
-			.on_builtin(config.rpc_url.as_str())
-			.await?;
+		Self::with_signer_backend(config, &SignerBackend::Local).await
+	}
+
+	/// Create a client that has its transactions signed by the signer of the backend. The
+	/// private key of the Ethereum config is only used by the local backend.
+	pub async fn with_signer_backend(
+		config: &EthConfig,
+		backend: &SignerBackend,
+	) -> Result<Self, anyhow::Error> {
+		let signer = backend.connect(config).await?;
+		Self::with_config(Config::with_signer(config, signer)?).await
+	}
+
+	/// Create a client sending its RPC requests on the transport, so tests can script
+	/// the node responses. The RPC url of the config is not used.
+	pub fn with_transport(config: Config, transport: BoxTransport) -> Self {
+		let rpc_provider = Self::transport_provider(&config, transport.clone());
+		let mut client = Self::with_provider(config, rpc_provider);
+		client.rpc_transport = Some(transport);
+		client
+	}
+
+	async fn with_config(config: Config) -> Result<Self, anyhow::Error> {
+		let rpc_provider = Self::connect_provider(&config, config.rpc_url.as_str()).await?;
+		Ok(Self::with_provider(config, rpc_provider))
+	}
+
+	fn with_provider(config: Config, rpc_provider: AlloyProvider) -> Self {
+		let signer_address = config.signer_address;
 
 		let initiator_contract =
 			AtomicBridgeInitiatorMOVE::new(config.initiator_contract, rpc_provider.clone());
 		let counterparty_contract =
 			AtomicBridgeCounterpartyMOVE::new(config.counterparty_contract, rpc_provider.clone());
 
-		Ok(EthClient {
+		EthClient {
 			rpc_provider,
 			initiator_contract,
 			counterparty_contract,
-			config: config.clone(),
+			config,
 			signer_address,
+			connection: ConnectionBreaker::new("Ethereum"),
+			connection_generation: 0,
+			faults: FaultInjector::default(),
+			rpc_metrics: RpcMetrics::default(),
+			metrics: BridgeMetrics::default(),
+			rpc_cache: RpcCache::default(),
+			rpc_transport: None,
+			pre_image_format: PreImageFormat::default(),
+			calldata_tag: None,
+			tx_explorer: TxExplorer::default(),
+			gas_strategy: GasStrategy::default(),
+			nonce_manager: NonceManager::default(),
+			redundancy: None,
+			fee_tracker: FeeTracker::default(),
+			sent_transactions: SentTransactions::default(),
+		}
+	}
+
+	pub(crate) async fn connect_provider(
+		config: &Config,
+		url: &str,
+	) -> Result<AlloyProvider, anyhow::Error> {
+		let provider = ProviderBuilder::new()
+			.with_recommended_fillers()
+			.wallet(config.wallet.clone())
+			.on_builtin(url)
+			.await?;
+		Ok(provider)
+	}
+
+	fn transport_provider(config: &Config, transport: BoxTransport) -> AlloyProvider {
+		ProviderBuilder::new()
+			.with_recommended_fillers()
+			.wallet(config.wallet.clone())
+			.on_client(RpcClient::new(transport, true))
+	}
+
+	/// Establish the HTTP and WS connections and verify they serve the configured chain,
+	/// so the first transfer doesn't pay the connection cost.
+	pub async fn warmup(&self) -> Result<(), anyhow::Error> {
+		let http_chain_id = self.chain_id().await?;
+		let ws_provider = Self::connect_provider(&self.config, self.config.ws_url.as_str()).await?;
+		let ws_chain_id = ws_provider.get_chain_id().await?;
+		if http_chain_id != ws_chain_id {
+			anyhow::bail!(
+				"Ethereum HTTP chain id {http_chain_id} differs from WS chain id {ws_chain_id}"
+			);
+		}
+		if self.config.chain_id != 0 && http_chain_id != self.config.chain_id {
+			anyhow::bail!(
+				"Ethereum chain id {http_chain_id} differs from configured chain id {}",
+				self.config.chain_id
+			);
+		}
+		info!("Ethereum connections warmed up, chain id: {http_chain_id}");
+		Ok(())
+	}
+
+	/// Re-establish the connection if a previous call failed on it.
+	async fn ensure_connection(&mut self) -> BridgeContractResult<()> {
+		let need_reconnect = self.connection.need_reconnect(self.connection_generation)?;
+		if let Err(err) = self.faults.check(DrillFault::RpcFailure, ETHEREUM) {
+			self.connection.record_failure();
+			return Err(err);
+		}
+		if !need_reconnect {
+			return Ok(());
+		}
+		tracing::info!("Reconnecting Ethereum client to {}", self.config.rpc_url);
+		let connected = match &self.rpc_transport {
+			Some(transport) => Ok(Self::transport_provider(&self.config, transport.clone())),
+			None => Self::connect_provider(&self.config, self.config.rpc_url.as_str()).await,
+		};
+		let rpc_provider = match connected {
+			Ok(rpc_provider) => rpc_provider,
+			Err(err) => {
+				self.connection.record_failure();
+				return Err(BridgeContractError::OnChainError(format!(
+					"Failed to reconnect Ethereum client: {err}"
+				)));
+			}
+		};
+		self.initiator_contract =
+			AtomicBridgeInitiatorMOVE::new(self.config.initiator_contract, rpc_provider.clone());
+		self.counterparty_contract = AtomicBridgeCounterpartyMOVE::new(
+			self.config.counterparty_contract,
+			rpc_provider.clone(),
+		);
+		self.rpc_provider = rpc_provider;
+		self.connection_generation = self.connection.record_success();
+		Ok(())
+	}
+
+	// Check the preimage has the format of the token pair, so the secret revealed
+	// on Ethereum is the one the Movement modules accept.
+	fn validate_pre_image(&self, pre_image: &HashLockPreImage) -> BridgeContractResult<()> {
+		self.pre_image_format.validate(pre_image).map_err(|err| {
+			tracing::warn!("Invalid preimage for the {:?} format: {err}", self.pre_image_format);
+			BridgeContractError::ParsePreimageError
 		})
 	}
 
+	/// Send the transaction of the operation on the transfer, tagged if configured.
+	async fn submit<D: CallDecoder + Clone>(
+		&self,
+		operation: &'static str,
+		bridge_transfer_id: BridgeTransferId,
+		call: CallBuilder<BoxTransport, &AlloyProvider, D, Ethereum>,
+	) -> BridgeContractResult<TransactionReceipt> {
+		let call = self.tag(operation, bridge_transfer_id, call)?;
+		let receipt = self.send(operation, Some(bridge_transfer_id), call).await?;
+		self.fee_tracker
+			.record(ChainId::ONE, bridge_transfer_id, transaction_fee(&receipt));
+		tracing::info!(
+			"Transaction {operation} of transfer {bridge_transfer_id} executed: {}",
+			self.tx_explorer.display(&receipt.transaction_hash.to_string())
+		);
+		Ok(receipt)
+	}
+
+	/// Send the critical transaction of the operation on the transfer through both RPC
+	/// providers, tagged if configured.
+	async fn submit_redundantly<D: CallDecoder + Clone>(
+		&self,
+		operation: &'static str,
+		bridge_transfer_id: BridgeTransferId,
+		call: CallBuilder<BoxTransport, &AlloyProvider, D, Ethereum>,
+		redundancy: &RedundantBroadcast,
+	) -> BridgeContractResult<TransactionReceipt> {
+		self.faults.check(DrillFault::SignerUnavailable, ETHEREUM)?;
+		let call = self.tag(operation, bridge_transfer_id, call)?;
+		let receipt = redundancy
+			.send(
+				call,
+				&self.config.wallet,
+				self.signer_address,
+				self.config.gas_limit,
+				&self.rpc_cache,
+				&self.gas_strategy,
+				&self.nonce_manager,
+				|tx_hash| {
+					self.sent_transactions.report(
+						ChainId::ONE,
+						operation,
+						Some(bridge_transfer_id),
+						tx_hash.as_slice(),
+					)
+				},
+			)
+			.await
+			.map_err(|e| {
+				BridgeContractError::OnChainError(format!("Failed to broadcast transaction: {e}"))
+			})?;
+		self.rpc_metrics.record_gas(operation, receipt.gas_used);
+		self.metrics.record_gas_spent(ChainId::ONE, transaction_fee(&receipt));
+		self.fee_tracker
+			.record(ChainId::ONE, bridge_transfer_id, transaction_fee(&receipt));
+		tracing::info!(
+			"Transaction {operation} of transfer {bridge_transfer_id} executed: {}",
+			self.tx_explorer.display(&receipt.transaction_hash.to_string())
+		);
+		Ok(receipt)
+	}
+
+	// The call tagged with the transfer id, if configured.
+	fn tag<D: CallDecoder + Clone>(
+		&self,
+		operation: &'static str,
+		bridge_transfer_id: BridgeTransferId,
+		call: CallBuilder<BoxTransport, &AlloyProvider, D, Ethereum>,
+	) -> BridgeContractResult<CallBuilder<BoxTransport, &AlloyProvider, D, Ethereum>> {
+		match &self.calldata_tag {
+			Some(tag) => with_calldata_tag(call, &calldata_tag(tag, &bridge_transfer_id))
+				.ok_or_else(|| {
+					BridgeContractError::GenericError(format!("Can't tag the {operation} call"))
+				}),
+			None => Ok(call),
+		}
+	}
+
+	/// Send the transaction of the operation, with its access list if configured,
+	/// and record the gas it used. The transaction of an operation on a transfer is reported
+	/// once accepted by the node.
+	async fn send<D: CallDecoder + Clone>(
+		&self,
+		operation: &'static str,
+		bridge_transfer_id: Option<BridgeTransferId>,
+		call: CallBuilder<BoxTransport, &AlloyProvider, D, Ethereum>,
+	) -> BridgeContractResult<TransactionReceipt> {
+		self.faults.check(DrillFault::SignerUnavailable, ETHEREUM)?;
+		let call = if self.config.use_access_lists {
+			with_access_list(call, self.signer_address).await
+		} else {
+			call
+		};
+		let receipt = self
+			.rpc_metrics
+			.observe(
+				self.config.rpc_url.as_str(),
+				send_transaction_reporting(
+					call,
+					self.signer_address,
+					&send_transaction_rules(),
+					self.config.transaction_send_retries,
+					self.config.gas_limit,
+					&self.rpc_cache,
+					&self.gas_strategy,
+					Some(&self.nonce_manager),
+					|tx_hash| {
+						self.sent_transactions.report(
+							ChainId::ONE,
+							operation,
+							bridge_transfer_id,
+							tx_hash.as_slice(),
+						)
+					},
+				),
+			)
+			.await
+			.inspect_err(|err| self.send_failed(err))
+			.map_err(|e| {
+				BridgeContractError::OnChainError(format!("Failed to send transaction: {}", e))
+			})?;
+		self.rpc_metrics.record_gas(operation, receipt.gas_used);
+		self.metrics.record_gas_spent(ChainId::ONE, transaction_fee(&receipt));
+		Ok(receipt)
+	}
+
+	// Send the calls of a batch, with the index of their transfer in the batch, in a single
+	// transaction and set the result of each transfer sent from the calls that reverted.
+	async fn send_batch(
+		&self,
+		operation: &'static str,
+		contract: Address,
+		calls: Vec<(usize, BridgeTransferId, Bytes)>,
+		results: &mut BatchResults,
+	) -> BridgeContractResult<()> {
+		if calls.is_empty() {
+			return Ok(());
+		}
+		let transfer_ids: Vec<_> = calls.iter().map(|(_, id, _)| *id).collect();
+		let data = calls.iter().map(|(_, _, call)| call.clone()).collect();
+		// The calls are delegated by the contract to itself, the relayer stays the sender.
+		let batch = IMulticall::new(contract, self.rpc_provider.clone());
+		let receipt = self.send(operation, None, batch.tryMulticall(data)).await?;
+		self.fee_tracker
+			.record_batch(ChainId::ONE, &transfer_ids, transaction_fee(&receipt));
+		let logs: Vec<_> = receipt
+			.inner
+			.logs()
+			.iter()
+			.filter(|log| log.address() == contract)
+			.map(|log| log.data().clone())
+			.collect();
+		let call_results = batch_call_results(&logs, calls.len());
+		let failed = call_results.iter().filter(|result| result.is_err()).count();
+		tracing::info!(
+			"{operation} of {} transfers executed, {failed} failed: {}",
+			calls.len(),
+			self.tx_explorer.display(&receipt.transaction_hash.to_string())
+		);
+		for ((index, _, _), result) in calls.iter().zip(call_results) {
+			results[*index] = result;
+		}
+		Ok(())
+	}
+
 	/// Start the gRPC server
 	/// internally this passes a cloned self `EthClient` as the service.
 	pub async fn serve_grpc(
@@ -139,18 +598,110 @@ impl EthClient {
 			contract.initialize(self.signer_address, initiator_address, U256::from(timelock.0));
 
 		// Send the transaction
-		send_transaction(
+		send_transaction_with_strategy(
 			call.to_owned(),
 			self.signer_address,
 			&send_transaction_rules(),
 			self.config.transaction_send_retries,
 			self.config.gas_limit,
+			&self.rpc_cache,
+			&self.gas_strategy,
+			Some(&self.nonce_manager),
 		)
 		.await?;
 
 		Ok(())
 	}
 
+	/// Transfer MOVE tokens from the signer account and return the transaction hash.
+	pub async fn transfer_move_token(
+		&self,
+		recipient: Address,
+		amount: Amount,
+	) -> Result<Vec<u8>, anyhow::Error> {
+		let contract =
+			MockMOVEToken::new(self.config.movetoken_contract, self.rpc_provider.clone());
+		let call = contract.transfer(recipient, U256::from(amount.0));
+		let receipt = self
+			.rpc_metrics
+			.observe(
+				self.config.rpc_url.as_str(),
+				send_transaction_with_strategy(
+					call,
+					self.signer_address,
+					&send_transaction_rules(),
+					self.config.transaction_send_retries,
+					self.config.gas_limit,
+					&self.rpc_cache,
+					&self.gas_strategy,
+					Some(&self.nonce_manager),
+				),
+			)
+			.await?;
+		Ok(receipt.transaction_hash.to_vec())
+	}
+
+	/// Spenders approved by the wallet on the token in the blocks, from its Approval events.
+	pub async fn approved_spenders(
+		&self,
+		token: Address,
+		from_block: u64,
+		to_block: u64,
+	) -> BridgeContractResult<BTreeSet<Address>> {
+		let contract = MockMOVEToken::new(token, self.rpc_provider.clone());
+		let approvals = contract
+			.Approval_filter()
+			.topic1(self.signer_address.into_word())
+			.from_block(BlockNumberOrTag::Number(from_block))
+			.to_block(BlockNumberOrTag::Number(to_block))
+			.query()
+			.await
+			.inspect_err(|_| self.rpc_failed())
+			.map_err(|e| {
+				BridgeContractError::OnChainError(format!("Failed to read the approvals: {e}"))
+			})?;
+		Ok(approvals.into_iter().map(|(approval, _)| approval.spender).collect())
+	}
+
+	/// Allowance of the wallet to the spender on the token.
+	pub async fn allowance(&self, token: Address, spender: Address) -> BridgeContractResult<U256> {
+		let contract = MockMOVEToken::new(token, self.rpc_provider.clone());
+		let allowance = contract
+			.allowance(self.signer_address, spender)
+			.call()
+			.await
+			.inspect_err(|_| self.rpc_failed())
+			.map_err(|e| {
+				BridgeContractError::OnChainError(format!("Failed to read the allowance: {e}"))
+			})?;
+		Ok(allowance._0)
+	}
+
+	/// MOVE token balance of the wallet.
+	pub async fn move_token_balance(&self) -> BridgeContractResult<U256> {
+		let contract =
+			MockMOVEToken::new(self.config.movetoken_contract, self.rpc_provider.clone());
+		let balance = contract
+			.balanceOf(self.signer_address)
+			.call()
+			.await
+			.inspect_err(|_| self.rpc_failed())
+			.map_err(|e| {
+				BridgeContractError::OnChainError(format!("Failed to read the balance: {e}"))
+			})?;
+		Ok(balance._0)
+	}
+
+	/// Set the allowance of the wallet to the spender on the token to zero.
+	pub async fn revoke_allowance(
+		&self,
+		token: Address,
+		spender: Address,
+	) -> BridgeContractResult<TransactionReceipt> {
+		let contract = MockMOVEToken::new(token, self.rpc_provider.clone());
+		self.send("revoke_allowance", None, contract.approve(spender, U256::ZERO)).await
+	}
+
 	pub async fn get_block_number(&self) -> Result<u64, anyhow::Error> {
 		self.rpc_provider
 			.get_block_number()
@@ -159,13 +710,236 @@ impl EthClient {
 	}
 
 	pub fn get_signer_address(&self) -> Address {
-		self.config.signer_private_key.address()
+		self.config.signer_address
+	}
+
+	/// The audit trail of the signatures of the wallet.
+	pub fn key_audit(&self) -> &KeyAudit {
+		&self.config.key_audit
+	}
+
+	/// Share the RPC metrics with other clients and the admin API.
+	pub fn set_rpc_metrics(&mut self, rpc_metrics: RpcMetrics) {
+		self.rpc_metrics = rpc_metrics;
+	}
+
+	/// Share the throughput and failure metrics with the other clients and the metrics
+	/// endpoints.
+	pub fn set_bridge_metrics(&mut self, metrics: BridgeMetrics) {
+		self.metrics = metrics;
+	}
+
+	/// Share the faults injected by the failover drills with the other clients.
+	pub fn set_fault_injector(&mut self, faults: FaultInjector) {
+		self.faults = faults;
+	}
+
+	pub fn connection(&self) -> &ConnectionBreaker {
+		&self.connection
+	}
+
+	// A failed RPC call: the connection is re-established before the next one.
+	fn rpc_failed(&self) {
+		self.connection.mark_stale();
+		self.metrics.record_rpc_error(ChainId::ONE);
+	}
+
+	// A failed transaction: only a failure of the transport breaks the connection, the node
+	// answered the reverts and the nonce conflicts.
+	fn send_failed(&self, err: &anyhow::Error) {
+		if is_transport_error(err) {
+			self.rpc_failed();
+		}
+	}
+
+	/// Set the format of the preimages of the token pair,
+	/// the preimages are checked against it before completing a transfer.
+	pub fn set_pre_image_format(&mut self, pre_image_format: PreImageFormat) {
+		self.pre_image_format = pre_image_format;
+	}
+
+	/// Tag the transactions with the tag of the relayer followed by the transfer id,
+	/// so they can be found in the block explorers.
+	pub fn set_calldata_tag(&mut self, calldata_tag: Option<String>) {
+		self.calldata_tag = calldata_tag;
+	}
+
+	/// Set the explorer of the transactions logged by the client.
+	pub fn set_tx_explorer(&mut self, tx_explorer: TxExplorer) {
+		self.tx_explorer = tx_explorer;
+	}
+
+	pub fn tx_explorer(&self) -> &TxExplorer {
+		&self.tx_explorer
+	}
+
+	/// Set the strategy of the fees of the transactions sent by the client.
+	pub fn set_gas_strategy(&mut self, gas_strategy: GasStrategy) {
+		self.gas_strategy = gas_strategy;
+	}
+
+	/// Share the cache of the idempotent RPC reads with other clients.
+	pub fn set_rpc_cache(&mut self, rpc_cache: RpcCache) {
+		self.rpc_cache = rpc_cache;
+	}
+
+	/// Share the fees paid on the transfers with the other clients and the status API.
+	pub fn set_fee_tracker(&mut self, fee_tracker: FeeTracker) {
+		self.fee_tracker = fee_tracker;
+	}
+
+	/// Report the transactions sent on the transfers to the outbox of the relayer loop.
+	pub fn set_sent_transactions(&mut self, sent_transactions: SentTransactions) {
+		self.sent_transactions = sent_transactions;
+	}
+
+	/// Broadcast the completions close to the expiry of their time lock through the second
+	/// provider too.
+	pub fn set_redundancy(&mut self, redundancy: Option<RedundantBroadcast>) {
+		self.redundancy = redundancy;
+	}
+
+	pub async fn chain_id(&self) -> Result<u64, anyhow::Error> {
+		let chain_id = self
+			.rpc_cache
+			.get_or_fetch(RpcRead::ChainId, "", self.rpc_provider.get_chain_id())
+			.await?;
+		Ok(chain_id)
+	}
+
+	pub async fn gas_price(&self) -> Result<u128, anyhow::Error> {
+		let gas_price = self
+			.rpc_cache
+			.get_or_fetch(RpcRead::GasPrice, "", self.rpc_provider.get_gas_price())
+			.await?;
+		Ok(gas_price)
+	}
+
+	/// Gas units the refund of the transfer is expected to use when sent by its initiator.
+	pub async fn estimate_refund_gas(
+		&self,
+		bridge_transfer_id: BridgeTransferId,
+		initiator: Address,
+	) -> Result<u128, anyhow::Error> {
+		let gas = self
+			.initiator_contract
+			.refundBridgeTransfer(FixedBytes(bridge_transfer_id.0))
+			.from(initiator)
+			.estimate_gas()
+			.await?;
+		Ok(gas)
+	}
+
+	/// The deployed code at the address, empty if no contract is deployed there.
+	pub async fn code_at(&self, address: Address) -> Result<Vec<u8>, anyhow::Error> {
+		let code = self
+			.rpc_cache
+			.get_or_fetch(
+				RpcRead::Code,
+				&address.to_string(),
+				self.rpc_provider.get_code_at(address).into_future(),
+			)
+			.await?;
+		Ok(code.to_vec())
+	}
+
+	pub async fn token_decimals(&self, token: Address) -> Result<u8, anyhow::Error> {
+		let contract = MockMOVEToken::new(token, self.rpc_provider.clone());
+		let decimals = self
+			.rpc_cache
+			.get_or_fetch(RpcRead::TokenDecimals, &token.to_string(), async {
+				contract.decimals().call().await.map(|decimals| decimals._0)
+			})
+			.await?;
+		Ok(decimals)
+	}
+
+	/// The ERC-20 token of the config, its decimals are read from the token contract
+	/// if they aren't configured.
+	pub async fn erc20_token(
+		&self,
+		config: &Erc20TokenConfig,
+	) -> Result<Erc20Token, anyhow::Error> {
+		let token_contract = config.token_contract.parse()?;
+		let decimals = match config.decimals {
+			Some(decimals) => decimals,
+			None => self.token_decimals(token_contract).await?,
+		};
+		Ok(Erc20Token {
+			symbol: config.symbol.clone(),
+			token_contract,
+			initiator_contract: config.initiator_contract.parse()?,
+			decimals,
+		})
+	}
+
+	/// Amount of the token the initiator contract of the token can transfer from the signer.
+	pub async fn erc20_allowance(&self, token: &Erc20Token) -> BridgeContractResult<U256> {
+		let contract = IERC20::new(token.token_contract, self.rpc_provider.clone());
+		let allowance = contract
+			.allowance(self.signer_address, token.initiator_contract)
+			.call()
+			.await
+			.map_err(|e| {
+				BridgeContractError::GenericError(format!(
+					"Failed to read the {} allowance: {e}",
+					token.symbol
+				))
+			})?;
+		Ok(allowance._0)
+	}
+
+	/// Initiate a transfer of an ERC-20 token with its initiator contract. The contract
+	/// is approved first for the amount if its allowance doesn't cover it.
+	pub async fn initiate_bridge_transfer_erc20(
+		&mut self,
+		token: &Erc20Token,
+		recipient: BridgeAddress<Vec<u8>>,
+		hash_lock: HashLock,
+		amount: Amount,
+	) -> BridgeContractResult<()> {
+		self.ensure_connection().await?;
+		let recipient_bytes: [u8; 32] = recipient.0.try_into().map_err(|e| {
+			BridgeContractError::ConversionFailed(format!(
+				"Failed to convert in [u8; 32] recipient: {e:?}"
+			))
+		})?;
+		let token_amount = token.to_token_amount(amount)?;
+		let allowance = self.erc20_allowance(token).await?;
+		if allowance < token_amount {
+			tracing::info!(
+				"Approve {token_amount} {} for {}, allowance: {allowance}",
+				token.symbol,
+				token.initiator_contract
+			);
+			let contract = IERC20::new(token.token_contract, self.rpc_provider.clone());
+			let call = contract
+				.approve(token.initiator_contract, token_amount)
+				.from(self.signer_address);
+			self.send("approve", None, call).await?;
+		}
+		let contract =
+			AtomicBridgeInitiatorMOVE::new(token.initiator_contract, self.rpc_provider.clone());
+		let call = contract
+			.initiateBridgeTransfer(
+				token_amount,
+				FixedBytes(recipient_bytes),
+				FixedBytes(hash_lock.0),
+			)
+			.from(self.signer_address);
+		self.send("initiate", None, call).await?;
+
+		Ok(())
 	}
 
 	pub fn set_initiator_contract(&mut self, contract: InitiatorContract) {
 		self.initiator_contract = contract;
 	}
 
+	pub fn signer_address(&self) -> Address {
+		self.signer_address
+	}
+
 	pub fn initiator_contract_address(&self) -> Address {
 		self.config.initiator_contract
 	}
@@ -187,6 +961,7 @@ impl bridge_util::chains::bridge_contracts::BridgeContract<EthAddress> for EthCl
 		hash_lock: HashLock,
 		amount: Amount, // the ETH amount
 	) -> BridgeContractResult<()> {
+		self.ensure_connection().await?;
 		let recipient_bytes: [u8; 32] = recipient.0.try_into().map_err(|e| {
 			BridgeContractError::ConversionFailed(format!(
 				"Failed to convert in [u8; 32] recipient: {e:?}"
@@ -203,17 +978,26 @@ impl bridge_util::chains::bridge_contracts::BridgeContract<EthAddress> for EthCl
 				FixedBytes(hash_lock.0),
 			)
 			.from(*initiator.0);
-		let _ = send_transaction(
-			call,
-			self.signer_address,
-			&send_transaction_rules(),
-			self.config.transaction_send_retries,
-			self.config.gas_limit,
-		)
-		.await
-		.map_err(|e| {
-			BridgeContractError::GenericError(format!("Failed to send transaction: {}", e))
-		})?;
+		let _ = self
+			.rpc_metrics
+			.observe(
+				self.config.rpc_url.as_str(),
+				send_transaction_with_strategy(
+					call,
+					self.signer_address,
+					&send_transaction_rules(),
+					self.config.transaction_send_retries,
+					self.config.gas_limit,
+					&self.rpc_cache,
+					&self.gas_strategy,
+					Some(&self.nonce_manager),
+				),
+			)
+			.await
+			.inspect_err(|err| self.send_failed(err))
+			.map_err(|e| {
+				BridgeContractError::GenericError(format!("Failed to send transaction: {}", e))
+			})?;
 
 		Ok(())
 	}
@@ -223,6 +1007,8 @@ impl bridge_util::chains::bridge_contracts::BridgeContract<EthAddress> for EthCl
 		bridge_transfer_id: BridgeTransferId,
 		pre_image: HashLockPreImage,
 	) -> BridgeContractResult<()> {
+		self.ensure_connection().await?;
+		self.validate_pre_image(&pre_image)?;
 		// The Alloy generated type for smart contract`pre_image` arg is `FixedBytes<32>`
 		// so it must be converted to `[u8; 32]`.
 		let generic_error = |desc| BridgeContractError::GenericError(String::from(desc));
@@ -233,23 +1019,38 @@ impl bridge_util::chains::bridge_contracts::BridgeContract<EthAddress> for EthCl
 			.try_into()
 			.map_err(|_| generic_error("Could not convert pre-image to [u8; 32]"))?;
 		info! {"Pre-image: {:?}", pre_image};
-		let contract = AtomicBridgeInitiatorMOVE::new(
-			self.config.initiator_contract,
-			self.rpc_provider.clone(),
-		);
-		let call = contract
+		// The time lock is only read if the completion may be broadcast through both providers.
+		let redundancy = match self.redundancy.clone() {
+			Some(redundancy) => {
+				let time_lock = self
+					.get_bridge_transfer_details_initiator(bridge_transfer_id)
+					.await?
+					.map(|details| details.time_lock.0)
+					.unwrap_or_default();
+				redundancy.is_critical(time_lock, now_secs()).then_some(redundancy)
+			}
+			None => None,
+		};
+		let call = self
+			.initiator_contract
 			.completeBridgeTransfer(FixedBytes(bridge_transfer_id.0), FixedBytes(pre_image));
-		send_transaction(
-			call,
-			self.signer_address,
-			&send_transaction_rules(),
-			self.config.transaction_send_retries,
-			self.config.gas_limit,
-		)
-		.await
-		.map_err(|e| {
-			BridgeContractError::OnChainError(format!("Failed to send transaction: {}", e))
-		})?;
+		match redundancy {
+			Some(redundancy) => {
+				tracing::info!(
+					"Completion of transfer {bridge_transfer_id} is close to its time lock"
+				);
+				self.submit_redundantly(
+					"complete_initiator",
+					bridge_transfer_id,
+					call,
+					&redundancy,
+				)
+				.await?;
+			}
+			None => {
+				self.submit("complete_initiator", bridge_transfer_id, call).await?;
+			}
+		}
 
 		Ok(())
 	}
@@ -259,6 +1060,8 @@ impl bridge_util::chains::bridge_contracts::BridgeContract<EthAddress> for EthCl
 		bridge_transfer_id: BridgeTransferId,
 		pre_image: HashLockPreImage,
 	) -> BridgeContractResult<()> {
+		self.ensure_connection().await?;
+		self.validate_pre_image(&pre_image)?;
 		// The Alloy generated type for smart contract`pre_image` arg is `FixedBytes<32>`
 		// so it must be converted to `[u8; 32]`.
 		let generic_error = |desc| BridgeContractError::GenericError(String::from(desc));
@@ -269,24 +1072,10 @@ impl bridge_util::chains::bridge_contracts::BridgeContract<EthAddress> for EthCl
 			.try_into()
 			.map_err(|_| generic_error("Could not convert pre-image to [u8; 32]"))?;
 
-		let contract = AtomicBridgeCounterpartyMOVE::new(
-			self.config.counterparty_contract,
-			self.rpc_provider.clone(),
-		);
-
-		let call = contract
+		let call = self
+			.counterparty_contract
 			.completeBridgeTransfer(FixedBytes(bridge_transfer_id.0), FixedBytes(pre_image));
-		send_transaction(
-			call,
-			self.signer_address,
-			&send_transaction_rules(),
-			self.config.transaction_send_retries,
-			self.config.gas_limit,
-		)
-		.await
-		.map_err(|e| {
-			BridgeContractError::OnChainError(format!("Failed to send transaction: {}", e))
-		})?;
+		self.submit("complete_counterparty", bridge_transfer_id, call).await?;
 
 		Ok(())
 	}
@@ -295,24 +1084,10 @@ impl bridge_util::chains::bridge_contracts::BridgeContract<EthAddress> for EthCl
 		&mut self,
 		bridge_transfer_id: BridgeTransferId,
 	) -> BridgeContractResult<()> {
-		let contract = AtomicBridgeInitiatorMOVE::new(
-			self.config.initiator_contract,
-			self.rpc_provider.clone(),
-		);
+		self.ensure_connection().await?;
 		tracing::info!("Bridge transfer ID: {:?}", bridge_transfer_id);
-		let call = contract.refundBridgeTransfer(FixedBytes(bridge_transfer_id.0));
-
-		send_transaction(
-			call,
-			self.signer_address,
-			&send_transaction_rules(),
-			self.config.transaction_send_retries,
-			self.config.gas_limit,
-		)
-		.await
-		.map_err(|e| {
-			BridgeContractError::OnChainError(format!("Failed to send transaction: {}", e))
-		})?;
+		let call = self.initiator_contract.refundBridgeTransfer(FixedBytes(bridge_transfer_id.0));
+		self.submit("refund", bridge_transfer_id, call).await?;
 
 		Ok(())
 	}
@@ -325,6 +1100,7 @@ impl bridge_util::chains::bridge_contracts::BridgeContract<EthAddress> for EthCl
 		recipient: BridgeAddress<EthAddress>,
 		amount: Amount,
 	) -> BridgeContractResult<()> {
+		self.ensure_connection().await?;
 		tracing::info!("Begin lockBridgeTransfer");
 		let initiator: [u8; 32] = initiator.0.try_into().map_err(|_| {
 			BridgeContractError::ConversionFailed("lock_bridge_transfer initiator".to_string())
@@ -346,17 +1122,7 @@ impl bridge_util::chains::bridge_contracts::BridgeContract<EthAddress> for EthCl
 			self.signer_address
 		);
 
-		let receipt = send_transaction(
-			call,
-			self.signer_address,
-			&send_transaction_rules(),
-			self.config.transaction_send_retries,
-			self.config.gas_limit,
-		)
-		.await
-		.map_err(|e| {
-			BridgeContractError::OnChainError(format!("Failed to send transaction: {}", e))
-		})?;
+		let receipt = self.submit("lock", bridge_transfer_id, call).await?;
 
 		tracing::info!("LockBridgeTransfer receipt: {:?}", receipt);
 
@@ -367,34 +1133,9 @@ impl bridge_util::chains::bridge_contracts::BridgeContract<EthAddress> for EthCl
 		&mut self,
 		bridge_transfer_id: BridgeTransferId,
 	) -> BridgeContractResult<()> {
-		let contract = AtomicBridgeCounterpartyMOVE::new(
-			self.config.counterparty_contract,
-			self.rpc_provider.clone(),
-		);
-		let call = contract.abortBridgeTransfer(FixedBytes(bridge_transfer_id.0));
-		send_transaction(
-			call,
-			self.signer_address,
-			&send_transaction_rules(),
-			self.config.transaction_send_retries,
-			self.config.gas_limit,
-		)
-		.await
-		.map_err(|e| {
-			BridgeContractError::OnChainError(format!("Failed to send transaction: {}", e))
-		})?;
-		let call = contract.abortBridgeTransfer(FixedBytes(bridge_transfer_id.0));
-		send_transaction(
-			call,
-			self.signer_address,
-			&send_transaction_rules(),
-			self.config.transaction_send_retries,
-			self.config.gas_limit,
-		)
-		.await
-		.map_err(|e| {
-			BridgeContractError::OnChainError(format!("Failed to send transaction: {}", e))
-		})?;
+		self.ensure_connection().await?;
+		let call = self.counterparty_contract.abortBridgeTransfer(FixedBytes(bridge_transfer_id.0));
+		self.submit("abort", bridge_transfer_id, call).await?;
 
 		Ok(())
 	}
@@ -403,15 +1144,22 @@ impl bridge_util::chains::bridge_contracts::BridgeContract<EthAddress> for EthCl
 		&mut self,
 		bridge_transfer_id: BridgeTransferId,
 	) -> BridgeContractResult<Option<BridgeTransferDetails<EthAddress>>> {
+		self.ensure_connection().await?;
 		let generic_error = |desc| BridgeContractError::GenericError(String::from(desc));
 
 		let mapping_slot = U256::from(0); // the mapping is the zeroth slot in the contract
 		let key = bridge_transfer_id.0.clone();
 		let storage_slot = calculate_storage_slot(key, mapping_slot);
 		let storage: U256 = self
-			.rpc_provider
-			.get_storage_at(self.initiator_contract_address(), storage_slot)
+			.rpc_metrics
+			.observe(
+				self.config.rpc_url.as_str(),
+				self.rpc_provider
+					.get_storage_at(self.initiator_contract_address(), storage_slot)
+					.into_future(),
+			)
 			.await
+			.inspect_err(|_| self.rpc_failed())
 			.map_err(|_| generic_error("could not find storage"))?;
 		let storage_bytes = storage.to_be_bytes::<32>();
 
@@ -435,15 +1183,22 @@ impl bridge_util::chains::bridge_contracts::BridgeContract<EthAddress> for EthCl
 		&mut self,
 		bridge_transfer_id: BridgeTransferId,
 	) -> BridgeContractResult<Option<BridgeTransferDetailsCounterparty<EthAddress>>> {
+		self.ensure_connection().await?;
 		let generic_error = |desc| BridgeContractError::GenericError(String::from(desc));
 
 		let mapping_slot = U256::from(0); // the mapping is the zeroth slot in the contract
 		let key = bridge_transfer_id.0.clone();
 		let storage_slot = calculate_storage_slot(key, mapping_slot);
 		let storage: U256 = self
-			.rpc_provider
-			.get_storage_at(self.initiator_contract_address(), storage_slot)
+			.rpc_metrics
+			.observe(
+				self.config.rpc_url.as_str(),
+				self.rpc_provider
+					.get_storage_at(self.initiator_contract_address(), storage_slot)
+					.into_future(),
+			)
 			.await
+			.inspect_err(|_| self.rpc_failed())
 			.map_err(|_| generic_error("could not find storage"))?;
 		let storage_bytes = storage.to_be_bytes::<32>();
 
@@ -462,6 +1217,97 @@ impl bridge_util::chains::bridge_contracts::BridgeContract<EthAddress> for EthCl
 			state: eth_details.state,
 		}))
 	}
+
+	async fn complete_bridge_transfers_batch(
+		&mut self,
+		transfers: Vec<(BridgeTransferId, HashLockPreImage)>,
+	) -> BridgeContractResult<BatchResults> {
+		if transfers.is_empty() {
+			return Ok(Vec::new());
+		}
+		self.ensure_connection().await?;
+		let mut results: BatchResults = transfers.iter().map(|_| Ok(())).collect();
+		let mut calls = Vec::with_capacity(transfers.len());
+		for (index, (bridge_transfer_id, pre_image)) in transfers.iter().enumerate() {
+			// An invalid transfer fails alone, the others are sent.
+			if let Err(err) = self.validate_pre_image(pre_image) {
+				results[index] = Err(err);
+				continue;
+			}
+			let call = AtomicBridgeInitiatorMOVE::completeBridgeTransferCall {
+				bridgeTransferId: FixedBytes(bridge_transfer_id.0),
+				preImage: FixedBytes(pre_image.0),
+			};
+			calls.push((index, *bridge_transfer_id, Bytes::from(call.abi_encode())));
+		}
+		let contract = self.config.initiator_contract;
+		self.send_batch("complete_initiator_batch", contract, calls, &mut results)
+			.await?;
+		Ok(results)
+	}
+
+	async fn lock_bridge_transfers_batch(
+		&mut self,
+		transfers: Vec<LockTransfer<EthAddress>>,
+	) -> BridgeContractResult<BatchResults> {
+		if transfers.is_empty() {
+			return Ok(Vec::new());
+		}
+		self.ensure_connection().await?;
+		let mut results: BatchResults = transfers.iter().map(|_| Ok(())).collect();
+		let mut calls = Vec::with_capacity(transfers.len());
+		for (index, transfer) in transfers.iter().enumerate() {
+			let Ok(initiator) = <[u8; 32]>::try_from(transfer.initiator.0.clone()) else {
+				results[index] = Err(BridgeContractError::ConversionFailed(
+					"lock_bridge_transfer initiator".to_string(),
+				));
+				continue;
+			};
+			let call = AtomicBridgeCounterpartyMOVE::lockBridgeTransferCall {
+				originator: FixedBytes(initiator),
+				bridgeTransferId: FixedBytes(transfer.bridge_transfer_id.0),
+				hashLock: FixedBytes(transfer.hash_lock.0),
+				recipient: *transfer.recipient.0,
+				amount: U256::from(transfer.amount.0),
+			};
+			calls.push((index, transfer.bridge_transfer_id, Bytes::from(call.abi_encode())));
+		}
+		let contract = self.config.counterparty_contract;
+		self.send_batch("lock_batch", contract, calls, &mut results).await?;
+		Ok(results)
+	}
+}
+
+// The result of each call of a batch, the reverted ones being logged by the contract with their
+// index in the batch and their revert data.
+fn batch_call_results(logs: &[LogData], count: usize) -> BatchResults {
+	let mut results: BatchResults = (0..count).map(|_| Ok(())).collect();
+	for log in logs {
+		let Ok(failed) = IMulticall::BatchCallFailed::decode_log_data(log, true) else {
+			continue;
+		};
+		let Some(result) =
+			usize::try_from(failed.index).ok().and_then(|index| results.get_mut(index))
+		else {
+			continue;
+		};
+		let reason = decode_revert_reason(&failed.reason)
+			.unwrap_or_else(|| format!("0x{}", alloy::hex::encode(&failed.reason)));
+		*result = Err(BridgeContractError::OnChainError(format!("Batch call reverted: {reason}")));
+	}
+	results
+}
+
+// Fee paid by the sender of the mined transaction, in wei.
+fn transaction_fee(receipt: &TransactionReceipt) -> u128 {
+	receipt.gas_used * receipt.effective_gas_price
+}
+
+fn now_secs() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|d| d.as_secs())
+		.unwrap_or_default()
 }
 
 #[cfg(test)]
@@ -472,8 +1318,210 @@ fn test_wrapping_to(a: &U256, b: u64) {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::chains::ethereum::utils::EthUtilError;
+	use bridge_test_fixtures::eth_rpc::{MockResponse, MockTransport};
+	use bridge_util::chains::bridge_contracts::BridgeContract;
 	use std::time::{SystemTime, UNIX_EPOCH};
 
+	fn mock_client(transport: &MockTransport) -> EthClient {
+		let signer =
+			bridge_test_fixtures::eth::anvil_signer(bridge_test_fixtures::eth::SIGNER_INDEX);
+		let config = Config {
+			rpc_url: "http://localhost:8545".parse().unwrap(),
+			ws_url: "ws://localhost:8545".parse().unwrap(),
+			chain_id: 0,
+			wallet: EthereumWallet::from(signer.clone()),
+			signer_address: signer.address(),
+			initiator_contract: Address::repeat_byte(1),
+			counterparty_contract: Address::repeat_byte(2),
+			movetoken_contract: Address::repeat_byte(3),
+			gas_limit: 10_000_000,
+			transaction_send_retries: 1,
+			use_access_lists: false,
+			asset: AssetKind::Move,
+			key_audit: KeyAudit::default(),
+		};
+		EthClient::with_transport(config, transport.boxed())
+	}
+
+	#[tokio::test]
+	async fn test_mock_rpc_reads() {
+		let transport = MockTransport::new();
+		let client = mock_client(&transport);
+
+		transport.expect("eth_chainId", MockResponse::Result("0x7a69".into()));
+		assert_eq!(client.chain_id().await.unwrap(), 31337);
+
+		// The node reorganizes to a shorter chain between two reads.
+		transport.push_result("0x64").push_result("0x62");
+		assert_eq!(client.get_block_number().await.unwrap(), 100);
+		assert_eq!(client.get_block_number().await.unwrap(), 98);
+
+		transport.push_result("not a block number");
+		assert!(client.get_block_number().await.is_err());
+		assert_eq!(
+			transport.requests(),
+			vec!["eth_chainId", "eth_blockNumber", "eth_blockNumber", "eth_blockNumber"]
+		);
+	}
+
+	#[tokio::test]
+	async fn test_mock_rpc_errors() {
+		let transport = MockTransport::new();
+		let mut client = mock_client(&transport);
+		let transfer_id = BridgeTransferId([1; 32]);
+
+		transport.push_error(-32000, "header not found");
+		assert!(matches!(
+			client.get_bridge_transfer_details_initiator(transfer_id).await,
+			Err(BridgeContractError::GenericError(_))
+		));
+
+		// The client reconnects on the same transport after a failure.
+		transport.push(MockResponse::TransportFailure("connection reset".to_string()));
+		assert!(client.get_bridge_transfer_details_initiator(transfer_id).await.is_err());
+		transport.push_result(format!("0x{}", "ff".repeat(32)));
+		assert!(matches!(
+			client.get_bridge_transfer_details_initiator(transfer_id).await,
+			Err(BridgeContractError::GenericError(err)) if err == "could not decode storage"
+		));
+		assert_eq!(transport.requests(), vec!["eth_getStorageAt"; 3]);
+		assert_eq!(transport.remaining(), 0);
+	}
+
+	#[tokio::test]
+	async fn test_mock_erc20_token() {
+		let transport = MockTransport::new();
+		let client = mock_client(&transport);
+		let config = Erc20TokenConfig {
+			symbol: "USDC".to_string(),
+			token_contract: Address::repeat_byte(4).to_string(),
+			initiator_contract: Address::repeat_byte(5).to_string(),
+			decimals: None,
+		};
+
+		// The decimals are read from the token contract when not configured.
+		transport.expect("eth_call", MockResponse::Result(format!("0x{:064x}", 6).into()));
+		let token = client.erc20_token(&config).await.unwrap();
+		assert_eq!(token.decimals, 6);
+		assert_eq!(token.initiator_contract, Address::repeat_byte(5));
+
+		transport.expect("eth_call", MockResponse::Result(format!("0x{:064x}", 1_500_000).into()));
+		assert_eq!(client.erc20_allowance(&token).await.unwrap(), U256::from(1_500_000));
+		assert_eq!(transport.remaining(), 0);
+	}
+
+	#[tokio::test]
+	async fn test_mock_access_list() {
+		let transport = MockTransport::new();
+		let client = mock_client(&transport);
+		let transfer_id = FixedBytes([1; 32]);
+
+		transport.expect(
+			"eth_createAccessList",
+			MockResponse::Result(serde_json::json!({
+				"accessList": [{
+					"address": "0x0101010101010101010101010101010101010101",
+					"storageKeys": [format!("0x{}", "02".repeat(32))],
+				}],
+				"gasUsed": "0x7530",
+			})),
+		);
+		let call = client.initiator_contract.refundBridgeTransfer(transfer_id);
+		let request =
+			with_access_list(call, client.signer_address).await.into_transaction_request();
+		let access_list = request.access_list.expect("Access list attached");
+		assert_eq!(access_list.0.len(), 1);
+		assert_eq!(access_list.0[0].address, client.config.initiator_contract);
+		assert_eq!(request.from, Some(client.signer_address));
+
+		// The call is sent without access list if the node can't compute it.
+		transport.push_error(-32601, "the method eth_createAccessList does not exist");
+		let call = client.initiator_contract.refundBridgeTransfer(transfer_id);
+		let request =
+			with_access_list(call, client.signer_address).await.into_transaction_request();
+		assert!(request.access_list.is_none());
+		assert_eq!(transport.remaining(), 0);
+	}
+
+	#[tokio::test]
+	async fn test_mock_nonce_manager() {
+		let transport = MockTransport::new();
+		let client = mock_client(&transport);
+		let nonce_manager = NonceManager::default();
+		let lease = |nonce_manager: &NonceManager| {
+			let nonce_manager = nonce_manager.clone();
+			let client = client.clone();
+			async move {
+				nonce_manager.lease(&client.rpc_provider, client.signer_address).await.unwrap()
+			}
+		};
+
+		// Concurrent transactions get consecutive nonces from the pending count.
+		transport.expect("eth_getTransactionCount", MockResponse::Result("0x5".into()));
+		let first = lease(&nonce_manager).await;
+		let second = lease(&nonce_manager).await;
+		let third = lease(&nonce_manager).await;
+		assert_eq!((first.nonce(), second.nonce(), third.nonce()), (5, 6, 7));
+		first.confirm();
+		third.confirm();
+
+		// An unused nonce is a gap: the next nonce is read again and the gap filled.
+		drop(second);
+		transport.expect("eth_getTransactionCount", MockResponse::Result("0x6".into()));
+		assert_eq!(lease(&nonce_manager).await.nonce(), 6);
+		assert_eq!(transport.remaining(), 0);
+
+		// The nonces still in flight are skipped after a read.
+		let mut state = NonceState::default();
+		assert_eq!(state.assign(Some(3)), 3);
+		assert_eq!(state.assign(None), 4);
+		assert_eq!(state.assign(Some(3)), 5);
+		assert_eq!(state.assign(Some(5)), 6);
+	}
+
+	#[tokio::test]
+	async fn test_mock_send_failures() {
+		let transport = MockTransport::new();
+		let client = mock_client(&transport);
+
+		// The node answered: the connection is kept for the next calls.
+		transport.push_error(3, "execution reverted");
+		let revert = client.rpc_provider.get_block_number().await.unwrap_err();
+		client.send_failed(&revert.into());
+		transport.push_error(-32000, "nonce too low");
+		let nonce_conflict = client.rpc_provider.get_block_number().await.unwrap_err();
+		client.send_failed(&nonce_conflict.into());
+		let mined_revert = EthUtilError::RpcTransactionExecution("receipt status 0".to_string());
+		client.send_failed(&mined_revert.into());
+		assert!(!client.connection().need_reconnect(0).unwrap());
+
+		// The request didn't reach the node: the connection is re-established.
+		transport.push(MockResponse::TransportFailure("connection reset".to_string()));
+		let failure = client.rpc_provider.get_block_number().await.unwrap_err();
+		client.send_failed(&failure.into());
+		assert!(client.connection().need_reconnect(0).unwrap());
+		assert_eq!(transport.remaining(), 0);
+	}
+
+	#[test]
+	fn test_batch_call_results() {
+		let failed = IMulticall::BatchCallFailed {
+			index: U256::from(1),
+			reason: Bytes::from_static(b"reverted"),
+		};
+		// Only the failures of the batch are decoded, the other events are ignored.
+		let other = LogData::new_unchecked(vec![FixedBytes::repeat_byte(9)], Bytes::new());
+		let results = batch_call_results(&[other, failed.encode_log_data()], 3);
+		assert_eq!(results.len(), 3);
+		assert!(results[0].is_ok());
+		assert!(matches!(&results[1], Err(BridgeContractError::OnChainError(_))));
+		assert!(results[2].is_ok());
+		// A failure outside of the batch is ignored.
+		let failed = IMulticall::BatchCallFailed { index: U256::from(3), reason: Bytes::new() };
+		assert!(batch_call_results(&[failed.encode_log_data()], 3).iter().all(Result::is_ok));
+	}
+
 	#[test]
 	fn test_wrapping_to_on_eth_details() {
 		let current_time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
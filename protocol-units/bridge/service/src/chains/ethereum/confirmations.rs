@@ -0,0 +1,104 @@
+//! Confirmation depth of the initiated events: an event is only handled once its block is
+//! deep enough, the events of the blocks replaced by a reorg before are dropped.
+use alloy::primitives::B256;
+use std::collections::BTreeMap;
+
+#[derive(Debug)]
+struct PendingBlock<E> {
+	hash: B256,
+	events: Vec<E>,
+}
+
+/// Events waiting for the confirmations of their block.
+#[derive(Debug)]
+pub struct ConfirmationTracker<E> {
+	confirmations: u64,
+	pending: BTreeMap<u64, PendingBlock<E>>,
+}
+
+impl<E: PartialEq> ConfirmationTracker<E> {
+	pub fn new(confirmations: u64) -> Self {
+		ConfirmationTracker { confirmations, pending: BTreeMap::new() }
+	}
+
+	/// Track an event of a block until the block is confirmed. An event seen again, when
+	/// its block is scanned again, is tracked once.
+	pub fn track(&mut self, block_number: u64, block_hash: B256, event: E) {
+		let block = self
+			.pending
+			.entry(block_number)
+			.or_insert_with(|| PendingBlock { hash: block_hash, events: Vec::new() });
+		// The block was replaced, its previous events aren't in the chain anymore.
+		if block.hash != block_hash {
+			block.hash = block_hash;
+			block.events.clear();
+		}
+		if !block.events.contains(&event) {
+			block.events.push(event);
+		}
+	}
+
+	/// Number and hash of the blocks with pending events, in block order.
+	pub fn pending_blocks(&self) -> Vec<(u64, B256)> {
+		self.pending.iter().map(|(number, block)| (*number, block.hash)).collect()
+	}
+
+	/// Drop the pending events of a block replaced by a reorg, and of the blocks after it.
+	/// Return the number of events dropped.
+	pub fn rollback(&mut self, block_number: u64) -> usize {
+		let dropped = self.pending.split_off(&block_number);
+		dropped.values().map(|block| block.events.len()).sum()
+	}
+
+	/// Remove and return the events of the blocks with the required confirmations
+	/// at the head block, with the number and hash of their block.
+	pub fn confirmed(&mut self, head: u64) -> Vec<(u64, B256, E)> {
+		let Some(last_confirmed) = head.checked_sub(self.confirmations) else {
+			return Vec::new();
+		};
+		let still_pending = self.pending.split_off(&(last_confirmed + 1));
+		let confirmed = std::mem::replace(&mut self.pending, still_pending);
+		confirmed
+			.into_iter()
+			.flat_map(|(number, block)| {
+				block.events.into_iter().map(move |event| (number, block.hash, event))
+			})
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn events<E>(confirmed: Vec<(u64, B256, E)>) -> Vec<E> {
+		confirmed.into_iter().map(|(_, _, event)| event).collect()
+	}
+
+	#[test]
+	fn test_confirmation_tracker() {
+		let mut tracker = ConfirmationTracker::new(3);
+		tracker.track(10, B256::repeat_byte(10), "a");
+		tracker.track(11, B256::repeat_byte(11), "b");
+		tracker.track(11, B256::repeat_byte(11), "b");
+		tracker.track(12, B256::repeat_byte(12), "c");
+		assert!(tracker.confirmed(12).is_empty());
+		assert_eq!(
+			tracker.confirmed(14),
+			[(10, B256::repeat_byte(10), "a"), (11, B256::repeat_byte(11), "b")]
+		);
+		assert_eq!(tracker.pending_blocks(), [(12, B256::repeat_byte(12))]);
+
+		// The block 12 is replaced, the block scanned again has another event.
+		tracker.track(13, B256::repeat_byte(13), "d");
+		assert_eq!(tracker.rollback(12), 2);
+		tracker.track(12, B256::repeat_byte(0x12), "e");
+		assert_eq!(events(tracker.confirmed(15)), ["e"]);
+
+		// A replaced block seen while scanning drops its previous events.
+		tracker.track(20, B256::repeat_byte(20), "f");
+		tracker.track(20, B256::repeat_byte(0x20), "g");
+		assert_eq!(events(tracker.confirmed(30)), ["g"]);
+		assert!(ConfirmationTracker::<&str>::new(3).confirmed(2).is_empty());
+	}
+}
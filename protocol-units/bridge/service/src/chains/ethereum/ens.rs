@@ -0,0 +1,98 @@
+//! Reverse resolution of the Ethereum addresses to their ENS primary name.
+use crate::labels::AddressResolver;
+use alloy::primitives::{keccak256, Address, B256};
+use alloy::providers::{ProviderBuilder, RootProvider};
+use alloy::transports::BoxTransport;
+
+alloy::sol!(
+	#[allow(missing_docs)]
+	#[sol(rpc)]
+	interface EnsRegistry {
+		function resolver(bytes32 node) external view returns (address);
+	}
+);
+
+alloy::sol!(
+	#[allow(missing_docs)]
+	#[sol(rpc)]
+	interface EnsResolver {
+		function name(bytes32 node) external view returns (string);
+		function addr(bytes32 node) external view returns (address);
+	}
+);
+
+/// Resolves the 20 bytes addresses with the reverse records of an ENS registry.
+pub struct EnsNames {
+	registry: Address,
+	provider: RootProvider<BoxTransport>,
+}
+
+impl EnsNames {
+	pub async fn new(rpc_url: &str, registry: Address) -> Result<Self, anyhow::Error> {
+		let provider = ProviderBuilder::new().on_builtin(rpc_url).await?;
+		Ok(EnsNames { registry, provider })
+	}
+
+	async fn resolver(&self, node: B256) -> Result<Option<Address>, anyhow::Error> {
+		let resolver =
+			EnsRegistry::new(self.registry, &self.provider).resolver(node).call().await?._0;
+		Ok((resolver != Address::ZERO).then_some(resolver))
+	}
+}
+
+#[async_trait::async_trait]
+impl AddressResolver for EnsNames {
+	async fn resolve(&self, address: &[u8]) -> Result<Option<String>, anyhow::Error> {
+		if address.len() != 20 {
+			return Ok(None);
+		}
+		let address = Address::from_slice(address);
+		let reverse_node = namehash(&format!("{}.addr.reverse", hex::encode(address)));
+		let Some(resolver) = self.resolver(reverse_node).await? else {
+			return Ok(None);
+		};
+		let name = EnsResolver::new(resolver, &self.provider).name(reverse_node).call().await?._0;
+		if name.is_empty() {
+			return Ok(None);
+		}
+		// Anyone can set the reverse record of their address to any name,
+		// it's only the primary name if the name resolves back to the address.
+		let node = namehash(&name);
+		let Some(resolver) = self.resolver(node).await? else {
+			return Ok(None);
+		};
+		let resolved = EnsResolver::new(resolver, &self.provider).addr(node).call().await?._0;
+		Ok((resolved == address).then_some(name))
+	}
+}
+
+/// ENS hash of a name, from its last label to its first.
+pub fn namehash(name: &str) -> B256 {
+	name.rsplit('.')
+		.filter(|label| !label.is_empty())
+		.fold(B256::ZERO, |node, label| {
+			keccak256([node.as_slice(), keccak256(label.as_bytes()).as_slice()].concat())
+		})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::str::FromStr;
+
+	#[test]
+	fn test_namehash() {
+		assert_eq!(namehash(""), B256::ZERO);
+		// Values of the ENS specification.
+		assert_eq!(
+			namehash("eth"),
+			B256::from_str("0x93cdeb708b7545dc668eb9280176169d1c33cfd8ed6f04690a0bcc88a93fc4ae")
+				.unwrap()
+		);
+		assert_eq!(
+			namehash("foo.eth"),
+			B256::from_str("0xde9b09fd7c5f901e23a3f19fecc54828e9c848539801e86591bd9801b019f84f")
+				.unwrap()
+		);
+	}
+}
@@ -0,0 +1,354 @@
+//! Conversion of the bridge contract logs into bridge events.
+//! The event layouts come from the bindings generated from the checked-in ABIs in `abis/`,
+//! so a contract upgrade only requires replacing the ABI file.
+use super::types::{AtomicBridgeCounterpartyMOVE, AtomicBridgeInitiatorMOVE, EthAddress};
+use alloy::primitives::{Address, LogData};
+use alloy::sol_types::SolEvent;
+use bridge_util::chains::bridge_contracts::{
+	BridgeContractError, BridgeContractEvent, BridgeContractEventType, BridgeContractResult,
+};
+use bridge_util::types::{
+	BridgeAddress, BridgeTransferDetails, BridgeTransferId, HashLock, HashLockPreImage, LockDetails,
+};
+
+pub fn initiated_event(
+	initiated: AtomicBridgeInitiatorMOVE::BridgeTransferInitiated,
+) -> BridgeContractEvent<EthAddress> {
+	BridgeContractEvent::Initiated(BridgeTransferDetails {
+		bridge_transfer_id: BridgeTransferId(*initiated._bridgeTransferId),
+		initiator: BridgeAddress(EthAddress(Address::from(initiated._originator))),
+		recipient: BridgeAddress(initiated._recipient.to_vec()),
+		hash_lock: HashLock(*initiated._hashLock),
+		time_lock: initiated._timeLock.into(),
+		amount: initiated.amount.into(),
+		state: 0,
+	})
+}
+
+pub fn locked_event(
+	locked: AtomicBridgeCounterpartyMOVE::BridgeTransferLocked,
+) -> BridgeContractEvent<EthAddress> {
+	BridgeContractEvent::Locked(LockDetails {
+		bridge_transfer_id: BridgeTransferId(*locked.bridgeTransferId),
+		// The originator is not part of the event.
+		initiator: BridgeAddress(vec![0; 32]),
+		recipient: BridgeAddress(EthAddress(Address::from(locked.recipient))),
+		hash_lock: HashLock(*locked.hashLock),
+		time_lock: locked.timeLock.into(),
+		amount: locked.amount.into(),
+	})
+}
+
+pub fn initiator_completed_event(
+	completed: AtomicBridgeInitiatorMOVE::BridgeTransferCompleted,
+) -> BridgeContractEvent<EthAddress> {
+	BridgeContractEvent::InitiatorCompleted(BridgeTransferId(*completed._bridgeTransferId))
+}
+
+pub fn refunded_event(
+	refunded: AtomicBridgeInitiatorMOVE::BridgeTransferRefunded,
+) -> BridgeContractEvent<EthAddress> {
+	BridgeContractEvent::Refunded(BridgeTransferId(*refunded._bridgeTransferId))
+}
+
+pub fn counterparty_completed_event(
+	completed: AtomicBridgeCounterpartyMOVE::BridgeTransferCompleted,
+) -> BridgeContractEvent<EthAddress> {
+	BridgeContractEvent::CounterPartyCompleted(
+		BridgeTransferId(*completed.bridgeTransferId),
+		HashLockPreImage(*completed.pre_image),
+	)
+}
+
+pub fn aborted_event(
+	aborted: AtomicBridgeCounterpartyMOVE::BridgeTransferAborted,
+) -> BridgeContractEvent<EthAddress> {
+	BridgeContractEvent::Cancelled(BridgeTransferId(*aborted.bridgeTransferId))
+}
+
+/// Decode a log emitted by the initiator contract.
+pub fn decode_initiator_log(
+	log: &LogData,
+) -> BridgeContractResult<BridgeContractEvent<EthAddress>> {
+	let topic = log.topics().first().ok_or(BridgeContractError::OnChainUnknownEvent)?;
+	if *topic == AtomicBridgeInitiatorMOVE::BridgeTransferInitiated::SIGNATURE_HASH {
+		let initiated = decode(log, BridgeContractEventType::Initiated)?;
+		Ok(initiated_event(initiated))
+	} else if *topic == AtomicBridgeInitiatorMOVE::BridgeTransferCompleted::SIGNATURE_HASH {
+		let completed = decode(log, BridgeContractEventType::InitiatorCompleted)?;
+		Ok(initiator_completed_event(completed))
+	} else if *topic == AtomicBridgeInitiatorMOVE::BridgeTransferRefunded::SIGNATURE_HASH {
+		let refunded = decode(log, BridgeContractEventType::Refunded)?;
+		Ok(refunded_event(refunded))
+	} else {
+		Err(BridgeContractError::OnChainUnknownEvent)
+	}
+}
+
+/// Decode a log emitted by the counterparty contract.
+pub fn decode_counterparty_log(
+	log: &LogData,
+) -> BridgeContractResult<BridgeContractEvent<EthAddress>> {
+	let topic = log.topics().first().ok_or(BridgeContractError::OnChainUnknownEvent)?;
+	if *topic == AtomicBridgeCounterpartyMOVE::BridgeTransferLocked::SIGNATURE_HASH {
+		let locked = decode(log, BridgeContractEventType::Locked)?;
+		Ok(locked_event(locked))
+	} else if *topic == AtomicBridgeCounterpartyMOVE::BridgeTransferCompleted::SIGNATURE_HASH {
+		let completed = decode(log, BridgeContractEventType::CounterPartyCompleted)?;
+		Ok(counterparty_completed_event(completed))
+	} else if *topic == AtomicBridgeCounterpartyMOVE::BridgeTransferAborted::SIGNATURE_HASH {
+		let aborted = decode(log, BridgeContractEventType::Cancelled)?;
+		Ok(aborted_event(aborted))
+	} else {
+		Err(BridgeContractError::OnChainUnknownEvent)
+	}
+}
+
+fn decode<E: SolEvent>(
+	log: &LogData,
+	event_type: BridgeContractEventType,
+) -> BridgeContractResult<E> {
+	E::decode_log_data(log, true)
+		.map_err(|err| BridgeContractError::EventDeserializingFail(err.to_string(), event_type))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::chains::ethereum::types::WETH9;
+	use alloy::primitives::{Bytes, B256, U256};
+	use bridge_util::types::{Amount, ChainId, TimeLock, TransferDirection};
+	use bridge_util::TransferEvent;
+	use std::str::FromStr;
+
+	// Transfer events built for the tests, no transfer of the deployed bridges is recorded.
+	const BRIDGE_LOGS: &str = include_str!("../../../abis/fixtures/bridge_logs.json");
+	// Logs emitted by the bridge proxies on Sepolia, with their transaction and block.
+	const RECORDED_LOGS: &str = include_str!("../../../abis/fixtures/recorded_logs.json");
+	const INITIATOR_DEPLOYMENT: &str = include_str!(
+		"../../../../contracts/broadcast/DeployAtomicBridgeInitiatorMOVE.s.sol/11155111/run-latest.json"
+	);
+	const COUNTERPARTY_DEPLOYMENT: &str = include_str!(
+		"../../../../contracts/broadcast/DeployAtomicBridgeCounterpartyMOVE.s.sol/11155111/run-latest.json"
+	);
+
+	/// Hex of the code of a contract deployed by a broadcast.
+	fn deployed_code(broadcast: &str, contract_name: &str) -> String {
+		let broadcast: serde_json::Value = serde_json::from_str(broadcast).unwrap();
+		let transaction = broadcast["transactions"]
+			.as_array()
+			.unwrap()
+			.iter()
+			.find(|transaction| transaction["contractName"] == contract_name)
+			.unwrap();
+		transaction["transaction"]["input"].as_str().unwrap().to_string()
+	}
+
+	fn fixture_log(fixture: &serde_json::Value) -> LogData {
+		let topics = fixture["topics"]
+			.as_array()
+			.unwrap()
+			.iter()
+			.map(|topic| B256::from_str(topic.as_str().unwrap()).unwrap())
+			.collect();
+		let data = Bytes::from_str(fixture["data"].as_str().unwrap()).unwrap();
+		LogData::new(topics, data).unwrap()
+	}
+
+	fn fixture_bytes<const N: usize>(value: &serde_json::Value) -> [u8; N] {
+		let bytes = hex::decode(value.as_str().unwrap().trim_start_matches("0x")).unwrap();
+		bytes.try_into().unwrap()
+	}
+
+	#[test]
+	fn test_bindings_match_deployed_contracts() {
+		// The contracts push the topics of the events they emit, those the bindings decode must
+		// be in the code deployed on Sepolia.
+		let initiator = deployed_code(INITIATOR_DEPLOYMENT, "AtomicBridgeInitiatorMOVE");
+		let counterparty = deployed_code(COUNTERPARTY_DEPLOYMENT, "AtomicBridgeCounterpartyMOVE");
+		for topic in [
+			AtomicBridgeInitiatorMOVE::BridgeTransferInitiated::SIGNATURE_HASH,
+			AtomicBridgeInitiatorMOVE::BridgeTransferCompleted::SIGNATURE_HASH,
+			AtomicBridgeInitiatorMOVE::BridgeTransferRefunded::SIGNATURE_HASH,
+		] {
+			assert!(
+				initiator.contains(&hex::encode(topic)),
+				"{topic} not emitted by the initiator"
+			);
+		}
+		for topic in [
+			AtomicBridgeCounterpartyMOVE::BridgeTransferLocked::SIGNATURE_HASH,
+			AtomicBridgeCounterpartyMOVE::BridgeTransferCompleted::SIGNATURE_HASH,
+			AtomicBridgeCounterpartyMOVE::BridgeTransferAborted::SIGNATURE_HASH,
+		] {
+			assert!(
+				counterparty.contains(&hex::encode(topic)),
+				"{topic} not emitted by the counterparty"
+			);
+		}
+	}
+
+	#[test]
+	fn test_decode_fixture_logs() {
+		let fixtures: Vec<serde_json::Value> = serde_json::from_str(BRIDGE_LOGS).unwrap();
+		for fixture in &fixtures {
+			let log = fixture_log(fixture);
+			let expected = &fixture["expected"];
+			let bridge_transfer_id =
+				BridgeTransferId(fixture_bytes(&expected["bridge_transfer_id"]));
+			let (event, direction) = match fixture["contract"].as_str().unwrap() {
+				"initiator" => (decode_initiator_log(&log), TransferDirection::EthToMovement),
+				"counterparty" => (decode_counterparty_log(&log), TransferDirection::MovementToEth),
+				contract => panic!("Unknown contract in fixture: {contract}"),
+			};
+			let event = event.unwrap();
+			assert_eq!(event.bridge_transfer_id(), bridge_transfer_id);
+			let transfer_event: TransferEvent<EthAddress> = (event.clone(), ChainId::ONE).into();
+			assert_eq!(transfer_event.direction(), direction);
+
+			match (fixture["event"].as_str().unwrap(), event) {
+				("BridgeTransferInitiated", BridgeContractEvent::Initiated(details)) => {
+					assert_eq!(
+						details.initiator.0 .0,
+						Address::from_str(expected["initiator"].as_str().unwrap()).unwrap()
+					);
+					assert_eq!(details.recipient.0, fixture_bytes::<32>(&expected["recipient"]));
+					assert_eq!(details.hash_lock, HashLock(fixture_bytes(&expected["hash_lock"])));
+					assert_eq!(details.amount, Amount(expected["amount"].as_u64().unwrap()));
+					assert_eq!(
+						details.time_lock,
+						TimeLock(expected["time_lock"].as_u64().unwrap())
+					);
+				}
+				("BridgeTransferLocked", BridgeContractEvent::Locked(details)) => {
+					assert_eq!(
+						details.recipient.0 .0,
+						Address::from_str(expected["recipient"].as_str().unwrap()).unwrap()
+					);
+					assert_eq!(details.hash_lock, HashLock(fixture_bytes(&expected["hash_lock"])));
+					assert_eq!(details.amount, Amount(expected["amount"].as_u64().unwrap()));
+					assert_eq!(
+						details.time_lock,
+						TimeLock(expected["time_lock"].as_u64().unwrap())
+					);
+				}
+				("BridgeTransferCompleted", BridgeContractEvent::InitiatorCompleted(_)) => (),
+				(
+					"BridgeTransferCompleted",
+					BridgeContractEvent::CounterPartyCompleted(_, pre_image),
+				) => {
+					assert_eq!(pre_image, HashLockPreImage(fixture_bytes(&expected["pre_image"])));
+				}
+				("BridgeTransferRefunded", BridgeContractEvent::Refunded(_))
+				| ("BridgeTransferAborted", BridgeContractEvent::Cancelled(_)) => (),
+				(name, event) => panic!("Fixture {name} decoded as {event:?}"),
+			}
+		}
+	}
+
+	#[test]
+	fn test_decode_unknown_log() {
+		let fixtures: Vec<serde_json::Value> = serde_json::from_str(BRIDGE_LOGS).unwrap();
+		// A counterparty log is unknown to the initiator decoder.
+		let locked = fixtures.iter().find(|f| f["event"] == "BridgeTransferLocked").unwrap();
+		assert!(matches!(
+			decode_initiator_log(&fixture_log(locked)),
+			Err(BridgeContractError::OnChainUnknownEvent)
+		));
+	}
+
+	#[test]
+	fn test_decode_recorded_logs() {
+		let fixtures: Vec<serde_json::Value> = serde_json::from_str(RECORDED_LOGS).unwrap();
+		for fixture in &fixtures {
+			let log = fixture_log(fixture);
+			let expected = &fixture["expected"];
+			let transaction = fixture["transaction_hash"].as_str().unwrap();
+			let contract = fixture["contract"].as_str().unwrap();
+			// The relayer skips the logs other than the transfer events.
+			let event = match contract {
+				"initiator" => decode_initiator_log(&log),
+				"counterparty" => decode_counterparty_log(&log),
+				contract => panic!("Unknown contract in fixture: {contract}"),
+			};
+			assert!(
+				matches!(event, Err(BridgeContractError::OnChainUnknownEvent)),
+				"Log of {transaction} decoded as {event:?}"
+			);
+
+			match fixture["event"].as_str().unwrap() {
+				"OwnershipTransferred" => {
+					let (previous_owner, new_owner) = if contract == "initiator" {
+						let transferred =
+							AtomicBridgeInitiatorMOVE::OwnershipTransferred::decode_log_data(
+								&log, true,
+							)
+							.unwrap();
+						(transferred.previousOwner, transferred.newOwner)
+					} else {
+						let transferred =
+							AtomicBridgeCounterpartyMOVE::OwnershipTransferred::decode_log_data(
+								&log, true,
+							)
+							.unwrap();
+						(transferred.previousOwner, transferred.newOwner)
+					};
+					assert_eq!(
+						previous_owner,
+						Address::from_str(expected["previous_owner"].as_str().unwrap()).unwrap()
+					);
+					assert_eq!(
+						new_owner,
+						Address::from_str(expected["new_owner"].as_str().unwrap()).unwrap()
+					);
+				}
+				"Initialized" => {
+					let version = if contract == "initiator" {
+						AtomicBridgeInitiatorMOVE::Initialized::decode_log_data(&log, true)
+							.unwrap()
+							.version
+					} else {
+						AtomicBridgeCounterpartyMOVE::Initialized::decode_log_data(&log, true)
+							.unwrap()
+							.version
+					};
+					assert_eq!(version, expected["version"].as_u64().unwrap());
+				}
+				name => panic!("Unknown event in fixture of {transaction}: {name}"),
+			}
+		}
+	}
+
+	#[test]
+	fn test_decode_weth9_logs() {
+		// keccak256("Deposit(address,uint256)") and keccak256("Withdrawal(address,uint256)").
+		let deposit_topic =
+			B256::from_str("0xe1fffcc4923d04b559f4d29a8bfc6cda04eb5b0d3c460751c2402c5c5cc9109c")
+				.unwrap();
+		let withdrawal_topic =
+			B256::from_str("0x7fcf532c15f0a6db0bd6d0e038bea71d30d808c7d98cb3bf7268a95bf5081b65")
+				.unwrap();
+		assert_eq!(WETH9::Deposit::SIGNATURE_HASH, deposit_topic);
+		assert_eq!(WETH9::Withdrawal::SIGNATURE_HASH, withdrawal_topic);
+
+		let account =
+			B256::from_str("0x0000000000000000000000001ad4b06b0b4b5c5a2e3c5d7e9b2f1a3c4d5e6f70")
+				.unwrap();
+		let wad =
+			Bytes::from_str("0x0000000000000000000000000000000000000000000000000de0b6b3a7640000")
+				.unwrap();
+		let deposit_log = LogData::new(vec![deposit_topic, account], wad.clone()).unwrap();
+		let withdrawal_log = LogData::new(vec![withdrawal_topic, account], wad).unwrap();
+
+		let deposit = WETH9::Deposit::decode_log_data(&deposit_log, true).unwrap();
+		assert_eq!(deposit.dst, Address::from_word(account));
+		assert_eq!(deposit.wad, U256::from(1_000_000_000_000_000_000u64));
+		let withdrawal = WETH9::Withdrawal::decode_log_data(&withdrawal_log, true).unwrap();
+		assert_eq!(withdrawal.src, Address::from_word(account));
+		assert_eq!(withdrawal.wad, U256::from(1_000_000_000_000_000_000u64));
+
+		// The events are told apart by their topic.
+		assert!(WETH9::Withdrawal::decode_log_data(&deposit_log, true).is_err());
+		assert!(WETH9::Deposit::decode_log_data(&withdrawal_log, true).is_err());
+	}
+}
@@ -1,26 +1,46 @@
-use super::types::EthAddress;
+use super::confirmations::ConfirmationTracker;
+use super::event_decoding::{
+	aborted_event, counterparty_completed_event, initiated_event, initiator_completed_event,
+	locked_event, refunded_event,
+};
+use super::reorg::ReorgMonitor;
+use super::types::{AlloyProvider, EthAddress, InitiatorContract};
+use crate::catchup::{CatchUpProgress, ETH_CHAIN};
+use crate::chains::ethereum::client::EthClient;
 use crate::chains::ethereum::types::AtomicBridgeCounterpartyMOVE;
 use crate::chains::ethereum::types::AtomicBridgeInitiatorMOVE;
+use crate::forensics::ScanRange;
 use alloy::eips::BlockNumberOrTag;
-use alloy::primitives::Address;
+use alloy::primitives::{Address, FixedBytes, B256};
 use alloy::providers::Provider;
-use alloy::providers::ProviderBuilder;
-use alloy_network::EthereumWallet;
 use bridge_config::common::eth::EthConfig;
 use bridge_util::chains::bridge_contracts::BridgeContractError;
 use bridge_util::chains::bridge_contracts::BridgeContractEvent;
 use bridge_util::chains::bridge_contracts::BridgeContractMonitoring;
 use bridge_util::chains::bridge_contracts::BridgeContractResult;
-use bridge_util::types::HashLockPreImage;
-use bridge_util::types::LockDetails;
-use bridge_util::types::{BridgeAddress, BridgeTransferDetails, BridgeTransferId, HashLock};
+use bridge_util::types::BridgeTransferId;
 use futures::SinkExt;
 use futures::{channel::mpsc::UnboundedReceiver, Stream, StreamExt};
+use std::time::Duration;
 use std::{pin::Pin, task::Poll};
 use tokio::sync::{mpsc, oneshot};
 
+// Backoff between the rounds while the node can't be reached, doubled on each failure.
+const RECONNECT_MIN_BACKOFF: Duration = Duration::from_secs(1);
+const RECONNECT_MAX_BACKOFF: Duration = Duration::from_secs(60);
+// Consecutive failures after which the connection to the node is re-established.
+const RECONNECT_AFTER_FAILURES: u32 = 3;
+// Maximum number of blocks scanned in a round, nodes limit the range of eth_getLogs.
+const MAX_SCANNED_BLOCKS: u64 = 1000;
+
+/// Stream of the events of both bridge contracts on Ethereum: initiated, completed
+/// and refunded on the initiator contract, locked, completed and aborted
+/// on the counterparty contract. The initiated events are only streamed once their block
+/// has the confirmations of the config.
 pub struct EthMonitoring {
 	listener: UnboundedReceiver<BridgeContractResult<BridgeContractEvent<EthAddress>>>,
+	dropped_initiations: Option<mpsc::UnboundedReceiver<BridgeTransferId>>,
+	pending_initiations: Option<mpsc::UnboundedReceiver<Vec<u8>>>,
 }
 
 impl BridgeContractMonitoring for EthMonitoring {
@@ -29,15 +49,47 @@ impl BridgeContractMonitoring for EthMonitoring {
 
 impl EthMonitoring {
 	pub async fn build(
+		config: &EthConfig,
+		health_check_rx: mpsc::Receiver<oneshot::Sender<bool>>,
+	) -> Result<Self, anyhow::Error> {
+		Self::build_with_catch_up(config, health_check_rx, CatchUpProgress::default()).await
+	}
+
+	/// Build the monitoring reporting its catch-up progress.
+	pub async fn build_with_catch_up(
+		config: &EthConfig,
+		health_check_rx: mpsc::Receiver<oneshot::Sender<bool>>,
+		catch_up: CatchUpProgress,
+	) -> Result<Self, anyhow::Error> {
+		Self::build_scan(config, health_check_rx, catch_up, None).await
+	}
+
+	/// Build the monitoring of the blocks of the range only, for the forensics mode. It has
+	/// caught up once the last block of the range is scanned.
+	pub async fn build_forensics(
+		config: &EthConfig,
+		health_check_rx: mpsc::Receiver<oneshot::Sender<bool>>,
+		catch_up: CatchUpProgress,
+		blocks: ScanRange,
+	) -> Result<Self, anyhow::Error> {
+		Self::build_scan(config, health_check_rx, catch_up, Some(blocks)).await
+	}
+
+	async fn build_scan(
 		config: &EthConfig,
 		mut health_check_rx: mpsc::Receiver<oneshot::Sender<bool>>,
+		catch_up: CatchUpProgress,
+		range: Option<ScanRange>,
 	) -> Result<Self, anyhow::Error> {
 		let client_config: crate::chains::ethereum::client::Config = config.try_into()?;
-		let rpc_provider = ProviderBuilder::new()
-			.with_recommended_fillers()
-			.wallet(EthereumWallet::from(client_config.signer_private_key.clone()))
-			.on_builtin(client_config.rpc_url.as_str())
-			.await?;
+		// The blocks are scanned over the WS connection if configured, and reconnected to it.
+		let monitoring_url = if config.monitoring_over_ws {
+			client_config.ws_url.clone()
+		} else {
+			client_config.rpc_url.clone()
+		};
+		let mut rpc_provider =
+			EthClient::connect_provider(&client_config, monitoring_url.as_str()).await?;
 
 		tracing::info!(
 			"Start Eth monitoring with initiator:{} counterpart:{}",
@@ -48,19 +100,27 @@ impl EthMonitoring {
 		let (mut sender, listener) = futures::channel::mpsc::unbounded::<
 			BridgeContractResult<BridgeContractEvent<EthAddress>>,
 		>();
+		let (dropped_initiation_tx, dropped_initiations) = mpsc::unbounded_channel();
+		let (pending_initiation_tx, pending_initiations) = mpsc::unbounded_channel();
+		catch_up.register(ETH_CHAIN);
 
 		tokio::spawn({
 			let config = config.clone();
 			async move {
-				let initiator_contract = AtomicBridgeInitiatorMOVE::new(
-					config.eth_initiator_contract.parse().unwrap(), //If unwrap start fail. Config must be updated.
+				let mut initiator_contract = AtomicBridgeInitiatorMOVE::new(
+					client_config.initiator_contract,
 					rpc_provider.clone(),
 				);
-				let counterpart_contract = AtomicBridgeCounterpartyMOVE::new(
-					config.eth_counterparty_contract.parse().unwrap(), //If unwrap start fail. Config must be updated.
+				let mut counterpart_contract = AtomicBridgeCounterpartyMOVE::new(
+					client_config.counterparty_contract,
 					rpc_provider.clone(),
 				);
-				let mut last_processed_block = 0;
+				let mut last_processed_block =
+					range.map_or(0, |range| range.from.saturating_sub(1));
+				let mut confirmation_tracker = ConfirmationTracker::new(config.confirmations);
+				let mut reorg_monitor = ReorgMonitor::new(config.confirmations);
+				let mut consecutive_failures = 0;
+				let mut backoff = RECONNECT_MIN_BACKOFF;
 				loop {
 					//Check if there's a health check request
 					match health_check_rx.try_recv() {
@@ -84,7 +144,7 @@ impl EthMonitoring {
 					)
 					.await
 					{
-						Ok(Ok(block_number)) => block_number,
+						Ok(Ok(block_number)) => Some(block_number),
 						Ok(Err(err)) => {
 							if sender
 								.send(Err(BridgeContractError::OnChainError(format!(
@@ -96,8 +156,7 @@ impl EthMonitoring {
 								tracing::error!("Failed to send event to listener channel");
 								break;
 							}
-							let _ = tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
-							continue;
+							None
 						}
 						Err(err) => {
 							if sender
@@ -110,33 +169,85 @@ impl EthMonitoring {
 								tracing::error!("Failed to send event to listener channel");
 								break;
 							}
-							let _ = tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
-							continue;
+							None
 						}
 					};
-					if last_processed_block < block_number {
-						last_processed_block = block_number;
+					let Some(block_number) = block_number else {
+						consecutive_failures += 1;
+						tokio::time::sleep(backoff).await;
+						backoff = (backoff * 2).min(RECONNECT_MAX_BACKOFF);
+						if consecutive_failures % RECONNECT_AFTER_FAILURES == 0 {
+							match EthClient::connect_provider(
+								&client_config,
+								monitoring_url.as_str(),
+							)
+							.await
+							{
+								Ok(provider) => {
+									tracing::info!(
+										"Eth monitoring reconnected after {consecutive_failures} failures"
+									);
+									rpc_provider = provider;
+									initiator_contract = AtomicBridgeInitiatorMOVE::new(
+										client_config.initiator_contract,
+										rpc_provider.clone(),
+									);
+									counterpart_contract = AtomicBridgeCounterpartyMOVE::new(
+										client_config.counterparty_contract,
+										rpc_provider.clone(),
+									);
+								}
+								Err(err) => {
+									tracing::warn!("Eth monitoring reconnection failed: {err}");
+								}
+							}
+						}
+						continue;
+					};
+					consecutive_failures = 0;
+					backoff = RECONNECT_MIN_BACKOFF;
+
+					// The blocks after the range of the forensics mode are never scanned.
+					let head = range.map_or(block_number, |range| block_number.min(range.to));
+					let mut scanned_events = 0;
+					let mut scan_failed = false;
+					if last_processed_block < head {
+						// Start at the head, then scan from the block following the last one
+						// processed, so the blocks produced while the node was unreachable
+						// are backfilled.
+						let from_block = if last_processed_block == 0 && range.is_none() {
+							block_number
+						} else {
+							last_processed_block + 1
+						};
+						let to_block = head.min(from_block + MAX_SCANNED_BLOCKS - 1);
 						let initiator_initiate_event_filter = initiator_contract
 							.BridgeTransferInitiated_filter()
-							.from_block(BlockNumberOrTag::Number(last_processed_block));
+							.from_block(BlockNumberOrTag::Number(from_block))
+							.to_block(BlockNumberOrTag::Number(to_block));
 						// event BridgeTransferCompleted(bytes32 indexed _bridgeTransferId, bytes32 pre_image);
 						let initiator_trcompleted_event_filter = initiator_contract
 							.BridgeTransferCompleted_filter()
-							.from_block(BlockNumberOrTag::Number(last_processed_block));
+							.from_block(BlockNumberOrTag::Number(from_block))
+							.to_block(BlockNumberOrTag::Number(to_block));
 						// event BridgeTransferRefunded(bytes32 indexed _bridgeTransferId);
 						let initiator_trrefund_event_filter = initiator_contract
 							.BridgeTransferRefunded_filter()
-							.from_block(BlockNumberOrTag::Number(last_processed_block));
+							.from_block(BlockNumberOrTag::Number(from_block))
+							.to_block(BlockNumberOrTag::Number(to_block));
 						let counterpart_trlocked_event_filter = counterpart_contract
 							.BridgeTransferLocked_filter()
-							.from_block(BlockNumberOrTag::Number(last_processed_block));
+							.from_block(BlockNumberOrTag::Number(from_block))
+							.to_block(BlockNumberOrTag::Number(to_block));
 						let counterpart_trcompleted_event_filter = counterpart_contract
 							.BridgeTransferCompleted_filter()
-							.from_block(BlockNumberOrTag::Number(last_processed_block));
+							.from_block(BlockNumberOrTag::Number(from_block))
+							.to_block(BlockNumberOrTag::Number(to_block));
 						//event BridgeTransferAborted(bytes32 indexed bridgeTransferId);
 						let counterpart_trcaborted_event_filter = counterpart_contract
 							.BridgeTransferAborted_filter()
-							.from_block(BlockNumberOrTag::Number(last_processed_block));
+							.from_block(BlockNumberOrTag::Number(from_block))
+							.to_block(BlockNumberOrTag::Number(to_block));
 
 						//Initiator event stream
 						match tokio::time::timeout(
@@ -146,34 +257,32 @@ impl EthMonitoring {
 						.await
 						{
 							Ok(Ok(events)) => {
-								for (initiated, _log) in events {
-									let event = {
-										// BridgeTransferInitiated(bridgeTransferId, originator, recipient, totalAmount, hashLock, initiatorTimeLockDuration);
-										let details: BridgeTransferDetails<EthAddress> =
-											BridgeTransferDetails {
-												bridge_transfer_id: BridgeTransferId(
-													*initiated._bridgeTransferId,
-												),
-												initiator: BridgeAddress(EthAddress(
-													Address::from(initiated._originator),
-												)),
-												recipient: BridgeAddress(
-													initiated._recipient.to_vec(),
-												),
-												hash_lock: HashLock(*initiated._hashLock),
-												time_lock: initiated._timeLock.into(),
-												amount: initiated.amount.into(),
-												state: 0,
-											};
-										BridgeContractEvent::Initiated(details)
-									};
-									if sender.send(Ok(event)).await.is_err() {
+								scanned_events += events.len() as u64;
+								for (initiated, log) in events {
+									let event = initiated_event(initiated);
+									if let (Some(number), Some(hash)) =
+										(log.block_number, log.block_hash)
+									{
+										if let BridgeContractEvent::Initiated(details) = &event {
+											// Nobody may listen, the prefetch is optional.
+											let _ = pending_initiation_tx
+												.send(details.recipient.0.clone());
+										}
+										confirmation_tracker.track(number, hash, event);
+									} else if sender
+										.send(Err(BridgeContractError::OnChainError(
+											"Eth initiated event log without block".to_string(),
+										)))
+										.await
+										.is_err()
+									{
 										tracing::error!("Failed to send event to listener channel");
 										break;
 									}
 								}
 							}
 							Ok(Err(_)) => {
+								scan_failed = true;
 								if sender
 									.send(Err(BridgeContractError::OnChainError("Eth monitoring query initiator_initiate_event_filter timeout.".to_string())))
 									.await
@@ -184,6 +293,7 @@ impl EthMonitoring {
 								}
 							}
 							Err(err) => {
+								scan_failed = true;
 								if sender
 									.send(Err(BridgeContractError::OnChainError(err.to_string())))
 									.await
@@ -195,22 +305,23 @@ impl EthMonitoring {
 							}
 						}
 						match tokio::time::timeout(
-							tokio::time::Duration::from_secs(config.rest_connection_timeout_secs),initiator_trcompleted_event_filter.query()).await {
+							tokio::time::Duration::from_secs(config.rest_connection_timeout_secs),
+							initiator_trcompleted_event_filter.query(),
+						)
+						.await
+						{
 							Ok(Ok(events)) => {
+								scanned_events += events.len() as u64;
 								for (completed, _log) in events {
-									if sender
-										.send(Ok(BridgeContractEvent::InitiatorCompleted(
-											BridgeTransferId(*completed._bridgeTransferId),
-										)))
-										.await
-										.is_err()
-									{
+									let event = initiator_completed_event(completed);
+									if sender.send(Ok(event)).await.is_err() {
 										tracing::error!("Failed to send event to listener channel");
 										break;
 									}
 								}
 							}
 							Ok(Err(_)) => {
+								scan_failed = true;
 								if sender
 									.send(Err(BridgeContractError::OnChainError("Eth monitoring query initiator_trcompleted_event_filter timeout.".to_string())))
 									.await
@@ -221,6 +332,7 @@ impl EthMonitoring {
 								}
 							}
 							Err(err) => {
+								scan_failed = true;
 								if sender
 									.send(Err(BridgeContractError::OnChainError(err.to_string())))
 									.await
@@ -238,20 +350,17 @@ impl EthMonitoring {
 						.await
 						{
 							Ok(Ok(events)) => {
+								scanned_events += events.len() as u64;
 								for (refund, _log) in events {
-									if sender
-										.send(Ok(BridgeContractEvent::Refunded(BridgeTransferId(
-											*refund._bridgeTransferId,
-										))))
-										.await
-										.is_err()
-									{
+									let event = refunded_event(refund);
+									if sender.send(Ok(event)).await.is_err() {
 										tracing::error!("Failed to send event to listener channel");
 										break;
 									}
 								}
 							}
 							Ok(Err(_)) => {
+								scan_failed = true;
 								if sender
 									.send(Err(BridgeContractError::OnChainError("Eth monitoring query initiator_trrefund_event_filter timeout.".to_string())))
 									.await
@@ -262,6 +371,7 @@ impl EthMonitoring {
 								}
 							}
 							Err(err) => {
+								scan_failed = true;
 								if sender
 									.send(Err(BridgeContractError::OnChainError(err.to_string())))
 									.await
@@ -281,27 +391,13 @@ impl EthMonitoring {
 						.await
 						{
 							Ok(Ok(events)) => {
+								scanned_events += events.len() as u64;
 								tracing::info!(
 									"Query completed for counterpart_trlocked_event_filter in {:?}",
 									start_time.elapsed()
 								);
 								for (trlocked, _log) in events {
-									let event = {
-										// BridgeTransferLocked(bridgeTransferId, originator, recipient, totalAmount, hashLock, initiatorTimeLockDuration);
-										let details: LockDetails<EthAddress> = LockDetails {
-											bridge_transfer_id: BridgeTransferId(
-												*trlocked.bridgeTransferId,
-											),
-											initiator: BridgeAddress([0, 32].into()), // TODO add the originator fields. trlocked.originator.to_vec()
-											recipient: BridgeAddress(EthAddress(Address::from(
-												trlocked.recipient,
-											))),
-											amount: trlocked.amount.into(),
-											hash_lock: HashLock(*trlocked.hashLock),
-											time_lock: trlocked.timeLock.into(),
-										};
-										BridgeContractEvent::Locked(details)
-									};
+									let event = locked_event(trlocked);
 									if sender.send(Ok(event)).await.is_err() {
 										tracing::error!("Failed to send event to listener channel");
 										break;
@@ -309,6 +405,7 @@ impl EthMonitoring {
 								}
 							}
 							Ok(Err(_)) => {
+								scan_failed = true;
 								if sender
 									.send(Err(BridgeContractError::OnChainError("Eth monitoring query counterpart_trlocked_event_filter timeout.".to_string())))
 									.await
@@ -319,6 +416,7 @@ impl EthMonitoring {
 								}
 							}
 							Err(err) => {
+								scan_failed = true;
 								if sender
 									.send(Err(BridgeContractError::OnChainError(err.to_string())))
 									.await
@@ -336,21 +434,17 @@ impl EthMonitoring {
 						.await
 						{
 							Ok(Ok(events)) => {
+								scanned_events += events.len() as u64;
 								for (completed, _log) in events {
-									if sender
-										.send(Ok(BridgeContractEvent::CounterPartyCompleted(
-											BridgeTransferId(*completed.bridgeTransferId),
-											HashLockPreImage(*completed.pre_image),
-										)))
-										.await
-										.is_err()
-									{
+									let event = counterparty_completed_event(completed);
+									if sender.send(Ok(event)).await.is_err() {
 										tracing::error!("Failed to send event to listener channel");
 										break;
 									}
 								}
 							}
 							Ok(Err(_)) => {
+								scan_failed = true;
 								if sender
 									.send(Err(BridgeContractError::OnChainError("Eth monitoring query counterpart_trcompleted_event_filter timeout.".to_string())))
 									.await
@@ -361,6 +455,7 @@ impl EthMonitoring {
 								}
 							}
 							Err(err) => {
+								scan_failed = true;
 								if sender
 									.send(Err(BridgeContractError::OnChainError(err.to_string())))
 									.await
@@ -378,20 +473,17 @@ impl EthMonitoring {
 						.await
 						{
 							Ok(Ok(events)) => {
+								scanned_events += events.len() as u64;
 								for (aborted, _log) in events {
-									if sender
-										.send(Ok(BridgeContractEvent::Cancelled(BridgeTransferId(
-											*aborted.bridgeTransferId,
-										))))
-										.await
-										.is_err()
-									{
+									let event = aborted_event(aborted);
+									if sender.send(Ok(event)).await.is_err() {
 										tracing::error!("Failed to send event to listener channel");
 										break;
 									}
 								}
 							}
 							Ok(Err(_)) => {
+								scan_failed = true;
 								if sender
 									.send(Err(BridgeContractError::OnChainError("Eth monitoring query counterpart_trcaborted_event_filter timeout.".to_string())))
 									.await
@@ -402,6 +494,7 @@ impl EthMonitoring {
 								}
 							}
 							Err(err) => {
+								scan_failed = true;
 								if sender
 									.send(Err(BridgeContractError::OnChainError(err.to_string())))
 									.await
@@ -412,15 +505,194 @@ impl EthMonitoring {
 								}
 							}
 						}
+						// A failed scan is retried from the same block on the next round,
+						// the events already sent are sent again and ignored by the relayer.
+						if !scan_failed {
+							last_processed_block = to_block;
+						}
+
+						// The pending initiated events of a block replaced by a reorg are
+						// dropped, and the scan restarts at this block, their transactions
+						// may be included in the new blocks.
+						match first_replaced_block(
+							&rpc_provider,
+							&confirmation_tracker.pending_blocks(),
+							Duration::from_secs(config.rest_connection_timeout_secs),
+						)
+						.await
+						{
+							Ok(Some(replaced)) => {
+								let dropped = confirmation_tracker.rollback(replaced);
+								tracing::warn!(
+									"Eth block {replaced} replaced by a reorg, {dropped} initiated events dropped"
+								);
+								last_processed_block =
+									last_processed_block.min(replaced.saturating_sub(1));
+							}
+							Ok(None) => {
+								for (number, hash, event) in
+									confirmation_tracker.confirmed(block_number)
+								{
+									reorg_monitor.record(number, hash, event.bridge_transfer_id());
+									if sender.send(Ok(event)).await.is_err() {
+										tracing::error!("Failed to send event to listener channel");
+										break;
+									}
+								}
+							}
+							Err(err) => {
+								if sender.send(Err(err)).await.is_err() {
+									tracing::error!("Failed to send event to listener channel");
+									break;
+								}
+							}
+						}
+
+						// The blocks of the initiated events already streamed can be replaced
+						// too, their transfers are checked once the new blocks are confirmed.
+						match first_replaced_block(
+							&rpc_provider,
+							&reorg_monitor.tracked_blocks(),
+							Duration::from_secs(config.rest_connection_timeout_secs),
+						)
+						.await
+						{
+							Ok(Some(replaced)) => {
+								let count = reorg_monitor.rollback(replaced, block_number);
+								tracing::warn!(
+									"Eth block {replaced} replaced by a reorg, {count} handled transfers to check"
+								);
+								last_processed_block =
+									last_processed_block.min(replaced.saturating_sub(1));
+							}
+							Ok(None) => reorg_monitor.prune(block_number),
+							Err(err) => {
+								if sender.send(Err(err)).await.is_err() {
+									tracing::error!("Failed to send event to listener channel");
+									break;
+								}
+							}
+						}
+						for transfer_id in reorg_monitor.due_transfers(block_number) {
+							match is_initiated(
+								&initiator_contract,
+								transfer_id,
+								Duration::from_secs(config.rest_connection_timeout_secs),
+							)
+							.await
+							{
+								Ok(true) => {
+									tracing::info!(
+										"Initiation of transfer {transfer_id} included again after a reorg"
+									);
+									reorg_monitor.checked(transfer_id);
+								}
+								Ok(false) => {
+									tracing::warn!(
+										"Initiation of transfer {transfer_id} dropped by a reorg"
+									);
+									reorg_monitor.checked(transfer_id);
+									if dropped_initiation_tx.send(transfer_id).is_err() {
+										tracing::warn!(
+											"No compensation of the dropped transfer {transfer_id}"
+										);
+									}
+								}
+								// Checked again on the next round.
+								Err(err) => {
+									if sender.send(Err(err)).await.is_err() {
+										tracing::error!("Failed to send event to listener channel");
+										break;
+									}
+								}
+							}
+						}
 					} // end match
+					catch_up.record_scan(
+						ETH_CHAIN,
+						last_processed_block,
+						Some(head),
+						scanned_events,
+					);
+					// The monitoring starts at the head, it has caught up once a scan reaches it.
+					if !scan_failed && last_processed_block >= head {
+						catch_up.mark_caught_up(ETH_CHAIN);
+					}
 
 					let _ = tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
 				} // end loop
 			} // End spawn
 		});
 
-		Ok(Self { listener })
+		Ok(Self {
+			listener,
+			dropped_initiations: Some(dropped_initiations),
+			pending_initiations: Some(pending_initiations),
+		})
+	}
+
+	/// Take the receiver of the transfers whose Initiated event, already streamed, has been
+	/// dropped by a reorg. The relayer must compensate them.
+	pub fn take_dropped_initiations(
+		&mut self,
+	) -> Option<mpsc::UnboundedReceiver<BridgeTransferId>> {
+		self.dropped_initiations.take()
+	}
+
+	/// Take the receiver of the recipients of the transfers initiated but still waiting for
+	/// their confirmations, to prefetch what their lock needs.
+	pub fn take_pending_initiations(&mut self) -> Option<mpsc::UnboundedReceiver<Vec<u8>>> {
+		self.pending_initiations.take()
+	}
+}
+
+// True if the transfer is initiated in the initiator contract at the head block.
+async fn is_initiated(
+	contract: &InitiatorContract,
+	transfer_id: BridgeTransferId,
+	timeout: Duration,
+) -> BridgeContractResult<bool> {
+	let transfer =
+		tokio::time::timeout(timeout, contract.bridgeTransfers(FixedBytes(transfer_id.0)).call())
+			.await
+			.map_err(|err| {
+				BridgeContractError::OnChainError(format!(
+					"Eth get transfer {transfer_id} timeout: {err}"
+				))
+			})?
+			.map_err(|err| {
+				BridgeContractError::OnChainError(format!(
+					"Eth get transfer {transfer_id} request failed: {err}"
+				))
+			})?;
+	Ok(transfer.originator != Address::ZERO)
+}
+
+// The first of the blocks that is no longer in the chain.
+async fn first_replaced_block(
+	provider: &AlloyProvider,
+	blocks: &[(u64, B256)],
+	timeout: Duration,
+) -> BridgeContractResult<Option<u64>> {
+	for (number, hash) in blocks {
+		let block = tokio::time::timeout(
+			timeout,
+			provider.get_block_by_number(BlockNumberOrTag::Number(*number), false),
+		)
+		.await
+		.map_err(|err| {
+			BridgeContractError::OnChainError(format!("Eth get block {number} timeout: {err}"))
+		})?
+		.map_err(|err| {
+			BridgeContractError::OnChainError(format!(
+				"Eth get block {number} request failed: {err}"
+			))
+		})?;
+		if block.and_then(|block| block.header.hash) != Some(*hash) {
+			return Ok(Some(*number));
+		}
 	}
+	Ok(None)
 }
 
 impl Stream for EthMonitoring {
@@ -0,0 +1,172 @@
+//! Fees of the EIP-1559 transactions, from the fee history of the last blocks,
+//! and their escalation when a transaction is stuck.
+use alloy::{
+	contract::{CallBuilder, CallDecoder},
+	eips::BlockNumberOrTag,
+	network::Ethereum,
+	providers::Provider,
+	transports::Transport,
+};
+use bridge_config::common::gas::GasConfig;
+use std::time::Duration;
+
+/// Fees per gas of an EIP-1559 transaction, in wei.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasFees {
+	pub max_fee_per_gas: u128,
+	pub max_priority_fee_per_gas: u128,
+}
+
+impl GasFees {
+	pub fn apply<T, P, D>(
+		&self,
+		call: CallBuilder<T, P, D, Ethereum>,
+	) -> CallBuilder<T, P, D, Ethereum>
+	where
+		T: Transport + Clone,
+		P: Provider<T, Ethereum>,
+		D: CallDecoder,
+	{
+		call.max_fee_per_gas(self.max_fee_per_gas)
+			.max_priority_fee_per_gas(self.max_priority_fee_per_gas)
+	}
+}
+
+/// Computes the fees of the transactions sent by the client. The default strategy is
+/// disabled: the transactions are sent as legacy ones at the node gas price.
+#[derive(Debug, Clone, Default)]
+pub struct GasStrategy {
+	eip1559: bool,
+	fee_history_blocks: u64,
+	priority_fee_percentile: f64,
+	base_fee_multiplier_percent: u128,
+	max_fee_per_gas_cap: Option<u128>,
+	stuck_after: Option<Duration>,
+	bump_percent: u128,
+	max_bumps: u32,
+}
+
+impl From<&GasConfig> for GasStrategy {
+	fn from(config: &GasConfig) -> Self {
+		GasStrategy {
+			eip1559: config.eip1559,
+			fee_history_blocks: config.fee_history_blocks.max(1),
+			priority_fee_percentile: config.priority_fee_percentile.min(100) as f64,
+			base_fee_multiplier_percent: config.base_fee_multiplier_percent.into(),
+			max_fee_per_gas_cap: config.max_fee_per_gas_cap.map(Into::into),
+			stuck_after: (config.stuck_after_secs != 0)
+				.then(|| Duration::from_secs(config.stuck_after_secs)),
+			bump_percent: config.bump_percent.into(),
+			max_bumps: config.max_bumps,
+		}
+	}
+}
+
+impl GasStrategy {
+	pub fn is_eip1559(&self) -> bool {
+		self.eip1559
+	}
+
+	/// Delay after which a transaction not mined is replaced, None if they are never.
+	pub fn stuck_after(&self) -> Option<Duration> {
+		self.stuck_after
+	}
+
+	pub fn max_bumps(&self) -> u32 {
+		self.max_bumps
+	}
+
+	/// Fees of a new transaction, from the fee history of the last blocks.
+	pub async fn fees<T, P>(&self, provider: &P) -> Result<GasFees, anyhow::Error>
+	where
+		T: Transport + Clone,
+		P: Provider<T, Ethereum>,
+	{
+		let history = provider
+			.get_fee_history(
+				self.fee_history_blocks,
+				BlockNumberOrTag::Latest,
+				&[self.priority_fee_percentile],
+			)
+			.await?;
+		// The base fees of the history end with the one of the next block.
+		let next_base_fee = history
+			.base_fee_per_gas
+			.last()
+			.copied()
+			.ok_or_else(|| anyhow::anyhow!("Empty fee history"))?;
+		let rewards: Vec<u128> = history
+			.reward
+			.unwrap_or_default()
+			.iter()
+			.filter_map(|rewards| rewards.first().copied())
+			.collect();
+		Ok(self.fees_of(next_base_fee, &rewards))
+	}
+
+	/// Fees from the next block base fee and the priority fees paid in the last blocks:
+	/// the priority fee is their median, the max fee leaves room for the base fee to rise.
+	fn fees_of(&self, next_base_fee: u128, rewards: &[u128]) -> GasFees {
+		let mut rewards = rewards.to_vec();
+		rewards.sort_unstable();
+		let max_priority_fee_per_gas = rewards.get(rewards.len() / 2).copied().unwrap_or(0);
+		let max_fee_per_gas =
+			next_base_fee * self.base_fee_multiplier_percent / 100 + max_priority_fee_per_gas;
+		self.capped(GasFees { max_fee_per_gas, max_priority_fee_per_gas })
+	}
+
+	/// Fees of the replacement of a stuck transaction.
+	pub fn bump(&self, fees: GasFees) -> GasFees {
+		let bump = |fee: u128| fee + (fee * self.bump_percent + 99) / 100;
+		self.capped(GasFees {
+			max_fee_per_gas: bump(fees.max_fee_per_gas),
+			max_priority_fee_per_gas: bump(fees.max_priority_fee_per_gas),
+		})
+	}
+
+	fn capped(&self, fees: GasFees) -> GasFees {
+		let max_fee_per_gas = match self.max_fee_per_gas_cap {
+			Some(cap) => fees.max_fee_per_gas.min(cap),
+			None => fees.max_fee_per_gas,
+		};
+		GasFees {
+			max_fee_per_gas,
+			max_priority_fee_per_gas: fees.max_priority_fee_per_gas.min(max_fee_per_gas),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const GWEI: u128 = 1_000_000_000;
+
+	#[test]
+	fn test_gas_fees() {
+		let strategy = GasStrategy::from(&GasConfig {
+			eip1559: true,
+			max_fee_per_gas_cap: Some((100 * GWEI) as u64),
+			..Default::default()
+		});
+		let fees = strategy.fees_of(30 * GWEI, &[3 * GWEI, GWEI, 2 * GWEI]);
+		assert_eq!(
+			fees,
+			GasFees { max_fee_per_gas: 62 * GWEI, max_priority_fee_per_gas: 2 * GWEI }
+		);
+
+		// Each replacement bumps the fees of 15%, up to the cap.
+		let fees = strategy.bump(fees);
+		assert_eq!(fees.max_fee_per_gas, 62 * GWEI * 115 / 100);
+		assert_eq!(fees.max_priority_fee_per_gas, 2 * GWEI * 115 / 100);
+		let fees = strategy.bump(strategy.bump(strategy.bump(fees)));
+		assert_eq!(fees.max_fee_per_gas, 100 * GWEI);
+
+		// The priority fee never exceeds the max fee.
+		let fees = strategy.fees_of(200 * GWEI, &[150 * GWEI]);
+		assert_eq!(
+			fees,
+			GasFees { max_fee_per_gas: 100 * GWEI, max_priority_fee_per_gas: 100 * GWEI }
+		);
+	}
+}
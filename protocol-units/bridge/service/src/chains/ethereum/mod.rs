@@ -1,4 +1,10 @@
 pub mod client;
+pub mod confirmations;
+pub mod ens;
+pub mod event_decoding;
 pub mod event_monitoring;
+pub mod gas;
+pub mod redundancy;
+pub mod reorg;
 pub mod types;
 pub mod utils;
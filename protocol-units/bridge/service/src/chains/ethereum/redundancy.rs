@@ -0,0 +1,219 @@
+//! Broadcast of the critical transactions through two independent RPC providers. The
+//! completion of a transfer whose time lock is about to expire can't wait for a provider to
+//! come back or for its mempool to propagate the transaction. The transaction is signed once
+//! and the same signed bytes, so the same nonce and hash, are sent to both providers at once:
+//! only one of the copies can be mined, and the receipt is read from either provider.
+use super::client::{Config, EthClient, NonceManager};
+use super::gas::GasStrategy;
+use super::types::AlloyProvider;
+use crate::rpc_cache::{RpcCache, RpcRead};
+use alloy::{
+	contract::{CallBuilder, CallDecoder},
+	eips::eip2718::Encodable2718,
+	network::{Ethereum, EthereumWallet, TransactionBuilder},
+	primitives::{Address, Bytes, B256},
+	providers::Provider,
+	rpc::types::TransactionReceipt,
+	transports::{BoxTransport, Transport},
+};
+use bridge_config::common::redundancy::RedundancyConfig;
+use std::time::Duration;
+
+// Gas of the broadcast transactions, the one of `send_transaction_with_strategy`.
+const BROADCAST_GAS: u128 = 720_000;
+// Interval of the receipt reads on both providers.
+const RECEIPT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The second provider of the critical transactions.
+#[derive(Clone)]
+pub struct RedundantBroadcast {
+	secondary: AlloyProvider,
+	secondary_url: String,
+	within_secs: u64,
+}
+
+impl RedundantBroadcast {
+	/// Connect to the second provider of the config with the wallet of the client, `None` if
+	/// redundancy is disabled.
+	pub async fn connect(
+		config: &RedundancyConfig,
+		eth_config: &Config,
+	) -> Result<Option<Self>, anyhow::Error> {
+		if !config.is_enabled() {
+			return Ok(None);
+		}
+		if config.secondary_rpc_url == eth_config.rpc_url.as_str() {
+			anyhow::bail!(
+				"The second RPC provider must differ from the one of the Ethereum config"
+			);
+		}
+		let secondary = EthClient::connect_provider(eth_config, &config.secondary_rpc_url).await?;
+		Ok(Some(Self::with_provider(secondary, &config.secondary_rpc_url, config.within_secs)))
+	}
+
+	pub fn with_provider(secondary: AlloyProvider, secondary_url: &str, within_secs: u64) -> Self {
+		RedundantBroadcast { secondary, secondary_url: secondary_url.to_string(), within_secs }
+	}
+
+	/// Whether the completion of a transfer with the time lock is critical at the time.
+	pub fn is_critical(&self, time_lock: u64, now_secs: u64) -> bool {
+		time_lock.saturating_sub(now_secs) <= self.within_secs
+	}
+
+	/// Sign the call once with a leased nonce and send it through the primary provider of the
+	/// call and the second provider at once, then wait for its receipt on either of them. The
+	/// hash of the transaction is reported once a provider accepted it.
+	pub async fn send<D: CallDecoder + Clone>(
+		&self,
+		call: CallBuilder<BoxTransport, &AlloyProvider, D, Ethereum>,
+		wallet: &EthereumWallet,
+		signer_address: Address,
+		gas_limit: u128,
+		rpc_cache: &RpcCache,
+		gas_strategy: &GasStrategy,
+		nonce_manager: &NonceManager,
+		on_sent: impl Fn(B256),
+	) -> Result<TransactionReceipt, anyhow::Error> {
+		let primary = call.provider;
+		let nonce_lease = nonce_manager.lease(primary, signer_address).await?;
+		let call = call.from(signer_address).gas(BROADCAST_GAS).nonce(nonce_lease.nonce());
+		let (call, gas_price) = if gas_strategy.is_eip1559() {
+			let fees = gas_strategy.fees(primary).await?;
+			(fees.apply(call), fees.max_fee_per_gas)
+		} else {
+			let gas_price =
+				rpc_cache.get_or_fetch(RpcRead::GasPrice, "", primary.get_gas_price()).await?;
+			(call.gas_price(gas_price), gas_price)
+		};
+		let transaction_fee_wei = BROADCAST_GAS * gas_price;
+		if transaction_fee_wei > gas_limit {
+			anyhow::bail!("Transaction fee {transaction_fee_wei} above the limit {gas_limit}");
+		}
+		let chain_id = rpc_cache.get_or_fetch(RpcRead::ChainId, "", primary.get_chain_id()).await?;
+
+		let (tx_hash, raw_tx) = sign(call, chain_id, wallet).await?;
+		broadcast(&raw_tx, &[(primary, "primary"), (&self.secondary, &self.secondary_url)]).await?;
+		tracing::info!("Transaction {tx_hash} broadcast through both RPC providers");
+		on_sent(tx_hash);
+		let receipt = wait_receipt(tx_hash, &[primary, &self.secondary]).await;
+		nonce_lease.confirm();
+		if !receipt.status() {
+			anyhow::bail!("Broadcast transaction {tx_hash} failed, receipt: {receipt:?}");
+		}
+		Ok(receipt)
+	}
+}
+
+// Sign the call with the wallet, returning the hash and the bytes of the signed transaction.
+async fn sign<T, P, D>(
+	call: CallBuilder<T, P, D, Ethereum>,
+	chain_id: u64,
+	wallet: &EthereumWallet,
+) -> Result<(B256, Bytes), anyhow::Error>
+where
+	T: Transport + Clone,
+	P: Provider<T, Ethereum>,
+	D: CallDecoder,
+{
+	let envelope = call.into_transaction_request().with_chain_id(chain_id).build(wallet).await?;
+	Ok((*envelope.tx_hash(), Bytes::from(envelope.encoded_2718())))
+}
+
+// Send the signed transaction to all the providers at once. It's broadcast if one of them
+// accepts it, the rejections of the others are only logged.
+async fn broadcast<T, P>(raw_tx: &Bytes, providers: &[(&P, &str)]) -> Result<(), anyhow::Error>
+where
+	T: Transport + Clone,
+	P: Provider<T, Ethereum>,
+{
+	let sends = providers.iter().map(|(provider, _)| provider.send_raw_transaction(raw_tx));
+	let mut accepted = false;
+	for ((_, name), sent) in providers.iter().zip(futures::future::join_all(sends).await) {
+		match sent {
+			Ok(_) => accepted = true,
+			Err(err) => tracing::warn!("RPC provider {name} rejected the transaction: {err}"),
+		}
+	}
+	if !accepted {
+		anyhow::bail!("No RPC provider accepted the transaction");
+	}
+	Ok(())
+}
+
+// Poll the providers for the receipt of the transaction, until one of them returns it.
+async fn wait_receipt<T, P>(tx_hash: B256, providers: &[&P]) -> TransactionReceipt
+where
+	T: Transport + Clone,
+	P: Provider<T, Ethereum>,
+{
+	loop {
+		for provider in providers {
+			match provider.get_transaction_receipt(tx_hash).await {
+				Ok(Some(receipt)) => return receipt,
+				Ok(None) => {}
+				Err(err) => tracing::debug!("Failed to read the receipt of {tx_hash}: {err}"),
+			}
+		}
+		tokio::time::sleep(RECEIPT_POLL_INTERVAL).await;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use alloy::{providers::ProviderBuilder, rpc::client::RpcClient};
+	use bridge_test_fixtures::eth_rpc::{MockResponse, MockTransport};
+
+	fn mock_provider(transport: &MockTransport, wallet: &EthereumWallet) -> AlloyProvider {
+		ProviderBuilder::new()
+			.with_recommended_fillers()
+			.wallet(wallet.clone())
+			.on_client(RpcClient::new(transport.boxed(), true))
+	}
+
+	#[tokio::test]
+	async fn test_redundant_broadcast() {
+		let signer =
+			bridge_test_fixtures::eth::anvil_signer(bridge_test_fixtures::eth::SIGNER_INDEX);
+		let wallet = EthereumWallet::from(signer.clone());
+		let (primary, secondary) = (MockTransport::new(), MockTransport::new());
+		let primary_provider = mock_provider(&primary, &wallet);
+		let redundancy = RedundantBroadcast::with_provider(
+			mock_provider(&secondary, &wallet),
+			"http://secondary:8545",
+			600,
+		);
+
+		// Critical within the window and past the time lock, not before the window.
+		assert!(redundancy.is_critical(1600, 1000));
+		assert!(redundancy.is_critical(900, 1000));
+		assert!(!redundancy.is_critical(1601, 1000));
+
+		let contract = super::super::types::AtomicBridgeInitiatorMOVE::new(
+			Address::repeat_byte(1),
+			primary_provider.clone(),
+		);
+		let call = contract
+			.refundBridgeTransfer([1; 32].into())
+			.from(signer.address())
+			.gas(BROADCAST_GAS)
+			.nonce(7)
+			.gas_price(1_000_000_000);
+		let (tx_hash, raw_tx) = sign(call, 31337, &wallet).await.unwrap();
+		assert!(!raw_tx.is_empty());
+
+		// The same signed transaction reaches both providers, one rejection is tolerated.
+		let hash = serde_json::Value::from(tx_hash.to_string());
+		primary.expect("eth_sendRawTransaction", MockResponse::Result(hash.clone()));
+		secondary.push_error(-32000, "nonce too low");
+		let providers =
+			[(&primary_provider, "primary"), (&redundancy.secondary, "http://secondary:8545")];
+		broadcast(&raw_tx, &providers).await.unwrap();
+		assert_eq!(secondary.requests(), ["eth_sendRawTransaction"]);
+
+		primary.push(MockResponse::TransportFailure("connection reset".to_string()));
+		secondary.push_error(-32000, "insufficient funds for gas * price + value");
+		assert!(broadcast(&raw_tx, &providers).await.is_err());
+		assert_eq!((primary.remaining(), secondary.remaining()), (0, 0));
+	}
+}
@@ -0,0 +1,112 @@
+//! Reorgs of the blocks of the initiated events already handled by the relayer. The transfers
+//! whose initiation isn't included again in the new blocks must be compensated.
+use alloy::primitives::B256;
+use bridge_util::types::BridgeTransferId;
+use std::collections::BTreeMap;
+
+/// Number of blocks the handled events are tracked for, older blocks are considered final.
+pub const TRACKED_BLOCKS: u64 = 64;
+
+#[derive(Debug)]
+struct TrackedBlock {
+	hash: B256,
+	transfers: Vec<BridgeTransferId>,
+}
+
+/// Blocks of the handled initiated events, and the transfers of the blocks replaced by a reorg
+/// waiting for their initiation to be checked in the new blocks.
+#[derive(Debug)]
+pub struct ReorgMonitor {
+	confirmations: u64,
+	blocks: BTreeMap<u64, TrackedBlock>,
+	// Transfers of the replaced blocks, with the head block when the reorg has been found.
+	replaced: Vec<(BridgeTransferId, u64)>,
+}
+
+impl ReorgMonitor {
+	/// The initiation of a replaced transfer is checked once the blocks following the reorg
+	/// have the confirmations, it may be included again in one of them.
+	pub fn new(confirmations: u64) -> Self {
+		ReorgMonitor { confirmations, blocks: BTreeMap::new(), replaced: Vec::new() }
+	}
+
+	/// Track the block of the Initiated event of a handled transfer.
+	pub fn record(&mut self, block_number: u64, block_hash: B256, transfer_id: BridgeTransferId) {
+		let block = self
+			.blocks
+			.entry(block_number)
+			.or_insert_with(|| TrackedBlock { hash: block_hash, transfers: Vec::new() });
+		if block.hash != block_hash {
+			block.hash = block_hash;
+			block.transfers.clear();
+		}
+		if !block.transfers.contains(&transfer_id) {
+			block.transfers.push(transfer_id);
+		}
+	}
+
+	/// Number and hash of the tracked blocks, in block order.
+	pub fn tracked_blocks(&self) -> Vec<(u64, B256)> {
+		self.blocks.iter().map(|(number, block)| (*number, block.hash)).collect()
+	}
+
+	/// A block has been replaced by a reorg found at the head block: the transfers of the
+	/// block and of the blocks after it wait for the check of their initiation.
+	/// Return the number of these transfers.
+	pub fn rollback(&mut self, block_number: u64, head: u64) -> usize {
+		let replaced = self.blocks.split_off(&block_number);
+		let count = self.replaced.len();
+		self.replaced
+			.extend(replaced.into_values().flat_map(|block| block.transfers).map(|id| (id, head)));
+		self.replaced.len() - count
+	}
+
+	/// The replaced transfers whose initiation can be checked at the head block.
+	pub fn due_transfers(&self, head: u64) -> Vec<BridgeTransferId> {
+		self.replaced
+			.iter()
+			.filter(|(_, reorg_head)| reorg_head + self.confirmations <= head)
+			.map(|(transfer_id, _)| *transfer_id)
+			.collect()
+	}
+
+	/// The initiation of a replaced transfer has been checked.
+	pub fn checked(&mut self, transfer_id: BridgeTransferId) {
+		self.replaced.retain(|(id, _)| *id != transfer_id);
+	}
+
+	/// Stop tracking the blocks final at the head block.
+	pub fn prune(&mut self, head: u64) {
+		let first_tracked = head.saturating_sub(TRACKED_BLOCKS);
+		self.blocks = self.blocks.split_off(&first_tracked);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_reorg_monitor() {
+		let transfer = |id: u8| BridgeTransferId([id; 32]);
+		let mut monitor = ReorgMonitor::new(2);
+		monitor.record(10, B256::repeat_byte(10), transfer(1));
+		monitor.record(11, B256::repeat_byte(11), transfer(2));
+		monitor.record(11, B256::repeat_byte(11), transfer(2));
+		monitor.record(12, B256::repeat_byte(12), transfer(3));
+
+		assert_eq!(monitor.rollback(11, 15), 2);
+		assert_eq!(monitor.tracked_blocks(), [(10, B256::repeat_byte(10))]);
+		// The new blocks don't have the confirmations yet.
+		assert!(monitor.due_transfers(16).is_empty());
+		assert_eq!(monitor.due_transfers(17), [transfer(2), transfer(3)]);
+		monitor.checked(transfer(2));
+		assert_eq!(monitor.due_transfers(17), [transfer(3)]);
+
+		monitor.record(100, B256::repeat_byte(100), transfer(4));
+		monitor.prune(100 + TRACKED_BLOCKS);
+		assert_eq!(monitor.tracked_blocks(), [(100, B256::repeat_byte(100))]);
+		monitor.prune(101 + TRACKED_BLOCKS);
+		assert!(monitor.tracked_blocks().is_empty());
+	}
+}
@@ -1,12 +1,13 @@
 use crate::types::AddressError;
 use alloy::network::{Ethereum, EthereumWallet};
-use alloy::primitives::Address;
+use alloy::primitives::{Address, U256};
 use alloy::providers::fillers::{
 	ChainIdFiller, FillProvider, GasFiller, JoinFill, NonceFiller, WalletFiller,
 };
 use alloy::providers::RootProvider;
 use alloy::rlp::{RlpDecodable, RlpEncodable};
 use alloy::transports::BoxTransport;
+use bridge_util::chains::bridge_contracts::{BridgeContractError, BridgeContractResult};
 use bridge_util::types::Amount;
 use bridge_util::types::BridgeAddress;
 use bridge_util::types::BridgeTransferDetails;
@@ -15,10 +16,14 @@ use bridge_util::types::HashLock;
 use bridge_util::types::HashLockPreImage;
 use bridge_util::types::LockDetails;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 use std::hash::{DefaultHasher, Hash, Hasher};
 
 pub const ETH_ADDRESS_LEN: usize = 20;
 
+/// Decimals of the bridge amounts, those of the MOVE token on both chains.
+pub const AMOUNT_DECIMALS: u8 = 8;
+
 // Codegen for the MOVE bridge contracts
 alloy::sol!(
 	#[allow(missing_docs)]
@@ -40,6 +45,96 @@ alloy::sol!(
 	"abis/MockMOVEToken.json"
 );
 
+// Multicall of the bridge contracts: the calls are delegated by the contract to itself, in one
+// transaction sent by the relayer. A reverted call is logged with its index in the batch and
+// doesn't revert the others.
+alloy::sol!(
+	#[allow(missing_docs)]
+	#[sol(rpc)]
+	interface IMulticall {
+		event BatchCallFailed(uint256 index, bytes reason);
+
+		function tryMulticall(bytes[] calldata data) external returns (bool[] memory successes);
+	}
+);
+
+alloy::sol!(
+	#[allow(missing_docs)]
+	#[sol(rpc)]
+	WETH9,
+	"abis/WETH9.json"
+);
+
+// Standard interface of the ERC-20 tokens bridged besides the MOVE token.
+alloy::sol!(
+	#[allow(missing_docs)]
+	#[sol(rpc)]
+	interface IERC20 {
+		function allowance(address owner, address spender) external view returns (uint256);
+		function approve(address spender, uint256 amount) external returns (bool);
+		function decimals() external view returns (uint8);
+	}
+);
+
+/// ERC-20 token bridged by its own initiator contract. The bridge amounts have
+/// `AMOUNT_DECIMALS` decimals, the amounts of the token are converted to them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Erc20Token {
+	pub symbol: String,
+	pub token_contract: Address,
+	pub initiator_contract: Address,
+	pub decimals: u8,
+}
+
+impl Erc20Token {
+	/// Amount of the token, in its smallest unit, of a bridge amount.
+	pub fn to_token_amount(&self, amount: Amount) -> BridgeContractResult<U256> {
+		let amount = U256::from(amount.0);
+		if self.decimals >= AMOUNT_DECIMALS {
+			amount.checked_mul(self.scale()).ok_or_else(|| {
+				BridgeContractError::ConversionFailed(format!(
+					"{amount} overflows the {} amounts",
+					self.symbol
+				))
+			})
+		} else if (amount % self.scale()).is_zero() {
+			Ok(amount / self.scale())
+		} else {
+			Err(BridgeContractError::ConversionFailed(format!(
+				"{amount} has more than the {} decimals of {}",
+				self.decimals, self.symbol
+			)))
+		}
+	}
+
+	/// Bridge amount of an amount of the token. The token amounts below the
+	/// precision of the bridge amounts are rejected rather than truncated.
+	pub fn to_amount(&self, token_amount: U256) -> BridgeContractResult<Amount> {
+		let amount = if self.decimals >= AMOUNT_DECIMALS {
+			if !(token_amount % self.scale()).is_zero() {
+				return Err(BridgeContractError::ConversionFailed(format!(
+					"{token_amount} {} has more than the {AMOUNT_DECIMALS} decimals of the bridge",
+					self.symbol
+				)));
+			}
+			token_amount / self.scale()
+		} else {
+			token_amount.checked_mul(self.scale()).unwrap_or(U256::MAX)
+		};
+		u64::try_from(amount).map(Amount).map_err(|_| {
+			BridgeContractError::ConversionFailed(format!(
+				"{token_amount} {} overflows the bridge amounts",
+				self.symbol
+			))
+		})
+	}
+
+	// Ratio between the smallest units of the token and of the bridge amounts.
+	fn scale(&self) -> U256 {
+		U256::from(10u64).pow(U256::from(self.decimals.abs_diff(AMOUNT_DECIMALS)))
+	}
+}
+
 /// Specifies the kind of asset being transferred,
 /// This will associate the client with its respective ABIs
 #[derive(Debug, Clone, Default)]
@@ -125,7 +220,7 @@ pub type AlloyProvider = FillProvider<
 	Ethereum,
 >;
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone, RlpEncodable, RlpDecodable)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, RlpEncodable, RlpDecodable, Serialize, Deserialize)]
 pub struct EthAddress(pub Address);
 
 impl From<EthAddress> for Vec<u8> {
@@ -175,7 +270,7 @@ impl From<[u8; 20]> for EthAddress {
 	}
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct CompletedDetails<A> {
 	pub bridge_transfer_id: BridgeTransferId,
 	pub recipient: BridgeAddress<A>,
@@ -211,3 +306,38 @@ where
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn token(decimals: u8) -> Erc20Token {
+		Erc20Token {
+			symbol: "USDC".to_string(),
+			token_contract: Address::repeat_byte(1),
+			initiator_contract: Address::repeat_byte(2),
+			decimals,
+		}
+	}
+
+	#[test]
+	fn test_erc20_amount_normalization() {
+		let usdc = token(6);
+		assert_eq!(usdc.to_token_amount(Amount(150_000_000)).unwrap(), U256::from(1_500_000));
+		assert_eq!(usdc.to_amount(U256::from(1_500_000)).unwrap(), Amount(150_000_000));
+		// The bridge amount has more decimals than the token.
+		assert!(usdc.to_token_amount(Amount(1)).is_err());
+
+		let dai = token(18);
+		let one_dai = U256::from(10u64).pow(U256::from(18));
+		assert_eq!(dai.to_amount(one_dai).unwrap(), Amount(100_000_000));
+		assert_eq!(dai.to_token_amount(Amount(100_000_000)).unwrap(), one_dai);
+		// The dust below the bridge precision is not dropped silently.
+		assert!(dai.to_amount(one_dai + U256::from(1)).is_err());
+		assert!(dai.to_amount(U256::MAX).is_err());
+
+		let movelike = token(AMOUNT_DECIMALS);
+		assert_eq!(movelike.to_token_amount(Amount(42)).unwrap(), U256::from(42));
+		assert_eq!(movelike.to_amount(U256::from(42)).unwrap(), Amount(42));
+	}
+}
@@ -1,20 +1,28 @@
+use crate::chains::ethereum::client::NonceManager;
+use crate::chains::ethereum::gas::{GasFees, GasStrategy};
 use crate::chains::ethereum::types::EthAddress;
+use crate::latency::{stage_span, Stage};
+use crate::rpc_cache::{RpcCache, RpcRead};
 use alloy::{
 	contract::{CallBuilder, CallDecoder},
 	network::Ethereum,
-	primitives::{Address, U256},
+	primitives::{Address, TxKind, B256, U256},
 	providers::Provider,
 	rlp::{Encodable, RlpEncodable},
 	rpc::types::TransactionReceipt,
-	transports::Transport,
+	transports::{RpcError, Transport, TransportError},
 };
 use keccak_hash::keccak;
 use mcr_settlement_client::send_eth_transaction::{
 	InsufficentFunds, SendTransactionErrorRule, UnderPriced, VerifyRule,
 };
 use std::str::FromStr;
+use std::time::{Duration, Instant};
 use thiserror::Error;
-use tracing::info;
+use tracing::{info, Instrument};
+
+// Interval of the receipt reads of a transaction that may be replaced.
+const RECEIPT_POLL_INTERVAL: Duration = Duration::from_secs(2);
 
 #[derive(Debug, Error)]
 pub enum EthUtilError {
@@ -32,6 +40,23 @@ pub enum EthUtilError {
 	RpcTransactionExecution(String),
 }
 
+/// Whether the error is a failure of the transport to the node. The other errors are answered
+/// by the node, like the reverts and the nonce conflicts, and leave the connection healthy.
+pub fn is_transport_error(err: &anyhow::Error) -> bool {
+	err.chain().any(|cause| {
+		let transport_error = match cause.downcast_ref::<EthUtilError>() {
+			Some(EthUtilError::SendTxError(alloy::contract::Error::TransportError(err))) => {
+				Some(err)
+			}
+			_ => match cause.downcast_ref::<alloy::contract::Error>() {
+				Some(alloy::contract::Error::TransportError(err)) => Some(err),
+				_ => cause.downcast_ref::<TransportError>(),
+			},
+		};
+		matches!(transport_error, Some(RpcError::Transport(_)))
+	})
+}
+
 impl FromStr for EthAddress {
 	type Err = EthUtilError;
 
@@ -69,6 +94,55 @@ pub fn send_transaction_rules() -> Vec<Box<dyn VerifyRule>> {
 	vec![rule1, rule2]
 }
 
+/// Attach the access list computed by the node to the call, so the accounts and storage
+/// slots it touches are charged at the warm price. The call is returned without access
+/// list if the node can't compute it.
+pub async fn with_access_list<
+	P: Provider<T, Ethereum> + Clone,
+	T: Transport + Clone,
+	D: CallDecoder + Clone,
+>(
+	call_builder: CallBuilder<T, &P, D, Ethereum>,
+	signer_address: Address,
+) -> CallBuilder<T, &P, D, Ethereum> {
+	let call_builder = call_builder.from(signer_address);
+	let request = call_builder.clone().into_transaction_request();
+	match call_builder.provider.create_access_list(&request).await {
+		Ok(access_list) => {
+			tracing::debug!(
+				"Access list of {} entries, {} gas used",
+				access_list.access_list.0.len(),
+				access_list.gas_used
+			);
+			call_builder.access_list(access_list.access_list)
+		}
+		Err(err) => {
+			tracing::warn!("Failed to create access list, sending without: {err}");
+			call_builder
+		}
+	}
+}
+
+/// The call with the tag appended to its calldata. The contracts decode their arguments
+/// from the start of the calldata, the trailing tag is only read by the block explorers.
+/// None if the call isn't a contract call.
+pub fn with_calldata_tag<
+	P: Provider<T, Ethereum> + Clone,
+	T: Transport + Clone,
+	D: CallDecoder + Clone,
+>(
+	call_builder: CallBuilder<T, &P, D, Ethereum>,
+	tag: &[u8],
+) -> Option<CallBuilder<T, &P, (), Ethereum>> {
+	let provider = call_builder.provider;
+	let request = call_builder.into_transaction_request();
+	let TxKind::Call(to) = request.to? else {
+		return None;
+	};
+	let calldata = [request.input.input()?.as_ref(), tag].concat();
+	Some(CallBuilder::new_raw(provider, calldata.into()).to(to))
+}
+
 pub async fn send_transaction<
 	P: Provider<T, Ethereum> + Clone,
 	T: Transport + Clone,
@@ -79,6 +153,91 @@ pub async fn send_transaction<
 	send_transaction_error_rules: &[Box<dyn VerifyRule>],
 	number_retry: u32,
 	gas_limit: u128,
+) -> Result<TransactionReceipt, anyhow::Error> {
+	send_transaction_with_cache(
+		base_call_builder,
+		signer_address,
+		send_transaction_error_rules,
+		number_retry,
+		gas_limit,
+		&RpcCache::default(),
+	)
+	.await
+}
+
+/// Same as `send_transaction`, reading the gas price through the RPC cache.
+pub async fn send_transaction_with_cache<
+	P: Provider<T, Ethereum> + Clone,
+	T: Transport + Clone,
+	D: CallDecoder + Clone,
+>(
+	base_call_builder: CallBuilder<T, &P, D, Ethereum>,
+	signer_address: Address,
+	send_transaction_error_rules: &[Box<dyn VerifyRule>],
+	number_retry: u32,
+	gas_limit: u128,
+	rpc_cache: &RpcCache,
+) -> Result<TransactionReceipt, anyhow::Error> {
+	send_transaction_with_strategy(
+		base_call_builder,
+		signer_address,
+		send_transaction_error_rules,
+		number_retry,
+		gas_limit,
+		rpc_cache,
+		&GasStrategy::default(),
+		None,
+	)
+	.await
+}
+
+/// Same as `send_transaction_with_cache`, with the fees of the gas strategy.
+/// The transactions not mined in time are replaced with bumped fees if it's configured.
+/// The nonces are assigned by the nonce manager if there is one, else by the provider.
+pub async fn send_transaction_with_strategy<
+	P: Provider<T, Ethereum> + Clone,
+	T: Transport + Clone,
+	D: CallDecoder + Clone,
+>(
+	base_call_builder: CallBuilder<T, &P, D, Ethereum>,
+	signer_address: Address,
+	send_transaction_error_rules: &[Box<dyn VerifyRule>],
+	number_retry: u32,
+	gas_limit: u128,
+	rpc_cache: &RpcCache,
+	gas_strategy: &GasStrategy,
+	nonce_manager: Option<&NonceManager>,
+) -> Result<TransactionReceipt, anyhow::Error> {
+	send_transaction_reporting(
+		base_call_builder,
+		signer_address,
+		send_transaction_error_rules,
+		number_retry,
+		gas_limit,
+		rpc_cache,
+		gas_strategy,
+		nonce_manager,
+		|_| (),
+	)
+	.await
+}
+
+/// Same as `send_transaction_with_strategy`, reporting the hash of each transaction accepted by
+/// the node before waiting for its receipt.
+pub async fn send_transaction_reporting<
+	P: Provider<T, Ethereum> + Clone,
+	T: Transport + Clone,
+	D: CallDecoder + Clone,
+>(
+	base_call_builder: CallBuilder<T, &P, D, Ethereum>,
+	signer_address: Address,
+	send_transaction_error_rules: &[Box<dyn VerifyRule>],
+	number_retry: u32,
+	gas_limit: u128,
+	rpc_cache: &RpcCache,
+	gas_strategy: &GasStrategy,
+	nonce_manager: Option<&NonceManager>,
+	on_sent: impl Fn(B256),
 ) -> Result<TransactionReceipt, anyhow::Error> {
 	info!("base_call_builder: {:?}", base_call_builder);
 	info!("Sending transaction with gas limit: {}", gas_limit);
@@ -98,36 +257,85 @@ pub async fn send_transaction<
 	for _ in 0..number_retry {
 		let call_builder = base_call_builder.clone().gas(estimate_gas);
 
+		// Each attempt leases a new nonce: a mined failed transaction has used its nonce.
+		let nonce_lease = match nonce_manager {
+			Some(nonce_manager) => {
+				Some(nonce_manager.lease(call_builder.provider, signer_address).await?)
+			}
+			None => None,
+		};
+		// A replaced transaction keeps its nonce, so it's set explicitly.
+		let nonce = match &nonce_lease {
+			Some(nonce_lease) => Some(nonce_lease.nonce()),
+			None if gas_strategy.stuck_after().is_some() => {
+				Some(call_builder.provider.get_transaction_count(signer_address).await?)
+			}
+			None => None,
+		};
+		let call_builder = match nonce {
+			Some(nonce) => call_builder.nonce(nonce),
+			None => call_builder,
+		};
+
 		tracing::info!("Eth send_transaction: {:?}", call_builder);
 
 		//detect if the gas price doesn't execeed the limit.
-		let gas_price = call_builder.provider.get_gas_price().await?;
+		let (call_builder, gas_price, fees) = if gas_strategy.is_eip1559() {
+			let fees = gas_strategy
+				.fees(call_builder.provider)
+				.instrument(stage_span(Stage::Simulate))
+				.await?;
+			let call_builder = fees.apply(call_builder);
+			(call_builder, fees.max_fee_per_gas, Some(fees))
+		} else {
+			let gas_price = rpc_cache
+				.get_or_fetch(RpcRead::GasPrice, "", call_builder.provider.get_gas_price())
+				.instrument(stage_span(Stage::Simulate))
+				.await?;
+			(call_builder, gas_price, None)
+		};
 		let transaction_fee_wei = estimate_gas * gas_price;
 		if transaction_fee_wei > gas_limit {
 			return Err(EthUtilError::GasLimitExceed(transaction_fee_wei, gas_limit).into());
 		}
 
 		//send the Transaction and detect send error.
-		let pending_transaction = match call_builder.send().await {
-			Ok(pending_transaction) => pending_transaction,
-			Err(err) => {
-				//apply defined rules.
-				for rule in send_transaction_error_rules {
-					// Verify all rules. If one rule return true or an error stop verification.
-					// If true retry with more gas else return the error.
-					if rule.verify(&err)? {
-						//increase gas of 10% and retry
-						estimate_gas += (estimate_gas * 10) / 100;
-						tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
-						continue;
+		let pending_transaction =
+			match call_builder.clone().send().instrument(stage_span(Stage::Submit)).await {
+				Ok(pending_transaction) => pending_transaction,
+				Err(err) => {
+					//apply defined rules.
+					for rule in send_transaction_error_rules {
+						// Verify all rules. If one rule return true or an error stop verification.
+						// If true retry with more gas else return the error.
+						if rule.verify(&err)? {
+							//increase gas of 10% and retry
+							estimate_gas += (estimate_gas * 10) / 100;
+							tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+							continue;
+						}
 					}
+
+					return Err(EthUtilError::from(err).into());
 				}
+			};
+
+		on_sent(*pending_transaction.tx_hash());
 
-				return Err(EthUtilError::from(err).into());
+		let confirm = stage_span(Stage::Confirm);
+		let receipt = match (fees, gas_strategy.stuck_after()) {
+			(Some(fees), Some(stuck_after)) => {
+				let tx_hash = *pending_transaction.tx_hash();
+				wait_receipt_or_replace(call_builder, tx_hash, fees, gas_strategy, stuck_after)
+					.instrument(confirm)
+					.await
 			}
+			_ => pending_transaction.get_receipt().instrument(confirm).await.map_err(Into::into),
 		};
-
-		match pending_transaction.get_receipt().await {
+		if let (Ok(_), Some(nonce_lease)) = (&receipt, nonce_lease) {
+			nonce_lease.confirm();
+		}
+		match receipt {
 			// Transaction execution fail
 			Ok(transaction_receipt) if !transaction_receipt.status() => {
 				tracing::debug!(
@@ -149,6 +357,7 @@ pub async fn send_transaction<
 				}
 			}
 			Ok(receipt) => return Ok(receipt),
+			Err(err) if is_transport_error(&err) => return Err(err),
 			Err(err) => return Err(EthUtilError::RpcTransactionExecution(err.to_string()).into()),
 		};
 	}
@@ -159,3 +368,50 @@ pub async fn send_transaction<
 	)
 	.into())
 }
+
+// Wait for the receipt of the transaction. Each time it isn't mined after the stuck delay,
+// it's replaced by the same call with the same nonce and bumped fees. The receipt of any
+// of the sent transactions is returned, only one of them can be mined.
+async fn wait_receipt_or_replace<
+	P: Provider<T, Ethereum> + Clone,
+	T: Transport + Clone,
+	D: CallDecoder + Clone,
+>(
+	call_builder: CallBuilder<T, &P, D, Ethereum>,
+	tx_hash: B256,
+	mut fees: GasFees,
+	gas_strategy: &GasStrategy,
+	stuck_after: Duration,
+) -> Result<TransactionReceipt, anyhow::Error> {
+	let mut tx_hashes = vec![tx_hash];
+	let mut sent_at = Instant::now();
+	let mut bumps = 0;
+	loop {
+		for tx_hash in &tx_hashes {
+			if let Some(receipt) = call_builder.provider.get_transaction_receipt(*tx_hash).await? {
+				return Ok(receipt);
+			}
+		}
+		if sent_at.elapsed() >= stuck_after {
+			if bumps == gas_strategy.max_bumps() {
+				return Err(EthUtilError::RpcTransactionExecution(format!(
+					"Transaction not mined after {bumps} replacements"
+				))
+				.into());
+			}
+			bumps += 1;
+			fees = gas_strategy.bump(fees);
+			tracing::warn!(
+				"Transaction {tx_hash} not mined after {stuck_after:?}, replace it with {fees:?}"
+			);
+			// The send fails if one of the previous transactions has been mined meanwhile,
+			// its receipt is read on the next poll.
+			match fees.apply(call_builder.clone()).send().await {
+				Ok(pending_transaction) => tx_hashes.push(*pending_transaction.tx_hash()),
+				Err(err) => tracing::warn!("Failed to replace transaction {tx_hash}: {err}"),
+			}
+			sent_at = Instant::now();
+		}
+		tokio::time::sleep(RECEIPT_POLL_INTERVAL).await;
+	}
+}
@@ -1,3 +1,4 @@
+pub mod connection;
 pub mod ethereum;
 pub mod movement;
 pub use bridge_util::chains::*;
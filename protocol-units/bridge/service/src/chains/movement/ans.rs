@@ -0,0 +1,70 @@
+//! Resolution of the Movement addresses to their primary name in the Aptos Names Service.
+use crate::labels::AddressResolver;
+use serde_json::json;
+
+/// Resolves the 32 bytes addresses with the primary names of the ANS router module
+/// published at the given address.
+pub struct AnsNames {
+	rest_url: String,
+	ans_address: String,
+	client: reqwest::Client,
+}
+
+impl AnsNames {
+	pub fn new(rest_url: String, ans_address: String) -> Self {
+		AnsNames { rest_url, ans_address, client: reqwest::Client::new() }
+	}
+}
+
+#[async_trait::async_trait]
+impl AddressResolver for AnsNames {
+	async fn resolve(&self, address: &[u8]) -> Result<Option<String>, anyhow::Error> {
+		if address.len() != 32 {
+			return Ok(None);
+		}
+		let request = json!({
+			"function": format!("{}::router::get_primary_name", self.ans_address),
+			"type_arguments": [],
+			"arguments": [format!("0x{}", hex::encode(address))],
+		});
+		let response = self
+			.client
+			.post(format!("{}/v1/view", self.rest_url.trim_end_matches('/')))
+			.header("Content-Type", "application/json")
+			.body(request.to_string())
+			.send()
+			.await?
+			.error_for_status()?;
+		let values: serde_json::Value = serde_json::from_str(&response.text().await?)?;
+		Ok(primary_name(&values))
+	}
+}
+
+// The view function returns the optional subdomain and domain of the primary name,
+// each as a Move option: `[{"vec": ["sub"]}, {"vec": ["domain"]}]`.
+fn primary_name(values: &serde_json::Value) -> Option<String> {
+	let part = |index: usize| values[index]["vec"][0].as_str().map(str::to_string);
+	let domain = part(1)?;
+	Some(match part(0) {
+		Some(subdomain) => format!("{subdomain}.{domain}.apt"),
+		None => format!("{domain}.apt"),
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_primary_name() {
+		assert_eq!(
+			primary_name(&json!([{ "vec": [] }, { "vec": ["treasury"] }])),
+			Some("treasury.apt".to_string())
+		);
+		assert_eq!(
+			primary_name(&json!([{ "vec": ["ops"] }, { "vec": ["bridge"] }])),
+			Some("ops.bridge.apt".to_string())
+		);
+		assert_eq!(primary_name(&json!([{ "vec": [] }, { "vec": [] }])), None);
+	}
+}
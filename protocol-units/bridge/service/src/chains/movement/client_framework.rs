@@ -1,24 +1,43 @@
+use super::entry_functions::{
+	AbortBridgeTransfer, BatchScripts, BridgeEntryFunctions, CompleteBridgeTransfer,
+	LockBridgeTransfer,
+};
+use super::prefetch::ResourcePrefetch;
+use super::sequence_numbers::SequenceNumberManager;
 use super::utils::{self, MovementAddress};
+use super::wait;
+use crate::chains::connection::ConnectionBreaker;
+use crate::drill::{DrillFault, FaultInjector};
+use crate::explorer::TxExplorer;
+use crate::fee_tracker::FeeTracker;
+use crate::key_audit::{KeyAudit, MOVEMENT};
+use crate::latency::{stage_span, Stage};
+use crate::metrics::BridgeMetrics;
+use crate::pre_image::PreImageFormat;
+use crate::rpc_metrics::RpcMetrics;
+use crate::submissions::SentTransactions;
 use anyhow::{Context, Result};
-use aptos_api_types::{EntryFunctionId, MoveModuleId, ViewRequest};
+use aptos_api_types::{EntryFunctionId, MoveModuleId, MoveType, ViewRequest};
 use aptos_sdk::{
-	move_types::identifier::Identifier,
-	rest_client::{Client, Response},
-	types::LocalAccount,
+	move_types::{identifier::Identifier, language_storage::TypeTag},
+	rest_client::{aptos_api_types::Transaction as AptosTransaction, Client, Response},
+	types::{transaction::TransactionPayload, LocalAccount},
 };
 use aptos_types::account_address::AccountAddress;
 use bridge_config::common::movement::MovementConfig;
 use bridge_util::{
-	chains::bridge_contracts::{BridgeContract, BridgeContractError, BridgeContractResult},
+	chains::bridge_contracts::{
+		BatchResults, BridgeContract, BridgeContractError, BridgeContractResult,
+	},
 	types::{
 		Amount, BridgeAddress, BridgeTransferDetails, BridgeTransferDetailsCounterparty,
-		BridgeTransferId, HashLock, HashLockPreImage, TimeLock,
+		BridgeTransferId, ChainId, HashLock, HashLockPreImage, LockTransfer, TimeLock,
 	},
 };
 use hex;
 use rand::prelude::*;
 use std::{path::Path, str::FromStr, sync::Arc};
-use tracing::{debug, info};
+use tracing::{debug, info, Instrument};
 use url::Url;
 
 pub const FRAMEWORK_ADDRESS: AccountAddress = AccountAddress::new([
@@ -28,6 +47,9 @@ pub const FRAMEWORK_ADDRESS: AccountAddress = AccountAddress::new([
 pub const INITIATOR_MODULE_NAME: &str = "atomic_bridge_initiator";
 pub const COUNTERPARTY_MODULE_NAME: &str = "atomic_bridge_counterparty";
 const DUMMY_ADDRESS: AccountAddress = AccountAddress::new([0; 32]);
+const ENTRY_FUNCTIONS: BridgeEntryFunctions = BridgeEntryFunctions::new(FRAMEWORK_ADDRESS);
+// Coin of the coin stores of the recipients.
+const APTOS_COIN_TYPE: &str = "0x1::aptos_coin::AptosCoin";
 
 #[allow(dead_code)]
 enum Call {
@@ -46,6 +68,24 @@ pub struct MovementClientFramework {
 	pub rest_client: Client,
 	///The signer account
 	signer: Arc<LocalAccount>,
+	node_connection_url: Url,
+	/// Expected chain id, not verified if 0.
+	chain_id: u8,
+	connection: ConnectionBreaker,
+	connection_generation: u64,
+	faults: FaultInjector,
+	rpc_metrics: RpcMetrics,
+	metrics: BridgeMetrics,
+	pre_image_format: PreImageFormat,
+	tx_explorer: TxExplorer,
+	key_audit: KeyAudit,
+	prefetch: ResourcePrefetch,
+	sequence_numbers: SequenceNumberManager,
+	auto_register_recipients: bool,
+	// Without the scripts, the batches are one transaction per transfer.
+	batch_scripts: Option<Arc<BatchScripts>>,
+	fee_tracker: FeeTracker,
+	sent_transactions: SentTransactions,
 }
 
 impl MovementClientFramework {
@@ -58,7 +98,437 @@ impl MovementClientFramework {
 		let signer =
 			utils::create_local_account(config.movement_signer_key.clone(), &rest_client).await?;
 		let native_address = AccountAddress::from_hex_literal(&config.movement_native_address)?;
-		Ok(MovementClientFramework { native_address, rest_client, signer: Arc::new(signer) })
+		let batch_scripts = match &config.mvt_batch_scripts_path {
+			Some(path) => Some(Arc::new(BatchScripts::load(Path::new(path))?)),
+			None => None,
+		};
+		Ok(MovementClientFramework {
+			native_address,
+			rest_client,
+			signer: Arc::new(signer),
+			node_connection_url,
+			chain_id: config.mvt_chain_id,
+			connection: ConnectionBreaker::new("Movement"),
+			connection_generation: 0,
+			faults: FaultInjector::default(),
+			rpc_metrics: RpcMetrics::default(),
+			metrics: BridgeMetrics::default(),
+			pre_image_format: PreImageFormat::default(),
+			tx_explorer: TxExplorer::default(),
+			key_audit: KeyAudit::default(),
+			prefetch: ResourcePrefetch::default(),
+			sequence_numbers: SequenceNumberManager::default(),
+			auto_register_recipients: config.mvt_auto_register_recipients,
+			batch_scripts,
+			fee_tracker: FeeTracker::default(),
+			sent_transactions: SentTransactions::default(),
+		})
+	}
+
+	/// Establish the REST connection and verify it serves the configured chain,
+	/// so the first transfer doesn't pay the connection cost.
+	pub async fn warmup(&self) -> Result<(), anyhow::Error> {
+		let index = self.rest_client.get_index().await?.into_inner();
+		if self.chain_id != 0 && index.chain_id != self.chain_id {
+			anyhow::bail!(
+				"Movement chain id {} differs from configured chain id {}",
+				index.chain_id,
+				self.chain_id
+			);
+		}
+		info!("Movement connection warmed up, chain id: {}", index.chain_id);
+		Ok(())
+	}
+
+	/// Re-establish the connection if a previous call failed on it.
+	async fn ensure_connection(&mut self) -> BridgeContractResult<()> {
+		let need_reconnect = self.connection.need_reconnect(self.connection_generation)?;
+		if let Err(err) = self.faults.check(DrillFault::RpcFailure, MOVEMENT) {
+			self.connection.record_failure();
+			return Err(err);
+		}
+		if !need_reconnect {
+			return Ok(());
+		}
+		info!("Reconnecting Movement client to {}", self.node_connection_url);
+		let rest_client = Client::new(self.node_connection_url.clone());
+		if let Err(err) = rest_client.get_index().await {
+			self.connection.record_failure();
+			return Err(BridgeContractError::OnChainError(format!(
+				"Failed to reconnect Movement client: {err}"
+			)));
+		}
+		self.rest_client = rest_client;
+		self.connection_generation = self.connection.record_success();
+		Ok(())
+	}
+
+	/// Share the RPC metrics with other clients and the admin API.
+	pub fn set_rpc_metrics(&mut self, rpc_metrics: RpcMetrics) {
+		self.rpc_metrics = rpc_metrics;
+	}
+
+	/// Share the throughput and failure metrics with the other clients and the metrics
+	/// endpoints.
+	pub fn set_bridge_metrics(&mut self, metrics: BridgeMetrics) {
+		self.metrics = metrics;
+	}
+
+	/// Share the faults injected by the failover drills with the other clients.
+	pub fn set_fault_injector(&mut self, faults: FaultInjector) {
+		self.faults = faults;
+	}
+
+	pub fn connection(&self) -> &ConnectionBreaker {
+		&self.connection
+	}
+
+	// A failed RPC call: the connection is re-established before the next one.
+	fn rpc_failed(&self) {
+		self.connection.mark_stale();
+		self.metrics.record_rpc_error(ChainId::TWO);
+	}
+
+	// A failed transaction: only a failure to reach the node breaks the connection, the node
+	// answered the aborts and the sequence number conflicts.
+	fn send_failed(&self, err: &BridgeContractError) {
+		if wait::is_connection_error(err) {
+			self.rpc_failed();
+		}
+	}
+
+	/// Set the format of the preimages of the token pair.
+	pub fn set_pre_image_format(&mut self, pre_image_format: PreImageFormat) {
+		self.pre_image_format = pre_image_format;
+	}
+
+	/// Set the explorer of the transactions logged by the client.
+	pub fn set_tx_explorer(&mut self, tx_explorer: TxExplorer) {
+		self.tx_explorer = tx_explorer;
+	}
+
+	/// Record the signatures of the signer in the audit trail shared with the other client.
+	pub fn set_key_audit(&mut self, key_audit: KeyAudit) {
+		self.key_audit = key_audit;
+	}
+
+	/// Share the fees paid on the transfers with the other client and the status API.
+	pub fn set_fee_tracker(&mut self, fee_tracker: FeeTracker) {
+		self.fee_tracker = fee_tracker;
+	}
+
+	/// Report the transactions sent on the transfers to the outbox of the relayer loop.
+	pub fn set_sent_transactions(&mut self, sent_transactions: SentTransactions) {
+		self.sent_transactions = sent_transactions;
+	}
+
+	// Bytes of the preimage passed to the Move modules, checked against the format.
+	fn move_pre_image(&self, preimage: &HashLockPreImage) -> BridgeContractResult<Vec<u8>> {
+		self.pre_image_format.to_move_bytes(preimage).map_err(|err| {
+			tracing::warn!("Invalid preimage for the {:?} format: {err}", self.pre_image_format);
+			BridgeContractError::ParsePreimageError
+		})
+	}
+
+	/// Sign and send the transaction of the operation, the signature is recorded in the
+	/// key audit. The transactions are pipelined with the sequence numbers of the manager, a
+	/// conflict resyncs it from the account and the transaction is signed again once.
+	async fn send_and_confirm_transaction(
+		&self,
+		operation: &'static str,
+		bridge_transfer_id: Option<BridgeTransferId>,
+		payload: TransactionPayload,
+	) -> BridgeContractResult<AptosTransaction> {
+		self.faults.check(DrillFault::SignerUnavailable, MOVEMENT)?;
+		let send = async {
+			let mut resynced = false;
+			loop {
+				match self.sign_and_submit(operation, bridge_transfer_id, payload.clone()).await {
+					Err(BridgeContractError::SequenceNumberConflict(err)) if !resynced => {
+						tracing::warn!("Sequence number conflict of the signer, resync: {err}");
+						self.sequence_numbers.resync();
+						resynced = true;
+					}
+					result => break result,
+				}
+			}
+		};
+		let transaction = self.rpc_metrics.observe(self.node_connection_url.as_str(), send).await;
+		// The sequence number moved on, or the prefetched one was wrong or conflicted: read it
+		// again.
+		self.prefetch.submitted();
+		let transaction = transaction?;
+		if let Some(fee) = transaction_fee(&transaction) {
+			self.metrics.record_gas_spent(ChainId::TWO, fee);
+			if let Some(bridge_transfer_id) = bridge_transfer_id {
+				self.fee_tracker.record(ChainId::TWO, bridge_transfer_id, fee);
+			}
+		}
+		if let Ok(info) = transaction.transaction_info() {
+			tracing::info!(
+				"Movement transaction executed: {}",
+				self.tx_explorer.display(&info.hash.to_string())
+			);
+		}
+		Ok(transaction)
+	}
+
+	// Share the fee of the batch transaction between its transfers.
+	fn record_batch_fee(&self, transfer_ids: &[BridgeTransferId], transaction: &AptosTransaction) {
+		if let Some(fee) = transaction_fee(transaction) {
+			self.fee_tracker.record_batch(ChainId::TWO, transfer_ids, fee);
+		}
+	}
+
+	// Complete the transfers in a transaction each, the result of each transfer is its own.
+	async fn complete_bridge_transfers_each(
+		&mut self,
+		transfers: Vec<(BridgeTransferId, HashLockPreImage)>,
+	) -> BatchResults {
+		let mut results = Vec::with_capacity(transfers.len());
+		for (bridge_transfer_id, pre_image) in transfers {
+			results
+				.push(self.initiator_complete_bridge_transfer(bridge_transfer_id, pre_image).await);
+		}
+		results
+	}
+
+	// Lock the transfers in a transaction each, the result of each transfer is its own.
+	async fn lock_bridge_transfers_each(
+		&mut self,
+		transfers: Vec<LockTransfer<MovementAddress>>,
+	) -> BatchResults {
+		let mut results = Vec::with_capacity(transfers.len());
+		for transfer in transfers {
+			results.push(
+				self.lock_bridge_transfer(
+					transfer.bridge_transfer_id,
+					transfer.hash_lock,
+					transfer.initiator,
+					transfer.recipient,
+					transfer.amount,
+				)
+				.await,
+			);
+		}
+		results
+	}
+
+	// Sign the payload with the next sequence number of the signer and wait for its
+	// commitment. The sequence number is released unless the transaction is committed.
+	async fn sign_and_submit(
+		&self,
+		operation: &'static str,
+		bridge_transfer_id: Option<BridgeTransferId>,
+		payload: TransactionPayload,
+	) -> BridgeContractResult<AptosTransaction> {
+		let prefetched = self.prefetch.take();
+		let account = async {
+			if let Some((_, sequence_number)) = prefetched {
+				return Ok(sequence_number);
+			}
+			self.rest_client
+				.get_account(self.signer.address())
+				.await
+				.map(|account| account.into_inner().sequence_number)
+				.map_err(|e| {
+					BridgeContractError::OnChainError(format!(
+						"Failed to get account information: {e}"
+					))
+				})
+		};
+		let lease = self
+			.sequence_numbers
+			.lease(account)
+			.instrument(stage_span(Stage::Simulate))
+			.await?;
+		let chain_id = match self.prefetch.chain_id() {
+			Some(chain_id) => chain_id,
+			None => {
+				let chain_id = self
+					.rest_client
+					.get_index()
+					.await
+					.map_err(|e| {
+						BridgeContractError::OnChainError(format!(
+							"Failed in getting chain id: {e}"
+						))
+					})?
+					.into_inner()
+					.chain_id;
+				self.prefetch.store_chain_id(chain_id);
+				chain_id
+			}
+		};
+		let signed_tx = utils::sign_aptos_transaction_with(
+			chain_id,
+			lease.sequence_number(),
+			self.signer.as_ref(),
+			payload,
+		);
+		self.key_audit.record(
+			MOVEMENT,
+			operation,
+			bridge_transfer_id,
+			&signed_tx.committed_hash().to_vec(),
+		);
+		let transaction = async {
+			utils::submit_aptos_transaction(&self.rest_client, &signed_tx).await?;
+			self.sent_transactions.report(
+				ChainId::TWO,
+				operation,
+				bridge_transfer_id,
+				&signed_tx.committed_hash().to_vec(),
+			);
+			utils::confirm_aptos_transaction(&self.rest_client, &signed_tx).await
+		}
+		.await;
+		// A committed transaction used its sequence number, even if its execution failed.
+		if matches!(
+			transaction,
+			Ok(_) | Err(BridgeContractError::OutOfGas | BridgeContractError::MoveAbort(_))
+		) {
+			lease.confirm();
+		}
+		transaction
+	}
+
+	/// Read ahead the chain id and the sequence number of the signer for the next transaction,
+	/// and whether the recipient has a coin store. The failures are only logged, the
+	/// submission then reads what it needs itself.
+	pub async fn prefetch_resources(&self, recipient: Option<AccountAddress>) {
+		let submissions = self.prefetch.submissions();
+		let chain_id = async {
+			if self.prefetch.chain_id().is_some() {
+				return Ok(None);
+			}
+			self.rest_client
+				.get_index()
+				.await
+				.map(|index| Some(index.into_inner().chain_id))
+		};
+		let account = self.rest_client.get_account(self.signer.address());
+		let registered = async {
+			match recipient {
+				Some(recipient) if !self.prefetch.is_registered(&recipient) => self
+					.is_coin_registered(recipient)
+					.await
+					.map(|registered| registered.then_some(recipient)),
+				_ => Ok(None),
+			}
+		};
+		let (chain_id, account, registered) = tokio::join!(chain_id, account, registered);
+		match chain_id {
+			Ok(Some(chain_id)) => self.prefetch.store_chain_id(chain_id),
+			Ok(None) => {}
+			Err(err) => tracing::warn!("Failed to prefetch the Movement chain id: {err}"),
+		}
+		match account {
+			Ok(account) => self
+				.prefetch
+				.store_sequence_number(account.into_inner().sequence_number, submissions),
+			Err(err) => {
+				tracing::warn!("Failed to prefetch the sequence number of the signer: {err}")
+			}
+		}
+		match registered {
+			Ok(Some(recipient)) => self.prefetch.mark_registered(recipient),
+			Ok(None) => {}
+			Err(err) => tracing::warn!("Failed to prefetch the coin store of the recipient: {err}"),
+		}
+	}
+
+	// Whether the account has a coin store of the Movement coin.
+	async fn is_coin_registered(&self, account: AccountAddress) -> Result<bool, anyhow::Error> {
+		let values = utils::send_view_request(
+			self,
+			AccountAddress::ONE.to_hex_literal(),
+			"coin".to_string(),
+			"is_account_registered".to_string(),
+			vec![MoveType::from_str(APTOS_COIN_TYPE)?],
+			vec![serde_json::json!(account.to_hex_literal())],
+		)
+		.await?;
+		values
+			.first()
+			.and_then(|value| value.as_bool())
+			.context("Invalid response of the coin registration view")
+	}
+
+	/// Balance of the coins of the signer, in octas.
+	pub async fn coin_balance(&self) -> Result<u64, anyhow::Error> {
+		let values = utils::send_view_request(
+			self,
+			AccountAddress::ONE.to_hex_literal(),
+			"coin".to_string(),
+			"balance".to_string(),
+			vec![MoveType::from_str(APTOS_COIN_TYPE)?],
+			vec![serde_json::json!(self.signer.address().to_hex_literal())],
+		)
+		.await?;
+		values
+			.first()
+			.and_then(|value| value.as_str())
+			.and_then(|balance| balance.parse().ok())
+			.context("Invalid response of the coin balance view")
+	}
+
+	/// Transfer coins of the signer to the recipient and return the transaction hash.
+	pub async fn transfer_coins(
+		&self,
+		recipient: AccountAddress,
+		amount: u64,
+	) -> BridgeContractResult<String> {
+		let payload = utils::make_aptos_payload(
+			AccountAddress::ONE,
+			"aptos_account",
+			"transfer_coins",
+			vec![TypeTag::from_str(APTOS_COIN_TYPE)
+				.map_err(|_| BridgeContractError::SerializationError)?],
+			vec![utils::serialize_vec(&recipient)?, utils::serialize_u64(&amount)?],
+		);
+		let transaction = self
+			.send_and_confirm_transaction("transfer_coins", None, payload)
+			.await
+			.inspect_err(|err| self.send_failed(err))
+			.map_err(|err| wait::operation_error(err, BridgeContractError::CallError))?;
+		let info = transaction
+			.transaction_info()
+			.map_err(|err| BridgeContractError::OnChainError(err.to_string()))?;
+		Ok(info.hash.to_string())
+	}
+
+	// Register the coin store of the recipient if it has none, so the completion of its
+	// transfer can deposit the coins. The registration is paid by the relayer.
+	async fn register_recipient(&self, recipient: AccountAddress) -> BridgeContractResult<()> {
+		if self.prefetch.is_registered(&recipient) {
+			return Ok(());
+		}
+		let registered = self.is_coin_registered(recipient).await.map_err(|err| {
+			tracing::warn!("Failed to check the coin store of recipient {recipient}: {err}");
+			self.rpc_failed();
+			BridgeContractError::CallError
+		})?;
+		if !registered {
+			info!("Register the coin store of recipient {recipient}");
+			// A transfer of no coin creates the coin store of the recipient.
+			let payload = utils::make_aptos_payload(
+				AccountAddress::ONE,
+				"aptos_account",
+				"transfer_coins",
+				vec![TypeTag::from_str(APTOS_COIN_TYPE)
+					.map_err(|_| BridgeContractError::SerializationError)?],
+				vec![utils::serialize_vec(&recipient)?, utils::serialize_u64(&0)?],
+			);
+			self.send_and_confirm_transaction("register_recipient", None, payload)
+				.await
+				.inspect_err(|err| self.send_failed(err))
+				.map_err(|err| {
+					wait::operation_error(err, BridgeContractError::LockTransferError)
+				})?;
+		}
+		self.prefetch.mark_registered(recipient);
+		Ok(())
 	}
 
 	pub fn rest_client(&self) -> &Client {
@@ -69,6 +539,17 @@ impl MovementClientFramework {
 		&self.signer
 	}
 
+	/// Height of the latest block of the ledger.
+	pub async fn get_block_height(&self) -> Result<u64> {
+		let state = self
+			.rest_client
+			.get_ledger_information()
+			.await
+			.context("Failed to get the ledger information")?
+			.into_inner();
+		Ok(state.block_height)
+	}
+
 	pub async fn initiator_set_timelock(
 		&mut self,
 		time_lock: u64,
@@ -83,9 +564,9 @@ impl MovementClientFramework {
 			args,
 		);
 
-		utils::send_and_confirm_aptos_transaction(&self.rest_client, self.signer.as_ref(), payload)
+		self.send_and_confirm_transaction("set_timelock", None, payload)
 			.await
-			.map_err(|_| BridgeContractError::CallError)?;
+			.map_err(|err| wait::operation_error(err, BridgeContractError::CallError))?;
 
 		Ok(())
 	}
@@ -104,14 +585,24 @@ impl MovementClientFramework {
 			args,
 		);
 
-		utils::send_and_confirm_aptos_transaction(&self.rest_client, self.signer.as_ref(), payload)
+		self.send_and_confirm_transaction("set_timelock", None, payload)
 			.await
-			.map_err(|_| BridgeContractError::CallError)?;
+			.map_err(|err| wait::operation_error(err, BridgeContractError::CallError))?;
 
 		Ok(())
 	}
 }
 
+// Fee paid by the sender of the committed transaction, in octas.
+fn transaction_fee(transaction: &AptosTransaction) -> Option<u128> {
+	let AptosTransaction::UserTransaction(user_txn) = transaction else {
+		return None;
+	};
+	let gas_used = u128::from(u64::from(user_txn.info.gas_used));
+	let gas_unit_price = u128::from(u64::from(user_txn.request.gas_unit_price));
+	Some(gas_used * gas_unit_price)
+}
+
 #[async_trait::async_trait]
 impl BridgeContract<MovementAddress> for MovementClientFramework {
 	async fn initiate_bridge_transfer(
@@ -121,6 +612,7 @@ impl BridgeContract<MovementAddress> for MovementClientFramework {
 		hash_lock: HashLock,
 		amount: Amount,
 	) -> BridgeContractResult<()> {
+		self.ensure_connection().await?;
 		debug!("Amount value: {:?}", amount);
 
 		let serialized_hash_lock = utils::serialize_vec_initiator(&hash_lock.0[..])?;
@@ -139,13 +631,13 @@ impl BridgeContract<MovementAddress> for MovementClientFramework {
 			args,
 		);
 
-		let _ = utils::send_and_confirm_aptos_transaction(
-			&self.rest_client,
-			self.signer.as_ref(),
-			payload,
-		)
-		.await
-		.map_err(|_| BridgeContractError::InitiateTransferError)?;
+		let _ = self
+			.send_and_confirm_transaction("initiate", None, payload)
+			.await
+			.inspect_err(|err| self.send_failed(err))
+			.map_err(|err| {
+				wait::operation_error(err, BridgeContractError::InitiateTransferError)
+			})?;
 
 		Ok(())
 	}
@@ -155,17 +647,11 @@ impl BridgeContract<MovementAddress> for MovementClientFramework {
 		bridge_transfer_id: BridgeTransferId,
 		preimage: HashLockPreImage,
 	) -> BridgeContractResult<()> {
-		let unpadded_preimage = {
-			let mut end = preimage.0.len();
-			while end > 0 && preimage.0[end - 1] == 0 {
-				end -= 1;
-			}
-			&preimage.0[..end]
-		};
-		println!("Unpadded preimage: {:?}", unpadded_preimage);
+		self.ensure_connection().await?;
+		let move_preimage = self.move_pre_image(&preimage)?;
 		let args2 = vec![
 			utils::serialize_vec_initiator(&bridge_transfer_id.0[..])?,
-			utils::serialize_vec_initiator(unpadded_preimage)?,
+			utils::serialize_vec_initiator(&move_preimage)?,
 		];
 
 		let payload = utils::make_aptos_payload(
@@ -176,13 +662,11 @@ impl BridgeContract<MovementAddress> for MovementClientFramework {
 			args2,
 		);
 
-		let _ = utils::send_and_confirm_aptos_transaction(
-			&self.rest_client,
-			self.signer.as_ref(),
-			payload,
-		)
-		.await
-		.map_err(|_| BridgeContractError::CompleteTransferError);
+		let _ = self
+			.send_and_confirm_transaction("complete_initiator", Some(bridge_transfer_id), payload)
+			.await
+			.inspect_err(|err| self.send_failed(err))
+			.map_err(|err| wait::operation_error(err, BridgeContractError::CompleteTransferError));
 
 		Ok(())
 	}
@@ -192,33 +676,21 @@ impl BridgeContract<MovementAddress> for MovementClientFramework {
 		bridge_transfer_id: BridgeTransferId,
 		preimage: HashLockPreImage,
 	) -> BridgeContractResult<()> {
-		let unpadded_preimage = {
-			let mut end = preimage.0.len();
-			while end > 0 && preimage.0[end - 1] == 0 {
-				end -= 1;
-			}
-			&preimage.0[..end]
-		};
-		let args2 = vec![
-			utils::serialize_vec(&bridge_transfer_id.0[..])?,
-			utils::serialize_vec(&unpadded_preimage)?,
-		];
-
-		let payload = utils::make_aptos_payload(
-			FRAMEWORK_ADDRESS,
-			COUNTERPARTY_MODULE_NAME,
-			"complete_bridge_transfer",
-			Vec::new(),
-			args2,
-		);
-
-		let result = utils::send_and_confirm_aptos_transaction(
-			&self.rest_client,
-			self.signer.as_ref(),
-			payload,
-		)
-		.await
-		.map_err(|_| BridgeContractError::CompleteTransferError);
+		self.ensure_connection().await?;
+		let payload = ENTRY_FUNCTIONS.complete_bridge_transfer(&CompleteBridgeTransfer {
+			bridge_transfer_id,
+			pre_image: self.move_pre_image(&preimage)?,
+		})?;
+
+		let result = self
+			.send_and_confirm_transaction(
+				"complete_counterparty",
+				Some(bridge_transfer_id),
+				payload,
+			)
+			.await
+			.inspect_err(|err| self.send_failed(err))
+			.map_err(|err| wait::operation_error(err, BridgeContractError::CompleteTransferError));
 
 		match &result {
 			Ok(tx_result) => {
@@ -240,32 +712,26 @@ impl BridgeContract<MovementAddress> for MovementClientFramework {
 		recipient: BridgeAddress<MovementAddress>,
 		amount: Amount,
 	) -> BridgeContractResult<()> {
+		self.ensure_connection().await?;
 		debug!("Starting lock bridge transfer");
-		debug!("Initiator: {:?}", initiator.0);
-
-		let args = vec![
-			utils::serialize_vec(&initiator.0)?,
-			utils::serialize_vec(&bridge_transfer_id.0[..])?,
-			utils::serialize_vec(&hash_lock.0[..])?,
-			utils::serialize_vec(&recipient.0)?,
-			utils::serialize_u64(&amount)?,
-		];
+		debug!("Initiator: {initiator}");
+		if self.auto_register_recipients {
+			self.register_recipient(recipient.0 .0).await?;
+		}
 
-		let payload = utils::make_aptos_payload(
-			FRAMEWORK_ADDRESS,
-			COUNTERPARTY_MODULE_NAME,
-			"lock_bridge_transfer_assets",
-			Vec::new(),
-			args,
-		);
+		let payload = ENTRY_FUNCTIONS.lock_bridge_transfer(&LockBridgeTransfer {
+			initiator: initiator.0,
+			bridge_transfer_id,
+			hash_lock,
+			recipient: recipient.0,
+			amount,
+		})?;
 
-		let _ = utils::send_and_confirm_aptos_transaction(
-			&self.rest_client,
-			self.signer.as_ref(),
-			payload,
-		)
-		.await
-		.map_err(|_| BridgeContractError::LockTransferError)?;
+		let _ = self
+			.send_and_confirm_transaction("lock", Some(bridge_transfer_id), payload)
+			.await
+			.inspect_err(|err| self.send_failed(err))
+			.map_err(|err| wait::operation_error(err, BridgeContractError::LockTransferError))?;
 
 		Ok(())
 	}
@@ -274,6 +740,7 @@ impl BridgeContract<MovementAddress> for MovementClientFramework {
 		&mut self,
 		bridge_transfer_id: BridgeTransferId,
 	) -> BridgeContractResult<()> {
+		self.ensure_connection().await?;
 		let args = vec![utils::serialize_vec_initiator(&bridge_transfer_id.0[..])?];
 
 		let payload = utils::make_aptos_payload(
@@ -284,9 +751,9 @@ impl BridgeContract<MovementAddress> for MovementClientFramework {
 			args,
 		);
 
-		utils::send_and_confirm_aptos_transaction(&self.rest_client, self.signer.as_ref(), payload)
+		self.send_and_confirm_transaction("refund", Some(bridge_transfer_id), payload)
 			.await
-			.map_err(|err| BridgeContractError::OnChainError(err.to_string()))?;
+			.inspect_err(|err| self.send_failed(err))?;
 
 		Ok(())
 	}
@@ -295,17 +762,13 @@ impl BridgeContract<MovementAddress> for MovementClientFramework {
 		&mut self,
 		bridge_transfer_id: BridgeTransferId,
 	) -> BridgeContractResult<()> {
-		let args3 = vec![utils::serialize_vec(&bridge_transfer_id.0[..])?];
-		let payload = utils::make_aptos_payload(
-			FRAMEWORK_ADDRESS,
-			COUNTERPARTY_MODULE_NAME,
-			"abort_bridge_transfer",
-			Vec::new(),
-			args3,
-		);
-		utils::send_and_confirm_aptos_transaction(&self.rest_client, self.signer.as_ref(), payload)
+		self.ensure_connection().await?;
+		let payload =
+			ENTRY_FUNCTIONS.abort_bridge_transfer(&AbortBridgeTransfer { bridge_transfer_id })?;
+		self.send_and_confirm_transaction("abort", Some(bridge_transfer_id), payload)
 			.await
-			.map_err(|_| BridgeContractError::AbortTransferError)?;
+			.inspect_err(|err| self.send_failed(err))
+			.map_err(|err| wait::operation_error(err, BridgeContractError::AbortTransferError))?;
 		Ok(())
 	}
 
@@ -313,6 +776,7 @@ impl BridgeContract<MovementAddress> for MovementClientFramework {
 		&mut self,
 		bridge_transfer_id: BridgeTransferId,
 	) -> BridgeContractResult<Option<BridgeTransferDetails<MovementAddress>>> {
+		self.ensure_connection().await?;
 		let bridge_transfer_id_hex = format!("0x{}", hex::encode(bridge_transfer_id.0));
 
 		let view_request = ViewRequest {
@@ -334,9 +798,10 @@ impl BridgeContract<MovementAddress> for MovementClientFramework {
 		};
 
 		let response: Response<Vec<serde_json::Value>> = self
-			.rest_client
-			.view(&view_request, None)
+			.rpc_metrics
+			.observe(self.node_connection_url.as_str(), self.rest_client.view(&view_request, None))
 			.await
+			.inspect_err(|_| self.rpc_failed())
 			.map_err(|_| BridgeContractError::CallError)?;
 
 		let values = response.inner();
@@ -399,6 +864,7 @@ impl BridgeContract<MovementAddress> for MovementClientFramework {
 		&mut self,
 		bridge_transfer_id: BridgeTransferId,
 	) -> BridgeContractResult<Option<BridgeTransferDetailsCounterparty<MovementAddress>>> {
+		self.ensure_connection().await?;
 		let bridge_transfer_id_hex = format!("0x{}", hex::encode(bridge_transfer_id.0));
 
 		let view_request = ViewRequest {
@@ -420,9 +886,10 @@ impl BridgeContract<MovementAddress> for MovementClientFramework {
 		};
 
 		let response: Response<Vec<serde_json::Value>> = self
-			.rest_client
-			.view(&view_request, None)
+			.rpc_metrics
+			.observe(self.node_connection_url.as_str(), self.rest_client.view(&view_request, None))
 			.await
+			.inspect_err(|_| self.rpc_failed())
 			.map_err(|_| BridgeContractError::CallError)?;
 
 		let values = response.inner();
@@ -480,6 +947,98 @@ impl BridgeContract<MovementAddress> for MovementClientFramework {
 
 		Ok(Some(details))
 	}
+
+	async fn complete_bridge_transfers_batch(
+		&mut self,
+		transfers: Vec<(BridgeTransferId, HashLockPreImage)>,
+	) -> BridgeContractResult<BatchResults> {
+		let Some(batch_scripts) = self.batch_scripts.clone() else {
+			return Ok(self.complete_bridge_transfers_each(transfers).await);
+		};
+		if transfers.is_empty() {
+			return Ok(Vec::new());
+		}
+		self.ensure_connection().await?;
+		let mut script_transfers = Vec::with_capacity(transfers.len());
+		for (bridge_transfer_id, pre_image) in &transfers {
+			// An invalid pre-image is rejected alone by the transaction of its transfer.
+			let Ok(pre_image) = self.move_pre_image(pre_image) else {
+				return Ok(self.complete_bridge_transfers_each(transfers).await);
+			};
+			script_transfers.push(CompleteBridgeTransfer {
+				bridge_transfer_id: *bridge_transfer_id,
+				pre_image,
+			});
+		}
+		let payload = batch_scripts.complete_bridge_transfers(&script_transfers)?;
+		let transaction = match self
+			.send_and_confirm_transaction("complete_initiator_batch", None, payload)
+			.await
+		{
+			Ok(transaction) => transaction,
+			// The script aborts as a whole on the first failed transfer: each transfer is sent
+			// alone to find which ones fail.
+			Err(BridgeContractError::MoveAbort(abort)) => {
+				tracing::warn!("Completion batch aborted, completing its transfers alone: {abort}");
+				return Ok(self.complete_bridge_transfers_each(transfers).await);
+			}
+			Err(err) => {
+				self.send_failed(&err);
+				return Err(wait::operation_error(err, BridgeContractError::CompleteTransferError));
+			}
+		};
+		let transfer_ids: Vec<_> = transfers.iter().map(|(id, _)| *id).collect();
+		self.record_batch_fee(&transfer_ids, &transaction);
+		// The script has been committed, all the transfers have been completed.
+		Ok(transfer_ids.iter().map(|_| Ok(())).collect())
+	}
+
+	async fn lock_bridge_transfers_batch(
+		&mut self,
+		transfers: Vec<LockTransfer<MovementAddress>>,
+	) -> BridgeContractResult<BatchResults> {
+		let Some(batch_scripts) = self.batch_scripts.clone() else {
+			return Ok(self.lock_bridge_transfers_each(transfers).await);
+		};
+		if transfers.is_empty() {
+			return Ok(Vec::new());
+		}
+		self.ensure_connection().await?;
+		if self.auto_register_recipients {
+			for transfer in &transfers {
+				self.register_recipient(transfer.recipient.0 .0).await?;
+			}
+		}
+		let script_transfers: Vec<_> = transfers
+			.iter()
+			.map(|transfer| LockBridgeTransfer {
+				initiator: transfer.initiator.0.clone(),
+				bridge_transfer_id: transfer.bridge_transfer_id,
+				hash_lock: transfer.hash_lock,
+				recipient: transfer.recipient.0.clone(),
+				amount: transfer.amount,
+			})
+			.collect();
+		let payload = batch_scripts.lock_bridge_transfers(&script_transfers)?;
+		let transaction = match self.send_and_confirm_transaction("lock_batch", None, payload).await
+		{
+			Ok(transaction) => transaction,
+			// The script aborts as a whole on the first failed transfer: each transfer is
+			// sent alone to find which ones fail.
+			Err(BridgeContractError::MoveAbort(abort)) => {
+				tracing::warn!("Lock batch aborted, locking its transfers alone: {abort}");
+				return Ok(self.lock_bridge_transfers_each(transfers).await);
+			}
+			Err(err) => {
+				self.send_failed(&err);
+				return Err(wait::operation_error(err, BridgeContractError::LockTransferError));
+			}
+		};
+		let transfer_ids: Vec<_> =
+			transfers.iter().map(|transfer| transfer.bridge_transfer_id).collect();
+		self.record_batch_fee(&transfer_ids, &transaction);
+		Ok(transfer_ids.iter().map(|_| Ok(())).collect())
+	}
 }
 
 //@TODO: feature flag from here for testing only
@@ -949,6 +1508,22 @@ impl MovementClientFramework {
 				native_address: DUMMY_ADDRESS,
 				rest_client,
 				signer: Arc::new(LocalAccount::generate(&mut rng)),
+				node_connection_url,
+				chain_id: 0,
+				connection: ConnectionBreaker::new("Movement"),
+				connection_generation: 0,
+				faults: FaultInjector::default(),
+				rpc_metrics: RpcMetrics::default(),
+				metrics: BridgeMetrics::default(),
+				pre_image_format: PreImageFormat::default(),
+				tx_explorer: TxExplorer::default(),
+				key_audit: KeyAudit::default(),
+				prefetch: ResourcePrefetch::default(),
+				sequence_numbers: SequenceNumberManager::default(),
+				auto_register_recipients: false,
+				batch_scripts: None,
+				fee_tracker: FeeTracker::default(),
+				sent_transactions: SentTransactions::default(),
 			},
 			child,
 		))
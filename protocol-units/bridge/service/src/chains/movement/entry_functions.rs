@@ -0,0 +1,284 @@
+//! Payloads of the entry functions of the counterparty module, built from typed arguments. Each
+//! field of an argument struct is BCS encoded as one argument of the entry function, in the
+//! order of its Move signature. The batches run the compiled scripts of the bridge modules,
+//! taking the fields of the transfers as vectors.
+use super::client_framework::COUNTERPARTY_MODULE_NAME;
+use super::utils::{self, MovementAddress};
+use anyhow::Context;
+use aptos_sdk::types::{
+	account_address::AccountAddress,
+	transaction::{Script, TransactionArgument, TransactionPayload},
+};
+use bridge_util::chains::bridge_contracts::BridgeContractResult;
+use bridge_util::types::{Amount, BridgeTransferId, HashLock};
+use std::path::Path;
+
+/// Arguments of an entry function.
+pub trait EntryFunctionArgs {
+	/// The BCS encoded arguments, in the order of the Move signature.
+	fn to_args(&self) -> BridgeContractResult<Vec<Vec<u8>>>;
+}
+
+/// Arguments of `lock_bridge_transfer_assets`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockBridgeTransfer {
+	/// Address of the initiator on the other chain.
+	pub initiator: Vec<u8>,
+	pub bridge_transfer_id: BridgeTransferId,
+	pub hash_lock: HashLock,
+	pub recipient: MovementAddress,
+	pub amount: Amount,
+}
+
+impl EntryFunctionArgs for LockBridgeTransfer {
+	fn to_args(&self) -> BridgeContractResult<Vec<Vec<u8>>> {
+		Ok(vec![
+			utils::serialize_vec(&self.initiator)?,
+			utils::serialize_vec(&self.bridge_transfer_id.0[..])?,
+			utils::serialize_vec(&self.hash_lock.0[..])?,
+			utils::serialize_vec(&self.recipient)?,
+			utils::serialize_u64(&self.amount.0)?,
+		])
+	}
+}
+
+/// Arguments of `complete_bridge_transfer`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompleteBridgeTransfer {
+	pub bridge_transfer_id: BridgeTransferId,
+	/// Preimage of the hash lock, in the bytes of the Move modules.
+	pub pre_image: Vec<u8>,
+}
+
+impl EntryFunctionArgs for CompleteBridgeTransfer {
+	fn to_args(&self) -> BridgeContractResult<Vec<Vec<u8>>> {
+		Ok(vec![
+			utils::serialize_vec(&self.bridge_transfer_id.0[..])?,
+			utils::serialize_vec(&self.pre_image)?,
+		])
+	}
+}
+
+/// Arguments of `abort_bridge_transfer`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AbortBridgeTransfer {
+	pub bridge_transfer_id: BridgeTransferId,
+}
+
+impl EntryFunctionArgs for AbortBridgeTransfer {
+	fn to_args(&self) -> BridgeContractResult<Vec<Vec<u8>>> {
+		Ok(vec![utils::serialize_vec(&self.bridge_transfer_id.0[..])?])
+	}
+}
+
+/// Builder of the payloads of the counterparty module published at an address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BridgeEntryFunctions {
+	address: AccountAddress,
+}
+
+impl BridgeEntryFunctions {
+	pub const fn new(address: AccountAddress) -> Self {
+		BridgeEntryFunctions { address }
+	}
+
+	pub fn lock_bridge_transfer(
+		&self,
+		args: &LockBridgeTransfer,
+	) -> BridgeContractResult<TransactionPayload> {
+		self.payload("lock_bridge_transfer_assets", args)
+	}
+
+	pub fn complete_bridge_transfer(
+		&self,
+		args: &CompleteBridgeTransfer,
+	) -> BridgeContractResult<TransactionPayload> {
+		self.payload("complete_bridge_transfer", args)
+	}
+
+	pub fn abort_bridge_transfer(
+		&self,
+		args: &AbortBridgeTransfer,
+	) -> BridgeContractResult<TransactionPayload> {
+		self.payload("abort_bridge_transfer", args)
+	}
+
+	fn payload(
+		&self,
+		function_name: &'static str,
+		args: &impl EntryFunctionArgs,
+	) -> BridgeContractResult<TransactionPayload> {
+		Ok(utils::make_aptos_payload(
+			self.address,
+			COUNTERPARTY_MODULE_NAME,
+			function_name,
+			Vec::new(),
+			args.to_args()?,
+		))
+	}
+}
+
+/// Compiled scripts locking or completing many transfers in one transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchScripts {
+	lock: Vec<u8>,
+	complete: Vec<u8>,
+}
+
+impl BatchScripts {
+	pub fn new(lock: Vec<u8>, complete: Vec<u8>) -> Self {
+		BatchScripts { lock, complete }
+	}
+
+	/// Read the bytecode of the scripts from the directory of the compiled scripts.
+	pub fn load(dir: &Path) -> Result<Self, anyhow::Error> {
+		let read = |name: &str| {
+			let path = dir.join(name);
+			std::fs::read(&path).with_context(|| format!("Failed to read the script {path:?}"))
+		};
+		Ok(BatchScripts::new(
+			read("lock_bridge_transfers.mv")?,
+			read("complete_bridge_transfers.mv")?,
+		))
+	}
+
+	/// The lock of the transfers on the counterparty module.
+	pub fn lock_bridge_transfers(
+		&self,
+		transfers: &[LockBridgeTransfer],
+	) -> BridgeContractResult<TransactionPayload> {
+		let initiators: Vec<_> = transfers.iter().map(|transfer| &transfer.initiator).collect();
+		let ids: Vec<_> = transfers
+			.iter()
+			.map(|transfer| transfer.bridge_transfer_id.0.to_vec())
+			.collect();
+		let hash_locks: Vec<_> =
+			transfers.iter().map(|transfer| transfer.hash_lock.0.to_vec()).collect();
+		let recipients: Vec<_> = transfers.iter().map(|transfer| transfer.recipient.0).collect();
+		let amounts: Vec<_> = transfers.iter().map(|transfer| transfer.amount.0).collect();
+		Ok(script_payload(
+			&self.lock,
+			vec![
+				utils::serialize_vec(&initiators)?,
+				utils::serialize_vec(&ids)?,
+				utils::serialize_vec(&hash_locks)?,
+				utils::serialize_vec(&recipients)?,
+				utils::serialize_vec(&amounts)?,
+			],
+		))
+	}
+
+	/// The completion of the transfers on the initiator module.
+	pub fn complete_bridge_transfers(
+		&self,
+		transfers: &[CompleteBridgeTransfer],
+	) -> BridgeContractResult<TransactionPayload> {
+		let ids: Vec<_> = transfers
+			.iter()
+			.map(|transfer| transfer.bridge_transfer_id.0.to_vec())
+			.collect();
+		let pre_images: Vec<_> = transfers.iter().map(|transfer| &transfer.pre_image).collect();
+		Ok(script_payload(
+			&self.complete,
+			vec![utils::serialize_vec(&ids)?, utils::serialize_vec(&pre_images)?],
+		))
+	}
+}
+
+// The vectors of vectors are passed to the scripts as BCS serialized arguments. The ids and
+// hash locks are vectors in Move, so they're serialized with their length unlike the arrays.
+fn script_payload(code: &[u8], args: Vec<Vec<u8>>) -> TransactionPayload {
+	TransactionPayload::Script(Script::new(
+		code.to_vec(),
+		Vec::new(),
+		args.into_iter().map(TransactionArgument::Serialized).collect(),
+	))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_bridge_entry_functions() {
+		let entry_functions = BridgeEntryFunctions::new(AccountAddress::ONE);
+		let lock = LockBridgeTransfer {
+			initiator: vec![1; 20],
+			bridge_transfer_id: BridgeTransferId([2; 32]),
+			hash_lock: HashLock([3; 32]),
+			recipient: MovementAddress(AccountAddress::new([4; 32])),
+			amount: Amount(5),
+		};
+		let TransactionPayload::EntryFunction(function) =
+			entry_functions.lock_bridge_transfer(&lock).unwrap()
+		else {
+			panic!("The lock is an entry function");
+		};
+		assert_eq!(*function.module().address(), AccountAddress::ONE);
+		assert_eq!(function.module().name().as_str(), COUNTERPARTY_MODULE_NAME);
+		assert_eq!(function.function().as_str(), "lock_bridge_transfer_assets");
+		// The vectors are length prefixed, the address and the amount are not.
+		let args = function.args();
+		assert_eq!(args[0], [vec![20], vec![1; 20]].concat());
+		assert_eq!(args[1], [vec![32], vec![2; 32]].concat());
+		assert_eq!(args[3], vec![4; 32]);
+		assert_eq!(args[4], 5u64.to_le_bytes());
+
+		let abort = AbortBridgeTransfer { bridge_transfer_id: BridgeTransferId([2; 32]) };
+		let TransactionPayload::EntryFunction(function) =
+			entry_functions.abort_bridge_transfer(&abort).unwrap()
+		else {
+			panic!("The abort is an entry function");
+		};
+		assert_eq!(function.function().as_str(), "abort_bridge_transfer");
+		assert_eq!(function.args().to_vec(), abort.to_args().unwrap());
+	}
+
+	#[test]
+	fn test_batch_scripts() {
+		let scripts = BatchScripts::new(vec![1], vec![2]);
+		let lock = LockBridgeTransfer {
+			initiator: vec![1; 20],
+			bridge_transfer_id: BridgeTransferId([2; 32]),
+			hash_lock: HashLock([3; 32]),
+			recipient: MovementAddress(AccountAddress::new([4; 32])),
+			amount: Amount(5),
+		};
+		let TransactionPayload::Script(script) =
+			scripts.lock_bridge_transfers(&[lock.clone(), lock]).unwrap()
+		else {
+			panic!("The batch is a script");
+		};
+		assert_eq!(script.code(), [1]);
+		// Each field is a vector of the fields of the transfers.
+		let TransactionArgument::Serialized(initiators) = &script.args()[0] else {
+			panic!("The initiators are serialized");
+		};
+		let initiators: Vec<Vec<u8>> = bcs::from_bytes(initiators).unwrap();
+		assert_eq!(initiators, [vec![1; 20], vec![1; 20]]);
+		let TransactionArgument::Serialized(ids) = &script.args()[1] else {
+			panic!("The ids are serialized");
+		};
+		assert_eq!(bcs::from_bytes::<Vec<Vec<u8>>>(ids).unwrap(), [vec![2; 32], vec![2; 32]]);
+		let TransactionArgument::Serialized(amounts) = &script.args()[4] else {
+			panic!("The amounts are serialized");
+		};
+		assert_eq!(bcs::from_bytes::<Vec<u64>>(amounts).unwrap(), [5, 5]);
+
+		let complete = CompleteBridgeTransfer {
+			bridge_transfer_id: BridgeTransferId([2; 32]),
+			pre_image: b"secret".to_vec(),
+		};
+		let TransactionPayload::Script(script) =
+			scripts.complete_bridge_transfers(&[complete]).unwrap()
+		else {
+			panic!("The batch is a script");
+		};
+		assert_eq!(script.code(), [2]);
+		let TransactionArgument::Serialized(pre_images) = &script.args()[1] else {
+			panic!("The preimages are serialized");
+		};
+		let pre_images: Vec<Vec<u8>> = bcs::from_bytes(pre_images).unwrap();
+		assert_eq!(pre_images, [b"secret".to_vec()]);
+	}
+}
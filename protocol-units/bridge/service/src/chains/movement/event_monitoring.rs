@@ -2,6 +2,9 @@ use super::{
 	client_framework::{MovementClientFramework, FRAMEWORK_ADDRESS},
 	utils::MovementAddress,
 };
+use crate::catchup::{CatchUpProgress, MOVEMENT_CHAIN};
+use crate::forensics::ScanRange;
+use crate::pre_image::PreImageFormat;
 use crate::{
 	chains::bridge_contracts::{
 		BridgeContractError, BridgeContractEvent, BridgeContractEventType,
@@ -78,6 +81,16 @@ impl MvtPullingState {
 		bridge_config::get_config_path(&dot_movement).join(PULL_STATE_FILE_NAME)
 	}
 
+	// Number of events pulled over all the event handles.
+	fn position(&self) -> u64 {
+		self.initiator_init
+			+ self.initiator_complete
+			+ self.initiator_refund
+			+ self.counterpart_lock
+			+ self.counterpart_complete
+			+ self.counterpart_cancel
+	}
+
 	fn update_state_with_event(
 		&mut self,
 		event: &BridgeContractEvent<MovementAddress>,
@@ -146,8 +159,51 @@ impl BridgeContractMonitoring for MovementMonitoring {
 
 impl MovementMonitoring {
 	pub async fn build(
+		config: &MovementConfig,
+		health_check_rx: mpsc::Receiver<oneshot::Sender<bool>>,
+	) -> Result<Self, anyhow::Error> {
+		Self::build_with_catch_up(
+			config,
+			health_check_rx,
+			CatchUpProgress::default(),
+			PreImageFormat::default(),
+		)
+		.await
+	}
+
+	/// Build the monitoring reporting its catch-up progress. The events are pulled
+	/// from the positions stored at the last run, the monitoring has caught up
+	/// once a pull finds no new event. The preimages of the completed events
+	/// are decoded with the format of the token pair.
+	pub async fn build_with_catch_up(
+		config: &MovementConfig,
+		health_check_rx: mpsc::Receiver<oneshot::Sender<bool>>,
+		catch_up: CatchUpProgress,
+		pre_image_format: PreImageFormat,
+	) -> Result<Self, anyhow::Error> {
+		Self::build_pull(config, health_check_rx, catch_up, pre_image_format, None).await
+	}
+
+	/// Build the monitoring of the events of the range of versions only, for the forensics
+	/// mode. The events are pulled from the first one, the positions of the normal runs
+	/// aren't read nor stored. It has caught up once a pull finds no event up to the end of
+	/// the range.
+	pub async fn build_forensics(
+		config: &MovementConfig,
+		health_check_rx: mpsc::Receiver<oneshot::Sender<bool>>,
+		catch_up: CatchUpProgress,
+		pre_image_format: PreImageFormat,
+		versions: ScanRange,
+	) -> Result<Self, anyhow::Error> {
+		Self::build_pull(config, health_check_rx, catch_up, pre_image_format, Some(versions)).await
+	}
+
+	async fn build_pull(
 		config: &MovementConfig,
 		mut health_check_rx: mpsc::Receiver<oneshot::Sender<bool>>,
+		catch_up: CatchUpProgress,
+		pre_image_format: PreImageFormat,
+		range: Option<ScanRange>,
 	) -> Result<Self, anyhow::Error> {
 		// Spawn a task to forward events to the listener channel
 		let (mut sender, listener) = futures::channel::mpsc::unbounded::<
@@ -155,7 +211,11 @@ impl MovementMonitoring {
 		>();
 
 		//read the pull state
-		let mut pull_state = MvtPullingState::build_from_store_file().await?;
+		let mut pull_state = match range {
+			Some(_) => MvtPullingState::default(),
+			None => MvtPullingState::build_from_store_file().await?,
+		};
+		catch_up.register(MOVEMENT_CHAIN);
 
 		tokio::spawn({
 			let config = config.clone();
@@ -192,6 +252,7 @@ impl MovementMonitoring {
 						&config.mvt_rpc_connection_url(),
 						&pull_state,
 						config.rest_connection_timeout_secs,
+						pre_image_format,
 					)
 					.await
 					{
@@ -200,14 +261,26 @@ impl MovementMonitoring {
 					};
 
 					//extract event sequence_number and update pull state
+					// In forensics mode the events out of the range are skipped, the range
+					// is processed once no event pulled is before its end.
+					let mut range_pending = false;
 					let (event_list, new_pull_state) =
 						init_event_list.drain(..).chain(counterpart_event_list.drain(..)).fold(
 							(Vec::new(), pull_state.clone()),
 							|(mut events, mut state), event| {
 								match event {
-									Ok((ev, seq)) => {
+									Ok((ev, seq, version)) => {
 										state.update_state_with_event(&ev, seq);
-										events.push(Ok(ev));
+										match range {
+											Some(range) if !range.contains(version) => {
+												range_pending |= version < range.to;
+											}
+											Some(_) => {
+												range_pending = true;
+												events.push(Ok(ev));
+											}
+											None => events.push(Ok(ev)),
+										}
 									}
 									Err(err) => {
 										state.update_state_with_error(&err);
@@ -218,6 +291,23 @@ impl MovementMonitoring {
 							},
 						);
 
+					let found_events =
+						event_list.iter().filter(|event| event.is_ok()).count() as u64;
+					let pull_failed = event_list.iter().any(|event| event.is_err());
+					catch_up.record_scan(
+						MOVEMENT_CHAIN,
+						new_pull_state.position(),
+						None,
+						found_events,
+					);
+					let caught_up = match range {
+						Some(_) => !range_pending,
+						None => found_events == 0,
+					};
+					if caught_up && !pull_failed {
+						catch_up.mark_caught_up(MOVEMENT_CHAIN);
+					}
+
 					for event in event_list {
 						if sender.send(event).await.is_err() {
 							tracing::error!("Failed to send event to listener channel");
@@ -226,8 +316,10 @@ impl MovementMonitoring {
 					}
 					pull_state = new_pull_state;
 
-					if let Err(err) = pull_state.save_to_store_file().await {
-						tracing::error!("MVT monitoring unable to store the file state because:{err} for state:{pull_state:?}");
+					if range.is_none() {
+						if let Err(err) = pull_state.save_to_store_file().await {
+							tracing::error!("MVT monitoring unable to store the file state because:{err} for state:{pull_state:?}");
+						}
 					}
 					let _ = tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
 				}
@@ -262,7 +354,7 @@ async fn pool_initiator_contract(
 	rest_url: &str,
 	pull_state: &MvtPullingState,
 	timeout_sec: u64,
-) -> BridgeContractResult<Vec<(BridgeContractEvent<MovementAddress>, u64)>> {
+) -> BridgeContractResult<Vec<(BridgeContractEvent<MovementAddress>, u64, u64)>> {
 	let struct_tag = format!(
 		"{}::atomic_bridge_initiator::BridgeInitiatorEvents",
 		framework_address.to_string()
@@ -281,7 +373,11 @@ async fn pool_initiator_contract(
 	.map(|e| {
 		let data: BridgeInitEventData = serde_json::from_str(&e.data.to_string())?;
 		let transfer_details = BridgeTransferDetails::try_from(data)?;
-		Ok((BridgeContractEvent::Initiated(transfer_details), e.sequence_number.into()))
+		Ok((
+			BridgeContractEvent::Initiated(transfer_details),
+			e.sequence_number.into(),
+			e.version.into(),
+		))
 	})
 	.collect::<Result<Vec<_>>>()
 	.map_err(|e| {
@@ -312,7 +408,7 @@ async fn pool_initiator_contract(
 			))
 			})?,
 		);
-		Ok((event, e.sequence_number.into()))
+		Ok((event, e.sequence_number.into(), e.version.into()))
 	})
 	.collect::<Result<Vec<_>>>()
 	.map_err(|e| {
@@ -341,7 +437,7 @@ async fn pool_initiator_contract(
 				err
 			))
 		})?);
-		Ok((event, e.sequence_number.into()))
+		Ok((event, e.sequence_number.into(), e.version.into()))
 	})
 	.collect::<Result<Vec<_>>>()
 	.map_err(|e| {
@@ -364,7 +460,8 @@ async fn pool_counterparty_contract(
 	rest_url: &str,
 	pull_state: &MvtPullingState,
 	timeout_sec: u64,
-) -> BridgeContractResult<Vec<(BridgeContractEvent<MovementAddress>, u64)>> {
+	pre_image_format: PreImageFormat,
+) -> BridgeContractResult<Vec<(BridgeContractEvent<MovementAddress>, u64, u64)>> {
 	let struct_tag = format!(
 		"{}::atomic_bridge_counterparty::BridgeCounterpartyEvents",
 		FRAMEWORK_ADDRESS.to_string()
@@ -384,8 +481,12 @@ async fn pool_counterparty_contract(
 	.map(|e| {
 		let data: BridgeInitEventData = serde_json::from_str(&e.data.to_string())?;
 		let transfer_details = LockDetails::try_from(data)?;
-		println!("Transfer details: {:?}", transfer_details);
-		Ok((BridgeContractEvent::Locked(transfer_details), e.sequence_number.into()))
+		tracing::debug!("Transfer details: {transfer_details}");
+		Ok((
+			BridgeContractEvent::Locked(transfer_details),
+			e.sequence_number.into(),
+			e.version.into(),
+		))
 	})
 	.collect::<Result<Vec<_>>>()
 	.map_err(|e| {
@@ -415,14 +516,14 @@ async fn pool_counterparty_contract(
 				err
 			))
 			})?,
-			HashLockPreImage(data.pre_image.try_into().map_err(|err| {
+			pre_image_format.from_move_bytes(&data.pre_image).map_err(|err| {
 				BridgeContractError::ConversionFailed(format!(
 				"MVT counterparty bridge_transfer_completed_events pre_image can't be reconstructed:{:?}",
 				err
 			))
-			})?),
+			})?,
 		);
-		Ok((event, e.sequence_number.into()))
+		Ok((event, e.sequence_number.into(), e.version.into()))
 	})
 	.collect::<Result<Vec<_>>>()
 	.map_err(|e| {
@@ -454,7 +555,7 @@ async fn pool_counterparty_contract(
 				err
 			))
 		})?);
-		Ok((event, e.sequence_number.into()))
+		Ok((event, e.sequence_number.into(), e.version.into()))
 	})
 	.collect::<Result<Vec<_>>>()
 	.map_err(|e| {
@@ -1,3 +1,8 @@
+pub mod ans;
 pub mod client_framework;
+pub mod entry_functions;
 pub mod event_monitoring;
+pub mod prefetch;
+pub mod sequence_numbers;
 pub mod utils;
+pub mod wait;
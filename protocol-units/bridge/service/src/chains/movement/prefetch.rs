@@ -0,0 +1,110 @@
+//! Account resources of the Movement submissions read ahead: the chain id and the sequence
+//! number of the signer, and the recipients with a coin store. They are read while the
+//! Ethereum initiations wait for their confirmations, so the lock is signed without a round
+//! trip to the node.
+use aptos_types::account_address::AccountAddress;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Default)]
+struct PrefetchedResources {
+	chain_id: Option<u8>,
+	// Next sequence number of the signer.
+	sequence_number: Option<u64>,
+	// Transactions submitted by the signer, a sequence number read before the last one is
+	// stale.
+	submissions: u64,
+	// Recipients known to have a coin store.
+	registered: HashSet<AccountAddress>,
+}
+
+/// Resources prefetched for the next submissions. Clones share the same resources.
+#[derive(Debug, Clone, Default)]
+pub struct ResourcePrefetch {
+	resources: Arc<Mutex<PrefetchedResources>>,
+}
+
+impl ResourcePrefetch {
+	fn lock(&self) -> std::sync::MutexGuard<'_, PrefetchedResources> {
+		self.resources.lock().expect("Prefetched resources lock poisoned")
+	}
+
+	/// Number of transactions submitted, to read before the sequence number.
+	pub fn submissions(&self) -> u64 {
+		self.lock().submissions
+	}
+
+	pub fn chain_id(&self) -> Option<u8> {
+		self.lock().chain_id
+	}
+
+	pub fn store_chain_id(&self, chain_id: u8) {
+		self.lock().chain_id = Some(chain_id);
+	}
+
+	/// Store the sequence number of the signer read after `submissions` transactions. It's
+	/// dropped if a transaction was submitted since.
+	pub fn store_sequence_number(&self, sequence_number: u64, submissions: u64) {
+		let mut resources = self.lock();
+		if resources.submissions == submissions {
+			resources.sequence_number = Some(sequence_number);
+		}
+	}
+
+	/// Take the chain id and the sequence number of the next transaction, if both were
+	/// prefetched.
+	pub fn take(&self) -> Option<(u8, u64)> {
+		let mut resources = self.lock();
+		let chain_id = resources.chain_id?;
+		resources
+			.sequence_number
+			.take()
+			.map(|sequence_number| (chain_id, sequence_number))
+	}
+
+	/// A transaction was submitted, the sequence number must be read again.
+	pub fn submitted(&self) {
+		let mut resources = self.lock();
+		resources.submissions += 1;
+		resources.sequence_number = None;
+	}
+
+	pub fn is_registered(&self, recipient: &AccountAddress) -> bool {
+		self.lock().registered.contains(recipient)
+	}
+
+	pub fn mark_registered(&self, recipient: AccountAddress) {
+		self.lock().registered.insert(recipient);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_resource_prefetch() {
+		let prefetch = ResourcePrefetch::default();
+		let submissions = prefetch.submissions();
+		prefetch.store_sequence_number(7, submissions);
+		// The chain id is needed to sign.
+		assert_eq!(prefetch.take(), None);
+		prefetch.store_chain_id(4);
+		prefetch.store_sequence_number(7, submissions);
+		assert_eq!(prefetch.clone().take(), Some((4, 7)));
+		assert_eq!(prefetch.take(), None);
+
+		// A sequence number read while a transaction was submitted is stale.
+		let submissions = prefetch.submissions();
+		prefetch.submitted();
+		prefetch.store_sequence_number(8, submissions);
+		assert_eq!(prefetch.take(), None);
+		prefetch.store_sequence_number(9, prefetch.submissions());
+		assert_eq!(prefetch.take(), Some((4, 9)));
+
+		let recipient = AccountAddress::new([2; 32]);
+		assert!(!prefetch.is_registered(&recipient));
+		prefetch.mark_registered(recipient);
+		assert!(prefetch.is_registered(&recipient));
+	}
+}
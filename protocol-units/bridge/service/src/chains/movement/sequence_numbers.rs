@@ -0,0 +1,156 @@
+//! Sequence numbers of the transactions of the relayer account submitted concurrently by the
+//! clones of the client. Reading the sequence number of the account for each transaction gives
+//! the same one to the transactions pipelined before the first is committed, and all but one
+//! fail with `SEQUENCE_NUMBER_TOO_OLD`. The sequence numbers submitted and not yet committed
+//! are tracked locally, and the next one is read again from the account when a transaction
+//! fails before its commitment, i.e. its sequence number may be a gap.
+use bridge_util::chains::bridge_contracts::BridgeContractResult;
+use std::collections::BTreeSet;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Default)]
+struct SequenceState {
+	// None when the next sequence number must be read from the account.
+	next: Option<u64>,
+	// Sequence numbers of the transactions submitted and not yet committed.
+	in_flight: BTreeSet<u64>,
+}
+
+impl SequenceState {
+	// The next sequence number not in flight, from the account if it was read.
+	fn assign(&mut self, on_chain: Option<u64>) -> u64 {
+		if let Some(on_chain) = on_chain {
+			// The transactions of the lower sequence numbers are committed.
+			self.in_flight.retain(|sequence_number| *sequence_number >= on_chain);
+			self.next = Some(on_chain);
+		}
+		let mut sequence_number = self.next.unwrap_or_default();
+		while self.in_flight.contains(&sequence_number) {
+			sequence_number += 1;
+		}
+		self.in_flight.insert(sequence_number);
+		self.next = Some(sequence_number + 1);
+		sequence_number
+	}
+}
+
+/// Assigns the sequence numbers of the signer. Clones share the same sequence numbers.
+#[derive(Debug, Clone, Default)]
+pub struct SequenceNumberManager {
+	state: Arc<Mutex<SequenceState>>,
+}
+
+impl SequenceNumberManager {
+	fn lock(&self) -> std::sync::MutexGuard<'_, SequenceState> {
+		self.state.lock().expect("Sequence number manager lock poisoned")
+	}
+
+	/// Lease the next sequence number of the signer, the sequence number of the account is
+	/// only read by `on_chain` after a resync. The sequence number is released when the lease
+	/// is dropped, unless it's confirmed once its transaction is committed.
+	pub async fn lease(
+		&self,
+		on_chain: impl Future<Output = BridgeContractResult<u64>>,
+	) -> BridgeContractResult<SequenceNumberLease> {
+		let on_chain = match self.lock().next {
+			Some(_) => None,
+			None => Some(on_chain.await?),
+		};
+		// Concurrent leases may have read the same one, the sequence numbers in flight are
+		// skipped.
+		let sequence_number = self.lock().assign(on_chain);
+		Ok(SequenceNumberLease { manager: self.clone(), sequence_number, confirmed: false })
+	}
+
+	/// Read the sequence number from the account for the next lease.
+	pub fn resync(&self) {
+		self.lock().next = None;
+	}
+}
+
+/// A sequence number assigned by the `SequenceNumberManager` to a transaction.
+#[derive(Debug)]
+pub struct SequenceNumberLease {
+	manager: SequenceNumberManager,
+	sequence_number: u64,
+	confirmed: bool,
+}
+
+impl SequenceNumberLease {
+	pub fn sequence_number(&self) -> u64 {
+		self.sequence_number
+	}
+
+	/// The transaction of the sequence number is committed, successfully or not.
+	pub fn confirm(mut self) {
+		self.manager.lock().in_flight.remove(&self.sequence_number);
+		self.confirmed = true;
+	}
+}
+
+impl Drop for SequenceNumberLease {
+	fn drop(&mut self) {
+		if !self.confirmed {
+			// The transaction may not have been committed: it's a gap to fill.
+			let mut state = self.manager.lock();
+			state.in_flight.remove(&self.sequence_number);
+			state.next = None;
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bridge_util::chains::bridge_contracts::BridgeContractError;
+	use std::sync::atomic::{AtomicU64, Ordering};
+
+	#[tokio::test]
+	async fn test_sequence_number_manager() {
+		let manager = SequenceNumberManager::default();
+		let reads = AtomicU64::new(0);
+		let lease = |on_chain: u64| {
+			let manager = manager.clone();
+			let reads = &reads;
+			async move {
+				manager
+					.lease(async {
+						reads.fetch_add(1, Ordering::SeqCst);
+						Ok(on_chain)
+					})
+					.await
+					.unwrap()
+			}
+		};
+
+		// Pipelined transactions get consecutive sequence numbers from a single read.
+		let first = lease(5).await;
+		let second = lease(5).await;
+		let third = lease(5).await;
+		assert_eq!(
+			(first.sequence_number(), second.sequence_number(), third.sequence_number()),
+			(5, 6, 7)
+		);
+		assert_eq!(reads.load(Ordering::SeqCst), 1);
+		first.confirm();
+		third.confirm();
+
+		// The second expired: the account is read again and the gap filled.
+		drop(second);
+		assert_eq!(lease(6).await.sequence_number(), 6);
+		assert_eq!(reads.load(Ordering::SeqCst), 2);
+
+		// The failure of the read fails the lease.
+		manager.resync();
+		let failed = manager.lease(async { Err(BridgeContractError::CallError) }).await;
+		assert_eq!(failed.unwrap_err(), BridgeContractError::CallError);
+
+		// The sequence numbers still in flight are skipped after a read.
+		let mut state = SequenceState::default();
+		assert_eq!(state.assign(Some(3)), 3);
+		assert_eq!(state.assign(None), 4);
+		assert_eq!(state.assign(Some(3)), 5);
+		assert_eq!(state.assign(Some(5)), 6);
+	}
+}
@@ -11,6 +11,7 @@ use aptos_sdk::{
 			EntryFunctionId, MoveType, Transaction as AptosTransaction, TransactionInfo,
 			ViewRequest,
 		},
+		error::RestError,
 		Client as RestClient, FaucetClient, Transaction,
 	},
 	transaction_builder::TransactionFactory,
@@ -34,9 +35,12 @@ use std::str::FromStr;
 use thiserror::Error;
 use tiny_keccak::{Hasher, Keccak};
 use tracing::log::{error, info};
+use tracing::Instrument;
 use url::Url;
 
 use super::client_framework::MovementClientFramework;
+use super::wait;
+use crate::latency::{stage_span, Stage};
 pub type TestRng = StdRng;
 
 const MOVEMENT_RPC_URL: &str = "https://testnet.bardock.movementnetwork.xyz";
@@ -169,17 +173,27 @@ pub async fn send_and_confirm_aptos_transaction(
 	rest_client: &RestClient,
 	signer: &LocalAccount,
 	payload: TransactionPayload,
-) -> Result<AptosTransaction, String> {
+) -> Result<AptosTransaction, BridgeContractError> {
 	info!("Starting send_aptos_transaction");
+	let signed_tx = sign_aptos_transaction(rest_client, signer, payload)
+		.await
+		.map_err(BridgeContractError::OnChainError)?;
+	submit_and_confirm_aptos_transaction(rest_client, &signed_tx).await
+}
+
+/// Build the transaction of the payload with the next sequence number of the signer,
+/// and sign it.
+pub async fn sign_aptos_transaction(
+	rest_client: &RestClient,
+	signer: &LocalAccount,
+	payload: TransactionPayload,
+) -> Result<SignedTransaction, String> {
 	let state = rest_client
 		.get_ledger_information()
 		.await
 		.map_err(|e| format!("Failed in getting chain id: {}", e))?
 		.into_inner();
 
-	let transaction_factory = TransactionFactory::new(ChainId::new(state.chain_id))
-		.with_gas_unit_price(100)
-		.with_max_gas_amount(GAS_UNIT_LIMIT);
 	let latest_account_info = rest_client
 		.get_account(signer.address())
 		.await
@@ -187,39 +201,68 @@ pub async fn send_and_confirm_aptos_transaction(
 	let account = latest_account_info.into_inner();
 	let latest_sequence_number = account.sequence_number;
 
+	Ok(sign_aptos_transaction_with(state.chain_id, latest_sequence_number, signer, payload))
+}
+
+/// Build the transaction of the payload with the chain id and sequence number already known,
+/// and sign it.
+pub fn sign_aptos_transaction_with(
+	chain_id: u8,
+	sequence_number: u64,
+	signer: &LocalAccount,
+	payload: TransactionPayload,
+) -> SignedTransaction {
+	let transaction_factory = TransactionFactory::new(ChainId::new(chain_id))
+		.with_gas_unit_price(100)
+		.with_max_gas_amount(GAS_UNIT_LIMIT);
 	let raw_tx = transaction_factory
 		.payload(payload)
 		.sender(signer.address())
-		.sequence_number(latest_sequence_number)
+		.sequence_number(sequence_number)
 		.build();
+	signer.sign_transaction(raw_tx)
+}
 
-	let signed_tx = signer.sign_transaction(raw_tx);
-
-	//info!("Signed TX: {:?}", signed_tx);
-
-	let response = rest_client.submit_and_wait(&signed_tx).await.map_err(|e| {
-		let err_msg = format!("Transaction submission error: {}", e.to_string());
-		error!("Full error: {}", err_msg); // Log the error in detail
-		err_msg
-	})?;
-
-	let txn = response.into_inner();
-	//info!("Response: {:?}", txn);
+/// Submit the signed transaction and wait for its successful execution.
+pub async fn submit_and_confirm_aptos_transaction(
+	rest_client: &RestClient,
+	signed_tx: &SignedTransaction,
+) -> Result<AptosTransaction, BridgeContractError> {
+	submit_aptos_transaction(rest_client, signed_tx).await?;
+	confirm_aptos_transaction(rest_client, signed_tx).await
+}
 
-	match &txn {
-		Transaction::UserTransaction(user_txn) => {
-			if !user_txn.info.success {
-				return Err(format!("Transaction failed with status: {}", user_txn.info.vm_status));
+/// Submit the signed transaction to the node.
+pub async fn submit_aptos_transaction(
+	rest_client: &RestClient,
+	signed_tx: &SignedTransaction,
+) -> Result<(), BridgeContractError> {
+	rest_client
+		.submit(signed_tx)
+		.instrument(stage_span(Stage::Submit))
+		.await
+		.map_err(|e| {
+			error!("Transaction submission error: {}", e);
+			match e {
+				// The node rejected the transaction.
+				RestError::Api(_) => wait::decode_submission_error(&e.to_string()),
+				e => BridgeContractError::OnChainError(format!(
+					"Failed to submit the transaction: {e}"
+				)),
 			}
-		}
-		_ => {
-			return Err(
-				"Expected a UserTransaction, but got a different transaction type.".to_string()
-			)
-		}
-	}
+		})?;
+	Ok(())
+}
 
-	Ok(txn)
+/// Wait for the successful execution of the submitted transaction.
+pub async fn confirm_aptos_transaction(
+	rest_client: &RestClient,
+	signed_tx: &SignedTransaction,
+) -> Result<AptosTransaction, BridgeContractError> {
+	wait::wait_for_transaction(rest_client, signed_tx)
+		.instrument(stage_span(Stage::Confirm))
+		.await
+		.inspect_err(|e| error!("Transaction not executed: {}", e))
 }
 
 pub fn extract_bridge_transfer_id(txn: Transaction) -> Option<String> {
@@ -0,0 +1,184 @@
+//! Wait for the commitment of the submitted transactions. The transaction is polled by hash
+//! until it's committed, or until the ledger passes its expiration while it's still pending or
+//! dropped by the mempool. The failures of the submission and of the execution are decoded
+//! into `BridgeContractError` variants, so the callers know whether to retry or to give up.
+use aptos_api_types::AptosErrorCode;
+use aptos_sdk::rest_client::{
+	aptos_api_types::Transaction as AptosTransaction, error::RestError, Client as RestClient,
+};
+use aptos_sdk::types::transaction::SignedTransaction;
+use bridge_util::chains::bridge_contracts::{BridgeContractError, BridgeContractResult};
+use std::time::Duration;
+
+// Delay between the reads of a pending transaction.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// Prefixes of the failures answered by the node that aren't decoded further.
+const EXECUTION_FAILURE: &str = "Transaction failed with status";
+const SUBMISSION_FAILURE: &str = "Transaction submission error";
+
+/// Poll the transaction until it's committed, successfully or not, or expired.
+pub async fn wait_for_transaction(
+	rest_client: &RestClient,
+	signed_tx: &SignedTransaction,
+) -> BridgeContractResult<AptosTransaction> {
+	let hash = signed_tx.committed_hash();
+	let expiration_secs = signed_tx.expiration_timestamp_secs();
+	loop {
+		let ledger_timestamp_usecs = match rest_client.get_transaction_by_hash(hash).await {
+			Ok(response) => {
+				let ledger_timestamp_usecs = response.state().timestamp_usecs;
+				match response.into_inner() {
+					AptosTransaction::PendingTransaction(_) => ledger_timestamp_usecs,
+					transaction => return check_execution(transaction),
+				}
+			}
+			// Not in the mempool yet, or dropped by it: only the ledger time tells.
+			Err(RestError::Api(err))
+				if err.error.error_code == AptosErrorCode::TransactionNotFound =>
+			{
+				rest_client
+					.get_ledger_information()
+					.await
+					.map_err(|err| BridgeContractError::OnChainError(err.to_string()))?
+					.into_inner()
+					.timestamp_usecs
+			}
+			Err(err) => return Err(BridgeContractError::OnChainError(err.to_string())),
+		};
+		if is_expired(ledger_timestamp_usecs, expiration_secs) {
+			return Err(BridgeContractError::TransactionExpired);
+		}
+		tokio::time::sleep(POLL_INTERVAL).await;
+	}
+}
+
+/// The transaction can't be committed anymore once the ledger is past its expiration.
+fn is_expired(ledger_timestamp_usecs: u64, expiration_secs: u64) -> bool {
+	ledger_timestamp_usecs / 1_000_000 > expiration_secs
+}
+
+/// Check that the committed transaction executed successfully.
+pub fn check_execution(transaction: AptosTransaction) -> BridgeContractResult<AptosTransaction> {
+	match &transaction {
+		AptosTransaction::UserTransaction(user_txn) if !user_txn.info.success => {
+			Err(decode_vm_status(&user_txn.info.vm_status))
+		}
+		AptosTransaction::UserTransaction(_) => Ok(transaction),
+		_ => Err(BridgeContractError::OnChainError(
+			"Expected a UserTransaction, but got a different transaction type.".to_string(),
+		)),
+	}
+}
+
+/// Decode the VM status of a failed execution.
+pub fn decode_vm_status(vm_status: &str) -> BridgeContractError {
+	if vm_status.starts_with("Move abort") {
+		BridgeContractError::MoveAbort(vm_status.to_string())
+	} else if vm_status.eq_ignore_ascii_case("out of gas") || vm_status.contains("OUT_OF_GAS") {
+		BridgeContractError::OutOfGas
+	} else {
+		BridgeContractError::OnChainError(format!("{EXECUTION_FAILURE}: {vm_status}"))
+	}
+}
+
+/// Decode the rejection of a submission by the node.
+pub fn decode_submission_error(message: &str) -> BridgeContractError {
+	if message.contains("SEQUENCE_NUMBER_TOO_OLD")
+		|| message.contains("SEQUENCE_NUMBER_TOO_NEW")
+		|| message.contains("already in mempool")
+	{
+		BridgeContractError::SequenceNumberConflict(message.to_string())
+	} else if message.contains("TRANSACTION_EXPIRED") {
+		BridgeContractError::TransactionExpired
+	} else if message.contains("INSUFFICIENT_BALANCE_FOR_TRANSACTION_FEE") {
+		BridgeContractError::AccountBalanceError
+	} else {
+		BridgeContractError::OnChainError(format!("{SUBMISSION_FAILURE}: {message}"))
+	}
+}
+
+/// Whether the failure of a transaction may come from the connection to the node. The failures
+/// answered by the node, like the aborts or the sequence number conflicts, don't.
+pub fn is_connection_error(err: &BridgeContractError) -> bool {
+	match err {
+		BridgeContractError::OnChainError(message) => {
+			!message.starts_with(EXECUTION_FAILURE) && !message.starts_with(SUBMISSION_FAILURE)
+		}
+		_ => false,
+	}
+}
+
+/// The error of a failed operation: the decoded failures of its transaction are kept, the
+/// others are replaced by the error of the operation.
+pub fn operation_error(
+	err: BridgeContractError,
+	operation_error: BridgeContractError,
+) -> BridgeContractError {
+	match err {
+		BridgeContractError::TransactionExpired
+		| BridgeContractError::SequenceNumberConflict(_)
+		| BridgeContractError::OutOfGas
+		| BridgeContractError::MoveAbort(_)
+		| BridgeContractError::AccountBalanceError => err,
+		_ => operation_error,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bridge_util::chains::bridge_contracts::BridgeContractErrorKind;
+
+	#[test]
+	fn test_decode_transaction_failures() {
+		let abort = "Move abort in 0x1::atomic_bridge_counterparty: EINVALID_PRE_IMAGE(0x10002)";
+		assert_eq!(decode_vm_status(abort), BridgeContractError::MoveAbort(abort.to_string()));
+		assert_eq!(decode_vm_status("Out of gas"), BridgeContractError::OutOfGas);
+		assert_eq!(
+			decode_vm_status("Miscellaneous error").kind(),
+			BridgeContractErrorKind::OnChainError
+		);
+
+		let too_old = "Invalid transaction: Type: Validation Code: SEQUENCE_NUMBER_TOO_OLD";
+		assert_eq!(
+			decode_submission_error(too_old),
+			BridgeContractError::SequenceNumberConflict(too_old.to_string())
+		);
+		assert_eq!(
+			decode_submission_error("Validation Code: TRANSACTION_EXPIRED"),
+			BridgeContractError::TransactionExpired
+		);
+		assert_eq!(
+			decode_submission_error("Code: INSUFFICIENT_BALANCE_FOR_TRANSACTION_FEE"),
+			BridgeContractError::AccountBalanceError
+		);
+		// The conflicts and expirations are retried with a new sequence number, the aborts not.
+		assert!(decode_submission_error(too_old).is_retryable());
+		assert!(!decode_vm_status(abort).is_retryable());
+
+		// Only the failures to reach the node break the connection.
+		assert!(!is_connection_error(&decode_vm_status(abort)));
+		assert!(!is_connection_error(&decode_vm_status("Miscellaneous error")));
+		assert!(!is_connection_error(&decode_submission_error(too_old)));
+		assert!(!is_connection_error(&decode_submission_error("Code: INVALID_SIGNATURE")));
+		assert!(is_connection_error(&BridgeContractError::OnChainError(
+			"Failed to submit the transaction: error sending request".to_string()
+		)));
+
+		assert!(!is_expired(30_000_000, 30));
+		assert!(is_expired(31_000_000, 30));
+
+		assert_eq!(
+			operation_error(BridgeContractError::OutOfGas, BridgeContractError::LockTransferError),
+			BridgeContractError::OutOfGas
+		);
+		assert_eq!(
+			operation_error(
+				BridgeContractError::OnChainError("timeout".to_string()),
+				BridgeContractError::LockTransferError
+			),
+			BridgeContractError::LockTransferError
+		);
+	}
+}
@@ -0,0 +1,340 @@
+//! Circuit breaker of the relayer: an anomaly pauses all the transfers, so the locks on the
+//! counterparty chains are held until an operator resets the breaker. The anomalies are a
+//! spike of initiations, more locked on a chain than initiated on the other, and transfers
+//! whose events are not found by the RPC of their chain.
+use crate::event_bus::{BusEvent, Subscription};
+use crate::metrics::chain_label;
+use crate::pause::{PauseRequest, PauseScope, PauseSwitches};
+use bridge_config::common::circuit_breaker::CircuitBreakerConfig;
+use bridge_util::chains::bridge_contracts::BridgeContractEvent;
+use bridge_util::types::ChainId;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Operator of the pauses of the breaker.
+const BREAKER_OPERATOR: &str = "circuit-breaker";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Anomaly {
+	InitiationSpike,
+	TotalsMismatch,
+	RpcDivergence,
+}
+
+/// Why and when the breaker was tripped.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BreakerTrip {
+	/// Missing when tripped by an operator.
+	pub anomaly: Option<Anomaly>,
+	pub reason: String,
+	pub operator: String,
+	pub tripped_at_secs: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BreakerStatus {
+	pub trip: Option<BreakerTrip>,
+	pub initiations_in_window: u64,
+	pub rpc_divergences_in_window: u64,
+	/// Amount locked beyond the amount initiated since the start, by initiation chain.
+	pub locked_excess: BTreeMap<&'static str, u128>,
+}
+
+/// Trip or reset request posted by an operator on the admin API.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BreakerRequest {
+	pub tripped: bool,
+	/// The authenticated operator, never read from the payload.
+	#[serde(skip_deserializing)]
+	pub operator: String,
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+	// Times of the initiations and of the RPC divergences in the window, oldest first.
+	initiations: VecDeque<u64>,
+	rpc_divergences: VecDeque<u64>,
+	// Amounts initiated and locked since the start, by initiation chain.
+	initiated: BTreeMap<&'static str, u128>,
+	locked: BTreeMap<&'static str, u128>,
+	trip: Option<BreakerTrip>,
+}
+
+impl BreakerState {
+	fn expire(&mut self, window_start: u64) {
+		for times in [&mut self.initiations, &mut self.rpc_divergences] {
+			while times.front().is_some_and(|at| *at <= window_start) {
+				times.pop_front();
+			}
+		}
+	}
+
+	fn locked_excess(&self, chain: &'static str) -> u128 {
+		let initiated = self.initiated.get(chain).copied().unwrap_or_default();
+		self.locked.get(chain).copied().unwrap_or_default().saturating_sub(initiated)
+	}
+}
+
+/// Watches the anomalies and pauses the bridge through the pause switches when one exceeds
+/// its threshold. Clones share the same state.
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+	config: CircuitBreakerConfig,
+	pause_switches: PauseSwitches,
+	state: Arc<Mutex<BreakerState>>,
+}
+
+impl CircuitBreaker {
+	pub fn new(config: &CircuitBreakerConfig, pause_switches: PauseSwitches) -> Self {
+		CircuitBreaker { config: config.clone(), pause_switches, state: Arc::default() }
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<'_, BreakerState> {
+		self.state.lock().expect("Circuit breaker lock poisoned")
+	}
+
+	/// Watch the contract events of the subscription until the bus is dropped.
+	pub async fn run(self, mut events: Subscription) {
+		while let Some(event) = events.recv().await {
+			self.observe(&event, now_secs());
+		}
+	}
+
+	fn observe(&self, event: &BusEvent, now_secs: u64) {
+		let BusEvent::Contract(event) = event else {
+			return;
+		};
+		let mut state = self.lock();
+		state.expire(now_secs.saturating_sub(self.config.window_secs));
+		match &event.contract_event {
+			BridgeContractEvent::Initiated(details) => {
+				state.initiations.push_back(now_secs);
+				*state.initiated.entry(chain_label(event.chain)).or_default() +=
+					u128::from(details.amount.0);
+				let initiations = state.initiations.len() as u64;
+				if initiations > self.config.max_initiations {
+					let reason = format!(
+						"{initiations} initiations in {}s, at most {}",
+						self.config.window_secs, self.config.max_initiations
+					);
+					self.trip(&mut state, Anomaly::InitiationSpike, reason, now_secs);
+				}
+			}
+			BridgeContractEvent::Locked(details) => {
+				let init_chain = chain_label(event.chain.other());
+				*state.locked.entry(init_chain).or_default() += u128::from(details.amount.0);
+				let excess = state.locked_excess(init_chain);
+				if excess > u128::from(self.config.max_locked_excess) {
+					let reason = format!(
+						"{excess} more locked for {init_chain} than initiated, at most {}",
+						self.config.max_locked_excess
+					);
+					self.trip(&mut state, Anomaly::TotalsMismatch, reason, now_secs);
+				}
+			}
+			_ => (),
+		}
+	}
+
+	/// Count a transfer whose event wasn't found by the RPC of the chain.
+	pub fn record_rpc_divergence(&self, chain: ChainId) {
+		self.record_rpc_divergence_at(chain, now_secs());
+	}
+
+	fn record_rpc_divergence_at(&self, chain: ChainId, now_secs: u64) {
+		let mut state = self.lock();
+		state.expire(now_secs.saturating_sub(self.config.window_secs));
+		state.rpc_divergences.push_back(now_secs);
+		let divergences = state.rpc_divergences.len() as u64;
+		if divergences > self.config.max_rpc_divergences {
+			let reason = format!(
+				"{divergences} transfers not found by the RPC in {}s, last on {chain}",
+				self.config.window_secs
+			);
+			self.trip(&mut state, Anomaly::RpcDivergence, reason, now_secs);
+		}
+	}
+
+	// Pause the bridge, unless the breaker is already tripped.
+	fn trip(&self, state: &mut BreakerState, anomaly: Anomaly, reason: String, now_secs: u64) {
+		if state.trip.is_some() {
+			return;
+		}
+		tracing::error!(target: "bridge_alert", "Circuit breaker tripped: {reason}");
+		self.pause_switches
+			.apply(Self::pause_request(true, BREAKER_OPERATOR.to_string()));
+		state.trip = Some(BreakerTrip {
+			anomaly: Some(anomaly),
+			reason,
+			operator: BREAKER_OPERATOR.to_string(),
+			tripped_at_secs: now_secs,
+		});
+	}
+
+	/// Trip or reset the breaker on the request of an operator. The reset resumes all the
+	/// transfers and acknowledges the anomalies seen so far.
+	pub fn apply(&self, request: BreakerRequest) {
+		let mut state = self.lock();
+		let now_secs = now_secs();
+		if request.tripped {
+			if state.trip.is_none() {
+				state.trip = Some(BreakerTrip {
+					anomaly: None,
+					reason: "tripped by an operator".to_string(),
+					operator: request.operator.clone(),
+					tripped_at_secs: now_secs,
+				});
+			}
+		} else {
+			state.trip = None;
+			state.initiations.clear();
+			state.rpc_divergences.clear();
+			let BreakerState { initiated, locked, .. } = &mut *state;
+			for (chain, locked) in locked.iter() {
+				let initiated = initiated.entry(*chain).or_default();
+				*initiated = (*initiated).max(*locked);
+			}
+		}
+		self.pause_switches
+			.apply(Self::pause_request(request.tripped, request.operator));
+	}
+
+	fn pause_request(paused: bool, operator: String) -> PauseRequest {
+		PauseRequest { scope: PauseScope { token: None, direction: None }, paused, operator }
+	}
+
+	pub fn status(&self) -> BreakerStatus {
+		let mut state = self.lock();
+		state.expire(now_secs().saturating_sub(self.config.window_secs));
+		BreakerStatus {
+			trip: state.trip.clone(),
+			initiations_in_window: state.initiations.len() as u64,
+			rpc_divergences_in_window: state.rpc_divergences.len() as u64,
+			locked_excess: state
+				.locked
+				.keys()
+				.map(|chain| (*chain, state.locked_excess(*chain)))
+				.collect(),
+		}
+	}
+}
+
+fn now_secs() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|d| d.as_secs())
+		.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::catchup::ETH_CHAIN;
+	use bridge_util::events::TransferEvent;
+	use bridge_util::types::{
+		Amount, BridgeAddress, BridgeTransferDetails, BridgeTransferId, HashLock, LockDetails,
+		TimeLock, TransferDirection,
+	};
+
+	fn initiated(byte: u8, amount: u64) -> BusEvent {
+		BusEvent::Contract(TransferEvent {
+			chain: ChainId::ONE,
+			contract_event: BridgeContractEvent::Initiated(BridgeTransferDetails {
+				bridge_transfer_id: BridgeTransferId([byte; 32]),
+				initiator: BridgeAddress(vec![1; 20]),
+				recipient: BridgeAddress(vec![2; 32]),
+				hash_lock: HashLock([3; 32]),
+				time_lock: TimeLock(100),
+				amount: Amount(amount),
+				state: 0,
+			}),
+		})
+	}
+
+	fn locked(byte: u8, amount: u64) -> BusEvent {
+		BusEvent::Contract(TransferEvent {
+			chain: ChainId::TWO,
+			contract_event: BridgeContractEvent::Locked(LockDetails {
+				bridge_transfer_id: BridgeTransferId([byte; 32]),
+				initiator: BridgeAddress(vec![1; 20]),
+				recipient: BridgeAddress(vec![2; 32]),
+				hash_lock: HashLock([3; 32]),
+				time_lock: TimeLock(100),
+				amount: Amount(amount),
+			}),
+		})
+	}
+
+	fn breaker(config: CircuitBreakerConfig) -> (CircuitBreaker, PauseSwitches) {
+		let pause_switches = PauseSwitches::new("MOVE".to_string());
+		(CircuitBreaker::new(&config, pause_switches.clone()), pause_switches)
+	}
+
+	#[test]
+	fn test_initiation_spike() {
+		let (breaker, pause_switches) = breaker(CircuitBreakerConfig {
+			window_secs: 10,
+			max_initiations: 2,
+			..Default::default()
+		});
+		breaker.observe(&initiated(1, 10), 100);
+		breaker.observe(&initiated(2, 10), 105);
+		// The first initiation left the window.
+		breaker.observe(&initiated(3, 10), 110);
+		assert!(!pause_switches.is_paused(TransferDirection::EthToMovement));
+		breaker.observe(&initiated(4, 10), 111);
+		assert!(pause_switches.is_paused(TransferDirection::EthToMovement));
+		assert!(pause_switches.is_paused(TransferDirection::MovementToEth));
+		let trip = breaker.status().trip.unwrap();
+		assert_eq!(trip.anomaly, Some(Anomaly::InitiationSpike));
+		assert_eq!((trip.operator.as_str(), trip.tripped_at_secs), (BREAKER_OPERATOR, 111));
+
+		breaker.apply(BreakerRequest { tripped: false, operator: "ops".to_string() });
+		assert!(!pause_switches.is_paused(TransferDirection::EthToMovement));
+		assert_eq!(breaker.status().trip, None);
+		// The window is cleared by the reset.
+		breaker.observe(&initiated(5, 10), 112);
+		assert!(!pause_switches.is_paused(TransferDirection::EthToMovement));
+	}
+
+	#[test]
+	fn test_totals_mismatch() {
+		let (breaker, pause_switches) =
+			breaker(CircuitBreakerConfig { max_locked_excess: 5, ..Default::default() });
+		breaker.observe(&initiated(1, 100), 100);
+		breaker.observe(&locked(1, 100), 101);
+		breaker.observe(&locked(2, 5), 102);
+		assert!(!pause_switches.is_paused(TransferDirection::EthToMovement));
+		assert_eq!(breaker.status().locked_excess, BTreeMap::from([(ETH_CHAIN, 5)]));
+		breaker.observe(&locked(3, 1), 103);
+		assert_eq!(breaker.status().trip.unwrap().anomaly, Some(Anomaly::TotalsMismatch));
+
+		// The reset acknowledges the excess.
+		breaker.apply(BreakerRequest { tripped: false, operator: "ops".to_string() });
+		assert_eq!(breaker.status().locked_excess, BTreeMap::from([(ETH_CHAIN, 0)]));
+		breaker.observe(&locked(4, 5), 104);
+		assert!(!pause_switches.is_paused(TransferDirection::EthToMovement));
+	}
+
+	#[test]
+	fn test_rpc_divergence_and_manual_trip() {
+		let (breaker, pause_switches) =
+			breaker(CircuitBreakerConfig { max_rpc_divergences: 1, ..Default::default() });
+		breaker.record_rpc_divergence_at(ChainId::TWO, 100);
+		assert!(breaker.status().trip.is_none());
+		breaker.record_rpc_divergence_at(ChainId::ONE, 101);
+		let trip = breaker.status().trip.unwrap();
+		assert_eq!(trip.anomaly, Some(Anomaly::RpcDivergence));
+		assert!(trip.reason.contains(&ChainId::ONE.to_string()));
+		breaker.apply(BreakerRequest { tripped: false, operator: "ops".to_string() });
+		assert_eq!(breaker.status().rpc_divergences_in_window, 0);
+
+		breaker.apply(BreakerRequest { tripped: true, operator: "ops".to_string() });
+		assert!(pause_switches.is_paused(TransferDirection::MovementToEth));
+		let trip = breaker.status().trip.unwrap();
+		assert_eq!((trip.anomaly, trip.operator.as_str()), (None, "ops"));
+	}
+}
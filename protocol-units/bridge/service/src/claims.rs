@@ -0,0 +1,363 @@
+//! Claims of the transfers whose end user holds the preimage: the user posts the secret on the
+//! REST API and the relayer completes the transfer on the lock chain on their behalf. The
+//! completion on the initiation chain follows like for any secret revealed on chain.
+use crate::chains::ethereum::client::EthClient;
+use crate::chains::movement::client_framework::MovementClientFramework;
+use crate::in_flight::InFlightTransfers;
+use crate::pre_image::{HashLockScheme, PreImageError};
+use bridge_config::common::claims::ClaimsConfig;
+use bridge_util::chains::bridge_contracts::BridgeContract;
+use bridge_util::reason::{ReasonCode, WithReasonCode};
+use bridge_util::states::TransferStateType;
+use bridge_util::types::{BridgeTransferId, ChainId, HashLockPreImage};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+// Length of the window of the rate limits of the clients.
+const RATE_LIMIT_WINDOW_SECS: u64 = 60;
+
+/// Secret posted by the end user of a transfer.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClaimRequest {
+	/// The 32 bytes of the preimage, hex encoded.
+	pub secret: String,
+}
+
+/// Completion submitted for a claim.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ClaimResponse {
+	pub transfer_id: String,
+	/// Chain the transfer was completed on.
+	pub chain: String,
+}
+
+#[derive(Debug, Error)]
+pub enum ClaimError {
+	#[error("Claims are disabled")]
+	Disabled,
+	#[error("Invalid secret: {0}")]
+	InvalidSecret(String),
+	#[error("No transfer {0} in flight")]
+	UnknownTransfer(BridgeTransferId),
+	#[error("Transfer {0} is not locked")]
+	NotLocked(BridgeTransferId),
+	#[error("Wrong secret: {0}")]
+	WrongSecret(PreImageError),
+	#[error("Too many wrong secrets posted, retry later")]
+	TooManyAttempts,
+	#[error("Too many claims, retry later")]
+	RateLimited,
+	#[error("Transfer {0} is already claimed")]
+	AlreadyClaimed(BridgeTransferId),
+	#[error("Completion failed: {0}")]
+	Submission(String),
+}
+
+impl WithReasonCode for ClaimError {
+	fn reason_code(&self) -> ReasonCode {
+		match self {
+			ClaimError::Disabled => ReasonCode::FeatureDisabled,
+			ClaimError::InvalidSecret(_) => ReasonCode::InvalidRequest,
+			ClaimError::UnknownTransfer(_) => ReasonCode::NotFound,
+			ClaimError::NotLocked(_) => ReasonCode::NotLocked,
+			ClaimError::WrongSecret(_) => ReasonCode::WrongSecret,
+			ClaimError::TooManyAttempts | ClaimError::RateLimited => ReasonCode::RateLimited,
+			ClaimError::AlreadyClaimed(_) => ReasonCode::AlreadyClaimed,
+			ClaimError::Submission(_) => ReasonCode::TransactionFailed,
+		}
+	}
+}
+
+// Claims and wrong secrets posted by a client in the rate limit window.
+#[derive(Debug, Default)]
+struct ClientUsage {
+	claims: u32,
+	invalid: u32,
+}
+
+#[derive(Debug, Default)]
+struct ClaimLimits {
+	// Transfers claimed or being claimed.
+	claimed: HashSet<BridgeTransferId>,
+	// Start of the rate limit window.
+	window_start: u64,
+	// Usage of each client in the window.
+	clients: HashMap<String, ClientUsage>,
+}
+
+/// Checks of the claims before the relayer pays for their completion: the secret must unlock
+/// the hash lock of the lock chain, and the claims of each client are rate limited so they
+/// can't be used to grief the relayer or the other users. Clones share the same limits.
+#[derive(Debug, Clone)]
+pub struct ClaimGuard {
+	in_flight: InFlightTransfers,
+	scheme: HashLockScheme,
+	max_invalid_per_client: u32,
+	max_per_minute: u32,
+	limits: Arc<Mutex<ClaimLimits>>,
+}
+
+impl ClaimGuard {
+	pub fn new(
+		config: &ClaimsConfig,
+		in_flight: InFlightTransfers,
+		scheme: HashLockScheme,
+	) -> Self {
+		ClaimGuard {
+			in_flight,
+			scheme,
+			max_invalid_per_client: config.max_invalid_per_client,
+			max_per_minute: config.max_per_minute,
+			limits: Arc::new(Mutex::new(ClaimLimits::default())),
+		}
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<'_, ClaimLimits> {
+		self.limits.lock().expect("Claim limits lock poisoned")
+	}
+
+	/// Check the claim of the secret posted by the client, and return the lock chain of the
+	/// transfer and its preimage. The transfer is claimed until it's released.
+	pub fn check(
+		&self,
+		client: &str,
+		transfer_id: BridgeTransferId,
+		secret: &str,
+		now_secs: u64,
+	) -> Result<(ChainId, HashLockPreImage), ClaimError> {
+		let mut limits = self.lock();
+		if now_secs >= limits.window_start + RATE_LIMIT_WINDOW_SECS {
+			limits.window_start = now_secs;
+			limits.clients.clear();
+			// The claims of the transfers no longer in flight are forgotten.
+			let in_flight = &self.in_flight;
+			limits.claimed.retain(|id| in_flight.get(*id).is_some());
+		}
+		let usage = limits.clients.entry(client.to_string()).or_default();
+		if usage.claims >= self.max_per_minute {
+			return Err(ClaimError::RateLimited);
+		}
+		usage.claims += 1;
+
+		let transfer = self
+			.in_flight
+			.get(transfer_id)
+			.ok_or(ClaimError::UnknownTransfer(transfer_id))?;
+		if transfer.state != Some(TransferStateType::Locked) {
+			return Err(ClaimError::NotLocked(transfer_id));
+		}
+		let lock_chain = transfer.init_chain.other();
+		// The secret is verified first, the wrong secrets of a client can't lock out the
+		// recipient of the transfer.
+		let verified = parse_secret(secret).and_then(|pre_image| {
+			self.scheme
+				.verify(lock_chain, &transfer.hash_lock, &pre_image)
+				.map(|()| pre_image)
+				.map_err(ClaimError::WrongSecret)
+		});
+		let pre_image = match verified {
+			Ok(pre_image) => pre_image,
+			Err(_) if usage.invalid >= self.max_invalid_per_client => {
+				return Err(ClaimError::TooManyAttempts);
+			}
+			Err(err) => {
+				usage.invalid += 1;
+				return Err(err);
+			}
+		};
+		if !limits.claimed.insert(transfer_id) {
+			return Err(ClaimError::AlreadyClaimed(transfer_id));
+		}
+		Ok((lock_chain, pre_image))
+	}
+
+	/// Release the claim of the transfer, after its completion failed.
+	pub fn release(&self, transfer_id: BridgeTransferId) {
+		self.lock().claimed.remove(&transfer_id);
+	}
+
+	/// True once the counterparty Completed event of the transfer has been received: the
+	/// recipient completed the lock on their own.
+	pub fn counterparty_completed(&self, transfer_id: BridgeTransferId) -> bool {
+		self.in_flight
+			.get(transfer_id)
+			.is_some_and(|transfer| transfer.state == Some(TransferStateType::SecretReceived))
+	}
+}
+
+fn parse_secret(secret: &str) -> Result<HashLockPreImage, ClaimError> {
+	let bytes = hex::decode(secret.strip_prefix("0x").unwrap_or(secret))
+		.map_err(|err| ClaimError::InvalidSecret(err.to_string()))?;
+	let bytes: [u8; 32] = bytes
+		.try_into()
+		.map_err(|_| ClaimError::InvalidSecret("the preimage is not 32 bytes".to_string()))?;
+	Ok(HashLockPreImage(bytes))
+}
+
+/// Completes the claimed transfers with the relayer accounts.
+#[derive(Clone)]
+pub struct ClaimService {
+	guard: ClaimGuard,
+	eth_client: EthClient,
+	movement_client: MovementClientFramework,
+}
+
+impl ClaimService {
+	/// Build the claims from the config, None if they are disabled.
+	pub fn from_config(
+		config: &ClaimsConfig,
+		in_flight: InFlightTransfers,
+		scheme: HashLockScheme,
+		eth_client: EthClient,
+		movement_client: MovementClientFramework,
+	) -> Option<Self> {
+		config.enabled.then(|| ClaimService {
+			guard: ClaimGuard::new(config, in_flight, scheme),
+			eth_client,
+			movement_client,
+		})
+	}
+
+	/// Complete the transfer on its lock chain with the secret of the claim posted by the
+	/// client.
+	pub async fn claim(
+		&self,
+		client: &str,
+		transfer_id: BridgeTransferId,
+		request: &ClaimRequest,
+	) -> Result<ClaimResponse, ClaimError> {
+		let (chain, pre_image) =
+			self.guard.check(client, transfer_id, &request.secret, now_secs())?;
+		tracing::info!(
+			target: "bridge_audit",
+			"Claim of transfer {transfer_id}, completing it on {chain}"
+		);
+		let result = match chain {
+			ChainId::ONE => {
+				self.eth_client
+					.clone()
+					.counterparty_complete_bridge_transfer(transfer_id, pre_image)
+					.await
+			}
+			ChainId::TWO => {
+				self.movement_client
+					.clone()
+					.counterparty_complete_bridge_transfer(transfer_id, pre_image)
+					.await
+			}
+		};
+		if let Err(err) = result {
+			// The recipient completed the lock before the claim, its completion is skipped.
+			if self.guard.counterparty_completed(transfer_id) {
+				tracing::info!(
+					"Transfer {transfer_id} already completed by its recipient, skip the claim"
+				);
+				return Err(ClaimError::AlreadyClaimed(transfer_id));
+			}
+			self.guard.release(transfer_id);
+			return Err(ClaimError::Submission(err.to_string()));
+		}
+		Ok(ClaimResponse { transfer_id: transfer_id.to_string(), chain: chain.to_string() })
+	}
+}
+
+fn now_secs() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|d| d.as_secs())
+		.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::event_bus::{BusEvent, StateChange};
+	use bridge_util::chains::bridge_contracts::BridgeContractEvent;
+	use bridge_util::events::TransferEvent;
+	use bridge_util::types::{Amount, BridgeAddress, BridgeTransferDetails, TimeLock};
+
+	#[test]
+	fn test_claim_guard() {
+		let in_flight = InFlightTransfers::default();
+		let scheme = HashLockScheme::default();
+		let config = ClaimsConfig { enabled: true, max_invalid_per_client: 2, max_per_minute: 6 };
+		let guard = ClaimGuard::new(&config, in_flight.clone(), scheme);
+		let id = |byte: u8| BridgeTransferId([byte; 32]);
+		let pre_image = HashLockPreImage([7; 32]);
+		let initiated = |byte: u8| {
+			BusEvent::Contract(TransferEvent {
+				chain: ChainId::ONE,
+				contract_event: BridgeContractEvent::Initiated(BridgeTransferDetails {
+					bridge_transfer_id: id(byte),
+					initiator: BridgeAddress(vec![1; 20]),
+					recipient: BridgeAddress(vec![2; 32]),
+					hash_lock: scheme.hash_lock(ChainId::TWO, &pre_image).unwrap(),
+					time_lock: TimeLock(200),
+					amount: Amount(10),
+					state: 0,
+				}),
+			})
+		};
+		let locked = |byte: u8| {
+			BusEvent::StateChange(StateChange {
+				transfer_id: id(byte),
+				sequence: 2,
+				previous: Some(TransferStateType::Initialized),
+				new: TransferStateType::Locked,
+				cause: None,
+				reason: None,
+			})
+		};
+		let secret = hex::encode(pre_image.0);
+		let wrong = format!("0x{}", hex::encode([8; 32]));
+
+		for byte in [1, 2] {
+			in_flight.observe(&initiated(byte), 100);
+		}
+		let check = |client: &str, byte: u8, secret: &str, now_secs: u64| {
+			guard.check(client, id(byte), secret, now_secs)
+		};
+		assert!(matches!(check("user", 3, &secret, 100), Err(ClaimError::UnknownTransfer(_))));
+		assert!(matches!(check("user", 1, &secret, 100), Err(ClaimError::NotLocked(_))));
+		in_flight.observe(&locked(1), 101);
+		in_flight.observe(&locked(2), 101);
+
+		assert!(matches!(check("attacker", 1, &wrong, 101), Err(ClaimError::WrongSecret(_))));
+		assert!(matches!(check("attacker", 1, "0x01", 101), Err(ClaimError::InvalidSecret(_))));
+		// The wrong secrets of the client are exhausted, on any transfer.
+		assert!(matches!(check("attacker", 1, &wrong, 101), Err(ClaimError::TooManyAttempts)));
+		assert!(matches!(check("attacker", 2, &wrong, 101), Err(ClaimError::TooManyAttempts)));
+		// The recipient isn't locked out by them.
+		assert_eq!(check("user", 1, &secret, 101).unwrap(), (ChainId::TWO, pre_image));
+
+		assert_eq!(check("user", 2, &secret, 102).unwrap(), (ChainId::TWO, pre_image));
+		assert!(matches!(check("user", 2, &secret, 102), Err(ClaimError::AlreadyClaimed(_))));
+		guard.release(id(2));
+		assert!(check("user", 2, &secret, 102).is_ok());
+
+		// The client posted 6 claims in the window, the others can still claim.
+		guard.release(id(2));
+		assert!(matches!(check("user", 2, &secret, 159), Err(ClaimError::RateLimited)));
+		assert!(check("other", 2, &secret, 159).is_ok());
+		guard.release(id(2));
+		assert!(check("user", 2, &secret, 160).is_ok());
+
+		// The recipient completed the lock on their own.
+		assert!(!guard.counterparty_completed(id(2)));
+		in_flight.observe(
+			&BusEvent::StateChange(StateChange {
+				transfer_id: id(2),
+				sequence: 3,
+				previous: Some(TransferStateType::Locked),
+				new: TransferStateType::SecretReceived,
+				cause: None,
+				reason: None,
+			}),
+			161,
+		);
+		assert!(guard.counterparty_completed(id(2)));
+	}
+}
@@ -0,0 +1,439 @@
+//! Failover drills of the test deployments. A drill injects a fault in the relayer for a while:
+//! the RPC of both chains failing, the signer unavailable or the transfer store read-only. The
+//! transfers hit by the fault fail as on a real outage. Once the fault is lifted, the drill
+//! reports whether the failover paths reacted: the clients saw the failures, the connection
+//! breakers opened, the store writes were refused, and hooks received the drill alerts.
+use crate::chains::ethereum::client::EthClient;
+use crate::chains::movement::client_framework::MovementClientFramework;
+use crate::in_flight::InFlightTransfer;
+use crate::key_audit::{ETHEREUM, MOVEMENT};
+use crate::runbook::{Alert, AlertKind, RunbookHooks};
+use crate::store::TransferStore;
+use bridge_config::common::drills::DrillsConfig;
+use bridge_util::chains::bridge_contracts::{
+	BridgeContract, BridgeContractError, BridgeContractResult,
+};
+use bridge_util::reason::{ReasonCode, WithReasonCode};
+use bridge_util::types::BridgeTransferId;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+// Reports kept for the admin API, the oldest are dropped beyond.
+const MAX_REPORTS: usize = 20;
+// Period of the RPC calls of the clients during an RPC failure drill.
+const PROBE_PERIOD: Duration = Duration::from_secs(1);
+// Component of the transfer store in the failures seen.
+pub const TRANSFER_STORE: &str = "transfer store";
+
+/// Fault injected by a drill.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DrillFault {
+	/// The RPC of both chains is unreachable.
+	RpcFailure,
+	/// The keys of the relayer can't sign.
+	SignerUnavailable,
+	/// The writes to the transfer store are refused.
+	StoreReadOnly,
+}
+
+impl DrillFault {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			DrillFault::RpcFailure => "rpc_failure",
+			DrillFault::SignerUnavailable => "signer_unavailable",
+			DrillFault::StoreReadOnly => "store_read_only",
+		}
+	}
+}
+
+impl fmt::Display for DrillFault {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+#[derive(Debug, Default)]
+struct InjectedFault {
+	active: Option<(DrillFault, Instant)>,
+	// Failures caused by the fault since it was injected, by component.
+	failures: BTreeMap<&'static str, u64>,
+}
+
+/// The fault injected in the clients and the store. Clones share the same fault.
+#[derive(Debug, Clone, Default)]
+pub struct FaultInjector {
+	fault: Arc<Mutex<InjectedFault>>,
+}
+
+impl FaultInjector {
+	fn lock(&self) -> std::sync::MutexGuard<'_, InjectedFault> {
+		self.fault.lock().expect("Fault injector lock poisoned")
+	}
+
+	fn inject(&self, fault: DrillFault, duration: Duration) {
+		let mut injected = self.lock();
+		injected.active = Some((fault, Instant::now() + duration));
+		injected.failures.clear();
+	}
+
+	// Lift the fault, returning the failures it caused.
+	fn lift(&self) -> BTreeMap<&'static str, u64> {
+		let mut injected = self.lock();
+		injected.active = None;
+		std::mem::take(&mut injected.failures)
+	}
+
+	/// Fail the call of the component if the fault is injected.
+	pub fn check(&self, fault: DrillFault, component: &'static str) -> BridgeContractResult<()> {
+		let mut injected = self.lock();
+		match injected.active {
+			Some((active, until)) if active == fault && Instant::now() < until => {
+				*injected.failures.entry(component).or_default() += 1;
+				Err(BridgeContractError::OnChainError(format!(
+					"{component}: {fault} injected by a failover drill"
+				)))
+			}
+			_ => Ok(()),
+		}
+	}
+}
+
+/// Transfer store refusing the writes while a read-only drill runs.
+pub struct DrillStore {
+	inner: Arc<dyn TransferStore>,
+	faults: FaultInjector,
+}
+
+impl DrillStore {
+	pub fn new(inner: Arc<dyn TransferStore>, faults: FaultInjector) -> Self {
+		DrillStore { inner, faults }
+	}
+}
+
+impl TransferStore for DrillStore {
+	fn transition(&self, transfer: &InFlightTransfer) -> Result<bool, anyhow::Error> {
+		self.faults.check(DrillFault::StoreReadOnly, TRANSFER_STORE)?;
+		self.inner.transition(transfer)
+	}
+
+	fn in_flight(&self) -> Result<Vec<InFlightTransfer>, anyhow::Error> {
+		self.inner.in_flight()
+	}
+
+	fn get(
+		&self,
+		transfer_id: BridgeTransferId,
+	) -> Result<Option<InFlightTransfer>, anyhow::Error> {
+		self.inner.get(transfer_id)
+	}
+}
+
+/// Drill requested by an operator on the admin API.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DrillRequest {
+	pub fault: DrillFault,
+	pub duration_secs: u64,
+	pub operator: String,
+}
+
+/// A failover path verified by a drill.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DrillCheck {
+	pub name: String,
+	pub passed: bool,
+	pub detail: String,
+}
+
+impl DrillCheck {
+	fn new(name: String, passed: bool, detail: String) -> Self {
+		DrillCheck { name, passed, detail }
+	}
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DrillReport {
+	pub fault: DrillFault,
+	pub operator: String,
+	pub started_at_secs: u64,
+	/// Missing while the drill runs.
+	pub ended_at_secs: Option<u64>,
+	pub checks: Vec<DrillCheck>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DrillError {
+	#[error("Failover drills are disabled")]
+	Disabled,
+	#[error("A failover drill is already running")]
+	AlreadyRunning,
+	#[error("Drill duration must be between 1 and {0}s")]
+	InvalidDuration(u64),
+}
+
+impl WithReasonCode for DrillError {
+	fn reason_code(&self) -> ReasonCode {
+		match self {
+			DrillError::Disabled => ReasonCode::FeatureDisabled,
+			DrillError::AlreadyRunning => ReasonCode::Conflict,
+			DrillError::InvalidDuration(_) => ReasonCode::InvalidRequest,
+		}
+	}
+}
+
+/// Runs the drills one at a time and keeps their reports. Clones share the same reports.
+#[derive(Clone)]
+pub struct FailoverDrills {
+	max_duration_secs: u64,
+	faults: FaultInjector,
+	eth_client: EthClient,
+	movement_client: MovementClientFramework,
+	runbook_hooks: RunbookHooks,
+	reports: Arc<Mutex<VecDeque<DrillReport>>>,
+}
+
+impl FailoverDrills {
+	pub fn new(
+		config: &DrillsConfig,
+		faults: FaultInjector,
+		eth_client: EthClient,
+		movement_client: MovementClientFramework,
+		runbook_hooks: RunbookHooks,
+	) -> Self {
+		FailoverDrills {
+			max_duration_secs: config.max_duration_secs,
+			faults,
+			eth_client,
+			movement_client,
+			runbook_hooks,
+			reports: Arc::default(),
+		}
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<DrillReport>> {
+		self.reports.lock().expect("Drill reports lock poisoned")
+	}
+
+	/// Start the drill in the background, unless another one runs.
+	pub fn start(&self, request: DrillRequest) -> Result<(), DrillError> {
+		if request.duration_secs == 0 || request.duration_secs > self.max_duration_secs {
+			return Err(DrillError::InvalidDuration(self.max_duration_secs));
+		}
+		let mut reports = self.lock();
+		if reports.back().is_some_and(|report| report.ended_at_secs.is_none()) {
+			return Err(DrillError::AlreadyRunning);
+		}
+		if reports.len() >= MAX_REPORTS {
+			reports.pop_front();
+		}
+		reports.push_back(DrillReport {
+			fault: request.fault,
+			operator: request.operator.clone(),
+			started_at_secs: now_secs(),
+			ended_at_secs: None,
+			checks: Vec::new(),
+		});
+		tokio::spawn(self.clone().run(request));
+		Ok(())
+	}
+
+	/// The reports of the drills, the most recent first.
+	pub fn reports(&self) -> Vec<DrillReport> {
+		self.lock().iter().rev().cloned().collect()
+	}
+
+	async fn run(self, request: DrillRequest) {
+		let DrillRequest { fault, duration_secs, operator } = request;
+		tracing::warn!(
+			target: "bridge_alert",
+			"Failover drill {fault} started by {operator} for {duration_secs}s"
+		);
+		self.runbook_hooks.raise(Alert {
+			kind: AlertKind::FailoverDrill,
+			summary: format!("Failover drill {fault} started by {operator} for {duration_secs}s"),
+			context: serde_json::json!({ "fault": fault, "operator": operator }),
+		});
+
+		let duration = Duration::from_secs(duration_secs);
+		self.faults.inject(fault, duration);
+		let until = Instant::now() + duration;
+		if fault == DrillFault::RpcFailure {
+			// The clients are called even without transfers, so their breakers react.
+			while Instant::now() < until {
+				self.probe_rpc().await;
+				tokio::time::sleep(
+					PROBE_PERIOD.min(until.saturating_duration_since(Instant::now())),
+				)
+				.await;
+			}
+		} else {
+			tokio::time::sleep(duration).await;
+		}
+		let breakers_open = [
+			(ETHEREUM, self.eth_client.connection().is_open()),
+			(MOVEMENT, self.movement_client.connection().is_open()),
+		];
+		let failures = self.faults.lift();
+		let checks = drill_checks(
+			fault,
+			&failures,
+			&breakers_open,
+			self.runbook_hooks.has_hooks(AlertKind::FailoverDrill),
+		);
+
+		let passed = checks.iter().filter(|check| check.passed).count();
+		let summary =
+			format!("Failover drill {fault} ended: {passed}/{} checks passed", checks.len());
+		tracing::warn!(target: "bridge_alert", "{summary}");
+		let report = {
+			let mut reports = self.lock();
+			let Some(report) = reports.back_mut() else {
+				return;
+			};
+			report.ended_at_secs = Some(now_secs());
+			report.checks = checks;
+			report.clone()
+		};
+		self.runbook_hooks.raise(Alert {
+			kind: AlertKind::FailoverDrill,
+			summary,
+			context: serde_json::to_value(&report).unwrap_or_default(),
+		});
+	}
+
+	// Read a transfer on both chains, the result is only the failure seen by the clients.
+	async fn probe_rpc(&self) {
+		let probe_id = BridgeTransferId([0; 32]);
+		let _ = self.eth_client.clone().get_bridge_transfer_details_initiator(probe_id).await;
+		let _ = self
+			.movement_client
+			.clone()
+			.get_bridge_transfer_details_counterparty(probe_id)
+			.await;
+	}
+}
+
+// The failover paths expected to react to the fault, from the failures it caused and the
+// state of the connection breakers at its end.
+fn drill_checks(
+	fault: DrillFault,
+	failures: &BTreeMap<&'static str, u64>,
+	breakers_open: &[(&'static str, bool)],
+	alert_hooks: bool,
+) -> Vec<DrillCheck> {
+	let failed = |component: &'static str, not_exercised: &str| {
+		let count = failures.get(component).copied().unwrap_or_default();
+		let detail =
+			if count > 0 { format!("{count} failures") } else { not_exercised.to_string() };
+		(count > 0, detail)
+	};
+	let mut checks = Vec::new();
+	match fault {
+		DrillFault::RpcFailure => {
+			for (chain, open) in breakers_open {
+				let (passed, detail) = failed(chain, "no RPC call during the drill");
+				checks.push(DrillCheck::new(format!("{chain} RPC failures"), passed, detail));
+				let detail = if *open { "open" } else { "closed at the end of the drill" };
+				checks.push(DrillCheck::new(
+					format!("{chain} connection breaker"),
+					*open,
+					detail.to_string(),
+				));
+			}
+		}
+		DrillFault::SignerUnavailable => {
+			for chain in [ETHEREUM, MOVEMENT] {
+				let (passed, detail) = failed(chain, "no transaction signed during the drill");
+				checks.push(DrillCheck::new(format!("{chain} signatures refused"), passed, detail));
+			}
+		}
+		DrillFault::StoreReadOnly => {
+			let (passed, detail) = failed(TRANSFER_STORE, "no transfer written during the drill");
+			checks.push(DrillCheck::new(
+				"Transfer store writes refused".to_string(),
+				passed,
+				detail,
+			));
+		}
+	}
+	let detail = if alert_hooks { "hooks attached" } else { "no hook of failover_drill alerts" };
+	checks.push(DrillCheck::new("Drill alerts".to_string(), alert_hooks, detail.to_string()));
+	checks
+}
+
+fn now_secs() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|d| d.as_secs())
+		.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::store::MemoryTransferStore;
+	use bridge_util::types::{ChainId, HashLock};
+
+	#[test]
+	fn test_fault_injector() {
+		let faults = FaultInjector::default();
+		assert!(faults.check(DrillFault::RpcFailure, ETHEREUM).is_ok());
+		faults.inject(DrillFault::StoreReadOnly, Duration::from_secs(60));
+		assert!(faults.check(DrillFault::RpcFailure, ETHEREUM).is_ok());
+
+		let store = DrillStore::new(Arc::new(MemoryTransferStore::default()), faults.clone());
+		let transfer = InFlightTransfer {
+			transfer_id: BridgeTransferId([1; 32]),
+			init_chain: ChainId::ONE,
+			state: None,
+			initiator: vec![3; 20],
+			recipient: vec![4; 32],
+			hash_lock: HashLock([2; 32]),
+			time_lock: 100,
+			amount: 10,
+			sequence: 1,
+			updated_at_secs: 1000,
+			pre_image: None,
+		};
+		assert!(store.transition(&transfer).is_err());
+		assert!(store.get(transfer.transfer_id).unwrap().is_none());
+		let failures = faults.lift();
+		assert_eq!(failures.get(TRANSFER_STORE), Some(&1));
+		assert!(store.transition(&transfer).unwrap());
+
+		// An expired fault is lifted.
+		faults.inject(DrillFault::SignerUnavailable, Duration::ZERO);
+		assert!(faults.check(DrillFault::SignerUnavailable, MOVEMENT).is_ok());
+	}
+
+	#[test]
+	fn test_drill_checks() {
+		let failures = BTreeMap::from([(ETHEREUM, 3)]);
+		let checks = drill_checks(
+			DrillFault::RpcFailure,
+			&failures,
+			&[(ETHEREUM, true), (MOVEMENT, false)],
+			true,
+		);
+		let passed: Vec<_> =
+			checks.iter().map(|check| (check.name.as_str(), check.passed)).collect();
+		assert_eq!(
+			passed,
+			[
+				("Ethereum RPC failures", true),
+				("Ethereum connection breaker", true),
+				("Movement RPC failures", false),
+				("Movement connection breaker", false),
+				("Drill alerts", true),
+			]
+		);
+		assert_eq!(checks[0].detail, "3 failures");
+
+		let checks = drill_checks(DrillFault::StoreReadOnly, &BTreeMap::new(), &[], false);
+		assert_eq!(checks.len(), 2);
+		assert!(checks.iter().all(|check| !check.passed));
+	}
+}
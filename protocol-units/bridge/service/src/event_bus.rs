@@ -0,0 +1,263 @@
+use bridge_indexer_db::client::Client as IndexerClient;
+use bridge_indexer_db::models::SubmissionStatus;
+use bridge_util::{
+	actions::TransferAction,
+	chains::bridge_contracts::BridgeContractEvent,
+	events::TransferEvent,
+	reason::ReasonCode,
+	states::TransferStateType,
+	types::{BridgeAddress, BridgeTransferDetails, BridgeTransferId, ChainId, LockDetails},
+};
+use std::time::Duration;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+pub const DEFAULT_BUS_CAPACITY: usize = 1024;
+// Maximum number of contract events indexed in a transaction.
+const INDEX_BATCH_SIZE: usize = 64;
+// Maximum time an event waits for the following ones before its batch is indexed.
+const INDEX_BATCH_LATENCY: Duration = Duration::from_millis(100);
+
+/// Topics a subscriber can filter the bus events on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Topic {
+	ContractEvents,
+	Actions,
+	StateChanges,
+}
+
+/// Reference of the contract event that caused a change of state.
+/// The event of a child HTLC references the child transfer id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventRef {
+	pub chain: ChainId,
+	pub event: &'static str,
+	pub transfer_id: BridgeTransferId,
+}
+
+impl<A> From<&TransferEvent<A>> for EventRef {
+	fn from(event: &TransferEvent<A>) -> Self {
+		EventRef {
+			chain: event.chain,
+			event: event.contract_event.kind(),
+			transfer_id: event.contract_event.bridge_transfer_id(),
+		}
+	}
+}
+
+/// Change of state of a transfer. The sequence number starts at 1 with the first
+/// state of the transfer and increases by one on each change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateChange {
+	pub transfer_id: BridgeTransferId,
+	pub sequence: u64,
+	pub previous: Option<TransferStateType>,
+	pub new: TransferStateType,
+	/// Missing when the change is caused by an operator decision or a failed action.
+	pub cause: Option<EventRef>,
+	/// Why the transfer is refunded, missing on the other changes.
+	pub reason: Option<ReasonCode>,
+}
+
+/// Event published by the relayer loop. The addresses of the contract events
+/// are converted to bytes so subscribers don't depend on the chain address types.
+#[derive(Debug, Clone)]
+pub enum BusEvent {
+	/// A contract event accepted by the state machine, before it is applied.
+	Contract(TransferEvent<Vec<u8>>),
+	/// A step of the submission of an action returned by the state machine.
+	Action(TransferAction, SubmissionStatus),
+	/// A change of state of a transfer, after the event or action causing it.
+	StateChange(StateChange),
+}
+
+impl BusEvent {
+	pub fn topic(&self) -> Topic {
+		match self {
+			BusEvent::Contract(_) => Topic::ContractEvents,
+			BusEvent::Action(..) => Topic::Actions,
+			BusEvent::StateChange(_) => Topic::StateChanges,
+		}
+	}
+}
+
+impl<A: Into<Vec<u8>>> From<TransferEvent<A>> for BusEvent {
+	fn from(event: TransferEvent<A>) -> Self {
+		let contract_event = match event.contract_event {
+			BridgeContractEvent::Initiated(details) => {
+				BridgeContractEvent::Initiated(BridgeTransferDetails {
+					bridge_transfer_id: details.bridge_transfer_id,
+					initiator: BridgeAddress(details.initiator.0.into()),
+					recipient: details.recipient,
+					hash_lock: details.hash_lock,
+					time_lock: details.time_lock,
+					amount: details.amount,
+					state: details.state,
+				})
+			}
+			BridgeContractEvent::Locked(details) => BridgeContractEvent::Locked(LockDetails {
+				bridge_transfer_id: details.bridge_transfer_id,
+				initiator: details.initiator,
+				recipient: BridgeAddress(details.recipient.0.into()),
+				hash_lock: details.hash_lock,
+				time_lock: details.time_lock,
+				amount: details.amount,
+			}),
+			BridgeContractEvent::InitiatorCompleted(id) => {
+				BridgeContractEvent::InitiatorCompleted(id)
+			}
+			BridgeContractEvent::CounterPartyCompleted(id, preimage) => {
+				BridgeContractEvent::CounterPartyCompleted(id, preimage)
+			}
+			BridgeContractEvent::Cancelled(id) => BridgeContractEvent::Cancelled(id),
+			BridgeContractEvent::Refunded(id) => BridgeContractEvent::Refunded(id),
+		};
+		BusEvent::Contract(TransferEvent { chain: event.chain, contract_event })
+	}
+}
+
+/// Broadcasts the canonical stream of the relayer events to the components
+/// that follow it. Clones publish on the same bus.
+#[derive(Debug, Clone)]
+pub struct EventBus {
+	tx: broadcast::Sender<BusEvent>,
+}
+
+impl EventBus {
+	pub fn new(capacity: usize) -> Self {
+		let (tx, _) = broadcast::channel(capacity);
+		EventBus { tx }
+	}
+
+	pub fn publish(&self, event: impl Into<BusEvent>) {
+		// Publishing without subscriber is not an error.
+		let _ = self.tx.send(event.into());
+	}
+
+	/// Subscribe to the events of the topics published from now on.
+	pub fn subscribe(&self, topics: &[Topic]) -> Subscription {
+		Subscription { rx: self.tx.subscribe(), topics: topics.to_vec() }
+	}
+}
+
+impl Default for EventBus {
+	fn default() -> Self {
+		EventBus::new(DEFAULT_BUS_CAPACITY)
+	}
+}
+
+pub struct Subscription {
+	rx: broadcast::Receiver<BusEvent>,
+	topics: Vec<Topic>,
+}
+
+impl Subscription {
+	/// Wait for the next event of the subscribed topics, None once the bus is dropped.
+	/// A subscriber falling behind by more than the bus capacity skips the oldest events.
+	pub async fn recv(&mut self) -> Option<BusEvent> {
+		loop {
+			match self.rx.recv().await {
+				Ok(event) if self.topics.contains(&event.topic()) => return Some(event),
+				Ok(_) => continue,
+				Err(RecvError::Lagged(skipped)) => {
+					tracing::warn!("Event bus subscriber lagging, {skipped} events skipped")
+				}
+				Err(RecvError::Closed) => return None,
+			}
+		}
+	}
+}
+
+/// Record the contract events published on the bus in the indexer.
+/// The events are written in batches, each in a single transaction,
+/// an event waits at most `INDEX_BATCH_LATENCY` for the ones following it.
+pub async fn index_events(mut client: IndexerClient, mut events: Subscription) {
+	while let Some(event) = next_contract_event(&mut events).await {
+		let mut batch = vec![event];
+		let deadline = tokio::time::Instant::now() + INDEX_BATCH_LATENCY;
+		while batch.len() < INDEX_BATCH_SIZE {
+			match tokio::time::timeout_at(deadline, next_contract_event(&mut events)).await {
+				Ok(Some(event)) => batch.push(event),
+				// The batch is indexed before the subscription ends.
+				Ok(None) | Err(_) => break,
+			}
+		}
+		index_batch(&mut client, batch);
+	}
+}
+
+async fn next_contract_event(events: &mut Subscription) -> Option<TransferEvent<Vec<u8>>> {
+	loop {
+		if let BusEvent::Contract(event) = events.recv().await? {
+			return Some(event);
+		}
+	}
+}
+
+fn index_batch(client: &mut IndexerClient, batch: Vec<TransferEvent<Vec<u8>>>) {
+	let contract_events = batch.iter().map(|event| event.contract_event.clone()).collect();
+	match client.insert_bridge_contract_events(contract_events) {
+		Ok(()) => {
+			for event in batch {
+				tracing::info!("index_event(success):{event}");
+			}
+		}
+		// The batch is rolled back, index the events one by one
+		// so an event failing doesn't drop the others.
+		Err(err) => {
+			tracing::warn!("Fail to index a batch of {} events: {err}", batch.len());
+			for event in batch {
+				match client.insert_bridge_contract_event(event.contract_event.clone()) {
+					Ok(()) => tracing::info!("index_event(success):{event}"),
+					Err(err) => {
+						tracing::warn!("Fail to index event {}: {err}", event.contract_event)
+					}
+				}
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bridge_util::types::{BridgeTransferId, ChainId};
+
+	#[tokio::test]
+	async fn test_topic_subscription() {
+		let bus = EventBus::new(2);
+		let mut contract_events = bus.subscribe(&[Topic::ContractEvents]);
+		let mut all_events = bus.subscribe(&[Topic::ContractEvents, Topic::Actions]);
+
+		let transfer_id = BridgeTransferId([1; 32]);
+		bus.publish(BusEvent::Action(
+			TransferAction {
+				chain: ChainId::ONE,
+				transfer_id,
+				kind: bridge_util::TransferActionType::NoAction,
+			},
+			SubmissionStatus::Planned,
+		));
+		bus.publish(TransferEvent::<Vec<u8>> {
+			chain: ChainId::TWO,
+			contract_event: BridgeContractEvent::Refunded(transfer_id),
+		});
+
+		assert!(matches!(contract_events.recv().await, Some(BusEvent::Contract(_))));
+		assert!(matches!(all_events.recv().await, Some(BusEvent::Action(..))));
+		assert!(matches!(all_events.recv().await, Some(BusEvent::Contract(_))));
+
+		// A lagging subscriber skips the oldest events.
+		for _ in 0..3 {
+			bus.publish(TransferEvent::<Vec<u8>> {
+				chain: ChainId::ONE,
+				contract_event: BridgeContractEvent::Cancelled(transfer_id),
+			});
+		}
+		drop(bus);
+		let mut received = 0;
+		while contract_events.recv().await.is_some() {
+			received += 1;
+		}
+		assert_eq!(received, 2);
+	}
+}
@@ -0,0 +1,69 @@
+use crate::event_bus::{BusEvent, Subscription};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Default)]
+struct Counters {
+	// Keyed by chain and event kind.
+	contract_events: BTreeMap<(String, &'static str), u64>,
+	// Keyed by action kind and submission status.
+	actions: BTreeMap<(String, &'static str), u64>,
+}
+
+/// Counts of the contract events and action submissions published on the event bus.
+/// Clones share the same counters.
+#[derive(Debug, Clone, Default)]
+pub struct EventMetrics {
+	counters: Arc<Mutex<Counters>>,
+}
+
+impl EventMetrics {
+	fn lock(&self) -> std::sync::MutexGuard<'_, Counters> {
+		self.counters.lock().expect("Event metrics lock poisoned")
+	}
+
+	/// Count the events of the subscription until the bus is dropped.
+	pub async fn run(self, mut events: Subscription) {
+		while let Some(event) = events.recv().await {
+			self.record(&event);
+		}
+	}
+
+	fn record(&self, event: &BusEvent) {
+		let mut counters = self.lock();
+		match event {
+			BusEvent::Contract(event) => {
+				*counters
+					.contract_events
+					.entry((event.chain.to_string(), event.contract_event.kind()))
+					.or_default() += 1;
+			}
+			BusEvent::Action(action, status) => {
+				*counters.actions.entry((action.kind.to_string(), status.as_str())).or_default() +=
+					1;
+			}
+			BusEvent::StateChange(_) => {}
+		}
+	}
+
+	pub fn export_prometheus(&self) -> String {
+		let mut out = String::new();
+		let counters = self.lock();
+		let _ = writeln!(out, "# TYPE bridge_contract_events counter");
+		for ((chain, event), count) in &counters.contract_events {
+			let _ = writeln!(
+				out,
+				"bridge_contract_events{{chain=\"{chain}\",event=\"{event}\"}} {count}"
+			);
+		}
+		let _ = writeln!(out, "# TYPE bridge_action_submissions counter");
+		for ((action, status), count) in &counters.actions {
+			let _ = writeln!(
+				out,
+				"bridge_action_submissions{{action=\"{action}\",status=\"{status}\"}} {count}"
+			);
+		}
+		out
+	}
+}
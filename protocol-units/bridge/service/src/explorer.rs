@@ -0,0 +1,50 @@
+use bridge_util::types::BridgeTransferId;
+
+/// Builds the explorer url of the transactions of a chain from a template
+/// where `{hash}` is replaced by the transaction hash.
+#[derive(Debug, Clone, Default)]
+pub struct TxExplorer {
+	tx_url: Option<String>,
+}
+
+impl TxExplorer {
+	pub fn new(tx_url: Option<String>) -> Self {
+		TxExplorer { tx_url }
+	}
+
+	/// Url of the transaction given by its hex encoded hash, None if no explorer is configured.
+	pub fn tx_url(&self, tx_hash: &str) -> Option<String> {
+		self.tx_url.as_ref().map(|template| template.replace("{hash}", tx_hash))
+	}
+
+	/// The transaction hash followed by its explorer url if there is one.
+	pub fn display(&self, tx_hash: &str) -> String {
+		match self.tx_url(tx_hash) {
+			Some(url) => format!("{tx_hash} ({url})"),
+			None => tx_hash.to_string(),
+		}
+	}
+}
+
+/// Calldata suffix identifying the relayer and the transfer of an Ethereum transaction:
+/// the tag followed by the transfer id.
+pub fn calldata_tag(tag: &str, bridge_transfer_id: &BridgeTransferId) -> Vec<u8> {
+	[tag.as_bytes(), &bridge_transfer_id.0].concat()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_tx_explorer() {
+		let explorer = TxExplorer::new(Some("https://etherscan.io/tx/{hash}".to_string()));
+		assert_eq!(explorer.tx_url("0xab").as_deref(), Some("https://etherscan.io/tx/0xab"));
+		assert_eq!(explorer.display("0xab"), "0xab (https://etherscan.io/tx/0xab)");
+		assert_eq!(TxExplorer::default().display("0xab"), "0xab");
+
+		let tag = calldata_tag("relayer-1", &BridgeTransferId([7; 32]));
+		assert_eq!(&tag[..9], b"relayer-1");
+		assert_eq!(&tag[9..], &[7; 32]);
+	}
+}
@@ -0,0 +1,283 @@
+//! Fees paid by the relayer accounts on each transfer, on both chains, so the operators can
+//! check the relayer fees cover them. The Ethereum fees are converted to the bridged token with
+//! the price of the config or of a price feed, the Movement gas is paid in the bridged MOVE.
+//! The fees of a batch transaction are shared between its transfers.
+use crate::metrics::chain_label;
+use bridge_config::common::fee_tracking::FeeTrackingConfig;
+use bridge_config::common::refund::RefundConfig;
+use bridge_util::types::{BridgeTransferId, ChainId};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// Transfers whose costs are kept for the status API, the oldest are dropped beyond. The
+// aggregate costs still count them.
+const MAX_TRANSFERS: usize = 10_000;
+
+/// Fees paid by the relayer on a transfer.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TransferCost {
+	pub eth_fee_wei: u128,
+	pub movement_fee_octas: u128,
+	/// Transactions of the relayer on the transfer, a batch transaction counting for each of
+	/// its transfers.
+	pub transactions: u64,
+	/// Fees on both chains in the smallest unit of the token. Missing if Ethereum fees were
+	/// paid without a price of the token.
+	pub cost: Option<u128>,
+}
+
+/// Fees paid by the relayer on all the transfers since the start.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FeeSummary {
+	pub transfers: u64,
+	pub eth_fee_wei: u128,
+	pub movement_fee_octas: u128,
+	pub cost: Option<u128>,
+	/// Average cost of a transfer, the minimum relayer fee to break even.
+	pub average_cost: Option<u128>,
+	/// Price used for the conversion, 0 if unknown.
+	pub eth_wei_per_token_unit: u64,
+}
+
+#[derive(Debug, Default)]
+struct Costs {
+	transfers: HashMap<BridgeTransferId, TransferCost>,
+	order: VecDeque<BridgeTransferId>,
+	// Transfers with fees since the start.
+	total_transfers: u64,
+	total: TransferCost,
+	eth_wei_per_token_unit: u64,
+}
+
+impl Costs {
+	// Cost of the fees in the token with the current price.
+	fn cost(&self, eth_fee_wei: u128, movement_fee_octas: u128) -> Option<u128> {
+		let eth_cost = match (eth_fee_wei, self.eth_wei_per_token_unit) {
+			(0, _) => 0,
+			(_, 0) => return None,
+			(wei, price) => wei.div_ceil(u128::from(price)),
+		};
+		Some(eth_cost + movement_fee_octas)
+	}
+}
+
+/// Records the fees of the relayer transactions by transfer. Clones share the same costs.
+#[derive(Debug, Clone, Default)]
+pub struct FeeTracker {
+	costs: Arc<Mutex<Costs>>,
+}
+
+impl FeeTracker {
+	/// The tracker converting with the fixed price of the config, or of the refund config.
+	pub fn new(config: &FeeTrackingConfig, refund: &RefundConfig) -> Self {
+		let tracker = FeeTracker::default();
+		tracker.set_price(match config.eth_wei_per_token_unit {
+			0 => refund.eth_wei_per_token_unit,
+			price => price,
+		});
+		tracker
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<'_, Costs> {
+		self.costs.lock().expect("Fee tracker lock poisoned")
+	}
+
+	/// Price in wei of the smallest unit of the token, 0 if unknown.
+	pub fn price(&self) -> u64 {
+		self.lock().eth_wei_per_token_unit
+	}
+
+	/// Set the price in wei of the smallest unit of the token.
+	pub fn set_price(&self, eth_wei_per_token_unit: u64) {
+		self.lock().eth_wei_per_token_unit = eth_wei_per_token_unit;
+	}
+
+	/// Add the fee of a transaction on the transfer, in the native unit of the chain.
+	pub fn record(&self, chain: ChainId, transfer_id: BridgeTransferId, fee: u128) {
+		self.record_batch(chain, &[transfer_id], fee);
+	}
+
+	/// Add the fee of a batch transaction, shared evenly between its transfers.
+	pub fn record_batch(&self, chain: ChainId, transfer_ids: &[BridgeTransferId], fee: u128) {
+		let Some(count) = u128::try_from(transfer_ids.len()).ok().filter(|count| *count > 0) else {
+			return;
+		};
+		let mut costs = self.lock();
+		let add = |cost: &mut TransferCost, fee: u128| {
+			match chain {
+				ChainId::ONE => cost.eth_fee_wei += fee,
+				ChainId::TWO => cost.movement_fee_octas += fee,
+			}
+			cost.transactions += 1;
+		};
+		for (index, transfer_id) in transfer_ids.iter().enumerate() {
+			// The first transfer pays the remainder.
+			let share = fee / count + if index == 0 { fee % count } else { 0 };
+			if !costs.transfers.contains_key(transfer_id) {
+				if costs.order.len() >= MAX_TRANSFERS {
+					if let Some(oldest) = costs.order.pop_front() {
+						costs.transfers.remove(&oldest);
+					}
+				}
+				costs.order.push_back(*transfer_id);
+				costs.total_transfers += 1;
+			}
+			add(costs.transfers.entry(*transfer_id).or_default(), share);
+			add(&mut costs.total, share);
+		}
+	}
+
+	/// The fees paid on the transfer, if it's still kept.
+	pub fn transfer_cost(&self, transfer_id: &BridgeTransferId) -> Option<TransferCost> {
+		let costs = self.lock();
+		let transfer = costs.transfers.get(transfer_id)?;
+		Some(TransferCost {
+			cost: costs.cost(transfer.eth_fee_wei, transfer.movement_fee_octas),
+			..transfer.clone()
+		})
+	}
+
+	pub fn summary(&self) -> FeeSummary {
+		let costs = self.lock();
+		let cost = costs.cost(costs.total.eth_fee_wei, costs.total.movement_fee_octas);
+		FeeSummary {
+			transfers: costs.total_transfers,
+			eth_fee_wei: costs.total.eth_fee_wei,
+			movement_fee_octas: costs.total.movement_fee_octas,
+			cost,
+			average_cost: cost
+				.filter(|_| costs.total_transfers > 0)
+				.map(|cost| cost.div_ceil(u128::from(costs.total_transfers))),
+			eth_wei_per_token_unit: costs.eth_wei_per_token_unit,
+		}
+	}
+
+	pub fn export_prometheus(&self) -> String {
+		let mut out = String::new();
+		let summary = self.summary();
+		let _ = writeln!(out, "# TYPE bridge_tracked_fees_total counter");
+		for (chain, fee) in
+			[(ChainId::ONE, summary.eth_fee_wei), (ChainId::TWO, summary.movement_fee_octas)]
+		{
+			let _ = writeln!(
+				out,
+				"bridge_tracked_fees_total{{chain=\"{}\"}} {fee}",
+				chain_label(chain)
+			);
+		}
+		if let Some(average_cost) = summary.average_cost {
+			let _ = writeln!(out, "# TYPE bridge_average_transfer_cost gauge");
+			let _ = writeln!(out, "bridge_average_transfer_cost {average_cost}");
+		}
+		out
+	}
+}
+
+/// Reads the price of the token from the feed of the config into the tracker.
+pub struct PriceFeed {
+	url: String,
+	pointer: String,
+	interval: Duration,
+	tracker: FeeTracker,
+	client: reqwest::Client,
+}
+
+impl PriceFeed {
+	/// The feed of the config, `None` if no feed is configured.
+	pub fn new(config: &FeeTrackingConfig, tracker: FeeTracker) -> Option<Self> {
+		if config.price_url.is_empty() {
+			return None;
+		}
+		Some(PriceFeed {
+			url: config.price_url.clone(),
+			pointer: config.price_pointer.clone(),
+			interval: Duration::from_secs(config.price_refresh_secs.max(1)),
+			tracker,
+			client: reqwest::Client::new(),
+		})
+	}
+
+	/// Read the price on every interval. The last price read is kept if the feed fails.
+	pub async fn run(self) {
+		let mut interval = tokio::time::interval(self.interval);
+		loop {
+			interval.tick().await;
+			match self.read().await {
+				Ok(price) => self.tracker.set_price(price),
+				Err(err) => {
+					tracing::warn!("Failed to read the token price from {}: {err}", self.url)
+				}
+			}
+		}
+	}
+
+	async fn read(&self) -> Result<u64, anyhow::Error> {
+		let body: serde_json::Value =
+			self.client.get(&self.url).send().await?.error_for_status()?.json().await?;
+		parse_price(&body, &self.pointer)
+	}
+}
+
+// The price at the pointer of the response, a number or a decimal string.
+fn parse_price(body: &serde_json::Value, pointer: &str) -> Result<u64, anyhow::Error> {
+	let value = body
+		.pointer(pointer)
+		.ok_or_else(|| anyhow::anyhow!("No price at {pointer} in the response"))?;
+	let price = match value {
+		serde_json::Value::String(price) => price.parse().ok(),
+		value => value.as_u64(),
+	};
+	price.ok_or_else(|| anyhow::anyhow!("Invalid price {value}"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_fee_tracker() {
+		let tracker = FeeTracker::new(&FeeTrackingConfig::default(), &RefundConfig::default());
+		let (first, second) = (BridgeTransferId([1; 32]), BridgeTransferId([2; 32]));
+		tracker.clone().record(ChainId::TWO, first, 40);
+		tracker.record(ChainId::ONE, first, 10_050);
+		// The batch fee is shared, the first transfer pays the remainder.
+		tracker.record_batch(ChainId::TWO, &[first, second], 101);
+		tracker.record_batch(ChainId::ONE, &[], 1000);
+
+		let cost = tracker.transfer_cost(&first).unwrap();
+		assert_eq!((cost.eth_fee_wei, cost.movement_fee_octas, cost.transactions), (10_050, 91, 3));
+		// No price of the token: the Ethereum fees can't be converted.
+		assert_eq!(cost.cost, None);
+		assert_eq!(tracker.transfer_cost(&second).unwrap().cost, Some(50));
+		assert!(tracker.transfer_cost(&BridgeTransferId([3; 32])).is_none());
+
+		// 10050 wei are 101 token units, rounded up.
+		tracker.set_price(100);
+		assert_eq!(tracker.transfer_cost(&first).unwrap().cost, Some(192));
+		let summary = tracker.summary();
+		assert_eq!(summary.transfers, 2);
+		assert_eq!(summary.cost, Some(242));
+		assert_eq!(summary.average_cost, Some(121));
+		let export = tracker.export_prometheus();
+		assert!(export.contains("bridge_tracked_fees_total{chain=\"movement\"} 141\n"));
+		assert!(export.contains("bridge_average_transfer_cost 121\n"));
+
+		let config = FeeTrackingConfig { eth_wei_per_token_unit: 7, ..Default::default() };
+		assert_eq!(
+			FeeTracker::new(&config, &RefundConfig::default())
+				.summary()
+				.eth_wei_per_token_unit,
+			7
+		);
+		assert!(PriceFeed::new(&config, tracker).is_none());
+
+		let body = serde_json::json!({ "price": 42, "data": { "wei": "1000" } });
+		assert_eq!(parse_price(&body, "/price").unwrap(), 42);
+		assert_eq!(parse_price(&body, "/data/wei").unwrap(), 1000);
+		assert!(parse_price(&body, "/missing").is_err());
+		assert!(parse_price(&serde_json::json!({ "price": -1 }), "/price").is_err());
+	}
+}
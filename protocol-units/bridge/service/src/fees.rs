@@ -0,0 +1,227 @@
+use crate::chains::ethereum::client::EthClient;
+use alloy::primitives::Address;
+use bigdecimal::ToPrimitive;
+use bridge_config::common::fees::FeesConfig;
+use bridge_indexer_db::client::Client as IndexerClient;
+use bridge_util::types::Amount;
+use serde::Serialize;
+use std::time::Duration;
+
+const TOTAL_SHARES_BPS: u64 = 10_000;
+
+#[derive(Debug, Clone)]
+pub struct FeeRecipient {
+	pub name: String,
+	pub address: Address,
+	pub share_bps: u16,
+}
+
+/// Split the accrued fees among the configured recipients on a schedule.
+/// Each share is sent with a MOVE token transfer on Eth and recorded in the indexer ledger,
+/// as pending before the transfer is sent so a share is never sent twice.
+#[derive(Debug, Clone)]
+pub struct FeeDistributor {
+	recipients: Vec<FeeRecipient>,
+	interval: Duration,
+}
+
+impl TryFrom<&FeesConfig> for FeeDistributor {
+	type Error = anyhow::Error;
+
+	fn try_from(conf: &FeesConfig) -> Result<Self, Self::Error> {
+		let recipients = conf
+			.recipients
+			.iter()
+			.map(|recipient| {
+				Ok(FeeRecipient {
+					name: recipient.name.clone(),
+					address: recipient.eth_address.parse()?,
+					share_bps: recipient.share_bps,
+				})
+			})
+			.collect::<Result<Vec<_>, anyhow::Error>>()?;
+		let total: u64 = recipients.iter().map(|recipient| recipient.share_bps as u64).sum();
+		if !recipients.is_empty() && total != TOTAL_SHARES_BPS {
+			anyhow::bail!("Fee recipient shares sum to {total} bps, expected {TOTAL_SHARES_BPS}");
+		}
+		Ok(FeeDistributor {
+			recipients,
+			interval: Duration::from_secs(conf.distribution_interval_secs),
+		})
+	}
+}
+
+impl FeeDistributor {
+	pub fn is_enabled(&self) -> bool {
+		!self.recipients.is_empty()
+	}
+
+	/// Split the amount by share. The rounding remainder goes to the first recipient.
+	pub fn split(&self, accrued: Amount) -> Vec<(&FeeRecipient, Amount)> {
+		let mut shares: Vec<_> = self
+			.recipients
+			.iter()
+			.map(|recipient| {
+				let share =
+					accrued.0 as u128 * recipient.share_bps as u128 / TOTAL_SHARES_BPS as u128;
+				(recipient, Amount(share as u64))
+			})
+			.collect();
+		let distributed: u64 = shares.iter().map(|(_, amount)| amount.0).sum();
+		if let Some((_, first)) = shares.first_mut() {
+			first.0 += accrued.0 - distributed;
+		}
+		shares
+	}
+
+	/// Distribute the fees accrued since the last distribution.
+	pub async fn distribute(
+		&self,
+		eth_client: &EthClient,
+		indexer_db_client: &mut IndexerClient,
+	) -> Result<(), anyhow::Error> {
+		// The transfer of a distribution left pending may be on chain, it's counted as
+		// distributed until it's checked by hand.
+		for pending in indexer_db_client.get_pending_fee_distributions()? {
+			tracing::error!(
+				target: "bridge_alert",
+				"Fee distribution {} of {} to {} (0x{}) pending since {}, check its transfer on chain",
+				pending.id,
+				pending.amount,
+				pending.recipient_name,
+				pending.recipient,
+				pending.created_at
+			);
+		}
+		let accrued = indexer_db_client.undistributed_fees()?;
+		let accrued = Amount(accrued.to_u64().ok_or_else(|| {
+			anyhow::anyhow!("Undistributed fees {accrued} doesn't fit in an amount")
+		})?);
+		if accrued.0 == 0 {
+			return Ok(());
+		}
+		tracing::info!("Distributing {} accrued fees", accrued.0);
+		for (recipient, amount) in self.split(accrued) {
+			if amount.0 == 0 {
+				continue;
+			}
+			let id = indexer_db_client.insert_pending_fee_distribution(
+				&recipient.name,
+				recipient.address.to_vec(),
+				amount,
+			)?;
+			let tx_hash = eth_client.transfer_move_token(recipient.address, amount).await?;
+			let tx = eth_client.tx_explorer().display(&format!("0x{}", hex::encode(&tx_hash)));
+			indexer_db_client.mark_fee_distribution_sent(id, tx_hash)?;
+			tracing::info!(
+				"Distributed {} fees to {} ({}) in {tx}",
+				amount.0,
+				recipient.name,
+				recipient.address
+			);
+		}
+		Ok(())
+	}
+
+	/// Distribute the fees every interval.
+	pub async fn run(self, eth_client: EthClient, mut indexer_db_client: IndexerClient) {
+		let mut interval = tokio::time::interval(self.interval);
+		loop {
+			interval.tick().await;
+			if let Err(err) = self.distribute(&eth_client, &mut indexer_db_client).await {
+				tracing::error!("Fee distribution failed: {err}");
+			}
+		}
+	}
+}
+
+/// Relayer fee deducted from the bridged amount before the lock on the destination chain:
+/// a flat part and a part in basis points of the amount.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RelayerFeeSchedule {
+	pub flat: u64,
+	pub bps: u16,
+}
+
+/// Fee of a bridged amount and the amount received on the destination chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct FeeQuote {
+	pub amount: u64,
+	pub fee: u64,
+	pub net_amount: u64,
+}
+
+impl From<&FeesConfig> for RelayerFeeSchedule {
+	fn from(conf: &FeesConfig) -> Self {
+		RelayerFeeSchedule { flat: conf.relayer_fee_flat, bps: conf.relayer_fee_bps }
+	}
+}
+
+impl RelayerFeeSchedule {
+	/// The fee of the amount, at most the amount. The proportional part is rounded down.
+	pub fn quote_fee(&self, amount: Amount) -> FeeQuote {
+		let proportional = u128::from(amount.0) * u128::from(self.bps) / TOTAL_SHARES_BPS as u128;
+		let fee = (u128::from(self.flat) + proportional).min(u128::from(amount.0)) as u64;
+		FeeQuote { amount: amount.0, fee, net_amount: amount.0 - fee }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bridge_config::common::fees::FeeRecipientConfig;
+
+	fn recipient(name: &str, share_bps: u16) -> FeeRecipientConfig {
+		FeeRecipientConfig {
+			name: name.to_string(),
+			eth_address: "0x90f79bf6eb2c4f870365e785982e1f101e93b906".to_string(),
+			share_bps,
+		}
+	}
+
+	#[test]
+	fn test_split_fees() {
+		let conf = FeesConfig {
+			distribution_interval_secs: 60,
+			recipients: vec![
+				recipient("treasury", 5000),
+				recipient("operator", 3333),
+				recipient("insurance", 1667),
+			],
+			..FeesConfig::default()
+		};
+		let distributor = FeeDistributor::try_from(&conf).unwrap();
+		let shares: Vec<_> = distributor
+			.split(Amount(1001))
+			.into_iter()
+			.map(|(r, a)| (r.name.as_str(), a.0))
+			.collect();
+		assert_eq!(shares, vec![("treasury", 502), ("operator", 333), ("insurance", 166)]);
+	}
+
+	#[test]
+	fn test_quote_fee() {
+		let conf =
+			FeesConfig { relayer_fee_flat: 10, relayer_fee_bps: 25, ..FeesConfig::default() };
+		let schedule = RelayerFeeSchedule::from(&conf);
+		assert_eq!(
+			schedule.quote_fee(Amount(10_000)),
+			FeeQuote { amount: 10_000, fee: 35, net_amount: 9_965 }
+		);
+		// 0.25% of 399 is rounded down.
+		assert_eq!(schedule.quote_fee(Amount(399)).fee, 10);
+		// The fee can't exceed the amount.
+		assert_eq!(schedule.quote_fee(Amount(8)).net_amount, 0);
+		assert_eq!(RelayerFeeSchedule::default().quote_fee(Amount(8)).net_amount, 8);
+	}
+
+	#[test]
+	fn test_invalid_shares() {
+		let conf = FeesConfig {
+			distribution_interval_secs: 60,
+			recipients: vec![recipient("treasury", 5000), recipient("operator", 4000)],
+			..FeesConfig::default()
+		};
+		assert!(FeeDistributor::try_from(&conf).is_err());
+	}
+}
@@ -0,0 +1,216 @@
+//! Forensics mode: the relayer processes a fixed range of Ethereum blocks and Movement
+//! versions instead of following the head of the chains, with the submissions disabled.
+//! The events of the range populate the indexer and the reports like in a normal run.
+use anyhow::anyhow;
+use bridge_config::common::forensics::ForensicsConfig;
+use bridge_util::chains::bridge_contracts::{BatchResults, BridgeContract, BridgeContractResult};
+use bridge_util::types::{
+	Amount, BridgeAddress, BridgeTransferDetails, BridgeTransferDetailsCounterparty,
+	BridgeTransferId, HashLock, HashLockPreImage, LockTransfer,
+};
+
+/// Range of blocks or versions processed, bounds included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScanRange {
+	pub from: u64,
+	pub to: u64,
+}
+
+impl ScanRange {
+	pub fn new(from: u64, to: u64) -> Result<Self, anyhow::Error> {
+		if from > to {
+			return Err(anyhow!("Invalid forensics range: {from} is after {to}"));
+		}
+		Ok(ScanRange { from, to })
+	}
+
+	pub fn contains(&self, position: u64) -> bool {
+		self.from <= position && position <= self.to
+	}
+}
+
+/// Ranges processed on each chain in forensics mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ForensicsMode {
+	pub eth_blocks: ScanRange,
+	pub movement_versions: ScanRange,
+}
+
+impl ForensicsMode {
+	/// The ranges of the config, none if the relayer follows the head of the chains.
+	pub fn from_config(config: &ForensicsConfig) -> Result<Option<Self>, anyhow::Error> {
+		if !config.enabled {
+			return Ok(None);
+		}
+		Ok(Some(ForensicsMode {
+			eth_blocks: ScanRange::new(config.eth_from_block, config.eth_to_block)?,
+			movement_versions: ScanRange::new(config.mvt_from_version, config.mvt_to_version)?,
+		}))
+	}
+}
+
+/// Client reading the contracts with the inner client. In forensics mode its submissions are
+/// logged and dropped, the transfers of the range are only replayed.
+#[derive(Clone)]
+pub struct ForensicsClient<C> {
+	inner: C,
+	forensics: bool,
+}
+
+impl<C> ForensicsClient<C> {
+	pub fn new(inner: C, forensics: bool) -> Self {
+		ForensicsClient { inner, forensics }
+	}
+
+	fn skipped(&self, operation: &str, bridge_transfer_id: Option<BridgeTransferId>) -> bool {
+		if self.forensics {
+			let transfer =
+				bridge_transfer_id.map(|id| format!(" of transfer {id}")).unwrap_or_default();
+			tracing::info!("Forensics mode: {operation}{transfer} not submitted");
+		}
+		self.forensics
+	}
+}
+
+#[async_trait::async_trait]
+impl<A, C> BridgeContract<A> for ForensicsClient<C>
+where
+	A: Send + Sync + 'static,
+	C: BridgeContract<A>,
+{
+	async fn initiate_bridge_transfer(
+		&mut self,
+		initiator: BridgeAddress<A>,
+		recipient: BridgeAddress<Vec<u8>>,
+		hash_lock: HashLock,
+		amount: Amount,
+	) -> BridgeContractResult<()> {
+		if self.skipped("initiate", None) {
+			return Ok(());
+		}
+		self.inner
+			.initiate_bridge_transfer(initiator, recipient, hash_lock, amount)
+			.await
+	}
+
+	async fn initiator_complete_bridge_transfer(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+		secret: HashLockPreImage,
+	) -> BridgeContractResult<()> {
+		if self.skipped("complete initiator", Some(bridge_transfer_id)) {
+			return Ok(());
+		}
+		self.inner.initiator_complete_bridge_transfer(bridge_transfer_id, secret).await
+	}
+
+	async fn counterparty_complete_bridge_transfer(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+		secret: HashLockPreImage,
+	) -> BridgeContractResult<()> {
+		if self.skipped("complete counterparty", Some(bridge_transfer_id)) {
+			return Ok(());
+		}
+		self.inner
+			.counterparty_complete_bridge_transfer(bridge_transfer_id, secret)
+			.await
+	}
+
+	async fn refund_bridge_transfer(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> BridgeContractResult<()> {
+		if self.skipped("refund", Some(bridge_transfer_id)) {
+			return Ok(());
+		}
+		self.inner.refund_bridge_transfer(bridge_transfer_id).await
+	}
+
+	async fn get_bridge_transfer_details_initiator(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> BridgeContractResult<Option<BridgeTransferDetails<A>>> {
+		self.inner.get_bridge_transfer_details_initiator(bridge_transfer_id).await
+	}
+
+	async fn get_bridge_transfer_details_counterparty(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> BridgeContractResult<Option<BridgeTransferDetailsCounterparty<A>>> {
+		self.inner.get_bridge_transfer_details_counterparty(bridge_transfer_id).await
+	}
+
+	async fn lock_bridge_transfer(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+		hash_lock: HashLock,
+		initiator: BridgeAddress<Vec<u8>>,
+		recipient: BridgeAddress<A>,
+		amount: Amount,
+	) -> BridgeContractResult<()> {
+		if self.skipped("lock", Some(bridge_transfer_id)) {
+			return Ok(());
+		}
+		self.inner
+			.lock_bridge_transfer(bridge_transfer_id, hash_lock, initiator, recipient, amount)
+			.await
+	}
+
+	async fn abort_bridge_transfer(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> BridgeContractResult<()> {
+		if self.skipped("abort", Some(bridge_transfer_id)) {
+			return Ok(());
+		}
+		self.inner.abort_bridge_transfer(bridge_transfer_id).await
+	}
+
+	async fn complete_bridge_transfers_batch(
+		&mut self,
+		transfers: Vec<(BridgeTransferId, HashLockPreImage)>,
+	) -> BridgeContractResult<BatchResults> {
+		if self.skipped(&format!("complete initiator of {} transfers", transfers.len()), None) {
+			return Ok(transfers.iter().map(|_| Ok(())).collect());
+		}
+		self.inner.complete_bridge_transfers_batch(transfers).await
+	}
+
+	async fn lock_bridge_transfers_batch(
+		&mut self,
+		transfers: Vec<LockTransfer<A>>,
+	) -> BridgeContractResult<BatchResults> {
+		if self.skipped(&format!("lock of {} transfers", transfers.len()), None) {
+			return Ok(transfers.iter().map(|_| Ok(())).collect());
+		}
+		self.inner.lock_bridge_transfers_batch(transfers).await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_forensics_mode() {
+		let mut config = ForensicsConfig {
+			enabled: false,
+			eth_from_block: 100,
+			eth_to_block: 200,
+			mvt_from_version: 1_000,
+			mvt_to_version: 1_000,
+		};
+		assert_eq!(ForensicsMode::from_config(&config).unwrap(), None);
+
+		config.enabled = true;
+		let mode = ForensicsMode::from_config(&config).unwrap().unwrap();
+		assert_eq!(mode.eth_blocks, ScanRange { from: 100, to: 200 });
+		assert!(mode.eth_blocks.contains(100) && mode.eth_blocks.contains(200));
+		assert!(!mode.eth_blocks.contains(99) && !mode.eth_blocks.contains(201));
+		assert!(mode.movement_versions.contains(1_000));
+
+		config.eth_from_block = 201;
+		assert!(ForensicsMode::from_config(&config).is_err());
+	}
+}
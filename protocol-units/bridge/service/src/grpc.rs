@@ -1,9 +1,19 @@
-use crate::chains::ethereum::client::EthClient;
+use crate::admin_auth::AdminAuth;
+use crate::chains::ethereum::{client::EthClient, types::EthAddress};
+use crate::chains::movement::utils::MovementAddress;
+use crate::in_flight::{InFlightTransfer, InFlightTransfers};
+use crate::live_updates::{LiveFilter, LiveUpdate, LiveUpdates};
+use crate::pause::{PauseRequest, PauseScope, PauseSwitches};
 use bridge_grpc::{
-	bridge_server::Bridge, health_check_response::ServingStatus, health_server::Health,
-	BridgeTransferDetailsResponse, GetBridgeTransferDetailsRequest, HealthCheckRequest,
-	HealthCheckResponse,
+	bridge_operator_server::BridgeOperator, bridge_server::Bridge,
+	health_check_response::ServingStatus, health_server::Health, BridgeTransferDetailsResponse,
+	ForceRefundRequest, ForceRefundResponse, GetBridgeTransferDetailsRequest, GetTransferRequest,
+	HealthCheckRequest, HealthCheckResponse, ListTransfersRequest, ListTransfersResponse,
+	PauseRelayerRequest, PauseRelayerResponse, TransferInfo, TransferUpdate, WatchTransfersRequest,
 };
+use bridge_util::chains::bridge_contracts::BridgeContract;
+use bridge_util::states::TransferStateType;
+use bridge_util::types::{BridgeTransferId, ChainId, TransferDirection};
 use futures::Stream;
 use std::pin::Pin;
 use tokio::sync::mpsc;
@@ -98,3 +108,219 @@ impl Bridge for EthClient {
 		unimplemented!()
 	}
 }
+
+/// gRPC service of the operators: the transfers in flight, the forced refunds, the pauses of
+/// the relayer and the stream of the changes of state for the dashboards. Every call is
+/// authenticated with the bearer token of an operator in its `authorization` metadata.
+#[derive(Clone)]
+pub struct OperatorService<E, M> {
+	admin_auth: AdminAuth,
+	in_flight: InFlightTransfers,
+	pause_switches: PauseSwitches,
+	live_updates: Option<LiveUpdates>,
+	eth_client: E,
+	movement_client: M,
+}
+
+impl<E, M> OperatorService<E, M> {
+	pub fn new(
+		in_flight: InFlightTransfers,
+		pause_switches: PauseSwitches,
+		live_updates: Option<LiveUpdates>,
+		eth_client: E,
+		movement_client: M,
+	) -> Self {
+		OperatorService {
+			admin_auth: AdminAuth::default(),
+			in_flight,
+			pause_switches,
+			live_updates,
+			eth_client,
+			movement_client,
+		}
+	}
+
+	pub fn with_admin_auth(mut self, admin_auth: AdminAuth) -> Self {
+		self.admin_auth = admin_auth;
+		self
+	}
+
+	// The operator of a call, authenticated with the bearer token of its metadata.
+	fn operator<T>(&self, request: &Request<T>) -> Result<String, Status> {
+		let authorization =
+			request.metadata().get("authorization").and_then(|value| value.to_str().ok());
+		self.admin_auth
+			.authenticate(authorization)
+			.map_err(|err| Status::unauthenticated(err.to_string()))
+	}
+}
+
+const TRANSFER_STATES: [TransferStateType; 9] = [
+	TransferStateType::Initialized,
+	TransferStateType::PendingApproval,
+	TransferStateType::Locked,
+	TransferStateType::SecretReceived,
+	TransferStateType::Refund,
+	TransferStateType::LockAborted,
+	TransferStateType::Completed,
+	TransferStateType::Refunded,
+	TransferStateType::Aborted,
+];
+
+fn parse_transfer_id(bytes: &[u8]) -> Result<BridgeTransferId, Status> {
+	<[u8; 32]>::try_from(bytes).map(BridgeTransferId).map_err(|_| {
+		Status::invalid_argument(format!("Invalid transfer id of {} bytes", bytes.len()))
+	})
+}
+
+fn transfer_info(transfer: InFlightTransfer) -> TransferInfo {
+	TransferInfo {
+		bridge_transfer_id: transfer.transfer_id.0.to_vec(),
+		init_chain: transfer.init_chain.to_string(),
+		state: transfer.state.map(|state| state.to_string()).unwrap_or_default(),
+		initiator: transfer.initiator,
+		recipient: transfer.recipient,
+		hash_lock: transfer.hash_lock.0.to_vec(),
+		time_lock: transfer.time_lock,
+		amount: transfer.amount,
+		sequence: transfer.sequence,
+		updated_at_secs: transfer.updated_at_secs,
+	}
+}
+
+fn transfer_update(update: LiveUpdate) -> TransferUpdate {
+	let notification = update.notification;
+	TransferUpdate {
+		bridge_transfer_id: hex::decode(notification.transfer_id.trim_start_matches("0x"))
+			.unwrap_or_default(),
+		sequence: notification.sequence,
+		previous_state: notification.previous_state.unwrap_or_default(),
+		new_state: notification.new_state,
+		cause: notification
+			.cause
+			.map(|cause| format!("{} on {}", cause.event, cause.chain))
+			.unwrap_or_default(),
+		timestamp_secs: notification.timestamp_secs,
+	}
+}
+
+#[tonic::async_trait]
+impl<E, M> BridgeOperator for OperatorService<E, M>
+where
+	E: BridgeContract<EthAddress> + 'static,
+	M: BridgeContract<MovementAddress> + 'static,
+{
+	type WatchTransfersStream =
+		Pin<Box<dyn Stream<Item = Result<TransferUpdate, Status>> + Send + 'static>>;
+
+	async fn list_transfers(
+		&self,
+		request: Request<ListTransfersRequest>,
+	) -> Result<Response<ListTransfersResponse>, Status> {
+		self.operator(&request)?;
+		let state = request.into_inner().state;
+		let state = match state.as_str() {
+			"" => None,
+			state => Some(
+				TRANSFER_STATES
+					.into_iter()
+					.find(|known| known.to_string() == state)
+					.ok_or_else(|| Status::invalid_argument(format!("Unknown state {state}")))?,
+			),
+		};
+		let transfers = self.in_flight.list(state).into_iter().map(transfer_info).collect();
+		Ok(Response::new(ListTransfersResponse { transfers }))
+	}
+
+	async fn get_transfer(
+		&self,
+		request: Request<GetTransferRequest>,
+	) -> Result<Response<TransferInfo>, Status> {
+		self.operator(&request)?;
+		let transfer_id = parse_transfer_id(&request.into_inner().bridge_transfer_id)?;
+		match self.in_flight.get(transfer_id) {
+			Some(transfer) => Ok(Response::new(transfer_info(transfer))),
+			None => Err(Status::not_found(format!("No transfer {transfer_id} in flight"))),
+		}
+	}
+
+	async fn force_refund(
+		&self,
+		request: Request<ForceRefundRequest>,
+	) -> Result<Response<ForceRefundResponse>, Status> {
+		let operator = self.operator(&request)?;
+		let transfer_id = parse_transfer_id(&request.into_inner().bridge_transfer_id)?;
+		let Some(transfer) = self.in_flight.get(transfer_id) else {
+			return Err(Status::not_found(format!("No transfer {transfer_id} in flight")));
+		};
+		tracing::info!(
+			target: "bridge_audit",
+			"Forced refund of transfer {transfer_id} on {} by {}",
+			transfer.init_chain,
+			operator
+		);
+		// The initiator contract rejects the refund before the time lock expires.
+		let result = match transfer.init_chain {
+			ChainId::ONE => self.eth_client.clone().refund_bridge_transfer(transfer_id).await,
+			ChainId::TWO => self.movement_client.clone().refund_bridge_transfer(transfer_id).await,
+		};
+		result
+			.map(|()| Response::new(ForceRefundResponse {}))
+			.map_err(|err| Status::failed_precondition(format!("Refund failed: {err}")))
+	}
+
+	async fn pause_relayer(
+		&self,
+		request: Request<PauseRelayerRequest>,
+	) -> Result<Response<PauseRelayerResponse>, Status> {
+		let operator = self.operator(&request)?;
+		let request = request.into_inner();
+		let direction = match request.direction.as_str() {
+			"" => None,
+			"eth_to_movement" => Some(TransferDirection::EthToMovement),
+			"movement_to_eth" => Some(TransferDirection::MovementToEth),
+			direction => {
+				return Err(Status::invalid_argument(format!("Unknown direction {direction}")))
+			}
+		};
+		let token = Some(request.token).filter(|token| !token.is_empty());
+		self.pause_switches.apply(PauseRequest {
+			scope: PauseScope { token, direction },
+			paused: request.paused,
+			operator,
+		});
+		let active_pauses = self
+			.pause_switches
+			.active()
+			.into_iter()
+			.map(|pause| format!("{} by {}", pause.scope, pause.operator))
+			.collect();
+		Ok(Response::new(PauseRelayerResponse { active_pauses }))
+	}
+
+	async fn watch_transfers(
+		&self,
+		request: Request<WatchTransfersRequest>,
+	) -> Result<Response<Self::WatchTransfersStream>, Status> {
+		self.operator(&request)?;
+		let Some(live_updates) = &self.live_updates else {
+			return Err(Status::unavailable("Live updates are not enabled"));
+		};
+		let request = request.into_inner();
+		let transfer_ids = request
+			.bridge_transfer_ids
+			.iter()
+			.map(|id| parse_transfer_id(id))
+			.collect::<Result<Vec<_>, _>>()?;
+		let address = Some(request.address.as_slice()).filter(|address| !address.is_empty());
+		let filter = LiveFilter::new(transfer_ids, address);
+		let updates = live_updates
+			.subscribe()
+			.ok_or_else(|| Status::resource_exhausted("Too many live update connections"))?;
+		let stream = futures::stream::unfold((updates, filter), |(mut updates, filter)| async {
+			let update = updates.next(&filter).await?;
+			Some((Ok(transfer_update(update)), (updates, filter)))
+		});
+		Ok(Response::new(Box::pin(stream)))
+	}
+}
@@ -0,0 +1,294 @@
+use crate::fee_tracker::FeeTracker;
+use crate::liquidity::RelayerLiquidity;
+use crate::pause::PauseSwitches;
+use bridge_config::common::guards::GuardsConfig;
+use bridge_config::Config;
+use bridge_util::reason::ReasonCode;
+use bridge_util::types::TransferDirection;
+use serde::{Deserialize, Serialize};
+
+const ETH_ADDRESS_LEN: usize = 20;
+const MOVEMENT_ADDRESS_LEN: usize = 32;
+const WEI_PER_GWEI: u128 = 1_000_000_000;
+
+/// A transfer a user is about to initiate.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProspectiveTransfer {
+	pub direction: TransferDirection,
+	pub token: String,
+	pub amount: u64,
+	/// Hex encoded recipient address on the counterparty chain.
+	pub recipient: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GuardFailure {
+	pub guard: &'static str,
+	pub code: ReasonCode,
+	pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PrecheckResult {
+	pub pass: bool,
+	/// The transfer is above the auto approval limit and will wait for an operator decision.
+	pub requires_approval: bool,
+	pub failures: Vec<GuardFailure>,
+}
+
+/// The transfer directions the bridge relays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct EnabledDirections {
+	pub eth_to_movement: bool,
+	pub movement_to_eth: bool,
+}
+
+impl Default for EnabledDirections {
+	fn default() -> Self {
+		EnabledDirections { eth_to_movement: true, movement_to_eth: true }
+	}
+}
+
+impl From<&GuardsConfig> for EnabledDirections {
+	fn from(config: &GuardsConfig) -> Self {
+		EnabledDirections {
+			eth_to_movement: config.eth_to_movement_enabled,
+			movement_to_eth: config.movement_to_eth_enabled,
+		}
+	}
+}
+
+impl EnabledDirections {
+	pub fn is_enabled(&self, direction: TransferDirection) -> bool {
+		match direction {
+			TransferDirection::EthToMovement => self.eth_to_movement,
+			TransferDirection::MovementToEth => self.movement_to_eth,
+		}
+	}
+}
+
+/// Guards applied to a transfer before it is initiated.
+#[derive(Debug, Clone)]
+pub struct TransferGuards {
+	supported_tokens: Vec<String>,
+	min_transfer_amount: u64,
+	max_transfer_amount: u64,
+	auto_approval_limit: u64,
+	recipient_allowlist: Vec<Vec<u8>>,
+	enabled_directions: EnabledDirections,
+	pause_switches: PauseSwitches,
+	liquidity: RelayerLiquidity,
+	/// Not checked if 0.
+	max_transfer_value_gwei: u64,
+	price: FeeTracker,
+}
+
+impl Default for TransferGuards {
+	fn default() -> Self {
+		TransferGuards::from(&Config::default())
+	}
+}
+
+impl From<&Config> for TransferGuards {
+	fn from(config: &Config) -> Self {
+		let recipient_allowlist = config
+			.guards
+			.recipient_allowlist
+			.iter()
+			.filter_map(|address| match decode_address(address) {
+				Ok(bytes) => Some(bytes),
+				Err(err) => {
+					tracing::warn!("Ignore recipient allowlist entry {address}: {err}");
+					None
+				}
+			})
+			.collect();
+		TransferGuards {
+			supported_tokens: vec![config.eth.asset.clone()],
+			min_transfer_amount: config.guards.min_transfer_amount,
+			max_transfer_amount: config.guards.max_transfer_amount,
+			auto_approval_limit: config.guards.auto_approval_limit,
+			recipient_allowlist,
+			enabled_directions: EnabledDirections::from(&config.guards),
+			pause_switches: PauseSwitches::new(config.eth.asset.clone()),
+			liquidity: RelayerLiquidity::default(),
+			max_transfer_value_gwei: config.guards.max_transfer_value_gwei,
+			price: FeeTracker::default(),
+		}
+	}
+}
+
+impl TransferGuards {
+	/// Run all guards on the transfer and collect the failing ones.
+	pub fn precheck(&self, transfer: &ProspectiveTransfer) -> PrecheckResult {
+		let failures: Vec<_> = [
+			self.check_direction(transfer),
+			self.check_pause(transfer),
+			self.check_token(transfer),
+			self.check_amount(transfer),
+			self.check_recipient(transfer),
+			self.check_liquidity(transfer),
+			self.check_value(transfer),
+		]
+		.into_iter()
+		.flatten()
+		.collect();
+		PrecheckResult {
+			pass: failures.is_empty(),
+			requires_approval: transfer.amount > self.auto_approval_limit,
+			failures,
+		}
+	}
+
+	pub fn enabled_directions(&self) -> EnabledDirections {
+		self.enabled_directions
+	}
+
+	/// Set the pause switches shared with the relayer loop.
+	pub fn with_pause_switches(mut self, pause_switches: PauseSwitches) -> Self {
+		self.pause_switches = pause_switches;
+		self
+	}
+
+	/// Set the relayer balances read in the background.
+	pub fn with_liquidity(mut self, liquidity: RelayerLiquidity) -> Self {
+		self.liquidity = liquidity;
+		self
+	}
+
+	/// Set the tracker holding the token price of the config or of the price feed.
+	pub fn with_price(mut self, price: FeeTracker) -> Self {
+		self.price = price;
+		self
+	}
+
+	fn check_direction(&self, transfer: &ProspectiveTransfer) -> Option<GuardFailure> {
+		(!self.enabled_directions.is_enabled(transfer.direction)).then(|| GuardFailure {
+			guard: "direction",
+			code: ReasonCode::DirectionDisabled,
+			reason: format!("{} transfers are currently disabled", transfer.direction),
+		})
+	}
+
+	fn check_pause(&self, transfer: &ProspectiveTransfer) -> Option<GuardFailure> {
+		let scope = self.pause_switches.paused_by(&transfer.token, transfer.direction)?;
+		Some(GuardFailure {
+			guard: "pause",
+			code: ReasonCode::Paused,
+			reason: format!("Transfers of {scope} are paused"),
+		})
+	}
+
+	fn check_token(&self, transfer: &ProspectiveTransfer) -> Option<GuardFailure> {
+		(!self.supported_tokens.contains(&transfer.token)).then(|| GuardFailure {
+			guard: "token",
+			code: ReasonCode::UnsupportedToken,
+			reason: format!("Token {} is not supported by the bridge", transfer.token),
+		})
+	}
+
+	fn check_amount(&self, transfer: &ProspectiveTransfer) -> Option<GuardFailure> {
+		if transfer.amount < self.min_transfer_amount {
+			Some(GuardFailure {
+				guard: "amount_limit",
+				code: ReasonCode::AmountTooLow,
+				reason: format!(
+					"Amount {} is below the minimum transfer amount {}",
+					transfer.amount, self.min_transfer_amount
+				),
+			})
+		} else if transfer.amount > self.max_transfer_amount {
+			Some(GuardFailure {
+				guard: "amount_limit",
+				code: ReasonCode::AmountTooHigh,
+				reason: format!(
+					"Amount {} is above the maximum transfer amount {}",
+					transfer.amount, self.max_transfer_amount
+				),
+			})
+		} else {
+			None
+		}
+	}
+
+	fn check_recipient(&self, transfer: &ProspectiveTransfer) -> Option<GuardFailure> {
+		let recipient = match decode_address(&transfer.recipient) {
+			Ok(recipient) => recipient,
+			Err(err) => {
+				return Some(GuardFailure {
+					guard: "recipient",
+					code: ReasonCode::InvalidRecipient,
+					reason: err,
+				})
+			}
+		};
+		let expected_len = match transfer.direction {
+			TransferDirection::EthToMovement => MOVEMENT_ADDRESS_LEN,
+			TransferDirection::MovementToEth => ETH_ADDRESS_LEN,
+		};
+		if recipient.len() != expected_len {
+			return Some(GuardFailure {
+				guard: "recipient",
+				code: ReasonCode::InvalidRecipient,
+				reason: format!(
+					"Recipient must be {expected_len} bytes for {} transfer, got {}",
+					transfer.direction,
+					recipient.len()
+				),
+			});
+		}
+		if !self.recipient_allowlist.is_empty() && !self.recipient_allowlist.contains(&recipient) {
+			return Some(GuardFailure {
+				guard: "recipient_allowlist",
+				code: ReasonCode::RecipientNotAllowed,
+				reason: format!("Recipient {} is not in the allowlist", transfer.recipient),
+			});
+		}
+		None
+	}
+
+	// Not checked until the balance of the relayer on the counterparty chain is read.
+	fn check_liquidity(&self, transfer: &ProspectiveTransfer) -> Option<GuardFailure> {
+		let available = self.liquidity.available(transfer.direction)?;
+		(transfer.amount > available).then(|| GuardFailure {
+			guard: "liquidity",
+			code: ReasonCode::Liquidity,
+			reason: format!(
+				"Amount {} is above the {available} the relayer can lock for {} transfers",
+				transfer.amount, transfer.direction
+			),
+		})
+	}
+
+	fn check_value(&self, transfer: &ProspectiveTransfer) -> Option<GuardFailure> {
+		if self.max_transfer_value_gwei == 0 {
+			return None;
+		}
+		let price = self.price.price();
+		if price == 0 {
+			return Some(GuardFailure {
+				guard: "value_limit",
+				code: ReasonCode::Unavailable,
+				reason: "The price of the token is unknown, the value can't be checked".to_string(),
+			});
+		}
+		let value_wei = u128::from(transfer.amount).saturating_mul(u128::from(price));
+		(value_wei > u128::from(self.max_transfer_value_gwei) * WEI_PER_GWEI).then(|| {
+			GuardFailure {
+				guard: "value_limit",
+				code: ReasonCode::ValueTooHigh,
+				reason: format!(
+					"Value {} gwei of amount {} is above the maximum transfer value {} gwei",
+					value_wei / WEI_PER_GWEI,
+					transfer.amount,
+					self.max_transfer_value_gwei
+				),
+			}
+		})
+	}
+}
+
+fn decode_address(address: &str) -> Result<Vec<u8>, String> {
+	let address = address.strip_prefix("0x").unwrap_or(address);
+	hex::decode(address).map_err(|err| format!("Invalid hex address {address}: {err}"))
+}
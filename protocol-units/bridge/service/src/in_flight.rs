@@ -0,0 +1,247 @@
+//! Transfers in flight, followed on the event bus so the operators can inspect them without
+//! the indexer db.
+use crate::event_bus::{BusEvent, Subscription};
+use crate::fee_tracker::TransferCost;
+use crate::latency::LatencyBreakdown;
+use bridge_util::chains::bridge_contracts::BridgeContractEvent;
+use bridge_util::states::TransferStateType;
+use bridge_util::types::{BridgeTransferId, ChainId, HashLock, HashLockPreImage};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A transfer not done yet, as last seen by the relayer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InFlightTransfer {
+	pub transfer_id: BridgeTransferId,
+	/// Chain of the initiation, or of the lock when the initiation wasn't seen.
+	pub init_chain: ChainId,
+	pub state: Option<TransferStateType>,
+	pub initiator: Vec<u8>,
+	pub recipient: Vec<u8>,
+	pub hash_lock: HashLock,
+	pub time_lock: u64,
+	pub amount: u64,
+	/// Sequence number of the last change of state.
+	pub sequence: u64,
+	pub updated_at_secs: u64,
+	/// Secret revealed on the counterparty chain, only known by the transfer store.
+	pub pre_image: Option<HashLockPreImage>,
+}
+
+/// A transfer in flight as returned by the REST API, the bytes hex encoded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TransferStatus {
+	pub transfer_id: String,
+	pub init_chain: String,
+	/// Missing until the relayer changes the state of the transfer.
+	pub state: Option<String>,
+	pub initiator: String,
+	pub recipient: String,
+	pub hash_lock: String,
+	pub time_lock: u64,
+	pub amount: u64,
+	pub sequence: u64,
+	pub updated_at_secs: u64,
+	/// Time spent in each stage of the processing, missing until a stage is done.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub latency: Option<LatencyBreakdown>,
+	/// Fees paid by the relayer on the transfer, missing until its first transaction.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub cost: Option<TransferCost>,
+}
+
+impl From<InFlightTransfer> for TransferStatus {
+	fn from(transfer: InFlightTransfer) -> Self {
+		let hex_string = |bytes: &[u8]| format!("0x{}", hex::encode(bytes));
+		TransferStatus {
+			transfer_id: hex_string(&transfer.transfer_id.0),
+			init_chain: transfer.init_chain.to_string(),
+			state: transfer.state.map(|state| state.to_string()),
+			initiator: hex_string(&transfer.initiator),
+			recipient: hex_string(&transfer.recipient),
+			hash_lock: hex_string(&transfer.hash_lock.0),
+			time_lock: transfer.time_lock,
+			amount: transfer.amount,
+			sequence: transfer.sequence,
+			updated_at_secs: transfer.updated_at_secs,
+			latency: None,
+			cost: None,
+		}
+	}
+}
+
+/// The transfers in flight. Clones share the same transfers.
+#[derive(Debug, Clone, Default)]
+pub struct InFlightTransfers {
+	transfers: Arc<Mutex<HashMap<BridgeTransferId, InFlightTransfer>>>,
+}
+
+impl InFlightTransfers {
+	fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<BridgeTransferId, InFlightTransfer>> {
+		self.transfers.lock().expect("In-flight transfers lock poisoned")
+	}
+
+	/// Follow the contract events and the state changes of the subscription until the bus
+	/// is dropped.
+	pub async fn run(self, mut events: Subscription) {
+		while let Some(event) = events.recv().await {
+			self.observe(&event, now_secs());
+		}
+	}
+
+	pub(crate) fn observe(&self, event: &BusEvent, now_secs: u64) {
+		let mut transfers = self.lock();
+		match event {
+			BusEvent::Contract(event) => {
+				let transfer = match &event.contract_event {
+					BridgeContractEvent::Initiated(details) => InFlightTransfer {
+						transfer_id: details.bridge_transfer_id,
+						init_chain: event.chain,
+						state: None,
+						initiator: details.initiator.0.clone(),
+						recipient: details.recipient.0.clone(),
+						hash_lock: details.hash_lock,
+						time_lock: details.time_lock.0,
+						amount: details.amount.0,
+						sequence: 0,
+						updated_at_secs: now_secs,
+						pre_image: None,
+					},
+					BridgeContractEvent::Locked(details) => InFlightTransfer {
+						transfer_id: details.bridge_transfer_id,
+						init_chain: event.chain.other(),
+						state: None,
+						initiator: details.initiator.0.clone(),
+						recipient: details.recipient.0.clone(),
+						hash_lock: details.hash_lock,
+						time_lock: details.time_lock.0,
+						amount: details.amount.0,
+						sequence: 0,
+						updated_at_secs: now_secs,
+						pre_image: None,
+					},
+					_ => return,
+				};
+				// The initiation describes the transfer, the lock only completes it.
+				transfers.entry(transfer.transfer_id).or_insert(transfer);
+			}
+			BusEvent::StateChange(change) => {
+				if change.new.is_final() {
+					transfers.remove(&change.transfer_id);
+				} else if let Some(transfer) = transfers.get_mut(&change.transfer_id) {
+					transfer.state = Some(change.new);
+					transfer.sequence = change.sequence;
+					transfer.updated_at_secs = now_secs;
+				}
+			}
+			BusEvent::Action(..) => (),
+		}
+	}
+
+	/// The transfers in flight, optionally in a state, the oldest update first.
+	pub fn list(&self, state: Option<TransferStateType>) -> Vec<InFlightTransfer> {
+		let mut transfers: Vec<InFlightTransfer> = self
+			.lock()
+			.values()
+			.filter(|transfer| state.map_or(true, |state| transfer.state == Some(state)))
+			.cloned()
+			.collect();
+		transfers.sort_by_key(|transfer| (transfer.updated_at_secs, transfer.transfer_id.0));
+		transfers
+	}
+
+	/// Follow the transfers reloaded from the transfer store, when the relayer restarts.
+	pub fn restore(&self, transfers: Vec<InFlightTransfer>) {
+		let mut in_flight = self.lock();
+		for transfer in transfers {
+			in_flight.insert(transfer.transfer_id, transfer);
+		}
+	}
+
+	pub fn get(&self, transfer_id: BridgeTransferId) -> Option<InFlightTransfer> {
+		self.lock().get(&transfer_id).cloned()
+	}
+}
+
+fn now_secs() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|d| d.as_secs())
+		.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::event_bus::StateChange;
+	use bridge_util::events::TransferEvent;
+	use bridge_util::types::{Amount, BridgeAddress, BridgeTransferDetails, LockDetails, TimeLock};
+
+	#[test]
+	fn test_in_flight_transfers() {
+		let transfers = InFlightTransfers::default();
+		let id = |byte: u8| BridgeTransferId([byte; 32]);
+		let change = |transfer_id, sequence, new| {
+			BusEvent::StateChange(StateChange {
+				transfer_id,
+				sequence,
+				previous: None,
+				new,
+				cause: None,
+				reason: None,
+			})
+		};
+		transfers.observe(
+			&BusEvent::Contract(TransferEvent {
+				chain: ChainId::ONE,
+				contract_event: BridgeContractEvent::Initiated(BridgeTransferDetails {
+					bridge_transfer_id: id(1),
+					initiator: BridgeAddress(vec![1; 20]),
+					recipient: BridgeAddress(vec![2; 32]),
+					hash_lock: HashLock([3; 32]),
+					time_lock: TimeLock(200),
+					amount: Amount(10),
+					state: 0,
+				}),
+			}),
+			100,
+		);
+		let locked = |byte: u8, time_lock: u64| {
+			BusEvent::Contract(TransferEvent {
+				chain: ChainId::TWO,
+				contract_event: BridgeContractEvent::Locked(LockDetails {
+					bridge_transfer_id: id(byte),
+					initiator: BridgeAddress(vec![1; 20]),
+					recipient: BridgeAddress(vec![2; 32]),
+					hash_lock: HashLock([3; 32]),
+					time_lock: TimeLock(time_lock),
+					amount: Amount(10),
+				}),
+			})
+		};
+		transfers.observe(&locked(1, 150), 101);
+		transfers.observe(&locked(2, 150), 90);
+		transfers.observe(&change(id(1), 2, TransferStateType::Locked), 102);
+		// A change of an unknown transfer is ignored.
+		transfers.observe(&change(id(3), 1, TransferStateType::Initialized), 102);
+
+		let transfer = transfers.get(id(1)).unwrap();
+		assert_eq!(transfer.init_chain, ChainId::ONE);
+		assert_eq!(transfer.time_lock, 200);
+		assert_eq!(transfer.state, Some(TransferStateType::Locked));
+		assert_eq!((transfer.sequence, transfer.updated_at_secs), (2, 102));
+		assert_eq!(transfers.get(id(2)).unwrap().init_chain, ChainId::ONE);
+		let status = TransferStatus::from(transfer);
+		assert_eq!(status.transfer_id, format!("0x{}", hex::encode([1; 32])));
+		assert_eq!(status.state.as_deref(), Some("Locked"));
+
+		let all: Vec<_> = transfers.list(None).iter().map(|t| t.transfer_id).collect();
+		assert_eq!(all, [id(2), id(1)]);
+		assert_eq!(transfers.list(Some(TransferStateType::Locked)).len(), 1);
+		transfers.observe(&change(id(1), 3, TransferStateType::Completed), 103);
+		assert_eq!(transfers.get(id(1)), None);
+		assert!(transfers.get(id(3)).is_none());
+	}
+}
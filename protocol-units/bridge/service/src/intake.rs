@@ -0,0 +1,92 @@
+use bridge_config::common::relayer::RelayerConfig;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Default)]
+struct IntakeCounters {
+	in_flight: usize,
+	backlog: usize,
+	backlogged_total: u64,
+}
+
+/// Cap on the number of transfers in flight, locked and not yet completed.
+/// Transfers initiated above the cap are backlogged by the relayer loop so a burst
+/// of initiations can't exhaust the gas and liquidity of the relayer.
+/// Clones share the same counters.
+#[derive(Debug, Clone)]
+pub struct IntakeLimit {
+	max_in_flight: usize,
+	counters: Arc<Mutex<IntakeCounters>>,
+}
+
+impl From<&RelayerConfig> for IntakeLimit {
+	fn from(config: &RelayerConfig) -> Self {
+		IntakeLimit::new(usize::try_from(config.max_in_flight_transfers).unwrap_or(usize::MAX))
+	}
+}
+
+impl Default for IntakeLimit {
+	fn default() -> Self {
+		IntakeLimit::from(&RelayerConfig::default())
+	}
+}
+
+impl IntakeLimit {
+	pub fn new(max_in_flight: usize) -> Self {
+		IntakeLimit { max_in_flight, counters: Arc::new(Mutex::new(IntakeCounters::default())) }
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<'_, IntakeCounters> {
+		self.counters.lock().expect("Intake counters lock poisoned")
+	}
+
+	/// Whether one more transfer can be locked with `in_flight` transfers in flight.
+	pub fn has_capacity(&self, in_flight: usize) -> bool {
+		in_flight < self.max_in_flight
+	}
+
+	/// Count a transfer sent to the backlog at intake.
+	pub fn record_backlogged(&self) {
+		self.lock().backlogged_total += 1;
+	}
+
+	/// Update the gauges with the current numbers of in-flight and backlogged transfers.
+	pub fn update(&self, in_flight: usize, backlog: usize) {
+		let mut counters = self.lock();
+		counters.in_flight = in_flight;
+		counters.backlog = backlog;
+	}
+
+	pub fn export_prometheus(&self) -> String {
+		let mut out = String::new();
+		let counters = self.lock();
+		let _ = writeln!(out, "# TYPE bridge_in_flight_transfers gauge");
+		let _ = writeln!(out, "bridge_in_flight_transfers {}", counters.in_flight);
+		let _ = writeln!(out, "# TYPE bridge_intake_backlog gauge");
+		let _ = writeln!(out, "bridge_intake_backlog {}", counters.backlog);
+		let _ = writeln!(out, "# TYPE bridge_intake_backlogged_total counter");
+		let _ = writeln!(out, "bridge_intake_backlogged_total {}", counters.backlogged_total);
+		out
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_intake_limit() {
+		let limit = IntakeLimit::new(2);
+		assert!(limit.has_capacity(1));
+		assert!(!limit.has_capacity(2));
+		assert!(IntakeLimit::default().has_capacity(usize::MAX - 1));
+
+		let shared = limit.clone();
+		shared.record_backlogged();
+		shared.update(2, 1);
+		let export = limit.export_prometheus();
+		assert!(export.contains("bridge_in_flight_transfers 2\n"));
+		assert!(export.contains("bridge_intake_backlog 1\n"));
+		assert!(export.contains("bridge_intake_backlogged_total 1\n"));
+	}
+}
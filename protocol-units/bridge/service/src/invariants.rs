@@ -0,0 +1,264 @@
+//! Reconciliation of the amounts locked on both chains: the transfers initiated on the Ethereum
+//! initiator contract and not completed nor refunded must be locked on the Movement
+//! counterparty module, less the relayer fee. The outstanding amounts of the transfers in
+//! flight are read on chain periodically, a difference beyond the threshold of the config
+//! raises an alert.
+use crate::catchup::{ETH_CHAIN, MOVEMENT_CHAIN};
+use crate::chains::ethereum::client::EthClient;
+use crate::chains::movement::client_framework::MovementClientFramework;
+use crate::fees::RelayerFeeSchedule;
+use crate::in_flight::InFlightTransfers;
+use bridge_config::common::invariants::InvariantsConfig;
+use bridge_util::chains::bridge_contracts::BridgeContract;
+use bridge_util::types::{
+	Amount, BridgeTransferDetails, BridgeTransferDetailsCounterparty, ChainId,
+};
+use serde::Serialize;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// State of a transfer initiated on the Ethereum initiator contract, not completed nor refunded.
+const ETH_STATE_INITIALIZED: u8 = 0;
+// State of a transfer locked on the Movement counterparty module, not completed nor aborted.
+const MOVEMENT_STATE_LOCKED: u8 = 1;
+
+/// Outstanding amounts of the transfers in flight from Ethereum to Movement.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct LockedTotals {
+	/// Amount initiated on the Ethereum initiator contract.
+	pub eth_initiated: u128,
+	/// Amount locked on the Movement counterparty module.
+	pub movement_locked: u128,
+	pub transfers: u64,
+	/// Transfers whose details couldn't be read, left out of the totals.
+	pub unread: u64,
+}
+
+impl LockedTotals {
+	/// Add the details of a transfer on both chains, missing if the transfer isn't there.
+	pub fn add<A, B>(
+		&mut self,
+		initiated: Option<&BridgeTransferDetails<A>>,
+		locked: Option<&BridgeTransferDetailsCounterparty<B>>,
+	) {
+		self.transfers += 1;
+		if let Some(details) = initiated.filter(|details| details.state == ETH_STATE_INITIALIZED) {
+			self.eth_initiated += u128::from(details.amount.0);
+		}
+		if let Some(details) = locked.filter(|details| details.state == MOVEMENT_STATE_LOCKED) {
+			self.movement_locked += u128::from(details.amount.0);
+		}
+	}
+
+	pub fn divergence(&self) -> u128 {
+		self.eth_initiated.abs_diff(self.movement_locked)
+	}
+}
+
+/// Result of the last reconciliation.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct InvariantStatus {
+	/// Unix timestamp of the last check, missing until the first one.
+	pub checked_at: Option<u64>,
+	pub totals: LockedTotals,
+	/// Checks whose totals diverged beyond the threshold since the start.
+	pub violations: u64,
+}
+
+/// Results of the reconciliations. Clones share the same results.
+#[derive(Debug, Clone, Default)]
+pub struct InvariantMetrics {
+	status: Arc<Mutex<InvariantStatus>>,
+}
+
+impl InvariantMetrics {
+	fn lock(&self) -> std::sync::MutexGuard<'_, InvariantStatus> {
+		self.status.lock().expect("Invariant metrics lock poisoned")
+	}
+
+	/// Record the totals of a check, true if they diverge beyond the threshold.
+	fn record(&self, totals: LockedTotals, max_divergence: u64, now_secs: u64) -> bool {
+		let mut status = self.lock();
+		let violated = totals.divergence() > u128::from(max_divergence);
+		if violated {
+			status.violations += 1;
+		}
+		status.checked_at = Some(now_secs);
+		status.totals = totals;
+		violated
+	}
+
+	pub fn status(&self) -> InvariantStatus {
+		self.lock().clone()
+	}
+
+	pub fn export_prometheus(&self) -> String {
+		let mut out = String::new();
+		let status = self.status();
+		if status.checked_at.is_none() {
+			return out;
+		}
+		let totals = &status.totals;
+		let _ = writeln!(out, "# TYPE bridge_locked_total gauge");
+		let _ =
+			writeln!(out, "bridge_locked_total{{chain=\"{ETH_CHAIN}\"}} {}", totals.eth_initiated);
+		let _ = writeln!(
+			out,
+			"bridge_locked_total{{chain=\"{MOVEMENT_CHAIN}\"}} {}",
+			totals.movement_locked
+		);
+		let _ = writeln!(out, "# TYPE bridge_locked_divergence gauge");
+		let _ = writeln!(out, "bridge_locked_divergence {}", totals.divergence());
+		let _ = writeln!(out, "# TYPE bridge_invariant_violations_total counter");
+		let _ = writeln!(out, "bridge_invariant_violations_total {}", status.violations);
+		out
+	}
+}
+
+/// Reads the transfers in flight from Ethereum to Movement on both chains and compares their
+/// outstanding amounts.
+pub struct InvariantChecker {
+	config: InvariantsConfig,
+	in_flight: InFlightTransfers,
+	eth_client: EthClient,
+	movement_client: MovementClientFramework,
+	fee_schedule: RelayerFeeSchedule,
+	metrics: InvariantMetrics,
+}
+
+impl InvariantChecker {
+	pub fn new(
+		config: &InvariantsConfig,
+		in_flight: InFlightTransfers,
+		eth_client: EthClient,
+		movement_client: MovementClientFramework,
+		fee_schedule: RelayerFeeSchedule,
+		metrics: InvariantMetrics,
+	) -> Self {
+		InvariantChecker {
+			config: config.clone(),
+			in_flight,
+			eth_client,
+			movement_client,
+			fee_schedule,
+			metrics,
+		}
+	}
+
+	pub async fn run(self) {
+		let mut interval =
+			tokio::time::interval(Duration::from_secs(self.config.check_interval_secs.max(1)));
+		loop {
+			interval.tick().await;
+			let now_secs = now_secs();
+			let totals = self.locked_totals(now_secs).await;
+			let divergence = totals.divergence();
+			let (eth_initiated, movement_locked) = (totals.eth_initiated, totals.movement_locked);
+			if self.metrics.record(totals, self.config.max_locked_divergence, now_secs) {
+				tracing::error!(
+					target: "bridge_alert",
+					"Locked totals diverge by {divergence}: {eth_initiated} initiated on Ethereum, {movement_locked} locked on Movement"
+				);
+			}
+		}
+	}
+
+	async fn locked_totals(&self, now_secs: u64) -> LockedTotals {
+		let settled_before = now_secs.saturating_sub(self.config.settle_secs);
+		let mut totals = LockedTotals::default();
+		for transfer in self.in_flight.list(None) {
+			if transfer.init_chain != ChainId::ONE || transfer.updated_at_secs > settled_before {
+				continue;
+			}
+			let transfer_id = transfer.transfer_id;
+			let initiated =
+				self.eth_client.clone().get_bridge_transfer_details_initiator(transfer_id).await;
+			let locked = self
+				.movement_client
+				.clone()
+				.get_bridge_transfer_details_counterparty(transfer_id)
+				.await;
+			match (initiated, locked) {
+				(Ok(initiated), Ok(locked)) => {
+					// The amount locked on Movement is the initiated one less the relayer fee.
+					let initiated = initiated.map(|details| BridgeTransferDetails {
+						amount: Amount(self.fee_schedule.quote_fee(details.amount).net_amount),
+						..details
+					});
+					totals.add(initiated.as_ref(), locked.as_ref())
+				}
+				(Err(err), _) | (_, Err(err)) => {
+					tracing::warn!("Invariants: transfer {transfer_id} not read: {err}");
+					totals.unread += 1;
+				}
+			}
+		}
+		totals
+	}
+}
+
+fn now_secs() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|d| d.as_secs())
+		.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bridge_util::types::{BridgeAddress, BridgeTransferId, HashLock, TimeLock};
+
+	fn initiated(amount: u64, state: u8) -> BridgeTransferDetails<Vec<u8>> {
+		BridgeTransferDetails {
+			bridge_transfer_id: BridgeTransferId([1; 32]),
+			initiator: BridgeAddress(vec![1; 20]),
+			recipient: BridgeAddress(vec![2; 32]),
+			hash_lock: HashLock([3; 32]),
+			time_lock: TimeLock(100),
+			amount: Amount(amount),
+			state,
+		}
+	}
+
+	fn locked(amount: u64, state: u8) -> BridgeTransferDetailsCounterparty<Vec<u8>> {
+		BridgeTransferDetailsCounterparty {
+			bridge_transfer_id: BridgeTransferId([1; 32]),
+			initiator: BridgeAddress(vec![1; 20]),
+			recipient: BridgeAddress(vec![2; 32]),
+			hash_lock: HashLock([3; 32]),
+			time_lock: TimeLock(100),
+			amount: Amount(amount),
+			state,
+		}
+	}
+
+	#[test]
+	fn test_locked_totals() {
+		let mut totals = LockedTotals::default();
+		totals.add(Some(&initiated(10, ETH_STATE_INITIALIZED)), Some(&locked(10, 1)));
+		// Completed on both chains.
+		totals.add(Some(&initiated(20, 1)), Some(&locked(20, 2)));
+		// Not locked yet.
+		let not_locked: Option<&BridgeTransferDetailsCounterparty<Vec<u8>>> = None;
+		totals.add(Some(&initiated(5, ETH_STATE_INITIALIZED)), not_locked);
+		assert_eq!(totals.eth_initiated, 15);
+		assert_eq!(totals.movement_locked, 10);
+		assert_eq!(totals.transfers, 3);
+		assert_eq!(totals.divergence(), 5);
+
+		let metrics = InvariantMetrics::default();
+		assert_eq!(metrics.export_prometheus(), "");
+		assert!(metrics.clone().record(totals.clone(), 4, 1000));
+		assert!(!metrics.record(totals, 5, 1010));
+		let status = metrics.status();
+		assert_eq!(status.checked_at, Some(1010));
+		assert_eq!(status.violations, 1);
+		let export = metrics.export_prometheus();
+		assert!(export.contains("bridge_locked_total{chain=\"ethereum\"} 15\n"));
+		assert!(export.contains("bridge_locked_total{chain=\"movement\"} 10\n"));
+		assert!(export.contains("bridge_locked_divergence 5\n"));
+		assert!(export.contains("bridge_invariant_violations_total 1\n"));
+	}
+}
@@ -0,0 +1,324 @@
+//! Audit trail of the signatures produced by the relayer keys. Each entry is chained to the
+//! previous one by its hash, so an entry changed or removed after it's recorded breaks the
+//! chain of the entries exported for a security review.
+use crate::chains::ethereum::types::{
+	AtomicBridgeCounterpartyMOVE, AtomicBridgeInitiatorMOVE, IMulticall, MockMOVEToken,
+};
+use crate::signer::decode_signing_payload;
+use alloy::consensus::SignableTransaction;
+use alloy::network::TxSigner;
+use alloy::primitives::{keccak256, Address, Signature, TxKind, B256};
+use alloy::sol_types::SolCall;
+use bridge_util::types::BridgeTransferId;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Number of entries kept in memory, the older ones are only in the audit logs.
+pub const KEY_AUDIT_HISTORY: usize = 10_000;
+
+pub const ETHEREUM: &str = "Ethereum";
+pub const MOVEMENT: &str = "Movement";
+
+/// A signature produced by a relayer key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyAuditEntry {
+	/// Position of the entry in the trail, starting at 1.
+	pub index: u64,
+	pub chain: String,
+	pub operation: String,
+	pub transfer_id: Option<String>,
+	/// Hex encoded digest of what the key signed: the signing hash of an Ethereum
+	/// transaction, the hash of a Movement transaction.
+	pub digest: String,
+	pub timestamp_secs: u64,
+	pub previous_hash: String,
+	pub hash: String,
+}
+
+impl KeyAuditEntry {
+	fn compute_hash(&self) -> String {
+		let content = format!(
+			"{}|{}|{}|{}|{}|{}|{}",
+			self.previous_hash,
+			self.index,
+			self.chain,
+			self.operation,
+			self.transfer_id.as_deref().unwrap_or_default(),
+			self.digest,
+			self.timestamp_secs
+		);
+		keccak256(content.as_bytes()).to_string()
+	}
+}
+
+/// Verify the entries are chained to each other and their hashes match their content.
+/// Return the index of the first entry that doesn't.
+pub fn verify_chain(entries: &[KeyAuditEntry]) -> Result<(), u64> {
+	let mut previous: Option<&KeyAuditEntry> = None;
+	for entry in entries {
+		let chained = previous.map_or(true, |previous| {
+			entry.index == previous.index + 1 && entry.previous_hash == previous.hash
+		});
+		if !chained || entry.hash != entry.compute_hash() {
+			return Err(entry.index);
+		}
+		previous = Some(entry);
+	}
+	Ok(())
+}
+
+/// Query of the entries after an index.
+#[derive(Debug, Clone, Deserialize)]
+pub struct KeyAuditQuery {
+	#[serde(default)]
+	pub after: u64,
+}
+
+/// Signatures counted per chain and operation since the start, and the entries in memory.
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyAuditReport {
+	pub counters: BTreeMap<String, BTreeMap<String, u64>>,
+	/// Index of the first entry breaking the chain, if any.
+	pub broken_at: Option<u64>,
+	pub entries: Vec<KeyAuditEntry>,
+}
+
+#[derive(Debug)]
+struct KeyAuditState {
+	entries: VecDeque<KeyAuditEntry>,
+	last_index: u64,
+	last_hash: String,
+	counters: BTreeMap<String, BTreeMap<String, u64>>,
+}
+
+/// Hash chained audit trail of the signatures, shared by the clients of both chains.
+#[derive(Debug, Clone)]
+pub struct KeyAudit {
+	state: Arc<Mutex<KeyAuditState>>,
+}
+
+impl Default for KeyAudit {
+	fn default() -> Self {
+		KeyAudit {
+			state: Arc::new(Mutex::new(KeyAuditState {
+				entries: VecDeque::new(),
+				last_index: 0,
+				last_hash: B256::ZERO.to_string(),
+				counters: BTreeMap::new(),
+			})),
+		}
+	}
+}
+
+impl KeyAudit {
+	fn lock(&self) -> std::sync::MutexGuard<'_, KeyAuditState> {
+		self.state.lock().expect("Key audit lock poisoned")
+	}
+
+	/// Record a signature of the operation of a chain, with the digest signed.
+	pub fn record(
+		&self,
+		chain: &str,
+		operation: &str,
+		transfer_id: Option<BridgeTransferId>,
+		digest: &[u8],
+	) {
+		let mut state = self.lock();
+		let mut entry = KeyAuditEntry {
+			index: state.last_index + 1,
+			chain: chain.to_string(),
+			operation: operation.to_string(),
+			transfer_id: transfer_id.map(|id| format!("0x{}", hex::encode(id.0))),
+			digest: format!("0x{}", hex::encode(digest)),
+			timestamp_secs: SystemTime::now()
+				.duration_since(UNIX_EPOCH)
+				.map(|d| d.as_secs())
+				.unwrap_or_default(),
+			previous_hash: state.last_hash.clone(),
+			hash: String::new(),
+		};
+		entry.hash = entry.compute_hash();
+		tracing::info!(target: "bridge_audit", "Key signature: {entry:?}");
+
+		*state
+			.counters
+			.entry(entry.chain.clone())
+			.or_default()
+			.entry(entry.operation.clone())
+			.or_default() += 1;
+		state.last_index = entry.index;
+		state.last_hash = entry.hash.clone();
+		if state.entries.len() >= KEY_AUDIT_HISTORY {
+			state.entries.pop_front();
+		}
+		state.entries.push_back(entry);
+	}
+
+	/// The counters and the entries in memory after the index.
+	pub fn report(&self, after: u64) -> KeyAuditReport {
+		let state = self.lock();
+		let entries: Vec<KeyAuditEntry> = state.entries.iter().cloned().collect();
+		KeyAuditReport {
+			counters: state.counters.clone(),
+			broken_at: verify_chain(&entries).err(),
+			entries: entries.into_iter().filter(|entry| entry.index > after).collect(),
+		}
+	}
+}
+
+/// Signs the Ethereum transactions with the inner signer, and records each signature in the
+/// key audit with the operation of the bridge contracts it calls.
+#[derive(Debug, Clone)]
+pub struct AuditedEthSigner<S> {
+	inner: S,
+	key_audit: KeyAudit,
+	initiator_contract: Address,
+	counterparty_contract: Address,
+}
+
+impl<S> AuditedEthSigner<S> {
+	pub fn new(
+		inner: S,
+		key_audit: KeyAudit,
+		initiator_contract: Address,
+		counterparty_contract: Address,
+	) -> Self {
+		AuditedEthSigner { inner, key_audit, initiator_contract, counterparty_contract }
+	}
+
+	// The operation of the call and its transfer, decoded from the calldata.
+	fn operation(&self, to: TxKind, input: &[u8]) -> (&'static str, Option<BridgeTransferId>) {
+		let TxKind::Call(to) = to else {
+			return ("deploy", None);
+		};
+		if to == self.initiator_contract {
+			if AtomicBridgeInitiatorMOVE::initiateBridgeTransferCall::abi_decode(input, true)
+				.is_ok()
+			{
+				return ("initiate", None);
+			}
+			if let Ok(call) =
+				AtomicBridgeInitiatorMOVE::completeBridgeTransferCall::abi_decode(input, true)
+			{
+				return ("complete_initiator", Some(BridgeTransferId(call.bridgeTransferId.0)));
+			}
+			if let Ok(call) =
+				AtomicBridgeInitiatorMOVE::refundBridgeTransferCall::abi_decode(input, true)
+			{
+				return ("refund", Some(BridgeTransferId(call.bridgeTransferId.0)));
+			}
+			if IMulticall::tryMulticallCall::abi_decode(input, true).is_ok() {
+				return ("complete_initiator_batch", None);
+			}
+		} else if to == self.counterparty_contract {
+			if let Ok(call) =
+				AtomicBridgeCounterpartyMOVE::lockBridgeTransferCall::abi_decode(input, true)
+			{
+				return ("lock", Some(BridgeTransferId(call.bridgeTransferId.0)));
+			}
+			if let Ok(call) =
+				AtomicBridgeCounterpartyMOVE::completeBridgeTransferCall::abi_decode(input, true)
+			{
+				return ("complete_counterparty", Some(BridgeTransferId(call.bridgeTransferId.0)));
+			}
+			if let Ok(call) =
+				AtomicBridgeCounterpartyMOVE::abortBridgeTransferCall::abi_decode(input, true)
+			{
+				return ("abort", Some(BridgeTransferId(call.bridgeTransferId.0)));
+			}
+			if IMulticall::tryMulticallCall::abi_decode(input, true).is_ok() {
+				return ("lock_batch", None);
+			}
+		} else if MockMOVEToken::approveCall::abi_decode(input, true)
+			.is_ok_and(|call| call.value.is_zero())
+		{
+			// A zero approval of a token revokes an allowance of the wallet.
+			return ("revoke_allowance", None);
+		}
+		("other", None)
+	}
+}
+
+#[async_trait::async_trait]
+impl<S: TxSigner<Signature> + Send + Sync> TxSigner<Signature> for AuditedEthSigner<S> {
+	fn address(&self) -> Address {
+		self.inner.address()
+	}
+
+	async fn sign_transaction(
+		&self,
+		tx: &mut dyn SignableTransaction<Signature>,
+	) -> alloy::signers::Result<Signature> {
+		let signature = self.inner.sign_transaction(tx).await?;
+		// Encoded after the signing, which may set the chain id of the transaction.
+		let payload = tx.encoded_for_signing();
+		let (operation, transfer_id) = match decode_signing_payload(&payload) {
+			Ok(tx) => self.operation(tx.to, &tx.input),
+			Err(_) => ("unknown", None),
+		};
+		self.key_audit
+			.record(ETHEREUM, operation, transfer_id, keccak256(&payload).as_slice());
+		Ok(signature)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use alloy::consensus::TxEip1559;
+	use alloy::primitives::FixedBytes;
+
+	#[tokio::test]
+	async fn test_key_audit() {
+		let key_audit = KeyAudit::default();
+		let counterparty = Address::repeat_byte(2);
+		let signer = AuditedEthSigner::new(
+			bridge_test_fixtures::eth::anvil_signer(bridge_test_fixtures::eth::SIGNER_INDEX),
+			key_audit.clone(),
+			Address::repeat_byte(1),
+			counterparty,
+		);
+		let abort = AtomicBridgeCounterpartyMOVE::abortBridgeTransferCall {
+			bridgeTransferId: FixedBytes([7; 32]),
+		};
+		let mut tx = TxEip1559 {
+			chain_id: 31337,
+			to: TxKind::Call(counterparty),
+			input: abort.abi_encode().into(),
+			..Default::default()
+		};
+		signer.sign_transaction(&mut tx).await.unwrap();
+		tx.to = TxKind::Call(Address::repeat_byte(3));
+		signer.sign_transaction(&mut tx).await.unwrap();
+		key_audit.record(MOVEMENT, "lock", Some(BridgeTransferId([8; 32])), &[9; 32]);
+
+		let report = key_audit.report(0);
+		assert_eq!(report.broken_at, None);
+		assert_eq!(report.counters[ETHEREUM]["abort"], 1);
+		assert_eq!(report.counters[ETHEREUM]["other"], 1);
+		assert_eq!(report.counters[MOVEMENT]["lock"], 1);
+		let entries = report.entries;
+		assert_eq!(entries[0].transfer_id, Some(format!("0x{}", hex::encode([7; 32]))));
+		assert_eq!(entries[0].digest, keccak256(tx_payload(abort)).to_string());
+		assert_eq!(key_audit.report(2).entries, entries[2..]);
+
+		// A changed or removed entry breaks the chain.
+		let mut changed = entries.clone();
+		changed[1].operation = "lock".to_string();
+		assert_eq!(verify_chain(&changed), Err(2));
+		assert_eq!(verify_chain(&[entries[0].clone(), entries[2].clone()]), Err(3));
+		assert_eq!(verify_chain(&entries[1..]), Ok(()));
+	}
+
+	fn tx_payload(abort: AtomicBridgeCounterpartyMOVE::abortBridgeTransferCall) -> Vec<u8> {
+		TxEip1559 {
+			chain_id: 31337,
+			to: TxKind::Call(Address::repeat_byte(2)),
+			input: abort.abi_encode().into(),
+			..Default::default()
+		}
+		.encoded_for_signing()
+	}
+}
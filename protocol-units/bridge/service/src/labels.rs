@@ -0,0 +1,216 @@
+use crate::chains::ethereum::ens::EnsNames;
+use crate::chains::movement::ans::AnsNames;
+use crate::event_bus::{BusEvent, Subscription};
+use bridge_config::Config;
+use bridge_util::chains::bridge_contracts::BridgeContractEvent;
+use bridge_util::types::DisplayAddress;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Source of the human label of an address, e.g. a naming service.
+#[async_trait::async_trait]
+pub trait AddressResolver: Send + Sync {
+	/// Label of the address given as bytes, None if the address has none
+	/// or is not an address of the resolver's chain.
+	async fn resolve(&self, address: &[u8]) -> Result<Option<String>, anyhow::Error>;
+}
+
+/// Labels read from a JSON file mapping hex encoded addresses to their label.
+#[derive(Debug, Clone, Default)]
+pub struct LabelFile {
+	labels: HashMap<Vec<u8>, String>,
+}
+
+impl LabelFile {
+	pub fn parse(json: &str) -> Result<Self, anyhow::Error> {
+		let entries: HashMap<String, String> = serde_json::from_str(json)?;
+		let labels = entries
+			.into_iter()
+			.map(|(address, label)| {
+				let bytes = hex::decode(address.trim_start_matches("0x"))
+					.map_err(|err| anyhow::anyhow!("Invalid labelled address {address}: {err}"))?;
+				Ok((bytes, label))
+			})
+			.collect::<Result<_, anyhow::Error>>()?;
+		Ok(LabelFile { labels })
+	}
+
+	pub fn load(path: &str) -> Result<Self, anyhow::Error> {
+		let json = std::fs::read_to_string(path)
+			.map_err(|err| anyhow::anyhow!("Failed to read label file {path}: {err}"))?;
+		Self::parse(&json)
+	}
+}
+
+#[async_trait::async_trait]
+impl AddressResolver for LabelFile {
+	async fn resolve(&self, address: &[u8]) -> Result<Option<String>, anyhow::Error> {
+		Ok(self.labels.get(address).cloned())
+	}
+}
+
+/// Resolves the labels of the addresses with the first resolver knowing them,
+/// in the order they have been added. Resolved labels, and their absence, are cached.
+/// Clones share the same cache.
+#[derive(Clone)]
+pub struct AddressLabels {
+	resolvers: Vec<Arc<dyn AddressResolver>>,
+	cache_ttl: Duration,
+	cache: Arc<Mutex<HashMap<Vec<u8>, (Option<String>, Instant)>>>,
+}
+
+impl AddressLabels {
+	pub fn new(cache_ttl: Duration) -> Self {
+		AddressLabels { resolvers: Vec::new(), cache_ttl, cache: Default::default() }
+	}
+
+	pub fn with_resolver(mut self, resolver: impl AddressResolver + 'static) -> Self {
+		self.resolvers.push(Arc::new(resolver));
+		self
+	}
+
+	/// Build the resolvers of the config: the label file first, then the naming services.
+	/// None if no resolver is configured.
+	pub async fn from_config(config: &Config) -> Result<Option<Self>, anyhow::Error> {
+		let labels_config = &config.labels;
+		let mut labels = AddressLabels::new(Duration::from_secs(labels_config.cache_secs));
+		if let Some(path) = &labels_config.label_file {
+			labels = labels.with_resolver(LabelFile::load(path)?);
+		}
+		if labels_config.ens_enabled {
+			let registry = alloy::primitives::Address::from_str(&labels_config.ens_registry)?;
+			labels = labels.with_resolver(
+				EnsNames::new(&config.eth.eth_rpc_connection_url(), registry).await?,
+			);
+		}
+		if let Some(ans_address) = &labels_config.movement_ans_address {
+			labels = labels.with_resolver(AnsNames::new(
+				config.movement.mvt_rpc_connection_url(),
+				ans_address.clone(),
+			));
+		}
+		Ok((!labels.resolvers.is_empty()).then_some(labels))
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Vec<u8>, (Option<String>, Instant)>> {
+		self.cache.lock().expect("Address label cache lock poisoned")
+	}
+
+	/// Label of the address, None if no resolver knows it.
+	/// A failing resolver is skipped and the result is not cached, so it's retried.
+	pub async fn label(&self, address: &[u8]) -> Option<String> {
+		if let Some((label, resolved_at)) = self.lock().get(address) {
+			if resolved_at.elapsed() < self.cache_ttl {
+				return label.clone();
+			}
+		}
+		let mut failed = false;
+		let mut label = None;
+		for resolver in &self.resolvers {
+			match resolver.resolve(address).await {
+				Ok(Some(resolved)) => {
+					label = Some(resolved);
+					break;
+				}
+				Ok(None) => (),
+				Err(err) => {
+					tracing::debug!(
+						"Fail to resolve the label of {}: {err}",
+						DisplayAddress(address)
+					);
+					failed = true;
+				}
+			}
+		}
+		if label.is_some() || !failed {
+			self.lock().insert(address.to_vec(), (label.clone(), Instant::now()));
+		}
+		label
+	}
+
+	/// The address in the canonical form of its chain followed by its label if it has one.
+	pub async fn display(&self, address: &[u8]) -> String {
+		match self.label(address).await {
+			Some(label) => format!("{} ({label})", DisplayAddress(address)),
+			None => DisplayAddress(address).to_string(),
+		}
+	}
+
+	/// Log the parties of the transfers initiated and locked, with their labels,
+	/// until the bus is dropped.
+	pub async fn run(self, mut events: Subscription) {
+		while let Some(event) = events.recv().await {
+			let BusEvent::Contract(event) = event else {
+				continue;
+			};
+			match event.contract_event {
+				BridgeContractEvent::Initiated(details) => {
+					let initiator = self.display(&details.initiator.0).await;
+					let recipient = self.display(&details.recipient.0).await;
+					tracing::info!(
+						"Transfer {} initiated on {} by {initiator} for {recipient}, amount {}",
+						details.bridge_transfer_id,
+						event.chain,
+						details.amount.0
+					);
+				}
+				BridgeContractEvent::Locked(details) => {
+					let recipient = self.display(&details.recipient.0).await;
+					tracing::info!(
+						"Transfer {} locked on {} for {recipient}, amount {}",
+						details.bridge_transfer_id,
+						event.chain,
+						details.amount.0
+					);
+				}
+				_ => (),
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::atomic::{AtomicUsize, Ordering};
+
+	// Labels every address, counting the resolutions.
+	struct CountingResolver(Arc<AtomicUsize>);
+
+	#[async_trait::async_trait]
+	impl AddressResolver for CountingResolver {
+		async fn resolve(&self, _address: &[u8]) -> Result<Option<String>, anyhow::Error> {
+			self.0.fetch_add(1, Ordering::SeqCst);
+			Ok(Some("resolved".to_string()))
+		}
+	}
+
+	#[tokio::test]
+	async fn test_address_labels() {
+		let treasury = [0x11; 20];
+		let file =
+			LabelFile::parse(&format!(r#"{{"0x{}": "treasury"}}"#, hex::encode(treasury))).unwrap();
+		let resolutions = Arc::new(AtomicUsize::new(0));
+		let labels = AddressLabels::new(Duration::from_secs(60))
+			.with_resolver(file)
+			.with_resolver(CountingResolver(resolutions.clone()));
+
+		// The label file takes precedence over the next resolvers.
+		assert_eq!(labels.label(&treasury).await.as_deref(), Some("treasury"));
+		assert_eq!(resolutions.load(Ordering::SeqCst), 0);
+		assert_eq!(
+			labels.display(&treasury).await,
+			format!("{} (treasury)", DisplayAddress(&treasury))
+		);
+
+		// The resolved labels are cached.
+		let other = [0x22; 32];
+		assert_eq!(labels.label(&other).await.as_deref(), Some("resolved"));
+		assert_eq!(labels.clone().label(&other).await.as_deref(), Some("resolved"));
+		assert_eq!(resolutions.load(Ordering::SeqCst), 1);
+
+		assert!(LabelFile::parse(r#"{"0xnothex": "label"}"#).is_err());
+	}
+}
@@ -0,0 +1,259 @@
+//! Latency breakdown of the transfers. Each stage of the processing of a transfer runs in a
+//! `bridge_stage` span, child of the span of the transfer. A tracing layer adds the duration
+//! of the stage spans, once closed, to the breakdown of their transfer, so the slow transfers
+//! show whether the time goes to the RPC calls, the confirmations or the scheduler.
+use bridge_util::types::BridgeTransferId;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+// Breakdowns kept, the ones of the oldest transfers are dropped beyond.
+const MAX_BREAKDOWNS: usize = 10_000;
+
+/// Stage of the processing of a transfer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+	/// Read of the transfer on chain when its event is received.
+	Observe,
+	/// The state machine computing the action of the event.
+	Decide,
+	/// Wait of the action for its client and its retry backoff.
+	Schedule,
+	/// Preparation of the transaction: the fees on Ethereum, the sequence number on Movement.
+	Simulate,
+	/// Submission of the transaction to the node.
+	Submit,
+	/// Wait for the execution of the transaction.
+	Confirm,
+}
+
+impl Stage {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Stage::Observe => "observe",
+			Stage::Decide => "decide",
+			Stage::Schedule => "schedule",
+			Stage::Simulate => "simulate",
+			Stage::Submit => "submit",
+			Stage::Confirm => "confirm",
+		}
+	}
+
+	fn parse(stage: &str) -> Option<Self> {
+		[
+			Stage::Observe,
+			Stage::Decide,
+			Stage::Schedule,
+			Stage::Simulate,
+			Stage::Submit,
+			Stage::Confirm,
+		]
+		.into_iter()
+		.find(|candidate| candidate.as_str() == stage)
+	}
+}
+
+impl fmt::Display for Stage {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+/// Span of a stage, child of the current span, the span of the transfer.
+pub fn stage_span(stage: Stage) -> tracing::Span {
+	tracing::info_span!("bridge_stage", stage = stage.as_str())
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct StageTiming {
+	/// Times the stage ran, retries included.
+	pub count: u64,
+	pub total_ms: u64,
+	pub max_ms: u64,
+}
+
+/// Time spent by a transfer in each stage.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct LatencyBreakdown {
+	pub stages: BTreeMap<Stage, StageTiming>,
+	pub total_ms: u64,
+}
+
+#[derive(Debug, Default)]
+struct Breakdowns {
+	transfers: HashMap<BridgeTransferId, LatencyBreakdown>,
+	// Transfers in the order of their first stage, the oldest are dropped first.
+	order: VecDeque<BridgeTransferId>,
+}
+
+/// Latency breakdowns of the transfers. Clones share the same breakdowns.
+#[derive(Debug, Clone, Default)]
+pub struct LatencyBreakdowns {
+	breakdowns: Arc<Mutex<Breakdowns>>,
+}
+
+impl LatencyBreakdowns {
+	fn lock(&self) -> std::sync::MutexGuard<'_, Breakdowns> {
+		self.breakdowns.lock().expect("Latency breakdowns lock poisoned")
+	}
+
+	/// Add the duration of a stage of the transfer.
+	pub fn record(&self, transfer_id: BridgeTransferId, stage: Stage, duration: Duration) {
+		let millis = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+		let mut breakdowns = self.lock();
+		if !breakdowns.transfers.contains_key(&transfer_id) {
+			if breakdowns.order.len() >= MAX_BREAKDOWNS {
+				if let Some(oldest) = breakdowns.order.pop_front() {
+					breakdowns.transfers.remove(&oldest);
+				}
+			}
+			breakdowns.order.push_back(transfer_id);
+		}
+		let breakdown = breakdowns.transfers.entry(transfer_id).or_default();
+		let timing = breakdown.stages.entry(stage).or_default();
+		timing.count += 1;
+		timing.total_ms = timing.total_ms.saturating_add(millis);
+		timing.max_ms = timing.max_ms.max(millis);
+		breakdown.total_ms = breakdown.total_ms.saturating_add(millis);
+	}
+
+	pub fn get(&self, transfer_id: &BridgeTransferId) -> Option<LatencyBreakdown> {
+		self.lock().transfers.get(transfer_id).cloned()
+	}
+
+	/// The layer timing the stage spans into the breakdowns.
+	pub fn layer(&self) -> StageTimingLayer {
+		StageTimingLayer { breakdowns: self.clone() }
+	}
+}
+
+// Extension of a transfer span: the id of the transfer.
+struct TransferSpanId(BridgeTransferId);
+
+// Extension of a stage span: the stage and when it started.
+struct StageStart(Stage, Instant);
+
+// Reads a field of a span as a string.
+struct FieldVisitor<'a> {
+	name: &'a str,
+	value: Option<String>,
+}
+
+impl Visit for FieldVisitor<'_> {
+	fn record_str(&mut self, field: &Field, value: &str) {
+		if field.name() == self.name {
+			self.value = Some(value.to_string());
+		}
+	}
+
+	fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+		if field.name() == self.name {
+			self.value = Some(format!("{value:?}"));
+		}
+	}
+}
+
+fn field_value(attrs: &Attributes<'_>, name: &str) -> Option<String> {
+	let mut visitor = FieldVisitor { name, value: None };
+	attrs.record(&mut visitor);
+	visitor.value
+}
+
+/// Tracing layer adding the duration of the stage spans to the breakdown of their transfer.
+pub struct StageTimingLayer {
+	breakdowns: LatencyBreakdowns,
+}
+
+impl<S> Layer<S> for StageTimingLayer
+where
+	S: Subscriber + for<'a> LookupSpan<'a>,
+{
+	fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+		let Some(span) = ctx.span(id) else {
+			return;
+		};
+		match span.name() {
+			"bridge_transfer" => {
+				let transfer_id = field_value(attrs, "bridge_transfer_id")
+					.and_then(|id| BridgeTransferId::parse(&id).ok());
+				if let Some(transfer_id) = transfer_id {
+					span.extensions_mut().insert(TransferSpanId(transfer_id));
+				}
+			}
+			"bridge_stage" => {
+				if let Some(stage) = field_value(attrs, "stage").and_then(|s| Stage::parse(&s)) {
+					span.extensions_mut().insert(StageStart(stage, Instant::now()));
+				}
+			}
+			_ => (),
+		}
+	}
+
+	fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+		let Some(span) = ctx.span(&id) else {
+			return;
+		};
+		let Some((stage, elapsed)) = span
+			.extensions()
+			.get::<StageStart>()
+			.map(|StageStart(stage, started)| (*stage, started.elapsed()))
+		else {
+			return;
+		};
+		let transfer_id = span.scope().skip(1).find_map(|parent| {
+			let extensions = parent.extensions();
+			extensions.get::<TransferSpanId>().map(|id| id.0)
+		});
+		if let Some(transfer_id) = transfer_id {
+			self.breakdowns.record(transfer_id, stage, elapsed);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::telemetry::transfer_span;
+	use tracing_subscriber::layer::SubscriberExt;
+
+	#[test]
+	fn test_latency_breakdowns() {
+		let breakdowns = LatencyBreakdowns::default();
+		let transfer_id = BridgeTransferId([1; 32]);
+		let subscriber = tracing_subscriber::registry().with(breakdowns.layer());
+		tracing::subscriber::with_default(subscriber, || {
+			let span = transfer_span(transfer_id);
+			span.in_scope(|| {
+				let _decide = stage_span(Stage::Decide).entered();
+			});
+			for _ in 0..2 {
+				span.in_scope(|| stage_span(Stage::Submit));
+			}
+			// A stage outside of a transfer isn't recorded.
+			let _ = stage_span(Stage::Confirm);
+		});
+		let breakdown = breakdowns.get(&transfer_id).expect("the transfer has a breakdown");
+		assert_eq!(breakdown.stages.keys().collect::<Vec<_>>(), [&Stage::Decide, &Stage::Submit]);
+		assert_eq!(breakdown.stages[&Stage::Submit].count, 2);
+
+		breakdowns
+			.clone()
+			.record(transfer_id, Stage::Confirm, Duration::from_millis(30));
+		breakdowns.record(transfer_id, Stage::Confirm, Duration::from_millis(10));
+		let breakdown = breakdowns.get(&transfer_id).expect("the transfer has a breakdown");
+		assert_eq!(
+			breakdown.stages[&Stage::Confirm],
+			StageTiming { count: 2, total_ms: 40, max_ms: 30 }
+		);
+		assert!(breakdown.total_ms >= 40);
+		assert_eq!(breakdowns.get(&BridgeTransferId([2; 32])), None);
+	}
+}
@@ -0,0 +1,187 @@
+//! Election of the leader among the relayer instances running against the same chains. The
+//! leader holds a lease in a store shared by the instances, and is the only one running the
+//! relayer loop. The others stand by, and the first to take the lease once it expires takes
+//! over the transfers in flight from the transfer store.
+use bridge_config::common::coordination::CoordinationConfig;
+use bridge_indexer_db::transfer_store::PgTransferStore;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const LEADER_LEASE: &str = "leader";
+
+/// Store of the leases shared by the instances.
+pub trait LeaseStore: Send + Sync {
+	/// Take or renew the lease for the holder until `expires_at`, unless another holder has it
+	/// until after `now`. Return whether the holder has the lease.
+	fn try_acquire(
+		&self,
+		name: &str,
+		holder: &str,
+		now: u64,
+		expires_at: u64,
+	) -> Result<bool, anyhow::Error>;
+}
+
+/// Leases of the instances running in the same process.
+#[derive(Debug, Default)]
+pub struct MemoryLeaseStore {
+	leases: Mutex<std::collections::HashMap<String, (String, u64)>>,
+}
+
+impl LeaseStore for MemoryLeaseStore {
+	fn try_acquire(
+		&self,
+		name: &str,
+		holder: &str,
+		now: u64,
+		expires_at: u64,
+	) -> Result<bool, anyhow::Error> {
+		let mut leases = self.leases.lock().expect("Lease store lock poisoned");
+		match leases.get(name) {
+			Some((current, current_expires_at))
+				if current != holder && *current_expires_at > now =>
+			{
+				Ok(false)
+			}
+			_ => {
+				leases.insert(name.to_string(), (holder.to_string(), expires_at));
+				Ok(true)
+			}
+		}
+	}
+}
+
+impl LeaseStore for PgTransferStore {
+	fn try_acquire(
+		&self,
+		name: &str,
+		holder: &str,
+		now: u64,
+		expires_at: u64,
+	) -> Result<bool, anyhow::Error> {
+		self.acquire_lease(name, holder, now, expires_at)
+	}
+}
+
+/// Lease of the leader held by this instance. Clones share the same status.
+#[derive(Clone)]
+pub struct LeaderElection {
+	store: Arc<dyn LeaseStore>,
+	instance_id: String,
+	lease: Duration,
+	is_leader: Arc<AtomicBool>,
+}
+
+impl LeaderElection {
+	pub fn new(store: Arc<dyn LeaseStore>, instance_id: String, lease: Duration) -> Self {
+		LeaderElection { store, instance_id, lease, is_leader: Arc::new(AtomicBool::new(false)) }
+	}
+
+	/// The election of the config, none if the instance runs alone. The lease is kept in the
+	/// indexer database.
+	pub fn from_config(config: &CoordinationConfig) -> Result<Option<Self>, anyhow::Error> {
+		if !config.enabled {
+			return Ok(None);
+		}
+		if config.instance_id.is_empty() {
+			anyhow::bail!("Coordination enabled without an instance id");
+		}
+		Ok(Some(LeaderElection::new(
+			Arc::new(PgTransferStore::from_env(1)?),
+			config.instance_id.clone(),
+			Duration::from_secs(config.lease_secs.max(3)),
+		)))
+	}
+
+	pub fn instance_id(&self) -> &str {
+		&self.instance_id
+	}
+
+	pub fn is_leader(&self) -> bool {
+		self.is_leader.load(Ordering::Relaxed)
+	}
+
+	fn renew_interval(&self) -> Duration {
+		self.lease / 3
+	}
+
+	// Take or renew the lease, false if another instance has it or the store failed.
+	fn try_acquire(&self, now: u64) -> bool {
+		let expires_at = now + self.lease.as_secs();
+		let acquired = self
+			.store
+			.try_acquire(LEADER_LEASE, &self.instance_id, now, expires_at)
+			.unwrap_or_else(|err| {
+				tracing::warn!("Failed to acquire the leader lease: {err}");
+				false
+			});
+		self.is_leader.store(acquired, Ordering::Relaxed);
+		acquired
+	}
+
+	/// Stand by until the instance holds the lease.
+	pub async fn acquire(&self) {
+		let mut interval = tokio::time::interval(self.renew_interval());
+		loop {
+			interval.tick().await;
+			if self.try_acquire(now_secs()) {
+				tracing::info!("Instance {} elected leader", self.instance_id);
+				return;
+			}
+		}
+	}
+
+	/// Renew the lease until it's lost, or until the shutdown. When the lease isn't renewed,
+	/// the relayer loop must stop before it expires and another instance takes over.
+	pub async fn hold(self, shutdown: impl Future<Output = ()>) {
+		tokio::pin!(shutdown);
+		let mut interval = tokio::time::interval(self.renew_interval());
+		interval.tick().await;
+		loop {
+			tokio::select! {
+				_ = &mut shutdown => return,
+				_ = interval.tick() => if !self.try_acquire(now_secs()) {
+					tracing::error!(
+						target: "bridge_alert",
+						"Instance {} lost the leader lease, stopping the relayer loop",
+						self.instance_id
+					);
+					return;
+				},
+			}
+		}
+	}
+}
+
+fn now_secs() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|d| d.as_secs())
+		.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_single_leader() {
+		let store: Arc<dyn LeaseStore> = Arc::new(MemoryLeaseStore::default());
+		let election =
+			|id: &str| LeaderElection::new(store.clone(), id.to_string(), Duration::from_secs(30));
+		let (one, two) = (election("one"), election("two"));
+
+		assert!(one.try_acquire(100));
+		assert!(!two.try_acquire(101));
+		assert!((one.is_leader(), two.is_leader()) == (true, false));
+		// The leader renews its lease.
+		assert!(one.try_acquire(120));
+		assert!(!two.try_acquire(140));
+		// Once the lease has expired, the other instance takes over.
+		assert!(two.try_acquire(151));
+		assert!(!one.try_acquire(152));
+		assert!(!one.is_leader());
+	}
+}
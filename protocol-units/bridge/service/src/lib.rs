@@ -1,25 +1,110 @@
-use crate::actions::process_action;
+use crate::actions::{batch_actions, process_action, process_batch};
+use crate::approvals::{ApprovalDecision, ApprovalQueue, ApprovalRequest};
+use crate::circuit_breaker::CircuitBreaker;
+use crate::event_bus::{BusEvent, EventBus, EventRef, StateChange};
+use crate::fees::RelayerFeeSchedule;
+use crate::guards::EnabledDirections;
+use crate::in_flight::InFlightTransfer;
+use crate::intake::IntakeLimit;
+use crate::latency::{stage_span, Stage};
+use crate::pause::PauseSwitches;
+use crate::rate_limit::{RateLimitDecision, RateLimiter};
+use crate::retry::RetryTable;
+use crate::split::{ChildLocked, SplitTransfers};
+use crate::store::TransferStore;
+use crate::submissions::SentTransaction;
+use crate::telemetry::transfer_span;
+use crate::timelock::LockMargin;
 use bridge_indexer_db::client::Client as IndexerClient;
+use bridge_indexer_db::models::SubmissionStatus;
 use bridge_util::{
 	actions::{ActionExecError, TransferAction, TransferActionType},
-	chains::bridge_contracts::{BridgeContract, BridgeContractEvent, BridgeContractMonitoring},
+	chains::bridge_contracts::{
+		BridgeContract, BridgeContractError, BridgeContractEvent, BridgeContractMonitoring,
+	},
 	events::{InvalidEventError, TransferEvent},
-	states::{TransferState, TransferStateType},
-	types::{BridgeTransferId, ChainId},
+	reason::{ReasonCode, WithReasonCode},
+	states::{InitiatedTransfer, TransferState, TransferStateType},
+	types::{Amount, BridgeAddress, BridgeTransferId, ChainId, TransferDirection},
+};
+use futures::future::BoxFuture;
+use futures::stream::{FuturesOrdered, FuturesUnordered};
+use std::{
+	collections::{HashMap, VecDeque},
+	sync::Arc,
 };
-use futures::stream::FuturesUnordered;
-use std::{collections::HashMap, sync::Arc};
 use tokio::sync::mpsc;
 use tokio::sync::oneshot;
 use tokio::{select, sync::Mutex};
 use tokio_stream::StreamExt;
+use tracing::Instrument;
 
 pub use bridge_util::types;
 
 mod actions;
+pub mod admin_auth;
+pub mod allowances;
+pub mod anomalies;
+pub mod approvals;
+pub mod canary;
+pub mod catchup;
 pub mod chains;
+pub mod circuit_breaker;
+pub mod claims;
+pub mod config_snapshot;
+pub mod drill;
+pub mod event_bus;
+pub mod event_metrics;
+pub mod explorer;
+pub mod fee_tracker;
+pub mod fees;
+pub mod forensics;
 pub mod grpc;
+pub mod guards;
+pub mod in_flight;
+pub mod intake;
+pub mod invariants;
+pub mod key_audit;
+pub mod labels;
+pub mod latency;
+pub mod leader;
+pub mod liquidity;
+pub mod live_updates;
+pub mod metrics;
+pub mod pause;
+pub mod pre_image;
+pub mod rate_limit;
+pub mod refund;
 pub mod rest;
+pub mod retry;
+pub mod revenue;
+pub mod rpc_cache;
+pub mod rpc_metrics;
+pub mod runbook;
+pub mod secrets;
+pub mod signer;
+pub mod split;
+pub mod startup_report;
+pub mod store;
+pub mod strict_mode;
+pub mod submissions;
+pub mod sweep;
+pub mod telemetry;
+pub mod timelock;
+pub mod transfer_search;
+pub mod user_notifications;
+pub mod vectors;
+pub mod webhooks;
+
+// Outbox status updates written in a transaction once this many are pending,
+// or after the interval.
+const SUBMISSION_UPDATES_BATCH_SIZE: usize = 64;
+const SUBMISSION_UPDATES_FLUSH_INTERVAL: std::time::Duration =
+	std::time::Duration::from_millis(100);
+// Transfers remembered as completed on chain to skip the errors of their late completions.
+const COMPLETED_TRANSFERS_KEPT: usize = 1024;
+// Time given to the submitted transactions to be executed on shutdown.
+const SHUTDOWN_GRACE_PERIOD: std::time::Duration = std::time::Duration::from_secs(30);
 
 #[derive(Debug)]
 struct HeathCheckStatus {
@@ -55,29 +140,135 @@ pub async fn run_bridge<
 	indexer_db_client: Option<IndexerClient>,
 	healthcheck_tx_one: mpsc::Sender<oneshot::Sender<bool>>,
 	healthcheck_tx_two: mpsc::Sender<oneshot::Sender<bool>>,
+	retry_table: RetryTable,
+	stateless_verification: bool,
+	approval_queue: ApprovalQueue,
+	mut approval_decision_rx: mpsc::Receiver<ApprovalRequest>,
+	mut dropped_initiation_rx: mpsc::UnboundedReceiver<BridgeTransferId>,
+	mut sent_transaction_rx: mpsc::UnboundedReceiver<SentTransaction>,
+	enabled_directions: EnabledDirections,
+	event_bus: EventBus,
+	split_transfers: SplitTransfers,
+	pause_switches: PauseSwitches,
+	intake_limit: IntakeLimit,
+	rate_limiter: RateLimiter,
+	fee_schedule: RelayerFeeSchedule,
+	circuit_breaker: CircuitBreaker,
+	lock_margin: LockMargin,
+	transfer_store: Option<Arc<dyn TransferStore>>,
+	shutdown: impl std::future::Future<Output = ()>,
 ) -> Result<(), anyhow::Error>
 where
 	Vec<u8>: From<A1>,
 	Vec<u8>: From<A2>,
 {
-	let mut state_runtime = Runtime::new(indexer_db_client);
+	// The transfers in flight when the relayer stopped are resumed. In stateless mode their
+	// state is read on chain for each event instead.
+	let restored = match &transfer_store {
+		Some(store) if !stateless_verification => store.in_flight()?,
+		_ => Vec::new(),
+	};
+	let mut state_runtime = Runtime::new(
+		indexer_db_client,
+		retry_table,
+		stateless_verification,
+		approval_queue,
+		enabled_directions,
+		event_bus,
+		split_transfers,
+		pause_switches,
+		intake_limit,
+		rate_limiter,
+		fee_schedule,
+		lock_margin,
+		transfer_store,
+	);
 
 	let mut client_exec_result_futures_one = FuturesUnordered::new();
 	let mut client_exec_result_futures_two = FuturesUnordered::new();
+	let mut batch_exec_result_futures = FuturesUnordered::new();
 	let mut health_check_result_futures = FuturesUnordered::new();
+	let mut observed_events_one = FuturesOrdered::new();
+	let mut observed_events_two = FuturesOrdered::new();
 
 	//only one client can use at a time.
 	let client_lock_one = Arc::new(Mutex::new(()));
 	let client_lock_two = Arc::new(Mutex::new(()));
 
+	tracing::info!("Resume {} transfers in flight", restored.len());
+	for transfer in restored {
+		// A lock sent before the relayer stopped isn't sent again.
+		let locked = if transfer.state == Some(TransferStateType::Initialized) {
+			let transfer_id = transfer.transfer_id;
+			let lock = match transfer.init_chain {
+				ChainId::ONE => {
+					client_two.clone().get_bridge_transfer_details_counterparty(transfer_id).await
+				}
+				ChainId::TWO => {
+					client_one.clone().get_bridge_transfer_details_counterparty(transfer_id).await
+				}
+			};
+			lock.map(|details| details.is_some())
+				.inspect_err(|err| {
+					tracing::warn!("Failed to read the lock of {transfer_id}: {err}")
+				})
+				.ok()
+		} else {
+			None
+		};
+		let Some(action) = state_runtime.restore_transfer(transfer, locked) else {
+			continue;
+		};
+		match action.chain {
+			ChainId::ONE => {
+				if let Some(jh) = retry_action(
+					action,
+					client_one.clone(),
+					client_lock_one.clone(),
+					std::time::Duration::ZERO,
+				) {
+					client_exec_result_futures_one.push(jh);
+				}
+			}
+			ChainId::TWO => {
+				if let Some(jh) = retry_action(
+					action,
+					client_two.clone(),
+					client_lock_two.clone(),
+					std::time::Duration::ZERO,
+				) {
+					client_exec_result_futures_two.push(jh);
+				}
+			}
+		}
+	}
+
 	let mut tranfer_log_interval = tokio::time::interval(tokio::time::Duration::from_secs(60));
 	let mut monitoring_health_check_interval =
 		tokio::time::interval(tokio::time::Duration::from_secs(5));
+	let mut held_lock_interval = tokio::time::interval(tokio::time::Duration::from_secs(5));
+	let mut submission_flush_interval = tokio::time::interval(SUBMISSION_UPDATES_FLUSH_INTERVAL);
 
 	let mut health_status = HeathCheckStatus::new();
+	tokio::pin!(shutdown);
 
 	loop {
 		select! {
+			// Stop once the submitted transactions have been executed.
+			_ = &mut shutdown => {
+				let mut in_flight = std::mem::take(&mut client_exec_result_futures_one);
+				in_flight.extend(std::mem::take(&mut client_exec_result_futures_two));
+				let in_flight_batches = std::mem::take(&mut batch_exec_result_futures);
+				let pending = in_flight.len() + in_flight_batches.len();
+				tracing::info!("Shutdown requested, wait for {pending} submitted transactions");
+				let in_flight = futures::stream::select(
+					in_flight.map(|res| res.map(|result| vec![result])),
+					in_flight_batches,
+				);
+				state_runtime.wait_submissions(in_flight, pending).await;
+				state_runtime.flush_submission_updates();
+				return Ok(());
+			}
 			//Manage REST HealthCheck request
 			Some(oneshot_tx) = healthcheck_request_rx.recv() => {
 				let res = if health_status.check() {
@@ -125,6 +316,52 @@ where
 					}
 				}
 			}
+			// Apply the operator decision on a transfer pending approval.
+			Some(request) = approval_decision_rx.recv() => {
+				let action = state_runtime.process_approval_decision(request);
+				for action in action.into_iter().chain(state_runtime.take_queued_actions()) {
+					match action.chain {
+						ChainId::ONE => if let Some(jh) = retry_action(action, client_one.clone(), client_lock_one.clone(), std::time::Duration::ZERO) {
+							client_exec_result_futures_one.push(jh);
+						},
+						ChainId::TWO => if let Some(jh) = retry_action(action, client_two.clone(), client_lock_two.clone(), std::time::Duration::ZERO) {
+							client_exec_result_futures_two.push(jh);
+						},
+					}
+				}
+			}
+			// Compensate the transfers initiated on chain one whose initiation has been
+			// dropped by a reorg.
+			Some(transfer_id) = dropped_initiation_rx.recv() => {
+				state_runtime.process_dropped_initiation(transfer_id);
+			}
+			// Lock the transfers held by a lifted pause or by the in-flight cap.
+			_ = held_lock_interval.tick() => {
+				let mut actions = state_runtime.release_held_locks();
+				actions.extend(state_runtime.take_queued_actions());
+				// The locks released together share the cost of their transactions.
+				let (batches, actions) = batch_actions(actions);
+				for batch in batches {
+					batch_exec_result_futures.push(match batch[0].chain {
+						ChainId::ONE => execute_batch(batch, client_one.clone(), client_lock_one.clone()),
+						ChainId::TWO => execute_batch(batch, client_two.clone(), client_lock_two.clone()),
+					});
+				}
+				for action in actions {
+					match action.chain {
+						ChainId::ONE => if let Some(jh) = retry_action(action, client_one.clone(), client_lock_one.clone(), std::time::Duration::ZERO) {
+							client_exec_result_futures_one.push(jh);
+						},
+						ChainId::TWO => if let Some(jh) = retry_action(action, client_two.clone(), client_lock_two.clone(), std::time::Duration::ZERO) {
+							client_exec_result_futures_two.push(jh);
+						},
+					}
+				}
+			}
+			// Record the transactions sent by the clients in the outbox.
+			Some(sent) = sent_transaction_rx.recv() => state_runtime.transaction_sent(sent),
+			// Bound the time the outbox status updates wait for their batch.
+			_ = submission_flush_interval.tick() => state_runtime.flush_submission_updates(),
 			// Log all current transfer
 			_ = tranfer_log_interval.tick() => {
 				//format logs
@@ -133,94 +370,233 @@ where
 					tracing::info!("Bridge current transfer processing:{:#?}", logs);
 				});
 			}
-			// Wait on chain one events.
+			// Wait on chain one events. In stateless mode the transfer is read on its init chain
+			// before the event is processed, without blocking the loop, the events are processed
+			// in order once read.
 			Some(event_res_one) = stream_one.next() =>{
 				match event_res_one {
 					Ok(event_one) => {
 						let event : TransferEvent<A1> = (event_one, ChainId::ONE).into();
-						tracing::info!("Receive event from chain ONE:{} ", event.contract_event);
-						match state_runtime.process_event(event) {
-							Ok(action) => {
-								//Execute action
-								match action.chain {
-									ChainId::ONE => {
-										let fut = process_action(action, client_one.clone());
-										if let Some(fut) = fut {
-											let jh = tokio::spawn({
-												let client_lock_clone = client_lock_one.clone();
-												async move {
-													let _lock = client_lock_clone.lock().await;
-													fut.await
-												}
-											});
-											client_exec_result_futures_one.push(jh);
-										}
+						let expected_state = state_runtime.stateless_expected_state(&event);
+						let observed =
+							observe_event(event, expected_state, client_one.clone(), client_two.clone());
+						observed_events_one.push_back(observed);
+					}
+					Err(err) => tracing::error!("Chain one event stream return an error:{err}"),
+				}
+			}
+			Some((event, read_state)) = observed_events_one.next() => {
+				// The event is processed in the span of its transfer, its action is executed in it.
+				let transfer_id = event.contract_event.bridge_transfer_id();
+				let span = transfer_span(transfer_id);
+				let rejected = span.in_scope(|| match read_state {
+					ReadState::NotRead => false,
+					ReadState::Read(state) => {
+						state_runtime.set_state(state);
+						false
+					}
+					ReadState::NotFound(init_chain) => {
+						tracing::warn!("Transfer {transfer_id} not found on chain {init_chain}");
+						circuit_breaker.record_rpc_divergence(init_chain);
+						false
+					}
+					ReadState::Rejected(err) => {
+						tracing::warn!("Event {event} rejected: {err}");
+						true
+					}
+					ReadState::Failed(init_chain, err) => {
+						tracing::warn!("Failed to read transfer {transfer_id} on chain {init_chain}: {err}");
+						false
+					}
+				});
+				if !rejected {
+					let result = span.in_scope(|| {
+						let _decide = stage_span(Stage::Decide).entered();
+						tracing::info!("Receive event from chain ONE: {event}");
+						state_runtime.process_event(event)
+					});
+					match result {
+						Ok(action) => {
+							//Execute action
+							match action.chain {
+								ChainId::ONE => {
+									// The wait for the client is the scheduling of the action.
+									let schedule = transfer_span(action.transfer_id).in_scope(|| stage_span(Stage::Schedule));
+									let fut = process_action(action, client_one.clone());
+									if let Some(fut) = fut {
+										let jh = tokio::spawn({
+											let client_lock_clone = client_lock_one.clone();
+											async move {
+												let _lock = client_lock_clone.lock().instrument(schedule).await;
+												fut.await
+											}
+										});
+										client_exec_result_futures_one.push(jh);
+									}
 
-									},
-									ChainId::TWO => {
-										let fut = process_action(action, client_two.clone());
-										if let Some(fut) = fut {
-											let jh = tokio::spawn({
-												let client_lock_clone = client_lock_two.clone();
-												async move {
-													let _lock = client_lock_clone.lock().await;
-													fut.await
-												}
-											});
-											client_exec_result_futures_two.push(jh);
-										}
+								},
+								ChainId::TWO => {
+									// The wait for the client is the scheduling of the action.
+									let schedule = transfer_span(action.transfer_id).in_scope(|| stage_span(Stage::Schedule));
+									let fut = process_action(action, client_two.clone());
+									if let Some(fut) = fut {
+										let jh = tokio::spawn({
+											let client_lock_clone = client_lock_two.clone();
+											async move {
+												let _lock = client_lock_clone.lock().instrument(schedule).await;
+												fut.await
+											}
+										});
+										client_exec_result_futures_two.push(jh);
 									}
 								}
+							}
+						},
+						Err(err) => tracing::warn!("Received an invalid event: {err}"),
+					}
+					// Locks of the other children of a split transfer, sent together.
+					let (batches, actions) = batch_actions(state_runtime.take_queued_actions());
+					for batch in batches {
+						batch_exec_result_futures.push(match batch[0].chain {
+							ChainId::ONE => execute_batch(batch, client_one.clone(), client_lock_one.clone()),
+							ChainId::TWO => execute_batch(batch, client_two.clone(), client_lock_two.clone()),
+						});
+					}
+					for action in actions {
+						match action.chain {
+							ChainId::ONE => if let Some(jh) = retry_action(action, client_one.clone(), client_lock_one.clone(), std::time::Duration::ZERO) {
+								client_exec_result_futures_one.push(jh);
+							},
+							ChainId::TWO => if let Some(jh) = retry_action(action, client_two.clone(), client_lock_two.clone(), std::time::Duration::ZERO) {
+								client_exec_result_futures_two.push(jh);
 							},
-							Err(err) => tracing::warn!("Received an invalid event: {err}"),
 						}
 					}
-					Err(err) => tracing::error!("Chain one event stream return an error:{err}"),
 				}
 			}
-			// Wait on chain two events.
+			// Wait on chain two events. In stateless mode the transfer is read on its init chain
+			// before the event is processed, without blocking the loop, the events are processed
+			// in order once read.
 			Some(event_res_two) = stream_two.next() =>{
 				match event_res_two {
 					Ok(event_two) => {
 						let event : TransferEvent<A2> = (event_two, ChainId::TWO).into();
-						tracing::info!("Receive event from chain TWO :{}", event.contract_event);
-						match state_runtime.process_event(event) {
-							Ok(action) => {
-								//Execute action
-								match action.chain {
-									ChainId::ONE => {
-										let fut = process_action(action, client_one.clone());
-										if let Some(fut) = fut {
-											let jh = tokio::spawn(fut);
-											client_exec_result_futures_one.push(jh);
-										}
+						let expected_state = state_runtime.stateless_expected_state(&event);
+						let observed =
+							observe_event(event, expected_state, client_one.clone(), client_two.clone());
+						observed_events_two.push_back(observed);
+					}
+					Err(err) => tracing::error!("Chain two event stream return an error:{err}"),
+				}
+			}
+			Some((event, read_state)) = observed_events_two.next() => {
+				// The event is processed in the span of its transfer, its action is executed in it.
+				let transfer_id = event.contract_event.bridge_transfer_id();
+				let span = transfer_span(transfer_id);
+				let rejected = span.in_scope(|| match read_state {
+					ReadState::NotRead => false,
+					ReadState::Read(state) => {
+						state_runtime.set_state(state);
+						false
+					}
+					ReadState::NotFound(init_chain) => {
+						tracing::warn!("Transfer {transfer_id} not found on chain {init_chain}");
+						circuit_breaker.record_rpc_divergence(init_chain);
+						false
+					}
+					ReadState::Rejected(err) => {
+						tracing::warn!("Event {event} rejected: {err}");
+						true
+					}
+					ReadState::Failed(init_chain, err) => {
+						tracing::warn!("Failed to read transfer {transfer_id} on chain {init_chain}: {err}");
+						false
+					}
+				});
+				if !rejected {
+					let result = span.in_scope(|| {
+						let _decide = stage_span(Stage::Decide).entered();
+						tracing::info!("Receive event from chain TWO: {event}");
+						state_runtime.process_event(event)
+					});
+					match result {
+						Ok(action) => {
+							//Execute action
+							match action.chain {
+								ChainId::ONE => {
+									// The wait for the client is the scheduling of the action.
+									let schedule = transfer_span(action.transfer_id).in_scope(|| stage_span(Stage::Schedule));
+									let fut = process_action(action, client_one.clone());
+									if let Some(fut) = fut {
+										let jh = tokio::spawn({
+											let client_lock_clone = client_lock_one.clone();
+											async move {
+												let _lock = client_lock_clone.lock().instrument(schedule).await;
+												fut.await
+											}
+										});
+										client_exec_result_futures_one.push(jh);
+									}
 
-									},
-									ChainId::TWO => {
-										let fut = process_action(action, client_two.clone());
-										if let Some(fut) = fut {
-											let jh = tokio::spawn(fut);
-											client_exec_result_futures_two.push(jh);
-										}
+								},
+								ChainId::TWO => {
+									// The wait for the client is the scheduling of the action.
+									let schedule = transfer_span(action.transfer_id).in_scope(|| stage_span(Stage::Schedule));
+									let fut = process_action(action, client_two.clone());
+									if let Some(fut) = fut {
+										let jh = tokio::spawn({
+											let client_lock_clone = client_lock_two.clone();
+											async move {
+												let _lock = client_lock_clone.lock().instrument(schedule).await;
+												fut.await
+											}
+										});
+										client_exec_result_futures_two.push(jh);
 									}
 								}
+							}
+						},
+						Err(err) => tracing::warn!("Received an invalid event: {err}"),
+					}
+					// Locks of the other children of a split transfer, sent together.
+					let (batches, actions) = batch_actions(state_runtime.take_queued_actions());
+					for batch in batches {
+						batch_exec_result_futures.push(match batch[0].chain {
+							ChainId::ONE => execute_batch(batch, client_one.clone(), client_lock_one.clone()),
+							ChainId::TWO => execute_batch(batch, client_two.clone(), client_lock_two.clone()),
+						});
+					}
+					for action in actions {
+						match action.chain {
+							ChainId::ONE => if let Some(jh) = retry_action(action, client_one.clone(), client_lock_one.clone(), std::time::Duration::ZERO) {
+								client_exec_result_futures_one.push(jh);
+							},
+							ChainId::TWO => if let Some(jh) = retry_action(action, client_two.clone(), client_lock_two.clone(), std::time::Duration::ZERO) {
+								client_exec_result_futures_two.push(jh);
 							},
-							Err(err) => tracing::warn!("Received an invalid event: {err}"),
 						}
 					}
-					Err(err) => tracing::error!("Chain two event stream return an error:{err}"),
 				}
 			}
 			// Wait on client tx execution result.
 			Some(res) = client_exec_result_futures_one.next() => {
 				match res {
 					//Client execution ok.
-					Ok(Ok(_)) => (),
+					Ok(Ok(action)) => transfer_span(action.transfer_id).in_scope(|| state_runtime.submission_confirmed(&action)),
 					Ok(Err(err)) => {
 						// Manage Tx execution error
-						let action = state_runtime.process_action_exec_error(err);
-						// TODO execute action the same way as normal event.
-						// TODO refactor to avopid code duplication.
+						let span = transfer_span(err.0.transfer_id);
+						if let Some((action, backoff)) = span.in_scope(|| state_runtime.process_action_exec_error(err)) {
+							state_runtime.submission_retried(&action);
+							match action.chain {
+								ChainId::ONE => if let Some(jh) = retry_action(action, client_one.clone(), client_lock_one.clone(), backoff) {
+									client_exec_result_futures_one.push(jh);
+								},
+								ChainId::TWO => if let Some(jh) = retry_action(action, client_two.clone(), client_lock_two.clone(), backoff) {
+									client_exec_result_futures_two.push(jh);
+								},
+							}
+						}
 					}
 					Err(err)=>{
 						// Tokio execution fail. Process should exit.
@@ -232,12 +608,21 @@ where
 			Some(res) = client_exec_result_futures_two.next() => {
 				match res {
 					//Client execution ok.
-					Ok(Ok(_)) => (),
+					Ok(Ok(action)) => transfer_span(action.transfer_id).in_scope(|| state_runtime.submission_confirmed(&action)),
 					Ok(Err(err)) => {
 						// Manage Tx execution error
-						let action = state_runtime.process_action_exec_error(err);
-						// TODO execute action the same way as normal event.
-						// TODO refactor to avopid code duplication.
+						let span = transfer_span(err.0.transfer_id);
+						if let Some((action, backoff)) = span.in_scope(|| state_runtime.process_action_exec_error(err)) {
+							state_runtime.submission_retried(&action);
+							match action.chain {
+								ChainId::ONE => if let Some(jh) = retry_action(action, client_one.clone(), client_lock_one.clone(), backoff) {
+									client_exec_result_futures_one.push(jh);
+								},
+								ChainId::TWO => if let Some(jh) = retry_action(action, client_two.clone(), client_lock_two.clone(), backoff) {
+									client_exec_result_futures_two.push(jh);
+								},
+							}
+						}
 					}
 					Err(err)=>{
 						// Tokio execution fail. Process should exit.
@@ -246,10 +631,155 @@ where
 					}
 				}
 			}
+			// Wait on the actions executed in a batch, only the failed ones are retried.
+			Some(res) = batch_exec_result_futures.next() => {
+				let results = match res {
+					Ok(results) => results,
+					Err(err) => {
+						// Tokio execution fail. Process should exit.
+						tracing::error!("Error during batch tokio task execution exiting: {err}");
+						return Err(err.into());
+					}
+				};
+				for result in results {
+					match result {
+						Ok(action) => transfer_span(action.transfer_id).in_scope(|| state_runtime.submission_confirmed(&action)),
+						Err(err) => {
+							let span = transfer_span(err.0.transfer_id);
+							if let Some((action, backoff)) = span.in_scope(|| state_runtime.process_action_exec_error(err)) {
+								state_runtime.submission_retried(&action);
+								match action.chain {
+									ChainId::ONE => if let Some(jh) = retry_action(action, client_one.clone(), client_lock_one.clone(), backoff) {
+										client_exec_result_futures_one.push(jh);
+									},
+									ChainId::TWO => if let Some(jh) = retry_action(action, client_two.clone(), client_lock_two.clone(), backoff) {
+										client_exec_result_futures_two.push(jh);
+									},
+								}
+							}
+						}
+					}
+				}
+			}
 		}
 	}
 }
 
+// State of the transfer of an event, read on its init chain in stateless mode.
+enum ReadState {
+	NotRead,
+	Read(TransferState),
+	NotFound(ChainId),
+	// The state of the initiator contract doesn't agree with the event.
+	Rejected(InvalidEventError),
+	Failed(ChainId, BridgeContractError),
+}
+
+// Read the state of the transfer of the event on its init chain, if it must be read.
+fn observe_event<A, A1, A2>(
+	event: TransferEvent<A>,
+	expected_state: Option<(ChainId, TransferStateType)>,
+	client_one: impl BridgeContract<A1> + 'static,
+	client_two: impl BridgeContract<A2> + 'static,
+) -> BoxFuture<'static, (TransferEvent<A>, ReadState)>
+where
+	A: Send + 'static,
+	A1: Clone + Send,
+	A2: Clone + Send,
+	Vec<u8>: From<A1>,
+	Vec<u8>: From<A2>,
+{
+	let Some((init_chain, expected_state)) = expected_state else {
+		return Box::pin(futures::future::ready((event, ReadState::NotRead)));
+	};
+	let transfer_id = event.contract_event.bridge_transfer_id();
+	let observe = transfer_span(transfer_id).in_scope(|| stage_span(Stage::Observe));
+	Box::pin(
+		async move {
+			let read = match init_chain {
+				ChainId::ONE => {
+					read_transfer_state(client_one, init_chain, transfer_id, expected_state).await
+				}
+				ChainId::TWO => {
+					read_transfer_state(client_two, init_chain, transfer_id, expected_state).await
+				}
+			};
+			// The state of the initiator contract is read once the event has been emitted,
+			// it must agree with the event.
+			let read_state = match read {
+				Ok(Some((contract_state, state))) => {
+					match TransferState::check_initiator_state(&event, init_chain, contract_state) {
+						Ok(()) => ReadState::Read(state),
+						Err(err) => ReadState::Rejected(err),
+					}
+				}
+				Ok(None) => ReadState::NotFound(init_chain),
+				Err(err) => ReadState::Failed(init_chain, err),
+			};
+			(event, read_state)
+		}
+		.instrument(observe),
+	)
+}
+
+// Rebuild the state of a transfer from the initiator details read on the init chain, returned
+// with the state field of the initiator contract.
+async fn read_transfer_state<A>(
+	mut client: impl BridgeContract<A>,
+	init_chain: ChainId,
+	transfer_id: BridgeTransferId,
+	expected_state: TransferStateType,
+) -> Result<Option<(u8, TransferState)>, BridgeContractError>
+where
+	A: Clone,
+	Vec<u8>: From<A>,
+{
+	let details = client.get_bridge_transfer_details_initiator(transfer_id).await?;
+	Ok(details.map(|details| {
+		let contract_state = details.state;
+		(contract_state, TransferState::from_initiator_details(init_chain, details, expected_state))
+	}))
+}
+
+// Re-execute an action in error after the backoff defined by the retry table.
+fn retry_action<A>(
+	action: TransferAction,
+	client: impl BridgeContract<A> + 'static,
+	client_lock: Arc<Mutex<()>>,
+	backoff: std::time::Duration,
+) -> Option<tokio::task::JoinHandle<Result<TransferAction, ActionExecError>>>
+where
+	A: Clone + Send + TryFrom<Vec<u8>>,
+{
+	let schedule = transfer_span(action.transfer_id).in_scope(|| stage_span(Stage::Schedule));
+	let fut = process_action(action, client)?;
+	Some(tokio::spawn(async move {
+		let _lock = async {
+			tokio::time::sleep(backoff).await;
+			client_lock.lock().await
+		}
+		.instrument(schedule)
+		.await;
+		fut.await
+	}))
+}
+
+// Execute a batch of actions of a chain once its client is free.
+fn execute_batch<A>(
+	actions: Vec<TransferAction>,
+	client: impl BridgeContract<A> + 'static,
+	client_lock: Arc<Mutex<()>>,
+) -> tokio::task::JoinHandle<Vec<Result<TransferAction, ActionExecError>>>
+where
+	A: Clone + Send + TryFrom<Vec<u8>>,
+{
+	let fut = process_batch(actions, client);
+	tokio::spawn(async move {
+		let _lock = client_lock.lock().await;
+		fut.await
+	})
+}
+
 async fn check_monitoring_loop_heath(
 	healthcheck_tx: mpsc::Sender<oneshot::Sender<bool>>,
 ) -> Result<bool, String> {
@@ -272,54 +802,336 @@ async fn check_monitoring_loop_heath(
 	Ok(res)
 }
 
+// A lock held by the relayer, with the time lock of its initiation to send it only while the
+// recipient has the time to complete it.
+struct HeldLock {
+	direction: TransferDirection,
+	// Time lock of the initiation, in the unit of the time locks of the init chain.
+	time_lock: u64,
+	action: TransferAction,
+}
+
+impl HeldLock {
+	// The lock is sent on the counterparty chain.
+	fn init_chain(&self) -> ChainId {
+		self.action.chain.other()
+	}
+}
+
 struct Runtime {
 	swap_state_map: HashMap<BridgeTransferId, TransferState>,
 	indexer_db_client: Option<IndexerClient>,
+	retry_table: RetryTable,
+	approval_queue: ApprovalQueue,
+	// Lock actions of the transfers waiting for an operator decision.
+	held_actions: HashMap<BridgeTransferId, HeldLock>,
+	// In stateless mode states are read on chain for each event
+	// and only kept while an action is executing.
+	stateless: bool,
+	// Transfers initiated in a disabled direction are refunded instead of locked.
+	enabled_directions: EnabledDirections,
+	// Contract events and action submissions are published for the indexer,
+	// the metrics and the canary.
+	event_bus: EventBus,
+	split_transfers: SplitTransfers,
+	// Actions to execute in addition to the one returned for an event.
+	queued_actions: Vec<TransferAction>,
+	pause_switches: PauseSwitches,
+	// Lock actions of the transfers initiated while their direction is paused.
+	paused_locks: HashMap<BridgeTransferId, HeldLock>,
+	intake_limit: IntakeLimit,
+	// Lock actions of the transfers initiated above the in-flight cap, oldest first.
+	backlog_locks: VecDeque<HeldLock>,
+	rate_limiter: RateLimiter,
+	// Lock actions of the transfers initiated over a rate limit, oldest first.
+	rate_limited_locks: VecDeque<HeldLock>,
+	// Relayer fee deducted from the amounts locked on the destination chain.
+	fee_schedule: RelayerFeeSchedule,
+	// Time left on the initiator time lock under which a lock isn't sent anymore.
+	lock_margin: LockMargin,
+	// Sequence number of the last change of state of each transfer not done.
+	state_sequences: HashMap<BridgeTransferId, u64>,
+	// Status updates of the outbox not written yet, written in a single transaction.
+	// An update lost on a crash leaves the submission unconfirmed, so it's checked again.
+	pending_submission_updates: Vec<(TransferAction, SubmissionStatus)>,
+	// Updates that failed to be written on their own, retried once with the next ones.
+	failed_submission_updates: Vec<(TransferAction, SubmissionStatus)>,
+	// Submissions of the outbox not executed yet, by chain, transfer and action kind, to
+	// match the transactions reported by the clients.
+	unconfirmed_submissions: HashMap<(ChainId, BridgeTransferId, String), TransferAction>,
+	// Last transfers whose initiator Completed event has been received, oldest first.
+	completed_transfers: VecDeque<BridgeTransferId>,
+	// Store of the last state of the transfers, written on each change of state so a
+	// restarted relayer resumes them.
+	transfer_store: Option<Arc<dyn TransferStore>>,
 }
 
 impl Runtime {
-	pub fn new(indexer_db_client: Option<IndexerClient>) -> Self {
-		Runtime { swap_state_map: HashMap::new(), indexer_db_client }
+	pub fn new(
+		indexer_db_client: Option<IndexerClient>,
+		retry_table: RetryTable,
+		stateless: bool,
+		approval_queue: ApprovalQueue,
+		enabled_directions: EnabledDirections,
+		event_bus: EventBus,
+		split_transfers: SplitTransfers,
+		pause_switches: PauseSwitches,
+		intake_limit: IntakeLimit,
+		rate_limiter: RateLimiter,
+		fee_schedule: RelayerFeeSchedule,
+		lock_margin: LockMargin,
+		transfer_store: Option<Arc<dyn TransferStore>>,
+	) -> Self {
+		Runtime {
+			swap_state_map: HashMap::new(),
+			indexer_db_client,
+			retry_table,
+			approval_queue,
+			held_actions: HashMap::new(),
+			stateless,
+			enabled_directions,
+			event_bus,
+			split_transfers,
+			queued_actions: Vec::new(),
+			pause_switches,
+			paused_locks: HashMap::new(),
+			intake_limit,
+			backlog_locks: VecDeque::new(),
+			rate_limiter,
+			rate_limited_locks: VecDeque::new(),
+			fee_schedule,
+			lock_margin,
+			state_sequences: HashMap::new(),
+			pending_submission_updates: Vec::new(),
+			failed_submission_updates: Vec::new(),
+			unconfirmed_submissions: HashMap::new(),
+			completed_transfers: VecDeque::new(),
+			transfer_store,
+		}
+	}
+
+	/// In stateless mode, return the init chain and the state the transfer must have
+	/// before the event is applied, so it can be read on chain.
+	pub fn stateless_expected_state<A>(
+		&self,
+		event: &TransferEvent<A>,
+	) -> Option<(ChainId, TransferStateType)> {
+		let child_event = self
+			.split_transfers
+			.parent_of(&event.contract_event.bridge_transfer_id())
+			.is_some();
+		if self.stateless && !child_event {
+			TransferState::expected_before_event(event)
+		} else {
+			None
+		}
+	}
+
+	pub fn set_state(&mut self, state: TransferState) {
+		self.swap_state_map.insert(state.transfer_id, state);
 	}
 
 	pub fn iter_state(&self) -> impl Iterator<Item = &TransferState> {
 		self.swap_state_map.values()
 	}
 
-	fn index_event<A>(&mut self, event: TransferEvent<A>) -> Result<(), InvalidEventError>
-	where
-		A: Into<Vec<u8>> + std::clone::Clone + std::fmt::Debug,
-	{
-		match self.indexer_db_client {
-			Some(ref mut client) => {
-				let event = event.contract_event;
+	pub fn take_queued_actions(&mut self) -> Vec<TransferAction> {
+		std::mem::take(&mut self.queued_actions)
+	}
 
-				client.insert_bridge_contract_event(event.clone()).map_err(|err| {
-					tracing::warn!("Fail to index event :{err}");
-					InvalidEventError::IndexingFailed(err.to_string())
-				})?;
-				tracing::info!("index_event(success):{event:?}");
-				Ok(())
+	// Number of transfers locked and not yet completed. In stateless mode
+	// only the transfers with an action executing are known.
+	fn in_flight(&self) -> usize {
+		self.swap_state_map.len().saturating_sub(
+			self.held_actions.len()
+				+ self.paused_locks.len()
+				+ self.backlog_locks.len()
+				+ self.rate_limited_locks.len(),
+		)
+	}
+
+	// Refund a transfer just initiated instead of locking it.
+	fn refund_initiated(
+		&mut self,
+		transfer: InitiatedTransfer,
+		cause: EventRef,
+		reason: ReasonCode,
+	) -> Result<TransferAction, InvalidEventError> {
+		let transfer_id = transfer.transfer_id;
+		let (transfer, action) = transfer.refund();
+		let state = transfer.into();
+		self.publish_state_change(None, &state, Some(cause), Some(reason));
+		self.swap_state_map.insert(transfer_id, state);
+		self.index_transfer_action(action.clone())?;
+		Ok(action)
+	}
+
+	// Record in the ledger the relayer fee deducted from the amount of a transfer to lock.
+	fn accrue_fee(&mut self, transfer_id: BridgeTransferId, fee: u64) {
+		if fee == 0 {
+			return;
+		}
+		if let Some(ref mut client) = self.indexer_db_client {
+			if let Err(err) = client.insert_fee_accrual(transfer_id, Amount(fee)) {
+				tracing::warn!("Fail to record the fee {fee} of transfer {transfer_id}: {err}");
 			}
-			None => {
-				tracing::warn!("No indexer db client found. Event not indexed");
-				Ok(())
+		}
+	}
+
+	// Hold the lock of a transfer while its direction is paused
+	// or while the number of in-flight transfers is at the cap.
+	fn schedule_lock(&mut self, held: HeldLock) -> TransferAction {
+		let transfer_id = held.action.transfer_id;
+		let no_action =
+			TransferAction { kind: TransferActionType::NoAction, ..held.action.clone() };
+		if self.pause_switches.is_paused(held.direction) {
+			tracing::warn!("{} transfers are paused, hold lock of {transfer_id}", held.direction);
+			self.paused_locks.insert(transfer_id, held);
+			return no_action;
+		}
+		if !self.intake_limit.has_capacity(self.in_flight()) {
+			tracing::warn!("Too many transfers in flight, backlog lock of {transfer_id}");
+			self.backlog_locks.push_back(held);
+			self.intake_limit.record_backlogged();
+			return no_action;
+		}
+		self.split_lock(held.action)
+	}
+
+	// Refund the transfers whose lock has been held until their initiator time lock is too
+	// close: once locked, the recipient couldn't complete them before the initiator can be
+	// refunded. The initiators are refunded at the expiry.
+	fn refund_late_locks(&mut self) {
+		let lock_margin = &self.lock_margin;
+		let late: Vec<BridgeTransferId> = self
+			.held_actions
+			.values()
+			.chain(self.paused_locks.values())
+			.chain(self.backlog_locks.iter())
+			.chain(self.rate_limited_locks.iter())
+			.filter(|held| !lock_margin.allows_lock(held.init_chain(), held.time_lock))
+			.map(|held| held.action.transfer_id)
+			.collect();
+		for transfer_id in late {
+			if self.held_actions.remove(&transfer_id).is_some() {
+				self.approval_queue.drop_pending(transfer_id);
+			}
+			self.paused_locks.remove(&transfer_id);
+			self.backlog_locks.retain(|held| held.action.transfer_id != transfer_id);
+			self.rate_limited_locks.retain(|held| held.action.transfer_id != transfer_id);
+			tracing::warn!(
+				target: "bridge_alert",
+				"Time lock of transfer {transfer_id} too close to send its held lock, refund it"
+			);
+			let (previous, (transfer, action)) = match self.swap_state_map.remove(&transfer_id) {
+				Some(TransferState::Initialized(transfer)) => {
+					(TransferStateType::Initialized, transfer.refund())
+				}
+				Some(TransferState::PendingApproval(transfer)) => {
+					(TransferStateType::PendingApproval, transfer.deny())
+				}
+				Some(state) => {
+					self.swap_state_map.insert(transfer_id, state);
+					continue;
+				}
+				None => continue,
+			};
+			let state = transfer.into();
+			self.publish_state_change(
+				Some(previous),
+				&state,
+				None,
+				Some(ReasonCode::TimelockTooClose),
+			);
+			self.swap_state_map.insert(transfer_id, state);
+			if let Err(err) = self.index_transfer_action(action.clone()) {
+				tracing::warn!("Fail to index refund action {action}: {err}");
 			}
 		}
 	}
 
+	/// Return the held locks that can be sent: the ones whose direction is no longer
+	/// paused and the backlogged ones, oldest first, while under the in-flight cap.
+	/// The rate limited locks are backlogged once the rate limit window has room for them.
+	pub fn release_held_locks(&mut self) -> Vec<TransferAction> {
+		self.refund_late_locks();
+		let rate_limiter = &self.rate_limiter;
+		let (released, limited): (VecDeque<_>, VecDeque<_>) =
+			std::mem::take(&mut self.rate_limited_locks)
+				.into_iter()
+				.partition(|held| rate_limiter.release(&held.action));
+		self.rate_limited_locks = limited;
+		self.backlog_locks.extend(released);
+		// Resumed locks go through the backlog to respect the cap.
+		let resumed: Vec<BridgeTransferId> = self
+			.paused_locks
+			.iter()
+			.filter(|(_, held)| !self.pause_switches.is_paused(held.direction))
+			.map(|(transfer_id, _)| *transfer_id)
+			.collect();
+		for transfer_id in resumed {
+			if let Some(held) = self.paused_locks.remove(&transfer_id) {
+				self.backlog_locks.push_back(held);
+			}
+		}
+
+		let mut actions = Vec::new();
+		while self.intake_limit.has_capacity(self.in_flight()) {
+			let Some(held) = self.backlog_locks.pop_front() else {
+				break;
+			};
+			// The direction has been paused while the transfer was backlogged.
+			if self.pause_switches.is_paused(held.direction) {
+				self.paused_locks.insert(held.action.transfer_id, held);
+				continue;
+			}
+			let action = self.split_lock(held.action);
+			if let Err(err) = self.index_transfer_action(action.clone()) {
+				tracing::warn!("Fail to index released lock {action}: {err}");
+			}
+			actions.push(action);
+		}
+		self.intake_limit.update(self.in_flight(), self.backlog_locks.len());
+		actions
+	}
+
+	// Return the lock to send for a transfer, splitting it into child HTLCs
+	// if the split policy applies. The locks of the other children are queued.
+	fn split_lock(&mut self, action: TransferAction) -> TransferAction {
+		match self.split_transfers.split(&action) {
+			Some(mut child_actions) => {
+				let first = child_actions.remove(0);
+				for action in &child_actions {
+					if let Err(err) = self.index_transfer_action(action.clone()) {
+						tracing::warn!("Fail to index child lock {action}: {err}");
+					}
+				}
+				self.queued_actions.extend(child_actions);
+				first
+			}
+			None => action,
+		}
+	}
+
 	pub fn index_transfer_action(
 		&mut self,
 		action: TransferAction,
 	) -> Result<(), InvalidEventError> {
+		self.event_bus
+			.publish(BusEvent::Action(action.clone(), SubmissionStatus::Planned));
+		self.track_submission(&action, SubmissionStatus::Planned);
+		// The outbox is written here rather than by a bus subscriber
+		// so the submission is recorded before it is sent.
+		// The pending status updates are written first to keep the outbox in order.
+		self.flush_submission_updates();
 		match self.indexer_db_client {
 			Some(ref mut client) => {
-				let action = action.kind;
-				client.insert_transfer_action(action.clone()).map_err(|err| {
-					tracing::warn!("Fail to index action");
+				// Record the submission in the outbox before it is sent.
+				client.record_planned_submission(&action).map_err(|err| {
+					tracing::warn!("Fail to record action {action} in the outbox");
 					InvalidEventError::BadEvent(err.to_string())
 				})?;
-				tracing::info!("index_transfer_action(success): {action:?}");
+				tracing::info!("index_transfer_action(success): {:?}", action.kind);
 				Ok(())
 			}
 			None => {
@@ -329,6 +1141,292 @@ impl Runtime {
 		}
 	}
 
+	// Publish a change of state of a transfer with its next sequence number,
+	// and the reason of the refunds.
+	fn publish_state_change(
+		&mut self,
+		previous: Option<TransferStateType>,
+		state: &TransferState,
+		cause: Option<EventRef>,
+		reason: Option<ReasonCode>,
+	) {
+		let new = state.state_type();
+		if previous == Some(new) {
+			return;
+		}
+		let sequence = self.state_sequences.entry(state.transfer_id).or_default();
+		*sequence += 1;
+		let sequence = *sequence;
+		self.store_transfer(state, sequence);
+		// No change follows the end of the transfer.
+		if new.is_final() {
+			self.state_sequences.remove(&state.transfer_id);
+		}
+		self.event_bus.publish(BusEvent::StateChange(StateChange {
+			transfer_id: state.transfer_id,
+			sequence,
+			previous,
+			new,
+			cause,
+			reason,
+		}));
+	}
+
+	// Write a change of state to the transfer store. A failed write is logged, the next change
+	// of the transfer writes it again.
+	fn store_transfer(&self, state: &TransferState, sequence: u64) {
+		let Some(store) = &self.transfer_store else {
+			return;
+		};
+		let transfer = store::stored_transfer(state, sequence, store::now_secs());
+		if let Err(err) = store.transition(&transfer) {
+			tracing::warn!("Failed to store transfer {}: {err}", state.transfer_id);
+		}
+	}
+
+	/// Resume a transfer read from the transfer store and return the action to execute.
+	/// `locked` tells if the lock of an initialized transfer is on the counterparty chain, None
+	/// if it couldn't be read. The locks to send again go through the backlog, so the cap and
+	/// the lock margin apply. The events emitted while the relayer was stopped aren't replayed.
+	pub fn restore_transfer(
+		&mut self,
+		transfer: InFlightTransfer,
+		locked: Option<bool>,
+	) -> Option<TransferAction> {
+		let transfer_id = transfer.transfer_id;
+		let state = store::restored_state(&transfer)?;
+		self.state_sequences.insert(transfer_id, transfer.sequence);
+		let init_chain = transfer.init_chain;
+		let direction = TransferDirection::from_init_chain(init_chain);
+		let lock = TransferAction {
+			chain: init_chain.other(),
+			transfer_id,
+			kind: TransferActionType::LockBridgeTransfer {
+				bridge_transfer_id: transfer_id,
+				hash_lock: transfer.hash_lock,
+				initiator: BridgeAddress(transfer.initiator.clone()),
+				recipient: BridgeAddress(transfer.recipient.clone()),
+				amount: Amount(self.fee_schedule.quote_fee(state.amount).net_amount),
+			},
+		};
+		let held = HeldLock { direction, time_lock: transfer.time_lock, action: lock };
+		let kind = match (&state, locked) {
+			// The Locked event has been emitted while the relayer was stopped.
+			(TransferState::Initialized(_), Some(true)) => {
+				let locked =
+					InFlightTransfer { state: Some(TransferStateType::Locked), ..transfer };
+				let locked = store::restored_state(&locked)?;
+				self.publish_state_change(
+					Some(TransferStateType::Initialized),
+					&locked,
+					None,
+					None,
+				);
+				self.swap_state_map.insert(transfer_id, locked);
+				return None;
+			}
+			(TransferState::Initialized(_), Some(false)) => {
+				self.backlog_locks.push_back(held);
+				TransferActionType::NoAction
+			}
+			(TransferState::Initialized(_), None) => {
+				tracing::error!(
+					target: "bridge_alert",
+					"Lock of transfer {transfer_id} not read on chain, lock it by hand if it's missing"
+				);
+				TransferActionType::NoAction
+			}
+			// The approval queue restores the transfer pending, the decision releases its lock.
+			(TransferState::PendingApproval(_), _) => {
+				self.held_actions.insert(transfer_id, held);
+				TransferActionType::NoAction
+			}
+			(TransferState::SecretReceived(_), _) => match transfer.pre_image {
+				Some(pre_image) => TransferActionType::WaitAndCompleteInitiator(0, pre_image),
+				None => {
+					tracing::error!(
+						target: "bridge_alert",
+						"Secret of transfer {transfer_id} not stored, complete it by hand before it expires"
+					);
+					TransferActionType::NoAction
+				}
+			},
+			(TransferState::Refund(_), _) => TransferActionType::RefundInitiator,
+			_ => TransferActionType::NoAction,
+		};
+		self.swap_state_map.insert(transfer_id, state);
+		if matches!(kind, TransferActionType::NoAction) {
+			return None;
+		}
+		let action = TransferAction { chain: init_chain, transfer_id, kind };
+		if let Err(err) = self.index_transfer_action(action.clone()) {
+			tracing::warn!("Fail to index resumed action {action}: {err}");
+		}
+		Some(action)
+	}
+
+	/// Plan again a failed submission before it's retried, its transaction is reported once
+	/// sent.
+	pub fn submission_retried(&mut self, action: &TransferAction) {
+		self.update_submission(action, SubmissionStatus::Planned);
+	}
+
+	/// Record the submission of the transaction reported by a client as sent, with the hash
+	/// of the transaction.
+	pub fn transaction_sent(&mut self, sent: SentTransaction) {
+		let Some(action_kind) = sent.action_kind() else {
+			return;
+		};
+		let key = (sent.chain, sent.transfer_id, action_kind.to_string());
+		let Some(action) = self.unconfirmed_submissions.get(&key).cloned() else {
+			tracing::debug!("Transaction {} sent for no submission of the outbox", sent.tx_hash);
+			return;
+		};
+		self.event_bus.publish(BusEvent::Action(action.clone(), SubmissionStatus::Sent));
+		// The pending status updates are older, they're written first to keep the outbox in
+		// order.
+		self.flush_submission_updates();
+		if let Some(ref mut client) = self.indexer_db_client {
+			if let Err(err) = client.mark_submission_sent(&action, &sent.tx_hash) {
+				tracing::warn!(
+					"Fail to record the transaction {} of {action} in the outbox: {err}",
+					sent.tx_hash
+				);
+			}
+		}
+	}
+
+	pub fn submission_confirmed(&mut self, action: &TransferAction) {
+		self.update_submission(action, SubmissionStatus::Confirmed);
+	}
+
+	// Record the result of the submitted transactions executed within the shutdown grace
+	// period, the others are left sent in the outbox.
+	async fn wait_submissions(
+		&mut self,
+		mut in_flight: impl futures::Stream<
+				Item = Result<Vec<Result<TransferAction, ActionExecError>>, tokio::task::JoinError>,
+			> + Unpin,
+		mut pending: usize,
+	) {
+		let deadline = tokio::time::Instant::now() + SHUTDOWN_GRACE_PERIOD;
+		loop {
+			match tokio::time::timeout_at(deadline, in_flight.next()).await {
+				Ok(Some(Ok(results))) => {
+					pending -= 1;
+					for result in results {
+						match result {
+							Ok(action) => self.submission_confirmed(&action),
+							Err(action_err) => {
+								let (action, err) = action_err.inner();
+								tracing::warn!(
+									"Client execution error for action:{action} err:{err}"
+								);
+								self.update_submission(&action, SubmissionStatus::Failed);
+							}
+						}
+					}
+				}
+				Ok(Some(Err(err))) => {
+					pending -= 1;
+					tracing::warn!("Submission task failed on shutdown: {err}");
+				}
+				Ok(None) => break,
+				Err(_) => {
+					tracing::warn!("{pending} submitted transactions not executed before shutdown");
+					break;
+				}
+			}
+		}
+	}
+
+	// Keep the submissions of the outbox until they're executed, to match the transactions
+	// reported by the clients.
+	fn track_submission(&mut self, action: &TransferAction, status: SubmissionStatus) {
+		if !matches!(
+			action.kind,
+			TransferActionType::LockBridgeTransfer { .. }
+				| TransferActionType::WaitAndCompleteInitiator(..)
+		) {
+			return;
+		}
+		let key = (action.chain, action.transfer_id, action.kind.to_string());
+		match status {
+			SubmissionStatus::Planned => {
+				self.unconfirmed_submissions.insert(key, action.clone());
+			}
+			SubmissionStatus::Confirmed | SubmissionStatus::Failed => {
+				self.unconfirmed_submissions.remove(&key);
+			}
+			SubmissionStatus::Sent => (),
+		}
+	}
+
+	fn update_submission(&mut self, action: &TransferAction, status: SubmissionStatus) {
+		self.track_submission(action, status);
+		self.event_bus.publish(BusEvent::Action(action.clone(), status));
+		if self.indexer_db_client.is_some() {
+			self.pending_submission_updates.push((action.clone(), status));
+			if self.pending_submission_updates.len() >= SUBMISSION_UPDATES_BATCH_SIZE {
+				self.flush_submission_updates();
+			}
+		}
+	}
+
+	/// Write the pending status updates of the outbox.
+	/// The transaction is rolled back when an update fails, the updates are then written one
+	/// by one and only the failed ones are retried at the next flush.
+	pub fn flush_submission_updates(&mut self) {
+		if self.pending_submission_updates.is_empty() && self.failed_submission_updates.is_empty() {
+			return;
+		}
+		// The retried updates are older, they're written first to keep the outbox in order.
+		let mut updates = std::mem::take(&mut self.failed_submission_updates);
+		let retried_count = updates.len();
+		updates.append(&mut self.pending_submission_updates);
+		let Some(ref mut client) = self.indexer_db_client else {
+			return;
+		};
+		let Err(err) = client.update_submission_statuses(&updates) else {
+			return;
+		};
+		tracing::warn!("Fail to write {} status updates in the outbox: {err}", updates.len());
+		let written: Vec<bool> = updates
+			.iter()
+			.map(|(action, status)| match client.update_submission_status(action, *status) {
+				Ok(()) => true,
+				Err(err) => {
+					tracing::warn!(
+						"Fail to write the {status:?} status of {action} in the outbox: {err}"
+					);
+					false
+				}
+			})
+			.collect();
+		let outbox_key =
+			|action: &TransferAction| (action.chain, action.transfer_id, action.kind.to_string());
+		for (index, (action, status)) in updates.iter().enumerate() {
+			if written[index] {
+				continue;
+			}
+			// A later update of the submission written supersedes the failed one.
+			let superseded =
+				updates.iter().zip(&written).skip(index + 1).any(|((later, _), written)| {
+					*written && outbox_key(later) == outbox_key(action)
+				});
+			if superseded {
+				continue;
+			}
+			if index < retried_count {
+				tracing::error!(
+					"Status {status:?} of {action} not written in the outbox after a retry, it stays unconfirmed"
+				);
+			} else {
+				self.failed_submission_updates.push((action.clone(), *status));
+			}
+		}
+	}
+
 	pub fn process_event<A>(
 		&mut self,
 		event: TransferEvent<A>,
@@ -336,18 +1434,107 @@ impl Runtime {
 	where
 		A: Into<Vec<u8>> + std::clone::Clone + std::fmt::Debug,
 	{
-		tracing::info!("Event received: {:?}", event);
-		self.validate_state(&event)?;
-		let indexer_event = event.clone();
-		self.index_event(indexer_event)?;
+		tracing::info!("Event received: {event}");
 		let event_transfer_id = event.contract_event.bridge_transfer_id();
+		if let Some(parent_id) = self.split_transfers.parent_of(&event_transfer_id) {
+			self.event_bus.publish(event.clone());
+			return self.process_child_event(parent_id, event);
+		}
+		self.validate_state(&event)?;
+		self.event_bus.publish(event.clone());
+		let cause = EventRef::from(&event);
 		let state_opt = self.swap_state_map.remove(&event_transfer_id);
+		// The transfer has been closed on chain before the operator decision.
+		if self.held_actions.remove(&event_transfer_id).is_some() {
+			self.approval_queue.drop_pending(event_transfer_id);
+		}
+		// or before its lock has been released.
+		self.paused_locks.remove(&event_transfer_id);
+		self.backlog_locks.retain(|held| held.action.transfer_id != event_transfer_id);
+		self.rate_limited_locks
+			.retain(|held| held.action.transfer_id != event_transfer_id);
 		//create swap state if need
-		let mut state = if let BridgeContractEvent::Initiated(detail) = event.contract_event {
-			let (state, mut action) =
-				TransferState::transition_from_initiated(event.chain, event_transfer_id, detail);
-			action.chain = state.init_chain.other();
-			self.swap_state_map.insert(state.transfer_id, state);
+		let state = if let BridgeContractEvent::Initiated(detail) = event.contract_event {
+			let (transfer, mut action) =
+				InitiatedTransfer::new(event.chain, event_transfer_id, detail);
+			action.chain = transfer.init_chain.other();
+			let direction = TransferDirection::from_init_chain(transfer.init_chain);
+			if !self.enabled_directions.is_enabled(direction) {
+				tracing::warn!(
+					"{direction} transfers are disabled, refund transfer {}",
+					transfer.transfer_id
+				);
+				return self.refund_initiated(transfer, cause, ReasonCode::DirectionDisabled);
+			}
+			let fee = self.fee_schedule.quote_fee(transfer.amount);
+			if fee.fee > 0 && fee.net_amount == 0 {
+				tracing::warn!(
+					"Amount {} doesn't cover the relayer fee, refund transfer {}",
+					fee.amount,
+					transfer.transfer_id
+				);
+				return self.refund_initiated(transfer, cause, ReasonCode::FeeNotCovered);
+			}
+			if let TransferActionType::LockBridgeTransfer { ref mut amount, .. } = action.kind {
+				*amount = Amount(fee.net_amount);
+			}
+			// The initiation is observed too late, the recipient couldn't complete the lock.
+			if !self.lock_margin.allows_lock(transfer.init_chain, transfer.time_lock.0) {
+				tracing::warn!(
+					target: "bridge_alert",
+					"Time lock of transfer {} too close to lock it, refund it",
+					transfer.transfer_id
+				);
+				return self.refund_initiated(transfer, cause, ReasonCode::TimelockTooClose);
+			}
+			let time_lock = transfer.time_lock.0;
+			if self.approval_queue.requires_approval(transfer.amount) {
+				// Hold the lock until an operator approves the transfer.
+				let transfer = transfer.hold_for_approval();
+				self.approval_queue.queue(
+					transfer.transfer_id,
+					transfer.init_chain,
+					transfer.amount,
+				);
+				self.held_actions.insert(
+					transfer.transfer_id,
+					HeldLock { direction, time_lock, action: action.clone() },
+				);
+				self.accrue_fee(transfer.transfer_id, fee.fee);
+				let state = transfer.into();
+				self.publish_state_change(None, &state, Some(cause), None);
+				self.swap_state_map.insert(event_transfer_id, state);
+				return Ok(TransferAction { kind: TransferActionType::NoAction, ..action });
+			}
+			// The transfers held for approval aren't rate limited, only counted once approved.
+			let action = match self.rate_limiter.check(&action) {
+				RateLimitDecision::Admit => {
+					self.schedule_lock(HeldLock { direction, time_lock, action })
+				}
+				RateLimitDecision::Defer => {
+					tracing::warn!(
+						target: "bridge_alert",
+						"Rate limit reached, defer lock of {}",
+						action.transfer_id
+					);
+					let no_action =
+						TransferAction { kind: TransferActionType::NoAction, ..action.clone() };
+					self.rate_limited_locks.push_back(HeldLock { direction, time_lock, action });
+					no_action
+				}
+				RateLimitDecision::Reject => {
+					tracing::warn!(
+						target: "bridge_alert",
+						"Rate limit exceeded, refund transfer {}",
+						transfer.transfer_id
+					);
+					return self.refund_initiated(transfer, cause, ReasonCode::RateLimited);
+				}
+			};
+			self.accrue_fee(transfer.transfer_id, fee.fee);
+			let state = transfer.into();
+			self.publish_state_change(None, &state, Some(cause), None);
+			self.swap_state_map.insert(event_transfer_id, state);
 			self.index_transfer_action(action.clone())?;
 			return Ok(action);
 		} else {
@@ -355,40 +1542,14 @@ impl Runtime {
 			state_opt.unwrap()
 		};
 
-		let (action_kind, chain_id) = match event.contract_event {
-			BridgeContractEvent::Initiated(_) => unreachable!(),
-			BridgeContractEvent::Locked(detail) => {
-				let (new_state, action_kind) =
-					state.transition_from_locked_done(event_transfer_id, detail);
-				state = new_state;
-				(action_kind, state.init_chain)
-			}
-			BridgeContractEvent::CounterPartyCompleted(_, preimage) => {
-				let (new_state, action_kind) =
-					state.transition_from_counterpart_completed(event_transfer_id, preimage);
-				state = new_state;
-				(action_kind, state.init_chain)
-			}
-			BridgeContractEvent::InitiatorCompleted(_) => {
-				let (new_state, action_kind) =
-					state.transition_from_initiator_completed(event_transfer_id);
-				state = new_state;
-
-				(action_kind, state.init_chain)
-			}
-			BridgeContractEvent::Cancelled(_) => {
-				let (new_state, action_kind) = state.transition_from_cancelled(event_transfer_id);
-				state = new_state;
-
-				(action_kind, state.init_chain)
-			}
-			BridgeContractEvent::Refunded(_) => {
-				let (new_state, action_kind) = state.transition_from_refunded(event_transfer_id);
-				state = new_state;
-
-				(action_kind, state.init_chain)
-			}
-		};
+		let previous = state.state_type();
+		let (state, action_kind) = state.apply_event(event.contract_event)?;
+		// The contracts refund a transfer once its time lock has expired.
+		let reason =
+			matches!(state.state_type(), TransferStateType::Refund | TransferStateType::LockAborted)
+				.then_some(ReasonCode::TimelockExpired);
+		self.publish_state_change(Some(previous), &state, Some(cause), reason);
+		let chain_id = state.init_chain;
 
 		let action =
 			TransferAction { chain: chain_id, transfer_id: state.transfer_id, kind: action_kind };
@@ -397,12 +1558,211 @@ impl Runtime {
 		// todo: really this should come after process_action completion, but the current use of process_action is hacky
 		self.index_transfer_action(action.clone())?;
 
-		if state.state != TransferStateType::Done {
+		if matches!(state, TransferState::Completed(_)) {
+			self.record_completed(state.transfer_id);
+		}
+		let action_pending = !matches!(action.kind, TransferActionType::NoAction);
+		if !state.state_type().is_final() && (!self.stateless || action_pending) {
 			self.swap_state_map.insert(state.transfer_id, state);
 		}
 		Ok(action)
 	}
 
+	/// Remember a transfer completed on its init chain, the oldest ones are forgotten.
+	fn record_completed(&mut self, transfer_id: BridgeTransferId) {
+		if self.completed_transfers.len() == COMPLETED_TRANSFERS_KEPT {
+			self.completed_transfers.pop_front();
+		}
+		self.completed_transfers.push_back(transfer_id);
+	}
+
+	/// Apply an event of a child HTLC to the split transfer it belongs to.
+	fn process_child_event<A>(
+		&mut self,
+		parent_id: BridgeTransferId,
+		event: TransferEvent<A>,
+	) -> Result<TransferAction, InvalidEventError>
+	where
+		A: Into<Vec<u8>> + std::clone::Clone + std::fmt::Debug,
+	{
+		let child_id = event.contract_event.bridge_transfer_id();
+		// Children are locked on the counterparty chain.
+		if self
+			.swap_state_map
+			.get(&parent_id)
+			.is_some_and(|state| state.init_chain == event.chain)
+		{
+			return Err(InvalidEventError::BadChain);
+		}
+		let cause = EventRef::from(&event);
+		let state = self.swap_state_map.remove(&parent_id);
+		let previous = state.as_ref().map(TransferState::state_type);
+		let no_action = TransferAction {
+			chain: event.chain,
+			transfer_id: child_id,
+			kind: TransferActionType::NoAction,
+		};
+
+		let (state, action) = match (state, event.contract_event) {
+			(state, BridgeContractEvent::Locked(detail)) => {
+				match (self.split_transfers.child_locked(child_id), state) {
+					(ChildLocked::Next(action), state) => (state, action),
+					(ChildLocked::AllLocked, Some(TransferState::Initialized(transfer))) => {
+						let (transfer, _) = transfer.lock_done(detail);
+						// A child may have been completed before the last one was locked.
+						match self.split_transfers.secret(&parent_id) {
+							Some(secret) => {
+								let (transfer, kind) = transfer.counterpart_completed(secret);
+								let action = TransferAction {
+									chain: transfer.init_chain,
+									transfer_id: parent_id,
+									kind,
+								};
+								(Some(transfer.into()), action)
+							}
+							None => (Some(transfer.into()), no_action),
+						}
+					}
+					(_, state) => (state, no_action),
+				}
+			}
+			(state, BridgeContractEvent::CounterPartyCompleted(_, preimage)) => {
+				match (self.split_transfers.child_completed(child_id, preimage), state) {
+					// The first revealed secret completes the whole transfer on the init chain.
+					(Some(secret), Some(TransferState::Locked(transfer))) => {
+						let (transfer, kind) = transfer.counterpart_completed(secret);
+						let action = TransferAction {
+							chain: transfer.init_chain,
+							transfer_id: parent_id,
+							kind,
+						};
+						(Some(transfer.into()), action)
+					}
+					(_, state) => (state, no_action),
+				}
+			}
+			(state, BridgeContractEvent::Cancelled(_)) => {
+				let all_aborted = self.split_transfers.child_aborted(child_id);
+				match state {
+					// All the children have been aborted, the transfer is over.
+					Some(TransferState::Locked(_)) if all_aborted => (None, no_action),
+					state => (state, no_action),
+				}
+			}
+			(state, event) => {
+				if let Some(state) = state {
+					self.swap_state_map.insert(parent_id, state);
+				}
+				return Err(InvalidEventError::BadEvent(format!(
+					"{event} can't be applied on a child of split transfer {parent_id}"
+				)));
+			}
+		};
+
+		if let Some(state) = state {
+			self.publish_state_change(previous, &state, Some(cause), None);
+			self.swap_state_map.insert(parent_id, state);
+		}
+		self.index_transfer_action(action.clone())?;
+		Ok(action)
+	}
+
+	/// Resume or refund a transfer pending approval depending on the operator decision.
+	fn process_approval_decision(&mut self, request: ApprovalRequest) -> Option<TransferAction> {
+		let Some(held) = self.held_actions.remove(&request.transfer_id) else {
+			tracing::warn!(
+				"Receive a decision for transfer {} not pending approval",
+				request.transfer_id
+			);
+			return None;
+		};
+		let Some(state) = self.swap_state_map.remove(&request.transfer_id) else {
+			tracing::warn!("Receive a decision but no state found for id:{}", request.transfer_id);
+			return None;
+		};
+		let TransferState::PendingApproval(transfer) = state else {
+			tracing::warn!(
+				"Receive a decision for transfer {} in state {}",
+				request.transfer_id,
+				state.state_type()
+			);
+			self.swap_state_map.insert(request.transfer_id, state);
+			return None;
+		};
+		self.approval_queue.resolve(&request);
+		let (state, action, reason): (TransferState, _, _) = match request.decision {
+			// The decision came too late, the recipient couldn't complete the lock.
+			ApprovalDecision::Approve
+				if !self.lock_margin.allows_lock(held.init_chain(), held.time_lock) =>
+			{
+				tracing::warn!(
+					target: "bridge_alert",
+					"Time lock of transfer {} too close to lock it once approved, refund it",
+					request.transfer_id
+				);
+				let (transfer, action) = transfer.deny();
+				(transfer.into(), action, Some(ReasonCode::TimelockTooClose))
+			}
+			ApprovalDecision::Approve => {
+				self.rate_limiter.record(&held.action);
+				(transfer.approve().into(), self.schedule_lock(held), None)
+			}
+			ApprovalDecision::Deny => {
+				let (transfer, action) = transfer.deny();
+				(transfer.into(), action, Some(ReasonCode::ApprovalRejected))
+			}
+		};
+		self.publish_state_change(Some(TransferStateType::PendingApproval), &state, None, reason);
+		self.swap_state_map.insert(request.transfer_id, state);
+		if let Err(err) = self.index_transfer_action(action.clone()) {
+			tracing::warn!("Fail to index approval action {action}: {err}");
+		}
+		Some(action)
+	}
+
+	/// Compensate a transfer whose Initiated event has been dropped by a reorg: its held lock
+	/// is dropped, and a lock sent on the counterpart chain is left to the time lock watcher,
+	/// which aborts it once its time lock has expired.
+	fn process_dropped_initiation(&mut self, transfer_id: BridgeTransferId) {
+		let pending_approval = self.held_actions.remove(&transfer_id).is_some();
+		if pending_approval {
+			self.approval_queue.drop_pending(transfer_id);
+		}
+		let paused = self.paused_locks.remove(&transfer_id).is_some();
+		let backlog_len = self.backlog_locks.len();
+		self.backlog_locks.retain(|held| held.action.transfer_id != transfer_id);
+		let limited_len = self.rate_limited_locks.len();
+		self.rate_limited_locks.retain(|held| held.action.transfer_id != transfer_id);
+		let lock_sent = !pending_approval
+			&& !paused && self.backlog_locks.len() == backlog_len
+			&& self.rate_limited_locks.len() == limited_len;
+
+		let Some(state) = self.swap_state_map.remove(&transfer_id) else {
+			tracing::warn!("Initiation of transfer {transfer_id} dropped but no state found");
+			return;
+		};
+		let previous = state.state_type();
+		if previous == TransferStateType::SecretReceived {
+			tracing::error!(
+				"Initiation of transfer {transfer_id} dropped after its completion on the counterpart chain"
+			);
+		}
+		let state = state.drop_initiation(lock_sent);
+		self.publish_state_change(Some(previous), &state, None, None);
+		if state.state_type() == TransferStateType::Refund {
+			// The lock can't be aborted before its time lock expires, the relayer funds stay
+			// locked until then and the recipient can complete it if they know the secret.
+			tracing::error!(
+				target: "bridge_alert",
+				"Initiation of transfer {transfer_id} dropped by a reorg after its lock on {}, the lock is aborted once its time lock expires",
+				state.init_chain.other()
+			);
+		}
+		if !state.state_type().is_final() {
+			self.swap_state_map.insert(transfer_id, state);
+		}
+	}
+
 	fn validate_state<A: std::fmt::Debug>(
 		&mut self,
 		event: &TransferEvent<A>,
@@ -416,7 +1776,7 @@ impl Runtime {
 			tracing::info!(
 				"Found existing state for transfer ID {:?}: {:?}",
 				event_transfer_id,
-				state.state
+				state.state_type()
 			);
 		} else {
 			tracing::info!("No existing state found for transfer ID {:?}", event_transfer_id);
@@ -438,38 +1798,93 @@ impl Runtime {
 		Ok(())
 	}
 
-	fn process_action_exec_error(&mut self, action_err: ActionExecError) -> Option<TransferAction> {
+	fn process_action_exec_error(
+		&mut self,
+		action_err: ActionExecError,
+	) -> Option<(TransferAction, std::time::Duration)> {
 		// Manage Tx execution error
 		let (action, err) = action_err.inner();
+		self.update_submission(&action, SubmissionStatus::Failed);
+		// The retries of a child HTLC are counted on its split transfer.
+		let state_id = self
+			.split_transfers
+			.parent_of(&action.transfer_id)
+			.unwrap_or(action.transfer_id);
+		// The user completed the transfer with the secret revealed by the counterparty
+		// completion before the relayer did, its completion is skipped.
+		if matches!(action.kind, TransferActionType::WaitAndCompleteInitiator(..))
+			&& self.completed_transfers.contains(&state_id)
+		{
+			tracing::info!(
+				"Transfer {} already completed on chain, skip the relayer completion",
+				action.transfer_id
+			);
+			return None;
+		}
 		tracing::warn!("Client execution error for action:{action} err:{err}");
-		// retry 5 time an action in error then abort.
-		match self.swap_state_map.get_mut(&action.transfer_id) {
+		// retry the action in error depending on the retry table then abort.
+		match self.swap_state_map.get_mut(&state_id) {
 			Some(state) => {
 				state.retry_on_error += 1;
-				if state.retry_on_error > 5 {
+				let retry = self.retry_table.classify(&err, state.retry_on_error);
+				if let Some(backoff) = retry {
+					//Rerun the action.
+					Some((action, backoff))
+				} else {
+					tracing::warn!(
+						"Action:{action} aborted after {} try, error kind:{}",
+						state.retry_on_error,
+						err.kind()
+					);
 					// Depending on the action cancel transfer
 					match action.kind {
 						TransferActionType::LockBridgeTransfer { .. } => {
-							//Lock fail. Refund initiator
-							let (new_state_type, action_kind) = state.transition_to_refund();
-							state.state = new_state_type;
-							let action = TransferAction {
-								chain: state.init_chain,
-								transfer_id: state.transfer_id,
-								kind: action_kind,
-							};
-							Some(action)
+							//Lock fail. Refund initiator if none of its children is locked.
+							let transfer_id = state_id;
+							match self.swap_state_map.remove(&transfer_id) {
+								Some(TransferState::Initialized(transfer))
+									if !self.split_transfers.any_locked(&transfer_id) =>
+								{
+									let (transfer, action) = transfer.refund();
+									let state = transfer.into();
+									self.publish_state_change(
+										Some(TransferStateType::Initialized),
+										&state,
+										None,
+										Some(err.reason_code()),
+									);
+									self.swap_state_map.insert(transfer_id, state);
+									Some((action, std::time::Duration::ZERO))
+								}
+								Some(state) => {
+									tracing::warn!(
+										"Lock of transfer {transfer_id} failed in state {}, no refund",
+										state.state_type()
+									);
+									self.swap_state_map.insert(transfer_id, state);
+									None
+								}
+								None => None,
+							}
 						}
+						// The state is kept: the completion can still be sent by hand before
+						// the initiation expires, the time lock watcher refunds it after.
 						TransferActionType::WaitAndCompleteInitiator(..) => {
-							todo!()
+							tracing::error!(
+								target: "bridge_alert",
+								"Completion of transfer {state_id} aborted, error kind:{}, complete it before its time lock expires",
+								err.kind()
+							);
+							None
 						}
-						TransferActionType::RefundInitiator => None, //will wait automatic refund
+						// Refunded by the time lock watcher once the initiation expires.
+						TransferActionType::RefundInitiator => None,
+						// The abort fails until the lock expires, the time lock watcher
+						// submits it again then.
+						TransferActionType::AbortCounterparty => None,
 						TransferActionType::TransferDone => None,
 						TransferActionType::NoAction => None,
 					}
-				} else {
-					//Rerun the action.
-					Some(action)
 				}
 			}
 			None => {
@@ -482,3 +1897,64 @@ impl Runtime {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bridge_config::common::rate_limit::RateLimitConfig;
+	use bridge_util::types::{BridgeTransferDetails, HashLock, TimeLock};
+
+	fn initiated(id: u8, amount: u64) -> TransferEvent<Vec<u8>> {
+		let bridge_transfer_id = BridgeTransferId([id; 32]);
+		TransferEvent {
+			chain: ChainId::ONE,
+			contract_event: BridgeContractEvent::Initiated(BridgeTransferDetails {
+				bridge_transfer_id,
+				initiator: BridgeAddress(vec![1; 20]),
+				recipient: BridgeAddress(vec![2; 32]),
+				hash_lock: HashLock([3; 32]),
+				time_lock: TimeLock(u64::MAX),
+				amount: Amount(amount),
+				state: 1,
+			}),
+		}
+	}
+
+	#[test]
+	fn test_dropped_initiation_of_rate_limited_lock() {
+		let rate_limiter = RateLimiter::from(&RateLimitConfig {
+			window_secs: 1,
+			max_amount_per_initiator: 50,
+			max_amount_global: 50,
+			defer_over_cap: true,
+		});
+		let (approval_queue, _decision_rx) = ApprovalQueue::new(u64::MAX);
+		let mut runtime = Runtime::new(
+			None,
+			RetryTable::default(),
+			false,
+			approval_queue,
+			EnabledDirections::default(),
+			EventBus::default(),
+			SplitTransfers::default(),
+			PauseSwitches::default(),
+			IntakeLimit::default(),
+			rate_limiter,
+			RelayerFeeSchedule::default(),
+			LockMargin::default(),
+			None,
+		);
+		let admitted = runtime.process_event(initiated(1, 50)).unwrap();
+		assert!(matches!(admitted.kind, TransferActionType::LockBridgeTransfer { .. }));
+		let deferred = runtime.process_event(initiated(2, 10)).unwrap();
+		assert!(matches!(deferred.kind, TransferActionType::NoAction));
+
+		// The deferred lock has never been sent, the transfer is closed.
+		let transfer_id = BridgeTransferId([2; 32]);
+		runtime.process_dropped_initiation(transfer_id);
+		assert!(!runtime.swap_state_map.contains_key(&transfer_id));
+		// Once the window has room again, the lock isn't sent.
+		std::thread::sleep(std::time::Duration::from_secs(1));
+		assert!(runtime.release_held_locks().is_empty());
+	}
+}
@@ -0,0 +1,81 @@
+//! Balances of the relayer accounts paying out the transfers on each chain, read in the
+//! background so the precheck can reject the transfers the relayer can't lock.
+use crate::chains::ethereum::client::EthClient;
+use crate::chains::movement::client_framework::MovementClientFramework;
+use bridge_util::types::TransferDirection;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Debug, Default)]
+struct Balances {
+	eth: Option<u64>,
+	movement: Option<u64>,
+}
+
+/// Last balances read of the relayer accounts, unknown until the first read.
+/// Clones share the same balances.
+#[derive(Debug, Clone, Default)]
+pub struct RelayerLiquidity {
+	balances: Arc<Mutex<Balances>>,
+}
+
+impl RelayerLiquidity {
+	fn lock(&self) -> std::sync::MutexGuard<'_, Balances> {
+		self.balances.lock().expect("Liquidity lock poisoned")
+	}
+
+	/// Amount the relayer can lock on the counterparty chain of the direction.
+	pub fn available(&self, direction: TransferDirection) -> Option<u64> {
+		let balances = self.lock();
+		match direction {
+			TransferDirection::EthToMovement => balances.movement,
+			TransferDirection::MovementToEth => balances.eth,
+		}
+	}
+
+	pub fn set_eth_balance(&self, balance: u64) {
+		self.lock().eth = Some(balance);
+	}
+
+	pub fn set_movement_balance(&self, balance: u64) {
+		self.lock().movement = Some(balance);
+	}
+
+	/// Read the balances on every interval. The last balance read is kept if a read fails.
+	pub async fn run(
+		self,
+		eth_client: EthClient,
+		movement_client: MovementClientFramework,
+		interval: Duration,
+	) {
+		let mut interval = tokio::time::interval(interval);
+		loop {
+			interval.tick().await;
+			match eth_client.move_token_balance().await {
+				Ok(balance) => self.set_eth_balance(u64::try_from(balance).unwrap_or(u64::MAX)),
+				Err(err) => tracing::warn!("Failed to read the Ethereum relayer balance: {err}"),
+			}
+			match movement_client.coin_balance().await {
+				Ok(balance) => self.set_movement_balance(balance),
+				Err(err) => tracing::warn!("Failed to read the Movement relayer balance: {err}"),
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_available_liquidity() {
+		let liquidity = RelayerLiquidity::default();
+		assert_eq!(liquidity.available(TransferDirection::EthToMovement), None);
+
+		liquidity.set_eth_balance(10);
+		liquidity.clone().set_movement_balance(20);
+		// The transfers are paid out on the counterparty chain.
+		assert_eq!(liquidity.available(TransferDirection::EthToMovement), Some(20));
+		assert_eq!(liquidity.available(TransferDirection::MovementToEth), Some(10));
+	}
+}
@@ -0,0 +1,267 @@
+//! Live changes of state of the transfers, streamed to the bridge frontends on a websocket of
+//! the REST API so they show the progress of a transfer without polling its status.
+use crate::event_bus::{BusEvent, Subscription, DEFAULT_BUS_CAPACITY};
+use crate::webhooks::TransferNotification;
+use bridge_config::common::live_updates::LiveUpdatesConfig;
+use bridge_util::chains::bridge_contracts::BridgeContractEvent;
+use bridge_util::types::BridgeTransferId;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Change of state of a transfer, with its accounts once its initiation or lock is seen.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LiveUpdate {
+	#[serde(flatten)]
+	pub notification: TransferNotification,
+	pub initiator: Option<String>,
+	pub recipient: Option<String>,
+}
+
+/// Query of the websocket: a comma separated list of transfer ids, or an account initiating
+/// or receiving the transfers. Without any, all the transfers are streamed.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LiveUpdatesQuery {
+	#[serde(default)]
+	pub transfer_ids: Option<String>,
+	#[serde(default)]
+	pub address: Option<String>,
+}
+
+/// Updates streamed to a websocket.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LiveFilter {
+	transfer_ids: HashSet<String>,
+	address: Option<String>,
+}
+
+impl TryFrom<&LiveUpdatesQuery> for LiveFilter {
+	type Error = String;
+
+	fn try_from(query: &LiveUpdatesQuery) -> Result<Self, Self::Error> {
+		let transfer_ids = query
+			.transfer_ids
+			.iter()
+			.flat_map(|ids| ids.split(','))
+			.filter(|id| !id.trim().is_empty())
+			.map(|id| {
+				let id = id.trim();
+				BridgeTransferId::parse(id.strip_prefix("0x").unwrap_or(id))
+					.map(|id| hex_string(&id.0))
+					.map_err(|err| format!("Invalid transfer id {id}: {err}"))
+			})
+			.collect::<Result<_, _>>()?;
+		let address = query
+			.address
+			.as_deref()
+			.map(|address| {
+				hex::decode(address.strip_prefix("0x").unwrap_or(address))
+					.map(|bytes| hex_string(&bytes))
+					.map_err(|err| format!("Invalid address {address}: {err}"))
+			})
+			.transpose()?;
+		Ok(LiveFilter { transfer_ids, address })
+	}
+}
+
+impl LiveFilter {
+	pub fn new(
+		transfer_ids: impl IntoIterator<Item = BridgeTransferId>,
+		address: Option<&[u8]>,
+	) -> Self {
+		LiveFilter {
+			transfer_ids: transfer_ids.into_iter().map(|id| hex_string(&id.0)).collect(),
+			address: address.map(hex_string),
+		}
+	}
+
+	pub fn matches(&self, update: &LiveUpdate) -> bool {
+		let transfer_id = &update.notification.transfer_id;
+		(self.transfer_ids.is_empty() || self.transfer_ids.contains(transfer_id))
+			&& self.address.as_ref().map_or(true, |address| {
+				update.initiator.as_ref() == Some(address)
+					|| update.recipient.as_ref() == Some(address)
+			})
+	}
+}
+
+fn hex_string(bytes: &[u8]) -> String {
+	format!("0x{}", hex::encode(bytes))
+}
+
+/// Updates of a websocket. Its connection is counted until it's dropped.
+pub struct LiveStream {
+	updates: broadcast::Receiver<LiveUpdate>,
+	_connection: OwnedSemaphorePermit,
+}
+
+impl LiveStream {
+	/// Wait for the next update of the filter, None once the relayer stops.
+	/// A websocket falling behind skips the oldest updates.
+	pub async fn next(&mut self, filter: &LiveFilter) -> Option<LiveUpdate> {
+		loop {
+			match self.updates.recv().await {
+				Ok(update) if filter.matches(&update) => return Some(update),
+				Ok(_) => continue,
+				Err(RecvError::Lagged(skipped)) => {
+					tracing::warn!("Live updates websocket lagging, {skipped} updates skipped")
+				}
+				Err(RecvError::Closed) => return None,
+			}
+		}
+	}
+}
+
+/// Broadcasts the changes of state of the event bus to the websockets. Clones share the same
+/// websockets.
+#[derive(Debug, Clone)]
+pub struct LiveUpdates {
+	tx: broadcast::Sender<LiveUpdate>,
+	// Initiator and recipient of the transfers not done.
+	accounts: Arc<Mutex<HashMap<BridgeTransferId, (String, String)>>>,
+	connections: Arc<Semaphore>,
+}
+
+impl LiveUpdates {
+	pub fn new(max_connections: usize) -> Self {
+		let (tx, _) = broadcast::channel(DEFAULT_BUS_CAPACITY);
+		LiveUpdates {
+			tx,
+			accounts: Arc::new(Mutex::new(HashMap::new())),
+			connections: Arc::new(Semaphore::new(max_connections)),
+		}
+	}
+
+	/// Build the live updates from the config, None if they are disabled.
+	pub fn from_config(config: &LiveUpdatesConfig) -> Option<Self> {
+		config.enabled.then(|| {
+			LiveUpdates::new(usize::try_from(config.max_connections).unwrap_or(usize::MAX))
+		})
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<BridgeTransferId, (String, String)>> {
+		self.accounts.lock().expect("Live updates lock poisoned")
+	}
+
+	/// The updates of a new websocket, None if the maximum of websockets is reached.
+	pub fn subscribe(&self) -> Option<LiveStream> {
+		let connection = self.connections.clone().try_acquire_owned().ok()?;
+		Some(LiveStream { updates: self.tx.subscribe(), _connection: connection })
+	}
+
+	/// Stream the changes of state of the subscription until the bus is dropped.
+	pub async fn run(self, mut events: Subscription) {
+		while let Some(event) = events.recv().await {
+			if let Some(update) = self.observe(&event) {
+				// Broadcasting without websocket is not an error.
+				let _ = self.tx.send(update);
+			}
+		}
+	}
+
+	fn observe(&self, event: &BusEvent) -> Option<LiveUpdate> {
+		let mut accounts = self.lock();
+		match event {
+			BusEvent::Contract(event) => {
+				let (id, initiator, recipient) = match &event.contract_event {
+					BridgeContractEvent::Initiated(details) => {
+						(details.bridge_transfer_id, &details.initiator.0, &details.recipient.0)
+					}
+					BridgeContractEvent::Locked(details) => {
+						(details.bridge_transfer_id, &details.initiator.0, &details.recipient.0)
+					}
+					_ => return None,
+				};
+				accounts
+					.entry(id)
+					.or_insert_with(|| (hex_string(initiator), hex_string(recipient)));
+				None
+			}
+			BusEvent::StateChange(change) => {
+				let transfer_accounts = if change.new.is_final() {
+					accounts.remove(&change.transfer_id)
+				} else {
+					accounts.get(&change.transfer_id).cloned()
+				};
+				let (initiator, recipient) = transfer_accounts.unzip();
+				Some(LiveUpdate {
+					notification: TransferNotification::from(*change),
+					initiator,
+					recipient,
+				})
+			}
+			BusEvent::Action(..) => None,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::event_bus::{EventRef, StateChange};
+	use bridge_util::events::TransferEvent;
+	use bridge_util::states::TransferStateType;
+	use bridge_util::types::{
+		Amount, BridgeAddress, BridgeTransferDetails, ChainId, HashLock, TimeLock,
+	};
+
+	#[test]
+	fn test_live_updates() {
+		let live_updates = LiveUpdates::new(1);
+		let id = BridgeTransferId([1; 32]);
+		let change = |new| {
+			BusEvent::StateChange(StateChange {
+				transfer_id: id,
+				sequence: 1,
+				previous: None,
+				new,
+				cause: Some(EventRef { chain: ChainId::ONE, event: "Initiated", transfer_id: id }),
+				reason: None,
+			})
+		};
+		live_updates.observe(&BusEvent::Contract(TransferEvent {
+			chain: ChainId::ONE,
+			contract_event: BridgeContractEvent::Initiated(BridgeTransferDetails {
+				bridge_transfer_id: id,
+				initiator: BridgeAddress(vec![1; 20]),
+				recipient: BridgeAddress(vec![2; 32]),
+				hash_lock: HashLock([3; 32]),
+				time_lock: TimeLock(100),
+				amount: Amount(10),
+				state: 0,
+			}),
+		}));
+		let update = live_updates.observe(&change(TransferStateType::Initialized)).unwrap();
+		assert_eq!(update.initiator, Some(hex_string(&[1; 20])));
+		assert_eq!(update.notification.new_state, "Initialized");
+
+		let filter = |transfer_ids: Option<&str>, address: Option<&str>| {
+			LiveFilter::try_from(&LiveUpdatesQuery {
+				transfer_ids: transfer_ids.map(str::to_string),
+				address: address.map(str::to_string),
+			})
+		};
+		assert!(filter(None, None).unwrap().matches(&update));
+		let ids = format!("{},0x{}", hex::encode([1; 32]), hex::encode([4; 32]));
+		assert!(filter(Some(&ids), None).unwrap().matches(&update));
+		assert!(!filter(Some(&hex::encode([4; 32])), None).unwrap().matches(&update));
+		let recipient = hex::encode([2; 32]).to_uppercase();
+		assert!(filter(None, Some(&recipient)).unwrap().matches(&update));
+		assert!(!filter(Some(&ids), Some("0x0303")).unwrap().matches(&update));
+		assert!(filter(Some("0x01"), None).is_err());
+		assert!(filter(None, Some("not hex")).is_err());
+
+		// The accounts are dropped with the transfer.
+		let done = live_updates.observe(&change(TransferStateType::Refunded)).unwrap();
+		assert_eq!(done.recipient, Some(hex_string(&[2; 32])));
+		assert!(live_updates.lock().is_empty());
+
+		let stream = live_updates.subscribe();
+		assert!(stream.is_some());
+		assert!(live_updates.subscribe().is_none());
+		drop(stream);
+		assert!(live_updates.subscribe().is_some());
+	}
+}
@@ -1,32 +1,82 @@
 use anyhow::Result;
+use aptos_types::account_address::AccountAddress;
 use bridge_config::Config;
 use bridge_grpc::{
-	bridge_server::BridgeServer, health_check_response::ServingStatus, health_server::HealthServer,
+	bridge_operator_server::BridgeOperatorServer, bridge_server::BridgeServer,
+	health_check_response::ServingStatus, health_server::HealthServer,
 };
 use bridge_indexer_db::client::Client;
+use bridge_indexer_db::transfer_store::PgTransferStore;
 use bridge_service::{
+	admin_auth::AdminAuth,
+	allowances::{AllowanceCollector, AllowanceGcMetrics},
+	anomalies::{AnomalyAdvisories, AnomalyDetector},
+	approvals::ApprovalQueue,
+	canary::{Canary, CanaryMetrics, CanaryTracker},
+	catchup::CatchUpProgress,
 	chains::{
-		ethereum::{client::EthClient, event_monitoring::EthMonitoring},
+		ethereum::{
+			client::EthClient, event_monitoring::EthMonitoring, gas::GasStrategy,
+			redundancy::RedundantBroadcast,
+		},
 		movement::{
 			client_framework::MovementClientFramework, event_monitoring::MovementMonitoring,
 		},
 	},
-	grpc::HealthCheckService,
+	circuit_breaker::CircuitBreaker,
+	claims::ClaimService,
+	config_snapshot::config_snapshot,
+	drill::{DrillStore, FailoverDrills, FaultInjector},
+	event_bus::{index_events, EventBus, Topic},
+	event_metrics::EventMetrics,
+	explorer::TxExplorer,
+	fee_tracker::{FeeTracker, PriceFeed},
+	fees::{FeeDistributor, RelayerFeeSchedule},
+	forensics::{ForensicsClient, ForensicsMode},
+	grpc::{HealthCheckService, OperatorService},
+	guards::{EnabledDirections, TransferGuards},
+	in_flight::InFlightTransfers,
+	intake::IntakeLimit,
+	invariants::{InvariantChecker, InvariantMetrics},
+	labels::AddressLabels,
+	leader::LeaderElection,
+	liquidity::RelayerLiquidity,
+	live_updates::LiveUpdates,
+	metrics::BridgeMetrics,
+	pause::PauseSwitches,
+	pre_image::{HashLockScheme, PreImageFormat},
+	rate_limit::RateLimiter,
+	refund::RefundTxBuilder,
 	rest::BridgeRest,
+	retry::RetryTable,
+	revenue::{GasLedger, RevenueReports},
+	rpc_cache::RpcCache,
+	rpc_metrics::RpcMetrics,
+	runbook::{RunbookHooks, StuckTransferMonitor},
+	secrets::SecretManager,
+	signer::SignerBackend,
+	split::{SplitPolicy, SplitTransfers},
+	startup_report::StartupReport,
+	store,
+	strict_mode::StrictMode,
+	submissions::SentTransactions,
+	sweep::ColdSweeper,
+	telemetry::Telemetry,
+	timelock::{LockMargin, TimeLockPolicy, TimelockWatcher},
+	transfer_search::TransferSearch,
+	user_notifications::UserNotifications,
+	webhooks::TransferWebhooks,
 };
+use bridge_util::types::HashLockPreImage;
 use godfig::{backend::config_file::ConfigFile, Godfig};
 use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
 use tonic::transport::Server;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-	use tracing_subscriber::EnvFilter;
-
-	tracing_subscriber::fmt()
-		.with_env_filter(
-			EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
-		)
-		.init();
+	let mut telemetry = Telemetry::init();
 
 	tracing::info!("Start Bridge");
 
@@ -41,15 +91,339 @@ async fn main() -> Result<()> {
 	let godfig: Godfig<Config, ConfigFile> = Godfig::new(ConfigFile::new(config_file), vec![]);
 	let bridge_config: Config = godfig.try_wait_for_ready().await?;
 
-	tracing::info!("Bridge config loaded: {bridge_config:?}");
+	telemetry.start_otlp_export(&bridge_config.telemetry)?;
+
+	let config_snapshot = config_snapshot(&bridge_config)?;
+	tracing::info!("Bridge config loaded: {config_snapshot}");
+	let strict_mode = StrictMode::from_config(&bridge_config);
+	strict_mode.check_config(&bridge_config)?;
+
+	// Check all the endpoints before starting, and report all their failures at once.
+	let startup_report = StartupReport::check(
+		&bridge_config,
+		Duration::from_secs(bridge_config.startup.check_timeout_secs),
+	)
+	.await;
+	if startup_report.is_ok() {
+		tracing::info!("{startup_report}");
+	} else if bridge_config.startup.fail_on_error {
+		anyhow::bail!("{startup_report}");
+	} else {
+		strict_mode.warn(startup_report)?;
+	}
 
+	let retry_table = RetryTable::try_from(&bridge_config.retry)?;
+	let pre_image_format =
+		PreImageFormat::from_config(&bridge_config.pre_image, &bridge_config.eth.asset)?;
+	// A transfer has the same hash lock on both chains, the digests of the contracts must agree.
+	let hash_lock_scheme =
+		HashLockScheme::from_config(&bridge_config.pre_image, &bridge_config.eth.asset)?;
+	if let Err(err) = hash_lock_scheme.check_agreement(&HashLockPreImage([b'0'; 32])) {
+		if bridge_config.startup.fail_on_error {
+			anyhow::bail!("{err}");
+		}
+		strict_mode.warn(err)?;
+	}
+	// The faults of the drills fail the transfers while they last.
+	if bridge_config.drills.enabled {
+		strict_mode.warn("Failover drills are enabled, they are meant for the test deployments")?;
+	}
+
+	// In forensics mode only the ranges of the config are processed, nothing is submitted.
+	let forensics = ForensicsMode::from_config(&bridge_config.forensics)?;
+	if let Some(forensics) = &forensics {
+		tracing::warn!(
+			"Forensics mode: process the Ethereum blocks {}..={} and the Movement versions {}..={}, submissions disabled",
+			forensics.eth_blocks.from,
+			forensics.eth_blocks.to,
+			forensics.movement_versions.from,
+			forensics.movement_versions.to
+		);
+	}
+
+	// Only the leader of the coordinated instances runs, the others stand by until its lease
+	// expires. The background tasks submit transactions too, so nothing is started before.
+	let leader_election = match forensics {
+		None => LeaderElection::from_config(&bridge_config.coordination)?,
+		Some(_) => None,
+	};
+	if let Some(election) = &leader_election {
+		tracing::info!("Instance {} standing by for the leader lease", election.instance_id());
+		tokio::select! {
+			_ = election.acquire() => (),
+			_ = shutdown_signal() => return Ok(()),
+		}
+	}
+
+	let catch_up = CatchUpProgress::default();
 	let (eth_health_tx, eth_health_rx) = tokio::sync::mpsc::channel(10);
-	let one_stream = EthMonitoring::build(&bridge_config.eth, eth_health_rx).await.unwrap();
-	let one_client = EthClient::new(&bridge_config.eth).await.unwrap();
-	let two_client = MovementClientFramework::new(&bridge_config.movement).await.unwrap();
+	let mut one_stream = match &forensics {
+		Some(forensics) => {
+			EthMonitoring::build_forensics(
+				&bridge_config.eth,
+				eth_health_rx,
+				catch_up.clone(),
+				forensics.eth_blocks,
+			)
+			.await
+		}
+		None => {
+			EthMonitoring::build_with_catch_up(&bridge_config.eth, eth_health_rx, catch_up.clone())
+				.await
+		}
+	}
+	.unwrap();
+	let dropped_initiation_rx = one_stream.take_dropped_initiations().unwrap();
+	let rpc_metrics = RpcMetrics::default();
+	let bridge_metrics = BridgeMetrics::default().with_catch_up(catch_up.clone());
+	let fault_injector = FaultInjector::default();
+	let fee_tracker = FeeTracker::new(&bridge_config.fee_tracking, &bridge_config.refund);
+	let signer_backend = SignerBackend::try_from(&bridge_config.signer)?;
+	// The clients report the transactions they send to the outbox of the relayer loop.
+	let (sent_transactions, sent_transaction_rx) = SentTransactions::channel();
+	let mut one_client =
+		EthClient::with_signer_backend(&bridge_config.eth, &signer_backend).await?;
+	one_client.set_rpc_metrics(rpc_metrics.clone());
+	one_client.set_bridge_metrics(bridge_metrics.clone());
+	one_client.set_fault_injector(fault_injector.clone());
+	one_client.set_rpc_cache(RpcCache::from(&bridge_config.rpc_cache));
+	one_client.set_pre_image_format(pre_image_format);
+	one_client.set_calldata_tag(bridge_config.explorer.eth_calldata_tag.clone());
+	one_client.set_tx_explorer(TxExplorer::new(bridge_config.explorer.eth_tx_url.clone()));
+	one_client.set_gas_strategy(GasStrategy::from(&bridge_config.gas));
+	one_client.set_fee_tracker(fee_tracker.clone());
+	one_client.set_sent_transactions(sent_transactions.clone());
+	one_client.set_redundancy(
+		RedundantBroadcast::connect(&bridge_config.redundancy, &one_client.config).await?,
+	);
+	let mut two_client = MovementClientFramework::new(&bridge_config.movement).await.unwrap();
+	two_client.set_rpc_metrics(rpc_metrics.clone());
+	two_client.set_bridge_metrics(bridge_metrics.clone());
+	two_client.set_fault_injector(fault_injector.clone());
+	two_client.set_pre_image_format(pre_image_format);
+	two_client.set_tx_explorer(TxExplorer::new(bridge_config.explorer.movement_tx_url.clone()));
+	two_client.set_key_audit(one_client.key_audit().clone());
+	two_client.set_fee_tracker(fee_tracker.clone());
+	two_client.set_sent_transactions(sent_transactions);
+	one_client.warmup().await?;
+	two_client.warmup().await?;
+	// The resources of the Movement locks are read while the initiations wait for their
+	// confirmations. Nothing is submitted in forensics mode.
+	let mut pending_initiation_rx = one_stream.take_pending_initiations().unwrap();
+	if bridge_config.movement.mvt_prefetch_resources && forensics.is_none() {
+		let two_client = two_client.clone();
+		tokio::spawn(async move {
+			while let Some(recipient) = pending_initiation_rx.recv().await {
+				let recipient = AccountAddress::from_bytes(&recipient).ok();
+				two_client.prefetch_resources(recipient).await;
+			}
+		});
+	}
 	let (mvt_health_tx, mvt_health_rx) = tokio::sync::mpsc::channel(10);
-	let two_stream =
-		MovementMonitoring::build(&bridge_config.movement, mvt_health_rx).await.unwrap();
+	let two_stream = match &forensics {
+		Some(forensics) => {
+			MovementMonitoring::build_forensics(
+				&bridge_config.movement,
+				mvt_health_rx,
+				catch_up.clone(),
+				pre_image_format,
+				forensics.movement_versions,
+			)
+			.await
+		}
+		None => {
+			MovementMonitoring::build_with_catch_up(
+				&bridge_config.movement,
+				mvt_health_rx,
+				catch_up.clone(),
+				pre_image_format,
+			)
+			.await
+		}
+	}
+	.unwrap();
+
+	// Initialize the gRPC health check service
+	let (health_tx, health_rx) = tokio::sync::mpsc::channel(10);
+	// Start the gRPC server on a specific address (e.g., localhost:50051)
+	// Create and run the REST service
+	let (approval_queue, approval_decision_rx) =
+		ApprovalQueue::new(bridge_config.guards.auto_approval_limit);
+	// The transfers pending approval and their audit trail are kept in the indexer db.
+	let approval_queue = match PgTransferStore::from_env(1) {
+		Ok(approval_store) if forensics.is_none() => {
+			approval_queue.with_store(Arc::new(approval_store))?
+		}
+		Ok(_) => approval_queue,
+		Err(e) => {
+			tracing::warn!("Approval queue kept in memory only, no indexer db: {e:?}");
+			approval_queue
+		}
+	};
+	let enabled_directions = EnabledDirections::from(&bridge_config.guards);
+	let event_bus = EventBus::default();
+	let canary_tracker = CanaryTracker::default();
+	tokio::spawn(canary_tracker.clone().run(event_bus.subscribe(&[Topic::ContractEvents])));
+	let event_metrics = EventMetrics::default();
+	tokio::spawn(
+		event_metrics
+			.clone()
+			.run(event_bus.subscribe(&[Topic::ContractEvents, Topic::Actions])),
+	);
+	tokio::spawn(bridge_metrics.clone().run(event_bus.subscribe(&[Topic::ContractEvents])));
+	let runbook_hooks =
+		RunbookHooks::try_from(&bridge_config.runbook)?.with_config_snapshot(config_snapshot);
+	tokio::spawn(
+		StuckTransferMonitor::new(&bridge_config.runbook, runbook_hooks.clone())
+			.run(event_bus.subscribe(&[Topic::StateChanges])),
+	);
+	let transfer_webhooks = TransferWebhooks::from_config(&bridge_config.webhooks);
+	if let Some(transfer_webhooks) = &transfer_webhooks {
+		tokio::spawn(transfer_webhooks.clone().run(event_bus.subscribe(&[Topic::StateChanges])));
+	}
+	let live_updates = LiveUpdates::from_config(&bridge_config.live_updates);
+	if let Some(live_updates) = &live_updates {
+		tokio::spawn(
+			live_updates
+				.clone()
+				.run(event_bus.subscribe(&[Topic::ContractEvents, Topic::StateChanges])),
+		);
+	}
+	// The end users aren't notified again of the transfers replayed in forensics mode.
+	let user_notifications = UserNotifications::from_config(&bridge_config.notifications)
+		.filter(|_| forensics.is_none());
+	if let Some(user_notifications) = &user_notifications {
+		tokio::spawn(
+			user_notifications
+				.clone()
+				.run(event_bus.subscribe(&[Topic::ContractEvents, Topic::StateChanges])),
+		);
+	}
+	let address_labels = AddressLabels::from_config(&bridge_config).await?;
+	if let Some(address_labels) = &address_labels {
+		tokio::spawn(address_labels.clone().run(event_bus.subscribe(&[Topic::ContractEvents])));
+	}
+	let split_transfers = SplitTransfers::new(SplitPolicy::from(&bridge_config.split));
+	let pause_switches = PauseSwitches::new(bridge_config.eth.asset.clone());
+	// The pauses are kept in the indexer db, a restart doesn't resume the bridge.
+	let pause_switches = match PgTransferStore::from_env(1) {
+		Ok(pause_store) if forensics.is_none() => {
+			pause_switches.with_store(Arc::new(pause_store))?
+		}
+		Ok(_) => pause_switches,
+		Err(e) => {
+			tracing::warn!("Pauses kept in memory only, no indexer db: {e:?}");
+			pause_switches
+		}
+	};
+	let intake_limit = IntakeLimit::from(&bridge_config.relayer);
+	let rate_limiter = RateLimiter::from(&bridge_config.rate_limit);
+	let fee_schedule = RelayerFeeSchedule::from(&bridge_config.fees);
+	let circuit_breaker =
+		CircuitBreaker::new(&bridge_config.circuit_breaker, pause_switches.clone());
+	tokio::spawn(circuit_breaker.clone().run(event_bus.subscribe(&[Topic::ContractEvents])));
+	let anomaly_advisories = AnomalyAdvisories::default();
+	let anomaly_detector =
+		AnomalyDetector::from_config(&bridge_config.anomalies, anomaly_advisories.clone());
+	if !anomaly_detector.is_empty() {
+		tokio::spawn(anomaly_detector.run(event_bus.subscribe(&[Topic::ContractEvents])));
+	}
+	let canary_metrics = CanaryMetrics::default();
+	let secret_manager = SecretManager::from_config(&bridge_config.secrets, hash_lock_scheme.eth)?;
+	tokio::spawn(secret_manager.clone().run(event_bus.subscribe(&[Topic::ContractEvents])));
+	if bridge_config.canary.enabled && forensics.is_none() {
+		match Canary::build(
+			&bridge_config,
+			enabled_directions,
+			canary_tracker.clone(),
+			canary_metrics.clone(),
+			runbook_hooks.clone(),
+			secret_manager.clone(),
+		)
+		.await
+		{
+			Ok(canary) => {
+				tokio::spawn(canary.run());
+			}
+			Err(e) => tracing::warn!("Canary disabled: {e:?}"),
+		}
+	}
+	let time_lock_policy = TimeLockPolicy::from_config(&bridge_config.timelock)?;
+	let (lock_margin, lock_margin_clocks) =
+		LockMargin::new(&bridge_config.timelock, time_lock_policy);
+	if bridge_config.timelock.enabled && forensics.is_none() {
+		tokio::spawn(
+			TimelockWatcher::new(
+				&bridge_config.timelock,
+				time_lock_policy,
+				one_client.clone(),
+				two_client.clone(),
+			)
+			.with_lock_margin(lock_margin_clocks)
+			.run(event_bus.subscribe(&[Topic::ContractEvents])),
+		);
+	}
+	let in_flight = InFlightTransfers::default();
+	// The transfers in flight when the relayer stopped are followed again, the relayer loop
+	// resumes them. The replayed ranges of forensics mode aren't stored.
+	let transfer_store = match &forensics {
+		None => {
+			let mut transfer_store = store::from_config(&bridge_config.transfer_store)?;
+			if bridge_config.drills.enabled {
+				transfer_store = Arc::new(DrillStore::new(transfer_store, fault_injector.clone()));
+			}
+			let restored = transfer_store.in_flight()?;
+			tracing::info!(
+				"{} transfers in flight restored from the {} transfer store",
+				restored.len(),
+				bridge_config.transfer_store.backend
+			);
+			in_flight.restore(restored);
+			Some(transfer_store)
+		}
+		Some(_) => None,
+	};
+	tokio::spawn(
+		in_flight
+			.clone()
+			.run(event_bus.subscribe(&[Topic::ContractEvents, Topic::StateChanges])),
+	);
+	let invariant_metrics = InvariantMetrics::default();
+	if bridge_config.invariants.enabled && forensics.is_none() {
+		tokio::spawn(
+			InvariantChecker::new(
+				&bridge_config.invariants,
+				in_flight.clone(),
+				one_client.clone(),
+				two_client.clone(),
+				fee_schedule,
+				invariant_metrics.clone(),
+			)
+			.run(),
+		);
+	}
+	if let Some(price_feed) = PriceFeed::new(&bridge_config.fee_tracking, fee_tracker.clone()) {
+		tokio::spawn(price_feed.run());
+	}
+	let liquidity = RelayerLiquidity::default();
+	if forensics.is_none() {
+		tokio::spawn(liquidity.clone().run(
+			one_client.clone(),
+			two_client.clone(),
+			Duration::from_secs(bridge_config.guards.liquidity_refresh_secs.max(1)),
+		));
+	}
+	let allowance_gc_metrics = AllowanceGcMetrics::default();
+	if bridge_config.allowance_gc.enabled && forensics.is_none() {
+		tokio::spawn(
+			AllowanceCollector::new(
+				&bridge_config.allowance_gc,
+				one_client.clone(),
+				allowance_gc_metrics.clone(),
+			)?
+			.run(),
+		);
+	}
 
 	let one_client_for_grpc = one_client.clone();
 
@@ -57,6 +431,17 @@ async fn main() -> Result<()> {
 	let health_service = HealthCheckService::default();
 	health_service.set_service_status("", ServingStatus::Serving);
 	health_service.set_service_status("Bridge", ServingStatus::Serving);
+	health_service.set_service_status("BridgeOperator", ServingStatus::Serving);
+	let admin_auth = AdminAuth::from_config(&bridge_config.admin)?;
+	// The forced refunds aren't submitted in forensics mode.
+	let operator_service = OperatorService::new(
+		in_flight.clone(),
+		pause_switches.clone(),
+		live_updates.clone(),
+		ForensicsClient::new(one_client.clone(), forensics.is_some()),
+		ForensicsClient::new(two_client.clone(), forensics.is_some()),
+	)
+	.with_admin_auth(admin_auth.clone());
 
 	let grpc_addr: SocketAddr = format!(
 		"{}:{}",
@@ -69,15 +454,138 @@ async fn main() -> Result<()> {
 		Server::builder()
 			.add_service(HealthServer::new(health_service))
 			.add_service(BridgeServer::new(one_client_for_grpc))
+			.add_service(BridgeOperatorServer::new(operator_service))
 			.serve(grpc_addr)
 			.await
 	});
 
-	// Initialize the gRPC health check service
-	let (health_tx, health_rx) = tokio::sync::mpsc::channel(10);
-	// Start the gRPC server on a specific address (e.g., localhost:50051)
-	// Create and run the REST service
-	let rest_service = BridgeRest::new(&bridge_config.movement, health_tx)?;
+	let rest_service = BridgeRest::new(&bridge_config.movement, health_tx)?
+		.with_admin_auth(admin_auth)
+		.with_retry_table(retry_table.clone())
+		.with_guards(
+			TransferGuards::from(&bridge_config)
+				.with_pause_switches(pause_switches.clone())
+				.with_liquidity(liquidity)
+				.with_price(fee_tracker.clone()),
+		)
+		.with_rpc_metrics(rpc_metrics)
+		.with_approval_queue(approval_queue.clone())
+		.with_refund_tx_builder(
+			RefundTxBuilder::new(one_client.clone(), two_client.clone())
+				.with_cost_config(bridge_config.refund.clone()),
+		)
+		.with_canary_metrics(canary_metrics)
+		.with_event_metrics(event_metrics)
+		.with_split_transfers(split_transfers.clone())
+		.with_pause_switches(pause_switches.clone())
+		.with_circuit_breaker(circuit_breaker.clone())
+		.with_intake_limit(intake_limit.clone())
+		.with_rate_limiter(rate_limiter.clone())
+		.with_fee_schedule(fee_schedule)
+		.with_invariant_metrics(invariant_metrics)
+		.with_anomaly_advisories(anomaly_advisories)
+		.with_allowance_gc_metrics(allowance_gc_metrics)
+		.with_fee_tracker(fee_tracker)
+		.with_key_audit(one_client.key_audit().clone())
+		.with_runbook_hooks(runbook_hooks.clone())
+		.with_catch_up(catch_up)
+		.with_bridge_metrics(bridge_metrics.clone())
+		.with_in_flight(in_flight.clone())
+		.with_latencies(telemetry.latencies());
+	let rest_service = match transfer_webhooks {
+		Some(transfer_webhooks) => rest_service.with_transfer_webhooks(transfer_webhooks),
+		None => rest_service,
+	};
+	let rest_service = match live_updates {
+		Some(live_updates) => rest_service.with_live_updates(live_updates),
+		None => rest_service,
+	};
+	// The claims are completed with the relayer accounts, not in forensics mode.
+	let claims = ClaimService::from_config(
+		&bridge_config.claims,
+		in_flight,
+		hash_lock_scheme,
+		one_client.clone(),
+		two_client.clone(),
+	)
+	.filter(|_| forensics.is_none());
+	let rest_service = match claims {
+		Some(claims) => rest_service.with_claims(claims),
+		None => rest_service,
+	};
+	let rest_service = if bridge_config.drills.enabled && forensics.is_none() {
+		rest_service.with_failover_drills(FailoverDrills::new(
+			&bridge_config.drills,
+			fault_injector,
+			one_client.clone(),
+			two_client.clone(),
+			runbook_hooks,
+		))
+	} else {
+		rest_service
+	};
+	let rest_service = match user_notifications {
+		Some(user_notifications) => rest_service.with_user_notifications(user_notifications),
+		None => rest_service,
+	};
+	let rest_service = match Client::from_env() {
+		Ok(search_db_client) => {
+			let transfer_search =
+				TransferSearch::new(bridge_config.eth.asset.clone(), search_db_client);
+			rest_service.with_transfer_search(match address_labels {
+				Some(address_labels) => transfer_search.with_labels(address_labels),
+				None => transfer_search,
+			})
+		}
+		Err(e) => {
+			tracing::warn!("Transfer search disabled, no indexer db: {e:?}");
+			rest_service
+		}
+	};
+	let rest_service = match Client::from_env() {
+		Ok(reports_db_client) => rest_service.with_revenue_reports(RevenueReports::new(
+			&bridge_config.reports,
+			&bridge_config.refund,
+			bridge_config.eth.asset.clone(),
+			reports_db_client,
+		)),
+		Err(e) => {
+			tracing::warn!("Revenue reports disabled, no indexer db: {e:?}");
+			rest_service
+		}
+	};
+	// The sweeps are recorded in the ledger of the indexer db, they need one.
+	let cold_sweeper = match Client::from_env() {
+		Ok(ledger) if forensics.is_none() => {
+			ColdSweeper::new(&bridge_config.sweep, one_client.clone(), two_client.clone(), ledger)?
+		}
+		Ok(_) => None,
+		Err(e) => {
+			if !bridge_config.sweep.admin_address.is_empty() {
+				tracing::warn!("Cold storage sweeps disabled, no indexer db: {e:?}");
+			}
+			None
+		}
+	};
+	let rest_service = match cold_sweeper {
+		Some(cold_sweeper) => {
+			if bridge_config.sweep.enabled {
+				tokio::spawn(cold_sweeper.clone().run());
+			}
+			rest_service.with_cold_sweeper(cold_sweeper)
+		}
+		None => rest_service,
+	};
+	if bridge_config.metrics.enabled {
+		let metrics_url =
+			format!("{}:{}", bridge_config.metrics.listener_hostname, bridge_config.metrics.port);
+		let metrics_service = rest_service.run_metrics_service(metrics_url);
+		tokio::spawn(async move {
+			if let Err(e) = metrics_service.await {
+				tracing::error!("Metrics service stopped: {e:?}");
+			}
+		});
+	}
 	let rest_service_future = rest_service.run_service();
 	let rest_jh = tokio::spawn(rest_service_future);
 
@@ -85,6 +593,13 @@ async fn main() -> Result<()> {
 	let indexer_db_client = match Client::from_env() {
 		Ok(mut client) => {
 			client.run_migrations()?;
+			if !client.encrypts_sensitive_fields() {
+				strict_mode.warn(format!(
+					"Indexer db stores the preimages in clear, set {} to encrypt them",
+					bridge_indexer_db::encryption::ENCRYPTION_KEY_FILE_ENV
+				))?;
+			}
+			report_unconfirmed_submissions(&mut client);
 			Some(client)
 		}
 		Err(e) => {
@@ -92,17 +607,72 @@ async fn main() -> Result<()> {
 			None
 		}
 	};
+	if indexer_db_client.is_some() {
+		match Client::from_env() {
+			Ok(event_db_client) => {
+				tokio::spawn(index_events(
+					event_db_client,
+					event_bus.subscribe(&[Topic::ContractEvents]),
+				));
+			}
+			Err(e) => tracing::warn!("Failed to create event indexer db client: {e:?}"),
+		}
+	}
+
+	if indexer_db_client.is_some() && forensics.is_none() {
+		match Client::from_env() {
+			Ok(gas_db_client) => {
+				let gas_ledger = GasLedger::new(&bridge_config.reports, bridge_metrics);
+				tokio::spawn(gas_ledger.run(gas_db_client));
+			}
+			Err(e) => tracing::warn!("Gas costs not recorded, no indexer db: {e:?}"),
+		}
+	}
+
+	let fee_distributor = FeeDistributor::try_from(&bridge_config.fees)?;
+	if fee_distributor.is_enabled() && forensics.is_none() {
+		match Client::from_env() {
+			Ok(fee_db_client) => {
+				tokio::spawn(fee_distributor.run(one_client.clone(), fee_db_client));
+			}
+			Err(e) => tracing::warn!("Fee distribution disabled, no indexer db: {e:?}"),
+		}
+	}
 
+	// The leader stops once its lease can't be renewed, before another instance takes over.
+	let shutdown = async move {
+		match leader_election {
+			Some(election) => election.hold(shutdown_signal()).await,
+			None => shutdown_signal().await,
+		}
+	};
 	let loop_jh = tokio::spawn(async move {
 		bridge_service::run_bridge(
-			one_client,
+			ForensicsClient::new(one_client, forensics.is_some()),
 			one_stream,
-			two_client,
+			ForensicsClient::new(two_client, forensics.is_some()),
 			two_stream,
 			health_rx,
 			indexer_db_client,
 			eth_health_tx,
 			mvt_health_tx,
+			retry_table,
+			bridge_config.relayer.stateless_verification,
+			approval_queue,
+			approval_decision_rx,
+			dropped_initiation_rx,
+			sent_transaction_rx,
+			enabled_directions,
+			event_bus,
+			split_transfers,
+			pause_switches,
+			intake_limit,
+			rate_limiter,
+			fee_schedule,
+			circuit_breaker,
+			lock_margin,
+			transfer_store,
+			shutdown,
 		)
 		.await
 	});
@@ -111,13 +681,53 @@ async fn main() -> Result<()> {
 		res = rest_jh => {
 			tracing::error!("Heath check Rest server exit because :{res:?}");
 		}
-		res = loop_jh => {
-			tracing::error!("Main relayer loop exit because :{res:?}");
-		}
+		res = loop_jh => match res {
+			Ok(Ok(())) => tracing::info!("Relayer stopped"),
+			res => tracing::error!("Main relayer loop exit because :{res:?}"),
+		},
 		res = grpc_jh => {
 			tracing::error!("gRpc server exit because :{res:?}");
 		}
 	};
 
+	telemetry.shutdown();
 	Ok(())
 }
+
+// Resolve when the process is asked to stop, on SIGTERM or Ctrl-C.
+async fn shutdown_signal() {
+	use tokio::signal::unix::{signal, SignalKind};
+
+	match signal(SignalKind::terminate()) {
+		Ok(mut sigterm) => {
+			tokio::select! {
+				_ = sigterm.recv() => tracing::info!("Receive SIGTERM"),
+				_ = tokio::signal::ctrl_c() => tracing::info!("Receive Ctrl-C"),
+			}
+		}
+		Err(err) => {
+			tracing::warn!("Failed to listen to SIGTERM, only Ctrl-C stops the relayer: {err}");
+			let _ = tokio::signal::ctrl_c().await;
+		}
+	}
+}
+
+// Log the submissions left unconfirmed by the previous run. Planned ones have never been
+// sent and can be executed again, sent ones must be checked on chain first by their hash.
+fn report_unconfirmed_submissions(client: &mut Client) {
+	match client.get_unconfirmed_submissions() {
+		Ok(submissions) => {
+			for submission in submissions {
+				tracing::warn!(
+					"Submission {} of transfer {} on chain {} left {} by the previous run, tx {}",
+					submission.action_kind,
+					submission.bridge_transfer_id,
+					submission.chain,
+					submission.status,
+					submission.tx_hash.as_deref().unwrap_or("unknown")
+				);
+			}
+		}
+		Err(err) => tracing::warn!("Failed to read the submission outbox: {err}"),
+	}
+}
@@ -0,0 +1,166 @@
+//! Throughput and failures of the relayer: the transfers initiated, completed and refunded on
+//! each chain, the failed RPC calls and the gas spent by the relayer accounts, and the lag of
+//! the events of the chain monitorings.
+use crate::catchup::{CatchUpProgress, ETH_CHAIN, MOVEMENT_CHAIN};
+use crate::event_bus::{BusEvent, Subscription};
+use bridge_util::chains::bridge_contracts::BridgeContractEvent;
+use bridge_util::types::ChainId;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Default)]
+struct Counters {
+	// All keyed by chain label.
+	initiated: BTreeMap<&'static str, u64>,
+	completed: BTreeMap<&'static str, u64>,
+	refunded: BTreeMap<&'static str, u64>,
+	rpc_errors: BTreeMap<&'static str, u64>,
+	// In wei on Ethereum, in octas on Movement.
+	gas_spent: BTreeMap<&'static str, u128>,
+}
+
+/// Label of the chain in the metrics, the same as the catch-up progress.
+pub fn chain_label(chain: ChainId) -> &'static str {
+	match chain {
+		ChainId::ONE => ETH_CHAIN,
+		ChainId::TWO => MOVEMENT_CHAIN,
+	}
+}
+
+/// Counters of the transfers of the event bus, and of the RPC errors and gas recorded by the
+/// chain clients. Clones share the same counters.
+#[derive(Debug, Clone, Default)]
+pub struct BridgeMetrics {
+	counters: Arc<Mutex<Counters>>,
+	catch_up: CatchUpProgress,
+}
+
+impl BridgeMetrics {
+	fn lock(&self) -> std::sync::MutexGuard<'_, Counters> {
+		self.counters.lock().expect("Bridge metrics lock poisoned")
+	}
+
+	/// Set the catch-up progress the event lags are measured on.
+	pub fn with_catch_up(mut self, catch_up: CatchUpProgress) -> Self {
+		self.catch_up = catch_up;
+		self
+	}
+
+	/// Count the transfers of the subscription until the bus is dropped.
+	pub async fn run(self, mut events: Subscription) {
+		while let Some(event) = events.recv().await {
+			self.record(&event);
+		}
+	}
+
+	fn record(&self, event: &BusEvent) {
+		let BusEvent::Contract(event) = event else {
+			return;
+		};
+		let mut counters = self.lock();
+		let counter = match event.contract_event {
+			BridgeContractEvent::Initiated(_) => &mut counters.initiated,
+			BridgeContractEvent::InitiatorCompleted(_) => &mut counters.completed,
+			BridgeContractEvent::Refunded(_) => &mut counters.refunded,
+			_ => return,
+		};
+		*counter.entry(chain_label(event.chain)).or_default() += 1;
+	}
+
+	/// Count a failed RPC call of the client of the chain.
+	pub fn record_rpc_error(&self, chain: ChainId) {
+		*self.lock().rpc_errors.entry(chain_label(chain)).or_default() += 1;
+	}
+
+	/// Add the fee of a transaction of the relayer on the chain, in its native unit.
+	pub fn record_gas_spent(&self, chain: ChainId, fee: u128) {
+		*self.lock().gas_spent.entry(chain_label(chain)).or_default() += fee;
+	}
+
+	/// The fees spent by the relayer accounts since the start, by chain label.
+	pub fn gas_spent(&self) -> BTreeMap<&'static str, u128> {
+		self.lock().gas_spent.clone()
+	}
+
+	pub fn export_prometheus(&self) -> String {
+		let mut out = String::new();
+		let counters = self.lock();
+		let mut export = |name: &str, kind: &str, values: Vec<(&str, u128)>| {
+			let _ = writeln!(out, "# TYPE {name} {kind}");
+			for (chain, value) in values {
+				let _ = writeln!(out, "{name}{{chain=\"{chain}\"}} {value}");
+			}
+		};
+		let values = |counter: &BTreeMap<&'static str, u64>| -> Vec<(&str, u128)> {
+			counter.iter().map(|(chain, count)| (*chain, u128::from(*count))).collect()
+		};
+		export("bridge_transfers_initiated_total", "counter", values(&counters.initiated));
+		export("bridge_transfers_completed_total", "counter", values(&counters.completed));
+		export("bridge_transfers_refunded_total", "counter", values(&counters.refunded));
+		export("bridge_rpc_errors_total", "counter", values(&counters.rpc_errors));
+		export(
+			"bridge_gas_spent_total",
+			"counter",
+			counters.gas_spent.iter().map(|(chain, fee)| (*chain, *fee)).collect(),
+		);
+		export(
+			"bridge_event_lag_seconds",
+			"gauge",
+			self.catch_up
+				.scan_lag_secs()
+				.into_iter()
+				.map(|(chain, lag)| (chain, u128::from(lag)))
+				.collect(),
+		);
+		out
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bridge_util::events::TransferEvent;
+	use bridge_util::types::{
+		Amount, BridgeAddress, BridgeTransferDetails, BridgeTransferId, HashLock, TimeLock,
+	};
+
+	#[test]
+	fn test_bridge_metrics() {
+		let catch_up = CatchUpProgress::default();
+		let metrics = BridgeMetrics::default().with_catch_up(catch_up.clone());
+		let id = BridgeTransferId([1; 32]);
+		let event = |chain, contract_event| {
+			BusEvent::Contract(TransferEvent::<Vec<u8>> { chain, contract_event })
+		};
+		metrics.record(&event(
+			ChainId::ONE,
+			BridgeContractEvent::Initiated(BridgeTransferDetails {
+				bridge_transfer_id: id,
+				initiator: BridgeAddress(vec![1; 20]),
+				recipient: BridgeAddress(vec![2; 32]),
+				hash_lock: HashLock([3; 32]),
+				time_lock: TimeLock(100),
+				amount: Amount(10),
+				state: 0,
+			}),
+		));
+		metrics.record(&event(ChainId::ONE, BridgeContractEvent::InitiatorCompleted(id)));
+		metrics.record(&event(ChainId::TWO, BridgeContractEvent::Refunded(id)));
+		metrics.record(&event(ChainId::TWO, BridgeContractEvent::Refunded(id)));
+		metrics.record(&event(ChainId::TWO, BridgeContractEvent::Cancelled(id)));
+		metrics.record_rpc_error(ChainId::TWO);
+		metrics.clone().record_gas_spent(ChainId::ONE, 21_000 * 10);
+		metrics.record_gas_spent(ChainId::ONE, 5);
+		catch_up.record_scan(ETH_CHAIN, 100, Some(100), 0);
+
+		let export = metrics.export_prometheus();
+		assert!(export.contains("bridge_transfers_initiated_total{chain=\"ethereum\"} 1\n"));
+		assert!(export.contains("bridge_transfers_completed_total{chain=\"ethereum\"} 1\n"));
+		assert!(export.contains("bridge_transfers_refunded_total{chain=\"movement\"} 2\n"));
+		assert!(export.contains("bridge_rpc_errors_total{chain=\"movement\"} 1\n"));
+		assert!(export.contains("bridge_gas_spent_total{chain=\"ethereum\"} 210005\n"));
+		assert!(export.contains("bridge_event_lag_seconds{chain=\"ethereum\"} 0\n"));
+		assert!(!export.contains("bridge_event_lag_seconds{chain=\"movement\"}"));
+	}
+}
@@ -0,0 +1,280 @@
+use bridge_indexer_db::models::ActivePauseRow;
+use bridge_indexer_db::transfer_store::PgTransferStore;
+use bridge_util::types::TransferDirection;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// What a pause applies to. A missing token or direction matches all of them,
+/// so the scope without token nor direction pauses the whole bridge.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct PauseScope {
+	#[serde(default)]
+	pub token: Option<String>,
+	#[serde(default)]
+	pub direction: Option<TransferDirection>,
+}
+
+impl PauseScope {
+	fn matches(&self, token: &str, direction: TransferDirection) -> bool {
+		self.token.as_deref().map_or(true, |t| t == token)
+			&& self.direction.map_or(true, |d| d == direction)
+	}
+}
+
+impl std::fmt::Display for PauseScope {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let token = self.token.as_deref().unwrap_or("all tokens");
+		match self.direction {
+			Some(direction) => write!(f, "{token} {direction}"),
+			None => write!(f, "{token} all directions"),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ActivePause {
+	pub scope: PauseScope,
+	pub operator: String,
+	pub paused_at_secs: u64,
+}
+
+/// Pause or resume request posted by an operator on the admin API.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PauseRequest {
+	#[serde(flatten)]
+	pub scope: PauseScope,
+	pub paused: bool,
+	/// The authenticated operator, never read from the payload.
+	#[serde(skip_deserializing)]
+	pub operator: String,
+}
+
+/// Store of the active pauses, so a restart doesn't resume the bridge.
+pub trait PauseStore: Send + Sync {
+	fn save(&self, pause: &ActivePause) -> Result<(), anyhow::Error>;
+
+	fn remove(&self, scope: &PauseScope) -> Result<(), anyhow::Error>;
+
+	fn active(&self) -> Result<Vec<ActivePause>, anyhow::Error>;
+}
+
+// The token and the direction are stored empty when the scope applies to all of them.
+fn scope_columns(scope: &PauseScope) -> (String, String) {
+	(
+		scope.token.clone().unwrap_or_default(),
+		scope.direction.map(|direction| direction.to_string()).unwrap_or_default(),
+	)
+}
+
+fn parse_direction(direction: &str) -> Result<Option<TransferDirection>, anyhow::Error> {
+	match direction {
+		"" => Ok(None),
+		"eth_to_movement" => Ok(Some(TransferDirection::EthToMovement)),
+		"movement_to_eth" => Ok(Some(TransferDirection::MovementToEth)),
+		direction => anyhow::bail!("Unknown pause direction {direction}"),
+	}
+}
+
+impl PauseStore for PgTransferStore {
+	fn save(&self, pause: &ActivePause) -> Result<(), anyhow::Error> {
+		let (token, direction) = scope_columns(&pause.scope);
+		self.save_pause(&ActivePauseRow {
+			token,
+			direction,
+			operator: pause.operator.clone(),
+			paused_at: i64::try_from(pause.paused_at_secs)?,
+		})
+	}
+
+	fn remove(&self, scope: &PauseScope) -> Result<(), anyhow::Error> {
+		let (token, direction) = scope_columns(scope);
+		self.remove_pause(&token, &direction)
+	}
+
+	fn active(&self) -> Result<Vec<ActivePause>, anyhow::Error> {
+		self.active_pauses()?
+			.into_iter()
+			.map(|row| {
+				Ok(ActivePause {
+					scope: PauseScope {
+						token: Some(row.token).filter(|token| !token.is_empty()),
+						direction: parse_direction(&row.direction)?,
+					},
+					operator: row.operator,
+					paused_at_secs: u64::try_from(row.paused_at)?,
+				})
+			})
+			.collect()
+	}
+}
+
+/// Pause switches of the bridge, per token and per direction.
+/// Paused transfers fail the precheck and are not locked by the relayer until resumed.
+/// Clones share the same switches.
+#[derive(Clone)]
+pub struct PauseSwitches {
+	// The token relayed by this bridge instance.
+	token: String,
+	pauses: Arc<Mutex<BTreeMap<PauseScope, ActivePause>>>,
+	store: Option<Arc<dyn PauseStore>>,
+}
+
+impl std::fmt::Debug for PauseSwitches {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("PauseSwitches")
+			.field("token", &self.token)
+			.field("pauses", &self.pauses)
+			.finish_non_exhaustive()
+	}
+}
+
+impl PauseSwitches {
+	pub fn new(token: String) -> Self {
+		PauseSwitches { token, pauses: Arc::default(), store: None }
+	}
+
+	/// Keep the pauses in the store, and restore the active ones from it.
+	pub fn with_store(mut self, store: Arc<dyn PauseStore>) -> Result<Self, anyhow::Error> {
+		{
+			let mut pauses = self.lock();
+			for pause in store.active()? {
+				tracing::info!("Restored pause of {} by {}", pause.scope, pause.operator);
+				pauses.insert(pause.scope.clone(), pause);
+			}
+		}
+		self.store = Some(store);
+		Ok(self)
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<PauseScope, ActivePause>> {
+		self.pauses.lock().expect("Pause switches lock poisoned")
+	}
+
+	pub fn apply(&self, request: PauseRequest) {
+		tracing::info!(
+			target: "bridge_audit",
+			"{} {} by {}",
+			if request.paused { "Pause" } else { "Resume" },
+			request.scope,
+			request.operator
+		);
+		// The store is written first, so a pause in memory is never lost on restart.
+		if request.paused {
+			let pause = ActivePause {
+				scope: request.scope.clone(),
+				operator: request.operator,
+				paused_at_secs: SystemTime::now()
+					.duration_since(UNIX_EPOCH)
+					.map(|d| d.as_secs())
+					.unwrap_or(0),
+			};
+			self.persist(|store| store.save(&pause));
+			self.lock().insert(request.scope, pause);
+		} else {
+			self.persist(|store| store.remove(&request.scope));
+			self.lock().remove(&request.scope);
+		}
+	}
+
+	fn persist(&self, write: impl FnOnce(&dyn PauseStore) -> Result<(), anyhow::Error>) {
+		if let Some(store) = &self.store {
+			if let Err(err) = write(store.as_ref()) {
+				tracing::error!(target: "bridge_alert", "Failed to store the pauses: {err}");
+			}
+		}
+	}
+
+	/// Return the pause applying to the token and direction, if any.
+	pub fn paused_by(&self, token: &str, direction: TransferDirection) -> Option<PauseScope> {
+		self.lock().keys().find(|scope| scope.matches(token, direction)).cloned()
+	}
+
+	/// True if the transfers of the bridged token in the direction are paused.
+	pub fn is_paused(&self, direction: TransferDirection) -> bool {
+		self.paused_by(&self.token, direction).is_some()
+	}
+
+	pub fn active(&self) -> Vec<ActivePause> {
+		self.lock().values().cloned().collect()
+	}
+}
+
+impl Default for PauseSwitches {
+	fn default() -> Self {
+		PauseSwitches::new(String::new())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn request(
+		token: Option<&str>,
+		direction: Option<TransferDirection>,
+		paused: bool,
+	) -> PauseRequest {
+		PauseRequest {
+			scope: PauseScope { token: token.map(str::to_string), direction },
+			paused,
+			operator: "ops".to_string(),
+		}
+	}
+
+	#[test]
+	fn test_pause_scopes() {
+		let switches = PauseSwitches::new("WETH".to_string());
+		switches.apply(request(Some("WETH"), Some(TransferDirection::EthToMovement), true));
+		assert!(switches.is_paused(TransferDirection::EthToMovement));
+		assert!(!switches.is_paused(TransferDirection::MovementToEth));
+		assert!(switches.paused_by("MOVE", TransferDirection::EthToMovement).is_none());
+
+		// The global pause applies to all tokens and directions.
+		switches.apply(request(None, None, true));
+		assert!(switches.paused_by("MOVE", TransferDirection::MovementToEth).is_some());
+		switches.apply(request(None, None, false));
+
+		switches.apply(request(Some("WETH"), Some(TransferDirection::EthToMovement), false));
+		assert!(!switches.is_paused(TransferDirection::EthToMovement));
+		assert!(switches.active().is_empty());
+	}
+
+	#[derive(Default)]
+	struct MemoryPauseStore {
+		pauses: Mutex<BTreeMap<PauseScope, ActivePause>>,
+	}
+
+	impl PauseStore for MemoryPauseStore {
+		fn save(&self, pause: &ActivePause) -> Result<(), anyhow::Error> {
+			self.pauses.lock().unwrap().insert(pause.scope.clone(), pause.clone());
+			Ok(())
+		}
+
+		fn remove(&self, scope: &PauseScope) -> Result<(), anyhow::Error> {
+			self.pauses.lock().unwrap().remove(scope);
+			Ok(())
+		}
+
+		fn active(&self) -> Result<Vec<ActivePause>, anyhow::Error> {
+			Ok(self.pauses.lock().unwrap().values().cloned().collect())
+		}
+	}
+
+	#[test]
+	fn test_pauses_restored_from_store() {
+		let store = Arc::new(MemoryPauseStore::default());
+		let switches = PauseSwitches::new("WETH".to_string()).with_store(store.clone()).unwrap();
+		switches.apply(request(None, Some(TransferDirection::MovementToEth), true));
+		switches.apply(request(Some("WETH"), None, true));
+		switches.apply(request(Some("WETH"), None, false));
+
+		// A restarted relayer is still paused.
+		let restarted = PauseSwitches::new("WETH".to_string()).with_store(store).unwrap();
+		assert!(restarted.is_paused(TransferDirection::MovementToEth));
+		assert!(!restarted.is_paused(TransferDirection::EthToMovement));
+		assert_eq!(restarted.active().len(), 1);
+		assert_eq!(restarted.active()[0].operator, "ops");
+	}
+}
@@ -0,0 +1,281 @@
+use bridge_config::common::pre_image::PreImageConfig;
+use bridge_util::types::{ChainId, HashLock, HashLockAlgorithm, HashLockPreImage};
+use std::str::FromStr;
+
+/// Format of the preimages of the hash locks of a token pair.
+/// The Ethereum contracts take the preimages as `bytes32`, the Move modules as a
+/// `vector<u8>`: the format defines the Move bytes of the 32 bytes Ethereum preimage,
+/// both must hash to the hash lock.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PreImageFormat {
+	/// 32 raw bytes, the same on both chains.
+	Raw,
+	/// UTF-8 string of at most 32 bytes, zero padded on Ethereum and unpadded on Movement.
+	#[default]
+	Utf8,
+	/// UTF-8 string of at most 32 bytes, zero padded on Ethereum and BCS encoded on Movement.
+	BcsString,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PreImageError {
+	#[error("Raw preimage of {0} bytes, expected 32")]
+	NotRaw(usize),
+	#[error("Preimage is not a UTF-8 string")]
+	NotUtf8,
+	#[error("Preimage is not a BCS encoded string")]
+	NotBcsString,
+	#[error("Preimage string of {0} bytes, at most 32 are supported")]
+	TooLong(usize),
+	#[error("Preimage doesn't unlock the hash lock with the {0} digest of {1:?}")]
+	HashLockMismatch(HashLockAlgorithm, ChainId),
+	#[error("Hash locks differ between Ethereum ({eth}) and Movement ({movement})")]
+	HashLockDisagreement { eth: HashLockAlgorithm, movement: HashLockAlgorithm },
+}
+
+impl FromStr for PreImageFormat {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"raw" => Ok(PreImageFormat::Raw),
+			"utf8" => Ok(PreImageFormat::Utf8),
+			"bcs_string" => Ok(PreImageFormat::BcsString),
+			_ => Err(format!("Unknown preimage format: {s}, expected raw, utf8 or bcs_string")),
+		}
+	}
+}
+
+impl PreImageFormat {
+	/// Format of the token pair of the asset.
+	pub fn from_config(config: &PreImageConfig, asset: &str) -> Result<Self, anyhow::Error> {
+		PreImageFormat::from_str(config.format_of(asset)).map_err(|err| anyhow::anyhow!(err))
+	}
+
+	/// Check the preimage has the format, before a transfer is completed with it.
+	pub fn validate(&self, pre_image: &HashLockPreImage) -> Result<(), PreImageError> {
+		self.to_move_bytes(pre_image).map(|_| ())
+	}
+
+	/// Bytes of the preimage taken and emitted by the Move modules.
+	pub fn to_move_bytes(&self, pre_image: &HashLockPreImage) -> Result<Vec<u8>, PreImageError> {
+		match self {
+			PreImageFormat::Raw => Ok(pre_image.0.to_vec()),
+			PreImageFormat::Utf8 => Ok(unpadded_string(pre_image)?.as_bytes().to_vec()),
+			PreImageFormat::BcsString => {
+				bcs::to_bytes(unpadded_string(pre_image)?).map_err(|_| PreImageError::NotBcsString)
+			}
+		}
+	}
+
+	/// Preimage of the bytes emitted by the Move modules.
+	pub fn from_move_bytes(&self, bytes: &[u8]) -> Result<HashLockPreImage, PreImageError> {
+		match self {
+			PreImageFormat::Raw => bytes
+				.try_into()
+				.map(HashLockPreImage)
+				.map_err(|_| PreImageError::NotRaw(bytes.len())),
+			PreImageFormat::Utf8 => {
+				let string = std::str::from_utf8(bytes).map_err(|_| PreImageError::NotUtf8)?;
+				padded(string)
+			}
+			PreImageFormat::BcsString => {
+				let string: String =
+					bcs::from_bytes(bytes).map_err(|_| PreImageError::NotBcsString)?;
+				padded(&string)
+			}
+		}
+	}
+}
+
+/// Digests of the hash locks of the contracts of each chain. A transfer has the same hash lock
+/// on both chains, so both must agree on the digest of its preimage.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HashLockScheme {
+	pub format: PreImageFormat,
+	pub eth: HashLockAlgorithm,
+	pub movement: HashLockAlgorithm,
+}
+
+impl HashLockScheme {
+	/// Scheme of the token pair of the asset.
+	pub fn from_config(config: &PreImageConfig, asset: &str) -> Result<Self, anyhow::Error> {
+		let algorithm = |algorithm: &str| {
+			HashLockAlgorithm::from_str(algorithm).map_err(|err| anyhow::anyhow!(err))
+		};
+		Ok(HashLockScheme {
+			format: PreImageFormat::from_config(config, asset)?,
+			eth: algorithm(&config.eth_hash_lock_algorithm)?,
+			movement: algorithm(&config.movement_hash_lock_algorithm)?,
+		})
+	}
+
+	pub fn algorithm(&self, chain: ChainId) -> HashLockAlgorithm {
+		match chain {
+			ChainId::ONE => self.eth,
+			ChainId::TWO => self.movement,
+		}
+	}
+
+	/// Hash lock of the preimage as computed by the contracts of the chain: the 32 bytes on
+	/// Ethereum, the bytes of the format on Movement.
+	pub fn hash_lock(
+		&self,
+		chain: ChainId,
+		pre_image: &HashLockPreImage,
+	) -> Result<HashLock, PreImageError> {
+		let algorithm = self.algorithm(chain);
+		match chain {
+			ChainId::ONE => Ok(HashLock::of_pre_image(&pre_image.0, algorithm)),
+			ChainId::TWO => {
+				Ok(HashLock::of_pre_image(&self.format.to_move_bytes(pre_image)?, algorithm))
+			}
+		}
+	}
+
+	/// Check the preimage unlocks the hash lock on the chain, before a transfer is completed
+	/// with it.
+	pub fn verify(
+		&self,
+		chain: ChainId,
+		hash_lock: &HashLock,
+		pre_image: &HashLockPreImage,
+	) -> Result<(), PreImageError> {
+		if self.hash_lock(chain, pre_image)? != *hash_lock {
+			return Err(PreImageError::HashLockMismatch(self.algorithm(chain), chain));
+		}
+		Ok(())
+	}
+
+	/// Check the preimage gives the same hash lock on both chains, and return it.
+	pub fn check_agreement(&self, pre_image: &HashLockPreImage) -> Result<HashLock, PreImageError> {
+		let hash_lock = self.hash_lock(ChainId::ONE, pre_image)?;
+		if self.hash_lock(ChainId::TWO, pre_image)? != hash_lock {
+			return Err(PreImageError::HashLockDisagreement {
+				eth: self.eth,
+				movement: self.movement,
+			});
+		}
+		Ok(hash_lock)
+	}
+}
+
+// The string of a preimage without its zero padding.
+fn unpadded_string(pre_image: &HashLockPreImage) -> Result<&str, PreImageError> {
+	let end = pre_image.0.iter().rposition(|byte| *byte != 0).map_or(0, |last| last + 1);
+	std::str::from_utf8(&pre_image.0[..end]).map_err(|_| PreImageError::NotUtf8)
+}
+
+// The preimage of a string, zero padded to 32 bytes.
+fn padded(string: &str) -> Result<HashLockPreImage, PreImageError> {
+	let bytes = string.as_bytes();
+	if bytes.len() > 32 {
+		return Err(PreImageError::TooLong(bytes.len()));
+	}
+	let mut pre_image = [0; 32];
+	pre_image[..bytes.len()].copy_from_slice(bytes);
+	Ok(HashLockPreImage(pre_image))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bridge_config::common::pre_image::PreImageFormatConfig;
+	use bridge_util::types::Preimage;
+
+	#[test]
+	fn test_pre_image_formats() {
+		let secret = padded("secret").unwrap();
+		assert_eq!(PreImageFormat::Utf8.to_move_bytes(&secret).unwrap(), b"secret");
+		assert_eq!(PreImageFormat::BcsString.to_move_bytes(&secret).unwrap(), b"\x06secret");
+		assert_eq!(PreImageFormat::Raw.to_move_bytes(&secret).unwrap(), secret.0);
+		for format in [PreImageFormat::Raw, PreImageFormat::Utf8, PreImageFormat::BcsString] {
+			let bytes = format.to_move_bytes(&secret).unwrap();
+			assert_eq!(format.from_move_bytes(&bytes).unwrap(), secret);
+		}
+
+		// Random bytes are only a raw preimage.
+		let random = HashLockPreImage([0xff; 32]);
+		assert!(PreImageFormat::Raw.validate(&random).is_ok());
+		assert_eq!(PreImageFormat::Utf8.validate(&random), Err(PreImageError::NotUtf8));
+		assert_eq!(PreImageFormat::BcsString.validate(&random), Err(PreImageError::NotUtf8));
+		assert_eq!(PreImageFormat::Raw.from_move_bytes(b"secret"), Err(PreImageError::NotRaw(6)));
+		assert_eq!(
+			PreImageFormat::Utf8.from_move_bytes(&[b'a'; 33]),
+			Err(PreImageError::TooLong(33))
+		);
+	}
+
+	#[test]
+	fn test_pre_image_format_config() {
+		let config = PreImageConfig {
+			format: "utf8".to_string(),
+			overrides: vec![PreImageFormatConfig {
+				asset: "WETH".to_string(),
+				format: "raw".to_string(),
+			}],
+			eth_hash_lock_algorithm: "keccak256".to_string(),
+			movement_hash_lock_algorithm: "sha2_256".to_string(),
+		};
+		assert_eq!(PreImageFormat::from_config(&config, "MOVE").unwrap(), PreImageFormat::Utf8);
+		assert_eq!(PreImageFormat::from_config(&config, "WETH").unwrap(), PreImageFormat::Raw);
+		let scheme = HashLockScheme::from_config(&config, "WETH").unwrap();
+		assert_eq!(scheme.algorithm(ChainId::TWO), HashLockAlgorithm::Sha2_256);
+		let config = PreImageConfig { format: "base64".to_string(), ..config };
+		assert!(PreImageFormat::from_config(&config, "MOVE").is_err());
+	}
+
+	#[test]
+	fn test_preimage_encodings() {
+		let pre_image = HashLockPreImage([7; 32]);
+		let preimage = Preimage::from(pre_image);
+		let movement_bytes = preimage.to_movement_bytes();
+		// The Move modules hash the length prefixed bytes.
+		assert_eq!(movement_bytes[0], 32);
+		assert_eq!(movement_bytes[1..], pre_image.0);
+		assert_eq!(Preimage::from_movement_bytes(&movement_bytes).unwrap(), preimage);
+		assert_eq!(HashLockPreImage::try_from(preimage.clone()).unwrap(), pre_image);
+		assert!(HashLockPreImage::try_from(Preimage(b"secret".to_vec())).is_err());
+		assert!(Preimage::from_movement_bytes(&movement_bytes[1..]).is_err());
+
+		assert_eq!(
+			preimage.hash_for_eth(),
+			HashLock::of_pre_image(&pre_image.0, HashLockAlgorithm::Keccak256)
+		);
+		assert_eq!(
+			preimage.hash_for_movement(),
+			HashLock::of_pre_image(&movement_bytes, HashLockAlgorithm::Keccak256)
+		);
+		assert_ne!(preimage.hash_for_eth(), preimage.hash_for_movement());
+	}
+
+	#[test]
+	fn test_hash_lock_scheme() {
+		let secret = padded("secret").unwrap();
+		let scheme = HashLockScheme {
+			format: PreImageFormat::Raw,
+			eth: HashLockAlgorithm::Sha2_256,
+			movement: HashLockAlgorithm::Sha2_256,
+		};
+		let hash_lock = scheme.check_agreement(&secret).unwrap();
+		assert_eq!(hash_lock, HashLock::of_pre_image(&secret.0, HashLockAlgorithm::Sha2_256));
+		assert_ne!(hash_lock, HashLock::of_pre_image(&secret.0, HashLockAlgorithm::Keccak256));
+		assert!(scheme.verify(ChainId::TWO, &hash_lock, &secret).is_ok());
+		assert_eq!(
+			scheme.verify(ChainId::ONE, &hash_lock, &HashLockPreImage([1; 32])),
+			Err(PreImageError::HashLockMismatch(HashLockAlgorithm::Sha2_256, ChainId::ONE))
+		);
+
+		// The Movement digest of the string differs from the Ethereum one of the padded bytes.
+		let scheme = HashLockScheme { format: PreImageFormat::Utf8, ..scheme };
+		assert!(scheme.check_agreement(&secret).is_err());
+		let scheme = HashLockScheme { movement: HashLockAlgorithm::Keccak256, ..scheme };
+		assert_eq!(
+			scheme.check_agreement(&HashLockPreImage([b'a'; 32])),
+			Err(PreImageError::HashLockDisagreement {
+				eth: HashLockAlgorithm::Sha2_256,
+				movement: HashLockAlgorithm::Keccak256
+			})
+		);
+	}
+}
@@ -0,0 +1,218 @@
+//! Caps on the amounts initiated in a sliding window, by initiator address and overall, so an
+//! exploit can't drain the liquidity of the relayer in one go. The locks of the transfers over
+//! a cap are deferred until the window has room for them, or refunded.
+use bridge_config::common::rate_limit::RateLimitConfig;
+use bridge_util::{TransferAction, TransferActionType};
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// What to do with the lock of a transfer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitDecision {
+	Admit,
+	/// Lock the transfer once the window has room for it.
+	Defer,
+	/// Refund the transfer.
+	Reject,
+}
+
+#[derive(Debug, Default)]
+struct RateLimitUsage {
+	// Amounts admitted in the window with their time and initiator, oldest first.
+	admitted: VecDeque<(u64, Vec<u8>, u64)>,
+	deferred_total: u64,
+	rejected_total: u64,
+}
+
+/// Sliding window of the amounts locked by the relayer. Clones share the same window.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+	window_secs: u64,
+	max_amount_per_initiator: u64,
+	max_amount_global: u64,
+	defer_over_cap: bool,
+	usage: Arc<Mutex<RateLimitUsage>>,
+}
+
+impl From<&RateLimitConfig> for RateLimiter {
+	fn from(config: &RateLimitConfig) -> Self {
+		RateLimiter {
+			window_secs: config.window_secs,
+			max_amount_per_initiator: config.max_amount_per_initiator,
+			max_amount_global: config.max_amount_global,
+			defer_over_cap: config.defer_over_cap,
+			usage: Arc::new(Mutex::new(RateLimitUsage::default())),
+		}
+	}
+}
+
+impl Default for RateLimiter {
+	fn default() -> Self {
+		RateLimiter::from(&RateLimitConfig::default())
+	}
+}
+
+impl RateLimiter {
+	fn lock(&self) -> std::sync::MutexGuard<'_, RateLimitUsage> {
+		self.usage.lock().expect("Rate limiter lock poisoned")
+	}
+
+	/// Decide what to do with the lock action of a transfer just initiated. An admitted lock
+	/// is counted in the window, the other actions are always admitted.
+	pub fn check(&self, action: &TransferAction) -> RateLimitDecision {
+		self.check_at(action, now_secs())
+	}
+
+	fn check_at(&self, action: &TransferAction, now_secs: u64) -> RateLimitDecision {
+		let Some((initiator, amount)) = locked_amount(action) else {
+			return RateLimitDecision::Admit;
+		};
+		if self.admit(initiator, amount, now_secs) {
+			return RateLimitDecision::Admit;
+		}
+		let mut usage = self.lock();
+		// A transfer above a cap would never fit in the window.
+		if self.defer_over_cap
+			&& amount <= self.max_amount_per_initiator.min(self.max_amount_global)
+		{
+			usage.deferred_total += 1;
+			RateLimitDecision::Defer
+		} else {
+			usage.rejected_total += 1;
+			RateLimitDecision::Reject
+		}
+	}
+
+	/// Whether the deferred lock action fits in the window now, it's then counted in it.
+	pub fn release(&self, action: &TransferAction) -> bool {
+		self.release_at(action, now_secs())
+	}
+
+	fn release_at(&self, action: &TransferAction, now_secs: u64) -> bool {
+		match locked_amount(action) {
+			Some((initiator, amount)) => self.admit(initiator, amount, now_secs),
+			None => true,
+		}
+	}
+
+	/// Count the lock action in the window without checking the caps, for the transfers
+	/// approved by an operator.
+	pub fn record(&self, action: &TransferAction) {
+		if let Some((initiator, amount)) = locked_amount(action) {
+			self.lock().admitted.push_back((now_secs(), initiator.to_vec(), amount));
+		}
+	}
+
+	fn admit(&self, initiator: &[u8], amount: u64, now_secs: u64) -> bool {
+		let mut usage = self.lock();
+		let window_start = now_secs.saturating_sub(self.window_secs);
+		while usage.admitted.front().is_some_and(|(at, ..)| *at <= window_start) {
+			usage.admitted.pop_front();
+		}
+		let (global, by_initiator) = usage.admitted.iter().fold(
+			(amount, amount),
+			|(global, by_initiator), (_, address, admitted)| {
+				let by_initiator = if address.as_slice() == initiator {
+					by_initiator.saturating_add(*admitted)
+				} else {
+					by_initiator
+				};
+				(global.saturating_add(*admitted), by_initiator)
+			},
+		);
+		if global > self.max_amount_global || by_initiator > self.max_amount_per_initiator {
+			return false;
+		}
+		usage.admitted.push_back((now_secs, initiator.to_vec(), amount));
+		true
+	}
+
+	pub fn export_prometheus(&self) -> String {
+		let mut out = String::new();
+		let usage = self.lock();
+		let _ = writeln!(out, "# TYPE bridge_rate_limit_deferred_total counter");
+		let _ = writeln!(out, "bridge_rate_limit_deferred_total {}", usage.deferred_total);
+		let _ = writeln!(out, "# TYPE bridge_rate_limit_rejected_total counter");
+		let _ = writeln!(out, "bridge_rate_limit_rejected_total {}", usage.rejected_total);
+		out
+	}
+}
+
+// The initiator and amount of a lock action.
+fn locked_amount(action: &TransferAction) -> Option<(&[u8], u64)> {
+	match &action.kind {
+		TransferActionType::LockBridgeTransfer { initiator, amount, .. } => {
+			Some((initiator.0.as_slice(), amount.0))
+		}
+		_ => None,
+	}
+}
+
+fn now_secs() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|d| d.as_secs())
+		.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bridge_util::types::{Amount, BridgeAddress, BridgeTransferId, ChainId, HashLock};
+
+	fn lock_action(initiator: u8, amount: u64) -> TransferAction {
+		TransferAction {
+			chain: ChainId::TWO,
+			transfer_id: BridgeTransferId([initiator; 32]),
+			kind: TransferActionType::LockBridgeTransfer {
+				bridge_transfer_id: BridgeTransferId([initiator; 32]),
+				hash_lock: HashLock([3; 32]),
+				initiator: BridgeAddress(vec![initiator; 20]),
+				recipient: BridgeAddress(vec![2; 32]),
+				amount: Amount(amount),
+			},
+		}
+	}
+
+	#[test]
+	fn test_rate_limiter() {
+		let config = RateLimitConfig {
+			window_secs: 100,
+			max_amount_per_initiator: 50,
+			max_amount_global: 80,
+			defer_over_cap: true,
+		};
+		let limiter = RateLimiter::from(&config);
+		assert_eq!(limiter.check_at(&lock_action(1, 30), 1000), RateLimitDecision::Admit);
+		assert_eq!(limiter.check_at(&lock_action(1, 20), 1010), RateLimitDecision::Admit);
+		// The initiator reached its cap, the others can still transfer.
+		assert_eq!(limiter.check_at(&lock_action(1, 1), 1020), RateLimitDecision::Defer);
+		assert_eq!(limiter.check_at(&lock_action(2, 30), 1020), RateLimitDecision::Admit);
+		// The global cap is reached.
+		assert_eq!(limiter.check_at(&lock_action(3, 1), 1030), RateLimitDecision::Defer);
+		// Above a cap, the transfer can't be deferred.
+		assert_eq!(limiter.check_at(&lock_action(4, 60), 1030), RateLimitDecision::Reject);
+		let refund =
+			TransferAction { kind: TransferActionType::RefundInitiator, ..lock_action(1, 100) };
+		assert_eq!(limiter.check_at(&refund, 1030), RateLimitDecision::Admit);
+
+		// The first lock leaves the window.
+		assert!(!limiter.release_at(&lock_action(1, 1), 1099));
+		assert!(limiter.release_at(&lock_action(1, 1), 1100));
+		assert!(!limiter.release_at(&lock_action(3, 30), 1100));
+
+		let export = limiter.clone().export_prometheus();
+		assert!(export.contains("bridge_rate_limit_deferred_total 2\n"));
+		assert!(export.contains("bridge_rate_limit_rejected_total 1\n"));
+
+		let limiter = RateLimiter::from(&RateLimitConfig { defer_over_cap: false, ..config });
+		assert_eq!(limiter.check_at(&lock_action(1, 50), 0), RateLimitDecision::Admit);
+		assert_eq!(limiter.check_at(&lock_action(1, 1), 0), RateLimitDecision::Reject);
+		assert_eq!(
+			RateLimiter::default().check_at(&lock_action(1, u64::MAX), 0),
+			RateLimitDecision::Admit
+		);
+	}
+}
@@ -0,0 +1,364 @@
+use crate::chains::ethereum::{
+	client::EthClient,
+	types::{AtomicBridgeInitiatorMOVE, EthAddress},
+};
+use crate::chains::movement::{
+	client_framework::{MovementClientFramework, FRAMEWORK_ADDRESS},
+	utils as movement_utils,
+};
+use alloy::primitives::FixedBytes;
+use alloy::sol_types::SolCall;
+use bridge_config::common::refund::RefundConfig;
+use bridge_util::chains::bridge_contracts::{BridgeContract, BridgeContractError};
+use bridge_util::reason::{ReasonCode, WithReasonCode};
+use bridge_util::types::{BridgeTransferDetails, BridgeTransferId};
+use serde::Serialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// The on chain state of an initiated transfer that can still be refunded.
+const ETH_STATE_INITIALIZED: u8 = 0;
+const MOVEMENT_STATE_INITIALIZED: u8 = 1;
+
+/// An unsigned transaction the initiator of a transfer can sign and send to refund it.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "chain", rename_all = "snake_case")]
+pub enum RefundTx {
+	Ethereum {
+		from: String,
+		to: String,
+		/// The hex encoded calldata of `refundBridgeTransfer`.
+		data: String,
+		value: String,
+		gas: u128,
+		chain_id: u64,
+	},
+	Movement {
+		sender: String,
+		function: String,
+		type_arguments: Vec<String>,
+		arguments: Vec<String>,
+		/// The hex encoded BCS of the entry function payload.
+		bcs_payload: String,
+	},
+}
+
+/// Expected cost of sending the refund transaction against the amount it recovers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RefundCost {
+	pub gas_units: u128,
+	/// Price of a gas unit, in wei on Ethereum and in octas on Movement.
+	pub gas_unit_price: u128,
+	/// Fee of the transaction, in wei on Ethereum and in octas on Movement.
+	pub fee: u128,
+	/// The fee in units of the bridged token, unknown on Ethereum without a token price.
+	pub fee_in_token: Option<u128>,
+	/// The refund costs at least the amount it recovers.
+	pub dust: bool,
+}
+
+impl RefundCost {
+	/// The cost of the refund of the amount. `wei_per_token_unit` converts the fee
+	/// to units of the bridged token, 1 when the fee is paid in the bridged token.
+	pub fn new(
+		gas_units: u128,
+		gas_unit_price: u128,
+		wei_per_token_unit: Option<u128>,
+		amount: u64,
+	) -> Self {
+		let fee = gas_units.saturating_mul(gas_unit_price);
+		let fee_in_token = wei_per_token_unit.map(|price| fee.div_ceil(price));
+		RefundCost {
+			gas_units,
+			gas_unit_price,
+			fee,
+			fee_in_token,
+			dust: fee_in_token.is_some_and(|fee| fee >= u128::from(amount)),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RefundTxResponse {
+	pub bridge_transfer_id: String,
+	pub amount: u64,
+	pub time_lock: u64,
+	pub transaction: RefundTx,
+	/// Missing when the cost couldn't be estimated.
+	pub cost: Option<RefundCost>,
+	/// False when the refund costs more than it recovers.
+	pub recommended: bool,
+	pub warning: Option<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RefundTxError {
+	#[error("Transfer not found on either chain")]
+	NotFound,
+	#[error("Transfer is not refundable, its on chain state is {0}")]
+	NotRefundable(u8),
+	#[error("Transfer time lock expires at {0}")]
+	TimeLockNotExpired(u64),
+	#[error("Refund transactions are not enabled")]
+	Disabled,
+	#[error(transparent)]
+	Contract(#[from] BridgeContractError),
+}
+
+impl WithReasonCode for RefundTxError {
+	fn reason_code(&self) -> ReasonCode {
+		match self {
+			RefundTxError::NotFound => ReasonCode::NotFound,
+			RefundTxError::NotRefundable(_) => ReasonCode::NotRefundable,
+			RefundTxError::TimeLockNotExpired(_) => ReasonCode::TimelockNotExpired,
+			RefundTxError::Disabled => ReasonCode::FeatureDisabled,
+			RefundTxError::Contract(err) => err.reason_code(),
+		}
+	}
+}
+
+/// Builds the refund transactions from the transfers initiated on chain.
+#[derive(Clone)]
+pub struct RefundTxBuilder {
+	eth_client: EthClient,
+	movement_client: MovementClientFramework,
+	cost_config: RefundConfig,
+}
+
+impl RefundTxBuilder {
+	pub fn new(eth_client: EthClient, movement_client: MovementClientFramework) -> Self {
+		Self { eth_client, movement_client, cost_config: RefundConfig::default() }
+	}
+
+	pub fn with_cost_config(mut self, cost_config: RefundConfig) -> Self {
+		self.cost_config = cost_config;
+		self
+	}
+
+	/// Looks the transfer up on both chains and builds the refund transaction
+	/// of the chain it was initiated on.
+	pub async fn build(
+		&self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> Result<RefundTxResponse, RefundTxError> {
+		let mut eth_client = self.eth_client.clone();
+		if let Some(details) =
+			eth_client.get_bridge_transfer_details_initiator(bridge_transfer_id).await?
+		{
+			// A missing entry reads as zeroed storage.
+			if details.amount.0 != 0 {
+				check_refundable(&details, ETH_STATE_INITIALIZED)?;
+				let cost = self.eth_refund_cost(&details).await;
+				return Ok(self.eth_refund_tx(&details).with_cost(cost));
+			}
+		}
+
+		let mut movement_client = self.movement_client.clone();
+		let details = match movement_client
+			.get_bridge_transfer_details_initiator(bridge_transfer_id)
+			.await
+		{
+			Ok(Some(details)) => details,
+			Ok(None) => return Err(RefundTxError::NotFound),
+			Err(err) => {
+				// The view aborts on unknown transfers.
+				tracing::debug!("Movement lookup of transfer {bridge_transfer_id} failed: {err}");
+				return Err(RefundTxError::NotFound);
+			}
+		};
+		check_refundable(&details, MOVEMENT_STATE_INITIALIZED)?;
+		let cost = self.movement_refund_cost(&details).await;
+		Ok(self.movement_refund_tx(&details)?.with_cost(Some(cost)))
+	}
+
+	async fn eth_refund_cost(
+		&self,
+		details: &BridgeTransferDetails<EthAddress>,
+	) -> Option<RefundCost> {
+		let gas_units = match self
+			.eth_client
+			.estimate_refund_gas(details.bridge_transfer_id, details.initiator.0 .0)
+			.await
+		{
+			Ok(gas_units) => gas_units,
+			Err(err) => {
+				tracing::debug!(
+					"Refund gas estimation of {} failed, use the gas limit: {err}",
+					details.bridge_transfer_id
+				);
+				self.eth_client.config.gas_limit
+			}
+		};
+		let gas_unit_price = match self.eth_client.gas_price().await {
+			Ok(gas_unit_price) => gas_unit_price,
+			Err(err) => {
+				tracing::warn!("Failed to get the gas price of the refund cost: {err}");
+				return None;
+			}
+		};
+		let wei_per_token_unit = (self.cost_config.eth_wei_per_token_unit != 0)
+			.then_some(u128::from(self.cost_config.eth_wei_per_token_unit));
+		Some(RefundCost::new(gas_units, gas_unit_price, wei_per_token_unit, details.amount.0))
+	}
+
+	// The Movement fees are paid in the bridged token.
+	async fn movement_refund_cost<A>(&self, details: &BridgeTransferDetails<A>) -> RefundCost {
+		let gas_unit_price = match self.movement_client.rest_client.estimate_gas_price().await {
+			Ok(estimation) => estimation.into_inner().gas_estimate,
+			Err(err) => {
+				tracing::debug!("Movement gas price estimation failed, use the default: {err}");
+				movement_utils::GAS_UNIT_PRICE
+			}
+		};
+		RefundCost::new(
+			u128::from(self.cost_config.movement_refund_gas_units),
+			u128::from(gas_unit_price),
+			Some(1),
+			details.amount.0,
+		)
+	}
+
+	fn eth_refund_tx<A>(&self, details: &BridgeTransferDetails<A>) -> RefundTxResponse
+	where
+		A: Clone + Into<Vec<u8>>,
+	{
+		let call = AtomicBridgeInitiatorMOVE::refundBridgeTransferCall {
+			bridgeTransferId: FixedBytes(details.bridge_transfer_id.0),
+		};
+		let config = &self.eth_client.config;
+		RefundTxResponse::new(
+			details,
+			RefundTx::Ethereum {
+				from: details.initiator.to_string(),
+				to: config.initiator_contract.to_string(),
+				data: format!("0x{}", hex::encode(call.abi_encode())),
+				value: "0x0".to_string(),
+				gas: config.gas_limit,
+				chain_id: config.chain_id,
+			},
+		)
+	}
+
+	fn movement_refund_tx<A>(
+		&self,
+		details: &BridgeTransferDetails<A>,
+	) -> Result<RefundTxResponse, RefundTxError>
+	where
+		A: Clone + Into<Vec<u8>>,
+	{
+		let args =
+			vec![movement_utils::serialize_vec_initiator(&details.bridge_transfer_id.0[..])?];
+		let payload = movement_utils::make_aptos_payload(
+			FRAMEWORK_ADDRESS,
+			"atomic_bridge_initiator",
+			"refund_bridge_transfer",
+			Vec::new(),
+			args,
+		);
+		let bcs_payload =
+			bcs::to_bytes(&payload).map_err(|_| BridgeContractError::SerializationError)?;
+
+		Ok(RefundTxResponse::new(
+			details,
+			RefundTx::Movement {
+				sender: details.initiator.to_string(),
+				function: format!(
+					"{}::atomic_bridge_initiator::refund_bridge_transfer",
+					FRAMEWORK_ADDRESS.to_hex_literal()
+				),
+				type_arguments: Vec::new(),
+				arguments: vec![format!("0x{}", hex::encode(details.bridge_transfer_id.0))],
+				bcs_payload: format!("0x{}", hex::encode(bcs_payload)),
+			},
+		))
+	}
+}
+
+impl RefundTxResponse {
+	fn new<A>(details: &BridgeTransferDetails<A>, transaction: RefundTx) -> Self {
+		Self {
+			bridge_transfer_id: hex::encode(details.bridge_transfer_id.0),
+			amount: details.amount.0,
+			time_lock: details.time_lock.0,
+			transaction,
+			cost: None,
+			recommended: true,
+			warning: None,
+		}
+	}
+
+	fn with_cost(mut self, cost: Option<RefundCost>) -> Self {
+		match &cost {
+			Some(RefundCost { dust: true, fee_in_token: Some(fee), .. }) => {
+				let warning = format!(
+					"The refund costs {fee} in fees to recover an amount of {}",
+					self.amount
+				);
+				tracing::warn!("Transfer {}: {warning}", self.bridge_transfer_id);
+				self.recommended = false;
+				self.warning = Some(warning);
+			}
+			Some(_) => (),
+			None => self.warning = Some("The refund cost couldn't be estimated".to_string()),
+		}
+		self.cost = cost;
+		self
+	}
+}
+
+fn check_refundable<A>(
+	details: &BridgeTransferDetails<A>,
+	initialized_state: u8,
+) -> Result<(), RefundTxError> {
+	if details.state != initialized_state {
+		return Err(RefundTxError::NotRefundable(details.state));
+	}
+	let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+	if now < details.time_lock.0 {
+		return Err(RefundTxError::TimeLockNotExpired(details.time_lock.0));
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_refund_cost() {
+		// 50000 gas at 20 gwei, with a token unit worth 1 gwei.
+		let cost = RefundCost::new(50_000, 20_000_000_000, Some(1_000_000_000), 2_000_000);
+		assert_eq!(cost.fee, 1_000_000_000_000_000);
+		assert_eq!(cost.fee_in_token, Some(1_000_000));
+		assert!(!cost.dust);
+		assert!(RefundCost::new(50_000, 20_000_000_000, Some(1_000_000_000), 1_000_000).dust);
+		// Without a token price the fee can't be compared to the amount.
+		assert!(!RefundCost::new(50_000, 20_000_000_000, None, 1).dust);
+
+		let response = |cost| {
+			RefundTxResponse {
+				bridge_transfer_id: "00".to_string(),
+				amount: 100,
+				time_lock: 0,
+				transaction: RefundTx::Ethereum {
+					from: String::new(),
+					to: String::new(),
+					data: String::new(),
+					value: "0x0".to_string(),
+					gas: 0,
+					chain_id: 1,
+				},
+				cost: None,
+				recommended: true,
+				warning: None,
+			}
+			.with_cost(cost)
+		};
+		let dust = response(Some(RefundCost::new(2_000, 100, Some(1), 100)));
+		assert!(!dust.recommended);
+		assert!(dust.warning.is_some());
+		let refund = response(Some(RefundCost::new(2_000, 100, None, 100)));
+		assert!(refund.recommended);
+		assert!(refund.warning.is_none());
+		assert!(response(None).warning.is_some());
+	}
+}
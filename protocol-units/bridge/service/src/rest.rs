@@ -1,18 +1,102 @@
+use crate::admin_auth::AdminAuth;
+use crate::allowances::{AllowanceGcMetrics, AllowanceGcStatus};
+use crate::anomalies::{Advisory, AnomalyAdvisories};
+use crate::approvals::{ApprovalQueue, OperatorDecision};
+use crate::canary::{CanaryMetrics, CanaryStats};
+use crate::catchup::CatchUpProgress;
+use crate::circuit_breaker::{BreakerRequest, BreakerStatus, CircuitBreaker};
+use crate::claims::{ClaimError, ClaimRequest, ClaimService};
+use crate::drill::{DrillError, DrillReport, DrillRequest, FailoverDrills};
+use crate::event_metrics::EventMetrics;
+use crate::fee_tracker::{FeeSummary, FeeTracker};
+use crate::fees::{FeeQuote, RelayerFeeSchedule};
+use crate::guards::{PrecheckResult, ProspectiveTransfer, TransferGuards};
+use crate::in_flight::{InFlightTransfers, TransferStatus};
+use crate::intake::IntakeLimit;
+use crate::invariants::{InvariantMetrics, InvariantStatus};
+use crate::key_audit::{KeyAudit, KeyAuditQuery};
+use crate::latency::LatencyBreakdowns;
+use crate::live_updates::{LiveFilter, LiveUpdates, LiveUpdatesQuery};
+use crate::metrics::BridgeMetrics;
+use crate::pause::{ActivePause, PauseRequest, PauseSwitches};
+use crate::rate_limit::RateLimiter;
+use crate::refund::{RefundTxBuilder, RefundTxError};
+use crate::retry::RetryTable;
+use crate::revenue::{RevenueError, RevenueQuery, RevenueReport, RevenueReports};
+use crate::rpc_metrics::{EndpointStats, RpcMetrics};
+use crate::runbook::{Alert, RunbookHooks};
+use crate::split::SplitTransfers;
+use crate::sweep::{ColdSweeper, SweepError, SweepRecord, SweepRequest};
+use crate::transfer_search::{
+	StateAtQuery, TransferPage, TransferQuery, TransferSearch, TransferSearchError, TransferStateAt,
+};
+use crate::user_notifications::{RegistrationError, RegistrationRequest, UserNotifications};
+use crate::webhooks::{ReplayQuery, TransferWebhooks};
 use anyhow::Error;
 use bridge_config::common::movement::MovementConfig;
+use bridge_util::reason::{ReasonCode, WithReasonCode};
+use bridge_util::types::{Amount, BridgeTransferId, TransferDirection};
 use futures::prelude::*;
 use poem::{
-	get, handler, listener::TcpListener, middleware::Tracing, web::Data, EndpointExt, IntoResponse,
-	Response, Route, Server,
+	delete, get, handler,
+	http::{header, HeaderName, HeaderValue, StatusCode},
+	listener::TcpListener,
+	middleware::Tracing,
+	post,
+	web::{
+		websocket::{Message, WebSocket},
+		Data, Json, Path, Query, RemoteAddr,
+	},
+	Endpoint, EndpointExt, IntoResponse, Request, Response, Route, Server,
 };
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Display;
 use std::future::Future;
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use tokio::sync::oneshot;
 use tracing::info;
 
+/// Latest version of the REST API. Responses of the older versions and of the
+/// unversioned paths carry a deprecation header and a link to the latest version.
+pub const LATEST_API_VERSION: &str = "v2";
+
+#[derive(Clone)]
 struct RestContext {
 	request_tx: mpsc::Sender<oneshot::Sender<String>>,
+	retry_table: RetryTable,
+	admin_auth: AdminAuth,
+	guards: TransferGuards,
+	rpc_metrics: RpcMetrics,
+	approval_queue: ApprovalQueue,
+	refund_tx_builder: Option<RefundTxBuilder>,
+	canary_metrics: CanaryMetrics,
+	event_metrics: EventMetrics,
+	bridge_metrics: BridgeMetrics,
+	split_transfers: SplitTransfers,
+	pause_switches: PauseSwitches,
+	circuit_breaker: Option<CircuitBreaker>,
+	intake_limit: IntakeLimit,
+	rate_limiter: RateLimiter,
+	fee_schedule: RelayerFeeSchedule,
+	invariant_metrics: InvariantMetrics,
+	anomaly_advisories: AnomalyAdvisories,
+	allowance_gc_metrics: AllowanceGcMetrics,
+	fee_tracker: FeeTracker,
+	key_audit: KeyAudit,
+	transfer_search: Option<TransferSearch>,
+	revenue_reports: Option<RevenueReports>,
+	transfer_webhooks: Option<TransferWebhooks>,
+	user_notifications: Option<UserNotifications>,
+	live_updates: Option<LiveUpdates>,
+	claims: Option<ClaimService>,
+	failover_drills: Option<FailoverDrills>,
+	cold_sweeper: Option<ColdSweeper>,
+	in_flight: InFlightTransfers,
+	latencies: LatencyBreakdowns,
+	runbook_hooks: RunbookHooks,
+	catch_up: CatchUpProgress,
 }
 
 pub struct BridgeRest {
@@ -29,10 +113,236 @@ impl BridgeRest {
 	) -> Result<Self, anyhow::Error> {
 		let url = format!("{}:{}", conf.rest_listener_hostname, conf.rest_port);
 
-		let context = RestContext { request_tx };
+		let context = RestContext {
+			request_tx,
+			retry_table: RetryTable::default(),
+			admin_auth: AdminAuth::default(),
+			guards: TransferGuards::default(),
+			rpc_metrics: RpcMetrics::default(),
+			approval_queue: ApprovalQueue::new(u64::MAX).0,
+			refund_tx_builder: None,
+			canary_metrics: CanaryMetrics::default(),
+			event_metrics: EventMetrics::default(),
+			bridge_metrics: BridgeMetrics::default(),
+			split_transfers: SplitTransfers::default(),
+			pause_switches: PauseSwitches::default(),
+			circuit_breaker: None,
+			intake_limit: IntakeLimit::default(),
+			rate_limiter: RateLimiter::default(),
+			fee_schedule: RelayerFeeSchedule::default(),
+			invariant_metrics: InvariantMetrics::default(),
+			anomaly_advisories: AnomalyAdvisories::default(),
+			allowance_gc_metrics: AllowanceGcMetrics::default(),
+			fee_tracker: FeeTracker::default(),
+			key_audit: KeyAudit::default(),
+			transfer_search: None,
+			revenue_reports: None,
+			transfer_webhooks: None,
+			user_notifications: None,
+			live_updates: None,
+			claims: None,
+			failover_drills: None,
+			cold_sweeper: None,
+			in_flight: InFlightTransfers::default(),
+			latencies: LatencyBreakdowns::default(),
+			runbook_hooks: RunbookHooks::default(),
+			catch_up: CatchUpProgress::default(),
+		};
 		Ok(Self { url, context: Arc::new(context) })
 	}
 
+	/// Set the retry classification exported by the admin API.
+	pub fn with_retry_table(mut self, retry_table: RetryTable) -> Self {
+		Arc::make_mut(&mut self.context).retry_table = retry_table;
+		self
+	}
+
+	/// Set the tokens of the operators allowed to change the relayer on the admin API.
+	pub fn with_admin_auth(mut self, admin_auth: AdminAuth) -> Self {
+		Arc::make_mut(&mut self.context).admin_auth = admin_auth;
+		self
+	}
+
+	/// Set the guards run by the precheck endpoint.
+	pub fn with_guards(mut self, guards: TransferGuards) -> Self {
+		Arc::make_mut(&mut self.context).guards = guards;
+		self
+	}
+
+	/// Set the RPC metrics shared with the chain clients.
+	pub fn with_rpc_metrics(mut self, rpc_metrics: RpcMetrics) -> Self {
+		Arc::make_mut(&mut self.context).rpc_metrics = rpc_metrics;
+		self
+	}
+
+	/// Set the queue of the transfers waiting for an operator approval.
+	pub fn with_approval_queue(mut self, approval_queue: ApprovalQueue) -> Self {
+		Arc::make_mut(&mut self.context).approval_queue = approval_queue;
+		self
+	}
+
+	/// Enable the refund transactions endpoint.
+	pub fn with_refund_tx_builder(mut self, refund_tx_builder: RefundTxBuilder) -> Self {
+		Arc::make_mut(&mut self.context).refund_tx_builder = Some(refund_tx_builder);
+		self
+	}
+
+	/// Set the results of the canary transfers.
+	pub fn with_canary_metrics(mut self, canary_metrics: CanaryMetrics) -> Self {
+		Arc::make_mut(&mut self.context).canary_metrics = canary_metrics;
+		self
+	}
+
+	/// Set the counts of the events published on the event bus.
+	pub fn with_event_metrics(mut self, event_metrics: EventMetrics) -> Self {
+		Arc::make_mut(&mut self.context).event_metrics = event_metrics;
+		self
+	}
+
+	/// Set the records of the split transfers shared with the relayer loop.
+	pub fn with_split_transfers(mut self, split_transfers: SplitTransfers) -> Self {
+		Arc::make_mut(&mut self.context).split_transfers = split_transfers;
+		self
+	}
+
+	/// Set the pause switches shared with the guards and the relayer loop.
+	pub fn with_pause_switches(mut self, pause_switches: PauseSwitches) -> Self {
+		Arc::make_mut(&mut self.context).pause_switches = pause_switches;
+		self
+	}
+
+	/// Enable the status and the manual trips of the circuit breaker.
+	pub fn with_circuit_breaker(mut self, circuit_breaker: CircuitBreaker) -> Self {
+		Arc::make_mut(&mut self.context).circuit_breaker = Some(circuit_breaker);
+		self
+	}
+
+	/// Set the in-flight cap shared with the relayer loop.
+	pub fn with_intake_limit(mut self, intake_limit: IntakeLimit) -> Self {
+		Arc::make_mut(&mut self.context).intake_limit = intake_limit;
+		self
+	}
+
+	/// Set the rate limiter shared with the relayer loop.
+	pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+		Arc::make_mut(&mut self.context).rate_limiter = rate_limiter;
+		self
+	}
+
+	/// Set the relayer fee schedule quoted by the fee endpoint.
+	pub fn with_fee_schedule(mut self, fee_schedule: RelayerFeeSchedule) -> Self {
+		Arc::make_mut(&mut self.context).fee_schedule = fee_schedule;
+		self
+	}
+
+	/// Set the results of the reconciliations of the locked totals.
+	pub fn with_invariant_metrics(mut self, invariant_metrics: InvariantMetrics) -> Self {
+		Arc::make_mut(&mut self.context).invariant_metrics = invariant_metrics;
+		self
+	}
+
+	/// Set the advisories raised on the patterns of the transfers.
+	pub fn with_anomaly_advisories(mut self, anomaly_advisories: AnomalyAdvisories) -> Self {
+		Arc::make_mut(&mut self.context).anomaly_advisories = anomaly_advisories;
+		self
+	}
+
+	/// Set the results of the collections of the orphaned allowances.
+	pub fn with_allowance_gc_metrics(mut self, allowance_gc_metrics: AllowanceGcMetrics) -> Self {
+		Arc::make_mut(&mut self.context).allowance_gc_metrics = allowance_gc_metrics;
+		self
+	}
+
+	/// Set the fees paid by the relayer on the transfers.
+	pub fn with_fee_tracker(mut self, fee_tracker: FeeTracker) -> Self {
+		Arc::make_mut(&mut self.context).fee_tracker = fee_tracker;
+		self
+	}
+
+	/// Set the audit trail of the signatures of the chain clients.
+	pub fn with_key_audit(mut self, key_audit: KeyAudit) -> Self {
+		Arc::make_mut(&mut self.context).key_audit = key_audit;
+		self
+	}
+
+	/// Enable the transfer search endpoint.
+	pub fn with_transfer_search(mut self, transfer_search: TransferSearch) -> Self {
+		Arc::make_mut(&mut self.context).transfer_search = Some(transfer_search);
+		self
+	}
+
+	/// Enable the revenue reports of the liquidity operators.
+	pub fn with_revenue_reports(mut self, revenue_reports: RevenueReports) -> Self {
+		Arc::make_mut(&mut self.context).revenue_reports = Some(revenue_reports);
+		self
+	}
+
+	/// Enable the replay of the transfer state notifications.
+	pub fn with_transfer_webhooks(mut self, transfer_webhooks: TransferWebhooks) -> Self {
+		Arc::make_mut(&mut self.context).transfer_webhooks = Some(transfer_webhooks);
+		self
+	}
+
+	/// Enable the registrations of the end users for the notifications of their transfers.
+	pub fn with_user_notifications(mut self, user_notifications: UserNotifications) -> Self {
+		Arc::make_mut(&mut self.context).user_notifications = Some(user_notifications);
+		self
+	}
+
+	/// Enable the websocket of the live transfer updates.
+	pub fn with_live_updates(mut self, live_updates: LiveUpdates) -> Self {
+		Arc::make_mut(&mut self.context).live_updates = Some(live_updates);
+		self
+	}
+
+	/// Enable the claims of the transfers with the secrets of the end users.
+	pub fn with_claims(mut self, claims: ClaimService) -> Self {
+		Arc::make_mut(&mut self.context).claims = Some(claims);
+		self
+	}
+
+	/// Enable the failover drills of the test deployments.
+	pub fn with_failover_drills(mut self, failover_drills: FailoverDrills) -> Self {
+		Arc::make_mut(&mut self.context).failover_drills = Some(failover_drills);
+		self
+	}
+
+	/// Enable the sweeps of the relayer accounts to cold storage.
+	pub fn with_cold_sweeper(mut self, cold_sweeper: ColdSweeper) -> Self {
+		Arc::make_mut(&mut self.context).cold_sweeper = Some(cold_sweeper);
+		self
+	}
+
+	/// Set the transfers in flight returned by the transfer status endpoint.
+	pub fn with_in_flight(mut self, in_flight: InFlightTransfers) -> Self {
+		Arc::make_mut(&mut self.context).in_flight = in_flight;
+		self
+	}
+
+	/// Set the latency breakdowns added to the transfer status.
+	pub fn with_latencies(mut self, latencies: LatencyBreakdowns) -> Self {
+		Arc::make_mut(&mut self.context).latencies = latencies;
+		self
+	}
+
+	/// Set the throughput and failure metrics shared with the chain clients.
+	pub fn with_bridge_metrics(mut self, bridge_metrics: BridgeMetrics) -> Self {
+		Arc::make_mut(&mut self.context).bridge_metrics = bridge_metrics;
+		self
+	}
+
+	/// Set the hooks run on the alerts raised through the admin API.
+	pub fn with_runbook_hooks(mut self, runbook_hooks: RunbookHooks) -> Self {
+		Arc::make_mut(&mut self.context).runbook_hooks = runbook_hooks;
+		self
+	}
+
+	/// Set the catch-up progress of the chain monitorings reported by the readiness endpoint.
+	pub fn with_catch_up(mut self, catch_up: CatchUpProgress) -> Self {
+		Arc::make_mut(&mut self.context).catch_up = catch_up;
+		self
+	}
+
 	pub fn run_service(&self) -> impl Future<Output = Result<(), Error>> + Send {
 		info!("Starting Movement REST service at {}", self.url);
 		let movement_rest = self.create_routes();
@@ -41,11 +351,144 @@ impl BridgeRest {
 			.map_err(Into::into)
 	}
 
+	/// Serve the Prometheus metrics alone on the url, apart from the API.
+	pub fn run_metrics_service(
+		&self,
+		url: String,
+	) -> impl Future<Output = Result<(), Error>> + Send {
+		info!("Starting metrics service at {url}");
+		let routes = Route::new().at("/metrics", get(metrics)).data(self.context.clone());
+		Server::new(TcpListener::bind(url)).run(routes).map_err(Into::into)
+	}
+
 	pub fn create_routes(&self) -> impl EndpointExt {
-		Route::new().at("/health", get(health)).with(Tracing).data(self.context.clone())
+		Route::new()
+			// Operational endpoints are not versioned.
+			.at("/health", get(health))
+			.at("/readyz", get(readyz))
+			.at("/ready", get(readyz))
+			.at("/metrics", get(metrics))
+			.nest("/v2", api_routes().around(latest_version))
+			.nest("/v1", api_routes().around(deprecated_version))
+			// Paths of the v1 API served before the API was versioned.
+			.nest("/", api_routes().around(deprecated_version))
+			.with(Tracing)
+			.data(self.context.clone())
 	}
 }
 
+// Routes shared by all the API versions. Payload changes of a new version
+// are applied by the endpoint wrapping the routes of that version.
+fn api_routes() -> Route {
+	Route::new()
+		.at("/precheck", post(precheck))
+		.at("/fees/quote", get(quote_fee))
+		.at("/transfers", get(search_transfers))
+		.at("/transfers/live", get(live_transfers))
+		.at("/transfers/:id", get(transfer_status))
+		.at("/transfers/:id/refund-tx", get(refund_tx))
+		.at("/transfers/:id/state-at", get(transfer_state_at))
+		.at("/transfers/:id/split", get(split_status))
+		.at("/transfers/:id/replay", post(replay_notifications))
+		.at("/transfers/:id/claim", post(claim_transfer))
+		.at("/notifications", post(register_notifications))
+		.at("/notifications/:token", delete(opt_out_notifications))
+		.at("/admin/retry-classification", get(retry_classification))
+		.at("/admin/rpc-stats", get(rpc_stats))
+		.at("/admin/canary", get(canary_stats))
+		.at("/admin/approvals", get(pending_approvals).post(approval_decision))
+		.at("/admin/approvals/audit", get(approval_audit))
+		.at("/admin/pauses", get(active_pauses).post(pause))
+		.at("/admin/circuit-breaker", get(circuit_breaker_status).post(circuit_breaker))
+		.at("/admin/invariants", get(invariant_status))
+		.at("/admin/anomalies", get(anomaly_advisories))
+		.at("/admin/allowances", get(allowance_gc_status))
+		.at("/admin/fees/costs", get(fee_summary))
+		.at("/admin/alerts", post(raise_alert))
+		.at("/admin/drills", get(drill_reports).post(start_drill))
+		.at("/admin/sweeps", get(sweep_history).post(manual_sweep))
+		.at("/admin/key-audit", get(key_audit))
+		.at("/admin/reports/revenue", get(revenue_report))
+		.at("/admin/reports/revenue.csv", get(revenue_report_csv))
+}
+
+/// Header carrying the reason code of an error response, on all the API versions.
+pub const REASON_CODE_HEADER: &str = "x-reason-code";
+
+/// Error body of the v2 API. The v1 API returns the message as plain text.
+/// Integrators branch on the stable code, the message is meant for the logs.
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiError {
+	pub code: ReasonCode,
+	pub error: String,
+}
+
+fn error_response(status: StatusCode, code: ReasonCode, message: impl Display) -> Response {
+	Response::builder()
+		.status(status)
+		.header(REASON_CODE_HEADER, code.as_str())
+		.body(message.to_string())
+}
+
+fn reason_error<E: WithReasonCode + Display>(status: StatusCode, err: E) -> Response {
+	error_response(status, err.reason_code(), err)
+}
+
+// The operator of an admin change, authenticated with the bearer token of the request.
+fn admin_operator(context: &RestContext, req: &Request) -> Result<String, Response> {
+	context
+		.admin_auth
+		.authenticate(req.header(header::AUTHORIZATION))
+		.map_err(|err| reason_error(StatusCode::UNAUTHORIZED, err))
+}
+
+// Code of the errors raised without one, e.g. by the extractors or the middlewares.
+fn status_reason_code(status: StatusCode) -> ReasonCode {
+	match status {
+		StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => ReasonCode::Unauthorized,
+		StatusCode::NOT_FOUND => ReasonCode::NotFound,
+		StatusCode::CONFLICT => ReasonCode::Conflict,
+		StatusCode::TOO_MANY_REQUESTS => ReasonCode::RateLimited,
+		StatusCode::SERVICE_UNAVAILABLE => ReasonCode::Unavailable,
+		status if status.is_client_error() => ReasonCode::InvalidRequest,
+		_ => ReasonCode::Internal,
+	}
+}
+
+// v2: errors are returned as JSON.
+async fn latest_version(routes: Arc<Route>, req: Request) -> poem::Result<Response> {
+	let mut resp = routes.call(req).await.unwrap_or_else(|err| err.into_response());
+	let status = resp.status();
+	if status.is_client_error() || status.is_server_error() {
+		let code = resp
+			.header(REASON_CODE_HEADER)
+			.and_then(|code| code.parse().ok())
+			.unwrap_or_else(|| status_reason_code(status));
+		let error = resp.take_body().into_string().await.unwrap_or_default();
+		let mut resp = (status, Json(ApiError { code, error })).into_response();
+		resp.headers_mut().insert(
+			HeaderName::from_static(REASON_CODE_HEADER),
+			HeaderValue::from_static(code.as_str()),
+		);
+		return Ok(resp);
+	}
+	Ok(resp)
+}
+
+// v1 and unversioned paths: the payloads are unchanged,
+// the response links to the same path of the latest version.
+async fn deprecated_version(routes: Arc<Route>, req: Request) -> poem::Result<Response> {
+	let successor =
+		format!("</{LATEST_API_VERSION}{}>; rel=\"successor-version\"", req.uri().path());
+	let mut resp = routes.call(req).await.unwrap_or_else(|err| err.into_response());
+	let headers = resp.headers_mut();
+	headers.insert(HeaderName::from_static("deprecation"), HeaderValue::from_static("true"));
+	if let Ok(link) = HeaderValue::from_str(&successor) {
+		headers.insert(header::LINK, link);
+	}
+	Ok(resp)
+}
+
 #[handler]
 async fn health(context: Data<&Arc<RestContext>>) -> Result<Response, anyhow::Error> {
 	let (tx, rx) = oneshot::channel();
@@ -53,3 +496,602 @@ async fn health(context: Data<&Arc<RestContext>>) -> Result<Response, anyhow::Er
 	let resp = rx.await?;
 	Ok(resp.into_response())
 }
+
+// Ready once the chain monitorings have caught up with the history,
+// so orchestrators don't route traffic to a relayer still replaying it.
+#[handler]
+async fn readyz(context: Data<&Arc<RestContext>>) -> Response {
+	let readiness = context.catch_up.readiness();
+	let status = if readiness.ready { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+	(status, Json(readiness)).into_response()
+}
+
+#[handler]
+async fn retry_classification(context: Data<&Arc<RestContext>>) -> Json<RetryTable> {
+	Json(context.retry_table.clone())
+}
+
+#[handler]
+async fn precheck(
+	context: Data<&Arc<RestContext>>,
+	Json(transfer): Json<ProspectiveTransfer>,
+) -> Json<PrecheckResult> {
+	Json(context.guards.precheck(&transfer))
+}
+
+#[derive(Debug, Deserialize)]
+struct FeeQuoteQuery {
+	amount: u64,
+}
+
+// The relayer fee deducted from the amount and the amount received on the destination chain.
+#[handler]
+async fn quote_fee(
+	context: Data<&Arc<RestContext>>,
+	Query(query): Query<FeeQuoteQuery>,
+) -> Json<FeeQuote> {
+	Json(context.fee_schedule.quote_fee(Amount(query.amount)))
+}
+
+#[handler]
+async fn metrics(context: Data<&Arc<RestContext>>) -> String {
+	context.rpc_metrics.export_prometheus()
+		+ &context.canary_metrics.export_prometheus()
+		+ &context.event_metrics.export_prometheus()
+		+ &context.intake_limit.export_prometheus()
+		+ &context.rate_limiter.export_prometheus()
+		+ &context.invariant_metrics.export_prometheus()
+		+ &context.anomaly_advisories.export_prometheus()
+		+ &context.allowance_gc_metrics.export_prometheus()
+		+ &context.fee_tracker.export_prometheus()
+		+ &context.catch_up.export_prometheus()
+		+ &context.bridge_metrics.export_prometheus()
+}
+
+#[handler]
+async fn rpc_stats(context: Data<&Arc<RestContext>>) -> Json<Vec<EndpointStats>> {
+	Json(context.rpc_metrics.snapshot())
+}
+
+#[handler]
+async fn canary_stats(
+	context: Data<&Arc<RestContext>>,
+) -> Json<BTreeMap<TransferDirection, CanaryStats>> {
+	Json(context.canary_metrics.snapshot())
+}
+
+#[handler]
+async fn invariant_status(context: Data<&Arc<RestContext>>) -> Json<InvariantStatus> {
+	Json(context.invariant_metrics.status())
+}
+
+#[handler]
+async fn anomaly_advisories(context: Data<&Arc<RestContext>>) -> Json<Vec<Advisory>> {
+	Json(context.anomaly_advisories.recent())
+}
+
+#[handler]
+async fn allowance_gc_status(context: Data<&Arc<RestContext>>) -> Json<AllowanceGcStatus> {
+	Json(context.allowance_gc_metrics.status())
+}
+
+// Fees paid by the relayer on all the transfers, to tune the relayer fees.
+#[handler]
+async fn fee_summary(context: Data<&Arc<RestContext>>) -> Json<FeeSummary> {
+	Json(context.fee_tracker.summary())
+}
+
+#[handler]
+async fn split_status(context: Data<&Arc<RestContext>>, Path(id): Path<String>) -> Response {
+	let transfer_id = match BridgeTransferId::parse(id.strip_prefix("0x").unwrap_or(&id)) {
+		Ok(transfer_id) => transfer_id,
+		Err(err) => {
+			return error_response(StatusCode::BAD_REQUEST, ReasonCode::InvalidRequest, err)
+		}
+	};
+	match context.split_transfers.status(&transfer_id) {
+		Some(status) => Json(status).into_response(),
+		None => {
+			error_response(StatusCode::NOT_FOUND, ReasonCode::NotFound, "Transfer is not split")
+		}
+	}
+}
+
+// The transfers pending approval and the audit trail are only read by the operators.
+#[handler]
+async fn pending_approvals(context: Data<&Arc<RestContext>>, req: &Request) -> Response {
+	if let Err(resp) = admin_operator(&context, req) {
+		return resp;
+	}
+	Json(context.approval_queue.pending()).into_response()
+}
+
+// The decision is recorded under the operator of the bearer token.
+#[handler]
+async fn approval_decision(
+	context: Data<&Arc<RestContext>>,
+	req: &Request,
+	Json(decision): Json<OperatorDecision>,
+) -> Result<Response, anyhow::Error> {
+	let operator = match admin_operator(&context, req) {
+		Ok(operator) => operator,
+		Err(resp) => return Ok(resp),
+	};
+	context.approval_queue.submit(decision, operator).await?;
+	Ok(StatusCode::ACCEPTED.into_response())
+}
+
+#[handler]
+async fn approval_audit(context: Data<&Arc<RestContext>>, req: &Request) -> Response {
+	if let Err(resp) = admin_operator(&context, req) {
+		return resp;
+	}
+	Json(context.approval_queue.audit_log()).into_response()
+}
+
+#[handler]
+async fn refund_tx(context: Data<&Arc<RestContext>>, Path(id): Path<String>) -> Response {
+	let transfer_id = match BridgeTransferId::parse(id.strip_prefix("0x").unwrap_or(&id)) {
+		Ok(transfer_id) => transfer_id,
+		Err(err) => {
+			return error_response(StatusCode::BAD_REQUEST, ReasonCode::InvalidRequest, err)
+		}
+	};
+	let result = match &context.refund_tx_builder {
+		Some(builder) => builder.build(transfer_id).await,
+		None => Err(RefundTxError::Disabled),
+	};
+	match result {
+		Ok(refund_tx) => Json(refund_tx).into_response(),
+		Err(err) => {
+			let status = match err {
+				RefundTxError::NotFound => StatusCode::NOT_FOUND,
+				RefundTxError::NotRefundable(_) | RefundTxError::TimeLockNotExpired(_) => {
+					StatusCode::CONFLICT
+				}
+				RefundTxError::Disabled => StatusCode::SERVICE_UNAVAILABLE,
+				RefundTxError::Contract(_) => StatusCode::BAD_GATEWAY,
+			};
+			reason_error(status, err)
+		}
+	}
+}
+
+// The relayer completes the transfer with the secret posted by its end user.
+#[handler]
+async fn claim_transfer(
+	context: Data<&Arc<RestContext>>,
+	remote_addr: &RemoteAddr,
+	Path(id): Path<String>,
+	Json(request): Json<ClaimRequest>,
+) -> Response {
+	let transfer_id = match BridgeTransferId::parse(id.strip_prefix("0x").unwrap_or(&id)) {
+		Ok(transfer_id) => transfer_id,
+		Err(err) => {
+			return error_response(StatusCode::BAD_REQUEST, ReasonCode::InvalidRequest, err)
+		}
+	};
+	// The claims are rate limited by client IP.
+	let client = match remote_addr.as_socket_addr() {
+		Some(addr) => addr.ip().to_string(),
+		None => remote_addr.to_string(),
+	};
+	let result = match &context.claims {
+		Some(claims) => claims.claim(&client, transfer_id, &request).await,
+		None => Err(ClaimError::Disabled),
+	};
+	match result {
+		Ok(claim) => Json(claim).into_response(),
+		Err(err) => {
+			let status = match err {
+				ClaimError::InvalidSecret(_) | ClaimError::WrongSecret(_) => {
+					StatusCode::BAD_REQUEST
+				}
+				ClaimError::UnknownTransfer(_) => StatusCode::NOT_FOUND,
+				ClaimError::NotLocked(_) | ClaimError::AlreadyClaimed(_) => StatusCode::CONFLICT,
+				ClaimError::TooManyAttempts | ClaimError::RateLimited => {
+					StatusCode::TOO_MANY_REQUESTS
+				}
+				ClaimError::Disabled => StatusCode::SERVICE_UNAVAILABLE,
+				ClaimError::Submission(_) => StatusCode::BAD_GATEWAY,
+			};
+			reason_error(status, err)
+		}
+	}
+}
+
+#[handler]
+async fn active_pauses(context: Data<&Arc<RestContext>>) -> Json<Vec<ActivePause>> {
+	Json(context.pause_switches.active())
+}
+
+// The pause is recorded under the operator of the bearer token.
+#[handler]
+async fn pause(
+	context: Data<&Arc<RestContext>>,
+	req: &Request,
+	Json(mut request): Json<PauseRequest>,
+) -> Response {
+	request.operator = match admin_operator(&context, req) {
+		Ok(operator) => operator,
+		Err(resp) => return resp,
+	};
+	context.pause_switches.apply(request);
+	StatusCode::NO_CONTENT.into_response()
+}
+
+#[handler]
+async fn circuit_breaker_status(context: Data<&Arc<RestContext>>) -> Response {
+	match &context.circuit_breaker {
+		Some(circuit_breaker) => Json::<BreakerStatus>(circuit_breaker.status()).into_response(),
+		None => error_response(
+			StatusCode::SERVICE_UNAVAILABLE,
+			ReasonCode::FeatureDisabled,
+			"No circuit breaker",
+		),
+	}
+}
+
+// Trip the breaker by hand, or reset it once the anomaly is understood. The change is recorded
+// under the operator of the bearer token.
+#[handler]
+async fn circuit_breaker(
+	context: Data<&Arc<RestContext>>,
+	req: &Request,
+	Json(mut request): Json<BreakerRequest>,
+) -> Response {
+	request.operator = match admin_operator(&context, req) {
+		Ok(operator) => operator,
+		Err(resp) => return resp,
+	};
+	match &context.circuit_breaker {
+		Some(circuit_breaker) => {
+			circuit_breaker.apply(request);
+			StatusCode::NO_CONTENT.into_response()
+		}
+		None => StatusCode::SERVICE_UNAVAILABLE.into_response(),
+	}
+}
+
+// The hooks run commands on the relayer host, only the operators can raise alerts.
+#[handler]
+async fn raise_alert(
+	context: Data<&Arc<RestContext>>,
+	req: &Request,
+	Json(alert): Json<Alert>,
+) -> Response {
+	let operator = match admin_operator(&context, req) {
+		Ok(operator) => operator,
+		Err(resp) => return resp,
+	};
+	tracing::error!(
+		target: "bridge_alert",
+		"{} alert raised by {operator}: {}",
+		alert.kind,
+		alert.summary
+	);
+	context.runbook_hooks.raise_by_operator(alert, &operator);
+	StatusCode::ACCEPTED.into_response()
+}
+
+#[handler]
+async fn drill_reports(context: Data<&Arc<RestContext>>) -> Json<Vec<DrillReport>> {
+	Json(
+		context
+			.failover_drills
+			.as_ref()
+			.map(FailoverDrills::reports)
+			.unwrap_or_default(),
+	)
+}
+
+// The drill runs in the background, its report is returned by the reports endpoint.
+#[handler]
+async fn start_drill(
+	context: Data<&Arc<RestContext>>,
+	req: &Request,
+	Json(request): Json<DrillRequest>,
+) -> Response {
+	if let Err(resp) = admin_operator(&context, req) {
+		return resp;
+	}
+	let result = match &context.failover_drills {
+		Some(failover_drills) => failover_drills.start(request),
+		None => Err(DrillError::Disabled),
+	};
+	match result {
+		Ok(()) => StatusCode::ACCEPTED.into_response(),
+		Err(err) => {
+			let status = match err {
+				DrillError::Disabled => StatusCode::SERVICE_UNAVAILABLE,
+				DrillError::AlreadyRunning => StatusCode::CONFLICT,
+				DrillError::InvalidDuration(_) => StatusCode::BAD_REQUEST,
+			};
+			reason_error(status, err)
+		}
+	}
+}
+
+#[handler]
+async fn sweep_history(context: Data<&Arc<RestContext>>) -> Json<Vec<SweepRecord>> {
+	Json(context.cold_sweeper.as_ref().map(ColdSweeper::records).unwrap_or_default())
+}
+
+// The request must be signed by the admin key, the relayer config alone can't sweep.
+#[handler]
+async fn manual_sweep(
+	context: Data<&Arc<RestContext>>,
+	req: &Request,
+	Json(request): Json<SweepRequest>,
+) -> Response {
+	if let Err(resp) = admin_operator(&context, req) {
+		return resp;
+	}
+	let result = match &context.cold_sweeper {
+		Some(cold_sweeper) => cold_sweeper.sweep_on_request(&request).await,
+		None => Err(SweepError::Disabled),
+	};
+	match result {
+		Ok(records) => Json(records).into_response(),
+		Err(err) => {
+			let status = match err {
+				SweepError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+				SweepError::Disabled => StatusCode::SERVICE_UNAVAILABLE,
+				SweepError::Failed(_) => StatusCode::INTERNAL_SERVER_ERROR,
+			};
+			reason_error(status, err)
+		}
+	}
+}
+
+// Signatures of the relayer keys, for the security reviews. Only the operators can read them.
+#[handler]
+async fn key_audit(
+	context: Data<&Arc<RestContext>>,
+	req: &Request,
+	Query(query): Query<KeyAuditQuery>,
+) -> Response {
+	if let Err(resp) = admin_operator(&context, req) {
+		return resp;
+	}
+	Json(context.key_audit.report(query.after)).into_response()
+}
+
+// Fee revenue, gas costs and utilization of the liquidity by period, for the operators.
+#[handler]
+async fn revenue_report(
+	context: Data<&Arc<RestContext>>,
+	req: &Request,
+	Query(query): Query<RevenueQuery>,
+) -> Response {
+	if let Err(resp) = admin_operator(&context, req) {
+		return resp;
+	}
+	match build_revenue_report(&context, query).await {
+		Ok(report) => Json(report).into_response(),
+		Err(err) => revenue_error(err),
+	}
+}
+
+#[handler]
+async fn revenue_report_csv(
+	context: Data<&Arc<RestContext>>,
+	req: &Request,
+	Query(query): Query<RevenueQuery>,
+) -> Response {
+	if let Err(resp) = admin_operator(&context, req) {
+		return resp;
+	}
+	match build_revenue_report(&context, query).await {
+		Ok(report) => report.to_csv().with_content_type("text/csv").into_response(),
+		Err(err) => revenue_error(err),
+	}
+}
+
+async fn build_revenue_report(
+	context: &RestContext,
+	query: RevenueQuery,
+) -> Result<RevenueReport, RevenueError> {
+	match &context.revenue_reports {
+		Some(revenue_reports) => revenue_reports.report(query).await,
+		None => Err(RevenueError::Disabled),
+	}
+}
+
+fn revenue_error(err: RevenueError) -> Response {
+	let status = match err {
+		RevenueError::InvalidQuery(_) => StatusCode::BAD_REQUEST,
+		RevenueError::Disabled => StatusCode::SERVICE_UNAVAILABLE,
+		RevenueError::Indexer(_) => StatusCode::INTERNAL_SERVER_ERROR,
+	};
+	reason_error(status, err)
+}
+
+#[handler]
+async fn search_transfers(
+	context: Data<&Arc<RestContext>>,
+	Query(query): Query<TransferQuery>,
+) -> Response {
+	let result: Result<TransferPage, _> = match &context.transfer_search {
+		Some(transfer_search) => transfer_search.search(query).await,
+		None => Err(TransferSearchError::Disabled),
+	};
+	match result {
+		Ok(page) => Json(page).into_response(),
+		Err(err) => transfer_search_error(err),
+	}
+}
+
+// The transfers done are searched on /transfers.
+#[handler]
+async fn transfer_status(context: Data<&Arc<RestContext>>, Path(id): Path<String>) -> Response {
+	let transfer_id = match BridgeTransferId::parse(id.strip_prefix("0x").unwrap_or(&id)) {
+		Ok(transfer_id) => transfer_id,
+		Err(err) => {
+			return error_response(StatusCode::BAD_REQUEST, ReasonCode::InvalidRequest, err)
+		}
+	};
+	match context.in_flight.get(transfer_id) {
+		Some(transfer) => Json(TransferStatus {
+			latency: context.latencies.get(&transfer_id),
+			cost: context.fee_tracker.transfer_cost(&transfer_id),
+			..TransferStatus::from(transfer)
+		})
+		.into_response(),
+		None => error_response(
+			StatusCode::NOT_FOUND,
+			ReasonCode::NotFound,
+			format!("No transfer {transfer_id} in flight"),
+		),
+	}
+}
+
+// What the relayer knew about the transfer at a past time, for the postmortems.
+#[handler]
+async fn transfer_state_at(
+	context: Data<&Arc<RestContext>>,
+	Path(id): Path<String>,
+	Query(query): Query<StateAtQuery>,
+) -> Response {
+	let result: Result<TransferStateAt, _> = match &context.transfer_search {
+		Some(transfer_search) => transfer_search.state_at(&id, query.at).await,
+		None => Err(TransferSearchError::Disabled),
+	};
+	match result {
+		Ok(state) => Json(state).into_response(),
+		Err(err) => transfer_search_error(err),
+	}
+}
+
+fn transfer_search_error(err: TransferSearchError) -> Response {
+	let status = match err {
+		TransferSearchError::InvalidQuery(_) => StatusCode::BAD_REQUEST,
+		TransferSearchError::Disabled => StatusCode::SERVICE_UNAVAILABLE,
+		TransferSearchError::Indexer(_) => StatusCode::INTERNAL_SERVER_ERROR,
+	};
+	reason_error(status, err)
+}
+
+#[handler]
+async fn replay_notifications(
+	context: Data<&Arc<RestContext>>,
+	Path(id): Path<String>,
+	Query(query): Query<ReplayQuery>,
+) -> Response {
+	let transfer_id = match BridgeTransferId::parse(id.strip_prefix("0x").unwrap_or(&id)) {
+		Ok(transfer_id) => transfer_id,
+		Err(err) => {
+			return error_response(StatusCode::BAD_REQUEST, ReasonCode::InvalidRequest, err)
+		}
+	};
+	match &context.transfer_webhooks {
+		Some(webhooks) => Json(webhooks.replay(transfer_id, query.after).await).into_response(),
+		None => error_response(
+			StatusCode::SERVICE_UNAVAILABLE,
+			ReasonCode::FeatureDisabled,
+			"Transfer webhooks are not enabled",
+		),
+	}
+}
+
+// Websocket streaming the changes of state of the transfers as JSON text messages,
+// until the client closes it.
+#[handler]
+async fn live_transfers(
+	context: Data<&Arc<RestContext>>,
+	Query(query): Query<LiveUpdatesQuery>,
+	ws: WebSocket,
+) -> Response {
+	let Some(live_updates) = &context.live_updates else {
+		return error_response(
+			StatusCode::SERVICE_UNAVAILABLE,
+			ReasonCode::FeatureDisabled,
+			"Live updates are not enabled",
+		);
+	};
+	let filter = match LiveFilter::try_from(&query) {
+		Ok(filter) => filter,
+		Err(err) => {
+			return error_response(StatusCode::BAD_REQUEST, ReasonCode::InvalidRequest, err)
+		}
+	};
+	let Some(mut updates) = live_updates.subscribe() else {
+		return error_response(
+			StatusCode::TOO_MANY_REQUESTS,
+			ReasonCode::RateLimited,
+			"Too many live update connections",
+		);
+	};
+	ws.on_upgrade(move |socket| async move {
+		let (mut sink, mut stream) = socket.split();
+		loop {
+			tokio::select! {
+				update = updates.next(&filter) => {
+					let Some(update) = update else { break };
+					let text = match serde_json::to_string(&update) {
+						Ok(text) => text,
+						Err(err) => {
+							tracing::warn!("Failed to serialize live update: {err}");
+							continue;
+						}
+					};
+					if sink.send(Message::Text(text)).await.is_err() {
+						break;
+					}
+				}
+				message = stream.next() => match message {
+					Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+					Some(Ok(_)) => (),
+				},
+			}
+		}
+	})
+	.into_response()
+}
+
+#[handler]
+async fn register_notifications(
+	context: Data<&Arc<RestContext>>,
+	Json(request): Json<RegistrationRequest>,
+) -> Response {
+	let Some(user_notifications) = &context.user_notifications else {
+		return error_response(
+			StatusCode::SERVICE_UNAVAILABLE,
+			ReasonCode::FeatureDisabled,
+			"User notifications are not enabled",
+		);
+	};
+	match user_notifications.register(request) {
+		Ok(registration) => Json(registration).into_response(),
+		Err(err) => {
+			let status = match err {
+				RegistrationError::Full => StatusCode::TOO_MANY_REQUESTS,
+				RegistrationError::EmailDisabled => StatusCode::SERVICE_UNAVAILABLE,
+				RegistrationError::MissingTransfer | RegistrationError::Invalid(_) => {
+					StatusCode::BAD_REQUEST
+				}
+			};
+			reason_error(status, err)
+		}
+	}
+}
+
+#[handler]
+async fn opt_out_notifications(
+	context: Data<&Arc<RestContext>>,
+	Path(token): Path<String>,
+) -> Response {
+	match &context.user_notifications {
+		Some(user_notifications) if user_notifications.opt_out(&token) => {
+			StatusCode::NO_CONTENT.into_response()
+		}
+		Some(_) => {
+			error_response(StatusCode::NOT_FOUND, ReasonCode::NotFound, "Unknown opt-out token")
+		}
+		None => error_response(
+			StatusCode::SERVICE_UNAVAILABLE,
+			ReasonCode::FeatureDisabled,
+			"User notifications are not enabled",
+		),
+	}
+}
@@ -0,0 +1,213 @@
+use bridge_config::common::retry::RetryConfig;
+use bridge_util::chains::bridge_contracts::{
+	BridgeContractError, BridgeContractErrorKind, RetryPolicy,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::time::Duration;
+
+const DEFAULT_MAX_RETRIES: usize = 5;
+const DEFAULT_BACKOFF_SECS: u64 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RetryDecision {
+	/// Re-execute the action until `max_retries` is reached.
+	Retry,
+	/// Abort the transfer on the first occurrence.
+	Abort,
+}
+
+impl FromStr for RetryDecision {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"retry" => Ok(RetryDecision::Retry),
+			"abort" => Ok(RetryDecision::Abort),
+			_ => Err(format!("Unknown retry decision: {s}, expected retry or abort")),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct RetryRule {
+	pub decision: RetryDecision,
+	pub max_retries: usize,
+	pub backoff_secs: u64,
+}
+
+impl RetryRule {
+	fn retry() -> Self {
+		RetryRule {
+			decision: RetryDecision::Retry,
+			max_retries: DEFAULT_MAX_RETRIES,
+			backoff_secs: DEFAULT_BACKOFF_SECS,
+		}
+	}
+
+	fn abort() -> Self {
+		RetryRule { decision: RetryDecision::Abort, max_retries: 0, backoff_secs: 0 }
+	}
+
+	pub fn backoff(&self) -> Duration {
+		Duration::from_secs(self.backoff_secs)
+	}
+}
+
+/// Classification of the action execution errors: which are retried, with what backoff,
+/// and which abort the transfer. Exported as is by the admin API.
+#[derive(Debug, Clone, Serialize)]
+pub struct RetryTable {
+	rules: BTreeMap<String, RetryRule>,
+}
+
+impl Default for RetryTable {
+	fn default() -> Self {
+		let rules = BridgeContractErrorKind::ALL
+			.iter()
+			.map(|kind| {
+				// Errors that depend on the RPC provider or on the chain state are retried,
+				// the others won't change by re-executing the same action.
+				let rule =
+					if kind.is_retryable() { RetryRule::retry() } else { RetryRule::abort() };
+				(kind.to_string(), rule)
+			})
+			.collect();
+		RetryTable { rules }
+	}
+}
+
+impl RetryTable {
+	pub fn rule(&self, kind: BridgeContractErrorKind) -> RetryRule {
+		// All kinds are present in the table, abort if not to be safe.
+		self.rules.get(kind.as_str()).copied().unwrap_or(RetryRule::abort())
+	}
+
+	/// Return the delay before the action is re-executed,
+	/// or None if the transfer must be aborted.
+	pub fn classify(&self, err: &BridgeContractError, nb_retry: usize) -> Option<Duration> {
+		let rule = self.rule(err.kind());
+		match rule.decision {
+			RetryDecision::Retry if nb_retry <= rule.max_retries => Some(rule.backoff()),
+			RetryDecision::Retry | RetryDecision::Abort => None,
+		}
+	}
+}
+
+/// Retries of the calls to the bridge contracts, before their error is returned to the caller.
+pub fn call_retry_policy(conf: &RetryConfig) -> RetryPolicy {
+	RetryPolicy {
+		max_retries: conf.call_max_retries,
+		initial_backoff: Duration::from_millis(conf.call_backoff_ms),
+		max_backoff: Duration::from_millis(conf.call_max_backoff_ms),
+	}
+}
+
+impl TryFrom<&RetryConfig> for RetryTable {
+	type Error = anyhow::Error;
+
+	fn try_from(conf: &RetryConfig) -> Result<Self, Self::Error> {
+		let mut table = RetryTable::default();
+		for rule_conf in &conf.overrides {
+			let kind = BridgeContractErrorKind::from_str(&rule_conf.error_kind)
+				.map_err(|err| anyhow::anyhow!(err))?;
+			let decision =
+				RetryDecision::from_str(&rule_conf.decision).map_err(|err| anyhow::anyhow!(err))?;
+			table.rules.insert(
+				kind.to_string(),
+				RetryRule {
+					decision,
+					max_retries: rule_conf.max_retries,
+					backoff_secs: rule_conf.backoff_secs,
+				},
+			);
+		}
+		Ok(table)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bridge_config::common::retry::RetryRuleConfig;
+
+	#[test]
+	fn test_default_table_classify() {
+		let table = RetryTable::default();
+		let err = BridgeContractError::OnChainError("timeout".to_string());
+		assert_eq!(table.classify(&err, 1), Some(Duration::from_secs(DEFAULT_BACKOFF_SECS)));
+		assert_eq!(table.classify(&err, DEFAULT_MAX_RETRIES + 1), None);
+		let err = BridgeContractError::BadAddressEncoding("bad".to_string());
+		assert_eq!(table.classify(&err, 1), None);
+	}
+
+	#[test]
+	fn test_config_override() {
+		let conf = RetryConfig {
+			overrides: vec![RetryRuleConfig {
+				error_kind: "BadAddressEncoding".to_string(),
+				decision: "retry".to_string(),
+				max_retries: 2,
+				backoff_secs: 10,
+			}],
+			..Default::default()
+		};
+		let table = RetryTable::try_from(&conf).unwrap();
+		let err = BridgeContractError::BadAddressEncoding("bad".to_string());
+		assert_eq!(table.classify(&err, 2), Some(Duration::from_secs(10)));
+		assert_eq!(table.classify(&err, 3), None);
+
+		let conf = RetryConfig {
+			overrides: vec![RetryRuleConfig {
+				error_kind: "NotAnError".to_string(),
+				decision: "retry".to_string(),
+				max_retries: 2,
+				backoff_secs: 10,
+			}],
+			..Default::default()
+		};
+		assert!(RetryTable::try_from(&conf).is_err());
+	}
+
+	#[tokio::test]
+	async fn test_call_retry_policy() {
+		let policy = call_retry_policy(&RetryConfig {
+			call_max_retries: 2,
+			call_backoff_ms: 1,
+			call_max_backoff_ms: 2,
+			..Default::default()
+		});
+		for retry in 0..4 {
+			let backoff = policy.backoff(retry);
+			assert!(backoff <= Duration::from_millis(2), "Backoff {backoff:?} over the max");
+		}
+
+		// A retryable error is retried until the retries are exhausted.
+		let mut calls = 0;
+		let res: Result<(), _> = policy
+			.retry(&mut calls, |calls| {
+				Box::pin(async move {
+					*calls += 1;
+					Err(BridgeContractError::CallError)
+				})
+			})
+			.await;
+		assert_eq!(res, Err(BridgeContractError::CallError));
+		assert_eq!(calls, 3);
+
+		// A terminal error is returned on the first call.
+		let mut calls = 0;
+		let res: Result<(), _> = policy
+			.retry(&mut calls, |calls| {
+				Box::pin(async move {
+					*calls += 1;
+					Err(BridgeContractError::ParsePreimageError)
+				})
+			})
+			.await;
+		assert_eq!(res, Err(BridgeContractError::ParsePreimageError));
+		assert_eq!(calls, 1);
+	}
+}
@@ -0,0 +1,414 @@
+//! Revenue reports of the liquidity operators: the fees accrued on the transfers, the gas spent
+//! by the relayer accounts, the net margin and the utilization of the liquidity, by period.
+//! The reports are computed from the ledger of the indexer db, the gas spent is recorded in it
+//! on an interval.
+use crate::metrics::BridgeMetrics;
+use bigdecimal::{BigDecimal, ToPrimitive};
+use bridge_config::common::refund::RefundConfig;
+use bridge_config::common::reports::ReportsConfig;
+use bridge_indexer_db::client::Client;
+use bridge_indexer_db::models::LedgerEntries;
+use bridge_util::reason::{ReasonCode, WithReasonCode};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+const SECS_PER_YEAR: f64 = 365.0 * 24.0 * 60.0 * 60.0;
+// Periods of a report whose request doesn't set its start.
+const DEFAULT_REPORT_PERIODS: u64 = 30;
+
+/// Query of a revenue report. Times are unix timestamps in seconds, the report ends now and
+/// starts 30 periods earlier by default.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RevenueQuery {
+	pub from: Option<i64>,
+	pub to: Option<i64>,
+	pub period_secs: Option<u64>,
+}
+
+/// Economics of the bridge over a period. The amounts are in the smallest unit of the token,
+/// the Movement gas being paid in the bridged MOVE.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RevenuePeriod {
+	pub from_secs: i64,
+	pub to_secs: i64,
+	/// Fees accrued on the transfers.
+	pub fee_revenue: u128,
+	pub eth_gas_cost_wei: u128,
+	pub movement_gas_cost_octas: u128,
+	/// Gas spent on both chains. Missing if Ethereum gas was spent without a price of the
+	/// token in wei.
+	pub gas_cost: Option<u128>,
+	pub net_margin: Option<i128>,
+	/// Amount locked on the counterparty chains.
+	pub locked_volume: u128,
+	/// Volume locked over the liquidity.
+	pub utilization: Option<f64>,
+	/// Net margin over the liquidity, annualized.
+	pub apy: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RevenueReport {
+	pub token: String,
+	pub liquidity: u64,
+	pub periods: Vec<RevenuePeriod>,
+}
+
+impl RevenueReport {
+	/// The periods of the report as CSV, one row by period.
+	pub fn to_csv(&self) -> String {
+		let mut csv = String::from(
+			"token,from_secs,to_secs,fee_revenue,eth_gas_cost_wei,movement_gas_cost_octas,gas_cost,net_margin,locked_volume,utilization,apy\n",
+		);
+		let optional = |value: Option<String>| value.unwrap_or_default();
+		for period in &self.periods {
+			let _ = writeln!(
+				csv,
+				"{},{},{},{},{},{},{},{},{},{},{}",
+				self.token,
+				period.from_secs,
+				period.to_secs,
+				period.fee_revenue,
+				period.eth_gas_cost_wei,
+				period.movement_gas_cost_octas,
+				optional(period.gas_cost.map(|cost| cost.to_string())),
+				optional(period.net_margin.map(|margin| margin.to_string())),
+				period.locked_volume,
+				optional(period.utilization.map(|utilization| format!("{utilization:.6}"))),
+				optional(period.apy.map(|apy| format!("{apy:.6}"))),
+			);
+		}
+		csv
+	}
+}
+
+#[derive(Debug, Error)]
+pub enum RevenueError {
+	#[error("Invalid query: {0}")]
+	InvalidQuery(String),
+	#[error("Revenue reports need the indexer db")]
+	Disabled,
+	#[error("Indexer query failed: {0}")]
+	Indexer(String),
+}
+
+impl WithReasonCode for RevenueError {
+	fn reason_code(&self) -> ReasonCode {
+		match self {
+			RevenueError::InvalidQuery(_) => ReasonCode::InvalidRequest,
+			RevenueError::Disabled => ReasonCode::FeatureDisabled,
+			RevenueError::Indexer(_) => ReasonCode::Unavailable,
+		}
+	}
+}
+
+impl RevenueQuery {
+	// The start, end and period length of the report.
+	fn range(
+		&self,
+		default_period_secs: u64,
+		max_periods: u32,
+		now_secs: i64,
+	) -> Result<(i64, i64, u64), RevenueError> {
+		let period_secs = self.period_secs.unwrap_or(default_period_secs);
+		let period = i64::try_from(period_secs)
+			.ok()
+			.filter(|period| *period > 0)
+			.ok_or_else(|| RevenueError::InvalidQuery(format!("invalid period {period_secs}")))?;
+		let to = self.to.unwrap_or(now_secs);
+		let from = self.from.unwrap_or(to.saturating_sub(period * DEFAULT_REPORT_PERIODS as i64));
+		if from >= to {
+			return Err(RevenueError::InvalidQuery("from must be before to".to_string()));
+		}
+		let periods = (to - from).div_ceil(period);
+		if periods > i64::from(max_periods) {
+			return Err(RevenueError::InvalidQuery(format!(
+				"{periods} periods requested, at most {max_periods}"
+			)));
+		}
+		Ok((from, to, period_secs))
+	}
+}
+
+/// What the periods of the reports are computed with.
+#[derive(Debug, Clone)]
+pub struct RevenueParams {
+	/// The token relayed by this bridge instance.
+	pub token: String,
+	pub liquidity: u64,
+	/// Price in wei of the smallest unit of the token, 0 if unknown.
+	pub eth_wei_per_token_unit: u64,
+}
+
+impl RevenueParams {
+	fn report(
+		&self,
+		from: i64,
+		to: i64,
+		period_secs: u64,
+		entries: &LedgerEntries,
+	) -> RevenueReport {
+		let period = period_secs as i64;
+		let mut periods: Vec<RevenuePeriod> = (from..to)
+			.step_by(period_secs as usize)
+			.map(|start| RevenuePeriod {
+				from_secs: start,
+				to_secs: (start + period).min(to),
+				fee_revenue: 0,
+				eth_gas_cost_wei: 0,
+				movement_gas_cost_octas: 0,
+				gas_cost: None,
+				net_margin: None,
+				locked_volume: 0,
+				utilization: None,
+				apy: None,
+			})
+			.collect();
+		let mut period_of = |at: &chrono::NaiveDateTime| {
+			let at = at.and_utc().timestamp();
+			let index = usize::try_from((at - from).div_euclid(period)).ok().filter(|_| at < to)?;
+			periods.get_mut(index)
+		};
+		for (at, amount) in &entries.fee_accruals {
+			if let Some(period) = period_of(at) {
+				period.fee_revenue += units(amount);
+			}
+		}
+		for (at, chain, amount) in &entries.gas_costs {
+			let Some(period) = period_of(at) else {
+				continue;
+			};
+			match chain.as_str() {
+				crate::catchup::ETH_CHAIN => period.eth_gas_cost_wei += units(amount),
+				crate::catchup::MOVEMENT_CHAIN => period.movement_gas_cost_octas += units(amount),
+				chain => tracing::warn!("Gas cost of unknown chain {chain} in the ledger"),
+			}
+		}
+		for (at, amount) in &entries.locked {
+			if let Some(period) = period_of(at) {
+				period.locked_volume += units(amount);
+			}
+		}
+		for period in &mut periods {
+			self.complete(period);
+		}
+		RevenueReport { token: self.token.clone(), liquidity: self.liquidity, periods }
+	}
+
+	// Compute the costs, margin and ratios of the period from its totals.
+	fn complete(&self, period: &mut RevenuePeriod) {
+		let eth_gas_cost = match (period.eth_gas_cost_wei, self.eth_wei_per_token_unit) {
+			(0, _) => Some(0),
+			(_, 0) => None,
+			(wei, price) => Some(wei.div_ceil(u128::from(price))),
+		};
+		period.gas_cost = eth_gas_cost.map(|cost| cost + period.movement_gas_cost_octas);
+		period.net_margin = period.gas_cost.map(|cost| period.fee_revenue as i128 - cost as i128);
+		if self.liquidity == 0 {
+			return;
+		}
+		let liquidity = self.liquidity as f64;
+		period.utilization = Some(period.locked_volume as f64 / liquidity);
+		let years = (period.to_secs - period.from_secs) as f64 / SECS_PER_YEAR;
+		period.apy = period.net_margin.map(|margin| margin as f64 / liquidity / years);
+	}
+}
+
+fn units(amount: &BigDecimal) -> u128 {
+	amount.to_u128().unwrap_or_default()
+}
+
+/// Computes the revenue reports from the indexer db.
+/// Clones share the same indexer connection.
+#[derive(Clone)]
+pub struct RevenueReports {
+	params: RevenueParams,
+	period_secs: u64,
+	max_periods: u32,
+	client: Arc<Mutex<Client>>,
+}
+
+impl RevenueReports {
+	pub fn new(
+		config: &ReportsConfig,
+		refund: &RefundConfig,
+		token: String,
+		client: Client,
+	) -> Self {
+		RevenueReports {
+			params: RevenueParams {
+				token,
+				liquidity: config.liquidity,
+				eth_wei_per_token_unit: refund.eth_wei_per_token_unit,
+			},
+			period_secs: config.period_secs,
+			max_periods: config.max_periods,
+			client: Arc::new(Mutex::new(client)),
+		}
+	}
+
+	pub async fn report(&self, query: RevenueQuery) -> Result<RevenueReport, RevenueError> {
+		let (from, to, period_secs) =
+			query.range(self.period_secs, self.max_periods, now_secs())?;
+		let (from_time, to_time) = (to_datetime(from)?, to_datetime(to)?);
+		let client = self.client.clone();
+		let entries = tokio::task::spawn_blocking(move || {
+			client
+				.lock()
+				.expect("Revenue reports client lock poisoned")
+				.get_ledger_entries(from_time, to_time)
+		})
+		.await
+		.map_err(|err| RevenueError::Indexer(err.to_string()))?
+		.map_err(|err| RevenueError::Indexer(err.to_string()))?;
+		Ok(self.params.report(from, to, period_secs, &entries))
+	}
+}
+
+fn to_datetime(secs: i64) -> Result<chrono::NaiveDateTime, RevenueError> {
+	chrono::DateTime::from_timestamp(secs, 0)
+		.map(|datetime| datetime.naive_utc())
+		.ok_or_else(|| RevenueError::InvalidQuery(format!("invalid timestamp {secs}")))
+}
+
+fn now_secs() -> i64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|d| d.as_secs() as i64)
+		.unwrap_or_default()
+}
+
+/// Records in the ledger the gas spent by the relayer accounts, as counted by the metrics.
+pub struct GasLedger {
+	metrics: BridgeMetrics,
+	interval: Duration,
+	// Gas already recorded, by chain label.
+	recorded: BTreeMap<&'static str, u128>,
+}
+
+impl GasLedger {
+	pub fn new(config: &ReportsConfig, metrics: BridgeMetrics) -> Self {
+		GasLedger {
+			metrics,
+			interval: Duration::from_secs(config.gas_record_interval_secs.max(1)),
+			recorded: BTreeMap::new(),
+		}
+	}
+
+	/// Record the gas spent on every interval. The gas spent since the last record is lost
+	/// if the relayer stops.
+	pub async fn run(mut self, mut indexer_db_client: Client) {
+		let mut interval = tokio::time::interval(self.interval);
+		loop {
+			interval.tick().await;
+			for (chain, amount) in self.unrecorded() {
+				match indexer_db_client.insert_gas_cost(chain, amount) {
+					Ok(()) => *self.recorded.entry(chain).or_default() += amount,
+					Err(err) => tracing::warn!("Failed to record the gas spent on {chain}: {err}"),
+				}
+			}
+		}
+	}
+
+	// The gas spent since the last record, by chain label.
+	fn unrecorded(&self) -> Vec<(&'static str, u128)> {
+		self.metrics
+			.gas_spent()
+			.into_iter()
+			.map(|(chain, spent)| {
+				(chain, spent.saturating_sub(self.recorded.get(chain).copied().unwrap_or_default()))
+			})
+			.filter(|(_, amount)| *amount > 0)
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::catchup::{ETH_CHAIN, MOVEMENT_CHAIN};
+	use bridge_util::types::ChainId;
+
+	#[test]
+	fn test_revenue_report() {
+		let params = RevenueParams {
+			token: "MOVE".to_string(),
+			liquidity: 1_000_000,
+			eth_wei_per_token_unit: 100,
+		};
+		let at = |secs| chrono::DateTime::from_timestamp(secs, 0).unwrap().naive_utc();
+		let entries = LedgerEntries {
+			fee_accruals: vec![
+				(at(1000), 500.into()),
+				(at(1500), 700.into()),
+				(at(2500), 300.into()),
+			],
+			gas_costs: vec![
+				(at(1100), ETH_CHAIN.to_string(), 10_050.into()),
+				(at(1200), MOVEMENT_CHAIN.to_string(), 40.into()),
+				(at(2100), MOVEMENT_CHAIN.to_string(), 400.into()),
+			],
+			locked: vec![(at(1000), 250_000.into()), (at(2999), 100_000.into())],
+		};
+		let report = params.report(1000, 2800, 1000, &entries);
+		assert_eq!(report.periods.len(), 2);
+		let first = &report.periods[0];
+		assert_eq!((first.from_secs, first.to_secs), (1000, 2000));
+		assert_eq!(first.fee_revenue, 1200);
+		// 10050 wei are 101 token units, rounded up.
+		assert_eq!(first.gas_cost, Some(141));
+		assert_eq!(first.net_margin, Some(1059));
+		assert_eq!(first.utilization, Some(0.25));
+		let apy = first.apy.unwrap();
+		assert!((apy - 1059.0 / 1_000_000.0 * SECS_PER_YEAR / 1000.0).abs() < 1e-9);
+		// The last period ends with the report, the lock after it isn't counted.
+		let second = &report.periods[1];
+		assert_eq!((second.from_secs, second.to_secs), (2000, 2800));
+		assert_eq!(second.net_margin, Some(-100));
+		assert_eq!(second.locked_volume, 0);
+
+		let csv = report.to_csv();
+		assert_eq!(csv.lines().count(), 3);
+		assert!(csv
+			.lines()
+			.nth(1)
+			.unwrap()
+			.starts_with("MOVE,1000,2000,1200,10050,40,141,1059,"));
+
+		// Without the price of the token, the Ethereum gas can't be deducted.
+		let params = RevenueParams { eth_wei_per_token_unit: 0, liquidity: 0, ..params };
+		let report = params.report(1000, 3000, 1000, &entries);
+		assert_eq!(report.periods[0].gas_cost, None);
+		assert_eq!(report.periods[0].apy, None);
+		assert_eq!(report.periods[0].utilization, None);
+		assert_eq!(report.periods[1].net_margin, Some(-100));
+		assert!(report.to_csv().lines().nth(1).unwrap().ends_with(",,,250000,,"));
+	}
+
+	#[test]
+	fn test_revenue_query() {
+		let query = |from, to, period_secs| RevenueQuery { from, to, period_secs };
+		assert_eq!(query(None, None, None).range(100, 40, 10_000).unwrap(), (7000, 10_000, 100));
+		assert_eq!(query(Some(0), Some(50), Some(20)).range(100, 3, 0).unwrap(), (0, 50, 20));
+		assert!(query(Some(0), Some(61), Some(20)).range(100, 3, 0).is_err());
+		assert!(query(Some(50), Some(50), None).range(100, 3, 0).is_err());
+		assert!(query(None, None, Some(0)).range(100, 3, 0).is_err());
+	}
+
+	#[test]
+	fn test_gas_ledger() {
+		let metrics = BridgeMetrics::default();
+		let mut ledger = GasLedger::new(&ReportsConfig::default(), metrics.clone());
+		assert!(ledger.unrecorded().is_empty());
+		metrics.record_gas_spent(ChainId::ONE, 100);
+		metrics.record_gas_spent(ChainId::TWO, 7);
+		assert_eq!(ledger.unrecorded(), [(ETH_CHAIN, 100), (MOVEMENT_CHAIN, 7)]);
+		ledger.recorded.insert(ETH_CHAIN, 100);
+		metrics.record_gas_spent(ChainId::ONE, 20);
+		assert_eq!(ledger.unrecorded(), [(ETH_CHAIN, 20), (MOVEMENT_CHAIN, 7)]);
+	}
+}
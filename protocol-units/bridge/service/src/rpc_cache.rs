@@ -0,0 +1,123 @@
+use bridge_config::common::rpc_cache::RpcCacheConfig;
+use std::any::Any;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// The idempotent RPC reads that can be served from the cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RpcRead {
+	ChainId,
+	TokenDecimals,
+	Code,
+	GasPrice,
+}
+
+type Entry = (Instant, Arc<dyn Any + Send + Sync>);
+
+/// Short lived cache of the idempotent RPC reads of one chain, keyed by the read
+/// and its target (ex: the contract address for the code reads).
+/// Clones share the same entries. The default cache is disabled and always reads the chain.
+#[derive(Debug, Clone, Default)]
+pub struct RpcCache {
+	ttl: Duration,
+	gas_price_ttl: Duration,
+	entries: Arc<Mutex<HashMap<(RpcRead, String), Entry>>>,
+}
+
+impl From<&RpcCacheConfig> for RpcCache {
+	fn from(config: &RpcCacheConfig) -> Self {
+		if !config.enabled {
+			return RpcCache::default();
+		}
+		RpcCache {
+			ttl: Duration::from_secs(config.ttl_secs),
+			gas_price_ttl: Duration::from_secs(config.gas_price_ttl_secs),
+			entries: Arc::default(),
+		}
+	}
+}
+
+impl RpcCache {
+	fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<(RpcRead, String), Entry>> {
+		self.entries.lock().expect("RPC cache lock poisoned")
+	}
+
+	fn ttl(&self, read: RpcRead) -> Duration {
+		match read {
+			RpcRead::GasPrice => self.gas_price_ttl,
+			RpcRead::ChainId | RpcRead::TokenDecimals | RpcRead::Code => self.ttl,
+		}
+	}
+
+	/// Return the cached value of the read if it is still fresh, else run the RPC call and
+	/// cache its result. Errors are not cached.
+	pub async fn get_or_fetch<T, E>(
+		&self,
+		read: RpcRead,
+		target: &str,
+		fetch: impl Future<Output = Result<T, E>>,
+	) -> Result<T, E>
+	where
+		T: Clone + Send + Sync + 'static,
+	{
+		let ttl = self.ttl(read);
+		if ttl.is_zero() {
+			return fetch.await;
+		}
+		let key = (read, target.to_string());
+		let cached = self
+			.lock()
+			.get(&key)
+			.filter(|(fetched_at, _)| fetched_at.elapsed() < ttl)
+			.and_then(|(_, value)| value.downcast_ref::<T>().cloned());
+		if let Some(value) = cached {
+			return Ok(value);
+		}
+
+		let value = fetch.await?;
+		self.lock().insert(key, (Instant::now(), Arc::new(value.clone())));
+		Ok(value)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::atomic::{AtomicU64, Ordering};
+
+	#[tokio::test]
+	async fn test_cached_reads() {
+		let cache =
+			RpcCache::from(&RpcCacheConfig { enabled: true, ttl_secs: 60, gas_price_ttl_secs: 0 });
+		let calls = AtomicU64::new(0);
+		let fetch = || async {
+			calls.fetch_add(1, Ordering::SeqCst);
+			Ok::<_, ()>(7u64)
+		};
+
+		assert_eq!(cache.get_or_fetch(RpcRead::ChainId, "", fetch()).await, Ok(7));
+		assert_eq!(cache.get_or_fetch(RpcRead::ChainId, "", fetch()).await, Ok(7));
+		assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+		// Another target is another entry.
+		cache.get_or_fetch(RpcRead::Code, "0x01", fetch()).await.unwrap();
+		assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+		// A zero time to live disables the cache of the read.
+		cache.get_or_fetch(RpcRead::GasPrice, "", fetch()).await.unwrap();
+		cache.get_or_fetch(RpcRead::GasPrice, "", fetch()).await.unwrap();
+		assert_eq!(calls.load(Ordering::SeqCst), 4);
+
+		// Errors are not cached.
+		let res = cache.get_or_fetch(RpcRead::TokenDecimals, "", async { Err::<u8, _>(()) }).await;
+		assert!(res.is_err());
+		assert_eq!(
+			cache
+				.get_or_fetch(RpcRead::TokenDecimals, "", async { Ok::<_, ()>(18u8) })
+				.await,
+			Ok(18)
+		);
+	}
+}
@@ -0,0 +1,342 @@
+use crate::event_bus::{BusEvent, Subscription};
+use bridge_config::common::runbook::RunbookConfig;
+use bridge_util::states::TransferStateType;
+use bridge_util::types::BridgeTransferId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
+
+// Period of the check of the stuck transfers.
+const STUCK_CHECK_PERIOD: Duration = Duration::from_secs(60);
+
+/// Kind of the operator alerts hooks can be attached to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertKind {
+	/// Raised by the liquidity monitoring through the admin API.
+	LiquidityLow,
+	/// A transfer has not changed state for longer than the SLA.
+	TransferStuck,
+	/// Raised by the reconciliation jobs through the admin API.
+	ReconciliationMismatch,
+	/// A canary transfer has failed.
+	CanaryFailed,
+	/// A failover drill started or ended, with its report.
+	FailoverDrill,
+}
+
+impl AlertKind {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			AlertKind::LiquidityLow => "liquidity_low",
+			AlertKind::TransferStuck => "transfer_stuck",
+			AlertKind::ReconciliationMismatch => "reconciliation_mismatch",
+			AlertKind::CanaryFailed => "canary_failed",
+			AlertKind::FailoverDrill => "failover_drill",
+		}
+	}
+}
+
+impl std::str::FromStr for AlertKind {
+	type Err = RunbookError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		[
+			AlertKind::LiquidityLow,
+			AlertKind::TransferStuck,
+			AlertKind::ReconciliationMismatch,
+			AlertKind::CanaryFailed,
+			AlertKind::FailoverDrill,
+		]
+		.into_iter()
+		.find(|kind| kind.as_str() == s)
+		.ok_or_else(|| RunbookError::UnknownAlert(s.to_string()))
+	}
+}
+
+impl std::fmt::Display for AlertKind {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.as_str())
+	}
+}
+
+/// Alert passed as JSON to the hooks, with the context needed to remediate it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Alert {
+	pub kind: AlertKind,
+	pub summary: String,
+	#[serde(default)]
+	pub context: serde_json::Value,
+}
+
+// Payload of the hooks: the alert, the operator who raised it if not the relayer, and the
+// snapshot of the relayer configuration.
+#[derive(Serialize)]
+struct HookPayload<'a> {
+	#[serde(flatten)]
+	alert: &'a Alert,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	raised_by: Option<&'a str>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	config: Option<&'a serde_json::Value>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RunbookError {
+	#[error("Unknown alert kind {0}")]
+	UnknownAlert(String),
+	#[error("Hook of {0} alerts must have either a command or an url")]
+	InvalidAction(AlertKind),
+}
+
+#[derive(Debug, Clone)]
+enum HookAction {
+	Command { program: String, args: Vec<String> },
+	Http { url: String },
+}
+
+#[derive(Debug, Clone)]
+struct RunbookHook {
+	kind: AlertKind,
+	action: HookAction,
+}
+
+/// Runs the configured hooks of the alerts. Clones share the same hooks.
+#[derive(Debug, Clone, Default)]
+pub struct RunbookHooks {
+	hooks: Arc<Vec<RunbookHook>>,
+	timeout: Duration,
+	client: reqwest::Client,
+	config_snapshot: Option<Arc<serde_json::Value>>,
+}
+
+impl TryFrom<&RunbookConfig> for RunbookHooks {
+	type Error = RunbookError;
+
+	fn try_from(config: &RunbookConfig) -> Result<Self, Self::Error> {
+		let hooks = config
+			.hooks
+			.iter()
+			.map(|hook| {
+				let kind = hook.alert.parse()?;
+				let action = match (hook.command.split_first(), &hook.url) {
+					(Some((program, args)), None) => {
+						HookAction::Command { program: program.clone(), args: args.to_vec() }
+					}
+					(None, Some(url)) => HookAction::Http { url: url.clone() },
+					_ => return Err(RunbookError::InvalidAction(kind)),
+				};
+				Ok(RunbookHook { kind, action })
+			})
+			.collect::<Result<_, _>>()?;
+		Ok(RunbookHooks {
+			hooks: Arc::new(hooks),
+			timeout: Duration::from_secs(config.hook_timeout_secs),
+			client: reqwest::Client::new(),
+			config_snapshot: None,
+		})
+	}
+}
+
+impl RunbookHooks {
+	/// Join the snapshot of the relayer configuration to the alerts passed to the hooks.
+	pub fn with_config_snapshot(mut self, snapshot: serde_json::Value) -> Self {
+		self.config_snapshot = Some(Arc::new(snapshot));
+		self
+	}
+
+	/// Whether hooks are attached to the alerts of the kind.
+	pub fn has_hooks(&self, kind: AlertKind) -> bool {
+		self.hooks.iter().any(|hook| hook.kind == kind)
+	}
+
+	/// Run the hooks of an alert of the relayer in the background.
+	pub fn raise(&self, alert: Alert) {
+		self.run_hooks(alert, None);
+	}
+
+	/// Run the hooks of an alert raised on the admin API by the authenticated operator.
+	pub fn raise_by_operator(&self, alert: Alert, operator: &str) {
+		self.run_hooks(alert, Some(operator));
+	}
+
+	fn run_hooks(&self, alert: Alert, raised_by: Option<&str>) {
+		let payload =
+			HookPayload { alert: &alert, raised_by, config: self.config_snapshot.as_deref() };
+		let payload = match serde_json::to_string(&payload) {
+			Ok(payload) => payload,
+			Err(err) => {
+				tracing::warn!("Failed to serialize {} alert: {err}", alert.kind);
+				return;
+			}
+		};
+		for hook in self.hooks.iter().filter(|hook| hook.kind == alert.kind) {
+			let action = hook.action.clone();
+			let payload = payload.clone();
+			let client = self.client.clone();
+			let timeout = self.timeout;
+			let kind = alert.kind;
+			tokio::spawn(async move {
+				let result = tokio::time::timeout(timeout, run_action(&action, payload, client))
+					.await
+					.unwrap_or_else(|_| Err(anyhow::anyhow!("timed out after {timeout:?}")));
+				match result {
+					Ok(()) => tracing::info!("Runbook hook {action:?} of {kind} alert done"),
+					Err(err) => {
+						tracing::warn!("Runbook hook {action:?} of {kind} alert failed: {err}")
+					}
+				}
+			});
+		}
+	}
+}
+
+async fn run_action(
+	action: &HookAction,
+	payload: String,
+	client: reqwest::Client,
+) -> Result<(), anyhow::Error> {
+	match action {
+		HookAction::Command { program, args } => {
+			let mut child = tokio::process::Command::new(program)
+				.args(args)
+				.stdin(Stdio::piped())
+				.kill_on_drop(true)
+				.spawn()?;
+			if let Some(mut stdin) = child.stdin.take() {
+				stdin.write_all(payload.as_bytes()).await?;
+			}
+			let status = child.wait().await?;
+			if !status.success() {
+				anyhow::bail!("command exited with {status}");
+			}
+		}
+		HookAction::Http { url } => {
+			client
+				.post(url)
+				.header("Content-Type", "application/json")
+				.body(payload)
+				.send()
+				.await?
+				.error_for_status()?;
+		}
+	}
+	Ok(())
+}
+
+/// Raises a transfer stuck alert for the transfers whose state hasn't changed
+/// for longer than the SLA, once per state.
+pub struct StuckTransferMonitor {
+	sla: Duration,
+	hooks: RunbookHooks,
+}
+
+struct TrackedTransfer {
+	state: TransferStateType,
+	since: Instant,
+	alerted: bool,
+}
+
+impl TrackedTransfer {
+	fn new(state: TransferStateType) -> Self {
+		TrackedTransfer { state, since: Instant::now(), alerted: false }
+	}
+}
+
+impl StuckTransferMonitor {
+	pub fn new(config: &RunbookConfig, hooks: RunbookHooks) -> Self {
+		StuckTransferMonitor { sla: Duration::from_secs(config.stuck_transfer_sla_secs), hooks }
+	}
+
+	/// Follow the state changes of the subscription until the bus is dropped.
+	pub async fn run(self, mut events: Subscription) {
+		let mut transfers: HashMap<BridgeTransferId, TrackedTransfer> = HashMap::new();
+		let mut check_interval = tokio::time::interval(STUCK_CHECK_PERIOD);
+		loop {
+			tokio::select! {
+				event = events.recv() => match event {
+					Some(BusEvent::StateChange(change)) => {
+						if change.new.is_final() {
+							transfers.remove(&change.transfer_id);
+						} else {
+							transfers.insert(change.transfer_id, TrackedTransfer::new(change.new));
+						}
+					}
+					Some(_) => (),
+					None => return,
+				},
+				_ = check_interval.tick() => {
+					for (transfer_id, transfer) in transfers.iter_mut() {
+						if transfer.alerted || transfer.since.elapsed() < self.sla {
+							continue;
+						}
+						transfer.alerted = true;
+						self.alert(*transfer_id, transfer);
+					}
+				}
+			}
+		}
+	}
+
+	fn alert(&self, transfer_id: BridgeTransferId, transfer: &TrackedTransfer) {
+		let transfer_id = format!("0x{}", hex::encode(transfer_id.0));
+		let stuck_secs = transfer.since.elapsed().as_secs();
+		tracing::error!(
+			target: "bridge_alert",
+			"Transfer {transfer_id} stuck in state {} for {stuck_secs}s",
+			transfer.state
+		);
+		self.hooks.raise(Alert {
+			kind: AlertKind::TransferStuck,
+			summary: format!("Transfer {transfer_id} stuck in state {}", transfer.state),
+			context: serde_json::json!({
+				"transfer_id": transfer_id,
+				"state": transfer.state.to_string(),
+				"stuck_secs": stuck_secs,
+				"sla_secs": self.sla.as_secs(),
+			}),
+		});
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bridge_config::common::runbook::RunbookHookConfig;
+
+	#[test]
+	fn test_hooks_from_config() {
+		let hook = |alert: &str, command: &[&str], url: Option<&str>| RunbookHookConfig {
+			alert: alert.to_string(),
+			command: command.iter().map(|arg| arg.to_string()).collect(),
+			url: url.map(str::to_string),
+		};
+		let mut config = RunbookConfig {
+			hooks: vec![
+				hook("liquidity_low", &["/usr/local/bin/rebalance.sh", "--eth"], None),
+				hook("transfer_stuck", &[], Some("http://localhost:8080/remediate")),
+			],
+			..Default::default()
+		};
+		let hooks = RunbookHooks::try_from(&config).unwrap();
+		assert_eq!(hooks.hooks.len(), 2);
+		match &hooks.hooks[0].action {
+			HookAction::Command { program, args } => {
+				assert_eq!(program, "/usr/local/bin/rebalance.sh");
+				assert_eq!(args, &["--eth"]);
+			}
+			action => panic!("Unexpected hook action {action:?}"),
+		}
+		assert_eq!(hooks.hooks[1].kind, AlertKind::TransferStuck);
+
+		config.hooks = vec![hook("gas_low", &[], Some("http://localhost"))];
+		assert!(matches!(RunbookHooks::try_from(&config), Err(RunbookError::UnknownAlert(_))));
+		config.hooks = vec![hook("canary_failed", &["true"], Some("http://localhost"))];
+		assert!(matches!(RunbookHooks::try_from(&config), Err(RunbookError::InvalidAction(_))));
+		config.hooks = vec![hook("canary_failed", &[], None)];
+		assert!(matches!(RunbookHooks::try_from(&config), Err(RunbookError::InvalidAction(_))));
+	}
+}
@@ -0,0 +1,394 @@
+//! Preimages of the hash locks of the transfers initiated by the relayer. They are generated
+//! at random, stored encrypted by transfer once the initiation is seen, and released to the
+//! completion only once the lock of the counterparty chain is seen with the same hash lock.
+use crate::event_bus::{BusEvent, Subscription};
+use bridge_config::common::secrets::SecretsConfig;
+use bridge_indexer_db::encryption::FieldCipher;
+use bridge_util::chains::bridge_contracts::BridgeContractEvent;
+use bridge_util::types::{BridgeTransferId, HashLock, HashLockAlgorithm, HashLockPreImage};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+const SECRETS_FILE_NAME: &str = "secrets.store";
+// Field of the encrypted preimages, bound to their hash lock.
+const PRE_IMAGE_FIELD: &str = "pre_image";
+
+#[derive(Debug, Clone, Copy)]
+struct TransferSecret {
+	hash_lock: HashLock,
+	pre_image: HashLockPreImage,
+	lock_seen: bool,
+}
+
+#[derive(Debug, Default)]
+struct SecretsState {
+	// Generated secrets whose transfer isn't initiated yet.
+	pending: HashMap<HashLock, HashLockPreImage>,
+	transfers: HashMap<BridgeTransferId, TransferSecret>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StoredSecrets {
+	pending: BTreeMap<String, String>,
+	transfers: BTreeMap<String, StoredTransferSecret>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredTransferSecret {
+	hash_lock: String,
+	pre_image: String,
+	lock_seen: bool,
+}
+
+#[derive(Clone)]
+struct SecretStore {
+	path: PathBuf,
+	cipher: FieldCipher,
+	hash_lock_algorithm: HashLockAlgorithm,
+}
+
+impl SecretStore {
+	fn load(&self) -> Result<SecretsState, anyhow::Error> {
+		if !self.path.exists() {
+			return Ok(SecretsState::default());
+		}
+		let stored: StoredSecrets = serde_json::from_str(&std::fs::read_to_string(&self.path)?)?;
+		let mut state = SecretsState::default();
+		for (hash_lock, pre_image) in stored.pending {
+			state
+				.pending
+				.insert(decode_hash_lock(&hash_lock)?, self.unseal(&hash_lock, &pre_image)?);
+		}
+		for (transfer_id, secret) in stored.transfers {
+			let transfer_id = BridgeTransferId(
+				hex::decode(&transfer_id)?
+					.try_into()
+					.map_err(|_| anyhow::anyhow!("Invalid transfer id {transfer_id}"))?,
+			);
+			state.transfers.insert(
+				transfer_id,
+				TransferSecret {
+					hash_lock: decode_hash_lock(&secret.hash_lock)?,
+					pre_image: self.unseal(&secret.hash_lock, &secret.pre_image)?,
+					lock_seen: secret.lock_seen,
+				},
+			);
+		}
+		Ok(state)
+	}
+
+	fn save(&self, state: &SecretsState) -> Result<(), anyhow::Error> {
+		let seal = |hash_lock: &HashLock, pre_image: &HashLockPreImage| {
+			self.cipher.encrypt(&hex::encode(hash_lock.0), PRE_IMAGE_FIELD, &pre_image.0)
+		};
+		let stored = StoredSecrets {
+			pending: state
+				.pending
+				.iter()
+				.map(|(hash_lock, pre_image)| {
+					(hex::encode(hash_lock.0), seal(hash_lock, pre_image))
+				})
+				.collect(),
+			transfers: state
+				.transfers
+				.iter()
+				.map(|(transfer_id, secret)| {
+					(
+						hex::encode(transfer_id.0),
+						StoredTransferSecret {
+							hash_lock: hex::encode(secret.hash_lock.0),
+							pre_image: seal(&secret.hash_lock, &secret.pre_image),
+							lock_seen: secret.lock_seen,
+						},
+					)
+				})
+				.collect(),
+		};
+		if let Some(parent) = self.path.parent() {
+			std::fs::create_dir_all(parent)?;
+		}
+		// Written aside then renamed, a crash doesn't leave a truncated store.
+		let tmp_path = self.path.with_extension("tmp");
+		std::fs::write(&tmp_path, serde_json::to_string(&stored)?)?;
+		std::fs::rename(&tmp_path, &self.path)?;
+		Ok(())
+	}
+
+	fn unseal(&self, hash_lock: &str, sealed: &str) -> Result<HashLockPreImage, anyhow::Error> {
+		let pre_image = self.cipher.decrypt(hash_lock, PRE_IMAGE_FIELD, sealed)?;
+		let pre_image = HashLockPreImage(
+			pre_image
+				.try_into()
+				.map_err(|_| anyhow::anyhow!("Invalid preimage of {hash_lock}"))?,
+		);
+		if hex::encode(hash_lock_of(&pre_image, self.hash_lock_algorithm).0) != hash_lock {
+			anyhow::bail!("Preimage of {hash_lock} doesn't match its hash lock");
+		}
+		Ok(pre_image)
+	}
+}
+
+fn decode_hash_lock(hash_lock: &str) -> Result<HashLock, anyhow::Error> {
+	Ok(HashLock(
+		hex::decode(hash_lock)?
+			.try_into()
+			.map_err(|_| anyhow::anyhow!("Invalid hash lock {hash_lock}"))?,
+	))
+}
+
+fn hash_lock_of(pre_image: &HashLockPreImage, algorithm: HashLockAlgorithm) -> HashLock {
+	HashLock::of_pre_image(&pre_image.0, algorithm)
+}
+
+/// Generates the preimages of the transfers initiated by the relayer, and keeps them
+/// until their transfer is completed, refunded or cancelled.
+#[derive(Clone, Default)]
+pub struct SecretManager {
+	state: Arc<Mutex<SecretsState>>,
+	// Stores the secrets encrypted, none to keep them in memory only.
+	store: Option<SecretStore>,
+	released: Arc<Notify>,
+	hash_lock_algorithm: HashLockAlgorithm,
+}
+
+impl SecretManager {
+	/// The manager storing the secrets with the key of the config. Without any key, the
+	/// secrets are kept in memory only and lost on restart. The hash locks are the digests of
+	/// the secrets with the algorithm of the contracts.
+	pub fn from_config(
+		config: &SecretsConfig,
+		hash_lock_algorithm: HashLockAlgorithm,
+	) -> Result<Self, anyhow::Error> {
+		let cipher = if config.key_file.is_empty() {
+			FieldCipher::from_env()?
+		} else {
+			let key = std::fs::read_to_string(&config.key_file).map_err(|e| {
+				anyhow::anyhow!("Failed to read secrets key {}: {e}", config.key_file)
+			})?;
+			Some(FieldCipher::from_hex(&key)?)
+		};
+		let Some(cipher) = cipher else {
+			tracing::warn!("No secrets key, the generated preimages are only kept in memory");
+			return Ok(SecretManager { hash_lock_algorithm, ..Default::default() });
+		};
+		let path = if config.store_file.is_empty() {
+			let dot_movement = dot_movement::DotMovement::try_from_env()
+				.unwrap_or(dot_movement::DotMovement::new(".movement"));
+			bridge_config::get_config_path(&dot_movement).join(SECRETS_FILE_NAME)
+		} else {
+			PathBuf::from(&config.store_file)
+		};
+		Self::open(path, cipher, hash_lock_algorithm)
+	}
+
+	/// The manager of the secrets stored in the file, encrypted with the cipher.
+	pub fn open(
+		path: PathBuf,
+		cipher: FieldCipher,
+		hash_lock_algorithm: HashLockAlgorithm,
+	) -> Result<Self, anyhow::Error> {
+		let store = SecretStore { path, cipher, hash_lock_algorithm };
+		let state = store.load()?;
+		Ok(SecretManager {
+			state: Arc::new(Mutex::new(state)),
+			store: Some(store),
+			released: Arc::new(Notify::new()),
+			hash_lock_algorithm,
+		})
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<'_, SecretsState> {
+		self.state.lock().expect("Secret manager lock poisoned")
+	}
+
+	fn save(&self, state: &SecretsState) -> Result<(), anyhow::Error> {
+		match &self.store {
+			Some(store) => store.save(state),
+			None => Ok(()),
+		}
+	}
+
+	/// Generate the secret of a new transfer, and return its hash lock. The secret is stored
+	/// before it's returned, so it isn't lost if the relayer restarts once initiated.
+	pub fn generate(&self) -> Result<HashLock, anyhow::Error> {
+		let pre_image = HashLockPreImage::random();
+		let hash_lock = hash_lock_of(&pre_image, self.hash_lock_algorithm);
+		let mut state = self.lock();
+		state.pending.insert(hash_lock, pre_image);
+		if let Err(err) = self.save(&state) {
+			state.pending.remove(&hash_lock);
+			return Err(err);
+		}
+		Ok(hash_lock)
+	}
+
+	/// Drop the secret of a transfer whose initiation failed.
+	pub fn discard(&self, hash_lock: HashLock) {
+		let mut state = self.lock();
+		if state.pending.remove(&hash_lock).is_some() {
+			if let Err(err) = self.save(&state) {
+				tracing::warn!("Failed to store the secrets: {err}");
+			}
+		}
+	}
+
+	/// Wait until the lock of the transfer is seen on the counterparty chain, and return its
+	/// secret. It's never returned before, the caller bounds the wait.
+	pub async fn release(&self, transfer_id: BridgeTransferId) -> HashLockPreImage {
+		loop {
+			let notified = self.released.notified();
+			tokio::pin!(notified);
+			notified.as_mut().enable();
+			if let Some(secret) = self.lock().transfers.get(&transfer_id) {
+				if secret.lock_seen {
+					tracing::info!("Secret of transfer {transfer_id} released");
+					return secret.pre_image;
+				}
+			}
+			notified.await;
+		}
+	}
+
+	/// Follow the contract events of the subscription until the bus is dropped.
+	pub async fn run(self, mut events: Subscription) {
+		while let Some(event) = events.recv().await {
+			if let BusEvent::Contract(event) = event {
+				self.observe(&event.contract_event);
+			}
+		}
+	}
+
+	fn observe<A>(&self, event: &BridgeContractEvent<A>) {
+		let mut state = self.lock();
+		let changed = match event {
+			BridgeContractEvent::Initiated(details) => {
+				match state.pending.remove(&details.hash_lock) {
+					Some(pre_image) => {
+						state.transfers.insert(
+							details.bridge_transfer_id,
+							TransferSecret {
+								hash_lock: details.hash_lock,
+								pre_image,
+								lock_seen: false,
+							},
+						);
+						true
+					}
+					None => false,
+				}
+			}
+			// A lock with another hash lock can't be completed with the secret.
+			BridgeContractEvent::Locked(details) => {
+				match state.transfers.get_mut(&details.bridge_transfer_id) {
+					Some(secret) if secret.hash_lock == details.hash_lock && !secret.lock_seen => {
+						secret.lock_seen = true;
+						true
+					}
+					Some(secret) if secret.hash_lock != details.hash_lock => {
+						tracing::warn!(
+							"Transfer {} locked with another hash lock, its secret is kept",
+							details.bridge_transfer_id
+						);
+						false
+					}
+					_ => false,
+				}
+			}
+			BridgeContractEvent::CounterPartyCompleted(id, _)
+			| BridgeContractEvent::Refunded(id)
+			| BridgeContractEvent::Cancelled(id) => state.transfers.remove(id).is_some(),
+			BridgeContractEvent::InitiatorCompleted(_) => false,
+		};
+		if changed {
+			if let Err(err) = self.save(&state) {
+				tracing::error!(target: "bridge_alert", "Failed to store the secrets: {err}");
+			}
+			self.released.notify_waiters();
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bridge_util::types::{Amount, BridgeAddress, BridgeTransferDetails, LockDetails, TimeLock};
+	use std::time::Duration;
+
+	#[tokio::test]
+	async fn test_secret_manager() -> Result<(), anyhow::Error> {
+		let path = std::env::temp_dir().join(format!("secrets-{}.store", std::process::id()));
+		let cipher = FieldCipher::new([7; 32]);
+		let secrets =
+			SecretManager::open(path.clone(), cipher.clone(), HashLockAlgorithm::Sha2_256)?;
+		let id = BridgeTransferId([1; 32]);
+		let hash_lock = secrets.generate()?;
+		let discarded = secrets.generate()?;
+		secrets.discard(discarded);
+
+		secrets.observe(&BridgeContractEvent::Initiated(BridgeTransferDetails {
+			bridge_transfer_id: id,
+			initiator: BridgeAddress(vec![1; 20]),
+			recipient: BridgeAddress(vec![2; 32]),
+			hash_lock,
+			time_lock: TimeLock(100),
+			amount: Amount(10),
+			state: 0,
+		}));
+		let locked = |hash_lock| {
+			BridgeContractEvent::Locked(LockDetails {
+				bridge_transfer_id: id,
+				initiator: BridgeAddress(vec![1; 20]),
+				recipient: BridgeAddress(vec![2; 32]),
+				hash_lock,
+				time_lock: TimeLock(100),
+				amount: Amount(10),
+			})
+		};
+		// Not released before the lock with the same hash lock.
+		let release = tokio::time::timeout(Duration::from_millis(20), secrets.release(id));
+		assert!(release.await.is_err());
+		secrets.observe(&locked(HashLock([9; 32])));
+		let release = tokio::time::timeout(Duration::from_millis(20), secrets.release(id));
+		assert!(release.await.is_err());
+
+		let waiting = tokio::spawn({
+			let secrets = secrets.clone();
+			async move { secrets.release(id).await }
+		});
+		secrets.observe(&locked(hash_lock));
+		let pre_image = waiting.await?;
+		assert_eq!(hash_lock_of(&pre_image, HashLockAlgorithm::Sha2_256), hash_lock);
+
+		// Stored encrypted, and found again after a restart.
+		let stored = std::fs::read_to_string(&path)?;
+		assert!(!stored.contains(&hex::encode(pre_image.0)));
+		assert!(!stored.contains(&hex::encode(discarded.0)));
+		// The hash locks of another algorithm don't match the stored preimages.
+		assert!(SecretManager::open(path.clone(), cipher.clone(), HashLockAlgorithm::Keccak256)
+			.is_err());
+		let restarted = SecretManager::open(path.clone(), cipher, HashLockAlgorithm::Sha2_256)?;
+		assert_eq!(restarted.release(id).await, pre_image);
+		assert!(SecretManager::open(
+			path.clone(),
+			FieldCipher::new([8; 32]),
+			HashLockAlgorithm::Sha2_256
+		)
+		.is_err());
+
+		restarted.observe(&BridgeContractEvent::<Vec<u8>>::Refunded(id));
+		assert!(restarted.lock().transfers.is_empty());
+		assert!(SecretManager::open(
+			path.clone(),
+			FieldCipher::new([7; 32]),
+			HashLockAlgorithm::Sha2_256
+		)?
+		.lock()
+		.transfers
+		.is_empty());
+		std::fs::remove_file(path)?;
+		Ok(())
+	}
+}
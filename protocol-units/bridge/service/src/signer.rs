@@ -0,0 +1,696 @@
+use crate::chains::ethereum::types::{
+	AtomicBridgeCounterpartyMOVE, AtomicBridgeInitiatorMOVE, IMulticall, MockMOVEToken,
+};
+use crate::sweep::SweepPolicy;
+use alloy::consensus::SignableTransaction;
+use alloy::network::TxSigner;
+use alloy::primitives::{keccak256, Address, Bytes, Signature, TxKind, U256};
+use alloy::rlp::{Decodable, Header};
+use alloy::signers::{aws::AwsSigner, local::PrivateKeySigner, SignerSync};
+use alloy::sol_types::SolCall;
+use bridge_config::common::eth::EthConfig;
+use bridge_config::common::signer::SignerConfig;
+use bridge_config::Config;
+use serde::{Deserialize, Serialize};
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+
+/// Request sent to the signer, one JSON object per line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum SignerRequest {
+	/// Return the address of the signing key.
+	Address,
+	/// Sign an Ethereum transaction. The payload is the hex encoded transaction
+	/// as it is hashed for signing.
+	SignEthTransaction { payload: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignerResponse {
+	Address(String),
+	/// Hex encoded 65 bytes signature.
+	Signature(String),
+	Rejected(String),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RemoteSignerError {
+	#[error("Rejected by the signer: {0}")]
+	Rejected(String),
+	#[error("Invalid signer message: {0}")]
+	InvalidMessage(String),
+	#[error("Signer connection failed: {0}")]
+	Connection(#[from] std::io::Error),
+}
+
+impl From<serde_json::Error> for RemoteSignerError {
+	fn from(err: serde_json::Error) -> Self {
+		RemoteSignerError::InvalidMessage(err.to_string())
+	}
+}
+
+/// Kind of a contract the signer sends transactions to, each accepts its own calls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ContractKind {
+	Initiator,
+	Counterparty,
+	Token,
+}
+
+/// Transactions the signer accepts to sign: the calls of the relayer to the bridge and token
+/// contracts, of at most `max_amount`. The tokens are only approved to the bridge contracts
+/// and transferred to the cold storage address or the fee recipients.
+#[derive(Debug, Clone)]
+pub struct SigningPolicy {
+	/// Expected chain id, not verified if 0.
+	chain_id: u64,
+	max_amount: U256,
+	allowed_contracts: Vec<(Address, ContractKind)>,
+	token_recipients: Vec<Address>,
+}
+
+impl TryFrom<&Config> for SigningPolicy {
+	type Error = anyhow::Error;
+
+	fn try_from(config: &Config) -> Result<Self, Self::Error> {
+		let mut allowed_contracts = vec![
+			(config.eth.eth_initiator_contract.parse()?, ContractKind::Initiator),
+			(config.eth.eth_counterparty_contract.parse()?, ContractKind::Counterparty),
+			(config.eth.eth_move_token_contract.parse()?, ContractKind::Token),
+		];
+		for token in &config.tokens.erc20 {
+			allowed_contracts.push((token.initiator_contract.parse()?, ContractKind::Initiator));
+			allowed_contracts.push((token.token_contract.parse()?, ContractKind::Token));
+		}
+		if config.signer.max_amount == 0 {
+			anyhow::bail!("No signer max amount configured, set signer.max_amount");
+		}
+		// The cold storage address is only trusted once signed by the admin key.
+		let mut token_recipients: Vec<Address> = SweepPolicy::verified(&config.sweep)?
+			.and_then(|policy| policy.eth_cold_address)
+			.into_iter()
+			.collect();
+		for recipient in &config.fees.recipients {
+			token_recipients.push(recipient.eth_address.parse()?);
+		}
+		if !config.signer.allowed_contracts.is_empty() {
+			let allowed = config
+				.signer
+				.allowed_contracts
+				.iter()
+				.map(|contract| contract.parse())
+				.collect::<Result<Vec<Address>, _>>()?;
+			// The calls of the other contracts are unknown, they can't be checked.
+			if let Some(unknown) = allowed
+				.iter()
+				.find(|address| !allowed_contracts.iter().any(|(contract, _)| contract == *address))
+			{
+				anyhow::bail!("Allowed contract {unknown} is not a bridge or token contract");
+			}
+			allowed_contracts.retain(|(contract, _)| allowed.contains(contract));
+		}
+		Ok(SigningPolicy {
+			chain_id: config.eth.eth_chain_id,
+			max_amount: U256::from(config.signer.max_amount),
+			allowed_contracts,
+			token_recipients,
+		})
+	}
+}
+
+impl SigningPolicy {
+	fn check(&self, tx: &EthTxFields) -> Result<(), String> {
+		if self.chain_id != 0 && tx.chain_id != Some(self.chain_id) {
+			return Err(format!("chain id {:?} is not {}", tx.chain_id, self.chain_id));
+		}
+		let TxKind::Call(to) = tx.to else {
+			return Err("contract creation".to_string());
+		};
+		let Some(&(_, kind)) = self.allowed_contracts.iter().find(|(contract, _)| *contract == to)
+		else {
+			return Err(format!("contract {to} is not allowed"));
+		};
+		let amount = self.call_amount(kind, &tx.input)?;
+		if amount.max(tx.value) > self.max_amount {
+			return Err(format!("amount {} is above {}", amount.max(tx.value), self.max_amount));
+		}
+		Ok(())
+	}
+
+	// The amount moved by a call of the relayer to a contract of the kind, the other calls are
+	// rejected. The amount of a multicall is the total of its calls.
+	fn call_amount(&self, kind: ContractKind, input: &[u8]) -> Result<U256, String> {
+		let Some(selector) = input.get(..4) else {
+			return Err("no function selector".to_string());
+		};
+		let selector: [u8; 4] = selector.try_into().expect("Selector of 4 bytes");
+		match (kind, selector) {
+			(
+				ContractKind::Initiator,
+				AtomicBridgeInitiatorMOVE::initiateBridgeTransferCall::SELECTOR,
+			) => Ok(decode_call::<AtomicBridgeInitiatorMOVE::initiateBridgeTransferCall>(input)?
+				.moveAmount),
+			(
+				ContractKind::Initiator,
+				AtomicBridgeInitiatorMOVE::completeBridgeTransferCall::SELECTOR,
+			) => {
+				decode_call::<AtomicBridgeInitiatorMOVE::completeBridgeTransferCall>(input)?;
+				Ok(U256::ZERO)
+			}
+			(
+				ContractKind::Initiator,
+				AtomicBridgeInitiatorMOVE::refundBridgeTransferCall::SELECTOR,
+			) => {
+				decode_call::<AtomicBridgeInitiatorMOVE::refundBridgeTransferCall>(input)?;
+				Ok(U256::ZERO)
+			}
+			(
+				ContractKind::Counterparty,
+				AtomicBridgeCounterpartyMOVE::lockBridgeTransferCall::SELECTOR,
+			) => {
+				Ok(decode_call::<AtomicBridgeCounterpartyMOVE::lockBridgeTransferCall>(input)?
+					.amount)
+			}
+			(
+				ContractKind::Counterparty,
+				AtomicBridgeCounterpartyMOVE::completeBridgeTransferCall::SELECTOR,
+			) => {
+				decode_call::<AtomicBridgeCounterpartyMOVE::completeBridgeTransferCall>(input)?;
+				Ok(U256::ZERO)
+			}
+			(
+				ContractKind::Counterparty,
+				AtomicBridgeCounterpartyMOVE::abortBridgeTransferCall::SELECTOR,
+			) => {
+				decode_call::<AtomicBridgeCounterpartyMOVE::abortBridgeTransferCall>(input)?;
+				Ok(U256::ZERO)
+			}
+			(
+				ContractKind::Initiator | ContractKind::Counterparty,
+				IMulticall::tryMulticallCall::SELECTOR,
+			) => decode_call::<IMulticall::tryMulticallCall>(input)?.data.iter().try_fold(
+				U256::ZERO,
+				|total, call| {
+					if call.get(..4) == Some(&IMulticall::tryMulticallCall::SELECTOR[..]) {
+						return Err("nested multicall".to_string());
+					}
+					Ok(total.saturating_add(self.call_amount(kind, call)?))
+				},
+			),
+			(ContractKind::Token, MockMOVEToken::transferCall::SELECTOR) => {
+				let call = decode_call::<MockMOVEToken::transferCall>(input)?;
+				if !self.token_recipients.contains(&call.to) {
+					return Err(format!("transfer to {} is not allowed", call.to));
+				}
+				Ok(call.value)
+			}
+			(ContractKind::Token, MockMOVEToken::approveCall::SELECTOR) => {
+				let call = decode_call::<MockMOVEToken::approveCall>(input)?;
+				// Revoking an allowance is always allowed.
+				let bridge_contract = self.allowed_contracts.iter().any(|(contract, kind)| {
+					*contract == call.spender && *kind != ContractKind::Token
+				});
+				if !call.value.is_zero() && !bridge_contract {
+					return Err(format!("approval to {} is not allowed", call.spender));
+				}
+				Ok(call.value)
+			}
+			(kind, selector) => Err(format!(
+				"function 0x{} of the {kind:?} contract is not allowed",
+				hex::encode(selector)
+			)),
+		}
+	}
+}
+
+fn decode_call<C: SolCall>(input: &[u8]) -> Result<C, String> {
+	C::abi_decode(input, true).map_err(|err| format!("invalid {} call: {err}", C::SIGNATURE))
+}
+
+/// The fields of a transaction checked by the policy.
+#[derive(Debug)]
+pub(crate) struct EthTxFields {
+	pub(crate) chain_id: Option<u64>,
+	pub(crate) to: TxKind,
+	pub(crate) value: U256,
+	pub(crate) input: Bytes,
+}
+
+/// Decode the legacy, EIP-2930 and EIP-1559 transactions encoded for signing.
+pub(crate) fn decode_signing_payload(mut payload: &[u8]) -> Result<EthTxFields, alloy::rlp::Error> {
+	let ty = match payload.first() {
+		Some(&ty) if ty <= 0x7f => {
+			payload = &payload[1..];
+			ty
+		}
+		_ => 0,
+	};
+	let header = Header::decode(&mut payload)?;
+	if !header.list {
+		return Err(alloy::rlp::Error::UnexpectedString);
+	}
+	let buf = &mut payload;
+	// The fields before `to` are not checked.
+	let (chain_id, skipped) = match ty {
+		// nonce, gas_price, gas_limit
+		0 => (None, 3),
+		// chain_id, nonce, gas_price, gas_limit
+		1 => (Some(u64::decode(buf)?), 3),
+		// chain_id, nonce, max_priority_fee_per_gas, max_fee_per_gas, gas_limit
+		2 => (Some(u64::decode(buf)?), 4),
+		_ => return Err(alloy::rlp::Error::Custom("unsupported transaction type")),
+	};
+	for _ in 0..skipped {
+		U256::decode(buf)?;
+	}
+	let to = TxKind::decode(buf)?;
+	let value = U256::decode(buf)?;
+	let input = Bytes::decode(buf)?;
+	// EIP-155 legacy transactions end with the chain id.
+	let chain_id = match chain_id {
+		None if !buf.is_empty() => Some(u64::decode(buf)?),
+		chain_id => chain_id,
+	};
+	Ok(EthTxFields { chain_id, to, value, input })
+}
+
+/// Holds the Ethereum key of the relayer and signs the transactions allowed by the policy.
+/// Runs in its own process so the relayer doesn't have the key in its address space.
+pub struct SigningService {
+	key: PrivateKeySigner,
+	policy: SigningPolicy,
+}
+
+impl SigningService {
+	pub fn new(key: PrivateKeySigner, policy: SigningPolicy) -> Self {
+		SigningService { key, policy }
+	}
+
+	pub fn handle(&self, request: SignerRequest) -> SignerResponse {
+		match request {
+			SignerRequest::Address => SignerResponse::Address(self.key.address().to_string()),
+			SignerRequest::SignEthTransaction { payload } => {
+				match self.sign_eth_transaction(&payload) {
+					Ok(signature) => SignerResponse::Signature(signature),
+					Err(reason) => {
+						tracing::warn!(target: "bridge_audit", "Signing rejected: {reason}");
+						SignerResponse::Rejected(reason)
+					}
+				}
+			}
+		}
+	}
+
+	fn sign_eth_transaction(&self, payload: &str) -> Result<String, String> {
+		let payload = hex::decode(payload.strip_prefix("0x").unwrap_or(payload))
+			.map_err(|err| format!("invalid payload: {err}"))?;
+		let tx = decode_signing_payload(&payload)
+			.map_err(|err| format!("invalid transaction: {err}"))?;
+		self.policy.check(&tx)?;
+		let signature = self
+			.key
+			.sign_hash_sync(&keccak256(&payload))
+			.map_err(|err| format!("signing failed: {err}"))?;
+		tracing::info!(target: "bridge_audit", "Signed transaction to {:?}", tx.to);
+		Ok(hex::encode(signature.as_bytes()))
+	}
+
+	/// Serve the requests received on the Unix socket until the listener fails.
+	pub async fn serve(self, socket_path: &str) -> Result<(), anyhow::Error> {
+		// Remove the socket left by a previous run.
+		let _ = std::fs::remove_file(socket_path);
+		let listener = UnixListener::bind(socket_path)?;
+		// Only the user running the signer can connect.
+		std::fs::set_permissions(socket_path, std::fs::Permissions::from_mode(0o600))?;
+		tracing::info!("Signer of {} listening on {socket_path}", self.key.address());
+
+		let service = Arc::new(self);
+		loop {
+			let (stream, _) = listener.accept().await?;
+			let service = service.clone();
+			tokio::spawn(async move {
+				if let Err(err) = service.serve_connection(stream).await {
+					tracing::warn!("Signer connection closed: {err}");
+				}
+			});
+		}
+	}
+
+	async fn serve_connection(&self, stream: UnixStream) -> Result<(), RemoteSignerError> {
+		let (reader, mut writer) = stream.into_split();
+		let mut lines = BufReader::new(reader).lines();
+		while let Some(line) = lines.next_line().await? {
+			let response = match serde_json::from_str(&line) {
+				Ok(request) => self.handle(request),
+				Err(err) => SignerResponse::Rejected(format!("invalid request: {err}")),
+			};
+			let mut response = serde_json::to_string(&response)?;
+			response.push('\n');
+			writer.write_all(response.as_bytes()).await?;
+		}
+		Ok(())
+	}
+}
+
+// Where the remote signer listens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SignerEndpoint {
+	Unix(PathBuf),
+	Http(String),
+}
+
+impl SignerEndpoint {
+	// A `unix://` URL or a bare path is a Unix socket.
+	fn parse(url: &str) -> Self {
+		if url.starts_with("http://") || url.starts_with("https://") {
+			SignerEndpoint::Http(url.to_string())
+		} else {
+			SignerEndpoint::Unix(url.strip_prefix("unix://").unwrap_or(url).into())
+		}
+	}
+}
+
+/// Signs the relayer transactions with the signer listening on a Unix socket, or with a
+/// signing service speaking the same requests over HTTP, one request by POST.
+#[derive(Debug, Clone)]
+pub struct RemoteEthSigner {
+	endpoint: SignerEndpoint,
+	address: Address,
+	chain_id: Option<u64>,
+}
+
+impl RemoteEthSigner {
+	/// Connect to the signer at the URL or socket path and fetch the address of its key.
+	pub async fn connect(url: &str, chain_id: u64) -> Result<Self, RemoteSignerError> {
+		let mut signer = RemoteEthSigner {
+			endpoint: SignerEndpoint::parse(url),
+			address: Address::ZERO,
+			chain_id: (chain_id != 0).then_some(chain_id),
+		};
+		signer.address = match signer.request(&SignerRequest::Address).await? {
+			SignerResponse::Address(address) => address
+				.parse()
+				.map_err(|_| RemoteSignerError::InvalidMessage(format!("address {address}")))?,
+			response => {
+				return Err(RemoteSignerError::InvalidMessage(format!("{response:?}")));
+			}
+		};
+		Ok(signer)
+	}
+
+	async fn request(&self, request: &SignerRequest) -> Result<SignerResponse, RemoteSignerError> {
+		let response = match &self.endpoint {
+			SignerEndpoint::Unix(socket_path) => {
+				let stream = UnixStream::connect(socket_path).await?;
+				let (reader, mut writer) = stream.into_split();
+				let mut line = serde_json::to_string(request)?;
+				line.push('\n');
+				writer.write_all(line.as_bytes()).await?;
+
+				let mut response = String::new();
+				BufReader::new(reader).read_line(&mut response).await?;
+				response
+			}
+			SignerEndpoint::Http(url) => {
+				let http_error = |err: reqwest::Error| {
+					std::io::Error::new(std::io::ErrorKind::Other, err.to_string())
+				};
+				reqwest::Client::new()
+					.post(url)
+					.json(request)
+					.send()
+					.await
+					.and_then(|response| response.error_for_status())
+					.map_err(http_error)?
+					.text()
+					.await
+					.map_err(http_error)?
+			}
+		};
+		match serde_json::from_str(&response)? {
+			SignerResponse::Rejected(reason) => Err(RemoteSignerError::Rejected(reason)),
+			response => Ok(response),
+		}
+	}
+
+	async fn sign_payload(&self, payload: &[u8]) -> Result<Signature, RemoteSignerError> {
+		let request = SignerRequest::SignEthTransaction { payload: hex::encode(payload) };
+		match self.request(&request).await? {
+			SignerResponse::Signature(signature) => hex::decode(&signature)
+				.ok()
+				.and_then(|bytes| Signature::try_from(bytes.as_slice()).ok())
+				.ok_or_else(|| RemoteSignerError::InvalidMessage(format!("signature {signature}"))),
+			response => Err(RemoteSignerError::InvalidMessage(format!("{response:?}"))),
+		}
+	}
+}
+
+#[async_trait::async_trait]
+impl TxSigner<Signature> for RemoteEthSigner {
+	fn address(&self) -> Address {
+		self.address
+	}
+
+	async fn sign_transaction(
+		&self,
+		tx: &mut dyn SignableTransaction<Signature>,
+	) -> alloy::signers::Result<Signature> {
+		if let Some(chain_id) = self.chain_id {
+			if !tx.set_chain_id_checked(chain_id) {
+				return Err(alloy::signers::Error::TransactionChainIdMismatch {
+					signer: chain_id,
+					tx: tx.chain_id().unwrap_or_default(),
+				});
+			}
+		}
+		let eip155_chain_id = if tx.use_eip155() { self.chain_id.or(tx.chain_id()) } else { None };
+		let payload = tx.encoded_for_signing();
+		let signature = self.sign_payload(&payload).await.map_err(alloy::signers::Error::other)?;
+		Ok(match eip155_chain_id {
+			Some(chain_id) => signature.with_chain_id(chain_id),
+			None => signature,
+		})
+	}
+}
+
+/// Signer of the Ethereum transactions of the relayer, selected by the signer config.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignerBackend {
+	/// The private key of the Ethereum config.
+	Local,
+	/// A secp256k1 key of AWS KMS, the key never leaves KMS.
+	AwsKms { key_id: String },
+	/// The signing service at the URL.
+	Remote { url: String },
+}
+
+impl TryFrom<&SignerConfig> for SignerBackend {
+	type Error = anyhow::Error;
+
+	fn try_from(config: &SignerConfig) -> Result<Self, Self::Error> {
+		match config.backend.as_str() {
+			"local" if config.socket_path.is_empty() => Ok(SignerBackend::Local),
+			"local" => Ok(SignerBackend::Remote { url: config.socket_path.clone() }),
+			"aws_kms" if config.aws_kms_key_id.is_empty() => {
+				anyhow::bail!("The aws_kms signer backend needs the id of a KMS key")
+			}
+			"aws_kms" => Ok(SignerBackend::AwsKms { key_id: config.aws_kms_key_id.clone() }),
+			"remote" => match (config.url.as_str(), config.socket_path.as_str()) {
+				("", "") => anyhow::bail!("The remote signer backend needs the URL of a signer"),
+				("", socket_path) => Ok(SignerBackend::Remote { url: socket_path.to_string() }),
+				(url, _) => Ok(SignerBackend::Remote { url: url.to_string() }),
+			},
+			backend => anyhow::bail!("Unknown signer backend {backend}"),
+		}
+	}
+}
+
+impl SignerBackend {
+	/// Connect to the signer of the backend. Only the local backend reads the private key of
+	/// the Ethereum config.
+	pub async fn connect(&self, config: &EthConfig) -> Result<EthSigner, anyhow::Error> {
+		let chain_id = (config.eth_chain_id != 0).then_some(config.eth_chain_id);
+		let signer = match self {
+			SignerBackend::Local => EthSigner::Local(config.signer_private_key.parse()?),
+			SignerBackend::AwsKms { key_id } => {
+				let aws_config = aws_config::load_from_env().await;
+				let kms = aws_sdk_kms::Client::new(&aws_config);
+				EthSigner::AwsKms(AwsSigner::new(kms, key_id.clone(), chain_id).await?)
+			}
+			SignerBackend::Remote { url } => {
+				EthSigner::Remote(RemoteEthSigner::connect(url, config.eth_chain_id).await?)
+			}
+		};
+		tracing::info!(
+			"Ethereum transactions signed by {} with the {self:?} signer",
+			signer.address()
+		);
+		Ok(signer)
+	}
+}
+
+/// The signer of a backend.
+#[derive(Debug, Clone)]
+pub enum EthSigner {
+	Local(PrivateKeySigner),
+	AwsKms(AwsSigner),
+	Remote(RemoteEthSigner),
+}
+
+#[async_trait::async_trait]
+impl TxSigner<Signature> for EthSigner {
+	fn address(&self) -> Address {
+		match self {
+			EthSigner::Local(signer) => signer.address(),
+			EthSigner::AwsKms(signer) => TxSigner::address(signer),
+			EthSigner::Remote(signer) => signer.address(),
+		}
+	}
+
+	async fn sign_transaction(
+		&self,
+		tx: &mut dyn SignableTransaction<Signature>,
+	) -> alloy::signers::Result<Signature> {
+		match self {
+			EthSigner::Local(signer) => signer.sign_transaction(tx).await,
+			EthSigner::AwsKms(signer) => signer.sign_transaction(tx).await,
+			EthSigner::Remote(signer) => signer.sign_transaction(tx).await,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use alloy::consensus::TxEip1559;
+
+	#[test]
+	fn test_signing_policy() {
+		let counterparty = Address::repeat_byte(0xcc);
+		let token = Address::repeat_byte(0xee);
+		let policy = SigningPolicy {
+			chain_id: 31337,
+			max_amount: U256::from(1000),
+			allowed_contracts: vec![
+				(counterparty, ContractKind::Counterparty),
+				(token, ContractKind::Token),
+			],
+			token_recipients: vec![Address::repeat_byte(1)],
+		};
+		let lock = |amount: u64| {
+			AtomicBridgeCounterpartyMOVE::lockBridgeTransferCall {
+				originator: Default::default(),
+				bridgeTransferId: Default::default(),
+				hashLock: Default::default(),
+				recipient: Address::repeat_byte(1),
+				amount: U256::from(amount),
+			}
+			.abi_encode()
+		};
+		let payload = |chain_id: u64, to: Address, input: Vec<u8>| {
+			TxEip1559 { chain_id, to: TxKind::Call(to), input: input.into(), ..Default::default() }
+				.encoded_for_signing()
+		};
+		let check = |payload: Vec<u8>| policy.check(&decode_signing_payload(&payload).unwrap());
+
+		assert!(check(payload(31337, counterparty, lock(1000))).is_ok());
+		assert!(check(payload(31337, counterparty, lock(1001))).is_err());
+		assert!(check(payload(1, counterparty, lock(10))).is_err());
+		assert!(check(payload(31337, Address::repeat_byte(0xdd), lock(10))).is_err());
+
+		// The amounts of the batches add up.
+		let batch = |amounts: &[u64]| {
+			IMulticall::tryMulticallCall {
+				data: amounts.iter().map(|amount| lock(*amount).into()).collect(),
+			}
+			.abi_encode()
+		};
+		assert!(check(payload(31337, counterparty, batch(&[500, 500]))).is_ok());
+		assert!(check(payload(31337, counterparty, batch(&[500, 501]))).is_err());
+
+		// The token transfers and approvals are bounded too.
+		let transfer_to = |to: Address, value: u64| {
+			MockMOVEToken::transferCall { to, value: U256::from(value) }.abi_encode()
+		};
+		let transfer = |value: u64| transfer_to(Address::repeat_byte(1), value);
+		let approve_to = |spender: Address, value: U256| {
+			MockMOVEToken::approveCall { spender, value }.abi_encode()
+		};
+		let approve = |value: U256| approve_to(counterparty, value);
+		assert!(check(payload(31337, token, transfer(1000))).is_ok());
+		assert!(check(payload(31337, token, transfer(1001))).is_err());
+		assert!(check(payload(31337, token, approve(U256::from(1000)))).is_ok());
+		assert!(check(payload(31337, token, approve(U256::MAX))).is_err());
+
+		// The tokens only go to the configured recipients and bridge contracts.
+		let attacker = Address::repeat_byte(0xaa);
+		assert!(check(payload(31337, token, transfer_to(attacker, 10))).is_err());
+		assert!(check(payload(31337, token, approve_to(attacker, U256::from(10)))).is_err());
+		assert!(check(payload(31337, token, approve_to(token, U256::from(10)))).is_err());
+		// Any allowance can be revoked.
+		assert!(check(payload(31337, token, approve_to(attacker, U256::ZERO))).is_ok());
+
+		// The other functions of the contracts are rejected.
+		let set_initiator = AtomicBridgeCounterpartyMOVE::setAtomicBridgeInitiatorCall {
+			_atomicBridgeInitiator: Address::repeat_byte(2),
+		}
+		.abi_encode();
+		assert!(check(payload(31337, counterparty, set_initiator)).is_err());
+		assert!(check(payload(31337, token, lock(10))).is_err());
+		assert!(check(payload(31337, counterparty, transfer(10))).is_err());
+		assert!(check(payload(31337, counterparty, Vec::new())).is_err());
+	}
+
+	#[tokio::test]
+	async fn test_signer_backend() {
+		let backend = |backend: &str, aws_kms_key_id: &str, url: &str, socket_path: &str| {
+			SignerBackend::try_from(&SignerConfig {
+				backend: backend.to_string(),
+				aws_kms_key_id: aws_kms_key_id.to_string(),
+				url: url.to_string(),
+				socket_path: socket_path.to_string(),
+				..SignerConfig::default()
+			})
+		};
+		assert_eq!(backend("local", "", "", "").unwrap(), SignerBackend::Local);
+		// The socket path of the earlier configs selects the remote signer.
+		let remote = SignerBackend::Remote { url: "/run/signer.sock".to_string() };
+		assert_eq!(backend("local", "", "", "/run/signer.sock").unwrap(), remote);
+		assert_eq!(backend("remote", "", "", "/run/signer.sock").unwrap(), remote);
+		assert_eq!(
+			backend("remote", "", "https://signer:9000", "/run/signer.sock").unwrap(),
+			SignerBackend::Remote { url: "https://signer:9000".to_string() }
+		);
+		assert_eq!(
+			backend("aws_kms", "alias/relayer", "", "").unwrap(),
+			SignerBackend::AwsKms { key_id: "alias/relayer".to_string() }
+		);
+		assert!(backend("aws_kms", "", "", "").is_err());
+		assert!(backend("remote", "", "", "").is_err());
+		assert!(backend("hsm", "", "", "").is_err());
+
+		assert_eq!(
+			SignerEndpoint::parse("unix:///run/signer.sock"),
+			SignerEndpoint::Unix("/run/signer.sock".into())
+		);
+		assert_eq!(
+			SignerEndpoint::parse("/run/signer.sock"),
+			SignerEndpoint::Unix("/run/signer.sock".into())
+		);
+		assert_eq!(
+			SignerEndpoint::parse("http://signer:9000/sign"),
+			SignerEndpoint::Http("http://signer:9000/sign".to_string())
+		);
+
+		let key = PrivateKeySigner::random();
+		let eth_config =
+			EthConfig { signer_private_key: key.to_bytes().to_string(), ..EthConfig::default() };
+		let signer = SignerBackend::Local.connect(&eth_config).await.unwrap();
+		assert_eq!(signer.address(), key.address());
+	}
+}
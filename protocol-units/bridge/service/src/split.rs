@@ -0,0 +1,380 @@
+use alloy::primitives::keccak256;
+use bridge_config::common::split::SplitConfig;
+use bridge_util::actions::{TransferAction, TransferActionType};
+use bridge_util::types::{Amount, BridgeTransferId, HashLockPreImage};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// How the child HTLCs of a split transfer are locked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SplitMode {
+	/// Lock the next child once the previous one is locked.
+	Sequential,
+	/// Lock all the children at once.
+	Parallel,
+}
+
+/// When and how a transfer is split into child HTLCs.
+#[derive(Debug, Clone, Copy)]
+pub struct SplitPolicy {
+	pub threshold: u64,
+	pub child_count: u64,
+	pub mode: SplitMode,
+}
+
+impl From<&SplitConfig> for SplitPolicy {
+	fn from(config: &SplitConfig) -> Self {
+		SplitPolicy {
+			threshold: config.threshold,
+			child_count: config.child_count,
+			mode: if config.parallel { SplitMode::Parallel } else { SplitMode::Sequential },
+		}
+	}
+}
+
+impl Default for SplitPolicy {
+	fn default() -> Self {
+		SplitPolicy::from(&SplitConfig::default())
+	}
+}
+
+impl SplitPolicy {
+	pub fn applies(&self, amount: Amount) -> bool {
+		self.child_count > 1 && amount.0 > self.threshold && amount.0 >= self.child_count
+	}
+
+	/// The amounts of the children. The remainder of the division goes to the first child.
+	pub fn child_amounts(&self, amount: Amount) -> Vec<Amount> {
+		let share = amount.0 / self.child_count;
+		let mut amounts = vec![Amount(share); self.child_count as usize];
+		amounts[0].0 += amount.0 % self.child_count;
+		amounts
+	}
+}
+
+/// The id of a child HTLC, derived from the id of its parent transfer.
+pub fn child_transfer_id(parent: BridgeTransferId, index: u64) -> BridgeTransferId {
+	let mut data = parent.0.to_vec();
+	data.extend_from_slice(&index.to_be_bytes());
+	BridgeTransferId(keccak256(data).0)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChildStatus {
+	/// Waiting for the previous child to be locked.
+	Pending,
+	/// The lock has been sent.
+	Locking,
+	Locked,
+	Completed,
+	Aborted,
+}
+
+impl ChildStatus {
+	fn is_closed(self) -> bool {
+		matches!(self, ChildStatus::Completed | ChildStatus::Aborted)
+	}
+}
+
+struct Child {
+	action: TransferAction,
+	amount: Amount,
+	status: ChildStatus,
+}
+
+struct SplitTransfer {
+	mode: SplitMode,
+	children: Vec<Child>,
+	// The secret revealed by the first completed child.
+	secret: Option<HashLockPreImage>,
+}
+
+/// Status of a split transfer, as reported on the REST API.
+#[derive(Debug, Clone, Serialize)]
+pub struct SplitTransferStatus {
+	/// Hex encoded id of the user transfer.
+	pub transfer_id: String,
+	pub mode: SplitMode,
+	pub children: Vec<ChildTransferStatus>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChildTransferStatus {
+	/// Hex encoded id of the child HTLC.
+	pub transfer_id: String,
+	pub amount: u64,
+	pub status: ChildStatus,
+}
+
+/// Result of a child lock for its parent transfer.
+#[derive(Debug)]
+pub enum ChildLocked {
+	/// The lock of the next child must be sent.
+	Next(TransferAction),
+	/// Other children are still being locked.
+	Waiting,
+	/// All the children are locked.
+	AllLocked,
+}
+
+#[derive(Default)]
+struct SplitState {
+	parents: HashMap<BridgeTransferId, SplitTransfer>,
+	child_to_parent: HashMap<BridgeTransferId, BridgeTransferId>,
+}
+
+/// Parent records of the split transfers. A split transfer keeps the id of the user transfer
+/// on the init chain, and is locked on the counterparty chain as several child HTLCs
+/// sharing its hash lock, so one secret completes them all.
+/// Clones share the same records.
+#[derive(Clone, Default)]
+pub struct SplitTransfers {
+	policy: SplitPolicy,
+	state: Arc<Mutex<SplitState>>,
+}
+
+impl SplitTransfers {
+	pub fn new(policy: SplitPolicy) -> Self {
+		SplitTransfers { policy, state: Arc::default() }
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<'_, SplitState> {
+		self.state.lock().expect("Split transfers lock poisoned")
+	}
+
+	/// Split the lock action of a transfer if the policy applies to its amount,
+	/// and return the child locks to send now.
+	pub fn split(&self, action: &TransferAction) -> Option<Vec<TransferAction>> {
+		let TransferActionType::LockBridgeTransfer {
+			bridge_transfer_id,
+			hash_lock,
+			initiator,
+			recipient,
+			amount,
+		} = &action.kind
+		else {
+			return None;
+		};
+		if !self.policy.applies(*amount) {
+			return None;
+		}
+
+		let parent = *bridge_transfer_id;
+		let children: Vec<Child> = self
+			.policy
+			.child_amounts(*amount)
+			.into_iter()
+			.enumerate()
+			.map(|(index, amount)| {
+				let child_id = child_transfer_id(parent, index as u64);
+				let kind = TransferActionType::LockBridgeTransfer {
+					bridge_transfer_id: child_id,
+					hash_lock: *hash_lock,
+					initiator: initiator.clone(),
+					recipient: recipient.clone(),
+					amount,
+				};
+				let action = TransferAction { chain: action.chain, transfer_id: child_id, kind };
+				Child { action, amount, status: ChildStatus::Pending }
+			})
+			.collect();
+		tracing::info!("Split transfer {parent} of {} into {} children", amount.0, children.len());
+
+		let mut split = SplitTransfer { mode: self.policy.mode, children, secret: None };
+		let to_send = match split.mode {
+			SplitMode::Sequential => 1,
+			SplitMode::Parallel => split.children.len(),
+		};
+		let actions = split.children[..to_send]
+			.iter_mut()
+			.map(|child| {
+				child.status = ChildStatus::Locking;
+				child.action.clone()
+			})
+			.collect();
+
+		let mut state = self.lock();
+		for child in &split.children {
+			state.child_to_parent.insert(child.action.transfer_id, parent);
+		}
+		state.parents.insert(parent, split);
+		Some(actions)
+	}
+
+	pub fn parent_of(&self, child: &BridgeTransferId) -> Option<BridgeTransferId> {
+		self.lock().child_to_parent.get(child).copied()
+	}
+
+	pub fn child_locked(&self, child: BridgeTransferId) -> ChildLocked {
+		let mut state = self.lock();
+		let Some(split) = parent_mut(&mut state, child) else {
+			return ChildLocked::Waiting;
+		};
+		if let Some(locked) = split.children.iter_mut().find(|c| c.action.transfer_id == child) {
+			locked.status = ChildStatus::Locked;
+		}
+		if let Some(next) = split.children.iter_mut().find(|c| c.status == ChildStatus::Pending) {
+			next.status = ChildStatus::Locking;
+			return ChildLocked::Next(next.action.clone());
+		}
+		if split.children.iter().all(|c| c.status != ChildStatus::Locking) {
+			ChildLocked::AllLocked
+		} else {
+			ChildLocked::Waiting
+		}
+	}
+
+	/// Record a completed child and return the secret if it is the first one revealed.
+	pub fn child_completed(
+		&self,
+		child: BridgeTransferId,
+		secret: HashLockPreImage,
+	) -> Option<HashLockPreImage> {
+		let mut state = self.lock();
+		let split = parent_mut(&mut state, child)?;
+		set_status(split, child, ChildStatus::Completed);
+		let first = split.secret.is_none();
+		split.secret.get_or_insert(secret);
+		let secret = first.then_some(secret);
+		forget_if_closed(&mut state, child);
+		secret
+	}
+
+	/// Record an aborted child and return true if all the children are aborted.
+	pub fn child_aborted(&self, child: BridgeTransferId) -> bool {
+		let mut state = self.lock();
+		let Some(split) = parent_mut(&mut state, child) else {
+			return false;
+		};
+		set_status(split, child, ChildStatus::Aborted);
+		let all_aborted = split.children.iter().all(|c| c.status == ChildStatus::Aborted);
+		forget_if_closed(&mut state, child);
+		all_aborted
+	}
+
+	/// The secret revealed by a child of the transfer, if any.
+	pub fn secret(&self, parent: &BridgeTransferId) -> Option<HashLockPreImage> {
+		self.lock().parents.get(parent).and_then(|split| split.secret)
+	}
+
+	/// True if at least one child of the transfer is locked on chain.
+	pub fn any_locked(&self, parent: &BridgeTransferId) -> bool {
+		self.lock().parents.get(parent).is_some_and(|split| {
+			split
+				.children
+				.iter()
+				.any(|c| c.status == ChildStatus::Locked || c.status.is_closed())
+		})
+	}
+
+	pub fn status(&self, parent: &BridgeTransferId) -> Option<SplitTransferStatus> {
+		let state = self.lock();
+		let split = state.parents.get(parent)?;
+		Some(SplitTransferStatus {
+			transfer_id: hex::encode(parent.0),
+			mode: split.mode,
+			children: split
+				.children
+				.iter()
+				.map(|child| ChildTransferStatus {
+					transfer_id: hex::encode(child.action.transfer_id.0),
+					amount: child.amount.0,
+					status: child.status,
+				})
+				.collect(),
+		})
+	}
+}
+
+fn parent_mut(state: &mut SplitState, child: BridgeTransferId) -> Option<&mut SplitTransfer> {
+	let parent = state.child_to_parent.get(&child)?;
+	state.parents.get_mut(parent)
+}
+
+fn set_status(split: &mut SplitTransfer, child: BridgeTransferId, status: ChildStatus) {
+	if let Some(child) = split.children.iter_mut().find(|c| c.action.transfer_id == child) {
+		child.status = status;
+	}
+}
+
+// Drop the record of a split transfer once all its children are closed.
+fn forget_if_closed(state: &mut SplitState, child: BridgeTransferId) {
+	let Some(parent) = state.child_to_parent.get(&child).copied() else {
+		return;
+	};
+	let closed = state
+		.parents
+		.get(&parent)
+		.is_some_and(|split| split.children.iter().all(|c| c.status.is_closed()));
+	if closed {
+		if let Some(split) = state.parents.remove(&parent) {
+			for child in split.children {
+				state.child_to_parent.remove(&child.action.transfer_id);
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bridge_util::types::{BridgeAddress, ChainId, HashLock};
+
+	fn lock_action(amount: u64) -> TransferAction {
+		let transfer_id = BridgeTransferId([1; 32]);
+		TransferAction {
+			chain: ChainId::TWO,
+			transfer_id,
+			kind: TransferActionType::LockBridgeTransfer {
+				bridge_transfer_id: transfer_id,
+				hash_lock: HashLock([2; 32]),
+				initiator: BridgeAddress(vec![3; 20]),
+				recipient: BridgeAddress(vec![4; 32]),
+				amount: Amount(amount),
+			},
+		}
+	}
+
+	#[test]
+	fn test_sequential_split() {
+		let policy = SplitPolicy { threshold: 100, child_count: 3, mode: SplitMode::Sequential };
+		assert_eq!(policy.child_amounts(Amount(1000)), vec![Amount(334), Amount(333), Amount(333)]);
+
+		let splits = SplitTransfers::new(policy);
+		assert!(splits.split(&lock_action(100)).is_none());
+
+		let parent = BridgeTransferId([1; 32]);
+		let actions = splits.split(&lock_action(1000)).unwrap();
+		assert_eq!(actions.len(), 1);
+		let first = actions[0].transfer_id;
+		assert_eq!(first, child_transfer_id(parent, 0));
+		assert_eq!(splits.parent_of(&first), Some(parent));
+
+		let ChildLocked::Next(second) = splits.child_locked(first) else { panic!() };
+		let ChildLocked::Next(third) = splits.child_locked(second.transfer_id) else { panic!() };
+		assert!(splits.any_locked(&parent));
+		assert!(matches!(splits.child_locked(third.transfer_id), ChildLocked::AllLocked));
+
+		let secret = HashLockPreImage([5; 32]);
+		assert_eq!(splits.child_completed(first, secret), Some(secret));
+		assert_eq!(splits.child_completed(second.transfer_id, secret), None);
+		assert_eq!(splits.secret(&parent), Some(secret));
+		assert!(!splits.child_aborted(third.transfer_id));
+		// All children are closed, the record is dropped.
+		assert!(splits.status(&parent).is_none());
+		assert!(splits.parent_of(&first).is_none());
+	}
+
+	#[test]
+	fn test_parallel_split() {
+		let policy = SplitPolicy { threshold: 100, child_count: 2, mode: SplitMode::Parallel };
+		let splits = SplitTransfers::new(policy);
+		let actions = splits.split(&lock_action(1000)).unwrap();
+		assert_eq!(actions.len(), 2);
+		assert!(matches!(splits.child_locked(actions[0].transfer_id), ChildLocked::Waiting));
+		assert!(matches!(splits.child_locked(actions[1].transfer_id), ChildLocked::AllLocked));
+	}
+}
@@ -0,0 +1,200 @@
+//! Checks of the endpoints of the config before the relayer starts. All the endpoints are
+//! checked concurrently and reported at once, instead of failing on the first one later
+//! in the start.
+use bridge_config::Config;
+use std::fmt;
+use std::future::Future;
+use std::time::Duration;
+use url::Url;
+
+/// Result of the check of an endpoint: its details or the reason it failed.
+#[derive(Debug, Clone)]
+pub struct EndpointCheck {
+	pub endpoint: &'static str,
+	pub url: String,
+	pub result: Result<String, String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct StartupReport {
+	pub checks: Vec<EndpointCheck>,
+}
+
+impl StartupReport {
+	/// Check the endpoints of the config: their host resolves, the TLS certificate of the
+	/// secure ones is valid, and the chains they serve have the configured chain ids.
+	pub async fn check(config: &Config, timeout: Duration) -> Self {
+		// Requests over https verify the TLS certificate of the endpoint.
+		let http = reqwest::Client::builder().timeout(timeout).build().unwrap_or_default();
+		let eth_rpc_url = config.eth.eth_rpc_connection_url();
+		let eth_ws_url = config.eth.eth_ws_connection_url();
+		let mvt_rpc_url = config.movement.mvt_rpc_connection_url();
+		let (eth_rpc, eth_ws, mvt_rpc) = futures::join!(
+			check_endpoint(
+				"Ethereum RPC",
+				&eth_rpc_url,
+				timeout,
+				eth_chain_id(&http, &eth_rpc_url, config.eth.eth_chain_id),
+			),
+			check_endpoint("Ethereum WS", &eth_ws_url, timeout, ws_tls(&http, &eth_ws_url)),
+			check_endpoint(
+				"Movement REST",
+				&mvt_rpc_url,
+				timeout,
+				movement_chain_id(&http, &mvt_rpc_url, config.movement.mvt_chain_id),
+			),
+		);
+		StartupReport { checks: vec![eth_rpc, eth_ws, mvt_rpc] }
+	}
+
+	pub fn is_ok(&self) -> bool {
+		self.checks.iter().all(|check| check.result.is_ok())
+	}
+}
+
+impl fmt::Display for StartupReport {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "Startup report:")?;
+		for check in &self.checks {
+			match &check.result {
+				Ok(details) => write!(f, "\n  [ok] {} {}: {details}", check.endpoint, check.url)?,
+				Err(err) => write!(f, "\n  [failed] {} {}: {err}", check.endpoint, check.url)?,
+			}
+		}
+		Ok(())
+	}
+}
+
+// Resolve the host of the url, then run the check of the endpoint, within the timeout.
+async fn check_endpoint(
+	endpoint: &'static str,
+	url: &str,
+	timeout: Duration,
+	check: impl Future<Output = Result<String, anyhow::Error>>,
+) -> EndpointCheck {
+	let checked = async {
+		resolve(url).await?;
+		check.await
+	};
+	let result = match tokio::time::timeout(timeout, checked).await {
+		Ok(result) => result.map_err(|err| format!("{err:#}")),
+		Err(_) => Err(format!("no answer after {timeout:?}")),
+	};
+	EndpointCheck { endpoint, url: url.to_string(), result }
+}
+
+async fn resolve(url: &str) -> Result<(), anyhow::Error> {
+	let url = Url::parse(url)?;
+	let host = url.host_str().ok_or_else(|| anyhow::anyhow!("Url without host"))?;
+	let port = url.port_or_known_default().unwrap_or(80);
+	let mut addresses = tokio::net::lookup_host((host, port))
+		.await
+		.map_err(|err| anyhow::anyhow!("DNS resolution of {host} failed: {err}"))?;
+	if addresses.next().is_none() {
+		anyhow::bail!("DNS resolution of {host} returned no address");
+	}
+	Ok(())
+}
+
+// The reqwest errors only display their context, the cause is in their sources.
+fn describe(err: reqwest::Error) -> anyhow::Error {
+	let mut description = err.to_string();
+	let mut source = std::error::Error::source(&err);
+	while let Some(cause) = source {
+		description.push_str(&format!(": {cause}"));
+		source = cause.source();
+	}
+	anyhow::anyhow!(description)
+}
+
+async fn eth_chain_id(
+	http: &reqwest::Client,
+	url: &str,
+	expected: u64,
+) -> Result<String, anyhow::Error> {
+	let request = serde_json::json!({
+		"jsonrpc": "2.0",
+		"id": 1,
+		"method": "eth_chainId",
+		"params": [],
+	});
+	let response: serde_json::Value =
+		http.post(url).json(&request).send().await.map_err(describe)?.json().await?;
+	let chain_id = response["result"]
+		.as_str()
+		.and_then(|chain_id| u64::from_str_radix(chain_id.trim_start_matches("0x"), 16).ok())
+		.ok_or_else(|| anyhow::anyhow!("Invalid eth_chainId response: {response}"))?;
+	if expected != 0 && chain_id != expected {
+		anyhow::bail!("chain id {chain_id} differs from configured chain id {expected}");
+	}
+	Ok(format!("chain id {chain_id}"))
+}
+
+// The WS connection is established by the event monitoring, only the TLS certificate
+// of a secure endpoint is verified here.
+async fn ws_tls(http: &reqwest::Client, url: &str) -> Result<String, anyhow::Error> {
+	let url = Url::parse(url)?;
+	if url.scheme() != "wss" {
+		return Ok("resolved".to_string());
+	}
+	let mut https_url = url.clone();
+	https_url.set_scheme("https").map_err(|_| anyhow::anyhow!("Invalid wss url"))?;
+	// Any HTTP answer, even an error status, means the TLS handshake succeeded.
+	http.get(https_url).send().await.map_err(describe)?;
+	Ok("resolved, TLS certificate verified".to_string())
+}
+
+async fn movement_chain_id(
+	http: &reqwest::Client,
+	url: &str,
+	expected: u8,
+) -> Result<String, anyhow::Error> {
+	let ledger: serde_json::Value = http
+		.get(format!("{}/v1", url.trim_end_matches('/')))
+		.send()
+		.await
+		.map_err(describe)?
+		.json()
+		.await?;
+	let chain_id = ledger["chain_id"]
+		.as_u64()
+		.ok_or_else(|| anyhow::anyhow!("Invalid ledger information: {ledger}"))?;
+	if expected != 0 && chain_id != u64::from(expected) {
+		anyhow::bail!("chain id {chain_id} differs from configured chain id {expected}");
+	}
+	Ok(format!("chain id {chain_id}"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn test_startup_report() {
+		let timeout = Duration::from_secs(5);
+		let resolved =
+			check_endpoint("Local", "http://localhost:1", timeout, async { Ok("up".to_string()) })
+				.await;
+		assert_eq!(resolved.result, Ok("up".to_string()));
+
+		// The check of an endpoint whose host doesn't resolve isn't run.
+		let unresolved = check_endpoint("Unknown", "http://relayer.invalid:1", timeout, async {
+			panic!("check run on an unresolved host")
+		})
+		.await;
+		assert!(unresolved.result.as_ref().unwrap_err().contains("DNS resolution"));
+
+		let slow = check_endpoint("Slow", "http://localhost:1", Duration::from_millis(10), async {
+			tokio::time::sleep(Duration::from_secs(1)).await;
+			Ok("up".to_string())
+		})
+		.await;
+		assert!(slow.result.as_ref().unwrap_err().starts_with("no answer"));
+
+		let report = StartupReport { checks: vec![resolved, unresolved] };
+		assert!(!report.is_ok());
+		let report = report.to_string();
+		assert!(report.contains("[ok] Local http://localhost:1: up"));
+		assert!(report.contains("[failed] Unknown http://relayer.invalid:1: DNS resolution"));
+	}
+}
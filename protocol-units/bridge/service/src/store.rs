@@ -0,0 +1,270 @@
+//! Last state of each transfer, written by the runtime on every change so a restarted relayer
+//! resumes the transfers in flight. The store is embedded in the relayer by default, or in the
+//! Postgres database of the indexer to be shared by the relayer instances.
+use crate::in_flight::InFlightTransfer;
+use anyhow::Context;
+use bigdecimal::{BigDecimal, ToPrimitive};
+use bridge_config::common::transfer_store::TransferStoreConfig;
+use bridge_indexer_db::models::TransferStateRow;
+use bridge_indexer_db::transfer_store::{PgTransferStore, Transition};
+use bridge_util::states::TransferState;
+use bridge_util::types::{
+	Amount, BridgeAddress, BridgeTransferDetails, BridgeTransferId, HashLock, HashLockPreImage,
+	TimeLock,
+};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Store of the last state of the transfers. A change is only written if it's later than the
+/// stored one, so the changes of concurrent relayers or replayed events never go backwards.
+pub trait TransferStore: Send + Sync {
+	/// Write the transfer, false if the store has the same or a later change of it.
+	fn transition(&self, transfer: &InFlightTransfer) -> Result<bool, anyhow::Error>;
+
+	/// The transfers not done, the oldest update first.
+	fn in_flight(&self) -> Result<Vec<InFlightTransfer>, anyhow::Error>;
+
+	fn get(&self, transfer_id: BridgeTransferId)
+		-> Result<Option<InFlightTransfer>, anyhow::Error>;
+}
+
+/// Build the store of the config.
+pub fn from_config(config: &TransferStoreConfig) -> Result<Arc<dyn TransferStore>, anyhow::Error> {
+	match config.backend.as_str() {
+		"memory" => Ok(Arc::new(MemoryTransferStore::default())),
+		"postgres" => Ok(Arc::new(PgTransferStore::from_env(config.pool_size)?)),
+		backend => anyhow::bail!("Unknown transfer store backend {backend}"),
+	}
+}
+
+/// Store in the memory of the relayer, lost when it stops. The done transfers are dropped.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryTransferStore {
+	transfers: Arc<Mutex<HashMap<BridgeTransferId, InFlightTransfer>>>,
+}
+
+impl MemoryTransferStore {
+	fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<BridgeTransferId, InFlightTransfer>> {
+		self.transfers.lock().expect("Transfer store lock poisoned")
+	}
+}
+
+impl TransferStore for MemoryTransferStore {
+	fn transition(&self, transfer: &InFlightTransfer) -> Result<bool, anyhow::Error> {
+		let mut transfers = self.lock();
+		if transfers
+			.get(&transfer.transfer_id)
+			.is_some_and(|stored| stored.sequence >= transfer.sequence)
+		{
+			return Ok(false);
+		}
+		if transfer.state.is_some_and(|state| state.is_final()) {
+			transfers.remove(&transfer.transfer_id);
+		} else {
+			transfers.insert(transfer.transfer_id, transfer.clone());
+		}
+		Ok(true)
+	}
+
+	fn in_flight(&self) -> Result<Vec<InFlightTransfer>, anyhow::Error> {
+		let mut transfers: Vec<InFlightTransfer> = self.lock().values().cloned().collect();
+		transfers.sort_by_key(|transfer| (transfer.updated_at_secs, transfer.transfer_id.0));
+		Ok(transfers)
+	}
+
+	fn get(
+		&self,
+		transfer_id: BridgeTransferId,
+	) -> Result<Option<InFlightTransfer>, anyhow::Error> {
+		Ok(self.lock().get(&transfer_id).cloned())
+	}
+}
+
+impl TransferStore for PgTransferStore {
+	fn transition(&self, transfer: &InFlightTransfer) -> Result<bool, anyhow::Error> {
+		let transition = PgTransferStore::transition(self, &to_row(transfer)?)?;
+		Ok(transition == Transition::Applied)
+	}
+
+	fn in_flight(&self) -> Result<Vec<InFlightTransfer>, anyhow::Error> {
+		PgTransferStore::in_flight(self)?.iter().map(from_row).collect()
+	}
+
+	fn get(
+		&self,
+		transfer_id: BridgeTransferId,
+	) -> Result<Option<InFlightTransfer>, anyhow::Error> {
+		PgTransferStore::get(self, &hex::encode(transfer_id.0))?
+			.as_ref()
+			.map(from_row)
+			.transpose()
+	}
+}
+
+fn to_row(transfer: &InFlightTransfer) -> Result<TransferStateRow, anyhow::Error> {
+	let updated_at = i64::try_from(transfer.updated_at_secs)
+		.ok()
+		.and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+		.with_context(|| format!("Invalid update time {}", transfer.updated_at_secs))?;
+	Ok(TransferStateRow {
+		bridge_transfer_id: hex::encode(transfer.transfer_id.0),
+		init_chain: transfer.init_chain.to_string(),
+		state: transfer.state.map(|state| state.to_string()),
+		sequence: i64::try_from(transfer.sequence)?,
+		initiator: hex::encode(&transfer.initiator),
+		recipient: hex::encode(&transfer.recipient),
+		hash_lock: hex::encode(transfer.hash_lock.0),
+		time_lock: i64::try_from(transfer.time_lock)?,
+		amount: BigDecimal::from(transfer.amount),
+		updated_at: updated_at.naive_utc(),
+		pre_image: transfer.pre_image.map(|pre_image| hex::encode(pre_image.0)),
+	})
+}
+
+fn from_row(row: &TransferStateRow) -> Result<InFlightTransfer, anyhow::Error> {
+	Ok(InFlightTransfer {
+		transfer_id: BridgeTransferId::parse(&row.bridge_transfer_id)?,
+		init_chain: row.init_chain.parse().map_err(anyhow::Error::msg)?,
+		state: row.state.as_deref().map(str::parse).transpose().map_err(anyhow::Error::msg)?,
+		initiator: hex::decode(&row.initiator)?,
+		recipient: hex::decode(&row.recipient)?,
+		hash_lock: HashLock::parse(&row.hash_lock)?,
+		time_lock: u64::try_from(row.time_lock)?,
+		amount: row.amount.to_u64().with_context(|| format!("Invalid amount {}", row.amount))?,
+		sequence: u64::try_from(row.sequence)?,
+		updated_at_secs: u64::try_from(row.updated_at.and_utc().timestamp())?,
+		pre_image: row.pre_image.as_deref().map(HashLockPreImage::parse).transpose()?,
+	})
+}
+
+/// The transfer to store after a change of state of the runtime.
+pub fn stored_transfer(state: &TransferState, sequence: u64, now_secs: u64) -> InFlightTransfer {
+	InFlightTransfer {
+		transfer_id: state.transfer_id,
+		init_chain: state.init_chain,
+		state: Some(state.state_type()),
+		initiator: BridgeAddress::<Vec<u8>>::from(state.intiator_address.clone()).0,
+		recipient: BridgeAddress::<Vec<u8>>::from(state.counter_part_address.clone()).0,
+		hash_lock: state.hash_lock,
+		time_lock: state.time_lock.0,
+		amount: state.amount.0,
+		sequence,
+		updated_at_secs: now_secs,
+		pre_image: state.pre_image,
+	}
+}
+
+/// The state of a stored transfer, to resume it in the runtime. None for the transfers over or
+/// without a change of state.
+pub fn restored_state(transfer: &InFlightTransfer) -> Option<TransferState> {
+	let state = transfer.state.filter(|state| !state.is_final())?;
+	let details = BridgeTransferDetails {
+		bridge_transfer_id: transfer.transfer_id,
+		initiator: BridgeAddress(transfer.initiator.clone()),
+		recipient: BridgeAddress(transfer.recipient.clone()),
+		hash_lock: transfer.hash_lock,
+		time_lock: TimeLock(transfer.time_lock),
+		amount: Amount(transfer.amount),
+		// The state of the contract is only checked in stateless mode, read on chain.
+		state: 0,
+	};
+	let mut state = TransferState::from_initiator_details(transfer.init_chain, details, state);
+	state.pre_image = transfer.pre_image;
+	Some(state)
+}
+
+pub(crate) fn now_secs() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|d| d.as_secs())
+		.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bridge_util::states::TransferStateType;
+	use bridge_util::types::ChainId;
+
+	#[test]
+	fn test_transfer_store() -> Result<(), anyhow::Error> {
+		let store = MemoryTransferStore::default();
+		let id = |byte: u8| BridgeTransferId([byte; 32]);
+		let details = |byte: u8| BridgeTransferDetails {
+			bridge_transfer_id: id(byte),
+			initiator: BridgeAddress(vec![1; 20]),
+			recipient: BridgeAddress(vec![2; 32]),
+			hash_lock: HashLock([3; 32]),
+			time_lock: TimeLock(200),
+			amount: Amount(10),
+			state: 1,
+		};
+		let state = |byte: u8, state| {
+			TransferState::from_initiator_details(ChainId::ONE, details(byte), state)
+		};
+
+		let locked = state(1, TransferStateType::Locked);
+		assert!(store.transition(&stored_transfer(&locked, 2, 102))?);
+		assert!(store.transition(&stored_transfer(
+			&state(2, TransferStateType::Initialized),
+			1,
+			103
+		))?);
+		let transfer = store.get(id(1))?.unwrap();
+		assert_eq!(transfer.state, Some(TransferStateType::Locked));
+		assert_eq!((transfer.sequence, transfer.updated_at_secs), (2, 102));
+		// An older change written by another relayer is refused.
+		assert!(!store.transition(&InFlightTransfer { sequence: 1, ..transfer.clone() })?);
+
+		// The secret revealed on the counterparty chain is stored with the transfer.
+		let (completable, _) = match locked {
+			TransferState::Locked(transfer) => {
+				transfer.counterpart_completed(HashLockPreImage([4; 32]))
+			}
+			_ => unreachable!(),
+		};
+		assert!(store.transition(&stored_transfer(&completable.into(), 3, 104))?);
+
+		// A restarted relayer resumes the transfers in their state.
+		let restored = store.in_flight()?;
+		assert_eq!(restored.iter().map(|t| t.transfer_id).collect::<Vec<_>>(), [id(2), id(1)]);
+		let restored: Vec<_> = restored.iter().filter_map(restored_state).collect();
+		assert_eq!(restored[0].state_type(), TransferStateType::Initialized);
+		assert_eq!(restored[1].state_type(), TransferStateType::SecretReceived);
+		assert_eq!(restored[1].pre_image, Some(HashLockPreImage([4; 32])));
+		assert_eq!((restored[1].amount, restored[1].time_lock), (Amount(10), TimeLock(200)));
+
+		let done = stored_transfer(&state(1, TransferStateType::Completed), 4, 105);
+		assert_eq!(restored_state(&done).map(|state| state.state_type()), None);
+		assert!(store.transition(&done)?);
+		assert_eq!(store.get(id(1))?, None);
+		assert_eq!(store.in_flight()?.len(), 1);
+		Ok(())
+	}
+
+	#[test]
+	fn test_transfer_row() -> Result<(), anyhow::Error> {
+		let transfer = InFlightTransfer {
+			transfer_id: BridgeTransferId([1; 32]),
+			init_chain: ChainId::TWO,
+			state: Some(TransferStateType::LockAborted),
+			initiator: vec![1; 32],
+			recipient: vec![2; 20],
+			hash_lock: HashLock([3; 32]),
+			time_lock: 200,
+			amount: u64::MAX,
+			sequence: 4,
+			updated_at_secs: 1_700_000_000,
+			pre_image: Some(HashLockPreImage([4; 32])),
+		};
+		let row = to_row(&transfer)?;
+		assert_eq!(row.bridge_transfer_id, hex::encode([1; 32]));
+		assert_eq!(row.state.as_deref(), Some("LockAborted"));
+		assert_eq!(from_row(&row)?, transfer);
+		assert_eq!(row.pre_image, Some(hex::encode([4; 32])));
+		assert_eq!(from_row(&TransferStateRow { pre_image: None, ..row.clone() })?.pre_image, None);
+		assert_eq!(from_row(&TransferStateRow { state: None, ..row })?.state, None);
+		Ok(())
+	}
+}
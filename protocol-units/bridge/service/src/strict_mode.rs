@@ -0,0 +1,123 @@
+//! Strict mode of the relayer, for the mainnet deployments: the unsafe fallbacks of the config
+//! are refused before the relayer starts, and its soft warnings become failures.
+use bridge_config::Config;
+use std::fmt;
+
+/// Unsafe fallback of the config, refused in strict mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StrictModeViolation {
+	/// The initiations on Ethereum are handled without confirmations, a reorg can drop them.
+	NoConfirmations,
+	/// The Ethereum blocks are polled over the RPC while a WS endpoint is configured.
+	PollingFallback,
+	/// The Ethereum key of the relayer is stored in clear in the config or its environment.
+	UnencryptedKey,
+}
+
+impl fmt::Display for StrictModeViolation {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			StrictModeViolation::NoConfirmations => {
+				write!(f, "no confirmations of the Ethereum initiations, set eth.confirmations")
+			}
+			StrictModeViolation::PollingFallback => write!(
+				f,
+				"Ethereum blocks polled over the RPC while a WS endpoint is configured, set eth.monitoring_over_ws"
+			),
+			StrictModeViolation::UnencryptedKey => write!(
+				f,
+				"Ethereum key of the relayer stored in clear, set signer.socket_path to sign with the signer process"
+			),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StrictMode {
+	enabled: bool,
+}
+
+impl StrictMode {
+	pub fn from_config(config: &Config) -> Self {
+		StrictMode { enabled: config.startup.strict_mode }
+	}
+
+	pub fn is_enabled(&self) -> bool {
+		self.enabled
+	}
+
+	/// The unsafe fallbacks of the config, whether strict mode is enabled or not.
+	pub fn violations(config: &Config) -> Vec<StrictModeViolation> {
+		let mut violations = Vec::new();
+		if config.eth.confirmations == 0 {
+			violations.push(StrictModeViolation::NoConfirmations);
+		}
+		if config.eth.eth_ws_configured() && !config.eth.monitoring_over_ws {
+			violations.push(StrictModeViolation::PollingFallback);
+		}
+		if config.signer.socket_path.is_empty() {
+			violations.push(StrictModeViolation::UnencryptedKey);
+		}
+		violations
+	}
+
+	/// Refuse the config if it has unsafe fallbacks, all reported at once.
+	pub fn check_config(&self, config: &Config) -> Result<(), anyhow::Error> {
+		if !self.enabled {
+			return Ok(());
+		}
+		let violations = Self::violations(config);
+		if violations.is_empty() {
+			return Ok(());
+		}
+		let violations: Vec<String> = violations.iter().map(ToString::to_string).collect();
+		anyhow::bail!("Strict mode refuses the config: {}", violations.join("; "))
+	}
+
+	/// Log the warning, or fail with it in strict mode.
+	pub fn warn(&self, warning: impl fmt::Display) -> Result<(), anyhow::Error> {
+		if self.enabled {
+			anyhow::bail!("Strict mode: {warning}");
+		}
+		tracing::warn!("{warning}");
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_strict_mode() {
+		let mut config = Config::default();
+		config.eth.eth_ws_connection_protocol = "ws".to_string();
+		config.eth.confirmations = 0;
+		config.signer.socket_path = String::new();
+		assert_eq!(
+			StrictMode::violations(&config),
+			[
+				StrictModeViolation::NoConfirmations,
+				StrictModeViolation::PollingFallback,
+				StrictModeViolation::UnencryptedKey
+			]
+		);
+		// The config is only refused in strict mode.
+		assert!(StrictMode::from_config(&config).check_config(&config).is_ok());
+		assert!(StrictMode::from_config(&config).warn("soft warning").is_ok());
+		config.startup.strict_mode = true;
+		let strict_mode = StrictMode::from_config(&config);
+		let err = strict_mode.check_config(&config).unwrap_err().to_string();
+		assert!(err.contains("eth.confirmations") && err.contains("signer.socket_path"));
+		assert!(strict_mode.warn("soft warning").is_err());
+
+		config.eth.confirmations = 12;
+		config.eth.monitoring_over_ws = true;
+		config.signer.socket_path = "/run/bridge-signer.sock".to_string();
+		assert!(strict_mode.check_config(&config).is_ok());
+		// Without WS endpoint, the blocks can only be polled over the RPC.
+		config.eth.eth_ws_connection_protocol = "http".to_string();
+		config.eth.monitoring_over_ws = false;
+		assert!(StrictMode::violations(&config).is_empty());
+	}
+}
@@ -0,0 +1,82 @@
+//! Transactions of the submissions handed to the chains. A client reports the hash of the
+//! transaction of a submission as soon as a node accepted it, so the outbox records the
+//! submission as sent with its hash before it's confirmed. After a crash, a submission left
+//! planned never reached a node, and a sent one is looked up by its hash.
+use bridge_util::types::{BridgeTransferId, ChainId};
+use tokio::sync::mpsc;
+
+/// Transaction of a submission accepted by a node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SentTransaction {
+	pub chain: ChainId,
+	pub transfer_id: BridgeTransferId,
+	/// Operation of the client, e.g. `lock`.
+	pub operation: &'static str,
+	/// Hex encoded hash of the transaction.
+	pub tx_hash: String,
+}
+
+impl SentTransaction {
+	/// Kind of the outbox action submitted by the operation, the name of its
+	/// `TransferActionType`. None for the operations not recorded in the outbox.
+	pub fn action_kind(&self) -> Option<&'static str> {
+		match self.operation {
+			"lock" => Some("LockBridgeTransfer"),
+			"complete_initiator" => Some("WaitAndCompleteInitiator"),
+			_ => None,
+		}
+	}
+}
+
+/// Reports of the clients to the relayer loop. Clones report to the same receiver, the
+/// default one drops the reports.
+#[derive(Debug, Clone, Default)]
+pub struct SentTransactions {
+	report_tx: Option<mpsc::UnboundedSender<SentTransaction>>,
+}
+
+impl SentTransactions {
+	/// Create the reports and their receiver for the relayer loop.
+	pub fn channel() -> (Self, mpsc::UnboundedReceiver<SentTransaction>) {
+		let (report_tx, report_rx) = mpsc::unbounded_channel();
+		(SentTransactions { report_tx: Some(report_tx) }, report_rx)
+	}
+
+	/// Report the transaction of the operation on the transfer, accepted by a node.
+	pub fn report(
+		&self,
+		chain: ChainId,
+		operation: &'static str,
+		transfer_id: Option<BridgeTransferId>,
+		tx_hash: &[u8],
+	) {
+		let (Some(report_tx), Some(transfer_id)) = (&self.report_tx, transfer_id) else {
+			return;
+		};
+		let sent = SentTransaction { chain, transfer_id, operation, tx_hash: hex::encode(tx_hash) };
+		if report_tx.send(sent).is_err() {
+			tracing::debug!("Relayer loop stopped, sent transaction of {transfer_id} not reported");
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn test_report_sent_transaction() {
+		let (sent_transactions, mut report_rx) = SentTransactions::channel();
+		let transfer_id = BridgeTransferId([1; 32]);
+		// The transactions of no transfer aren't reported.
+		sent_transactions.report(ChainId::ONE, "approve", None, &[0xab; 32]);
+		sent_transactions.report(ChainId::TWO, "lock", Some(transfer_id), &[0xab; 32]);
+
+		let sent = report_rx.recv().await.unwrap();
+		assert_eq!(sent.chain, ChainId::TWO);
+		assert_eq!(sent.transfer_id, transfer_id);
+		assert_eq!(sent.tx_hash, hex::encode([0xab; 32]));
+		assert_eq!(sent.action_kind(), Some("LockBridgeTransfer"));
+		assert!(report_rx.try_recv().is_err());
+	}
+}
@@ -0,0 +1,384 @@
+//! Sweep of the fees accumulated on the hot relayer accounts to cold storage. The balance of
+//! each relayer account above the threshold of its chain is transferred to the cold storage
+//! address of the chain, on a schedule or on a request of the admin API. The relayer config
+//! alone can't redirect the funds: the cold storage addresses and thresholds are only used if
+//! signed by the admin key, and a manual sweep must be requested with a fresh signature of
+//! the admin key. Each sweep is recorded in the ledger of the indexer db.
+use crate::catchup::{ETH_CHAIN, MOVEMENT_CHAIN};
+use crate::chains::ethereum::client::EthClient;
+use crate::chains::movement::client_framework::MovementClientFramework;
+use alloy::primitives::{Address, Signature};
+use alloy::signers::{local::PrivateKeySigner, SignerSync};
+use aptos_sdk::types::account_address::AccountAddress;
+use bridge_config::common::sweep::SweepConfig;
+use bridge_indexer_db::client::Client;
+use bridge_util::reason::{ReasonCode, WithReasonCode};
+use bridge_util::types::Amount;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+// Sweeps kept for the admin API, the oldest are dropped beyond.
+const MAX_SWEEPS: usize = 100;
+// Age of the signature of a manual sweep request above which it's refused.
+const MAX_REQUEST_AGE_SECS: u64 = 5 * 60;
+
+/// Cold storage addresses and thresholds signed by the admin key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SweepPolicy {
+	pub eth_cold_address: Option<Address>,
+	pub movement_cold_address: Option<AccountAddress>,
+	pub eth_threshold: u64,
+	pub movement_threshold: u64,
+}
+
+impl SweepPolicy {
+	pub fn from_config(config: &SweepConfig) -> Result<Self, anyhow::Error> {
+		let eth_cold_address = match config.eth_cold_address.as_str() {
+			"" => None,
+			address => Some(address.parse().map_err(|err| {
+				anyhow::anyhow!("Invalid Ethereum cold storage address {address}: {err}")
+			})?),
+		};
+		let movement_cold_address = match config.movement_cold_address.as_str() {
+			"" => None,
+			address => Some(AccountAddress::from_hex_literal(address).map_err(|err| {
+				anyhow::anyhow!("Invalid Movement cold storage address {address}: {err}")
+			})?),
+		};
+		Ok(SweepPolicy {
+			eth_cold_address,
+			movement_cold_address,
+			eth_threshold: config.eth_threshold,
+			movement_threshold: config.movement_threshold,
+		})
+	}
+
+	/// The policy of the config, `None` if no admin key is configured. It must be signed by
+	/// the admin key.
+	pub fn verified(config: &SweepConfig) -> Result<Option<Self>, anyhow::Error> {
+		if config.admin_address.is_empty() {
+			return Ok(None);
+		}
+		let admin_address: Address = config.admin_address.parse()?;
+		let policy = SweepPolicy::from_config(config)?;
+		let signer = recover_signer(&policy.message(), &config.policy_signature)
+			.map_err(|err| anyhow::anyhow!("Invalid sweep policy signature: {err}"))?;
+		if signer != admin_address {
+			anyhow::bail!("Sweep policy signed by {signer}, not by the admin key {admin_address}");
+		}
+		Ok(Some(policy))
+	}
+
+	/// The message signed by the admin key to authorize the policy.
+	pub fn message(&self) -> String {
+		let eth = self.eth_cold_address.map(|address| address.to_string()).unwrap_or_default();
+		let movement = self
+			.movement_cold_address
+			.map(|address| address.to_hex_literal())
+			.unwrap_or_default();
+		format!(
+			"movement-bridge sweep policy\nethereum: {eth} above {}\nmovement: {movement} above {}",
+			self.eth_threshold, self.movement_threshold
+		)
+	}
+}
+
+/// The message signed by the admin key to request a sweep at the time.
+pub fn request_message(requested_at_secs: u64) -> String {
+	format!("movement-bridge sweep request at {requested_at_secs}")
+}
+
+/// Sign the message with the admin key, hex encoded.
+pub fn sign(admin_key: &PrivateKeySigner, message: &str) -> Result<String, anyhow::Error> {
+	let signature = admin_key.sign_message_sync(message.as_bytes())?;
+	Ok(format!("0x{}", hex::encode(signature.as_bytes())))
+}
+
+/// The address of the key that signed the message.
+pub fn recover_signer(message: &str, signature: &str) -> Result<Address, anyhow::Error> {
+	let bytes = hex::decode(signature.strip_prefix("0x").unwrap_or(signature))?;
+	let signature = Signature::try_from(bytes.as_slice())?;
+	Ok(signature.recover_address_from_msg(message.as_bytes())?)
+}
+
+/// The amount above the threshold, if any.
+fn sweep_amount(balance: u128, threshold: u64) -> Option<u128> {
+	balance.checked_sub(u128::from(threshold)).filter(|amount| *amount > 0)
+}
+
+/// A manual sweep requested on the admin API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SweepRequest {
+	/// Unix timestamp of the request, in seconds.
+	pub requested_at: u64,
+	/// Signature by the admin key of the request message of the timestamp.
+	pub signature: String,
+}
+
+/// A transfer of a relayer account to its cold storage address.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SweepRecord {
+	pub chain: &'static str,
+	pub destination: String,
+	/// MOVE tokens in the smallest unit on Ethereum, octas on Movement.
+	pub amount: String,
+	pub tx_hash: String,
+	pub swept_at: u64,
+}
+
+#[derive(Debug, Error)]
+pub enum SweepError {
+	#[error("Sweep request not signed by the admin key: {0}")]
+	Unauthorized(String),
+	#[error("Cold storage sweeps are not configured")]
+	Disabled,
+	#[error("Sweep failed: {0}")]
+	Failed(String),
+}
+
+impl WithReasonCode for SweepError {
+	fn reason_code(&self) -> ReasonCode {
+		match self {
+			SweepError::Unauthorized(_) => ReasonCode::Unauthorized,
+			SweepError::Disabled => ReasonCode::FeatureDisabled,
+			SweepError::Failed(_) => ReasonCode::TransactionFailed,
+		}
+	}
+}
+
+// Serializes the sweeps and holds the clients and the ledger.
+struct Sweeps {
+	eth_client: EthClient,
+	movement_client: MovementClientFramework,
+	ledger: Client,
+	// Timestamp of the last manual request accepted, a request can't be replayed.
+	last_request_secs: u64,
+}
+
+/// Sweeps the relayer accounts to the cold storage addresses. Clones share the same clients
+/// and records.
+#[derive(Clone)]
+pub struct ColdSweeper {
+	policy: SweepPolicy,
+	admin_address: Address,
+	interval: Duration,
+	sweeps: Arc<tokio::sync::Mutex<Sweeps>>,
+	records: Arc<Mutex<VecDeque<SweepRecord>>>,
+}
+
+impl ColdSweeper {
+	/// The sweeper of the config, `None` if no admin key is configured. The policy of the
+	/// config must be signed by the admin key.
+	pub fn new(
+		config: &SweepConfig,
+		eth_client: EthClient,
+		movement_client: MovementClientFramework,
+		ledger: Client,
+	) -> Result<Option<Self>, anyhow::Error> {
+		let Some(policy) = SweepPolicy::verified(config)? else {
+			return Ok(None);
+		};
+		let admin_address: Address = config.admin_address.parse()?;
+		Ok(Some(ColdSweeper {
+			policy,
+			admin_address,
+			interval: Duration::from_secs(config.interval_secs.max(1)),
+			sweeps: Arc::new(tokio::sync::Mutex::new(Sweeps {
+				eth_client,
+				movement_client,
+				ledger,
+				last_request_secs: 0,
+			})),
+			records: Arc::default(),
+		}))
+	}
+
+	/// Sweep on every interval, the first time one interval after the start.
+	pub async fn run(self) {
+		let start = tokio::time::Instant::now() + self.interval;
+		let mut interval = tokio::time::interval_at(start, self.interval);
+		loop {
+			interval.tick().await;
+			if let Err(err) = self.sweep().await {
+				tracing::error!("Cold storage sweep failed: {err}");
+			}
+		}
+	}
+
+	/// Sweep now on the request of the admin, checking it's signed by the admin key.
+	pub async fn sweep_on_request(
+		&self,
+		request: &SweepRequest,
+	) -> Result<Vec<SweepRecord>, SweepError> {
+		let mut sweeps = self.sweeps.lock().await;
+		authorize(request, self.admin_address, sweeps.last_request_secs, now_secs())?;
+		sweeps.last_request_secs = request.requested_at;
+		self.sweep_locked(&mut sweeps).await
+	}
+
+	async fn sweep(&self) -> Result<Vec<SweepRecord>, SweepError> {
+		let mut sweeps = self.sweeps.lock().await;
+		self.sweep_locked(&mut sweeps).await
+	}
+
+	async fn sweep_locked(&self, sweeps: &mut Sweeps) -> Result<Vec<SweepRecord>, SweepError> {
+		let failed = |err: &dyn std::fmt::Display| SweepError::Failed(err.to_string());
+		let mut swept = Vec::new();
+		if let Some(destination) = self.policy.eth_cold_address {
+			let balance = sweeps.eth_client.move_token_balance().await.map_err(|e| failed(&e))?;
+			let balance = u128::try_from(balance).map_err(|e| failed(&e))?;
+			if let Some(amount) = sweep_amount(balance, self.policy.eth_threshold) {
+				let amount = u64::try_from(amount).map_err(|e| failed(&e))?;
+				let tx_hash = sweeps
+					.eth_client
+					.transfer_move_token(destination, Amount(amount))
+					.await
+					.map_err(|e| failed(&e))?;
+				let tx_hash = format!("0x{}", hex::encode(tx_hash));
+				swept.push(self.record(
+					&mut sweeps.ledger,
+					ETH_CHAIN,
+					destination.to_string(),
+					amount.into(),
+					tx_hash,
+				));
+			}
+		}
+		if let Some(destination) = self.policy.movement_cold_address {
+			let balance = sweeps.movement_client.coin_balance().await.map_err(|e| failed(&e))?;
+			if let Some(amount) = sweep_amount(balance.into(), self.policy.movement_threshold) {
+				let amount = u64::try_from(amount).map_err(|e| failed(&e))?;
+				let tx_hash = sweeps
+					.movement_client
+					.transfer_coins(destination, amount)
+					.await
+					.map_err(|e| failed(&e))?;
+				swept.push(self.record(
+					&mut sweeps.ledger,
+					MOVEMENT_CHAIN,
+					destination.to_hex_literal(),
+					amount.into(),
+					tx_hash,
+				));
+			}
+		}
+		Ok(swept)
+	}
+
+	// Record the sweep in the ledger and the recent sweeps.
+	fn record(
+		&self,
+		ledger: &mut Client,
+		chain: &'static str,
+		destination: String,
+		amount: u128,
+		tx_hash: String,
+	) -> SweepRecord {
+		tracing::info!("Swept {amount} from the {chain} relayer account to {destination}");
+		if let Err(err) = ledger.insert_fee_sweep(chain, &destination, amount, &tx_hash) {
+			tracing::error!(
+				target: "bridge_alert",
+				"Sweep {tx_hash} on {chain} not recorded in the ledger: {err}"
+			);
+		}
+		let record = SweepRecord {
+			chain,
+			destination,
+			amount: amount.to_string(),
+			tx_hash,
+			swept_at: now_secs(),
+		};
+		let mut records = self.records.lock().expect("Sweep records lock poisoned");
+		if records.len() >= MAX_SWEEPS {
+			records.pop_front();
+		}
+		records.push_back(record.clone());
+		record
+	}
+
+	/// The sweeps since the start, the most recent first.
+	pub fn records(&self) -> Vec<SweepRecord> {
+		self.records
+			.lock()
+			.expect("Sweep records lock poisoned")
+			.iter()
+			.rev()
+			.cloned()
+			.collect()
+	}
+}
+
+// Check the request is signed by the admin key, recent and newer than the last one accepted.
+fn authorize(
+	request: &SweepRequest,
+	admin_address: Address,
+	last_request_secs: u64,
+	now_secs: u64,
+) -> Result<(), SweepError> {
+	if request.requested_at <= last_request_secs {
+		return Err(SweepError::Unauthorized("request already used".to_string()));
+	}
+	if now_secs.abs_diff(request.requested_at) > MAX_REQUEST_AGE_SECS {
+		return Err(SweepError::Unauthorized("request expired".to_string()));
+	}
+	let signer = recover_signer(&request_message(request.requested_at), &request.signature)
+		.map_err(|err| SweepError::Unauthorized(err.to_string()))?;
+	if signer != admin_address {
+		return Err(SweepError::Unauthorized(format!("signed by {signer}")));
+	}
+	Ok(())
+}
+
+fn now_secs() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|d| d.as_secs())
+		.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_sweep_authorization() {
+		let admin_key = PrivateKeySigner::random();
+		let config = SweepConfig {
+			eth_cold_address: "0x90f79bf6eb2c4f870365e785982e1f101e93b906".to_string(),
+			movement_cold_address: "0x1234".to_string(),
+			eth_threshold: 1000,
+			..SweepConfig::default()
+		};
+		let policy = SweepPolicy::from_config(&config).unwrap();
+		assert_eq!(
+			policy.movement_cold_address,
+			Some(AccountAddress::from_hex_literal("0x1234").unwrap())
+		);
+		let signature = sign(&admin_key, &policy.message()).unwrap();
+		assert_eq!(recover_signer(&policy.message(), &signature).unwrap(), admin_key.address());
+		// The signature doesn't authorize another policy.
+		let other = SweepPolicy { eth_threshold: 0, ..policy.clone() };
+		assert_ne!(recover_signer(&other.message(), &signature).unwrap(), admin_key.address());
+		assert!(recover_signer(&policy.message(), "0x1234").is_err());
+		let invalid = SweepConfig { eth_cold_address: "0x01".to_string(), ..config };
+		assert!(SweepPolicy::from_config(&invalid).is_err());
+
+		let request = |requested_at, key: &PrivateKeySigner| SweepRequest {
+			requested_at,
+			signature: sign(key, &request_message(requested_at)).unwrap(),
+		};
+		let admin = admin_key.address();
+		assert!(authorize(&request(1000, &admin_key), admin, 0, 1100).is_ok());
+		// Replayed, expired or signed by another key.
+		assert!(authorize(&request(1000, &admin_key), admin, 1000, 1100).is_err());
+		assert!(authorize(&request(1000, &admin_key), admin, 0, 1000 + 301).is_err());
+		assert!(authorize(&request(1000, &PrivateKeySigner::random()), admin, 0, 1100).is_err());
+
+		assert_eq!(sweep_amount(1500, 1000), Some(500));
+		assert_eq!(sweep_amount(1000, 1000), None);
+		assert_eq!(sweep_amount(10, 1000), None);
+	}
+}
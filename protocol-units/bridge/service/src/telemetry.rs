@@ -0,0 +1,97 @@
+//! Tracing of the relayer. The processing of a transfer, from the event of the monitoring to
+//! the state machine and the calls of the contracts, runs in a span carrying its id, so the
+//! logs of a transfer can be correlated and its spans exported to an OpenTelemetry collector.
+//! The stages of a transfer run in child spans timed into its latency breakdown.
+use crate::latency::LatencyBreakdowns;
+use bridge_config::common::telemetry::TelemetryConfig;
+use bridge_util::types::BridgeTransferId;
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::trace::{Tracer, TracerProvider};
+use opentelemetry_sdk::Resource;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+type OtlpLayer = Option<OpenTelemetryLayer<Registry, Tracer>>;
+
+/// Span of the processing of a transfer, its id hex encoded.
+pub fn transfer_span(transfer_id: BridgeTransferId) -> tracing::Span {
+	tracing::info_span!("bridge_transfer", bridge_transfer_id = %hex::encode(transfer_id.0))
+}
+
+/// The tracing of the relayer, installed before its config is loaded.
+pub struct Telemetry {
+	otlp: reload::Handle<OtlpLayer, Registry>,
+	provider: Option<TracerProvider>,
+	latencies: LatencyBreakdowns,
+}
+
+impl Telemetry {
+	/// Install the logs of the relayer, filtered by `RUST_LOG`, and the timing of the stages
+	/// of the transfers.
+	pub fn init() -> Self {
+		let (otlp, handle) = reload::Layer::new(None);
+		let latencies = LatencyBreakdowns::default();
+		tracing_subscriber::registry()
+			.with(otlp)
+			.with(latencies.layer())
+			.with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
+			.with(tracing_subscriber::fmt::layer())
+			.init();
+		Telemetry { otlp: handle, provider: None, latencies }
+	}
+
+	/// The latency breakdowns of the transfers, timed from their stage spans.
+	pub fn latencies(&self) -> LatencyBreakdowns {
+		self.latencies.clone()
+	}
+
+	/// Start the export of the spans if the config enables it.
+	pub fn start_otlp_export(&mut self, config: &TelemetryConfig) -> Result<(), anyhow::Error> {
+		if !config.otlp_enabled {
+			return Ok(());
+		}
+		let provider = opentelemetry_otlp::new_pipeline()
+			.tracing()
+			.with_exporter(
+				opentelemetry_otlp::new_exporter().tonic().with_endpoint(&config.otlp_endpoint),
+			)
+			.with_trace_config(opentelemetry_sdk::trace::Config::default().with_resource(
+				Resource::new(vec![KeyValue::new("service.name", config.service_name.clone())]),
+			))
+			.install_batch(opentelemetry_sdk::runtime::Tokio)?;
+		let layer = tracing_opentelemetry::layer().with_tracer(provider.tracer("bridge"));
+		self.otlp.reload(Some(layer))?;
+		self.provider = Some(provider);
+		tracing::info!("Export the tracing spans to {}", config.otlp_endpoint);
+		Ok(())
+	}
+
+	/// Export the spans not sent yet, when the relayer stops.
+	pub fn shutdown(self) {
+		if let Some(provider) = self.provider {
+			if let Err(err) = provider.shutdown() {
+				tracing::warn!("Failed to export the last tracing spans: {err}");
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_transfer_span() {
+		let subscriber = tracing_subscriber::registry().with(tracing_subscriber::fmt::layer());
+		tracing::subscriber::with_default(subscriber, || {
+			let span = transfer_span(BridgeTransferId([1; 32]));
+			let field = span.field("bridge_transfer_id").expect("the span has the transfer id");
+			assert_eq!(field.name(), "bridge_transfer_id");
+			assert_eq!(span.metadata().map(|metadata| metadata.name()), Some("bridge_transfer"));
+		});
+	}
+}
@@ -0,0 +1,635 @@
+//! Refund of the initiators whose counterparty never reveals the secret, and abort of the locks
+//! never completed, once their time lock is expired. The time locks are read from the contract
+//! events of the bus, the refunds and aborts are submitted by the relayer keys. The contracts of
+//! a chain express their time locks either as timestamps or as block heights. A transfer is
+//! only locked while its initiator time lock leaves the recipient the time to complete it.
+use crate::chains::ethereum::client::EthClient;
+use crate::chains::movement::client_framework::MovementClientFramework;
+use crate::event_bus::{BusEvent, Subscription};
+use bridge_config::common::timelock::TimelockConfig;
+use bridge_util::chains::bridge_contracts::{BridgeContract, BridgeContractEvent};
+use bridge_util::events::TransferEvent;
+use bridge_util::types::{BridgeTransferId, ChainId};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::watch;
+
+/// Unit of the time locks of the contracts of a chain.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimeLockUnit {
+	/// Seconds since the epoch.
+	#[default]
+	Timestamp,
+	/// Height of the blocks on Ethereum, of the ledger on Movement.
+	BlockHeight,
+}
+
+impl FromStr for TimeLockUnit {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"timestamp" => Ok(TimeLockUnit::Timestamp),
+			"block_height" => Ok(TimeLockUnit::BlockHeight),
+			_ => Err(format!("Unknown time lock unit: {s}")),
+		}
+	}
+}
+
+impl fmt::Display for TimeLockUnit {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let s = match self {
+			TimeLockUnit::Timestamp => "timestamp",
+			TimeLockUnit::BlockHeight => "block_height",
+		};
+		write!(f, "{}", s)
+	}
+}
+
+/// Unit of the time locks of a chain and the average time between its blocks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChainTimeLocks {
+	pub unit: TimeLockUnit,
+	pub block_time_ms: u64,
+}
+
+/// Clocks of the chains at a check of the time locks. The heights are only read on the chains
+/// with block height time locks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChainClocks {
+	pub now_secs: u64,
+	pub eth_height: Option<u64>,
+	pub movement_height: Option<u64>,
+}
+
+impl ChainClocks {
+	fn height(&self, chain: ChainId) -> Option<u64> {
+		match chain {
+			ChainId::ONE => self.eth_height,
+			ChainId::TWO => self.movement_height,
+		}
+	}
+}
+
+/// Converts the time locks of each chain between seconds and block heights, so the time locks
+/// of both units expire the same way.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TimeLockPolicy {
+	pub eth: ChainTimeLocks,
+	pub movement: ChainTimeLocks,
+}
+
+impl TimeLockPolicy {
+	pub fn from_config(config: &TimelockConfig) -> Result<Self, anyhow::Error> {
+		let unit = |unit: &str| TimeLockUnit::from_str(unit).map_err(|err| anyhow::anyhow!(err));
+		Ok(TimeLockPolicy {
+			eth: ChainTimeLocks {
+				unit: unit(&config.eth_unit)?,
+				block_time_ms: config.eth_block_time_ms,
+			},
+			movement: ChainTimeLocks {
+				unit: unit(&config.mvt_unit)?,
+				block_time_ms: config.mvt_block_time_ms,
+			},
+		})
+	}
+
+	pub fn chain(&self, chain: ChainId) -> ChainTimeLocks {
+		match chain {
+			ChainId::ONE => self.eth,
+			ChainId::TWO => self.movement,
+		}
+	}
+
+	/// Whether the height of the chain is needed to check its time locks.
+	pub fn uses_heights(&self, chain: ChainId) -> bool {
+		self.chain(chain).unit == TimeLockUnit::BlockHeight
+	}
+
+	/// The duration in the unit of the chain: the seconds, or the blocks produced in them
+	/// rounded up.
+	pub fn to_chain_units(&self, chain: ChainId, secs: u64) -> u64 {
+		let time_locks = self.chain(chain);
+		match time_locks.unit {
+			TimeLockUnit::Timestamp => secs,
+			TimeLockUnit::BlockHeight => {
+				secs.saturating_mul(1000).div_ceil(time_locks.block_time_ms.max(1))
+			}
+		}
+	}
+
+	/// The duration in seconds of a number of units of the chain, estimated with the block
+	/// time for the heights.
+	pub fn to_secs(&self, chain: ChainId, units: u64) -> u64 {
+		let time_locks = self.chain(chain);
+		match time_locks.unit {
+			TimeLockUnit::Timestamp => units,
+			TimeLockUnit::BlockHeight => units.saturating_mul(time_locks.block_time_ms) / 1000,
+		}
+	}
+
+	// The current position of the chain in the unit of its time locks, None while its height
+	// is unknown.
+	fn now(&self, chain: ChainId, clocks: &ChainClocks) -> Option<u64> {
+		match self.chain(chain).unit {
+			TimeLockUnit::Timestamp => Some(clocks.now_secs),
+			TimeLockUnit::BlockHeight => clocks.height(chain),
+		}
+	}
+
+	/// Whether the time lock of the chain is expired for more than the grace delay.
+	pub fn is_expired(
+		&self,
+		chain: ChainId,
+		time_lock: u64,
+		clocks: &ChainClocks,
+		grace_secs: u64,
+	) -> bool {
+		self.now(chain, clocks).is_some_and(|now| {
+			time_lock.saturating_add(self.to_chain_units(chain, grace_secs)) <= now
+		})
+	}
+
+	/// Expiry of the time lock in seconds since the epoch, estimated from the current height
+	/// for the heights. None while the height of the chain is unknown.
+	pub fn expiry_secs(&self, chain: ChainId, time_lock: u64, clocks: &ChainClocks) -> Option<u64> {
+		let now = self.now(chain, clocks)?;
+		Some(match self.chain(chain).unit {
+			TimeLockUnit::Timestamp => time_lock,
+			TimeLockUnit::BlockHeight if time_lock >= now => {
+				clocks.now_secs.saturating_add(self.to_secs(chain, time_lock - now))
+			}
+			TimeLockUnit::BlockHeight => {
+				clocks.now_secs.saturating_sub(self.to_secs(chain, now - time_lock))
+			}
+		})
+	}
+}
+
+/// Minimum time left on the initiator time lock of a transfer to lock it on the counterparty
+/// chain. Clones share the clocks of the chains, read by the timelock watcher.
+#[derive(Debug, Clone)]
+pub struct LockMargin {
+	policy: TimeLockPolicy,
+	min_margin_secs: u64,
+	clocks: watch::Receiver<ChainClocks>,
+}
+
+impl LockMargin {
+	/// Create the margin and the sender of the clocks read by the timelock watcher.
+	pub fn new(
+		config: &TimelockConfig,
+		policy: TimeLockPolicy,
+	) -> (Self, watch::Sender<ChainClocks>) {
+		let (clocks_tx, clocks) = watch::channel(ChainClocks::default());
+		(LockMargin { policy, min_margin_secs: config.min_lock_margin_secs, clocks }, clocks_tx)
+	}
+
+	/// Whether the time lock of the initiation on the chain leaves the margin. The time locks
+	/// in block heights are allowed while the height of their chain is unknown.
+	pub fn allows_lock(&self, init_chain: ChainId, time_lock: u64) -> bool {
+		let now_secs = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map(|d| d.as_secs())
+			.unwrap_or_default();
+		self.allows_lock_at(init_chain, time_lock, now_secs)
+	}
+
+	fn allows_lock_at(&self, init_chain: ChainId, time_lock: u64, now_secs: u64) -> bool {
+		// The expiry of a height is estimated at the last read of the height.
+		let clocks = *self.clocks.borrow();
+		match self.policy.expiry_secs(init_chain, time_lock, &clocks) {
+			Some(expiry_secs) => expiry_secs >= now_secs.saturating_add(self.min_margin_secs),
+			None => true,
+		}
+	}
+}
+
+impl Default for LockMargin {
+	fn default() -> Self {
+		LockMargin::new(&TimelockConfig::default(), TimeLockPolicy::default()).0
+	}
+}
+
+/// What is submitted once a time lock is expired.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExpiryAction {
+	/// Refund the initiator on the chain the transfer was initiated on.
+	Refund,
+	/// Abort the lock on the counterparty chain.
+	Abort,
+}
+
+/// A time lock of a transfer not yet completed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Deadline {
+	pub chain: ChainId,
+	/// Expiry of the time lock, in the unit of the time locks of the chain.
+	pub time_lock: u64,
+	attempts: u32,
+}
+
+/// Time locks of the transfers whose refund or abort may be needed.
+#[derive(Debug, Default)]
+pub struct TimeLocks {
+	policy: TimeLockPolicy,
+	deadlines: HashMap<(BridgeTransferId, ExpiryAction), Deadline>,
+	// Locks observed on the counterparty chain and not cancelled: the initiator of such a
+	// transfer is only refunded once its lock is cancelled, even if the abort was given up.
+	open_locks: HashSet<BridgeTransferId>,
+}
+
+impl TimeLocks {
+	pub fn new(policy: TimeLockPolicy) -> Self {
+		TimeLocks { policy, deadlines: HashMap::new(), open_locks: HashSet::new() }
+	}
+
+	/// Track the time lock of an initiation or a lock, and stop tracking it once the
+	/// transfer is completed, refunded or aborted.
+	pub fn observe<A>(&mut self, event: &TransferEvent<A>) {
+		let deadline = |time_lock: u64| Deadline { chain: event.chain, time_lock, attempts: 0 };
+		match &event.contract_event {
+			BridgeContractEvent::Initiated(details) => {
+				self.deadlines.insert(
+					(details.bridge_transfer_id, ExpiryAction::Refund),
+					deadline(details.time_lock.0),
+				);
+			}
+			BridgeContractEvent::Locked(details) => {
+				self.deadlines.insert(
+					(details.bridge_transfer_id, ExpiryAction::Abort),
+					deadline(details.time_lock.0),
+				);
+				self.open_locks.insert(details.bridge_transfer_id);
+			}
+			// The secret is revealed, the initiator must be completed instead of refunded.
+			BridgeContractEvent::CounterPartyCompleted(id, _) => {
+				self.deadlines.remove(&(*id, ExpiryAction::Abort));
+				self.deadlines.remove(&(*id, ExpiryAction::Refund));
+				self.open_locks.remove(id);
+			}
+			BridgeContractEvent::InitiatorCompleted(id) | BridgeContractEvent::Refunded(id) => {
+				self.deadlines.remove(&(*id, ExpiryAction::Refund));
+			}
+			BridgeContractEvent::Cancelled(id) => {
+				self.deadlines.remove(&(*id, ExpiryAction::Abort));
+				self.open_locks.remove(id);
+			}
+		}
+	}
+
+	/// The time locks expired for more than the grace delay at the clocks, the oldest expiry
+	/// first whatever the unit of its chain. The refund of an initiator whose lock isn't
+	/// cancelled yet waits for the cancel: the recipient could still complete the lock with the
+	/// secret while the initiator gets their funds back.
+	pub fn expired(
+		&self,
+		clocks: &ChainClocks,
+		grace_secs: u64,
+	) -> Vec<(BridgeTransferId, ExpiryAction, Deadline)> {
+		let mut expired: Vec<_> = self
+			.deadlines
+			.iter()
+			.filter(|((id, action), deadline)| {
+				let lock_open = *action == ExpiryAction::Refund && self.open_locks.contains(id);
+				!lock_open
+					&& self.policy.is_expired(
+						deadline.chain,
+						deadline.time_lock,
+						clocks,
+						grace_secs,
+					)
+			})
+			.map(|((id, action), deadline)| (*id, *action, *deadline))
+			.collect();
+		expired.sort_by_key(|(_, _, deadline)| {
+			self.policy.expiry_secs(deadline.chain, deadline.time_lock, clocks)
+		});
+		expired
+	}
+
+	/// Record a submission of the refund or abort. A successful one, or the last failed one
+	/// allowed, stops the tracking. Return the number of attempts made.
+	pub fn submitted(
+		&mut self,
+		transfer_id: BridgeTransferId,
+		action: ExpiryAction,
+		success: bool,
+		max_attempts: u32,
+	) -> u32 {
+		let key = (transfer_id, action);
+		let Some(deadline) = self.deadlines.get_mut(&key) else {
+			return 0;
+		};
+		deadline.attempts += 1;
+		let attempts = deadline.attempts;
+		if success || attempts >= max_attempts {
+			self.deadlines.remove(&key);
+		}
+		attempts
+	}
+}
+
+/// Submits the refunds and aborts of the expired time locks.
+pub struct TimelockWatcher {
+	config: TimelockConfig,
+	policy: TimeLockPolicy,
+	eth_client: EthClient,
+	movement_client: MovementClientFramework,
+	clocks_tx: Option<watch::Sender<ChainClocks>>,
+}
+
+impl TimelockWatcher {
+	pub fn new(
+		config: &TimelockConfig,
+		policy: TimeLockPolicy,
+		eth_client: EthClient,
+		movement_client: MovementClientFramework,
+	) -> Self {
+		TimelockWatcher {
+			config: config.clone(),
+			policy,
+			eth_client,
+			movement_client,
+			clocks_tx: None,
+		}
+	}
+
+	/// Share the clocks read at each check with the margin of the locks.
+	pub fn with_lock_margin(mut self, clocks_tx: watch::Sender<ChainClocks>) -> Self {
+		self.clocks_tx = Some(clocks_tx);
+		self
+	}
+
+	/// Follow the contract events of the subscription until the bus is dropped.
+	pub async fn run(self, mut events: Subscription) {
+		let mut time_locks = TimeLocks::new(self.policy);
+		let mut check_interval =
+			tokio::time::interval(Duration::from_secs(self.config.check_interval_secs.max(1)));
+		loop {
+			tokio::select! {
+				event = events.recv() => match event {
+					Some(BusEvent::Contract(event)) => time_locks.observe(&event),
+					Some(_) => (),
+					None => return,
+				},
+				_ = check_interval.tick() => {
+					let clocks = self.clocks().await;
+					if let Some(clocks_tx) = &self.clocks_tx {
+						clocks_tx.send_replace(clocks);
+					}
+					for (transfer_id, action, deadline) in
+						time_locks.expired(&clocks, self.config.grace_secs)
+					{
+						self.submit(&mut time_locks, transfer_id, action, deadline).await;
+					}
+				}
+			}
+		}
+	}
+
+	// The time and the heights of the chains with block height time locks. The time locks of
+	// a chain whose height can't be read are checked at the next tick.
+	async fn clocks(&self) -> ChainClocks {
+		let now_secs = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map(|d| d.as_secs())
+			.unwrap_or_default();
+		let eth_height = if self.policy.uses_heights(ChainId::ONE) {
+			self.eth_client
+				.get_block_number()
+				.await
+				.inspect_err(|err| tracing::warn!("Time locks: no Ethereum height: {err}"))
+				.ok()
+		} else {
+			None
+		};
+		let movement_height = if self.policy.uses_heights(ChainId::TWO) {
+			self.movement_client
+				.get_block_height()
+				.await
+				.inspect_err(|err| tracing::warn!("Time locks: no Movement height: {err}"))
+				.ok()
+		} else {
+			None
+		};
+		ChainClocks { now_secs, eth_height, movement_height }
+	}
+
+	async fn submit(
+		&self,
+		time_locks: &mut TimeLocks,
+		transfer_id: BridgeTransferId,
+		action: ExpiryAction,
+		deadline: Deadline,
+	) {
+		tracing::info!(
+			"Time lock {} ({}) of transfer {transfer_id} on {:?} expired, submit {action:?}",
+			deadline.time_lock,
+			self.policy.chain(deadline.chain).unit,
+			deadline.chain
+		);
+		let result = match (deadline.chain, action) {
+			(ChainId::ONE, ExpiryAction::Refund) => {
+				self.eth_client.clone().refund_bridge_transfer(transfer_id).await
+			}
+			(ChainId::ONE, ExpiryAction::Abort) => {
+				self.eth_client.clone().abort_bridge_transfer(transfer_id).await
+			}
+			(ChainId::TWO, ExpiryAction::Refund) => {
+				self.movement_client.clone().refund_bridge_transfer(transfer_id).await
+			}
+			(ChainId::TWO, ExpiryAction::Abort) => {
+				self.movement_client.clone().abort_bridge_transfer(transfer_id).await
+			}
+		};
+		let attempts =
+			time_locks.submitted(transfer_id, action, result.is_ok(), self.config.max_attempts);
+		match result {
+			Ok(()) => tracing::info!("{action:?} of expired transfer {transfer_id} submitted"),
+			Err(err) if attempts >= self.config.max_attempts && action == ExpiryAction::Abort => {
+				tracing::error!(
+					target: "bridge_alert",
+					"Abort of expired transfer {transfer_id} failed {attempts} times, given up, its initiator isn't refunded until the lock is cancelled: {err}"
+				)
+			}
+			Err(err) if attempts >= self.config.max_attempts => tracing::error!(
+				target: "bridge_alert",
+				"{action:?} of expired transfer {transfer_id} failed {attempts} times, given up: {err}"
+			),
+			Err(err) => tracing::warn!(
+				"{action:?} of expired transfer {transfer_id} failed, attempt {attempts}: {err}"
+			),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bridge_util::types::{
+		Amount, BridgeAddress, BridgeTransferDetails, HashLock, HashLockPreImage, LockDetails,
+		TimeLock,
+	};
+
+	#[test]
+	fn test_time_locks() {
+		let id = |byte: u8| BridgeTransferId([byte; 32]);
+		let initiated = |byte: u8, time_lock: u64| TransferEvent {
+			chain: ChainId::ONE,
+			contract_event: BridgeContractEvent::Initiated(BridgeTransferDetails {
+				bridge_transfer_id: id(byte),
+				initiator: BridgeAddress(vec![1; 20]),
+				recipient: BridgeAddress(vec![2; 32]),
+				hash_lock: HashLock([3; 32]),
+				time_lock: TimeLock(time_lock),
+				amount: Amount(10),
+				state: 0,
+			}),
+		};
+		let locked = |byte: u8, time_lock: u64| TransferEvent {
+			chain: ChainId::TWO,
+			contract_event: BridgeContractEvent::Locked(LockDetails {
+				bridge_transfer_id: id(byte),
+				initiator: BridgeAddress(vec![1; 20]),
+				recipient: BridgeAddress(vec![2; 32]),
+				hash_lock: HashLock([3; 32]),
+				time_lock: TimeLock(time_lock),
+				amount: Amount(10),
+			}),
+		};
+		let event = |chain, contract_event| TransferEvent::<Vec<u8>> { chain, contract_event };
+		let at = |now_secs| ChainClocks { now_secs, ..Default::default() };
+
+		let mut time_locks = TimeLocks::default();
+		time_locks.observe(&initiated(1, 200));
+		time_locks.observe(&locked(1, 100));
+		time_locks.observe(&initiated(2, 150));
+		assert!(time_locks.expired(&at(100), 10).is_empty());
+		let expired_at = |time_locks: &TimeLocks, now_secs| {
+			let expired = time_locks.expired(&at(now_secs), 10);
+			expired.iter().map(|(id, action, _)| (*id, *action)).collect::<Vec<_>>()
+		};
+		// The refund of transfer 1 waits for the cancel of its lock.
+		assert_eq!(
+			expired_at(&time_locks, 210),
+			[(id(1), ExpiryAction::Abort), (id(2), ExpiryAction::Refund)]
+		);
+		// The abort given up doesn't release the refund.
+		assert_eq!(time_locks.submitted(id(1), ExpiryAction::Abort, false, 1), 1);
+		assert_eq!(expired_at(&time_locks, 210), [(id(2), ExpiryAction::Refund)]);
+		time_locks.observe(&event(ChainId::TWO, BridgeContractEvent::Cancelled(id(1))));
+		assert_eq!(
+			expired_at(&time_locks, 210),
+			[(id(2), ExpiryAction::Refund), (id(1), ExpiryAction::Refund)]
+		);
+		time_locks.observe(&locked(1, 100));
+
+		// The revealed secret stops the refund and the abort of the transfer.
+		time_locks.observe(&event(
+			ChainId::TWO,
+			BridgeContractEvent::CounterPartyCompleted(id(1), HashLockPreImage([4; 32])),
+		));
+		assert_eq!(time_locks.expired(&at(210), 10).len(), 1);
+
+		// A failed refund is submitted again until the maximum of attempts.
+		assert_eq!(time_locks.submitted(id(2), ExpiryAction::Refund, false, 2), 1);
+		assert_eq!(time_locks.expired(&at(210), 10).len(), 1);
+		assert_eq!(time_locks.submitted(id(2), ExpiryAction::Refund, false, 2), 2);
+		assert!(time_locks.expired(&at(210), 10).is_empty());
+
+		time_locks.observe(&locked(3, 100));
+		time_locks.observe(&event(ChainId::TWO, BridgeContractEvent::Cancelled(id(3))));
+		time_locks.observe(&initiated(4, 100));
+		assert_eq!(time_locks.submitted(id(4), ExpiryAction::Refund, true, 2), 1);
+		assert!(time_locks.expired(&at(210), 10).is_empty());
+	}
+
+	#[test]
+	fn test_lock_margin() {
+		let config = TimelockConfig {
+			min_lock_margin_secs: 100,
+			mvt_unit: "block_height".to_string(),
+			mvt_block_time_ms: 1000,
+			..TimelockConfig::default()
+		};
+		let policy = TimeLockPolicy::from_config(&config).unwrap();
+		let (margin, clocks_tx) = LockMargin::new(&config, policy);
+		assert!(margin.allows_lock_at(ChainId::ONE, 1_100, 1_000));
+		assert!(!margin.allows_lock_at(ChainId::ONE, 1_099, 1_000));
+		// The Movement height is unknown, the margin can't be checked.
+		assert!(margin.allows_lock_at(ChainId::TWO, 0, 1_000));
+
+		// Height 500 read at 1_000, 10 seconds ago: the height 600 expires at 1_100.
+		clocks_tx.send_replace(ChainClocks {
+			now_secs: 1_000,
+			eth_height: None,
+			movement_height: Some(500),
+		});
+		assert!(margin.allows_lock_at(ChainId::TWO, 610, 1_010));
+		assert!(!margin.allows_lock_at(ChainId::TWO, 609, 1_010));
+	}
+
+	#[test]
+	fn test_time_lock_policy() {
+		let config = TimelockConfig {
+			mvt_unit: "block_height".to_string(),
+			mvt_block_time_ms: 500,
+			..TimelockConfig::default()
+		};
+		let policy = TimeLockPolicy::from_config(&config).unwrap();
+		assert!(!policy.uses_heights(ChainId::ONE) && policy.uses_heights(ChainId::TWO));
+		assert_eq!(policy.to_chain_units(ChainId::TWO, 10), 20);
+		assert_eq!(policy.to_secs(ChainId::TWO, 21), 10);
+		assert_eq!(policy.to_chain_units(ChainId::ONE, 10), 10);
+		assert!(TimeLockPolicy::from_config(&TimelockConfig {
+			eth_unit: "blocks".to_string(),
+			..TimelockConfig::default()
+		})
+		.is_err());
+
+		// A lock on Movement at height 1_000, an initiation on Ethereum at a timestamp.
+		let locked = TransferEvent::<Vec<u8>> {
+			chain: ChainId::TWO,
+			contract_event: BridgeContractEvent::Locked(LockDetails {
+				bridge_transfer_id: BridgeTransferId([1; 32]),
+				initiator: BridgeAddress(vec![1; 20]),
+				recipient: BridgeAddress(vec![2; 32]),
+				hash_lock: HashLock([3; 32]),
+				time_lock: TimeLock(1_000),
+				amount: Amount(10),
+			}),
+		};
+		let initiated = TransferEvent::<Vec<u8>> {
+			chain: ChainId::ONE,
+			contract_event: BridgeContractEvent::Initiated(BridgeTransferDetails {
+				bridge_transfer_id: BridgeTransferId([2; 32]),
+				initiator: BridgeAddress(vec![1; 20]),
+				recipient: BridgeAddress(vec![2; 32]),
+				hash_lock: HashLock([3; 32]),
+				time_lock: TimeLock(5_000),
+				amount: Amount(10),
+				state: 0,
+			}),
+		};
+		let mut time_locks = TimeLocks::new(policy);
+		time_locks.observe(&locked);
+		time_locks.observe(&initiated);
+		let clocks =
+			|now_secs, movement_height| ChainClocks { now_secs, eth_height: None, movement_height };
+		// The heights are unknown, the lock can't expire.
+		assert_eq!(time_locks.expired(&clocks(6_000, None), 10).len(), 1);
+		// The grace delay of 10 seconds is 20 Movement blocks.
+		assert!(time_locks.expired(&clocks(4_000, Some(1_019)), 10).is_empty());
+		assert_eq!(
+			policy.expiry_secs(ChainId::TWO, 1_000, &clocks(4_000, Some(1_020))),
+			Some(3_990)
+		);
+		// The lock expired 520 seconds ago, before the initiation.
+		let expired = time_locks.expired(&clocks(5_010, Some(2_040)), 10);
+		let expired: Vec<_> = expired.iter().map(|(_, action, _)| *action).collect();
+		assert_eq!(expired, [ExpiryAction::Abort, ExpiryAction::Refund]);
+	}
+}
@@ -0,0 +1,453 @@
+use crate::labels::AddressLabels;
+use bridge_indexer_db::client::{BridgeEventPackage, Client};
+use bridge_indexer_db::models::{
+	IndexedTransfer, IndexedTransferState, Submission, TransferFilter,
+};
+use bridge_util::reason::{ReasonCode, WithReasonCode};
+use bridge_util::types::{BridgeTransferId, DisplayAddress};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+pub const DEFAULT_PAGE_SIZE: i64 = 50;
+pub const MAX_PAGE_SIZE: i64 = 200;
+
+/// Query of the transfer search endpoint. Times are unix timestamps in seconds,
+/// `page` is the cursor returned as `next_page` by the previous page.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TransferQuery {
+	pub initiator: Option<String>,
+	pub recipient: Option<String>,
+	pub state: Option<IndexedTransferState>,
+	pub token: Option<String>,
+	pub from: Option<i64>,
+	pub to: Option<i64>,
+	pub page: Option<String>,
+	pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TransferSummary {
+	pub bridge_transfer_id: String,
+	pub initiator: String,
+	pub recipient: String,
+	/// Labels of the addresses, when address labels are configured and know them.
+	pub initiator_label: Option<String>,
+	pub recipient_label: Option<String>,
+	pub hash_lock: String,
+	pub time_lock: i64,
+	pub amount: String,
+	pub token: String,
+	pub state: IndexedTransferState,
+	pub created_at_secs: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TransferPage {
+	pub transfers: Vec<TransferSummary>,
+	/// Cursor of the next page, missing on the last page.
+	pub next_page: Option<String>,
+}
+
+/// Query of the transfer state endpoint, the unix timestamp in seconds of the time
+/// the state is reconstructed at.
+#[derive(Debug, Clone, Deserialize)]
+pub struct StateAtQuery {
+	pub at: i64,
+}
+
+/// A contract event of a transfer, as indexed by the relayer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HistoryEvent {
+	pub kind: &'static str,
+	pub indexed_at_secs: i64,
+}
+
+/// A transaction the relayer planned to submit for a transfer.
+#[derive(Debug, Clone, Serialize)]
+pub struct HistorySubmission {
+	pub chain: String,
+	pub action_kind: String,
+	pub planned_at_secs: i64,
+	/// Status at the time of the query. Only the last status update is recorded,
+	/// None when the status has been updated after that time: the submission was
+	/// then planned or sent.
+	pub status: Option<String>,
+}
+
+/// What the relayer knew about a transfer at a past time, reconstructed from
+/// the events and the submissions recorded by the indexer until then.
+#[derive(Debug, Clone, Serialize)]
+pub struct TransferStateAt {
+	pub bridge_transfer_id: String,
+	pub at_secs: i64,
+	/// None if the transfer was not indexed yet.
+	pub state: Option<IndexedTransferState>,
+	/// The events indexed until then, oldest first.
+	pub events: Vec<HistoryEvent>,
+	/// The submissions planned until then, oldest first.
+	pub submissions: Vec<HistorySubmission>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TransferSearchError {
+	#[error("Invalid query: {0}")]
+	InvalidQuery(String),
+	#[error("Transfer search is not enabled")]
+	Disabled,
+	#[error("Indexer query failed: {0}")]
+	Indexer(String),
+}
+
+impl WithReasonCode for TransferSearchError {
+	fn reason_code(&self) -> ReasonCode {
+		match self {
+			TransferSearchError::InvalidQuery(_) => ReasonCode::InvalidRequest,
+			TransferSearchError::Disabled => ReasonCode::FeatureDisabled,
+			TransferSearchError::Indexer(_) => ReasonCode::Unavailable,
+		}
+	}
+}
+
+/// Searches the transfers recorded by the indexer.
+/// Clones share the same indexer connection.
+#[derive(Clone)]
+pub struct TransferSearch {
+	// The token relayed by this bridge instance.
+	token: String,
+	client: Arc<Mutex<Client>>,
+	labels: Option<AddressLabels>,
+}
+
+impl TransferSearch {
+	pub fn new(token: String, client: Client) -> Self {
+		TransferSearch { token, client: Arc::new(Mutex::new(client)), labels: None }
+	}
+
+	pub fn with_labels(mut self, labels: AddressLabels) -> Self {
+		self.labels = Some(labels);
+		self
+	}
+
+	pub async fn search(&self, query: TransferQuery) -> Result<TransferPage, TransferSearchError> {
+		let (filter, limit) = query.filter()?;
+		// The indexer only records the token relayed by this instance.
+		if query
+			.token
+			.as_ref()
+			.is_some_and(|token| !token.eq_ignore_ascii_case(&self.token))
+		{
+			return Ok(TransferPage { transfers: Vec::new(), next_page: None });
+		}
+
+		let client = self.client.clone();
+		let transfers = tokio::task::spawn_blocking(move || {
+			client
+				.lock()
+				.expect("Transfer search client lock poisoned")
+				.search_transfers(&filter, limit)
+		})
+		.await
+		.map_err(|err| TransferSearchError::Indexer(err.to_string()))?
+		.map_err(|err| TransferSearchError::Indexer(err.to_string()))?;
+
+		let next_page = match transfers.last() {
+			Some(last) if transfers.len() as i64 == limit => Some(last.initiated.id.to_string()),
+			_ => None,
+		};
+		let mut summaries = Vec::with_capacity(transfers.len());
+		for transfer in transfers {
+			let (initiator_label, recipient_label) = match &self.labels {
+				Some(labels) => (
+					stored_address_label(labels, &transfer.initiated.initiator).await,
+					stored_address_label(labels, &transfer.initiated.recipient).await,
+				),
+				None => (None, None),
+			};
+			summaries.push(TransferSummary {
+				initiator_label,
+				recipient_label,
+				..self.summary(transfer)
+			});
+		}
+		Ok(TransferPage { transfers: summaries, next_page })
+	}
+
+	/// Reconstruct the state of the transfer at the given unix timestamp.
+	pub async fn state_at(
+		&self,
+		transfer_id: &str,
+		at: i64,
+	) -> Result<TransferStateAt, TransferSearchError> {
+		let transfer_id =
+			BridgeTransferId::parse(transfer_id.strip_prefix("0x").unwrap_or(transfer_id))
+				.map_err(|err| {
+					TransferSearchError::InvalidQuery(format!("invalid transfer id: {err}"))
+				})?;
+		let at_time = to_datetime(at)?;
+
+		let client = self.client.clone();
+		let (events, submissions) = tokio::task::spawn_blocking(move || {
+			let mut client = client.lock().expect("Transfer search client lock poisoned");
+			let events = client.find_all_events_for_bridge_transfer_id(transfer_id)?;
+			let submissions = client.get_submissions_for_bridge_transfer_id(transfer_id)?;
+			Ok::<_, anyhow::Error>((events, submissions))
+		})
+		.await
+		.map_err(|err| TransferSearchError::Indexer(err.to_string()))?
+		.map_err(|err| TransferSearchError::Indexer(err.to_string()))?;
+
+		let events = events_until(&events, at_time);
+		Ok(TransferStateAt {
+			bridge_transfer_id: format!("0x{}", hex::encode(transfer_id.0)),
+			at_secs: at,
+			state: state_of(&events),
+			events,
+			submissions: submissions_until(submissions, at_time),
+		})
+	}
+
+	fn summary(&self, transfer: IndexedTransfer) -> TransferSummary {
+		let initiated = transfer.initiated;
+		TransferSummary {
+			bridge_transfer_id: format!("0x{}", initiated.bridge_transfer_id),
+			initiator: display_hex_address(&initiated.initiator),
+			recipient: display_hex_address(&initiated.recipient),
+			initiator_label: None,
+			recipient_label: None,
+			hash_lock: format!("0x{}", initiated.hash_lock),
+			time_lock: initiated.time_lock,
+			amount: initiated.amount.to_string(),
+			token: self.token.clone(),
+			state: transfer.state,
+			created_at_secs: initiated.created_at.and_utc().timestamp(),
+		}
+	}
+}
+
+impl TransferQuery {
+	fn filter(&self) -> Result<(TransferFilter, i64), TransferSearchError> {
+		let limit = self.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+		if !(1..=MAX_PAGE_SIZE).contains(&limit) {
+			return Err(TransferSearchError::InvalidQuery(format!(
+				"limit must be between 1 and {MAX_PAGE_SIZE}"
+			)));
+		}
+		if let (Some(from), Some(to)) = (self.from, self.to) {
+			if from >= to {
+				return Err(TransferSearchError::InvalidQuery(
+					"from must be before to".to_string(),
+				));
+			}
+		}
+		let before_id =
+			self.page.as_deref().map(str::parse).transpose().map_err(|_| {
+				TransferSearchError::InvalidQuery("invalid page cursor".to_string())
+			})?;
+
+		let filter = TransferFilter {
+			initiator: self.initiator.as_deref().map(normalize_address).transpose()?,
+			recipient: self.recipient.as_deref().map(normalize_address).transpose()?,
+			state: self.state,
+			from: self.from.map(to_datetime).transpose()?,
+			to: self.to.map(to_datetime).transpose()?,
+			before_id,
+		};
+		Ok((filter, limit))
+	}
+}
+
+// The indexer stores the addresses lowercase hex encoded without prefix.
+fn normalize_address(address: &str) -> Result<String, TransferSearchError> {
+	let address = address.strip_prefix("0x").unwrap_or(address).to_ascii_lowercase();
+	hex::decode(&address)
+		.map_err(|_| TransferSearchError::InvalidQuery(format!("invalid address 0x{address}")))?;
+	Ok(address)
+}
+
+// Render an address stored by the indexer in the canonical form of its chain.
+fn display_hex_address(address: &str) -> String {
+	match hex::decode(address) {
+		Ok(bytes) => DisplayAddress(&bytes).to_string(),
+		Err(_) => format!("0x{address}"),
+	}
+}
+
+// Label of an address stored by the indexer.
+async fn stored_address_label(labels: &AddressLabels, address: &str) -> Option<String> {
+	labels.label(&hex::decode(address).ok()?).await
+}
+
+// The events of the package indexed until the time, oldest first.
+fn events_until(package: &BridgeEventPackage, at: chrono::NaiveDateTime) -> Vec<HistoryEvent> {
+	let times = package
+		.initiated_events
+		.iter()
+		.map(|event| ("initiated", event.created_at))
+		.chain(package.locked_events.iter().map(|event| ("locked", event.created_at)))
+		.chain(
+			package
+				.counter_party_completed_events
+				.iter()
+				.map(|event| ("counter_party_completed", event.created_at)),
+		)
+		.chain(
+			package
+				.initiator_completed_events
+				.iter()
+				.map(|event| ("initiator_completed", event.created_at)),
+		)
+		.chain(package.cancelled_events.iter().map(|event| ("cancelled", event.created_at)))
+		.chain(package.refunded_events.iter().map(|event| ("refunded", event.created_at)));
+	let mut events: Vec<HistoryEvent> = times
+		.filter(|(_, created_at)| *created_at <= at)
+		.map(|(kind, created_at)| HistoryEvent {
+			kind,
+			indexed_at_secs: created_at.and_utc().timestamp(),
+		})
+		.collect();
+	events.sort_by_key(|event| event.indexed_at_secs);
+	events
+}
+
+// State of a transfer with the events, with the precedence of the transfer search.
+fn state_of(events: &[HistoryEvent]) -> Option<IndexedTransferState> {
+	let has = |kind: &str| events.iter().any(|event| event.kind == kind);
+	if has("refunded") {
+		Some(IndexedTransferState::Refunded)
+	} else if has("cancelled") {
+		Some(IndexedTransferState::Cancelled)
+	} else if has("initiator_completed") {
+		Some(IndexedTransferState::Completed)
+	} else if has("counter_party_completed") {
+		Some(IndexedTransferState::SecretReceived)
+	} else if has("locked") {
+		Some(IndexedTransferState::Locked)
+	} else if has("initiated") {
+		Some(IndexedTransferState::Initiated)
+	} else {
+		None
+	}
+}
+
+// The submissions planned until the time, with their status then if known.
+fn submissions_until(
+	submissions: Vec<Submission>,
+	at: chrono::NaiveDateTime,
+) -> Vec<HistorySubmission> {
+	submissions
+		.into_iter()
+		.filter(|submission| submission.created_at <= at)
+		.map(|submission| HistorySubmission {
+			planned_at_secs: submission.created_at.and_utc().timestamp(),
+			status: (submission.updated_at <= at).then_some(submission.status),
+			chain: submission.chain,
+			action_kind: submission.action_kind,
+		})
+		.collect()
+}
+
+fn to_datetime(secs: i64) -> Result<chrono::NaiveDateTime, TransferSearchError> {
+	chrono::DateTime::from_timestamp(secs, 0)
+		.map(|datetime| datetime.naive_utc())
+		.ok_or_else(|| TransferSearchError::InvalidQuery(format!("invalid timestamp {secs}")))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bridge_indexer_db::models::{CounterPartyCompletedEvent, RefundedEvent};
+
+	#[test]
+	fn test_query_filter() {
+		let query = TransferQuery {
+			initiator: Some("0xABCD".to_string()),
+			state: Some(IndexedTransferState::Locked),
+			from: Some(1_700_000_000),
+			to: Some(1_700_003_600),
+			page: Some("42".to_string()),
+			..Default::default()
+		};
+		let (filter, limit) = query.filter().unwrap();
+		assert_eq!(limit, DEFAULT_PAGE_SIZE);
+		assert_eq!(filter.initiator.as_deref(), Some("abcd"));
+		assert_eq!(filter.recipient, None);
+		assert_eq!(filter.state, Some(IndexedTransferState::Locked));
+		assert_eq!(filter.before_id, Some(42));
+		assert_eq!(filter.from.unwrap().and_utc().timestamp(), 1_700_000_000);
+
+		let invalid = [
+			TransferQuery { recipient: Some("0xnothex".to_string()), ..Default::default() },
+			TransferQuery { from: Some(10), to: Some(5), ..Default::default() },
+			TransferQuery { page: Some("next".to_string()), ..Default::default() },
+			TransferQuery { limit: Some(MAX_PAGE_SIZE + 1), ..Default::default() },
+		];
+		for query in invalid {
+			assert!(matches!(query.filter(), Err(TransferSearchError::InvalidQuery(_))));
+		}
+	}
+
+	#[test]
+	fn test_state_at() {
+		let time = |secs| to_datetime(secs).unwrap();
+		let package = BridgeEventPackage {
+			initiated_events: Vec::new(),
+			locked_events: Vec::new(),
+			initiator_completed_events: Vec::new(),
+			counter_party_completed_events: vec![CounterPartyCompletedEvent {
+				id: 1,
+				bridge_transfer_id: "ab".to_string(),
+				pre_image: "cd".to_string(),
+				created_at: time(300),
+			}],
+			cancelled_events: Vec::new(),
+			refunded_events: vec![RefundedEvent {
+				id: 1,
+				bridge_transfer_id: "ab".to_string(),
+				created_at: time(200),
+			}],
+		};
+		assert_eq!(state_of(&events_until(&package, time(100))), None);
+		let events = events_until(&package, time(300));
+		assert_eq!(
+			events.iter().map(|event| event.kind).collect::<Vec<_>>(),
+			["refunded", "counter_party_completed"]
+		);
+		assert_eq!(state_of(&events), Some(IndexedTransferState::Refunded));
+		assert_eq!(
+			state_of(&events_until(
+				&BridgeEventPackage { refunded_events: Vec::new(), ..package },
+				time(300)
+			)),
+			Some(IndexedTransferState::SecretReceived)
+		);
+
+		let submission = |created_at, updated_at| Submission {
+			id: 1,
+			chain: "1".to_string(),
+			bridge_transfer_id: "ab".to_string(),
+			action_kind: "LockBridgeTransfer".to_string(),
+			status: "confirmed".to_string(),
+			created_at: time(created_at),
+			updated_at: time(updated_at),
+			tx_hash: None,
+		};
+		let submissions = submissions_until(
+			vec![submission(100, 150), submission(100, 250), submission(250, 250)],
+			time(200),
+		);
+		assert_eq!(submissions.len(), 2);
+		assert_eq!(submissions[0].status.as_deref(), Some("confirmed"));
+		assert_eq!(submissions[1].status, None);
+	}
+
+	#[test]
+	fn test_display_hex_address() {
+		assert_eq!(
+			display_hex_address("5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"),
+			"0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+		);
+		assert_eq!(display_hex_address(&format!("{}01", "00".repeat(31))), "0x1");
+		let movement_address = format!("{}10", "00".repeat(31));
+		assert_eq!(display_hex_address(&movement_address), format!("0x{movement_address}"));
+	}
+}
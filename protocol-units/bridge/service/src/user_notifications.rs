@@ -0,0 +1,400 @@
+use crate::event_bus::{BusEvent, StateChange, Subscription};
+use bridge_config::common::notifications::NotificationsConfig;
+use bridge_util::chains::bridge_contracts::BridgeContractEvent;
+use bridge_util::reason::{ReasonCode, WithReasonCode};
+use bridge_util::states::TransferStateType;
+use bridge_util::types::{BridgeTransferId, HashLock};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+// Length of the window of the rate limit of each destination.
+const RATE_LIMIT_WINDOW_SECS: u64 = 60 * 60;
+
+/// Where the notifications of a registration are sent.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Destination {
+	/// Url receiving a JSON POST of each notification.
+	Webhook(String),
+	/// Email address, sent through the email gateway of the config.
+	Email(String),
+}
+
+/// Registration of an end user for the notifications of a transfer. The transfer is
+/// identified by its id, or by its hash lock when the user registers before initiating it.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RegistrationRequest {
+	#[serde(default)]
+	pub transfer_id: Option<String>,
+	#[serde(default)]
+	pub hash_lock: Option<String>,
+	pub destination: Destination,
+}
+
+/// Accepted registration, its token opts out of the notifications.
+#[derive(Debug, Clone, Serialize)]
+pub struct Registration {
+	pub opt_out_token: String,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum RegistrationError {
+	#[error("Either transfer_id or hash_lock is required")]
+	MissingTransfer,
+	#[error("Invalid {0}")]
+	Invalid(&'static str),
+	#[error("Email notifications are not enabled")]
+	EmailDisabled,
+	#[error("Too many registrations, retry later")]
+	Full,
+}
+
+impl WithReasonCode for RegistrationError {
+	fn reason_code(&self) -> ReasonCode {
+		match self {
+			RegistrationError::MissingTransfer | RegistrationError::Invalid(_) => {
+				ReasonCode::InvalidRequest
+			}
+			RegistrationError::EmailDisabled => ReasonCode::FeatureDisabled,
+			RegistrationError::Full => ReasonCode::RateLimited,
+		}
+	}
+}
+
+/// Stage of a transfer the end user is notified of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+	/// Locked on the counterpart chain, the recipient must claim it.
+	Locked,
+	Completed,
+	/// Refunded to the initiator or aborted.
+	Refunded,
+}
+
+impl Stage {
+	fn of(change: &StateChange) -> Option<Self> {
+		match change.new {
+			TransferStateType::Locked => Some(Stage::Locked),
+			TransferStateType::Completed => Some(Stage::Completed),
+			TransferStateType::Refunded | TransferStateType::Aborted => Some(Stage::Refunded),
+			_ => None,
+		}
+	}
+
+	/// Action expected from the end user at this stage.
+	pub fn required_action(&self) -> Option<&'static str> {
+		match self {
+			Stage::Locked => Some(
+				"Claim the transfer on the counterpart chain with your secret before its time lock expires",
+			),
+			Stage::Completed | Stage::Refunded => None,
+		}
+	}
+}
+
+/// Payload of a notification.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UserNotification {
+	pub transfer_id: String,
+	pub stage: Stage,
+	pub required_action: Option<&'static str>,
+	pub timestamp_secs: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TransferKey {
+	Id(BridgeTransferId),
+	HashLock(HashLock),
+}
+
+#[derive(Debug, Default)]
+struct NotificationState {
+	// Registrations by opt-out token.
+	registrations: HashMap<String, (TransferKey, Destination)>,
+	// Start of the rate limit window of each destination and the notifications sent in it.
+	sent: HashMap<Destination, (u64, u32)>,
+}
+
+/// Registrations of the end users, notified of the lock, the completion or the refund
+/// of their transfers from the event bus. Clones share the same registrations.
+#[derive(Debug, Clone)]
+pub struct UserNotifications {
+	config: NotificationsConfig,
+	state: Arc<Mutex<NotificationState>>,
+	client: reqwest::Client,
+}
+
+impl UserNotifications {
+	pub fn new(config: NotificationsConfig) -> Self {
+		UserNotifications {
+			config,
+			state: Arc::new(Mutex::new(NotificationState::default())),
+			client: reqwest::Client::new(),
+		}
+	}
+
+	/// Build the notifications from the config, None if they are disabled.
+	pub fn from_config(config: &NotificationsConfig) -> Option<Self> {
+		config.enabled.then(|| UserNotifications::new(config.clone()))
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<'_, NotificationState> {
+		self.state.lock().expect("User notifications lock poisoned")
+	}
+
+	pub fn register(
+		&self,
+		request: RegistrationRequest,
+	) -> Result<Registration, RegistrationError> {
+		let key = match (&request.transfer_id, &request.hash_lock) {
+			(Some(id), _) => TransferKey::Id(
+				BridgeTransferId::parse(id.strip_prefix("0x").unwrap_or(id))
+					.map_err(|_| RegistrationError::Invalid("transfer id"))?,
+			),
+			(None, Some(hash_lock)) => TransferKey::HashLock(
+				HashLock::parse(hash_lock.strip_prefix("0x").unwrap_or(hash_lock))
+					.map_err(|_| RegistrationError::Invalid("hash lock"))?,
+			),
+			(None, None) => return Err(RegistrationError::MissingTransfer),
+		};
+		match &request.destination {
+			Destination::Webhook(url) => match url::Url::parse(url) {
+				Ok(url) if matches!(url.scheme(), "http" | "https") => (),
+				_ => return Err(RegistrationError::Invalid("webhook url")),
+			},
+			Destination::Email(_) if self.config.email_gateway_url.is_none() => {
+				return Err(RegistrationError::EmailDisabled)
+			}
+			Destination::Email(email) => {
+				if !email
+					.split_once('@')
+					.is_some_and(|(user, domain)| !user.is_empty() && domain.contains('.'))
+				{
+					return Err(RegistrationError::Invalid("email"));
+				}
+			}
+		}
+
+		let mut state = self.lock();
+		if state.registrations.len() as u64 >= self.config.max_registrations {
+			return Err(RegistrationError::Full);
+		}
+		let opt_out_token = hex::encode(rand::random::<[u8; 16]>());
+		state.registrations.insert(opt_out_token.clone(), (key, request.destination));
+		Ok(Registration { opt_out_token })
+	}
+
+	/// Remove the registration of the token, false if there is none.
+	pub fn opt_out(&self, opt_out_token: &str) -> bool {
+		self.lock().registrations.remove(opt_out_token).is_some()
+	}
+
+	/// Notify the registered end users until the bus is dropped.
+	pub async fn run(self, mut events: Subscription) {
+		while let Some(event) = events.recv().await {
+			for (destination, notification) in self.notifications(&event, now_secs()) {
+				self.deliver(&destination, &notification).await;
+			}
+		}
+	}
+
+	// The notifications of an event of the bus, within the rate limit of their destination.
+	fn notifications(
+		&self,
+		event: &BusEvent,
+		now_secs: u64,
+	) -> Vec<(Destination, UserNotification)> {
+		let mut state = self.lock();
+		match event {
+			// The registrations by hash lock are bound to the transfer once it's initiated.
+			BusEvent::Contract(event) => {
+				if let BridgeContractEvent::Initiated(details) = &event.contract_event {
+					for (key, _) in state.registrations.values_mut() {
+						if *key == TransferKey::HashLock(details.hash_lock) {
+							*key = TransferKey::Id(details.bridge_transfer_id);
+						}
+					}
+				}
+				Vec::new()
+			}
+			BusEvent::StateChange(change) => {
+				let Some(stage) = Stage::of(change) else {
+					return Vec::new();
+				};
+				let key = TransferKey::Id(change.transfer_id);
+				let destinations: Vec<Destination> = state
+					.registrations
+					.values()
+					.filter(|(registered, _)| *registered == key)
+					.map(|(_, destination)| destination.clone())
+					.collect();
+				// Nothing is notified after the end of the transfer.
+				if change.new.is_final() {
+					state.registrations.retain(|_, (registered, _)| *registered != key);
+				}
+				let notification = UserNotification {
+					transfer_id: format!("0x{}", hex::encode(change.transfer_id.0)),
+					stage,
+					required_action: stage.required_action(),
+					timestamp_secs: now_secs,
+				};
+				destinations
+					.into_iter()
+					.filter(|destination| {
+						let (window_start, count) =
+							state.sent.entry(destination.clone()).or_insert((now_secs, 0));
+						if now_secs >= *window_start + RATE_LIMIT_WINDOW_SECS {
+							*window_start = now_secs;
+							*count = 0;
+						}
+						*count += 1;
+						let allowed = *count <= self.config.max_per_hour;
+						if !allowed {
+							tracing::warn!(
+								"Notification rate limit of {destination:?} reached, {:?} of transfer {} dropped",
+								stage,
+								notification.transfer_id
+							);
+						}
+						allowed
+					})
+					.map(|destination| (destination, notification.clone()))
+					.collect()
+			}
+			BusEvent::Action(..) => Vec::new(),
+		}
+	}
+
+	async fn deliver(&self, destination: &Destination, notification: &UserNotification) {
+		let request = match destination {
+			Destination::Webhook(url) => self.client.post(url).json(notification),
+			Destination::Email(email) => {
+				let Some(gateway_url) = &self.config.email_gateway_url else {
+					return;
+				};
+				let mut body = format!(
+					"Your bridge transfer {} is {:?}.",
+					notification.transfer_id, notification.stage
+				);
+				if let Some(action) = notification.required_action {
+					body.push_str(&format!(" {action}."));
+				}
+				self.client.post(gateway_url).json(&serde_json::json!({
+					"to": email,
+					"subject": format!("Bridge transfer {:?}", notification.stage),
+					"body": body,
+				}))
+			}
+		};
+		match request.send().await {
+			Ok(response) if !response.status().is_success() => tracing::warn!(
+				"Notification {:?} of transfer {} rejected: {}",
+				notification.stage,
+				notification.transfer_id,
+				response.status()
+			),
+			Ok(_) => (),
+			Err(err) => tracing::warn!(
+				"Failed to send notification {:?} of transfer {}: {err}",
+				notification.stage,
+				notification.transfer_id
+			),
+		}
+	}
+}
+
+fn now_secs() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|d| d.as_secs())
+		.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::event_bus::EventRef;
+	use bridge_util::events::TransferEvent;
+	use bridge_util::types::{Amount, BridgeAddress, BridgeTransferDetails, ChainId, TimeLock};
+
+	#[test]
+	fn test_user_notifications() {
+		let notifications = UserNotifications::new(NotificationsConfig {
+			enabled: true,
+			max_per_hour: 2,
+			..Default::default()
+		});
+		let transfer_id = BridgeTransferId([1; 32]);
+		let hash_lock = HashLock([2; 32]);
+		let webhook = Destination::Webhook("http://localhost/hook".to_string());
+		let registration = |hash_lock: &str, destination| RegistrationRequest {
+			transfer_id: None,
+			hash_lock: Some(hash_lock.to_string()),
+			destination,
+		};
+		let hash_lock_hex = format!("0x{}", hex::encode(hash_lock.0));
+		notifications.register(registration(&hash_lock_hex, webhook.clone())).unwrap();
+		let opted_out = notifications
+			.register(registration(&hash_lock_hex, Destination::Webhook("https://x.io".into())))
+			.unwrap();
+		assert!(notifications.opt_out(&opted_out.opt_out_token));
+		assert!(!notifications.opt_out(&opted_out.opt_out_token));
+		assert_eq!(
+			notifications
+				.register(registration(&hash_lock_hex, Destination::Email("a@b.io".into())))
+				.unwrap_err(),
+			RegistrationError::EmailDisabled
+		);
+		assert_eq!(
+			notifications.register(registration("0x12", webhook.clone())).unwrap_err(),
+			RegistrationError::Invalid("hash lock")
+		);
+
+		let initiated = BusEvent::from(TransferEvent {
+			chain: ChainId::ONE,
+			contract_event: BridgeContractEvent::Initiated(BridgeTransferDetails {
+				bridge_transfer_id: transfer_id,
+				initiator: BridgeAddress(vec![3; 20]),
+				recipient: BridgeAddress(vec![4; 32]),
+				hash_lock,
+				time_lock: TimeLock(100),
+				amount: Amount(10),
+				state: 0,
+			}),
+		});
+		assert!(notifications.notifications(&initiated, 0).is_empty());
+		let change = |sequence, new, event| {
+			BusEvent::StateChange(StateChange {
+				transfer_id,
+				sequence,
+				previous: None,
+				new,
+				cause: Some(EventRef { chain: ChainId::TWO, event, transfer_id }),
+				reason: None,
+			})
+		};
+
+		let locked =
+			notifications.notifications(&change(2, TransferStateType::Locked, "Locked"), 0);
+		assert_eq!(locked.len(), 1);
+		assert_eq!(locked[0].0, webhook);
+		assert_eq!(locked[0].1.stage, Stage::Locked);
+		assert!(locked[0].1.required_action.is_some());
+		let initialized = change(1, TransferStateType::Initialized, "Initiated");
+		assert!(notifications.notifications(&initialized, 0).is_empty());
+		let locked = change(2, TransferStateType::Locked, "Locked");
+		assert_eq!(notifications.notifications(&locked, 10).len(), 1);
+		// The rate limit of the destination is reached until the next window.
+		assert!(notifications.notifications(&locked, 20).is_empty());
+		let refunded = change(3, TransferStateType::Aborted, "Cancelled");
+		let refunded = notifications.notifications(&refunded, RATE_LIMIT_WINDOW_SECS);
+		assert_eq!(refunded[0].1.stage, Stage::Refunded);
+		// The registration ends with the transfer.
+		let completed = change(4, TransferStateType::Completed, "InitiatorCompleted");
+		assert!(notifications.notifications(&completed, RATE_LIMIT_WINDOW_SECS).is_empty());
+	}
+}
@@ -0,0 +1,262 @@
+//! Canonical test vectors of the relayer encodings: preimages, hash locks, transfer ids,
+//! Ethereum calldata and Movement BCS payloads, derived from agreed parameters. The Solidity
+//! and Move contract teams check their implementations against them.
+use crate::chains::ethereum::types::{AtomicBridgeCounterpartyMOVE, AtomicBridgeInitiatorMOVE};
+use crate::chains::movement::client_framework::{
+	COUNTERPARTY_MODULE_NAME, FRAMEWORK_ADDRESS, INITIATOR_MODULE_NAME,
+};
+use crate::chains::movement::utils::make_aptos_payload;
+use crate::pre_image::PreImageFormat;
+use alloy::primitives::{keccak256, Address, FixedBytes, U256};
+use alloy::sol_types::SolCall;
+use aptos_sdk::types::account_address::AccountAddress;
+use bridge_util::types::{BridgeTransferId, HashLock, HashLockAlgorithm, HashLockPreImage};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Parameters the vectors are derived from. The same parameters always give the same
+/// vectors.
+#[derive(Debug, Clone)]
+pub struct VectorParams {
+	/// Seed of the preimages.
+	pub seed: String,
+	/// Number of vectors.
+	pub count: u32,
+	/// Ethereum account initiating and receiving the transfers.
+	pub eth_address: Address,
+	/// Movement account initiating and receiving the transfers.
+	pub movement_address: AccountAddress,
+	pub amount: u64,
+	/// Time lock duration of the initiator contract, in seconds.
+	pub time_lock_duration: u64,
+	/// Timestamp of the block of the initiations.
+	pub timestamp: u64,
+	/// Nonce of the initiator contract at the first initiation, incremented by each vector.
+	pub first_nonce: u64,
+	pub pre_image_format: PreImageFormat,
+	/// Digest of the hash locks, the same on both chains.
+	pub hash_lock_algorithm: HashLockAlgorithm,
+}
+
+/// Encodings of a transfer, all the bytes are hex encoded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TestVector {
+	pub index: u32,
+	/// Preimage taken by the Ethereum contracts, as `bytes32`.
+	pub pre_image: String,
+	/// Preimage taken and emitted by the Move modules, in the format of the parameters.
+	pub move_pre_image: String,
+	/// Digest of the Ethereum preimage, with the algorithm of the parameters.
+	pub hash_lock: String,
+	/// Id of the transfer initiated on Ethereum, as computed by the initiator contract.
+	pub transfer_id: String,
+	pub nonce: u64,
+	/// Calldata of the calls of the Ethereum contracts, by function.
+	pub eth_calldata: BTreeMap<String, String>,
+	/// BCS encoded entry function payloads of the Move modules, by module and function.
+	pub movement_payloads: BTreeMap<String, String>,
+}
+
+/// Generate the vectors of the parameters.
+pub fn generate_vectors(params: &VectorParams) -> Result<Vec<TestVector>, anyhow::Error> {
+	(0..params.count).map(|index| generate_vector(params, index)).collect()
+}
+
+fn generate_vector(params: &VectorParams, index: u32) -> Result<TestVector, anyhow::Error> {
+	let pre_image = derive_pre_image(params, index);
+	let move_pre_image = params.pre_image_format.to_move_bytes(&pre_image)?;
+	let hash_lock = HashLock::of_pre_image(&pre_image.0, params.hash_lock_algorithm);
+	let nonce = params.first_nonce + u64::from(index);
+	let transfer_id = eth_transfer_id(params, hash_lock, nonce);
+	let eth_address = params.eth_address.to_vec();
+
+	let mut eth_calldata = BTreeMap::new();
+	let mut calldata = |name: &str, calldata: Vec<u8>| {
+		eth_calldata.insert(name.to_string(), hex::encode(calldata));
+	};
+	calldata(
+		"initiator.initiateBridgeTransfer",
+		AtomicBridgeInitiatorMOVE::initiateBridgeTransferCall {
+			moveAmount: U256::from(params.amount),
+			recipient: FixedBytes(params.movement_address.into_bytes()),
+			hashLock: FixedBytes(hash_lock.0),
+		}
+		.abi_encode(),
+	);
+	calldata(
+		"initiator.completeBridgeTransfer",
+		AtomicBridgeInitiatorMOVE::completeBridgeTransferCall {
+			bridgeTransferId: FixedBytes(transfer_id.0),
+			preImage: FixedBytes(pre_image.0),
+		}
+		.abi_encode(),
+	);
+	calldata(
+		"initiator.refundBridgeTransfer",
+		AtomicBridgeInitiatorMOVE::refundBridgeTransferCall {
+			bridgeTransferId: FixedBytes(transfer_id.0),
+		}
+		.abi_encode(),
+	);
+	calldata(
+		"counterparty.lockBridgeTransfer",
+		AtomicBridgeCounterpartyMOVE::lockBridgeTransferCall {
+			originator: FixedBytes(params.movement_address.into_bytes()),
+			bridgeTransferId: FixedBytes(transfer_id.0),
+			hashLock: FixedBytes(hash_lock.0),
+			recipient: params.eth_address,
+			amount: U256::from(params.amount),
+		}
+		.abi_encode(),
+	);
+	calldata(
+		"counterparty.completeBridgeTransfer",
+		AtomicBridgeCounterpartyMOVE::completeBridgeTransferCall {
+			bridgeTransferId: FixedBytes(transfer_id.0),
+			preImage: FixedBytes(pre_image.0),
+		}
+		.abi_encode(),
+	);
+	calldata(
+		"counterparty.abortBridgeTransfer",
+		AtomicBridgeCounterpartyMOVE::abortBridgeTransferCall {
+			bridgeTransferId: FixedBytes(transfer_id.0),
+		}
+		.abi_encode(),
+	);
+
+	// The arguments are BCS encoded like the Movement client does.
+	let id = bcs::to_bytes(&transfer_id.0[..])?;
+	let mut movement_payloads = BTreeMap::new();
+	let mut payload = |module: &'static str, function: &'static str, args: Vec<Vec<u8>>| {
+		let payload = make_aptos_payload(FRAMEWORK_ADDRESS, module, function, Vec::new(), args);
+		bcs::to_bytes(&payload).map(|bytes| {
+			movement_payloads.insert(format!("{module}::{function}"), hex::encode(bytes));
+		})
+	};
+	payload(
+		INITIATOR_MODULE_NAME,
+		"initiate_bridge_transfer",
+		vec![
+			bcs::to_bytes(&eth_address)?,
+			bcs::to_bytes(&hash_lock.0[..])?,
+			bcs::to_bytes(&params.amount)?,
+		],
+	)?;
+	payload(
+		INITIATOR_MODULE_NAME,
+		"complete_bridge_transfer",
+		vec![id.clone(), bcs::to_bytes(&move_pre_image)?],
+	)?;
+	payload(INITIATOR_MODULE_NAME, "refund_bridge_transfer", vec![id.clone()])?;
+	payload(
+		COUNTERPARTY_MODULE_NAME,
+		"lock_bridge_transfer_assets",
+		vec![
+			bcs::to_bytes(&eth_address)?,
+			id.clone(),
+			bcs::to_bytes(&hash_lock.0[..])?,
+			bcs::to_bytes(&params.movement_address)?,
+			bcs::to_bytes(&params.amount)?,
+		],
+	)?;
+	payload(
+		COUNTERPARTY_MODULE_NAME,
+		"complete_bridge_transfer",
+		vec![id.clone(), bcs::to_bytes(&move_pre_image)?],
+	)?;
+	payload(COUNTERPARTY_MODULE_NAME, "abort_bridge_transfer", vec![id])?;
+
+	Ok(TestVector {
+		index,
+		pre_image: hex::encode(pre_image.0),
+		move_pre_image: hex::encode(move_pre_image),
+		hash_lock: hex::encode(hash_lock.0),
+		transfer_id: hex::encode(transfer_id.0),
+		nonce,
+		eth_calldata,
+		movement_payloads,
+	})
+}
+
+// The preimage of the vector, derived from the seed. The string formats take the hex of the
+// first half of the digest, a 32 bytes string the same on both chains.
+fn derive_pre_image(params: &VectorParams, index: u32) -> HashLockPreImage {
+	let digest = keccak256(format!("{}:{index}", params.seed));
+	match params.pre_image_format {
+		PreImageFormat::Raw => HashLockPreImage(digest.0),
+		PreImageFormat::Utf8 | PreImageFormat::BcsString => HashLockPreImage(
+			hex::encode(&digest[..16]).into_bytes().try_into().expect("32 hex characters"),
+		),
+	}
+}
+
+// keccak256(abi.encodePacked(originator, recipient, hashLock, initiatorTimeLockDuration,
+// block.timestamp, nonce)) of the initiator contract.
+fn eth_transfer_id(params: &VectorParams, hash_lock: HashLock, nonce: u64) -> BridgeTransferId {
+	let mut packed = Vec::with_capacity(20 + 32 * 5);
+	packed.extend_from_slice(params.eth_address.as_slice());
+	packed.extend_from_slice(params.movement_address.as_ref());
+	packed.extend_from_slice(&hash_lock.0);
+	packed.extend_from_slice(&U256::from(params.time_lock_duration).to_be_bytes::<32>());
+	packed.extend_from_slice(&U256::from(params.timestamp).to_be_bytes::<32>());
+	packed.extend_from_slice(&U256::from(nonce).to_be_bytes::<32>());
+	BridgeTransferId(keccak256(packed).0)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use aptos_sdk::types::transaction::TransactionPayload;
+
+	#[test]
+	fn test_generate_vectors() -> Result<(), anyhow::Error> {
+		let params = VectorParams {
+			seed: "bridge".to_string(),
+			count: 3,
+			eth_address: Address::repeat_byte(1),
+			movement_address: AccountAddress::new([2; 32]),
+			amount: 100,
+			time_lock_duration: 3600,
+			timestamp: 1_700_000_000,
+			first_nonce: 7,
+			pre_image_format: PreImageFormat::Utf8,
+			hash_lock_algorithm: HashLockAlgorithm::Keccak256,
+		};
+		let vectors = generate_vectors(&params)?;
+		assert_eq!(vectors, generate_vectors(&params)?);
+		assert_eq!(vectors.len(), 3);
+		assert_ne!(vectors[0].pre_image, vectors[1].pre_image);
+		assert_eq!(vectors[2].nonce, 9);
+		let sha2_params =
+			VectorParams { hash_lock_algorithm: HashLockAlgorithm::Sha2_256, ..params.clone() };
+		let sha2_vectors = generate_vectors(&sha2_params)?;
+		assert_eq!(sha2_vectors[0].pre_image, vectors[0].pre_image);
+		assert_ne!(sha2_vectors[0].hash_lock, vectors[0].hash_lock);
+		assert_ne!(sha2_vectors[0].transfer_id, vectors[0].transfer_id);
+
+		let vector = &vectors[0];
+		let pre_image = hex::decode(&vector.pre_image)?;
+		assert_eq!(hex::encode(keccak256(&pre_image)), vector.hash_lock);
+		// The 32 characters string is the same on both chains.
+		assert_eq!(vector.move_pre_image, vector.pre_image);
+
+		let lock = AtomicBridgeCounterpartyMOVE::lockBridgeTransferCall::abi_decode(
+			&hex::decode(&vector.eth_calldata["counterparty.lockBridgeTransfer"])?,
+			true,
+		)?;
+		assert_eq!(hex::encode(lock.bridgeTransferId), vector.transfer_id);
+		assert_eq!(lock.recipient, params.eth_address);
+
+		let payload: TransactionPayload = bcs::from_bytes(&hex::decode(
+			&vector.movement_payloads["atomic_bridge_counterparty::complete_bridge_transfer"],
+		)?)?;
+		let TransactionPayload::EntryFunction(entry_function) = payload else {
+			panic!("Not an entry function payload");
+		};
+		assert_eq!(entry_function.args()[1], bcs::to_bytes(&pre_image)?);
+		assert_eq!(vector.movement_payloads.len(), 6);
+		assert_eq!(vector.eth_calldata.len(), 6);
+		Ok(())
+	}
+}
@@ -0,0 +1,240 @@
+use crate::event_bus::{BusEvent, EventRef, StateChange, Subscription};
+use bridge_config::common::webhooks::WebhooksConfig;
+use bridge_util::reason::ReasonCode;
+use bridge_util::types::BridgeTransferId;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Contract event that caused a change of state.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EventReference {
+	pub chain: String,
+	pub event: &'static str,
+	pub transfer_id: String,
+}
+
+impl From<EventRef> for EventReference {
+	fn from(event: EventRef) -> Self {
+		EventReference {
+			chain: event.chain.to_string(),
+			event: event.event,
+			transfer_id: format!("0x{}", hex::encode(event.transfer_id.0)),
+		}
+	}
+}
+
+/// Payload posted on each change of state of a transfer. The sequence number
+/// increases by one on each change of the transfer, so consumers can detect missed
+/// or out of order deliveries and ask for the missing ones with the replay endpoint.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TransferNotification {
+	pub transfer_id: String,
+	pub sequence: u64,
+	pub previous_state: Option<String>,
+	pub new_state: String,
+	/// Missing when the change is caused by an operator decision or a failed action.
+	pub cause: Option<EventReference>,
+	/// Stable code of the reason of a refund, missing on the other changes.
+	pub reason_code: Option<ReasonCode>,
+	pub timestamp_secs: u64,
+}
+
+impl From<StateChange> for TransferNotification {
+	fn from(change: StateChange) -> Self {
+		TransferNotification {
+			transfer_id: format!("0x{}", hex::encode(change.transfer_id.0)),
+			sequence: change.sequence,
+			previous_state: change.previous.map(|state| state.to_string()),
+			new_state: change.new.to_string(),
+			cause: change.cause.map(EventReference::from),
+			reason_code: change.reason,
+			timestamp_secs: SystemTime::now()
+				.duration_since(UNIX_EPOCH)
+				.map(|d| d.as_secs())
+				.unwrap_or_default(),
+		}
+	}
+}
+
+/// Query of the replay endpoint: the notifications with a sequence number above `after`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ReplayQuery {
+	#[serde(default)]
+	pub after: u64,
+}
+
+/// Posts the changes of state of the transfers published on the event bus to a webhook
+/// and keeps the last notifications for replay. Clones share the same history.
+#[derive(Debug, Clone)]
+pub struct TransferWebhooks {
+	url: String,
+	history_size: usize,
+	history: Arc<Mutex<VecDeque<(BridgeTransferId, TransferNotification)>>>,
+	client: reqwest::Client,
+}
+
+impl TransferWebhooks {
+	pub fn new(url: String, history_size: usize) -> Self {
+		TransferWebhooks {
+			url,
+			history_size,
+			history: Arc::new(Mutex::new(VecDeque::new())),
+			client: reqwest::Client::new(),
+		}
+	}
+
+	/// Build the webhooks from the config, None if no url is configured.
+	pub fn from_config(config: &WebhooksConfig) -> Option<Self> {
+		let url = config.transfer_state_url.clone()?;
+		Some(TransferWebhooks::new(url, usize::try_from(config.history_size).unwrap_or(usize::MAX)))
+	}
+
+	fn lock(
+		&self,
+	) -> std::sync::MutexGuard<'_, VecDeque<(BridgeTransferId, TransferNotification)>> {
+		self.history.lock().expect("Webhook history lock poisoned")
+	}
+
+	/// Notify the state changes of the subscription until the bus is dropped.
+	/// Notifications are delivered one at a time, in the order of the changes.
+	pub async fn run(self, mut events: Subscription) {
+		while let Some(event) = events.recv().await {
+			if let BusEvent::StateChange(change) = event {
+				let notification = self.record(change);
+				self.deliver(&notification).await;
+			}
+		}
+	}
+
+	fn record(&self, change: StateChange) -> TransferNotification {
+		let notification = TransferNotification::from(change);
+		let mut history = self.lock();
+		if history.len() >= self.history_size {
+			history.pop_front();
+		}
+		history.push_back((change.transfer_id, notification.clone()));
+		notification
+	}
+
+	async fn deliver(&self, notification: &TransferNotification) {
+		let body = match serde_json::to_string(notification) {
+			Ok(body) => body,
+			Err(err) => {
+				tracing::warn!("Failed to serialize transfer notification: {err}");
+				return;
+			}
+		};
+		let request = self
+			.client
+			.post(&self.url)
+			.header("Content-Type", "application/json")
+			.body(body);
+		match request.send().await {
+			Ok(response) if !response.status().is_success() => tracing::warn!(
+				"Webhook {} rejected notification {} of transfer {}: {}",
+				self.url,
+				notification.sequence,
+				notification.transfer_id,
+				response.status()
+			),
+			Ok(_) => (),
+			Err(err) => tracing::warn!(
+				"Failed to send notification {} of transfer {} to {}: {err}",
+				notification.sequence,
+				notification.transfer_id,
+				self.url
+			),
+		}
+	}
+
+	/// The notifications of a transfer still in the history, after the given sequence number.
+	pub fn history(&self, transfer_id: BridgeTransferId, after: u64) -> Vec<TransferNotification> {
+		self.lock()
+			.iter()
+			.filter(|(id, notification)| *id == transfer_id && notification.sequence > after)
+			.map(|(_, notification)| notification.clone())
+			.collect()
+	}
+
+	/// Deliver again the notifications of a transfer after the given sequence number
+	/// and return them.
+	pub async fn replay(
+		&self,
+		transfer_id: BridgeTransferId,
+		after: u64,
+	) -> Vec<TransferNotification> {
+		let notifications = self.history(transfer_id, after);
+		for notification in &notifications {
+			self.deliver(notification).await;
+		}
+		notifications
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bridge_util::states::TransferStateType;
+	use bridge_util::types::ChainId;
+
+	#[test]
+	fn test_notification_history() {
+		let webhooks = TransferWebhooks::new("http://localhost".to_string(), 3);
+		let transfer_id = BridgeTransferId([1; 32]);
+		let other_id = BridgeTransferId([2; 32]);
+		let change = |transfer_id, sequence, previous, new| StateChange {
+			transfer_id,
+			sequence,
+			previous,
+			new,
+			cause: Some(EventRef { chain: ChainId::ONE, event: "Initiated", transfer_id }),
+			reason: None,
+		};
+
+		let first = webhooks.record(change(transfer_id, 1, None, TransferStateType::Initialized));
+		assert_eq!(first.previous_state, None);
+		assert_eq!(first.new_state, "Initialized");
+		assert_eq!(first.cause.unwrap().chain, "ONE");
+		assert_eq!(first.reason_code, None);
+		webhooks.record(change(other_id, 1, None, TransferStateType::Initialized));
+		webhooks.record(change(
+			transfer_id,
+			2,
+			Some(TransferStateType::Initialized),
+			TransferStateType::Locked,
+		));
+		let sequences: Vec<u64> = webhooks
+			.history(transfer_id, 0)
+			.iter()
+			.map(|notification| notification.sequence)
+			.collect();
+		assert_eq!(sequences, vec![1, 2]);
+		assert_eq!(
+			webhooks.history(transfer_id, 1)[0].previous_state.as_deref(),
+			Some("Initialized")
+		);
+
+		// The oldest notification is dropped once the history is full.
+		webhooks.record(change(
+			transfer_id,
+			3,
+			Some(TransferStateType::Locked),
+			TransferStateType::SecretReceived,
+		));
+		assert_eq!(webhooks.history(transfer_id, 0).len(), 2);
+		assert_eq!(webhooks.history(other_id, 0).len(), 1);
+
+		// Refunds carry the code of their reason.
+		let refund = webhooks.record(StateChange {
+			reason: Some(ReasonCode::TimelockExpired),
+			..change(other_id, 2, Some(TransferStateType::Initialized), TransferStateType::Refund)
+		});
+		assert_eq!(refund.reason_code, Some(ReasonCode::TimelockExpired));
+		assert_eq!(
+			serde_json::to_value(&refund).unwrap()["reason_code"],
+			serde_json::json!("ERR_TIMELOCK_EXPIRED")
+		);
+	}
+}
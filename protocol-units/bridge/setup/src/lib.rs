@@ -4,6 +4,52 @@ use bridge_config::Config;
 pub mod deploy;
 pub mod local;
 
+/// Update the bridge config with the Movement node config: its REST and faucet endpoints
+/// and its signer.
+pub fn apply_maptos_config(
+	config: &mut Config,
+	maptos_config: &maptos_execution_util::config::Config,
+) {
+	config.movement.mvt_rpc_connection_hostname =
+		maptos_config.client.maptos_rest_connection_hostname.clone();
+	config.movement.mvt_rpc_connection_port = maptos_config.client.maptos_rest_connection_port;
+	config.movement.mvt_faucet_connection_hostname =
+		maptos_config.client.maptos_faucet_rest_connection_hostname.clone();
+	config.movement.mvt_faucet_connection_port =
+		maptos_config.client.maptos_faucet_rest_connection_port;
+
+	//update signer with maptos private key
+	config.movement.movement_signer_key = maptos_config.chain.maptos_private_key.clone();
+}
+
+/// Update the bridge config with the settlement config: its Ethereum connection,
+/// and the signer and keys of its testing accounts.
+pub fn apply_settlement_config(
+	config: &mut Config,
+	settlement_config: &mcr_settlement_config::Config,
+) -> Result<(), anyhow::Error> {
+	let eth_connection = &settlement_config.eth_connection;
+	config.eth.eth_rpc_connection_protocol = eth_connection.eth_rpc_connection_protocol.clone();
+	config.eth.eth_rpc_connection_hostname = eth_connection.eth_rpc_connection_hostname.clone();
+	config.eth.eth_rpc_connection_port = eth_connection.eth_rpc_connection_port;
+
+	config.eth.eth_ws_connection_protocol = eth_connection.eth_ws_connection_protocol.clone();
+	config.eth.eth_ws_connection_hostname = eth_connection.eth_ws_connection_hostname.clone();
+	config.eth.eth_ws_connection_port = eth_connection.eth_ws_connection_port;
+
+	config.eth.eth_chain_id = eth_connection.eth_chain_id;
+
+	//update signer and keys
+	let testing = settlement_config
+		.testing
+		.as_ref()
+		.ok_or_else(|| anyhow::anyhow!("Settlement config has no testing accounts"))?;
+	config.eth.signer_private_key = testing.mcr_testing_admin_account_private_key.clone();
+	config.testing.eth_well_known_account_private_keys =
+		testing.well_known_account_private_keys.clone();
+	Ok(())
+}
+
 pub async fn process_compose_setup(config: Config) -> Result<Config, anyhow::Error> {
 	// Currently local only
 	tracing::info!("Bridge process_compose_setup");
@@ -17,6 +63,7 @@ pub async fn test_eth_setup(mut config: Config) -> Result<Config, anyhow::Error>
 	//let anvil = local::setup_eth(&mut config.eth, &mut config.testing);
 	//Define the timelock to 15s for the test
 	config.eth.time_lock_secs = 15;
+	config.timelock.min_lock_margin_secs = 5;
 	//Deploy locally
 	crate::deploy::setup_local_ethereum(&mut config).await?;
 	Ok(config)
@@ -1,10 +1,8 @@
 use alloy::node_bindings::{Anvil, AnvilInstance};
 use alloy::signers::local::PrivateKeySigner;
-use aptos_sdk::types::LocalAccount;
 use bridge_config::common::eth::EthConfig;
 use bridge_config::common::movement::MovementConfig;
 use bridge_config::common::testing::TestingConfig;
-use rand::prelude::*;
 use std::process::Stdio;
 use tokio::io::AsyncBufReadExt;
 use tokio::io::BufReader;
@@ -13,7 +11,8 @@ use tokio::process::Command as TokioCommand;
 pub fn setup_eth(config: &mut EthConfig, testing_config: &mut TestingConfig) -> AnvilInstance {
 	let anvil = Anvil::new().port(config.eth_rpc_connection_port).spawn();
 	//update config with Anvil address
-	let signer: PrivateKeySigner = anvil.keys()[1].clone().into();
+	let signer: PrivateKeySigner =
+		anvil.keys()[bridge_test_fixtures::eth::SIGNER_INDEX].clone().into();
 	config.signer_private_key = signer.to_bytes().to_string();
 	for key in anvil.keys().iter().skip(2) {
 		let privkey: PrivateKeySigner = (key.clone()).into();
@@ -115,8 +114,7 @@ pub async fn setup_movement_node(
 	// On some PC the Movement make more time to start. Wait a little.
 	std::thread::sleep(std::time::Duration::from_secs(7));
 
-	let mut rng = ::rand::rngs::StdRng::from_seed([3u8; 32]);
-	let signer = LocalAccount::generate(&mut rng);
+	let signer = bridge_test_fixtures::movement::test_account();
 	config.movement_signer_key = signer.private_key().clone();
 
 	Ok(child)
@@ -50,54 +50,16 @@ async fn main() -> Result<(), anyhow::Error> {
 			// Update config with Movement node conf if present
 			if let Ok(maptos_config) = maptos_config {
 				println!("Update bridge config with suzuka node config");
-				config.movement.mvt_rpc_connection_hostname =
-					maptos_config.client.maptos_rest_connection_hostname;
-				config.movement.mvt_rpc_connection_port =
-					maptos_config.client.maptos_rest_connection_port;
-				config.movement.mvt_faucet_connection_hostname =
-					maptos_config.client.maptos_faucet_rest_connection_hostname;
-				config.movement.mvt_faucet_connection_port =
-					maptos_config.client.maptos_faucet_rest_connection_port;
-
-				//update signer with maptos private key
-				config.movement.movement_signer_key = maptos_config.chain.maptos_private_key;
+				bridge_setup::apply_maptos_config(&mut config, &maptos_config);
 			}
 			if let Ok(settlement_config) = settlement_config {
 				println!("Update bridge config with settlement config");
-				config.eth.eth_rpc_connection_protocol =
-					settlement_config.eth_connection.eth_rpc_connection_protocol;
-				config.eth.eth_rpc_connection_hostname =
-					settlement_config.eth_connection.eth_rpc_connection_hostname;
-				config.eth.eth_rpc_connection_port =
-					settlement_config.eth_connection.eth_rpc_connection_port;
-
-				config.eth.eth_ws_connection_protocol =
-					settlement_config.eth_connection.eth_ws_connection_protocol;
-				config.eth.eth_ws_connection_hostname =
-					settlement_config.eth_connection.eth_ws_connection_hostname;
-				config.eth.eth_ws_connection_port =
-					settlement_config.eth_connection.eth_ws_connection_port;
-
-				config.eth.eth_chain_id = settlement_config.eth_connection.eth_chain_id;
-
-				//update signer and keys
-				//				config.eth.signer_private_key = settlement_config.settle.signer_private_key;
-				config.eth.signer_private_key = settlement_config
-					.testing
-					.as_ref()
-					.unwrap()
-					.mcr_testing_admin_account_private_key
-					.clone();
-				config.testing.eth_well_known_account_private_keys = settlement_config
-					.testing
-					.as_ref()
-					.unwrap()
-					.well_known_account_private_keys
-					.clone();
+				bridge_setup::apply_settlement_config(&mut config, &settlement_config)?;
 			}
 
 			//set timelock for e2e test
 			config.eth.time_lock_secs = 60; // 1mn for the e2e test.
+			config.timelock.min_lock_margin_secs = 20;
 
 			// Use custom as movement node in init.
 			config.movement.mvt_init_network = "custom".to_string();
@@ -0,0 +1,135 @@
+//! Scripted JSON-RPC transport, so the Ethereum clients can be tested without a node.
+
+use alloy::rpc::json_rpc::{
+	ErrorPayload, RequestPacket, Response, ResponsePacket, ResponsePayload, SerializedRequest,
+};
+use alloy::transports::{BoxTransport, TransportError, TransportErrorKind, TransportFut};
+use serde_json::value::RawValue;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+/// Response of the mock to a request.
+#[derive(Debug, Clone)]
+pub enum MockResponse {
+	/// The JSON-RPC `result`. A value of the wrong shape simulates malformed node data.
+	Result(serde_json::Value),
+	/// A JSON-RPC error object.
+	RpcError { code: i64, message: String },
+	/// The request doesn't reach the node.
+	TransportFailure(String),
+}
+
+#[derive(Debug, Default)]
+struct Script {
+	// Responses to the next requests, with the method each one expects if any.
+	responses: VecDeque<(Option<String>, MockResponse)>,
+	// Methods of the requests received.
+	requests: Vec<String>,
+}
+
+/// Transport answering the requests with the scripted responses, in order.
+/// A request without scripted response fails as a transport error.
+/// Clones share the same script.
+#[derive(Debug, Clone, Default)]
+pub struct MockTransport {
+	script: Arc<Mutex<Script>>,
+}
+
+impl MockTransport {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<'_, Script> {
+		self.script.lock().expect("Mock transport lock poisoned")
+	}
+
+	/// Answer the next request, whatever its method.
+	pub fn push(&self, response: MockResponse) -> &Self {
+		self.lock().responses.push_back((None, response));
+		self
+	}
+
+	/// Answer the next request, which must call the method.
+	pub fn expect(&self, method: &str, response: MockResponse) -> &Self {
+		self.lock().responses.push_back((Some(method.to_string()), response));
+		self
+	}
+
+	/// Answer the next request with the result.
+	pub fn push_result(&self, result: impl serde::Serialize) -> &Self {
+		let result = serde_json::to_value(result).expect("Mock result must serialize to JSON");
+		self.push(MockResponse::Result(result))
+	}
+
+	/// Answer the next request with a JSON-RPC error.
+	pub fn push_error(&self, code: i64, message: &str) -> &Self {
+		self.push(MockResponse::RpcError { code, message: message.to_string() })
+	}
+
+	/// The methods of the requests received, in order.
+	pub fn requests(&self) -> Vec<String> {
+		self.lock().requests.clone()
+	}
+
+	/// The number of scripted responses not consumed yet.
+	pub fn remaining(&self) -> usize {
+		self.lock().responses.len()
+	}
+
+	pub fn boxed(&self) -> BoxTransport {
+		BoxTransport::new(self.clone())
+	}
+
+	fn respond(&self, request: &SerializedRequest) -> Result<Response, TransportError> {
+		let method = request.method().to_string();
+		let mut script = self.lock();
+		script.requests.push(method.clone());
+		let Some((expected, response)) = script.responses.pop_front() else {
+			return Err(TransportErrorKind::custom_str(&format!(
+				"No scripted response for {method}"
+			)));
+		};
+		if let Some(expected) = expected.filter(|expected| *expected != method) {
+			return Err(TransportErrorKind::custom_str(&format!(
+				"Expected a {expected} request, received {method}"
+			)));
+		}
+		let payload = match response {
+			MockResponse::Result(result) => ResponsePayload::Success(
+				RawValue::from_string(result.to_string())
+					.expect("JSON values are valid raw values"),
+			),
+			MockResponse::RpcError { code, message } => {
+				ResponsePayload::Failure(ErrorPayload { code, message, data: None })
+			}
+			MockResponse::TransportFailure(err) => {
+				return Err(TransportErrorKind::custom_str(&err));
+			}
+		};
+		Ok(Response { id: request.id().clone(), payload })
+	}
+}
+
+impl tower::Service<RequestPacket> for MockTransport {
+	type Response = ResponsePacket;
+	type Error = TransportError;
+	type Future = TransportFut<'static>;
+
+	fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		Poll::Ready(Ok(()))
+	}
+
+	fn call(&mut self, request: RequestPacket) -> Self::Future {
+		let response = match request {
+			RequestPacket::Single(request) => self.respond(&request).map(ResponsePacket::Single),
+			RequestPacket::Batch(requests) => requests
+				.iter()
+				.map(|request| self.respond(request))
+				.collect::<Result<_, _>>()
+				.map(ResponsePacket::Batch),
+		};
+		Box::pin(async move { response })
+	}
+}
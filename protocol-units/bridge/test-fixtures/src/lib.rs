@@ -0,0 +1,114 @@
+//! Canonical keys, addresses and amounts shared by the bridge tests.
+
+pub mod eth_rpc;
+
+pub mod eth {
+	use alloy::primitives::{address, Address};
+	use alloy::signers::local::PrivateKeySigner;
+
+	/// The mnemonic Anvil derives its default accounts from.
+	pub const ANVIL_MNEMONIC: &str = "test test test test test test test test test test test junk";
+
+	/// The balance, in wei, of each default Anvil account (10_000 ETH).
+	pub const ANVIL_ACCOUNT_BALANCE: u128 = 10_000_000_000_000_000_000_000;
+
+	/// The private keys of the first default Anvil accounts.
+	pub const ANVIL_PRIVATE_KEYS: [&str; 4] = [
+		"0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+		"0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d",
+		"0x5de4111afa1a4b94908f83103eb1f1706367c2e68ca870fc3fb9a804cdab365a",
+		"0x7c852118294e51e653712a81e05800f419141751be58f605c371e15141b007a6",
+	];
+
+	/// The checksummed addresses of the first default Anvil accounts.
+	pub const ANVIL_ADDRESSES: [Address; 4] = [
+		address!("f39Fd6e51aad88F6F4ce6aB8827279cffFb92266"),
+		address!("70997970C51812dc3A010C7d01b50e0d17dc79C8"),
+		address!("3C44CdDdB6a900fa2b585dd299e03d12FA4293BC"),
+		address!("90F79bf6EB2c4f870365E785982E1f101E93b906"),
+	];
+
+	/// The account the setup uses to deploy the contracts and sign the relayer transactions.
+	pub const SIGNER_INDEX: usize = 1;
+	/// The account initiating the transfers from Ethereum.
+	pub const INITIATOR_INDEX: usize = 2;
+	/// The account receiving the transfers on Ethereum.
+	pub const RECIPIENT_INDEX: usize = 3;
+
+	/// The signer of the default Anvil account at the index.
+	pub fn anvil_signer(index: usize) -> PrivateKeySigner {
+		ANVIL_PRIVATE_KEYS[index].parse().expect("Anvil private keys are valid")
+	}
+
+	pub fn initiator() -> PrivateKeySigner {
+		anvil_signer(INITIATOR_INDEX)
+	}
+
+	pub fn recipient() -> PrivateKeySigner {
+		anvil_signer(RECIPIENT_INDEX)
+	}
+}
+
+pub mod movement {
+	use aptos_sdk::types::{account_address::AccountAddress, LocalAccount};
+	use rand::SeedableRng;
+
+	/// The seed of the deterministic Movement test account.
+	pub const ACCOUNT_SEED: [u8; 32] = [3u8; 32];
+
+	/// The dummy recipient of the transfers to Movement.
+	pub const RECIPIENT: AccountAddress = AccountAddress::new(*b"0x00000000000000000000000000face");
+
+	/// The dummy initiator of the transfers from Movement.
+	pub const INITIATOR: AccountAddress = AccountAddress::new(*b"0x000000000000000000000000A55018");
+
+	/// Generates the deterministic Movement test account.
+	pub fn test_account() -> LocalAccount {
+		let mut rng = rand::rngs::StdRng::from_seed(ACCOUNT_SEED);
+		LocalAccount::generate(&mut rng)
+	}
+}
+
+pub mod amounts {
+	/// The decimals of the MOVE token, on both chains.
+	pub const MOVE_DECIMALS: u8 = 8;
+
+	/// One MOVE in its smallest unit.
+	pub const ONE_MOVE: u64 = 10u64.pow(MOVE_DECIMALS as u32);
+
+	/// The amount the tests request from the Movement faucet.
+	pub const FAUCET_AMOUNT: u64 = ONE_MOVE;
+
+	/// The amount the tests request from the Movement faucet to pay for many transactions.
+	pub const LARGE_FAUCET_AMOUNT: u64 = 1_000 * ONE_MOVE;
+
+	/// The amount bridged by default in the tests.
+	pub const TRANSFER_AMOUNT: u64 = 100;
+
+	/// The time lock, in seconds, of the test transfers.
+	pub const TIME_LOCK: u64 = 3600;
+}
+
+pub mod secrets {
+	/// The secret the test transfers are locked with.
+	pub const SECRET: &[u8] = b"secret";
+
+	/// The test secret, zero padded to 32 bytes.
+	pub fn padded_secret() -> [u8; 32] {
+		let mut padded = [0u8; 32];
+		padded[..SECRET.len()].copy_from_slice(SECRET);
+		padded
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_anvil_keys_match_addresses() {
+		for (index, address) in eth::ANVIL_ADDRESSES.iter().enumerate() {
+			assert_eq!(eth::anvil_signer(index).address(), *address);
+		}
+	}
+}
@@ -42,6 +42,7 @@ pub enum TransferActionType {
 	},
 	WaitAndCompleteInitiator(u64, HashLockPreImage),
 	RefundInitiator,
+	AbortCounterparty,
 	TransferDone,
 	NoAction,
 }
@@ -52,6 +53,7 @@ impl fmt::Display for TransferActionType {
 			TransferActionType::LockBridgeTransfer { .. } => "LockBridgeTransfer",
 			TransferActionType::WaitAndCompleteInitiator(..) => "WaitAndCompleteInitiator",
 			TransferActionType::RefundInitiator => "RefundInitiator",
+			TransferActionType::AbortCounterparty => "AbortCounterparty",
 			TransferActionType::TransferDone => "TransferDone",
 			TransferActionType::NoAction => "NoAction",
 		};
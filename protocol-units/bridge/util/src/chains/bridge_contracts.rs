@@ -1,10 +1,17 @@
+use crate::reason::{ReasonCode, WithReasonCode};
 use crate::types::{BridgeTransferDetailsCounterparty, LockDetails};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
 use thiserror::Error;
 use tokio_stream::Stream;
 
 use crate::types::{
 	Amount, BridgeAddress, BridgeTransferDetails, BridgeTransferId, HashLock, HashLockPreImage,
+	LockTransfer,
 };
 
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
@@ -59,12 +66,263 @@ pub enum BridgeContractError {
 	BadAddressEncoding(String),
 	#[error("Error during deserializing an event :{1:?} : {0}")]
 	EventDeserializingFail(String, BridgeContractEventType),
+	#[error("Transaction expired before being committed")]
+	TransactionExpired,
+	#[error("Sequence number conflict:{0}")]
+	SequenceNumberConflict(String),
+	#[error("Transaction ran out of gas")]
+	OutOfGas,
+	#[error("Move abort:{0}")]
+	MoveAbort(String),
 }
 
 impl BridgeContractError {
 	pub fn generic<E: std::error::Error>(e: E) -> Self {
 		Self::GenericError(e.to_string())
 	}
+
+	/// Return the payload-free kind of the error, used to classify it.
+	pub fn kind(&self) -> BridgeContractErrorKind {
+		match self {
+			Self::AccountBalanceError => BridgeContractErrorKind::AccountBalanceError,
+			Self::FundingError => BridgeContractErrorKind::FundingError,
+			Self::InvalidUrl => BridgeContractErrorKind::InvalidUrl,
+			Self::TransferIdExtractionError => BridgeContractErrorKind::TransferIdExtractionError,
+			Self::MintError => BridgeContractErrorKind::MintError,
+			Self::CallError => BridgeContractErrorKind::CallError,
+			Self::SerializationError => BridgeContractErrorKind::SerializationError,
+			Self::InvalidResponseLength => BridgeContractErrorKind::InvalidResponseLength,
+			Self::FunctionViewError => BridgeContractErrorKind::FunctionViewError,
+			Self::InitiateTransferError => BridgeContractErrorKind::InitiateTransferError,
+			Self::CompleteTransferError => BridgeContractErrorKind::CompleteTransferError,
+			Self::ParsePreimageError => BridgeContractErrorKind::ParsePreimageError,
+			Self::ContractAddressError => BridgeContractErrorKind::ContractAddressError,
+			Self::ConversionFailed(_) => BridgeContractErrorKind::ConversionFailed,
+			Self::GenericError(_) => BridgeContractErrorKind::GenericError,
+			Self::ModuleViewError => BridgeContractErrorKind::ModuleViewError,
+			Self::ViewSerializationError => BridgeContractErrorKind::ViewSerializationError,
+			Self::LockTransferError => BridgeContractErrorKind::LockTransferError,
+			Self::AbortTransferError => BridgeContractErrorKind::AbortTransferError,
+			Self::AddressNotSet => BridgeContractErrorKind::AddressNotSet,
+			Self::SignerError => BridgeContractErrorKind::SignerError,
+			Self::OnChainUnknownEvent => BridgeContractErrorKind::OnChainUnknownEvent,
+			Self::OnChainError(_) => BridgeContractErrorKind::OnChainError,
+			Self::BadAddressEncoding(_) => BridgeContractErrorKind::BadAddressEncoding,
+			Self::EventDeserializingFail(..) => BridgeContractErrorKind::EventDeserializingFail,
+			Self::TransactionExpired => BridgeContractErrorKind::TransactionExpired,
+			Self::SequenceNumberConflict(_) => BridgeContractErrorKind::SequenceNumberConflict,
+			Self::OutOfGas => BridgeContractErrorKind::OutOfGas,
+			Self::MoveAbort(_) => BridgeContractErrorKind::MoveAbort,
+		}
+	}
+
+	/// True if the error may not happen again when the call is retried.
+	pub fn is_retryable(&self) -> bool {
+		self.kind().is_retryable()
+	}
+}
+
+impl WithReasonCode for BridgeContractError {
+	fn reason_code(&self) -> ReasonCode {
+		match self {
+			Self::AccountBalanceError | Self::FundingError => ReasonCode::Liquidity,
+			Self::InvalidUrl
+			| Self::ParsePreimageError
+			| Self::ContractAddressError
+			| Self::ConversionFailed(_)
+			| Self::AddressNotSet
+			| Self::BadAddressEncoding(_) => ReasonCode::InvalidRequest,
+			Self::CallError | Self::FunctionViewError | Self::ModuleViewError => {
+				ReasonCode::Unavailable
+			}
+			Self::MintError
+			| Self::InitiateTransferError
+			| Self::CompleteTransferError
+			| Self::LockTransferError
+			| Self::AbortTransferError
+			| Self::SequenceNumberConflict(_) => ReasonCode::TransactionFailed,
+			// The reverts and aborts of the contracts name their cause.
+			Self::OnChainError(message) | Self::MoveAbort(message) => {
+				let message = message.to_lowercase();
+				if message.contains("timelock") || message.contains("time lock") {
+					ReasonCode::TimelockExpired
+				} else if message.contains("insufficient") || message.contains("balance") {
+					ReasonCode::Liquidity
+				} else {
+					ReasonCode::TransactionFailed
+				}
+			}
+			Self::TransactionExpired => ReasonCode::TransactionExpired,
+			Self::OutOfGas => ReasonCode::OutOfGas,
+			Self::TransferIdExtractionError
+			| Self::SerializationError
+			| Self::InvalidResponseLength
+			| Self::GenericError(_)
+			| Self::ViewSerializationError
+			| Self::SignerError
+			| Self::OnChainUnknownEvent
+			| Self::EventDeserializingFail(..) => ReasonCode::Internal,
+		}
+	}
+}
+
+/// Payload-free taxonomy of `BridgeContractError`.
+/// The string form is stable: it is exported by the admin API and used as key in config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BridgeContractErrorKind {
+	AccountBalanceError,
+	FundingError,
+	InvalidUrl,
+	TransferIdExtractionError,
+	MintError,
+	CallError,
+	SerializationError,
+	InvalidResponseLength,
+	FunctionViewError,
+	InitiateTransferError,
+	CompleteTransferError,
+	ParsePreimageError,
+	ContractAddressError,
+	ConversionFailed,
+	GenericError,
+	ModuleViewError,
+	ViewSerializationError,
+	LockTransferError,
+	AbortTransferError,
+	AddressNotSet,
+	SignerError,
+	OnChainUnknownEvent,
+	OnChainError,
+	BadAddressEncoding,
+	EventDeserializingFail,
+	TransactionExpired,
+	SequenceNumberConflict,
+	OutOfGas,
+	MoveAbort,
+}
+
+impl BridgeContractErrorKind {
+	pub const ALL: [BridgeContractErrorKind; 29] = [
+		Self::AccountBalanceError,
+		Self::FundingError,
+		Self::InvalidUrl,
+		Self::TransferIdExtractionError,
+		Self::MintError,
+		Self::CallError,
+		Self::SerializationError,
+		Self::InvalidResponseLength,
+		Self::FunctionViewError,
+		Self::InitiateTransferError,
+		Self::CompleteTransferError,
+		Self::ParsePreimageError,
+		Self::ContractAddressError,
+		Self::ConversionFailed,
+		Self::GenericError,
+		Self::ModuleViewError,
+		Self::ViewSerializationError,
+		Self::LockTransferError,
+		Self::AbortTransferError,
+		Self::AddressNotSet,
+		Self::SignerError,
+		Self::OnChainUnknownEvent,
+		Self::OnChainError,
+		Self::BadAddressEncoding,
+		Self::EventDeserializingFail,
+		Self::TransactionExpired,
+		Self::SequenceNumberConflict,
+		Self::OutOfGas,
+		Self::MoveAbort,
+	];
+
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::AccountBalanceError => "AccountBalanceError",
+			Self::FundingError => "FundingError",
+			Self::InvalidUrl => "InvalidUrl",
+			Self::TransferIdExtractionError => "TransferIdExtractionError",
+			Self::MintError => "MintError",
+			Self::CallError => "CallError",
+			Self::SerializationError => "SerializationError",
+			Self::InvalidResponseLength => "InvalidResponseLength",
+			Self::FunctionViewError => "FunctionViewError",
+			Self::InitiateTransferError => "InitiateTransferError",
+			Self::CompleteTransferError => "CompleteTransferError",
+			Self::ParsePreimageError => "ParsePreimageError",
+			Self::ContractAddressError => "ContractAddressError",
+			Self::ConversionFailed => "ConversionFailed",
+			Self::GenericError => "GenericError",
+			Self::ModuleViewError => "ModuleViewError",
+			Self::ViewSerializationError => "ViewSerializationError",
+			Self::LockTransferError => "LockTransferError",
+			Self::AbortTransferError => "AbortTransferError",
+			Self::AddressNotSet => "AddressNotSet",
+			Self::SignerError => "SignerError",
+			Self::OnChainUnknownEvent => "OnChainUnknownEvent",
+			Self::OnChainError => "OnChainError",
+			Self::BadAddressEncoding => "BadAddressEncoding",
+			Self::EventDeserializingFail => "EventDeserializingFail",
+			Self::TransactionExpired => "TransactionExpired",
+			Self::SequenceNumberConflict => "SequenceNumberConflict",
+			Self::OutOfGas => "OutOfGas",
+			Self::MoveAbort => "MoveAbort",
+		}
+	}
+}
+
+impl BridgeContractErrorKind {
+	/// True for the errors that depend on the RPC provider or on the chain state,
+	/// false for the errors that won't change by retrying the same call.
+	pub fn is_retryable(&self) -> bool {
+		match self {
+			Self::AccountBalanceError
+			| Self::FundingError
+			| Self::CallError
+			| Self::FunctionViewError
+			| Self::ModuleViewError
+			| Self::InitiateTransferError
+			| Self::CompleteTransferError
+			| Self::LockTransferError
+			| Self::AbortTransferError
+			| Self::OnChainError
+			| Self::TransactionExpired
+			| Self::SequenceNumberConflict
+			| Self::GenericError => true,
+			Self::InvalidUrl
+			| Self::TransferIdExtractionError
+			| Self::MintError
+			| Self::SerializationError
+			| Self::InvalidResponseLength
+			| Self::ParsePreimageError
+			| Self::ContractAddressError
+			| Self::ConversionFailed
+			| Self::ViewSerializationError
+			| Self::AddressNotSet
+			| Self::SignerError
+			| Self::OnChainUnknownEvent
+			| Self::BadAddressEncoding
+			| Self::EventDeserializingFail
+			| Self::OutOfGas
+			| Self::MoveAbort => false,
+		}
+	}
+}
+
+impl fmt::Display for BridgeContractErrorKind {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.as_str())
+	}
+}
+
+impl std::str::FromStr for BridgeContractErrorKind {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Self::ALL
+			.iter()
+			.find(|kind| kind.as_str() == s)
+			.copied()
+			.ok_or_else(|| format!("Unknown bridge contract error kind: {s}"))
+	}
 }
 
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
@@ -85,7 +343,7 @@ impl BridgeContractWETH9Error {
 pub type BridgeContractResult<T> = Result<T, BridgeContractError>;
 pub type BridgeContractWETH9Result<T> = Result<T, BridgeContractWETH9Error>;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum BridgeContractEventType {
 	Initiated,
 	Locked,
@@ -95,7 +353,7 @@ pub enum BridgeContractEventType {
 	Refunded,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum BridgeContractEvent<A> {
 	Initiated(BridgeTransferDetails<A>),
 	Locked(LockDetails<A>),
@@ -117,6 +375,23 @@ impl<A> BridgeContractEvent<A> {
 		}
 	}
 
+	/// True for the events emitted by the counterparty contract of the chain.
+	pub fn is_counterparty_event(&self) -> bool {
+		matches!(self, Self::Locked(_) | Self::CounterPartyCompleted(..) | Self::Cancelled(_))
+	}
+
+	/// Name of the event kind, without its content.
+	pub fn kind(&self) -> &'static str {
+		match self {
+			Self::Initiated(_) => "Initiated",
+			Self::Locked(_) => "Locked",
+			Self::InitiatorCompleted(_) => "InitiatorCompleted",
+			Self::CounterPartyCompleted(_, _) => "CounterPartyCompleted",
+			Self::Cancelled(_) => "Cancelled",
+			Self::Refunded(_) => "Refunded",
+		}
+	}
+
 	pub fn is_initiated_event(&self) -> bool {
 		if let BridgeContractEvent::Initiated(_) = self {
 			true
@@ -128,15 +403,7 @@ impl<A> BridgeContractEvent<A> {
 
 impl<A> fmt::Display for BridgeContractEvent<A> {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-		let kind = match self {
-			Self::Initiated(_) => "Initiated",
-			Self::Locked(_) => "Locked",
-			Self::InitiatorCompleted(_) => "InitiatorCompleted",
-			Self::CounterPartyCompleted(_, _) => "CounterPartyCompleted",
-			Self::Cancelled(_) => "Cancelled",
-			Self::Refunded(_) => "Refunded",
-		};
-		write!(f, "Contract event: {}/ transfer id: {}", kind, self.bridge_transfer_id(),)
+		write!(f, "Contract event: {}/ transfer id: {}", self.kind(), self.bridge_transfer_id(),)
 	}
 }
 
@@ -196,6 +463,268 @@ pub trait BridgeContract<A>: Clone + Unpin + Send + Sync {
 		&mut self,
 		bridge_transfer_id: BridgeTransferId,
 	) -> BridgeContractResult<()>;
+
+	/// Complete the transfers on the initiator contract, in a single transaction when the
+	/// chain supports it so its fixed cost is shared. Return the result of each transfer.
+	async fn complete_bridge_transfers_batch(
+		&mut self,
+		transfers: Vec<(BridgeTransferId, HashLockPreImage)>,
+	) -> BridgeContractResult<BatchResults>;
+
+	/// Lock the transfers on the counterparty contract, in a single transaction when the chain
+	/// supports it so its fixed cost is shared. Return the result of each transfer.
+	async fn lock_bridge_transfers_batch(
+		&mut self,
+		transfers: Vec<LockTransfer<A>>,
+	) -> BridgeContractResult<BatchResults>;
+}
+
+/// Result of each transfer of a batch, in the order of the batch.
+pub type BatchResults = Vec<BridgeContractResult<()>>;
+
+/// Retries of the calls failing with a retryable error, with an exponential backoff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+	pub max_retries: u32,
+	/// Delay before the first retry, doubled on each retry up to `max_backoff`.
+	pub initial_backoff: Duration,
+	pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+	fn default() -> Self {
+		RetryPolicy {
+			max_retries: 3,
+			initial_backoff: Duration::from_millis(500),
+			max_backoff: Duration::from_secs(10),
+		}
+	}
+}
+
+impl RetryPolicy {
+	/// Delay before the retry, the first one being 0. Between half and all of the
+	/// exponential backoff, so the callers failing together don't retry together.
+	pub fn backoff(&self, retry: u32) -> Duration {
+		let backoff = self
+			.initial_backoff
+			.saturating_mul(2u32.saturating_pow(retry))
+			.min(self.max_backoff);
+		let half_millis = backoff.as_millis() as u64 / 2;
+		Duration::from_millis(half_millis + rand::thread_rng().gen_range(0, half_millis + 1))
+	}
+
+	/// Run the call until it succeeds, fails with an error that is not retryable
+	/// or the retries are exhausted, and return its last result.
+	pub async fn retry<C, T, F>(&self, client: &mut C, mut call: F) -> BridgeContractResult<T>
+	where
+		F: for<'a> FnMut(
+			&'a mut C,
+		) -> Pin<Box<dyn Future<Output = BridgeContractResult<T>> + Send + 'a>>,
+	{
+		let mut retry = 0;
+		loop {
+			match call(client).await {
+				Err(err) if err.is_retryable() && retry < self.max_retries => {
+					tokio::time::sleep(self.backoff(retry)).await;
+					retry += 1;
+				}
+				result => return result,
+			}
+		}
+	}
+
+	/// Run the batch call, then again with only its items failed with a retryable error until
+	/// they succeed or the retries are exhausted. A failed batch fails all its items. Return
+	/// the last result of each item.
+	pub async fn retry_batch<C, I, F>(
+		&self,
+		client: &mut C,
+		items: Vec<I>,
+		mut call: F,
+	) -> BridgeContractResult<BatchResults>
+	where
+		I: Clone,
+		F: for<'a> FnMut(
+			&'a mut C,
+			Vec<I>,
+		) -> Pin<
+			Box<dyn Future<Output = BridgeContractResult<BatchResults>> + Send + 'a>,
+		>,
+	{
+		let mut results: BatchResults =
+			items.iter().map(|_| Err(BridgeContractError::InvalidResponseLength)).collect();
+		let mut pending: Vec<usize> = (0..items.len()).collect();
+		let mut retry = 0;
+		while !pending.is_empty() {
+			let batch = pending.iter().map(|index| items[*index].clone()).collect();
+			let batch_results = match call(client, batch).await {
+				Ok(batch_results) => batch_results,
+				Err(err) => pending.iter().map(|_| Err(err.clone())).collect(),
+			};
+			for (index, result) in pending.iter().zip(batch_results) {
+				results[*index] = result;
+			}
+			pending.retain(|index| results[*index].as_ref().is_err_and(|err| err.is_retryable()));
+			if retry >= self.max_retries {
+				break;
+			}
+			if !pending.is_empty() {
+				tokio::time::sleep(self.backoff(retry)).await;
+				retry += 1;
+			}
+		}
+		Ok(results)
+	}
+}
+
+/// Bridge contract retrying the calls of the wrapped one with a retry policy.
+#[derive(Debug, Clone)]
+pub struct RetryingBridgeContract<C> {
+	inner: C,
+	policy: RetryPolicy,
+}
+
+impl<C> RetryingBridgeContract<C> {
+	pub fn new(inner: C, policy: RetryPolicy) -> Self {
+		RetryingBridgeContract { inner, policy }
+	}
+
+	pub fn into_inner(self) -> C {
+		self.inner
+	}
+}
+
+#[async_trait::async_trait]
+impl<A, C> BridgeContract<A> for RetryingBridgeContract<C>
+where
+	A: Clone + Send + Sync + 'static,
+	C: BridgeContract<A>,
+{
+	async fn initiate_bridge_transfer(
+		&mut self,
+		initiator: BridgeAddress<A>,
+		recipient: BridgeAddress<Vec<u8>>,
+		hash_lock: HashLock,
+		amount: Amount,
+	) -> BridgeContractResult<()> {
+		self.policy
+			.retry(&mut self.inner, |inner| {
+				inner.initiate_bridge_transfer(
+					initiator.clone(),
+					recipient.clone(),
+					hash_lock,
+					amount,
+				)
+			})
+			.await
+	}
+
+	async fn initiator_complete_bridge_transfer(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+		secret: HashLockPreImage,
+	) -> BridgeContractResult<()> {
+		self.policy
+			.retry(&mut self.inner, |inner| {
+				inner.initiator_complete_bridge_transfer(bridge_transfer_id, secret)
+			})
+			.await
+	}
+
+	async fn counterparty_complete_bridge_transfer(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+		secret: HashLockPreImage,
+	) -> BridgeContractResult<()> {
+		self.policy
+			.retry(&mut self.inner, |inner| {
+				inner.counterparty_complete_bridge_transfer(bridge_transfer_id, secret)
+			})
+			.await
+	}
+
+	async fn refund_bridge_transfer(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> BridgeContractResult<()> {
+		self.policy
+			.retry(&mut self.inner, |inner| inner.refund_bridge_transfer(bridge_transfer_id))
+			.await
+	}
+
+	async fn get_bridge_transfer_details_initiator(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> BridgeContractResult<Option<BridgeTransferDetails<A>>> {
+		self.policy
+			.retry(&mut self.inner, |inner| {
+				inner.get_bridge_transfer_details_initiator(bridge_transfer_id)
+			})
+			.await
+	}
+
+	async fn get_bridge_transfer_details_counterparty(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> BridgeContractResult<Option<BridgeTransferDetailsCounterparty<A>>> {
+		self.policy
+			.retry(&mut self.inner, |inner| {
+				inner.get_bridge_transfer_details_counterparty(bridge_transfer_id)
+			})
+			.await
+	}
+
+	async fn lock_bridge_transfer(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+		hash_lock: HashLock,
+		initiator: BridgeAddress<Vec<u8>>,
+		recipient: BridgeAddress<A>,
+		amount: Amount,
+	) -> BridgeContractResult<()> {
+		self.policy
+			.retry(&mut self.inner, |inner| {
+				inner.lock_bridge_transfer(
+					bridge_transfer_id,
+					hash_lock,
+					initiator.clone(),
+					recipient.clone(),
+					amount,
+				)
+			})
+			.await
+	}
+
+	async fn abort_bridge_transfer(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> BridgeContractResult<()> {
+		self.policy
+			.retry(&mut self.inner, |inner| inner.abort_bridge_transfer(bridge_transfer_id))
+			.await
+	}
+
+	async fn complete_bridge_transfers_batch(
+		&mut self,
+		transfers: Vec<(BridgeTransferId, HashLockPreImage)>,
+	) -> BridgeContractResult<BatchResults> {
+		self.policy
+			.retry_batch(&mut self.inner, transfers, |inner, transfers| {
+				inner.complete_bridge_transfers_batch(transfers)
+			})
+			.await
+	}
+
+	async fn lock_bridge_transfers_batch(
+		&mut self,
+		transfers: Vec<LockTransfer<A>>,
+	) -> BridgeContractResult<BatchResults> {
+		self.policy
+			.retry_batch(&mut self.inner, transfers, |inner, transfers| {
+				inner.lock_bridge_transfers_batch(transfers)
+			})
+			.await
+	}
 }
 
 #[async_trait::async_trait]
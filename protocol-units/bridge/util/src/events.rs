@@ -1,5 +1,6 @@
 use crate::chains::bridge_contracts::BridgeContractEvent;
-use crate::types::ChainId;
+use crate::types::{ChainId, TransferDirection};
+use serde::{Deserialize, Serialize};
 use std::fmt;
 use thiserror::Error;
 
@@ -15,9 +16,13 @@ pub enum InvalidEventError {
 	StateNotFound,
 	#[error("Error during event indexing:{0}")]
 	IndexingFailed(String),
+	#[error("Receive a lock event with a hash lock not matching the initiated transfer")]
+	HashLockMismatch,
+	#[error("The initiator contract state {0} doesn't match the event")]
+	InitiatorStateMismatch(u8),
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TransferEvent<A> {
 	pub chain: ChainId,
 	pub contract_event: BridgeContractEvent<A>,
@@ -29,15 +34,27 @@ impl<A> From<(BridgeContractEvent<A>, ChainId)> for TransferEvent<A> {
 	}
 }
 
-impl<A: std::fmt::Debug> fmt::Display for TransferEvent<A> {
+impl<A> TransferEvent<A> {
+	/// The direction of the transfer the event belongs to. Counterparty events are emitted
+	/// on the chain opposite to the one the transfer has been initiated on.
+	pub fn direction(&self) -> TransferDirection {
+		let init_chain = if self.contract_event.is_counterparty_event() {
+			self.chain.other()
+		} else {
+			self.chain
+		};
+		TransferDirection::from_init_chain(init_chain)
+	}
+}
+
+impl<A: Clone + Into<Vec<u8>>> fmt::Display for TransferEvent<A> {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-		write!(
-			f,
-			"Event: {}: {} => {:?}",
-			self.chain,
-			self.contract_event.bridge_transfer_id(),
-			self.contract_event,
-		)
+		write!(f, "Event: {} ({}): ", self.chain, self.direction())?;
+		match &self.contract_event {
+			BridgeContractEvent::Initiated(details) => write!(f, "Initiated {details}"),
+			BridgeContractEvent::Locked(details) => write!(f, "Locked {details}"),
+			event => write!(f, "{event}"),
+		}
 	}
 }
 
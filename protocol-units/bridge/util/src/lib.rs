@@ -1,6 +1,7 @@
 pub mod actions;
 pub mod chains;
 pub mod events;
+pub mod reason;
 pub mod states;
 pub mod types;
 
@@ -14,5 +15,9 @@ pub use crate::events::InvalidEventError;
 pub use crate::events::TransferEvent;
 pub use crate::states::TransferState;
 pub use crate::states::TransferStateType;
+pub use crate::states::{
+	AbortedTransfer, CompletableTransfer, CompletedTransfer, InitiatedTransfer,
+	LockAbortedTransfer, LockedTransfer, PendingApprovalTransfer, RefundTransfer, RefundedTransfer,
+};
 pub use crate::types::BridgeTransferId;
 pub use crate::types::ChainId;
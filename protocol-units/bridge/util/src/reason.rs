@@ -0,0 +1,143 @@
+//! Stable reason codes of the rejections and failures surfaced to the users and integrators,
+//! returned next to the message by the REST API, the CLI and the webhooks. Integrators branch
+//! on the codes and present their own localized messages: the code of a reason never changes,
+//! new reasons get new codes.
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReasonCode {
+	InvalidRequest,
+	NotFound,
+	Unauthorized,
+	/// The feature is not enabled on this relayer.
+	FeatureDisabled,
+	RateLimited,
+	/// The request conflicts with an operation in progress.
+	Conflict,
+	/// A chain or the indexer db failed to answer.
+	Unavailable,
+	Internal,
+	DirectionDisabled,
+	Paused,
+	UnsupportedToken,
+	AmountTooLow,
+	AmountTooHigh,
+	/// The value of the amount at the price of the token is above the limit.
+	ValueTooHigh,
+	InvalidRecipient,
+	RecipientNotAllowed,
+	/// The amount doesn't cover the relayer fee.
+	FeeNotCovered,
+	ApprovalRejected,
+	TimelockExpired,
+	TimelockNotExpired,
+	/// The initiator time lock is too close to its expiry to lock the transfer.
+	TimelockTooClose,
+	/// The relayer account lacks the funds to lock or pay the gas.
+	Liquidity,
+	NotRefundable,
+	NotLocked,
+	AlreadyClaimed,
+	WrongSecret,
+	TransactionFailed,
+	TransactionExpired,
+	OutOfGas,
+}
+
+impl ReasonCode {
+	pub const ALL: [ReasonCode; 29] = [
+		Self::InvalidRequest,
+		Self::NotFound,
+		Self::Unauthorized,
+		Self::FeatureDisabled,
+		Self::RateLimited,
+		Self::Conflict,
+		Self::Unavailable,
+		Self::Internal,
+		Self::DirectionDisabled,
+		Self::Paused,
+		Self::UnsupportedToken,
+		Self::AmountTooLow,
+		Self::AmountTooHigh,
+		Self::ValueTooHigh,
+		Self::InvalidRecipient,
+		Self::RecipientNotAllowed,
+		Self::FeeNotCovered,
+		Self::ApprovalRejected,
+		Self::TimelockExpired,
+		Self::TimelockNotExpired,
+		Self::TimelockTooClose,
+		Self::Liquidity,
+		Self::NotRefundable,
+		Self::NotLocked,
+		Self::AlreadyClaimed,
+		Self::WrongSecret,
+		Self::TransactionFailed,
+		Self::TransactionExpired,
+		Self::OutOfGas,
+	];
+
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::InvalidRequest => "ERR_INVALID_REQUEST",
+			Self::NotFound => "ERR_NOT_FOUND",
+			Self::Unauthorized => "ERR_UNAUTHORIZED",
+			Self::FeatureDisabled => "ERR_FEATURE_DISABLED",
+			Self::RateLimited => "ERR_RATE_LIMITED",
+			Self::Conflict => "ERR_CONFLICT",
+			Self::Unavailable => "ERR_UNAVAILABLE",
+			Self::Internal => "ERR_INTERNAL",
+			Self::DirectionDisabled => "ERR_DIRECTION_DISABLED",
+			Self::Paused => "ERR_PAUSED",
+			Self::UnsupportedToken => "ERR_UNSUPPORTED_TOKEN",
+			Self::AmountTooLow => "ERR_AMOUNT_TOO_LOW",
+			Self::AmountTooHigh => "ERR_AMOUNT_TOO_HIGH",
+			Self::ValueTooHigh => "ERR_VALUE_TOO_HIGH",
+			Self::InvalidRecipient => "ERR_INVALID_RECIPIENT",
+			Self::RecipientNotAllowed => "ERR_RECIPIENT_NOT_ALLOWED",
+			Self::FeeNotCovered => "ERR_FEE_NOT_COVERED",
+			Self::ApprovalRejected => "ERR_APPROVAL_REJECTED",
+			Self::TimelockExpired => "ERR_TIMELOCK_EXPIRED",
+			Self::TimelockNotExpired => "ERR_TIMELOCK_NOT_EXPIRED",
+			Self::TimelockTooClose => "ERR_TIMELOCK_TOO_CLOSE",
+			Self::Liquidity => "ERR_LIQUIDITY",
+			Self::NotRefundable => "ERR_NOT_REFUNDABLE",
+			Self::NotLocked => "ERR_NOT_LOCKED",
+			Self::AlreadyClaimed => "ERR_ALREADY_CLAIMED",
+			Self::WrongSecret => "ERR_WRONG_SECRET",
+			Self::TransactionFailed => "ERR_TRANSACTION_FAILED",
+			Self::TransactionExpired => "ERR_TRANSACTION_EXPIRED",
+			Self::OutOfGas => "ERR_OUT_OF_GAS",
+		}
+	}
+}
+
+impl std::str::FromStr for ReasonCode {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Self::ALL
+			.iter()
+			.find(|code| code.as_str() == s)
+			.copied()
+			.ok_or_else(|| format!("Unknown reason code: {s}"))
+	}
+}
+
+impl fmt::Display for ReasonCode {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.as_str())
+	}
+}
+
+impl Serialize for ReasonCode {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(self.as_str())
+	}
+}
+
+/// Errors surfaced to the users, with the reason code of each failure.
+pub trait WithReasonCode {
+	fn reason_code(&self) -> ReasonCode;
+}
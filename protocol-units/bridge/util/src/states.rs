@@ -9,6 +9,7 @@ use crate::types::{BridgeTransferId, ChainId, HashLock, TimeLock};
 use crate::TransferAction;
 use crate::TransferActionType;
 use std::fmt;
+use std::ops::{Deref, DerefMut};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct TransferAddress(Vec<u8>);
@@ -29,30 +30,100 @@ impl<A: From<Vec<u8>>> From<TransferAddress> for BridgeAddress<A> {
 #[derive(Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash)]
 pub enum TransferStateType {
 	Initialized,
+	PendingApproval,
 	Locked,
 	SecretReceived,
-	CompletedIntiator,
-	Done,
 	Refund,
+	LockAborted,
+	Completed,
+	Refunded,
+	Aborted,
+}
+
+impl TransferStateType {
+	/// Whether the transfer is over, no event or action follows.
+	pub fn is_final(&self) -> bool {
+		matches!(self, Self::Completed | Self::Refunded | Self::Aborted)
+	}
 }
 
 impl fmt::Display for TransferStateType {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		let kind = match self {
 			Self::Initialized => "Initialized",
+			Self::PendingApproval => "PendingApproval",
 			Self::Locked => "Locked",
 			Self::SecretReceived => "SecretReceived",
-			Self::CompletedIntiator => "CompletedIntiator",
-			Self::Done => "Done",
 			Self::Refund => "Refund",
+			Self::LockAborted => "LockAborted",
+			Self::Completed => "Completed",
+			Self::Refunded => "Refunded",
+			Self::Aborted => "Aborted",
 		};
 		write!(f, "{}", kind,)
 	}
 }
 
+impl std::str::FromStr for TransferStateType {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"Initialized" => Ok(Self::Initialized),
+			"PendingApproval" => Ok(Self::PendingApproval),
+			"Locked" => Ok(Self::Locked),
+			"SecretReceived" => Ok(Self::SecretReceived),
+			"Refund" => Ok(Self::Refund),
+			"LockAborted" => Ok(Self::LockAborted),
+			"Completed" => Ok(Self::Completed),
+			"Refunded" => Ok(Self::Refunded),
+			"Aborted" => Ok(Self::Aborted),
+			_ => Err(format!("Unknown transfer state: {s}")),
+		}
+	}
+}
+
+/// State of a transfer in the initiator contract of its init chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InitiatorContractState {
+	Initialized,
+	Completed,
+	Refunded,
+}
+
+impl InitiatorContractState {
+	/// Decode the state field of the initiator details. The Ethereum contract numbers its
+	/// states from 0, the Movement module from 1.
+	pub fn from_details(init_chain: ChainId, state: u8) -> Option<Self> {
+		let first = match init_chain {
+			ChainId::ONE => 0,
+			ChainId::TWO => 1,
+		};
+		match state.checked_sub(first)? {
+			0 => Some(Self::Initialized),
+			1 => Some(Self::Completed),
+			2 => Some(Self::Refunded),
+			_ => None,
+		}
+	}
+
+	/// Whether the contract can be in this state once the event has been emitted.
+	pub fn agrees_with<A>(&self, event: &BridgeContractEvent<A>) -> bool {
+		match event {
+			BridgeContractEvent::Initiated(_)
+			| BridgeContractEvent::Locked(_)
+			| BridgeContractEvent::CounterPartyCompleted(..) => *self == Self::Initialized,
+			// The counterparty lock can be aborted after the initiator refund.
+			BridgeContractEvent::Cancelled(_) => *self != Self::Completed,
+			BridgeContractEvent::InitiatorCompleted(_) => *self == Self::Completed,
+			BridgeContractEvent::Refunded(_) => *self == Self::Refunded,
+		}
+	}
+}
+
+/// The data shared by all the states of a transfer.
 #[allow(dead_code)]
-pub struct TransferState {
-	pub state: TransferStateType,
+pub struct TransferData {
 	pub init_chain: ChainId,
 	pub transfer_id: BridgeTransferId,
 	pub intiator_address: TransferAddress,
@@ -63,6 +134,219 @@ pub struct TransferState {
 	pub contract_state: u8,
 	//Max number time action are retry for the whole transfer.
 	pub retry_on_error: usize,
+	/// Secret revealed by the counterpart completion, to complete the initiator side.
+	pub pre_image: Option<HashLockPreImage>,
+}
+
+impl TransferData {
+	fn from_initiator_details<A: Into<Vec<u8>> + Clone>(
+		init_chain: ChainId,
+		transfer_id: BridgeTransferId,
+		detail: &BridgeTransferDetails<A>,
+	) -> Self {
+		TransferData {
+			init_chain,
+			transfer_id,
+			intiator_address: detail.initiator.clone().into(),
+			counter_part_address: detail.recipient.clone().into(),
+			hash_lock: detail.hash_lock,
+			time_lock: detail.time_lock,
+			amount: detail.amount,
+			contract_state: detail.state,
+			retry_on_error: 0,
+			pre_image: None,
+		}
+	}
+}
+
+// Each state of a transfer is its own type and only exposes the transitions valid from it,
+// so the relayer can't apply an invalid transition: it doesn't compile.
+macro_rules! transfer_typestate {
+	($($(#[$doc:meta])* $name:ident => $variant:ident,)*) => {
+		$(
+			$(#[$doc])*
+			pub struct $name(TransferData);
+
+			impl Deref for $name {
+				type Target = TransferData;
+				fn deref(&self) -> &TransferData {
+					&self.0
+				}
+			}
+
+			impl DerefMut for $name {
+				fn deref_mut(&mut self) -> &mut TransferData {
+					&mut self.0
+				}
+			}
+
+			impl From<$name> for TransferState {
+				fn from(transfer: $name) -> Self {
+					TransferState::$variant(transfer)
+				}
+			}
+		)*
+	};
+}
+
+transfer_typestate! {
+	/// The transfer has been initiated, the relayer must lock it on the counterpart chain.
+	InitiatedTransfer => Initialized,
+	/// The transfer has been initiated and its lock waits for an operator decision.
+	PendingApprovalTransfer => PendingApproval,
+	/// The transfer is locked on the counterpart chain, waiting for the recipient to complete it.
+	///
+	/// It can't be completed before the secret is revealed:
+	/// ```compile_fail
+	/// # fn complete(transfer: bridge_util::LockedTransfer) {
+	/// transfer.complete();
+	/// # }
+	/// ```
+	LockedTransfer => Locked,
+	/// The secret has been revealed on the counterpart chain, the initiator side can be completed.
+	CompletableTransfer => SecretReceived,
+	/// The transfer is being refunded to the initiator.
+	RefundTransfer => Refund,
+	/// The lock expired and has been aborted, the initiator is refunded once its own time lock
+	/// expires.
+	LockAbortedTransfer => LockAborted,
+	/// The initiator side has been completed with the secret, the transfer is over.
+	///
+	/// Nothing can be applied on it anymore:
+	/// ```compile_fail
+	/// # fn refund(transfer: bridge_util::CompletedTransfer) {
+	/// transfer.refund();
+	/// # }
+	/// ```
+	CompletedTransfer => Completed,
+	/// The initiator has been refunded, the transfer is over.
+	RefundedTransfer => Refunded,
+	/// The transfer has been closed without being completed nor refunded: its initiation was
+	/// dropped, or its lock aborted with nothing to refund.
+	AbortedTransfer => Aborted,
+}
+
+impl InitiatedTransfer {
+	/// Create the transfer from an Initiated event and return the lock action to execute.
+	pub fn new<A: Into<Vec<u8>> + Clone>(
+		chain_id: ChainId,
+		transfer_id: BridgeTransferId,
+		detail: BridgeTransferDetails<A>,
+	) -> (Self, TransferAction) {
+		let transfer =
+			InitiatedTransfer(TransferData::from_initiator_details(chain_id, transfer_id, &detail));
+
+		let action_type = TransferActionType::LockBridgeTransfer {
+			bridge_transfer_id: transfer_id,
+			hash_lock: detail.hash_lock,
+			initiator: BridgeAddress(detail.initiator.0.into()),
+			recipient: BridgeAddress(detail.recipient.0.into()),
+			amount: detail.amount,
+		};
+		let action = TransferAction { chain: chain_id, transfer_id, kind: action_type };
+		(transfer, action)
+	}
+
+	/// Hold the lock until an operator decides on the transfer.
+	pub fn hold_for_approval(self) -> PendingApprovalTransfer {
+		PendingApprovalTransfer(self.0)
+	}
+
+	pub fn lock_done<A: Into<Vec<u8>> + Clone>(
+		self,
+		_detail: LockDetails<A>,
+	) -> (LockedTransfer, TransferActionType) {
+		(LockedTransfer(self.0), TransferActionType::NoAction)
+	}
+
+	/// Refund the initiator, the transfer has not been locked.
+	pub fn refund(self) -> (RefundTransfer, TransferAction) {
+		refund(self.0)
+	}
+}
+
+impl PendingApprovalTransfer {
+	/// The operator approved the transfer, its lock can be executed.
+	pub fn approve(self) -> InitiatedTransfer {
+		InitiatedTransfer(self.0)
+	}
+
+	/// The operator denied the transfer, refund the initiator.
+	pub fn deny(self) -> (RefundTransfer, TransferAction) {
+		refund(self.0)
+	}
+}
+
+impl LockedTransfer {
+	pub fn counterpart_completed(
+		mut self,
+		secret: HashLockPreImage,
+	) -> (CompletableTransfer, TransferActionType) {
+		self.0.pre_image = Some(secret);
+		(CompletableTransfer(self.0), TransferActionType::WaitAndCompleteInitiator(0, secret))
+	}
+}
+
+impl CompletableTransfer {
+	/// The initiator side has been completed with the secret.
+	pub fn complete(self) -> (CompletedTransfer, TransferActionType) {
+		(CompletedTransfer(self.0), TransferActionType::NoAction)
+	}
+}
+
+fn refund(data: TransferData) -> (RefundTransfer, TransferAction) {
+	let action = TransferAction {
+		chain: data.init_chain,
+		transfer_id: data.transfer_id,
+		kind: TransferActionType::RefundInitiator,
+	};
+	(RefundTransfer(data), action)
+}
+
+/// A transfer in any of its states, as stored by the relayer.
+pub enum TransferState {
+	Initialized(InitiatedTransfer),
+	PendingApproval(PendingApprovalTransfer),
+	Locked(LockedTransfer),
+	SecretReceived(CompletableTransfer),
+	Refund(RefundTransfer),
+	LockAborted(LockAbortedTransfer),
+	Completed(CompletedTransfer),
+	Refunded(RefundedTransfer),
+	Aborted(AbortedTransfer),
+}
+
+impl Deref for TransferState {
+	type Target = TransferData;
+	fn deref(&self) -> &TransferData {
+		match self {
+			Self::Initialized(t) => t,
+			Self::PendingApproval(t) => t,
+			Self::Locked(t) => t,
+			Self::SecretReceived(t) => t,
+			Self::Refund(t) => t,
+			Self::LockAborted(t) => t,
+			Self::Completed(t) => t,
+			Self::Refunded(t) => t,
+			Self::Aborted(t) => t,
+		}
+	}
+}
+
+impl DerefMut for TransferState {
+	fn deref_mut(&mut self) -> &mut TransferData {
+		match self {
+			Self::Initialized(t) => t,
+			Self::PendingApproval(t) => t,
+			Self::Locked(t) => t,
+			Self::SecretReceived(t) => t,
+			Self::Refund(t) => t,
+			Self::LockAborted(t) => t,
+			Self::Completed(t) => t,
+			Self::Refunded(t) => t,
+			Self::Aborted(t) => t,
+		}
+	}
 }
 
 impl fmt::Display for TransferState {
@@ -70,28 +354,56 @@ impl fmt::Display for TransferState {
 		write!(
 			f,
 			"Transfer State: {} / transfer id: {} / init_chain: {} ",
-			self.state, self.transfer_id, self.init_chain
+			self.state_type(),
+			self.transfer_id,
+			self.init_chain
 		)
 	}
 }
 
 impl TransferState {
+	pub fn state_type(&self) -> TransferStateType {
+		match self {
+			Self::Initialized(_) => TransferStateType::Initialized,
+			Self::PendingApproval(_) => TransferStateType::PendingApproval,
+			Self::Locked(_) => TransferStateType::Locked,
+			Self::SecretReceived(_) => TransferStateType::SecretReceived,
+			Self::Refund(_) => TransferStateType::Refund,
+			Self::LockAborted(_) => TransferStateType::LockAborted,
+			Self::Completed(_) => TransferStateType::Completed,
+			Self::Refunded(_) => TransferStateType::Refunded,
+			Self::Aborted(_) => TransferStateType::Aborted,
+		}
+	}
+
 	pub fn validate_event<A: std::fmt::Debug>(
 		&self,
 		event: &TransferEvent<A>,
 	) -> Result<(), InvalidEventError> {
-		match (&event.contract_event, &self.state) {
+		match (&event.contract_event, self.state_type()) {
 			(BridgeContractEvent::Initiated(_), _) => {
 				// already present invalid
 				Err(InvalidEventError::InitAnAlreadyExist)
 			}
 			// Lock event must on on the counter part chain.
-			(BridgeContractEvent::Locked(_), TransferStateType::Initialized) => (event.chain
-				!= self.init_chain)
-				.then_some(())
-				.ok_or(InvalidEventError::BadChain),
+			(BridgeContractEvent::Locked(details), TransferStateType::Initialized) => {
+				if event.chain == self.init_chain {
+					Err(InvalidEventError::BadChain)
+				} else if details.hash_lock != self.hash_lock {
+					// The lock could not be claimed with the initiator secret.
+					Err(InvalidEventError::HashLockMismatch)
+				} else {
+					Ok(())
+				}
+			}
+			// The lock sent before the initiation was dropped, kept until it's aborted.
+			(BridgeContractEvent::Locked(_), TransferStateType::Refund) => {
+				(event.chain != self.init_chain)
+					.then_some(())
+					.ok_or(InvalidEventError::BadChain)
+			}
 			// Lock event is only applied on Initialized swap state
-			(BridgeContractEvent::Locked(details), _) => Err(InvalidEventError::BadEvent(format!("Received a locked event with state not Initialized, transfer_id: {} state:{} details: {details:?}", self.transfer_id, self.state))),
+			(BridgeContractEvent::Locked(details), state) => Err(InvalidEventError::BadEvent(format!("Received a locked event with state not Initialized, transfer_id: {} state:{} details: {details:?}", self.transfer_id, state))),
 			// CounterPartCompleted event must on on the counter part chain.
 			(BridgeContractEvent::CounterPartyCompleted(_, _), TransferStateType::Locked) => {
 				(event.chain != self.init_chain)
@@ -99,8 +411,8 @@ impl TransferState {
 					.ok_or(InvalidEventError::BadChain)
 			}
 			// CounterPartCompleted event is only applied on Locked swap state
-			(BridgeContractEvent::CounterPartyCompleted(_, _), _) => {
-				Err(InvalidEventError::BadEvent(format!("Received a CounterPartCompleted event with state not Locked, transfer_id: {} state:{}", self.transfer_id, self.state)))
+			(BridgeContractEvent::CounterPartyCompleted(_, _), state) => {
+				Err(InvalidEventError::BadEvent(format!("Received a CounterPartCompleted event with state not Locked, transfer_id: {} state:{}", self.transfer_id, state)))
 			}
 			// InitiatorCompleted event must on on the init chain.
 			(BridgeContractEvent::InitiatorCompleted(_), TransferStateType::SecretReceived) => {
@@ -108,91 +420,281 @@ impl TransferState {
 					.then_some(())
 					.ok_or(InvalidEventError::BadChain)
 			}
-			(BridgeContractEvent::InitiatorCompleted(_), _) => Err(InvalidEventError::BadEvent(format!("Received a InitialtorCompleted event with state not SecretReceived, transfer_id: {} state:{}", self.transfer_id, self.state))),
+			(BridgeContractEvent::InitiatorCompleted(_), state) => Err(InvalidEventError::BadEvent(format!("Received a InitialtorCompleted event with state not SecretReceived, transfer_id: {} state:{}", self.transfer_id, state))),
 			(BridgeContractEvent::Refunded(_), _) => Ok(()),
 			(&BridgeContractEvent::Cancelled(_), _) => Ok(()),
 		}
 	}
 
-	pub fn transition_from_initiated<A: Into<Vec<u8>> + Clone>(
-		chain_id: ChainId,
-		transfer_id: BridgeTransferId,
+	/// Return the init chain of the transfer and the state it must be in before the event
+	/// is applied, or None for the Initiated event that creates the state.
+	pub fn expected_before_event<A>(
+		event: &TransferEvent<A>,
+	) -> Option<(ChainId, TransferStateType)> {
+		match event.contract_event {
+			BridgeContractEvent::Initiated(_) => None,
+			BridgeContractEvent::Locked(_) => {
+				Some((event.chain.other(), TransferStateType::Initialized))
+			}
+			BridgeContractEvent::CounterPartyCompleted(..) | BridgeContractEvent::Cancelled(_) => {
+				Some((event.chain.other(), TransferStateType::Locked))
+			}
+			BridgeContractEvent::InitiatorCompleted(_) => {
+				Some((event.chain, TransferStateType::SecretReceived))
+			}
+			BridgeContractEvent::Refunded(_) => Some((event.chain, TransferStateType::Initialized)),
+		}
+	}
+
+	/// Check the state of the initiator contract, read on the init chain once the event has
+	/// been emitted, against the event. The event is rejected if they disagree.
+	pub fn check_initiator_state<A>(
+		event: &TransferEvent<A>,
+		init_chain: ChainId,
+		contract_state: u8,
+	) -> Result<(), InvalidEventError> {
+		match InitiatorContractState::from_details(init_chain, contract_state) {
+			Some(state) if state.agrees_with(&event.contract_event) => Ok(()),
+			_ => Err(InvalidEventError::InitiatorStateMismatch(contract_state)),
+		}
+	}
+
+	/// Rebuild the state of a transfer from the details read on the init chain.
+	pub fn from_initiator_details<A: Into<Vec<u8>> + Clone>(
+		init_chain: ChainId,
 		detail: BridgeTransferDetails<A>,
-	) -> (Self, TransferAction) {
-		println!("State transition_from_initiated amount {:?}", detail.amount);
+		state: TransferStateType,
+	) -> Self {
+		let data =
+			TransferData::from_initiator_details(init_chain, detail.bridge_transfer_id, &detail);
+		match state {
+			TransferStateType::Initialized => InitiatedTransfer(data).into(),
+			TransferStateType::PendingApproval => PendingApprovalTransfer(data).into(),
+			TransferStateType::Locked => LockedTransfer(data).into(),
+			TransferStateType::SecretReceived => CompletableTransfer(data).into(),
+			TransferStateType::Refund => RefundTransfer(data).into(),
+			TransferStateType::LockAborted => LockAbortedTransfer(data).into(),
+			TransferStateType::Completed => CompletedTransfer(data).into(),
+			TransferStateType::Refunded => RefundedTransfer(data).into(),
+			TransferStateType::Aborted => AbortedTransfer(data).into(),
+		}
+	}
 
-		let state = TransferState {
-			state: TransferStateType::Initialized,
-			init_chain: chain_id,
-			transfer_id,
-			intiator_address: detail.initiator.clone().into(),
-			counter_part_address: detail.recipient.clone().into(),
-			hash_lock: detail.hash_lock,
-			time_lock: detail.time_lock,
-			amount: detail.amount,
-			contract_state: detail.state,
-			retry_on_error: 0,
-		};
+	/// The Initiated event of the transfer has been dropped by a reorg of its init chain.
+	/// A transfer with a lock sent on the counterpart chain waits for the abort of the lock,
+	/// which can only be submitted once its time lock has expired. A transfer not locked is
+	/// aborted. The transfers whose secret has been revealed, or already over, are left
+	/// unchanged.
+	pub fn drop_initiation(self, lock_sent: bool) -> Self {
+		match self {
+			Self::Initialized(InitiatedTransfer(data)) if lock_sent => RefundTransfer(data).into(),
+			Self::Locked(LockedTransfer(data)) => RefundTransfer(data).into(),
+			state @ (Self::Initialized(_) | Self::PendingApproval(_)) => state.abort().into(),
+			state => state,
+		}
+	}
 
-		let action_type = TransferActionType::LockBridgeTransfer {
-			bridge_transfer_id: transfer_id,
-			hash_lock: detail.hash_lock,
-			initiator: BridgeAddress(detail.initiator.0.into()),
-			recipient: BridgeAddress(detail.recipient.0.into()),
-			amount: detail.amount,
+	/// Apply a contract event to the transfer and return its new state with the action to execute.
+	/// The event must have been validated with `validate_event`.
+	pub fn apply_event<A: Into<Vec<u8>> + Clone>(
+		self,
+		event: BridgeContractEvent<A>,
+	) -> Result<(Self, TransferActionType), InvalidEventError> {
+		let (state, action_kind) = match (self, event) {
+			(Self::Initialized(transfer), BridgeContractEvent::Locked(detail)) => {
+				let (transfer, action_kind) = transfer.lock_done(detail);
+				(transfer.into(), action_kind)
+			}
+			(Self::Locked(transfer), BridgeContractEvent::CounterPartyCompleted(_, preimage)) => {
+				let (transfer, action_kind) = transfer.counterpart_completed(preimage);
+				(transfer.into(), action_kind)
+			}
+			(Self::SecretReceived(transfer), BridgeContractEvent::InitiatorCompleted(_)) => {
+				let (transfer, action_kind) = transfer.complete();
+				(transfer.into(), action_kind)
+			}
+			// The time lock watcher aborts the lock once it has expired.
+			(Self::Refund(transfer), BridgeContractEvent::Locked(_)) => {
+				(transfer.into(), TransferActionType::NoAction)
+			}
+			// The initiator is refunded by the time lock watcher, the transfer is over then.
+			(Self::Locked(LockedTransfer(data)), BridgeContractEvent::Cancelled(_)) => {
+				(LockAbortedTransfer(data).into(), TransferActionType::NoAction)
+			}
+			(Self::LockAborted(transfer), BridgeContractEvent::Cancelled(_)) => {
+				(transfer.into(), TransferActionType::NoAction)
+			}
+			// The transfer has been closed on chain, whatever its state.
+			(state, BridgeContractEvent::Refunded(_)) if !state.state_type().is_final() => {
+				(RefundedTransfer(state.into_data()).into(), TransferActionType::NoAction)
+			}
+			(state, BridgeContractEvent::Cancelled(_)) if !state.state_type().is_final() => {
+				(state.abort().into(), TransferActionType::NoAction)
+			}
+			(state, event) => {
+				return Err(InvalidEventError::BadEvent(format!(
+					"{event} can't be applied on state {}",
+					state.state_type()
+				)))
+			}
 		};
-		let action = TransferAction { chain: chain_id, transfer_id, kind: action_type };
-		(state, action)
+		Ok((state, action_kind))
 	}
 
-	pub fn transition_from_locked_done<A: Into<Vec<u8>> + Clone>(
-		mut self,
-		_transfer_id: BridgeTransferId,
-		_detail: LockDetails<A>,
-	) -> (Self, TransferActionType) {
-		self.state = TransferStateType::Locked;
-		let action_type = TransferActionType::NoAction;
-		(self, action_type)
+	fn abort(self) -> AbortedTransfer {
+		AbortedTransfer(self.into_data())
 	}
 
-	pub fn transition_from_counterpart_completed(
-		mut self,
-		_transfer_id: BridgeTransferId,
-		secret: HashLockPreImage,
-	) -> (Self, TransferActionType) {
-		self.state = TransferStateType::SecretReceived;
-		let action_type = TransferActionType::WaitAndCompleteInitiator(0, secret);
-		(self, action_type)
+	fn into_data(self) -> TransferData {
+		match self {
+			Self::Initialized(InitiatedTransfer(data))
+			| Self::PendingApproval(PendingApprovalTransfer(data))
+			| Self::Locked(LockedTransfer(data))
+			| Self::SecretReceived(CompletableTransfer(data))
+			| Self::Refund(RefundTransfer(data))
+			| Self::LockAborted(LockAbortedTransfer(data))
+			| Self::Completed(CompletedTransfer(data))
+			| Self::Refunded(RefundedTransfer(data))
+			| Self::Aborted(AbortedTransfer(data)) => data,
+		}
 	}
+}
 
-	pub fn transition_from_initiator_completed(
-		mut self,
-		_transfer_id: BridgeTransferId,
-	) -> (Self, TransferActionType) {
-		self.state = TransferStateType::Done;
-		let action_type = TransferActionType::NoAction;
-		(self, action_type)
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn initiated(transfer_id: BridgeTransferId) -> TransferState {
+		let details = BridgeTransferDetails {
+			bridge_transfer_id: transfer_id,
+			initiator: BridgeAddress(vec![1; 20]),
+			recipient: BridgeAddress(vec![2; 32]),
+			hash_lock: HashLock([3; 32]),
+			time_lock: TimeLock(200),
+			amount: Amount(10),
+			state: 1,
+		};
+		InitiatedTransfer::new(ChainId::ONE, transfer_id, details).0.into()
 	}
 
-	pub fn transition_from_cancelled(
-		mut self,
-		_transfer_id: BridgeTransferId,
-	) -> (Self, TransferActionType) {
-		self.state = TransferStateType::Done;
-		let action_type = TransferActionType::NoAction;
-		(self, action_type)
+	// Validate and apply the event of the chain.
+	fn apply(
+		state: TransferState,
+		chain: ChainId,
+		contract_event: BridgeContractEvent<Vec<u8>>,
+	) -> Result<TransferState, InvalidEventError> {
+		let event = TransferEvent { chain, contract_event };
+		state.validate_event(&event)?;
+		Ok(state.apply_event(event.contract_event)?.0)
 	}
 
-	pub fn transition_from_refunded(
-		mut self,
-		_transfer_id: BridgeTransferId,
-	) -> (Self, TransferActionType) {
-		self.state = TransferStateType::Done;
-		let action_type = TransferActionType::NoAction;
-		(self, action_type)
+	#[test]
+	fn test_aborted_transfer_refunded() -> Result<(), InvalidEventError> {
+		let transfer_id = BridgeTransferId([4; 32]);
+		let lock = LockDetails {
+			bridge_transfer_id: transfer_id,
+			initiator: BridgeAddress(vec![1; 20]),
+			recipient: BridgeAddress(vec![2; 32]),
+			hash_lock: HashLock([3; 32]),
+			time_lock: TimeLock(100),
+			amount: Amount(10),
+		};
+		let state = apply(initiated(transfer_id), ChainId::TWO, BridgeContractEvent::Locked(lock))?;
+		assert_eq!(state.state_type(), TransferStateType::Locked);
+		// The aborted lock waits for the refund of the initiator, a replayed abort is a no-op.
+		let state = apply(state, ChainId::TWO, BridgeContractEvent::Cancelled(transfer_id))?;
+		assert_eq!(state.state_type(), TransferStateType::LockAborted);
+		let state = apply(state, ChainId::TWO, BridgeContractEvent::Cancelled(transfer_id))?;
+		assert_eq!(state.state_type(), TransferStateType::LockAborted);
+		let state = apply(state, ChainId::ONE, BridgeContractEvent::Refunded(transfer_id))?;
+		assert_eq!(state.state_type(), TransferStateType::Refunded);
+		assert!(state.state_type().is_final());
+		assert_eq!("LockAborted".parse::<TransferStateType>(), Ok(TransferStateType::LockAborted));
+		Ok(())
+	}
+
+	#[test]
+	fn test_final_states() -> Result<(), InvalidEventError> {
+		let transfer_id = BridgeTransferId([7; 32]);
+		let lock = LockDetails {
+			bridge_transfer_id: transfer_id,
+			initiator: BridgeAddress(vec![1; 20]),
+			recipient: BridgeAddress(vec![2; 32]),
+			hash_lock: HashLock([3; 32]),
+			time_lock: TimeLock(100),
+			amount: Amount(10),
+		};
+		let locked = || {
+			apply(initiated(transfer_id), ChainId::TWO, BridgeContractEvent::Locked(lock.clone()))
+		};
+		let secret =
+			BridgeContractEvent::CounterPartyCompleted(transfer_id, HashLockPreImage([6; 32]));
+		let completed = BridgeContractEvent::InitiatorCompleted(transfer_id);
+
+		// Completed once the initiator side is completed with the secret.
+		let state = apply(locked()?, ChainId::TWO, secret)?;
+		let state = apply(state, ChainId::ONE, completed.clone())?;
+		assert!(matches!(state, TransferState::Completed(_)));
+		// Refunded before the lock.
+		let state = apply(
+			initiated(transfer_id),
+			ChainId::ONE,
+			BridgeContractEvent::Refunded(transfer_id),
+		)?;
+		assert!(matches!(state, TransferState::Refunded(_)));
+		// Aborted once its initiation is dropped before the lock.
+		let state = initiated(transfer_id).drop_initiation(false);
+		assert!(matches!(state, TransferState::Aborted(_)));
+		// The lock of a dropped initiation is aborted, there is nothing to refund.
+		let state = locked()?.drop_initiation(true);
+		assert_eq!(state.state_type(), TransferStateType::Refund);
+		let state = apply(state, ChainId::TWO, BridgeContractEvent::Cancelled(transfer_id))?;
+		assert!(matches!(state, TransferState::Aborted(_)));
+
+		// Nothing is applied on a transfer over.
+		for event in [
+			completed,
+			BridgeContractEvent::Refunded(transfer_id),
+			BridgeContractEvent::Cancelled(transfer_id),
+		] {
+			let aborted = initiated(transfer_id).drop_initiation(false);
+			assert!(matches!(aborted.apply_event(event), Err(InvalidEventError::BadEvent(_))));
+		}
+		for state in ["Completed", "Refunded", "Aborted"] {
+			assert!(state.parse::<TransferStateType>().unwrap().is_final());
+		}
+		assert!(!TransferStateType::LockAborted.is_final());
+		assert!("Done".parse::<TransferStateType>().is_err());
+		Ok(())
 	}
 
-	pub fn transition_to_refund(&self) -> (TransferStateType, TransferActionType) {
-		(TransferStateType::Refund, TransferActionType::RefundInitiator)
+	#[test]
+	fn test_illegal_transitions() {
+		let transfer_id = BridgeTransferId([5; 32]);
+		let secret =
+			BridgeContractEvent::CounterPartyCompleted(transfer_id, HashLockPreImage([6; 32]));
+		// The secret can't be revealed before the lock.
+		assert!(apply(initiated(transfer_id), ChainId::TWO, secret.clone()).is_err());
+		assert!(initiated(transfer_id).apply_event(secret).is_err());
+		// The initiator can't be completed before the secret is revealed.
+		let completed = BridgeContractEvent::InitiatorCompleted(transfer_id);
+		assert!(matches!(
+			apply(initiated(transfer_id), ChainId::ONE, completed),
+			Err(InvalidEventError::BadEvent(_))
+		));
+		// A transfer can't be initiated twice.
+		let details = BridgeTransferDetails {
+			bridge_transfer_id: transfer_id,
+			initiator: BridgeAddress(vec![1; 20]),
+			recipient: BridgeAddress(vec![2; 32]),
+			hash_lock: HashLock([3; 32]),
+			time_lock: TimeLock(200),
+			amount: Amount(10),
+			state: 1,
+		};
+		assert!(matches!(
+			apply(initiated(transfer_id), ChainId::ONE, BridgeContractEvent::Initiated(details)),
+			Err(InvalidEventError::InitAnAlreadyExist)
+		));
 	}
 }
@@ -3,7 +3,7 @@ use alloy::serde::quantity::vec;
 use derive_more::{Deref, DerefMut};
 use hex::{self, FromHexError};
 use rand::Rng;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
 use std::fmt;
 use std::{fmt::Debug, hash::Hash};
@@ -23,7 +23,7 @@ pub enum AddressError {
 	AddressConvertionlError(String),
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum ChainId {
 	ONE,
 	TWO,
@@ -48,7 +48,47 @@ impl fmt::Display for ChainId {
 	}
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+impl std::str::FromStr for ChainId {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"ONE" => Ok(ChainId::ONE),
+			"TWO" => Ok(ChainId::TWO),
+			_ => Err(format!("Unknown chain: {s}, expected ONE or TWO")),
+		}
+	}
+}
+
+/// Direction of a transfer, named after the initiator chain then the counterparty chain.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferDirection {
+	EthToMovement,
+	MovementToEth,
+}
+
+impl TransferDirection {
+	/// The direction of a transfer initiated on the chain. Chain ONE is Ethereum.
+	pub fn from_init_chain(chain: ChainId) -> Self {
+		match chain {
+			ChainId::ONE => TransferDirection::EthToMovement,
+			ChainId::TWO => TransferDirection::MovementToEth,
+		}
+	}
+}
+
+impl fmt::Display for TransferDirection {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let s = match self {
+			TransferDirection::EthToMovement => "eth_to_movement",
+			TransferDirection::MovementToEth => "movement_to_eth",
+		};
+		write!(f, "{}", s)
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct BridgeTransferId(pub BridgeHash);
 
 impl BridgeTransferId {
@@ -84,7 +124,7 @@ impl fmt::Display for BridgeTransferId {
 	}
 }
 
-#[derive(Deref, Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[derive(Deref, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct BridgeAddress<A>(pub A);
 
 impl BridgeAddress<Vec<u8>> {
@@ -106,6 +146,38 @@ impl From<String> for BridgeAddress<Vec<u8>> {
 	}
 }
 
+/// The address rendered in the canonical form of its chain.
+impl<A> fmt::Display for BridgeAddress<A>
+where
+	A: Clone + Into<Vec<u8>>,
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		DisplayAddress(&self.0.clone().into()).fmt(f)
+	}
+}
+
+/// Renders the bytes of an address in the canonical form of its chain:
+/// EIP-55 checksummed hex for the 20 bytes Ethereum addresses, lowercase hex for
+/// the 32 bytes Movement addresses with the special addresses 0x0 to 0xf in short form.
+#[derive(Debug, Clone, Copy)]
+pub struct DisplayAddress<'a>(pub &'a [u8]);
+
+impl fmt::Display for DisplayAddress<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self.0 {
+			bytes if bytes.len() == 20 => {
+				write!(f, "{}", alloy::primitives::Address::from_slice(bytes).to_checksum(None))
+			}
+			[zeros @ .., last]
+				if zeros.len() == 31 && *last < 0x10 && zeros.iter().all(|b| *b == 0) =>
+			{
+				write!(f, "0x{last:x}")
+			}
+			bytes => write!(f, "0x{}", hex::encode(bytes)),
+		}
+	}
+}
+
 #[derive(Error, Debug)]
 pub enum BridgeAddressError {
 	#[error("Invalid conversion from BridgeAddress to Vec<u8>")]
@@ -127,7 +199,51 @@ where
 	}
 }
 
-#[derive(Deref, Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+/// Digest of the preimages giving the hash locks. The Ethereum contracts use keccak256, a Move
+/// module may use sha2-256.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HashLockAlgorithm {
+	#[default]
+	Keccak256,
+	Sha2_256,
+}
+
+impl HashLockAlgorithm {
+	pub fn digest(&self, bytes: &[u8]) -> [u8; 32] {
+		match self {
+			HashLockAlgorithm::Keccak256 => alloy::primitives::keccak256(bytes).0,
+			HashLockAlgorithm::Sha2_256 => {
+				use sha2::Digest;
+				sha2::Sha256::digest(bytes).into()
+			}
+		}
+	}
+}
+
+impl std::str::FromStr for HashLockAlgorithm {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"keccak256" => Ok(HashLockAlgorithm::Keccak256),
+			"sha2_256" => Ok(HashLockAlgorithm::Sha2_256),
+			_ => Err(format!("Unknown hash lock algorithm: {s}, expected keccak256 or sha2_256")),
+		}
+	}
+}
+
+impl fmt::Display for HashLockAlgorithm {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let s = match self {
+			HashLockAlgorithm::Keccak256 => "keccak256",
+			HashLockAlgorithm::Sha2_256 => "sha2_256",
+		};
+		write!(f, "{}", s)
+	}
+}
+
+#[derive(Deref, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct HashLock(pub [u8; 32]);
 
 impl HashLock {
@@ -149,9 +265,19 @@ impl HashLock {
 		let array = [0u8; 32];
 		HashLock(array)
 	}
+
+	/// Hash lock of the preimage bytes taken by a contract, with the digest of the contract.
+	pub fn of_pre_image(pre_image: &[u8], algorithm: HashLockAlgorithm) -> Self {
+		HashLock(algorithm.digest(pre_image))
+	}
+
+	/// Whether the preimage bytes taken by a contract unlock the hash lock.
+	pub fn is_unlocked_by(&self, pre_image: &[u8], algorithm: HashLockAlgorithm) -> bool {
+		algorithm.digest(pre_image) == self.0
+	}
 }
 
-#[derive(Deref, Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[derive(Deref, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct HashLockPreImage(pub [u8; 32]);
 
 impl AsRef<[u8]> for HashLockPreImage {
@@ -161,6 +287,12 @@ impl AsRef<[u8]> for HashLockPreImage {
 }
 
 impl HashLockPreImage {
+	pub fn parse(s: &str) -> Result<Self, FromHexError> {
+		let bytes = hex::decode(s)?;
+		let array: [u8; 32] =
+			bytes.as_slice().try_into().map_err(|_| FromHexError::InvalidStringLength)?;
+		Ok(HashLockPreImage(array))
+	}
 	/// Generate a cryptographically secure random secret
 	pub fn random() -> Self {
 		let mut rng = rand::thread_rng();
@@ -170,7 +302,50 @@ impl HashLockPreImage {
 	}
 }
 
-#[derive(Deref, Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+/// Preimage of a hash lock, with the bytes each chain hashes: the Ethereum contracts hash the
+/// raw bytes, the Move modules the BCS encoding of the `vector<u8>` they take.
+#[derive(Deref, Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Preimage(pub Vec<u8>);
+
+impl Preimage {
+	/// Bytes hashed by the Move modules.
+	pub fn to_movement_bytes(&self) -> Vec<u8> {
+		bcs::to_bytes(&self.0).expect("BCS encoding of bytes can't fail")
+	}
+
+	/// Preimage of the bytes hashed by the Move modules.
+	pub fn from_movement_bytes(bytes: &[u8]) -> Result<Self, bcs::Error> {
+		bcs::from_bytes(bytes).map(Preimage)
+	}
+
+	/// Hash lock of the preimage on Ethereum: keccak256 of the raw bytes.
+	pub fn hash_for_eth(&self) -> HashLock {
+		HashLock::of_pre_image(&self.0, HashLockAlgorithm::Keccak256)
+	}
+
+	/// Hash lock of the preimage on Movement: keccak256 of the BCS encoded bytes.
+	pub fn hash_for_movement(&self) -> HashLock {
+		HashLock::of_pre_image(&self.to_movement_bytes(), HashLockAlgorithm::Keccak256)
+	}
+}
+
+impl From<HashLockPreImage> for Preimage {
+	fn from(pre_image: HashLockPreImage) -> Self {
+		Preimage(pre_image.0.to_vec())
+	}
+}
+
+impl TryFrom<Preimage> for HashLockPreImage {
+	type Error = Preimage;
+
+	/// The 32 bytes preimage taken by the Ethereum contracts, the preimage back if it's not
+	/// 32 bytes long.
+	fn try_from(pre_image: Preimage) -> Result<Self, Self::Error> {
+		<[u8; 32]>::try_from(pre_image.0).map(HashLockPreImage).map_err(Preimage)
+	}
+}
+
+#[derive(Deref, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TimeLock(pub u64);
 
 impl From<Uint<256, 4>> for TimeLock {
@@ -181,7 +356,7 @@ impl From<Uint<256, 4>> for TimeLock {
 	}
 }
 
-#[derive(Deref, DerefMut, Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[derive(Deref, DerefMut, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Amount(pub u64);
 
 impl From<Uint<256, 4>> for Amount {
@@ -198,7 +373,7 @@ pub enum ConversionError {
 	InvalidConversion,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct BridgeTransferDetails<A> {
 	pub bridge_transfer_id: BridgeTransferId,
 	pub initiator: BridgeAddress<A>,
@@ -209,7 +384,22 @@ pub struct BridgeTransferDetails<A> {
 	pub state: u8,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Deserialize)]
+impl<A: Clone + Into<Vec<u8>>> fmt::Display for BridgeTransferDetails<A> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{} / initiator: {} / recipient: {} / hash lock: {} / time lock: {} / amount: {}",
+			self.bridge_transfer_id,
+			self.initiator,
+			self.recipient,
+			hex::encode(self.hash_lock.0),
+			self.time_lock.0,
+			self.amount.0
+		)
+	}
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct BridgeTransferDetailsCounterparty<A> {
 	pub bridge_transfer_id: BridgeTransferId,
 	pub initiator: BridgeAddress<Vec<u8>>,
@@ -220,7 +410,7 @@ pub struct BridgeTransferDetailsCounterparty<A> {
 	pub state: u8,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct LockDetails<A> {
 	pub bridge_transfer_id: BridgeTransferId,
 	pub initiator: BridgeAddress<Vec<u8>>,
@@ -229,3 +419,85 @@ pub struct LockDetails<A> {
 	pub time_lock: TimeLock,
 	pub amount: Amount,
 }
+
+/// A transfer to lock on the counterparty contract.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct LockTransfer<A> {
+	pub bridge_transfer_id: BridgeTransferId,
+	pub hash_lock: HashLock,
+	pub initiator: BridgeAddress<Vec<u8>>,
+	pub recipient: BridgeAddress<A>,
+	pub amount: Amount,
+}
+
+impl<A: Clone + Into<Vec<u8>>> fmt::Display for LockDetails<A> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{} / initiator: {} / recipient: {} / hash lock: {} / time lock: {} / amount: {}",
+			self.bridge_transfer_id,
+			self.initiator,
+			self.recipient,
+			hex::encode(self.hash_lock.0),
+			self.time_lock.0,
+			self.amount.0
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::chains::bridge_contracts::BridgeContractEvent;
+	use crate::events::TransferEvent;
+	use serde::de::DeserializeOwned;
+
+	// The value is the same after a JSON and a BCS round trip.
+	fn assert_round_trip<T: Serialize + DeserializeOwned + PartialEq + Debug>(value: T) {
+		let json = serde_json::to_string(&value).unwrap();
+		assert_eq!(serde_json::from_str::<T>(&json).unwrap(), value);
+		let bytes = bcs::to_bytes(&value).unwrap();
+		assert_eq!(bcs::from_bytes::<T>(&bytes).unwrap(), value);
+	}
+
+	#[test]
+	fn test_shared_types_round_trip() {
+		let details = BridgeTransferDetails {
+			bridge_transfer_id: BridgeTransferId([1; 32]),
+			initiator: BridgeAddress(vec![2; 20]),
+			recipient: BridgeAddress(vec![3; 32]),
+			hash_lock: HashLock([4; 32]),
+			time_lock: TimeLock(100),
+			amount: Amount(1000),
+			state: 1,
+		};
+		assert_round_trip(details.clone());
+		let lock = LockDetails {
+			bridge_transfer_id: BridgeTransferId([1; 32]),
+			initiator: BridgeAddress(vec![2; 20]),
+			recipient: BridgeAddress([3u8; 32]),
+			hash_lock: HashLock([4; 32]),
+			time_lock: TimeLock(100),
+			amount: Amount(1000),
+		};
+		assert_round_trip(lock);
+		assert_round_trip(HashLockPreImage([5; 32]));
+		assert_round_trip(Preimage(b"secret".to_vec()));
+
+		let events = [
+			BridgeContractEvent::Initiated(details),
+			BridgeContractEvent::Locked(LockDetails {
+				recipient: BridgeAddress(lock.recipient.0.to_vec()),
+				..lock
+			}),
+			BridgeContractEvent::CounterPartyCompleted(
+				BridgeTransferId([1; 32]),
+				HashLockPreImage([5; 32]),
+			),
+			BridgeContractEvent::Refunded(BridgeTransferId([1; 32])),
+		];
+		for event in events {
+			assert_round_trip(TransferEvent { chain: ChainId::TWO, contract_event: event });
+		}
+	}
+}
@@ -69,8 +69,64 @@ where
 	AffinePoint<C>: FromEncodedPoint<C> + ToEncodedPoint<C> + VerifyPrimitive<C>,
 	FieldBytesSize<C>: ModulusSize,
 {
-	async fn verify(&self, blob: CelestiaBlob, height: u64) -> Result<Verified<IntermediateBlobRepresentation>, Error> {
+	async fn verify(
+		&self,
+		blob: CelestiaBlob,
+		height: u64,
+	) -> Result<Verified<IntermediateBlobRepresentation>, Error> {
 		let verified_blob = self.celestia.verify(blob, height).await?;
 		self.known_signers.verify(verified_blob.into_inner(), height).await
 	}
 }
+
+/// A verifier of blobs from a DA backend without a Celestia node.
+/// Only checks that the blob decodes and is signed by a known signer.
+#[derive(Clone)]
+pub struct LocalVerifier<C>
+where
+	C: PrimeCurve + CurveArithmetic + DigestPrimitive + PointCompression,
+	Scalar<C>: Invert<Output = CtOption<Scalar<C>>> + SignPrimitive<C>,
+	SignatureSize<C>: ArrayLength<u8>,
+	AffinePoint<C>: FromEncodedPoint<C> + ToEncodedPoint<C> + VerifyPrimitive<C>,
+	FieldBytesSize<C>: ModulusSize,
+{
+	/// The verifier for known signers
+	pub known_signers: InKnownSignersVerifier<C>,
+}
+
+impl<C> LocalVerifier<C>
+where
+	C: PrimeCurve + CurveArithmetic + DigestPrimitive + PointCompression,
+	Scalar<C>: Invert<Output = CtOption<Scalar<C>>> + SignPrimitive<C>,
+	SignatureSize<C>: ArrayLength<u8>,
+	AffinePoint<C>: FromEncodedPoint<C> + ToEncodedPoint<C> + VerifyPrimitive<C>,
+	FieldBytesSize<C>: ModulusSize,
+{
+	pub fn new<T>(known_signers_sec1_bytes: T) -> Self
+	where
+		T: IntoIterator,
+		T::Item: Into<String>,
+	{
+		Self { known_signers: InKnownSignersVerifier::new(known_signers_sec1_bytes) }
+	}
+}
+
+#[tonic::async_trait]
+impl<C> VerifierOperations<CelestiaBlob, IntermediateBlobRepresentation> for LocalVerifier<C>
+where
+	C: PrimeCurve + CurveArithmetic + DigestPrimitive + PointCompression,
+	Scalar<C>: Invert<Output = CtOption<Scalar<C>>> + SignPrimitive<C>,
+	SignatureSize<C>: ArrayLength<u8>,
+	AffinePoint<C>: FromEncodedPoint<C> + ToEncodedPoint<C> + VerifyPrimitive<C>,
+	FieldBytesSize<C>: ModulusSize,
+{
+	async fn verify(
+		&self,
+		blob: CelestiaBlob,
+		height: u64,
+	) -> Result<Verified<IntermediateBlobRepresentation>, Error> {
+		let ir_blob = IntermediateBlobRepresentation::try_from(blob)
+			.map_err(|e| Error::Internal(e.to_string()))?;
+		self.known_signers.verify(ir_blob, height).await
+	}
+}
@@ -0,0 +1,114 @@
+use celestia_rpc::Client;
+use movement_celestia_da_util::config::Config;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tracing::{info, warn};
+
+#[derive(Debug, Default)]
+struct AuthStatus {
+	token: Option<String>,
+	// Set when the node rejected the token, until the client is reconnected.
+	rejected: Option<String>,
+	refresh_error: Option<String>,
+}
+
+/// A Celestia client whose auth token can be rotated at runtime.
+/// The token is re-read from the configured file on an interval or when the node rejects it,
+/// and the client is reconnected when the token changed.
+/// Clones share the same client.
+#[derive(Clone)]
+pub struct CelestiaClient {
+	config: Config,
+	client: Arc<RwLock<Arc<Client>>>,
+	status: Arc<Mutex<AuthStatus>>,
+	refresh_notify: Arc<Notify>,
+}
+
+impl CelestiaClient {
+	/// Connects to the Celestia node with the configured auth token.
+	pub async fn connect(config: Config) -> Result<Self, anyhow::Error> {
+		let client = config.connect_celestia().await?;
+		Ok(Self {
+			config,
+			client: Arc::new(RwLock::new(Arc::new(client))),
+			status: Arc::new(Mutex::new(AuthStatus::default())),
+			refresh_notify: Arc::new(Notify::new()),
+		})
+	}
+
+	/// Gets the current client. Calls in flight keep the client they started with.
+	pub fn client(&self) -> Arc<Client> {
+		self.client.read().expect("Celestia client lock poisoned").clone()
+	}
+
+	/// Checks whether a Celestia call failed on the auth token and triggers a refresh if so.
+	pub fn observe_error(&self, error: &impl std::fmt::Display) {
+		let error = error.to_string();
+		if error.contains("401") || error.to_lowercase().contains("unauthorized") {
+			warn!(error = %error, "Celestia node rejected the auth token");
+			self.status().rejected = Some(format!("auth token rejected: {error}"));
+			self.refresh_notify.notify_one();
+		}
+	}
+
+	/// Re-reads the auth token and reconnects if it changed.
+	/// Returns whether the client was replaced.
+	pub async fn refresh(&self) -> Result<bool, anyhow::Error> {
+		let path = match self.config.celestia_auth_token_path() {
+			Some(path) => path,
+			None => return Ok(false),
+		};
+		let token = tokio::fs::read_to_string(&path)
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to read the auth token from {path}: {e}"))?
+			.trim()
+			.to_string();
+		if token.is_empty() {
+			anyhow::bail!("Auth token file {path} is empty");
+		}
+		if self.status().token.as_ref() == Some(&token) {
+			return Ok(false);
+		}
+
+		let client = self.config.connect_celestia_with_auth_token(&token).await?;
+		*self.client.write().expect("Celestia client lock poisoned") = Arc::new(client);
+		let mut status = self.status();
+		status.token = Some(token);
+		status.rejected = None;
+		info!("Reconnected to Celestia with a refreshed auth token from {path}");
+		Ok(true)
+	}
+
+	/// Refreshes the auth token on an interval or when the node rejected it.
+	pub async fn run_refresh(&self) -> Result<(), anyhow::Error> {
+		let interval_secs = self.config.celestia_auth_token_refresh_interval_secs();
+		let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+		loop {
+			tokio::select! {
+				_ = interval.tick() => {}
+				_ = self.refresh_notify.notified() => {}
+			}
+			match self.refresh().await {
+				Ok(_) => self.status().refresh_error = None,
+				Err(e) => {
+					warn!(error = %e, "failed to refresh the Celestia auth token");
+					self.status().refresh_error = Some(e.to_string());
+				}
+			}
+		}
+	}
+
+	/// Returns the auth error, if the token was rejected or could not be refreshed.
+	pub fn health(&self) -> Result<(), String> {
+		let status = self.status();
+		match status.rejected.as_ref().or(status.refresh_error.as_ref()) {
+			Some(error) => Err(error.clone()),
+			None => Ok(()),
+		}
+	}
+
+	fn status(&self) -> std::sync::MutexGuard<'_, AuthStatus> {
+		self.status.lock().expect("Celestia auth status lock poisoned")
+	}
+}
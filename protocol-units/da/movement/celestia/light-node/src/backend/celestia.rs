@@ -0,0 +1,82 @@
+use super::{DaBackend, HeightStream};
+use crate::auth::CelestiaClient;
+use celestia_rpc::{BlobClient, Client, HeaderClient, StateClient};
+use celestia_types::{nmt::Namespace, Blob as CelestiaBlob, TxConfig};
+use movement_celestia_da_util::config::Config;
+use std::sync::Arc;
+use tokio_stream::StreamExt;
+
+/// Stores blobs on Celestia through a Celestia light node.
+#[derive(Clone)]
+pub struct CelestiaBackend {
+	pub client: CelestiaClient,
+	pub namespace: Namespace,
+}
+
+impl CelestiaBackend {
+	pub async fn connect(config: Config) -> Result<Self, anyhow::Error> {
+		let namespace = config.celestia_namespace();
+		let client = CelestiaClient::connect(config).await?;
+		Ok(Self { client, namespace })
+	}
+
+	/// Gets the Celestia client connected with the current auth token.
+	pub fn default_client(&self) -> Arc<Client> {
+		self.client.client()
+	}
+}
+
+#[tonic::async_trait]
+impl DaBackend for CelestiaBackend {
+	async fn submit_blobs(&self, blobs: &[CelestiaBlob]) -> Result<u64, anyhow::Error> {
+		self.default_client()
+			.blob_submit(blobs, TxConfig::default())
+			.await
+			.map_err(|e| {
+				self.client.observe_error(&e);
+				anyhow::anyhow!("Failed submitting the blob: {}", e)
+			})
+	}
+
+	async fn get_blobs_at_height(&self, height: u64) -> Result<Vec<CelestiaBlob>, anyhow::Error> {
+		let blobs = self
+			.default_client()
+			.blob_get_all(height, &[self.namespace])
+			.await
+			.inspect_err(|e| self.client.observe_error(e))?;
+		Ok(blobs.unwrap_or_default())
+	}
+
+	async fn head_height(&self) -> Result<u64, anyhow::Error> {
+		let header = self
+			.default_client()
+			.header_network_head()
+			.await
+			.inspect_err(|e| self.client.observe_error(e))?;
+		Ok(header.height().into())
+	}
+
+	async fn subscribe_heights(&self) -> Result<HeightStream, anyhow::Error> {
+		let mut subscription = self.default_client().header_subscribe().await?;
+		let stream = async_stream::try_stream! {
+			while let Some(header) = subscription.next().await {
+				let height: u64 = header?.height().into();
+				yield height;
+			}
+		};
+		Ok(Box::pin(stream))
+	}
+
+	async fn account_balance(&self) -> Result<Option<u64>, anyhow::Error> {
+		let balance = self.default_client().state_balance().await?;
+		Ok(Some(balance.amount.to_string().parse()?))
+	}
+
+	async fn run_background_tasks(&self) -> Result<(), anyhow::Error> {
+		self.client.run_refresh().await
+	}
+
+	fn health(&self) -> Result<(), String> {
+		self.client.health()
+	}
+}
@@ -0,0 +1,104 @@
+use super::{watch_heights, DaBackend, HeightStream};
+use celestia_types::{nmt::Namespace, Blob as CelestiaBlob};
+use std::path::PathBuf;
+use tokio::sync::{watch, Mutex};
+
+/// Stores blobs in a local directory, one JSON file per height.
+/// Meant for CI and local development without a Celestia node; blobs survive restarts.
+pub struct LocalFileBackend {
+	path: PathBuf,
+	namespace: Namespace,
+	// Serializes the submissions so each one gets its own height.
+	submit_lock: Mutex<()>,
+	head: watch::Sender<u64>,
+}
+
+impl LocalFileBackend {
+	/// Opens the backend directory, creating it if needed, and resumes from its latest height.
+	pub async fn try_new(
+		path: impl Into<PathBuf>,
+		namespace: Namespace,
+	) -> Result<Self, anyhow::Error> {
+		let path = path.into();
+		tokio::fs::create_dir_all(&path).await?;
+
+		let mut head = 0;
+		let mut entries = tokio::fs::read_dir(&path).await?;
+		while let Some(entry) = entries.next_entry().await? {
+			let height = entry
+				.file_name()
+				.to_str()
+				.and_then(|name| name.strip_suffix(".json"))
+				.and_then(|height| height.parse::<u64>().ok());
+			if let Some(height) = height {
+				head = head.max(height);
+			}
+		}
+
+		let (head, _) = watch::channel(head);
+		Ok(Self { path, namespace, submit_lock: Mutex::new(()), head })
+	}
+
+	fn height_path(&self, height: u64) -> PathBuf {
+		self.path.join(format!("{height}.json"))
+	}
+}
+
+#[tonic::async_trait]
+impl DaBackend for LocalFileBackend {
+	async fn submit_blobs(&self, blobs: &[CelestiaBlob]) -> Result<u64, anyhow::Error> {
+		let _lock = self.submit_lock.lock().await;
+		let height = *self.head.borrow() + 1;
+		let data = serde_json::to_vec(blobs)?;
+
+		// write then rename so readers never see a partial file
+		let tmp_path = self.path.join(format!("{height}.json.tmp"));
+		tokio::fs::write(&tmp_path, data).await?;
+		tokio::fs::rename(&tmp_path, self.height_path(height)).await?;
+
+		self.head.send_replace(height);
+		Ok(height)
+	}
+
+	async fn get_blobs_at_height(&self, height: u64) -> Result<Vec<CelestiaBlob>, anyhow::Error> {
+		let data = match tokio::fs::read(self.height_path(height)).await {
+			Ok(data) => data,
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+			Err(e) => return Err(e.into()),
+		};
+		let blobs: Vec<CelestiaBlob> = serde_json::from_slice(&data)?;
+		Ok(blobs.into_iter().filter(|blob| blob.namespace == self.namespace).collect())
+	}
+
+	async fn head_height(&self) -> Result<u64, anyhow::Error> {
+		Ok(*self.head.borrow())
+	}
+
+	async fn subscribe_heights(&self) -> Result<HeightStream, anyhow::Error> {
+		Ok(watch_heights(self.head.subscribe()))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn test_local_file_backend_resumes() -> Result<(), anyhow::Error> {
+		let dir = tempfile::tempdir()?;
+		let namespace = Namespace::new_v0(b"movement")?;
+		let blob = CelestiaBlob::new(namespace, vec![1, 2, 3])?;
+
+		let backend = LocalFileBackend::try_new(dir.path(), namespace).await?;
+		assert_eq!(backend.submit_blobs(&[blob.clone()]).await?, 1);
+		assert_eq!(backend.submit_blobs(&[blob.clone(), blob.clone()]).await?, 2);
+		drop(backend);
+
+		let backend = LocalFileBackend::try_new(dir.path(), namespace).await?;
+		assert_eq!(backend.head_height().await?, 2);
+		assert_eq!(backend.get_blobs_at_height(2).await?, vec![blob.clone(), blob]);
+		assert!(backend.get_blobs_at_height(3).await?.is_empty());
+
+		Ok(())
+	}
+}
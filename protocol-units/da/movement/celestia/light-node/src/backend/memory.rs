@@ -0,0 +1,76 @@
+use super::{watch_heights, DaBackend, HeightStream};
+use celestia_types::{nmt::Namespace, Blob as CelestiaBlob};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tokio::sync::watch;
+
+/// Keeps blobs in memory, each submission producing a new height.
+/// Meant for tests and local development without a Celestia node.
+pub struct MemoryBackend {
+	namespace: Namespace,
+	blobs: Mutex<BTreeMap<u64, Vec<CelestiaBlob>>>,
+	head: watch::Sender<u64>,
+}
+
+impl MemoryBackend {
+	pub fn new(namespace: Namespace) -> Self {
+		let (head, _) = watch::channel(0);
+		Self { namespace, blobs: Mutex::new(BTreeMap::new()), head }
+	}
+}
+
+#[tonic::async_trait]
+impl DaBackend for MemoryBackend {
+	async fn submit_blobs(&self, blobs: &[CelestiaBlob]) -> Result<u64, anyhow::Error> {
+		let mut stored = self.blobs.lock().expect("Memory backend lock poisoned");
+		let height = *self.head.borrow() + 1;
+		stored.insert(height, blobs.to_vec());
+		self.head.send_replace(height);
+		Ok(height)
+	}
+
+	async fn get_blobs_at_height(&self, height: u64) -> Result<Vec<CelestiaBlob>, anyhow::Error> {
+		let stored = self.blobs.lock().expect("Memory backend lock poisoned");
+		Ok(stored
+			.get(&height)
+			.into_iter()
+			.flatten()
+			.filter(|blob| blob.namespace == self.namespace)
+			.cloned()
+			.collect())
+	}
+
+	async fn head_height(&self) -> Result<u64, anyhow::Error> {
+		Ok(*self.head.borrow())
+	}
+
+	async fn subscribe_heights(&self) -> Result<HeightStream, anyhow::Error> {
+		Ok(watch_heights(self.head.subscribe()))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tokio_stream::StreamExt;
+
+	#[tokio::test]
+	async fn test_memory_backend() -> Result<(), anyhow::Error> {
+		let namespace = Namespace::new_v0(b"movement")?;
+		let backend = MemoryBackend::new(namespace);
+		let mut heights = backend.subscribe_heights().await?;
+
+		let blob = CelestiaBlob::new(namespace, vec![1, 2, 3])?;
+		assert_eq!(backend.submit_blobs(&[blob.clone()]).await?, 1);
+		assert_eq!(backend.submit_blobs(&[blob.clone(), blob]).await?, 2);
+
+		assert_eq!(backend.head_height().await?, 2);
+		assert_eq!(backend.get_blobs_at_height(1).await?.len(), 1);
+		assert_eq!(backend.get_blobs_at_height(2).await?.len(), 2);
+		assert!(backend.get_blobs_at_height(3).await?.is_empty());
+		assert_eq!(heights.next().await.transpose()?, Some(1));
+		assert_eq!(heights.next().await.transpose()?, Some(2));
+
+		Ok(())
+	}
+}
@@ -0,0 +1,60 @@
+pub mod celestia;
+pub mod file;
+pub mod memory;
+
+pub use celestia::CelestiaBackend;
+pub use file::LocalFileBackend;
+pub use memory::MemoryBackend;
+
+use celestia_types::Blob as CelestiaBlob;
+use std::pin::Pin;
+use tokio::sync::watch;
+use tokio_stream::Stream;
+
+pub type HeightStream = Pin<Box<dyn Stream<Item = Result<u64, anyhow::Error>> + Send>>;
+
+/// The data availability layer the light node submits blobs to and reads blobs from.
+#[tonic::async_trait]
+pub trait DaBackend: Send + Sync {
+	/// Submits the blobs and returns the height they were included at.
+	async fn submit_blobs(&self, blobs: &[CelestiaBlob]) -> Result<u64, anyhow::Error>;
+
+	/// Gets the blobs of the light node namespace included at the height.
+	async fn get_blobs_at_height(&self, height: u64) -> Result<Vec<CelestiaBlob>, anyhow::Error>;
+
+	/// Gets the latest height of the DA layer.
+	async fn head_height(&self) -> Result<u64, anyhow::Error>;
+
+	/// Streams the new heights as they are produced.
+	async fn subscribe_heights(&self) -> Result<HeightStream, anyhow::Error>;
+
+	/// Gets the balance of the account paying for the submissions, if there is one.
+	async fn account_balance(&self) -> Result<Option<u64>, anyhow::Error> {
+		Ok(None)
+	}
+
+	/// Runs the background tasks of the backend.
+	async fn run_background_tasks(&self) -> Result<(), anyhow::Error> {
+		Ok(())
+	}
+
+	/// Returns an error if the backend can't currently serve requests.
+	fn health(&self) -> Result<(), String> {
+		Ok(())
+	}
+}
+
+/// Streams every height from the current one on, including the ones skipped by the watch channel.
+fn watch_heights(mut head: watch::Receiver<u64>) -> HeightStream {
+	let mut last = *head.borrow_and_update();
+	let stream = async_stream::stream! {
+		while head.changed().await.is_ok() {
+			let current = *head.borrow_and_update();
+			for height in last + 1..=current {
+				yield Ok::<_, anyhow::Error>(height);
+			}
+			last = current;
+		}
+	};
+	Box::pin(stream)
+}
@@ -1 +1,4 @@
+pub mod auth;
+pub mod backend;
+pub mod metrics;
 pub mod v1;
@@ -0,0 +1,237 @@
+use crate::backend::DaBackend;
+use poem::{
+	get, handler, http::StatusCode, listener::TcpListener, web::Data, EndpointExt, IntoResponse,
+	Response, Route, Server,
+};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::info;
+
+// Upper bounds of the blob size buckets in bytes.
+const BLOB_SIZE_BUCKETS: [f64; 8] =
+	[1_024.0, 4_096.0, 16_384.0, 65_536.0, 262_144.0, 524_288.0, 1_048_576.0, 2_097_152.0];
+// Upper bounds of the submission latency buckets in seconds.
+const SUBMIT_LATENCY_BUCKETS: [f64; 9] = [0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0];
+
+/// A Prometheus histogram with fixed buckets.
+#[derive(Debug)]
+struct Histogram {
+	bounds: &'static [f64],
+	counts: Vec<u64>,
+	sum: f64,
+	count: u64,
+}
+
+impl Histogram {
+	fn new(bounds: &'static [f64]) -> Self {
+		Self { bounds, counts: vec![0; bounds.len()], sum: 0.0, count: 0 }
+	}
+
+	fn observe(&mut self, value: f64) {
+		for (bound, count) in self.bounds.iter().zip(self.counts.iter_mut()) {
+			if value <= *bound {
+				*count += 1;
+			}
+		}
+		self.sum += value;
+		self.count += 1;
+	}
+
+	fn export(&self, name: &str, out: &mut String) {
+		let _ = writeln!(out, "# TYPE {name} histogram");
+		for (bound, count) in self.bounds.iter().zip(self.counts.iter()) {
+			let _ = writeln!(out, "{name}_bucket{{le=\"{bound}\"}} {count}");
+		}
+		let _ = writeln!(out, "{name}_bucket{{le=\"+Inf\"}} {}", self.count);
+		let _ = writeln!(out, "{name}_sum {}", self.sum);
+		let _ = writeln!(out, "{name}_count {}", self.count);
+	}
+}
+
+#[derive(Debug)]
+struct Histograms {
+	blob_size_bytes: Histogram,
+	submit_latency_seconds: Histogram,
+}
+
+#[derive(Debug)]
+struct Inner {
+	blobs_submitted: AtomicU64,
+	blobs_fetched: AtomicU64,
+	submission_failures: AtomicU64,
+	verification_failures: AtomicU64,
+	fee_paid_utia: AtomicU64,
+	last_balance_utia: AtomicU64,
+	last_fetched_height: AtomicU64,
+	celestia_head_height: AtomicU64,
+	histograms: Mutex<Histograms>,
+}
+
+/// Metrics of the blobs submitted to and fetched from Celestia by the light node.
+/// Clones share the same metrics.
+#[derive(Debug, Clone)]
+pub struct LightNodeMetrics {
+	inner: Arc<Inner>,
+}
+
+impl Default for LightNodeMetrics {
+	fn default() -> Self {
+		Self {
+			inner: Arc::new(Inner {
+				blobs_submitted: AtomicU64::new(0),
+				blobs_fetched: AtomicU64::new(0),
+				submission_failures: AtomicU64::new(0),
+				verification_failures: AtomicU64::new(0),
+				fee_paid_utia: AtomicU64::new(0),
+				last_balance_utia: AtomicU64::new(0),
+				last_fetched_height: AtomicU64::new(0),
+				celestia_head_height: AtomicU64::new(0),
+				histograms: Mutex::new(Histograms {
+					blob_size_bytes: Histogram::new(&BLOB_SIZE_BUCKETS),
+					submit_latency_seconds: Histogram::new(&SUBMIT_LATENCY_BUCKETS),
+				}),
+			}),
+		}
+	}
+}
+
+impl LightNodeMetrics {
+	/// Records a successful submission of blobs of the given sizes.
+	pub fn record_submission(&self, blob_sizes: &[usize], latency: Duration) {
+		self.inner.blobs_submitted.fetch_add(blob_sizes.len() as u64, Ordering::Relaxed);
+		let mut histograms = self.histograms();
+		for size in blob_sizes {
+			histograms.blob_size_bytes.observe(*size as f64);
+		}
+		histograms.submit_latency_seconds.observe(latency.as_secs_f64());
+	}
+
+	pub fn record_submission_failure(&self) {
+		self.inner.submission_failures.fetch_add(1, Ordering::Relaxed);
+	}
+
+	/// Records the balance of the submitting account after a submission.
+	/// The decrease since the previous sample is counted as fee paid, top-ups are ignored.
+	pub fn record_balance(&self, balance_utia: u64) {
+		let previous = self.inner.last_balance_utia.swap(balance_utia, Ordering::Relaxed);
+		if previous > balance_utia {
+			self.inner.fee_paid_utia.fetch_add(previous - balance_utia, Ordering::Relaxed);
+		}
+	}
+
+	pub fn record_fetch(&self, height: u64, blob_count: usize) {
+		self.inner.blobs_fetched.fetch_add(blob_count as u64, Ordering::Relaxed);
+		self.inner.last_fetched_height.fetch_max(height, Ordering::Relaxed);
+	}
+
+	pub fn record_verification_failure(&self) {
+		self.inner.verification_failures.fetch_add(1, Ordering::Relaxed);
+	}
+
+	pub fn record_celestia_head(&self, height: u64) {
+		self.inner.celestia_head_height.store(height, Ordering::Relaxed);
+	}
+
+	/// Number of Celestia blocks between the head and the last height fetched.
+	pub fn height_lag(&self) -> u64 {
+		let head = self.inner.celestia_head_height.load(Ordering::Relaxed);
+		let fetched = self.inner.last_fetched_height.load(Ordering::Relaxed);
+		head.saturating_sub(fetched)
+	}
+
+	/// Exports the metrics in the Prometheus text format.
+	pub fn export_prometheus(&self) -> String {
+		let mut out = String::new();
+		let counters = [
+			("movement_da_blobs_submitted_total", &self.inner.blobs_submitted),
+			("movement_da_blobs_fetched_total", &self.inner.blobs_fetched),
+			("movement_da_submission_failures_total", &self.inner.submission_failures),
+			("movement_da_verification_failures_total", &self.inner.verification_failures),
+			("movement_da_fee_paid_utia_total", &self.inner.fee_paid_utia),
+		];
+		for (name, value) in counters {
+			let _ = writeln!(out, "# TYPE {name} counter");
+			let _ = writeln!(out, "{name} {}", value.load(Ordering::Relaxed));
+		}
+		let gauges = [
+			(
+				"movement_da_last_fetched_height",
+				self.inner.last_fetched_height.load(Ordering::Relaxed),
+			),
+			(
+				"movement_da_celestia_head_height",
+				self.inner.celestia_head_height.load(Ordering::Relaxed),
+			),
+			("movement_da_height_lag", self.height_lag()),
+		];
+		for (name, value) in gauges {
+			let _ = writeln!(out, "# TYPE {name} gauge");
+			let _ = writeln!(out, "{name} {value}");
+		}
+		let histograms = self.histograms();
+		histograms.blob_size_bytes.export("movement_da_blob_size_bytes", &mut out);
+		histograms
+			.submit_latency_seconds
+			.export("movement_da_celestia_submit_latency_seconds", &mut out);
+		out
+	}
+
+	/// Serves the metrics on `/metrics` and the DA backend health on `/health`.
+	pub async fn run_server(
+		self,
+		address: String,
+		backend: Arc<dyn DaBackend>,
+	) -> Result<(), anyhow::Error> {
+		info!("Metrics server listening on: {}", address);
+		let routes = Route::new()
+			.at("/metrics", get(metrics))
+			.at("/health", get(health))
+			.data(self)
+			.data(backend);
+		Server::new(TcpListener::bind(address)).run(routes).await?;
+		Ok(())
+	}
+
+	fn histograms(&self) -> std::sync::MutexGuard<'_, Histograms> {
+		self.inner.histograms.lock().expect("Light node metrics lock poisoned")
+	}
+}
+
+#[handler]
+async fn metrics(metrics: Data<&LightNodeMetrics>) -> String {
+	metrics.export_prometheus()
+}
+
+#[handler]
+async fn health(backend: Data<&Arc<dyn DaBackend>>) -> Response {
+	match backend.health() {
+		Ok(()) => "OK".into_response(),
+		Err(error) => (StatusCode::SERVICE_UNAVAILABLE, error).into_response(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_export_metrics() {
+		let metrics = LightNodeMetrics::default();
+		metrics.record_submission(&[2_000, 100_000], Duration::from_millis(800));
+		metrics.record_balance(1_000);
+		metrics.record_balance(900);
+		metrics.record_balance(5_000);
+		metrics.record_fetch(10, 3);
+		metrics.record_celestia_head(14);
+
+		assert_eq!(metrics.height_lag(), 4);
+		let out = metrics.export_prometheus();
+		assert!(out.contains("movement_da_blobs_submitted_total 2"));
+		assert!(out.contains("movement_da_blobs_fetched_total 3"));
+		assert!(out.contains("movement_da_fee_paid_utia_total 100"));
+		assert!(out.contains("movement_da_blob_size_bytes_bucket{le=\"4096\"} 1"));
+		assert!(out.contains("movement_da_celestia_submit_latency_seconds_bucket{le=\"1\"} 1"));
+	}
+}
@@ -1,23 +1,26 @@
 use movement_celestia_da_util::ir_blob::IntermediateBlobRepresentation;
 use std::fmt::{self, Debug, Formatter};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio_stream::{Stream, StreamExt};
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
-use celestia_rpc::{BlobClient, Client, HeaderClient};
-use celestia_types::{nmt::Namespace, Blob as CelestiaBlob, TxConfig};
+use celestia_types::{nmt::Namespace, Blob as CelestiaBlob};
 
 // FIXME: glob imports are bad style
 use movement_celestia_da_light_node_verifier::{
-	permissioned_signers::Verifier, VerifierOperations,
+	permissioned_signers::{LocalVerifier, Verifier},
+	VerifierOperations,
 };
 use movement_celestia_da_util::{
-	config::Config,
+	config::{local::da_light_node::DaBackend as DaBackendConfig, Config},
 	ir_blob::{celestia::CelestiaIntermediateBlobRepresentation, InnerSignedBlobV1Data},
 };
 use movement_da_light_node_proto::light_node_service_server::LightNodeService;
 use movement_da_light_node_proto::*;
 
+use crate::backend::{CelestiaBackend, DaBackend, LocalFileBackend, MemoryBackend};
+use crate::metrics::LightNodeMetrics;
 use crate::v1::LightNodeV1Operations;
 use ecdsa::{
 	elliptic_curve::{
@@ -43,11 +46,12 @@ where
 {
 	pub config: Config,
 	pub celestia_namespace: Namespace,
-	pub default_client: Arc<Client>,
+	pub backend: Arc<dyn DaBackend>,
 	pub verifier: Arc<
 		Box<dyn VerifierOperations<CelestiaBlob, IntermediateBlobRepresentation> + Send + Sync>,
 	>,
 	pub signing_key: SigningKey<C>,
+	pub metrics: LightNodeMetrics,
 }
 
 impl<C> Debug for LightNodeV1<C>
@@ -75,7 +79,31 @@ where
 {
 	/// Tries to create a new LightNodeV1 instance from the toml config file.
 	async fn try_from_config(config: Config) -> Result<Self, anyhow::Error> {
-		let client = Arc::new(config.connect_celestia().await?);
+		let known_signers = config.da_signers_sec1_keys();
+		let namespace = config.celestia_namespace();
+		let (backend, verifier): (
+			Arc<dyn DaBackend>,
+			Box<dyn VerifierOperations<CelestiaBlob, IntermediateBlobRepresentation> + Send + Sync>,
+		) = match config.da_backend() {
+			DaBackendConfig::Celestia => {
+				let backend = CelestiaBackend::connect(config.clone()).await?;
+				let verifier =
+					Verifier::<C>::new(backend.default_client(), namespace, known_signers);
+				(Arc::new(backend), Box::new(verifier))
+			}
+			DaBackendConfig::Memory => {
+				info!("Using the in-memory DA backend.");
+				(
+					Arc::new(MemoryBackend::new(namespace)),
+					Box::new(LocalVerifier::<C>::new(known_signers)),
+				)
+			}
+			DaBackendConfig::LocalFile { path } => {
+				info!("Using the local file DA backend at {path}.");
+				let backend = LocalFileBackend::try_new(path, namespace).await?;
+				(Arc::new(backend), Box::new(LocalVerifier::<C>::new(known_signers)))
+			}
+		};
 
 		let signing_key_str = config.da_signing_key();
 		let hex_bytes = hex::decode(signing_key_str)?;
@@ -86,13 +114,10 @@ where
 		Ok(Self {
 			config: config.clone(),
 			celestia_namespace: config.celestia_namespace(),
-			default_client: client.clone(),
-			verifier: Arc::new(Box::new(Verifier::<C>::new(
-				client,
-				config.celestia_namespace(),
-				config.da_signers_sec1_keys(),
-			))),
+			backend,
+			verifier: Arc::new(verifier),
 			signing_key,
+			metrics: LightNodeMetrics::default(),
 		})
 	}
 
@@ -102,6 +127,15 @@ where
 
 	/// Runs background tasks for the LightNodeV1 instance.
 	async fn run_background_tasks(&self) -> Result<(), anyhow::Error> {
+		let metrics_server = self
+			.metrics
+			.clone()
+			.run_server(self.config.movement_da_light_node_metrics_service(), self.backend.clone());
+		tokio::try_join!(
+			metrics_server,
+			self.run_head_tracker(),
+			self.backend.run_background_tasks()
+		)?;
 		Ok(())
 	}
 }
@@ -129,30 +163,47 @@ where
 
 	/// Submits a CelestiaBlob to the Celestia node.
 	pub async fn submit_celestia_blob(&self, blob: CelestiaBlob) -> Result<u64, anyhow::Error> {
-		let config = TxConfig::default();
-		// config.with_gas(2);
-		let height = self.default_client.blob_submit(&[blob], config).await.map_err(|e| {
-			error!(error = %e, "failed to submit the blob");
-			anyhow::anyhow!("Failed submitting the blob: {}", e)
-		})?;
-
-		Ok(height)
+		self.submit_celestia_blobs(&[blob]).await
 	}
 
-	/// Submits Celestia blobs to the Celestia node.
+	/// Submits Celestia blobs to the DA backend.
 	pub async fn submit_celestia_blobs(
 		&self,
 		blobs: &[CelestiaBlob],
 	) -> Result<u64, anyhow::Error> {
-		let height =
-			self.default_client.blob_submit(blobs, TxConfig::default()).await.map_err(|e| {
-				error!(error = %e, "failed to submit the blobs");
-				anyhow::anyhow!("Failed submitting the blob: {}", e)
-			})?;
+		let start = Instant::now();
+		let height = self.backend.submit_blobs(blobs).await.inspect_err(|e| {
+			error!(error = %e, "failed to submit the blobs");
+			self.metrics.record_submission_failure();
+		})?;
+		let blob_sizes = blobs.iter().map(|blob| blob.data.len()).collect::<Vec<_>>();
+		self.metrics.record_submission(&blob_sizes, start.elapsed());
+		self.record_balance().await;
 
 		Ok(height)
 	}
 
+	/// Samples the balance of the submitting account to track the fees paid.
+	async fn record_balance(&self) {
+		match self.backend.account_balance().await {
+			Ok(Some(amount)) => self.metrics.record_balance(amount),
+			Ok(None) => {}
+			Err(e) => warn!(error = %e, "failed to get the account balance"),
+		}
+	}
+
+	/// Polls the DA head to track the height lag of the fetched blobs.
+	async fn run_head_tracker(&self) -> Result<(), anyhow::Error> {
+		let mut interval = tokio::time::interval(Duration::from_secs(10));
+		loop {
+			interval.tick().await;
+			match self.backend.head_height().await {
+				Ok(height) => self.metrics.record_celestia_head(height),
+				Err(e) => warn!(error = %e, "failed to get the DA head height"),
+			}
+		}
+	}
+
 	/// Submits a blob to the Celestia node.
 	pub async fn submit_blob(&self, data: Vec<u8>) -> Result<Blob, anyhow::Error> {
 		let celestia_blob = self.create_new_celestia_blob(data)?;
@@ -166,14 +217,12 @@ where
 		height: u64,
 	) -> Result<Vec<IntermediateBlobRepresentation>, anyhow::Error> {
 		let height = if height == 0 { 1 } else { height };
-		match self.default_client.blob_get_all(height, &[self.celestia_namespace]).await {
+		match self.backend.get_blobs_at_height(height).await {
 			Err(e) => {
 				error!(error = %e, "failed to get blobs at height {height}");
 				anyhow::bail!(e);
 			}
 			Ok(blobs) => {
-				let blobs = blobs.unwrap_or_default();
-
 				let mut verified_blobs = Vec::new();
 				for blob in blobs {
 					match self.verifier.verify(blob, height).await {
@@ -184,9 +233,11 @@ where
 						}
 						Err(e) => {
 							error!(error = %e, "failed to verify blob");
+							self.metrics.record_verification_failure();
 						}
 					}
 				}
+				self.metrics.record_fetch(height, verified_blobs.len());
 
 				Ok(verified_blobs)
 			}
@@ -246,16 +297,15 @@ where
 	> {
 		let start_height = start_height.unwrap_or_else(|| u64::MAX);
 		let me = Arc::new(self.clone());
-		let mut subscription = me.default_client.header_subscribe().await?;
+		let mut subscription = me.backend.subscribe_heights().await?;
 
 		let stream = async_stream::try_stream! {
 			let mut first_flag = true;
-			while let Some(header_res) = subscription.next().await {
+			while let Some(height_res) = subscription.next().await {
 
-				let header = header_res?;
-				let height = header.height().into();
+				let height = height_res?;
 
-				info!("Stream got header: {:?}", header.height());
+				info!("Stream got header: {:?}", height);
 
 				// back fetch the blobs
 				if first_flag && (height > start_height) {
@@ -28,7 +28,6 @@ use tokio::{
 use tokio_stream::Stream;
 use tracing::{debug, info};
 
-use celestia_rpc::HeaderClient;
 use memseq::{Sequencer, Transaction};
 use movement_algs::grouping_heuristic::{
 	apply::ToApply, binpacking::FirstFitBinpacking, drop_success::DropSuccess, skip::SkipFor,
@@ -111,7 +110,7 @@ where
 	}
 
 	async fn run_background_tasks(&self) -> Result<(), anyhow::Error> {
-		self.run_block_proposer().await?;
+		tokio::try_join!(self.pass_through.run_background_tasks(), self.run_block_proposer())?;
 
 		Ok(())
 	}
@@ -453,12 +452,10 @@ where
 		let blobs_for_submission = request.into_inner().blobs;
 		let height: u64 = self
 			.pass_through
-			.default_client
-			.header_network_head()
+			.backend
+			.head_height()
 			.await
-			.map_err(|e| tonic::Status::internal(e.to_string()))?
-			.height()
-			.into();
+			.map_err(|e| tonic::Status::internal(e.to_string()))?;
 
 		// make transactions from the blobs
 		let mut transactions = Vec::new();
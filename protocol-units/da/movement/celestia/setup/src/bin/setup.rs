@@ -1,17 +1,40 @@
+use clap::Parser;
 use godfig::{backend::config_file::ConfigFile, Godfig};
-use movement_celestia_da_light_node_setup::setup;
+use movement_celestia_da_light_node_setup::{common::keys, setup};
 use movement_celestia_da_util::config::CelestiaDaLightNodeConfig;
+use std::path::PathBuf;
+
+/// Sets up the M1 DA Light Node.
+#[derive(Parser, Debug, Clone)]
+struct SetupArgs {
+	/// Imports the Celestia funding key before the setup, from a keyring directory,
+	/// a key file written by `--export-key` or a mnemonic file.
+	#[clap(long)]
+	import_key: Option<PathBuf>,
+
+	/// Exports the Celestia funding key after the setup, encrypted, to the path.
+	#[clap(long)]
+	export_key: Option<PathBuf>,
+}
 
 #[tokio::main]
 async fn main() -> Result<(), anyhow::Error> {
 	use tracing_subscriber::EnvFilter;
 
+	let args = SetupArgs::parse();
+
 	tracing_subscriber::fmt()
 		.with_env_filter(
 			EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
 		)
 		.init();
 
+	// check the passphrase before doing anything
+	let passphrase = match (&args.import_key, &args.export_key) {
+		(None, None) => None,
+		_ => Some(keys::passphrase_from_env()?),
+	};
+
 	// get the config file
 	let dot_movement = dot_movement::DotMovement::try_from_env()?;
 	let mut config_file = dot_movement.try_get_or_create_config_file().await?;
@@ -24,17 +47,19 @@ async fn main() -> Result<(), anyhow::Error> {
 	godfig
 		.try_transaction(|config| async move {
 			println!("Config: {:?}", config);
-			match config {
-				Some(config) => {
-					let config = setup(dot_movement.clone(), config).await?;
-					Ok(Some(config))
-				}
-				None => {
-					let config = CelestiaDaLightNodeConfig::default();
-					let config = setup(dot_movement.clone(), config).await?;
-					Ok(Some(config))
-				}
+			let config = config.unwrap_or_default();
+
+			if let (Some(path), Some(passphrase)) = (&args.import_key, &passphrase) {
+				keys::import_key(&dot_movement, &config, path, passphrase).await?;
 			}
+
+			let config = setup(dot_movement.clone(), config).await?;
+
+			if let (Some(path), Some(passphrase)) = (&args.export_key, &passphrase) {
+				keys::export_key(&dot_movement, &config, path, passphrase).await?;
+			}
+
+			Ok(Some(config))
 		})
 		.await?;
 
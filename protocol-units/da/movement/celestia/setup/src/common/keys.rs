@@ -0,0 +1,322 @@
+use anyhow::Context;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use dot_movement::DotMovement;
+use movement_celestia_da_util::config::{CelestiaDaLightNodeConfig, Config};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tracing::info;
+
+/// The environment variable holding the passphrase the exported keys are encrypted with.
+pub const KEY_PASSPHRASE_ENV: &str = "CELESTIA_KEY_PASSPHRASE";
+
+const KEY_FILE_VERSION: u32 = 1;
+const KDF_ROUNDS: u32 = 600_000;
+
+/// The Celestia funding key, either as a mnemonic or as the files of a `test` keyring.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyMaterial {
+	Mnemonic(String),
+	Keyring(BTreeMap<String, String>),
+}
+
+/// A key encrypted with a passphrase, as written to disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptedKey {
+	pub version: u32,
+	pub salt: String,
+	pub nonce: String,
+	pub ciphertext: String,
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Key {
+	let mut key = Key::default();
+	pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, KDF_ROUNDS, &mut key);
+	key
+}
+
+impl EncryptedKey {
+	pub fn encrypt(material: &KeyMaterial, passphrase: &str) -> Result<Self, anyhow::Error> {
+		let mut rng = rand::thread_rng();
+		let salt: [u8; 16] = rng.gen();
+		let nonce: [u8; 12] = rng.gen();
+
+		let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt));
+		let plaintext = serde_json::to_vec(material)?;
+		let ciphertext = cipher
+			.encrypt(Nonce::from_slice(&nonce), plaintext.as_slice())
+			.map_err(|_| anyhow::anyhow!("Failed to encrypt the Celestia key."))?;
+
+		Ok(Self {
+			version: KEY_FILE_VERSION,
+			salt: hex::encode(salt),
+			nonce: hex::encode(nonce),
+			ciphertext: hex::encode(ciphertext),
+		})
+	}
+
+	pub fn decrypt(&self, passphrase: &str) -> Result<KeyMaterial, anyhow::Error> {
+		if self.version != KEY_FILE_VERSION {
+			anyhow::bail!("Unsupported Celestia key file version {}.", self.version);
+		}
+		let salt = hex::decode(&self.salt).context("Invalid salt in the Celestia key file.")?;
+		let nonce = hex::decode(&self.nonce).context("Invalid nonce in the Celestia key file.")?;
+		if nonce.len() != 12 {
+			anyhow::bail!("Invalid nonce length in the Celestia key file.");
+		}
+		let ciphertext = hex::decode(&self.ciphertext)
+			.context("Invalid ciphertext in the Celestia key file.")?;
+
+		let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt));
+		let plaintext =
+			cipher.decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice()).map_err(|_| {
+				anyhow::anyhow!("Failed to decrypt the Celestia key, wrong passphrase?")
+			})?;
+
+		Ok(serde_json::from_slice(&plaintext)?)
+	}
+}
+
+/// Gets the passphrase the keys are encrypted with from the environment.
+pub fn passphrase_from_env() -> Result<String, anyhow::Error> {
+	let passphrase = std::env::var(KEY_PASSPHRASE_ENV)
+		.with_context(|| format!("{KEY_PASSPHRASE_ENV} must be set to import or export keys."))?;
+	if passphrase.is_empty() {
+		anyhow::bail!("{KEY_PASSPHRASE_ENV} must not be empty.");
+	}
+	Ok(passphrase)
+}
+
+/// The network name cel-key and the Celestia light node use for the config.
+fn network(config: &Config) -> &'static str {
+	match config {
+		Config::Local(_) => "private",
+		Config::Arabica(_) => "arabica",
+		Config::Mocha(_) => "mocha",
+	}
+}
+
+/// The name of the key paying for the blob submissions.
+fn key_name(config: &Config) -> &'static str {
+	match config {
+		Config::Local(_) => "validator",
+		Config::Arabica(_) | Config::Mocha(_) => "my_celes_key",
+	}
+}
+
+/// The directory of the `test` keyring the light node signs with.
+pub fn keyring_dir(config: &CelestiaDaLightNodeConfig) -> Result<PathBuf, anyhow::Error> {
+	match &config.celestia_da_light_node_config {
+		Config::Local(local) => {
+			let node_path = local
+				.bridge
+				.celestia_bridge_path
+				.clone()
+				.context("Celestia Node path is not set in the config.")?;
+			Ok(Path::new(&node_path).join("keys").join("keyring-test"))
+		}
+		Config::Arabica(_) => Ok(home_dir()?.join(".celestia-light-arabica-11/keys/keyring-test")),
+		Config::Mocha(_) => Ok(home_dir()?.join(".celestia-light-mocha-4/keys/keyring-test")),
+	}
+}
+
+fn home_dir() -> Result<PathBuf, anyhow::Error> {
+	Ok(PathBuf::from(std::env::var("HOME").context("HOME is not set.")?))
+}
+
+/// The encrypted copy of the key kept under DotMovement.
+pub fn stored_key_path(dot_movement: &DotMovement, config: &CelestiaDaLightNodeConfig) -> PathBuf {
+	dot_movement
+		.get_path()
+		.join("celestia")
+		.join("keys")
+		.join(format!("{}.json", network(&config.celestia_da_light_node_config)))
+}
+
+async fn read_keyring(dir: &Path) -> Result<BTreeMap<String, String>, anyhow::Error> {
+	let mut files = BTreeMap::new();
+	let mut entries = tokio::fs::read_dir(dir)
+		.await
+		.with_context(|| format!("Failed to read the keyring at {}.", dir.display()))?;
+	while let Some(entry) = entries.next_entry().await? {
+		if !entry.file_type().await?.is_file() {
+			continue;
+		}
+		let name = entry.file_name().to_str().context("Invalid keyring file name.")?.to_string();
+		files.insert(name, hex::encode(tokio::fs::read(entry.path()).await?));
+	}
+	if files.is_empty() {
+		anyhow::bail!("The keyring at {} is empty.", dir.display());
+	}
+	Ok(files)
+}
+
+async fn write_keyring(dir: &Path, files: &BTreeMap<String, String>) -> Result<(), anyhow::Error> {
+	tokio::fs::create_dir_all(dir).await?;
+	for (name, contents) in files {
+		// keyring file names never contain a path
+		if Path::new(name).file_name() != Some(name.as_ref()) {
+			anyhow::bail!("Invalid keyring file name {name}.");
+		}
+		tokio::fs::write(dir.join(name), hex::decode(contents)?).await?;
+	}
+	Ok(())
+}
+
+/// Recovers the key from the mnemonic into the keyring.
+async fn recover_mnemonic(
+	config: &CelestiaDaLightNodeConfig,
+	mnemonic: &str,
+) -> Result<(), anyhow::Error> {
+	let keyring_dir = keyring_dir(config)?;
+	let keys_dir = keyring_dir.parent().context("Invalid keyring path.")?;
+	tokio::fs::create_dir_all(keys_dir).await?;
+	let keys_dir = keys_dir.to_str().context("Failed to convert path to string.")?;
+	let inner = &config.celestia_da_light_node_config;
+
+	// cel-key add <name> --recover --keyring-backend test --keyring-dir <dir> --p2p.network <net>
+	// run_command can't write to stdin, so the mnemonic is passed here
+	let args = [
+		"add",
+		key_name(inner),
+		"--recover",
+		"--keyring-backend",
+		"test",
+		"--keyring-dir",
+		keys_dir,
+		"--node.type",
+		"light",
+		"--p2p.network",
+		network(inner),
+	];
+	info!("Running command: cel-key {}", args.join(" "));
+	let mut child = tokio::process::Command::new("cel-key")
+		.args(args)
+		.stdin(Stdio::piped())
+		.stdout(Stdio::null())
+		.stderr(Stdio::piped())
+		.spawn()?;
+	let mut stdin = child.stdin.take().context("Failed to open the stdin of cel-key.")?;
+	stdin.write_all(mnemonic.trim().as_bytes()).await?;
+	stdin.write_all(b"\n").await?;
+	drop(stdin);
+
+	let output = child.wait_with_output().await?;
+	if !output.status.success() {
+		anyhow::bail!(
+			"Failed to recover the Celestia key from the mnemonic: {}",
+			String::from_utf8_lossy(&output.stderr)
+		);
+	}
+	Ok(())
+}
+
+/// Installs the key into the keyring of the light node.
+pub async fn install_key(
+	config: &CelestiaDaLightNodeConfig,
+	material: &KeyMaterial,
+) -> Result<(), anyhow::Error> {
+	match material {
+		KeyMaterial::Mnemonic(mnemonic) => recover_mnemonic(config, mnemonic).await,
+		KeyMaterial::Keyring(files) => write_keyring(&keyring_dir(config)?, files).await,
+	}
+}
+
+async fn write_encrypted(path: &Path, key: &EncryptedKey) -> Result<(), anyhow::Error> {
+	if let Some(parent) = path.parent() {
+		tokio::fs::create_dir_all(parent).await?;
+	}
+	tokio::fs::write(path, serde_json::to_vec_pretty(key)?).await?;
+	#[cfg(unix)]
+	{
+		use std::os::unix::fs::PermissionsExt;
+		tokio::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600)).await?;
+	}
+	Ok(())
+}
+
+/// Reads the key to import from the path.
+/// The path can be a keyring directory, a key file exported by this tool or a mnemonic file.
+pub async fn read_key(path: &Path, passphrase: &str) -> Result<KeyMaterial, anyhow::Error> {
+	if tokio::fs::metadata(path).await?.is_dir() {
+		return Ok(KeyMaterial::Keyring(read_keyring(path).await?));
+	}
+
+	let contents = tokio::fs::read_to_string(path)
+		.await
+		.with_context(|| format!("Failed to read the key from {}.", path.display()))?;
+	match serde_json::from_str::<EncryptedKey>(&contents) {
+		Ok(key) => key.decrypt(passphrase),
+		Err(_) => {
+			let mnemonic = contents.trim();
+			if mnemonic.split_whitespace().count() < 12 {
+				anyhow::bail!("{} is neither a key file nor a mnemonic.", path.display());
+			}
+			Ok(KeyMaterial::Mnemonic(mnemonic.to_string()))
+		}
+	}
+}
+
+/// Imports the funding key from the path into the keyring of the light node,
+/// keeping an encrypted copy under DotMovement.
+pub async fn import_key(
+	dot_movement: &DotMovement,
+	config: &CelestiaDaLightNodeConfig,
+	path: &Path,
+	passphrase: &str,
+) -> Result<(), anyhow::Error> {
+	info!("Importing the Celestia key from {}.", path.display());
+	let material = read_key(path, passphrase).await?;
+	install_key(config, &material).await?;
+
+	let stored_path = stored_key_path(dot_movement, config);
+	write_encrypted(&stored_path, &EncryptedKey::encrypt(&material, passphrase)?).await?;
+	info!("Stored the encrypted Celestia key at {}.", stored_path.display());
+
+	Ok(())
+}
+
+/// Exports the funding key from the keyring of the light node to the path, encrypted,
+/// and keeps a copy under DotMovement.
+pub async fn export_key(
+	dot_movement: &DotMovement,
+	config: &CelestiaDaLightNodeConfig,
+	path: &Path,
+	passphrase: &str,
+) -> Result<(), anyhow::Error> {
+	let keyring_dir = keyring_dir(config)?;
+	info!("Exporting the Celestia key from {}.", keyring_dir.display());
+	let material = KeyMaterial::Keyring(read_keyring(&keyring_dir).await?);
+	let key = EncryptedKey::encrypt(&material, passphrase)?;
+
+	write_encrypted(path, &key).await?;
+	write_encrypted(&stored_key_path(dot_movement, config), &key).await?;
+	info!("Exported the encrypted Celestia key to {}.", path.display());
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_encrypted_key_roundtrip() -> Result<(), anyhow::Error> {
+		let material = KeyMaterial::Keyring(BTreeMap::from([(
+			"my_celes_key.info".to_string(),
+			hex::encode(b"key"),
+		)]));
+
+		let key = EncryptedKey::encrypt(&material, "passphrase")?;
+		assert_eq!(key.decrypt("passphrase")?, material);
+		assert!(key.decrypt("wrong").is_err());
+
+		Ok(())
+	}
+}
@@ -0,0 +1,8 @@
+//! Prints the JSON schema of the Celestia DA light node config.
+use movement_celestia_da_util::config::CelestiaDaLightNodeConfig;
+
+fn main() -> Result<(), anyhow::Error> {
+	let schema = godfig::schema::json_schema::<CelestiaDaLightNodeConfig>();
+	println!("{}", serde_json::to_string_pretty(&schema)?);
+	Ok(())
+}
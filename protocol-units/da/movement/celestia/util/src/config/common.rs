@@ -82,6 +82,14 @@ env_default!(
 	30730
 );
 
+// The default M1 DA Light Node metrics listen port
+env_default!(
+	default_movement_da_light_node_metrics_port,
+	"MOVEMENT_DA_LIGHT_NODE_METRICS_PORT",
+	u16,
+	30731
+);
+
 // The default M1 DA Light Node connection protocol
 env_default!(
 	default_movement_da_light_node_connection_protocol,
@@ -119,6 +127,14 @@ pub fn default_celestia_namespace() -> Namespace {
 	}
 }
 
+// The default interval in seconds between two Celestia auth token refreshes
+env_default!(
+	default_celestia_auth_token_refresh_interval_secs,
+	"CELESTIA_AUTH_TOKEN_REFRESH_INTERVAL_SECS",
+	u64,
+	300
+);
+
 // The default Celestia chain id
 env_default!(default_celestia_chain_id, "CELESTIA_CHAIN_ID", String, "movement".to_string());
 
@@ -1,15 +1,18 @@
 use crate::config::common::{
 	default_celestia_appd_replace_args, default_celestia_appd_use_replace_args,
-	default_celestia_chain_id, default_celestia_namespace, default_celestia_rpc_listen_hostname,
+	default_celestia_auth_token_refresh_interval_secs, default_celestia_chain_id,
+	default_celestia_namespace, default_celestia_rpc_listen_hostname,
 	default_celestia_rpc_listen_port, default_celestia_websocket_connection_hostname,
 	default_celestia_websocket_connection_port, default_celestia_websocket_connection_protocol,
 };
 
 use celestia_types::nmt::Namespace;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// The inner configuration for the local Celestia Appd Runner
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Config {
 	/// The URL of the Celestia RPC
 	#[serde(default = "default_celestia_rpc_listen_hostname")]
@@ -34,12 +37,21 @@ pub struct Config {
 	/// The auth token for the Celestia node
 	pub celestia_auth_token: Option<String>,
 
+	/// A file the auth token is re-read from, so the token can be rotated without a restart
+	#[serde(default)]
+	pub celestia_auth_token_path: Option<String>,
+
+	/// The interval in seconds between two auth token refreshes
+	#[serde(default = "default_celestia_auth_token_refresh_interval_secs")]
+	pub celestia_auth_token_refresh_interval_secs: u64,
+
 	/// The Chain ID for the Celestia node
 	#[serde(default = "default_celestia_chain_id")]
 	pub celestia_chain_id: String,
 
 	/// The namespace for the Celestia node
 	#[serde(default = "default_celestia_namespace")]
+	#[schemars(with = "String")]
 	pub celestia_namespace: Namespace,
 
 	/// The celestia app path for when that is being orchestrated locally
@@ -71,6 +83,9 @@ impl Default for Config {
 			celestia_websocket_connection_port: default_celestia_websocket_connection_port(),
 			celestia_chain_id: default_celestia_chain_id(),
 			celestia_auth_token: None,
+			celestia_auth_token_path: None,
+			celestia_auth_token_refresh_interval_secs:
+				default_celestia_auth_token_refresh_interval_secs(),
 			celestia_namespace: default_celestia_namespace(),
 			celestia_path: None,
 			celestia_validator_address: None,
@@ -4,10 +4,12 @@ use crate::config::common::{
 	default_celestia_rpc_connection_protocol, default_celestia_websocket_listen_hostname,
 	default_celestia_websocket_listen_port,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// The inner configuration for the local Celestia Bridge Runner
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Config {
 	/// The protocol for the Celestia RPC
 	#[serde(default = "default_celestia_rpc_connection_protocol")]
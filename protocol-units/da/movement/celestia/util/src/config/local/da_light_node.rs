@@ -4,13 +4,16 @@ use crate::config::common::{
 	default_celestia_websocket_connection_port, default_movement_da_light_node_connection_hostname,
 	default_movement_da_light_node_connection_port, default_movement_da_light_node_http1,
 	default_movement_da_light_node_listen_hostname, default_movement_da_light_node_listen_port,
+	default_movement_da_light_node_metrics_port,
 };
 use ecdsa::SigningKey;
 use k256::Secp256k1;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct DaSigners {
 	pub private_key_hex: String,
 	pub public_keys_hex: HashSet<String>,
@@ -36,6 +39,31 @@ pub fn default_da_signing_private_key() -> SigningKey<Secp256k1> {
 	}
 }
 
+/// The backend the light node stores blobs in
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DaBackend {
+	/// A Celestia light node
+	Celestia,
+	/// An in-process store, lost on restart
+	Memory,
+	/// A directory on the local filesystem
+	LocalFile { path: String },
+}
+
+/// The default DA backend
+pub fn default_da_backend() -> DaBackend {
+	match std::env::var("MOVEMENT_DA_BACKEND").as_deref() {
+		Ok("memory") => DaBackend::Memory,
+		Ok("local_file") => DaBackend::LocalFile {
+			path: std::env::var("MOVEMENT_DA_BACKEND_PATH")
+				.expect("MOVEMENT_DA_BACKEND_PATH must be set for the local_file DA backend"),
+		},
+		Ok("celestia") | Err(std::env::VarError::NotPresent) => DaBackend::Celestia,
+		_ => panic!("Invalid MOVEMENT_DA_BACKEND"),
+	}
+}
+
 pub fn default_da_signers_sec1_keys() -> HashSet<String> {
 	match std::env::var("DA_SIGNERS_SEC1_KEYS") {
 		Ok(val) => val.split(',').map(|s| s.to_string()).collect(),
@@ -89,7 +117,8 @@ pub mod signers_serialization_test {
 }
 
 /// The inner configuration for the local Celestia Appd Runner
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Config {
 	/// The protocol for the Celestia RPC
 	#[serde(default = "default_celestia_rpc_connection_protocol")]
@@ -121,6 +150,10 @@ pub struct Config {
 	#[serde(default = "default_movement_da_light_node_listen_port")]
 	pub movement_da_light_node_listen_port: u16,
 
+	/// The port to serve the movement-celestia-da-light-node Prometheus metrics on
+	#[serde(default = "default_movement_da_light_node_metrics_port")]
+	pub movement_da_light_node_metrics_port: u16,
+
 	/// The protocol for movement-celestia-da-light-node connection
 	#[serde(default = "default_celestia_rpc_connection_protocol")]
 	pub movement_da_light_node_connection_protocol: String,
@@ -140,6 +173,10 @@ pub struct Config {
 	/// The DA signers
 	#[serde(default = "default_da_signers")]
 	pub da_signers: DaSigners,
+
+	/// The backend blobs are submitted to and read from
+	#[serde(default = "default_da_backend")]
+	pub da_backend: DaBackend,
 }
 
 impl Default for Config {
@@ -155,12 +192,14 @@ impl Default for Config {
 			movement_da_light_node_listen_hostname: default_movement_da_light_node_listen_hostname(
 			),
 			movement_da_light_node_listen_port: default_movement_da_light_node_listen_port(),
+			movement_da_light_node_metrics_port: default_movement_da_light_node_metrics_port(),
 			movement_da_light_node_connection_hostname:
 				default_movement_da_light_node_connection_hostname(),
 			movement_da_light_node_connection_port: default_movement_da_light_node_connection_port(
 			),
 			movement_da_light_node_http1: default_movement_da_light_node_http1(),
 			da_signers: default_da_signers(),
+			da_backend: default_da_backend(),
 		}
 	}
 }
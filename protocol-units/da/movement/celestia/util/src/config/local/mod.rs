@@ -4,9 +4,11 @@ pub mod da_light_node;
 use crate::config::common::{default_celestia_force_new_chain, default_da_light_node_is_initial};
 use aptos_account_whitelist::config::Config as WhitelistConfig;
 use memseq_util::Config as MemseqConfig;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Config {
 	/// The appd configuration
 	#[serde(default)]
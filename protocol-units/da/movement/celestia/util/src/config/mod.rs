@@ -2,13 +2,14 @@ use anyhow::Context;
 use aptos_types::account_address::AccountAddress;
 use celestia_rpc::Client;
 use celestia_types::nmt::Namespace;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
 pub mod common;
 pub mod local;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 pub enum Config {
 	Local(local::Config),
 	Arabica(local::Config),
@@ -106,6 +107,50 @@ impl Config {
 		}
 	}
 
+	/// Connects to a Celestia node with the given auth token instead of the configured one
+	pub async fn connect_celestia_with_auth_token(
+		&self,
+		celestia_auth_token: &str,
+	) -> Result<Client, anyhow::Error> {
+		let appd = match self {
+			Config::Local(local) => &local.appd,
+			Config::Arabica(local) => &local.appd,
+			Config::Mocha(local) => &local.appd,
+		};
+		let celestia_node_url = format!(
+			"{}://{}:{}",
+			appd.celestia_websocket_connection_protocol,
+			appd.celestia_websocket_connection_hostname,
+			appd.celestia_websocket_connection_port
+		);
+
+		Client::new(&celestia_node_url, Some(celestia_auth_token)).await.map_err(|e| {
+			anyhow::anyhow!(
+				"Failed to connect to Celestia client at {:?}: {}",
+				celestia_node_url,
+				e
+			)
+		})
+	}
+
+	/// Gets the path of the file the Celestia auth token is refreshed from
+	pub fn celestia_auth_token_path(&self) -> Option<String> {
+		match self {
+			Config::Local(local) => local.appd.celestia_auth_token_path.clone(),
+			Config::Arabica(local) => local.appd.celestia_auth_token_path.clone(),
+			Config::Mocha(local) => local.appd.celestia_auth_token_path.clone(),
+		}
+	}
+
+	/// Gets the interval in seconds between two Celestia auth token refreshes
+	pub fn celestia_auth_token_refresh_interval_secs(&self) -> u64 {
+		match self {
+			Config::Local(local) => local.appd.celestia_auth_token_refresh_interval_secs,
+			Config::Arabica(local) => local.appd.celestia_auth_token_refresh_interval_secs,
+			Config::Mocha(local) => local.appd.celestia_auth_token_refresh_interval_secs,
+		}
+	}
+
 	/// Gets the Celestia namespace
 	pub fn celestia_namespace(&self) -> Namespace {
 		match self {
@@ -161,6 +206,22 @@ impl Config {
 		format!("{}:{}", hostname, port)
 	}
 
+	/// Gets M1 DA Light Node metrics port
+	pub fn movement_da_light_node_metrics_port(&self) -> u16 {
+		match self {
+			Config::Local(local) => local.da_light_node.movement_da_light_node_metrics_port,
+			Config::Arabica(local) => local.da_light_node.movement_da_light_node_metrics_port,
+			Config::Mocha(local) => local.da_light_node.movement_da_light_node_metrics_port,
+		}
+	}
+
+	/// Gets M1 DA Light Node metrics service
+	pub fn movement_da_light_node_metrics_service(&self) -> String {
+		let hostname = self.movement_da_light_node_listen_hostname();
+		let port = self.movement_da_light_node_metrics_port();
+		format!("{}:{}", hostname, port)
+	}
+
 	/// Gets M1 DA Light Node connection hostname
 	pub fn movement_da_light_node_connection_hostname(&self) -> String {
 		match self {
@@ -194,6 +255,15 @@ impl Config {
 		}
 	}
 
+	/// Gets the DA backend
+	pub fn da_backend(&self) -> local::da_light_node::DaBackend {
+		match self {
+			Config::Local(local) => local.da_light_node.da_backend.clone(),
+			Config::Arabica(local) => local.da_light_node.da_backend.clone(),
+			Config::Mocha(local) => local.da_light_node.da_backend.clone(),
+		}
+	}
+
 	/// Gets the memseq path
 	pub fn try_memseq_path(&self) -> Result<String, anyhow::Error> {
 		match self {
@@ -251,7 +321,9 @@ impl Config {
 }
 
 /// The M1 DA Light Node configuration as should be read from file.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+/// It is flattened in the node config, so it cannot deny the unknown fields itself:
+/// its inner configurations do.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 pub struct CelestiaDaLightNodeConfig {
 	#[serde(default)]
 	pub celestia_da_light_node_config: Config,
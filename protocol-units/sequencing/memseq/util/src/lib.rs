@@ -1,10 +1,12 @@
 use dot_movement::DotMovement;
 use godfig::env_default;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 /// The configuration for the MemSeq sequencer
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Config {
 	/// The chain id of the sequencer
 	#[serde(default = "Config::default_sequencer_chain_id")]
@@ -148,8 +148,11 @@ impl BackendOperations for ConfigFile {
 	{
 		let key_clone = key.into();
 		loop {
-			if let Ok(Some(result)) = self.try_get(key_clone.clone()).await {
-				return Ok(result);
+			match self.try_get(key_clone.clone()).await {
+				Ok(Some(result)) => return Ok(result),
+				// A misspelled key does not fix itself, fail instead of waiting on it.
+				Err(error @ GodfigBackendError::UnknownField(_)) => return Err(error),
+				_ => {}
 			}
 			tokio::time::sleep(self.polling_interval).await;
 		}
@@ -375,6 +378,35 @@ pub mod test {
 		let result = config_file.try_get::<_, TestConfig>(vec!["key".to_string()]).await?;
 		assert_eq!(result, Some(TestConfig { key: "test".to_string(), value: 42 }));
 
+		Ok(())
+	}
+	#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+	#[serde(deny_unknown_fields)]
+	pub struct StrictConfig {
+		#[serde(default)]
+		pub threshold: u64,
+	}
+
+	#[tokio::test]
+	async fn test_unknown_field() -> Result<(), anyhow::Error> {
+		let file = tempfile::tempfile()?;
+		let config_file = ConfigFile::new(file.into());
+
+		// set a misspelled value
+		config_file
+			.try_set(vec!["key".to_string()], Some(serde_json::json!({ "threshhold": 42 })))
+			.await?;
+
+		// waiting for the value fails instead of polling forever
+		let result = config_file.try_wait_for::<_, StrictConfig>(vec!["key".to_string()]).await;
+		match result {
+			Err(GodfigBackendError::UnknownField(unknown_field)) => {
+				assert_eq!(unknown_field.field, "threshhold");
+				assert_eq!(unknown_field.suggestion(), Some("threshold"));
+			}
+			other => panic!("Expected an unknown field error, got {other:?}"),
+		}
+
 		Ok(())
 	}
 }
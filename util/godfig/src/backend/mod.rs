@@ -12,6 +12,8 @@ pub enum GodfigBackendError {
 	BackendError(#[from] anyhow::Error),
 	#[error("IO Error: {0}")]
 	IOError(#[from] std::io::Error),
+	#[error("{0}")]
+	UnknownField(UnknownField),
 	// any other error
 	#[error("Error: {0}")]
 	Error(String),
@@ -19,8 +21,67 @@ pub enum GodfigBackendError {
 
 impl From<serde_json::Error> for GodfigBackendError {
 	fn from(error: serde_json::Error) -> Self {
-		GodfigBackendError::BackendError(error.into())
+		match UnknownField::parse(&error.to_string()) {
+			Some(unknown_field) => GodfigBackendError::UnknownField(unknown_field),
+			None => GodfigBackendError::BackendError(error.into()),
+		}
+	}
+}
+
+/// A config key matching no field of a contract denying unknown fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownField {
+	pub field: String,
+	pub expected: Vec<String>,
+}
+
+impl UnknownField {
+	/// Parses the serde error raised for the unknown fields, if it is one.
+	pub fn parse(message: &str) -> Option<Self> {
+		let rest = message.strip_prefix("unknown field `")?;
+		let (field, rest) = rest.split_once('`')?;
+		// The expected fields are quoted with backticks.
+		let expected = rest.split('`').skip(1).step_by(2).map(str::to_string).collect();
+		Some(UnknownField { field: field.to_string(), expected })
+	}
+
+	/// The expected field close enough to the unknown one to be a typo of it.
+	pub fn suggestion(&self) -> Option<&str> {
+		let max_distance = (self.field.len() / 3).max(1);
+		self.expected
+			.iter()
+			.map(|expected| (edit_distance(&self.field, expected), expected))
+			.filter(|(distance, _)| *distance <= max_distance)
+			.min_by_key(|(distance, _)| *distance)
+			.map(|(_, expected)| expected.as_str())
+	}
+}
+
+impl std::fmt::Display for UnknownField {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Unknown config field `{}`", self.field)?;
+		match self.suggestion() {
+			Some(suggestion) => write!(f, ", did you mean `{suggestion}`?"),
+			None if self.expected.is_empty() => Ok(()),
+			None => write!(f, ", expected one of: {}", self.expected.join(", ")),
+		}
+	}
+}
+
+/// Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+	let b: Vec<char> = b.chars().collect();
+	let mut row: Vec<usize> = (0..=b.len()).collect();
+	for (i, a_char) in a.chars().enumerate() {
+		let mut diagonal = row[0];
+		row[0] = i + 1;
+		for (j, b_char) in b.iter().enumerate() {
+			let substitution = diagonal + usize::from(a_char != *b_char);
+			diagonal = row[j + 1];
+			row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+		}
 	}
+	row[b.len()]
 }
 
 impl From<FileRwLockError> for GodfigBackendError {
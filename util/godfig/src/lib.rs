@@ -1,5 +1,6 @@
 pub mod backend;
 pub mod godfig;
+pub mod schema;
 pub use godfig::*;
 
 #[macro_export]
@@ -0,0 +1,26 @@
+use schemars::gen::SchemaSettings;
+use schemars::schema::{RootSchema, SchemaObject};
+use schemars::visit::{self, Visitor};
+use schemars::JsonSchema;
+
+/// Drops the default values from the schema. They are read from the environment
+/// of the process generating the schema and can hold private keys.
+#[derive(Debug, Clone)]
+struct StripDefaults;
+
+impl Visitor for StripDefaults {
+	fn visit_schema_object(&mut self, schema: &mut SchemaObject) {
+		if let Some(metadata) = schema.metadata.as_mut() {
+			metadata.default = None;
+		}
+		visit::visit_schema_object(self, schema);
+	}
+}
+
+/// The JSON schema of a config contract, without its default values.
+pub fn json_schema<T: JsonSchema>() -> RootSchema {
+	SchemaSettings::draft07()
+		.with_visitor(StripDefaults)
+		.into_generator()
+		.into_root_schema_for::<T>()
+}